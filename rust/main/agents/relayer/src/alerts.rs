@@ -0,0 +1,380 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use hyperlane_base::{settings::ChainConf, CoreMetrics};
+use hyperlane_core::{HyperlaneDomain, HyperlaneProvider, H256, U256};
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+use tracing::{debug, info_span, instrument::Instrumented, trace, warn, Instrument};
+
+use crate::settings::{AlertConf, AlertSeverity, AlertWebhookConf, AlertWebhookKind};
+
+/// How often the signer balance and block height of destinations are checked
+const CHAIN_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Source reported to webhooks
+const ALERT_SOURCE: &str = "hyperlane-relayer";
+
+/// A critical condition detected by the relayer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// The relayer's signer on a destination is running out of funds
+    SignerBalanceLow,
+    /// A destination stopped producing blocks
+    DestinationHalted,
+    /// Operations are piling up in the prepare queue of a destination
+    QueueSaturated,
+    /// An operation failed to be processed many times in a row
+    RepeatedOperationFailures,
+    /// The gas oracle of an origin's IGP is missing or drifted from its
+    /// reference values
+    GasOracleDrift,
+}
+
+impl AlertKind {
+    pub fn severity(&self) -> AlertSeverity {
+        match self {
+            AlertKind::SignerBalanceLow
+            | AlertKind::DestinationHalted
+            | AlertKind::RepeatedOperationFailures => AlertSeverity::Critical,
+            AlertKind::QueueSaturated | AlertKind::GasOracleDrift => AlertSeverity::Warning,
+        }
+    }
+}
+
+impl Display for AlertKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AlertKind::SignerBalanceLow => "signer_balance_low",
+            AlertKind::DestinationHalted => "destination_halted",
+            AlertKind::QueueSaturated => "queue_saturated",
+            AlertKind::RepeatedOperationFailures => "repeated_operation_failures",
+            AlertKind::GasOracleDrift => "gas_oracle_drift",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    /// Name of the chain the condition was detected on
+    pub chain: String,
+    pub summary: String,
+}
+
+/// Sends alerts about critical conditions to the configured webhooks, so
+/// operators without a full monitoring stack still get paged.
+///
+/// Alerts are routed to the webhooks by severity. Repeated alerts of the same
+/// kind for the same chain are only sent once per rate limit window.
+#[derive(Debug)]
+pub struct AlertSink {
+    conf: AlertConf,
+    client: reqwest::Client,
+    /// When an alert was last sent, keyed by (kind, chain)
+    last_sent: Mutex<HashMap<(AlertKind, String), Instant>>,
+}
+
+impl AlertSink {
+    pub fn new(conf: AlertConf) -> Self {
+        Self {
+            conf,
+            client: reqwest::Client::new(),
+            last_sent: Default::default(),
+        }
+    }
+
+    /// Sends the alert in the background, unless the rate limit suppresses it
+    pub fn alert(&self, alert: Alert) {
+        if !self.should_send(&alert, Instant::now()) {
+            trace!(?alert, "Alert suppressed by rate limit");
+            return;
+        }
+        let webhooks: Vec<_> = self
+            .conf
+            .webhooks
+            .iter()
+            .filter(|webhook| alert.kind.severity() >= webhook.min_severity)
+            .cloned()
+            .collect();
+        warn!(?alert, webhooks = webhooks.len(), "Sending alert");
+
+        let client = self.client.clone();
+        tokio::spawn(async move {
+            for webhook in webhooks {
+                let result = client
+                    .post(&webhook.url)
+                    .json(&webhook_body(&webhook, &alert))
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                if let Err(err) = result {
+                    warn!(?err, url = %webhook.url, "Failed to send alert to webhook");
+                }
+            }
+        });
+    }
+
+    /// Alerts if the prepare queue of a destination holds more operations than
+    /// the configured threshold
+    pub fn check_queue_length(&self, destination: &HyperlaneDomain, length: usize) {
+        match self.conf.queue_saturation_threshold {
+            Some(threshold) if length >= threshold => self.alert(Alert {
+                kind: AlertKind::QueueSaturated,
+                chain: destination.name().to_owned(),
+                summary: format!(
+                    "{length} operations are queued for {destination}, reaching the threshold of {threshold}"
+                ),
+            }),
+            _ => {}
+        }
+    }
+
+    /// Alerts if an operation failed at least as many times in a row as the
+    /// configured threshold
+    pub fn check_operation_failures(
+        &self,
+        destination: &HyperlaneDomain,
+        operation_id: H256,
+        retry_count: u32,
+        reason: impl Display,
+    ) {
+        match self.conf.operation_failure_threshold {
+            Some(threshold) if retry_count >= threshold => self.alert(Alert {
+                kind: AlertKind::RepeatedOperationFailures,
+                chain: destination.name().to_owned(),
+                summary: format!(
+                    "Operation {operation_id:?} to {destination} failed {retry_count} times, last because of: {reason}"
+                ),
+            }),
+            _ => {}
+        }
+    }
+
+    fn should_send(&self, alert: &Alert, now: Instant) -> bool {
+        let mut last_sent = self.last_sent.lock().expect("alert sink lock poisoned");
+        let key = (alert.kind, alert.chain.clone());
+        match last_sent.get(&key) {
+            Some(sent_at) if now.duration_since(*sent_at) < self.conf.rate_limit => false,
+            _ => {
+                last_sent.insert(key, now);
+                true
+            }
+        }
+    }
+
+    /// Periodically checks the signer balance and block height of a
+    /// destination, alerting if the balance is below the configured threshold
+    /// or no block was produced within the configured timeout
+    pub async fn spawn_chain_checks(
+        self: Arc<Self>,
+        chain_conf: &ChainConf,
+        agent_name: String,
+        core_metrics: &CoreMetrics,
+    ) -> eyre::Result<Option<Instrumented<JoinHandle<()>>>> {
+        let domain = chain_conf.domain.clone();
+        let balance_threshold = self
+            .conf
+            .signer_balance_thresholds
+            .get(&domain.id())
+            .copied();
+        let halted_chain_timeout = self.conf.halted_chain_timeout;
+        if balance_threshold.is_none() && halted_chain_timeout.is_none() {
+            return Ok(None);
+        }
+
+        let signer_address = chain_conf.agent_metrics_conf(agent_name).await?.address;
+        let provider = chain_conf.build_provider(core_metrics).await?;
+        let mut checks = ChainChecks {
+            sink: self,
+            domain: domain.clone(),
+            provider,
+            signer_address,
+            balance_threshold,
+            halted_chain_timeout,
+            last_block: None,
+        };
+        let span = info_span!("AlertChainChecks", chain = %domain);
+        Ok(Some(
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(CHAIN_CHECK_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    checks.check_signer_balance().await;
+                    checks.check_block_height(Instant::now()).await;
+                }
+            })
+            .instrument(span),
+        ))
+    }
+}
+
+struct ChainChecks {
+    sink: Arc<AlertSink>,
+    domain: HyperlaneDomain,
+    provider: Box<dyn HyperlaneProvider>,
+    signer_address: Option<String>,
+    balance_threshold: Option<U256>,
+    halted_chain_timeout: Option<Duration>,
+    /// The latest block height seen and when it was first seen
+    last_block: Option<(u64, Instant)>,
+}
+
+impl ChainChecks {
+    async fn check_signer_balance(&self) {
+        let (Some(threshold), Some(address)) = (self.balance_threshold, &self.signer_address)
+        else {
+            return;
+        };
+        match self.provider.get_balance(address.clone()).await {
+            Ok(balance) if balance < threshold => self.sink.alert(Alert {
+                kind: AlertKind::SignerBalanceLow,
+                chain: self.domain.name().to_owned(),
+                summary: format!(
+                    "Relayer signer {address} on {} has a balance of {balance}, below the threshold of {threshold}",
+                    self.domain
+                ),
+            }),
+            Ok(balance) => trace!(%balance, "Signer balance is above the alert threshold"),
+            Err(err) => debug!(?err, "Failed to get signer balance for alerting"),
+        }
+    }
+
+    async fn check_block_height(&mut self, now: Instant) {
+        let Some(timeout) = self.halted_chain_timeout else {
+            return;
+        };
+        let height = match self.provider.get_chain_metrics().await {
+            Ok(Some(metrics)) => metrics.latest_block.number,
+            Ok(None) => return,
+            Err(err) => {
+                debug!(?err, "Failed to get block height for alerting");
+                return;
+            }
+        };
+        if let Some(stalled_for) = self.observe_block_height(height, now) {
+            if stalled_for >= timeout {
+                self.sink.alert(Alert {
+                    kind: AlertKind::DestinationHalted,
+                    chain: self.domain.name().to_owned(),
+                    summary: format!(
+                        "{} has been at block {height} for {}s",
+                        self.domain,
+                        stalled_for.as_secs()
+                    ),
+                });
+            }
+        }
+    }
+
+    /// Records the block height, returning for how long it hasn't changed
+    fn observe_block_height(&mut self, height: u64, now: Instant) -> Option<Duration> {
+        match self.last_block {
+            Some((last_height, seen_at)) if last_height == height => {
+                Some(now.duration_since(seen_at))
+            }
+            _ => {
+                self.last_block = Some((height, now));
+                None
+            }
+        }
+    }
+}
+
+/// Formats the alert for the webhook's service
+fn webhook_body(webhook: &AlertWebhookConf, alert: &Alert) -> Value {
+    let severity = alert.kind.severity();
+    match &webhook.kind {
+        AlertWebhookKind::Slack => json!({
+            "text": format!("[{severity}] {ALERT_SOURCE}: {}", alert.summary),
+        }),
+        AlertWebhookKind::PagerDuty { routing_key } => json!({
+            "routing_key": routing_key,
+            "event_action": "trigger",
+            // Lets PagerDuty group repeated alerts into a single incident
+            "dedup_key": format!("{ALERT_SOURCE}-{}-{}", alert.kind, alert.chain),
+            "payload": {
+                "summary": alert.summary,
+                "source": ALERT_SOURCE,
+                "severity": severity.to_string(),
+                "component": alert.chain,
+                "class": alert.kind.to_string(),
+            },
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sink(rate_limit: Duration) -> AlertSink {
+        AlertSink::new(AlertConf {
+            webhooks: vec![],
+            rate_limit,
+            signer_balance_thresholds: HashMap::new(),
+            queue_saturation_threshold: None,
+            operation_failure_threshold: None,
+            halted_chain_timeout: None,
+        })
+    }
+
+    fn alert(kind: AlertKind, chain: &str) -> Alert {
+        Alert {
+            kind,
+            chain: chain.to_owned(),
+            summary: "summary".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_alerts_are_rate_limited_by_kind_and_chain() {
+        let sink = sink(Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(sink.should_send(&alert(AlertKind::QueueSaturated, "ethereum"), start));
+        assert!(!sink.should_send(
+            &alert(AlertKind::QueueSaturated, "ethereum"),
+            start + Duration::from_secs(30)
+        ));
+        // Other kinds and chains have their own windows
+        assert!(sink.should_send(
+            &alert(AlertKind::SignerBalanceLow, "ethereum"),
+            start + Duration::from_secs(30)
+        ));
+        assert!(sink.should_send(
+            &alert(AlertKind::QueueSaturated, "arbitrum"),
+            start + Duration::from_secs(30)
+        ));
+        assert!(sink.should_send(
+            &alert(AlertKind::QueueSaturated, "ethereum"),
+            start + Duration::from_secs(60)
+        ));
+    }
+
+    #[test]
+    fn test_pagerduty_body() {
+        let webhook = AlertWebhookConf {
+            kind: AlertWebhookKind::PagerDuty {
+                routing_key: "key".to_owned(),
+            },
+            url: "https://events.pagerduty.com/v2/enqueue".to_owned(),
+            min_severity: AlertSeverity::Critical,
+        };
+        let body = webhook_body(&webhook, &alert(AlertKind::DestinationHalted, "ethereum"));
+
+        assert_eq!(body["routing_key"], "key");
+        assert_eq!(body["event_action"], "trigger");
+        assert_eq!(
+            body["dedup_key"],
+            "hyperlane-relayer-destination_halted-ethereum"
+        );
+        assert_eq!(body["payload"]["severity"], "critical");
+        assert_eq!(body["payload"]["component"], "ethereum");
+    }
+}
@@ -0,0 +1,514 @@
+//! Startup of the chains that are unreachable when the relayer starts.
+//!
+//! Building the mailbox of a destination or the validator announce of an
+//! origin needs its RPC, e.g. to fetch the chain id the signer signs for. A
+//! chain that's unreachable at startup used to be dropped until the next
+//! restart. Its contracts are now built lazily instead: a [`LazyChainHandle`]
+//! keeps retrying to build its contract in the background, and until it
+//! succeeds every call to the contract fails like a call to an unreachable
+//! RPC does, so the operations using it are retried later like any other.
+//!
+//! Until all of its components started, a chain is reported as not ready on
+//! `/ready`, and its number of components still starting is exported as
+//! `chain_components_starting`.
+
+use std::{fmt::Debug, future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use eyre::Result;
+use hyperlane_base::{settings::ChainConf, AgentHealth, CoreMetrics, Retrier};
+use hyperlane_core::{
+    rpc_clients::RetryPolicy, Announcement, BatchItem, BatchResult, ChainResult, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Mailbox,
+    PreTransaction, QueueOperation, ReorgPeriod, SignedType, TxCostEstimate, TxOutcome,
+    ValidatorAnnounce, H256, U256,
+};
+use prometheus::IntGaugeVec;
+use tokio::sync::OnceCell;
+use tracing::{info, info_span, warn, Instrument};
+
+/// Retries of building a component of a chain in the background. Once the
+/// policy gives up, it starts over.
+pub const CHAIN_COMPONENT_RETRY_POLICY: RetryPolicy =
+    RetryPolicy::exponential(Duration::from_secs(5), Duration::from_secs(60), 10);
+
+/// Tracks the components of each chain that are still starting
+#[derive(Debug, Clone)]
+pub struct ChainStartup {
+    health: Arc<AgentHealth>,
+    /// Components of a chain still starting
+    ///
+    /// Labels:
+    /// - `chain`: The chain of the components.
+    components_starting: IntGaugeVec,
+}
+
+impl ChainStartup {
+    pub fn new(health: Arc<AgentHealth>, components_starting: IntGaugeVec) -> Self {
+        Self {
+            health,
+            components_starting,
+        }
+    }
+
+    /// Records that a component of the chain is starting, so that the chain
+    /// isn't ready until it started
+    pub fn starting(&self, domain: &HyperlaneDomain, component: &str) {
+        let starting = self
+            .health
+            .report_component_starting(domain.name(), component);
+        self.set_components_starting(domain, starting);
+    }
+
+    /// Records that a component of the chain started
+    pub fn started(&self, domain: &HyperlaneDomain, component: &str) {
+        let starting = self
+            .health
+            .report_component_started(domain.name(), component);
+        self.set_components_starting(domain, starting);
+    }
+
+    fn set_components_starting(&self, domain: &HyperlaneDomain, starting: usize) {
+        self.components_starting
+            .with_label_values(&[domain.name()])
+            .set(starting as i64);
+    }
+}
+
+type BuildFuture<T> = Pin<Box<dyn Future<Output = Result<Arc<T>>> + Send>>;
+
+/// A contract of a chain, built on first use or by a background task,
+/// whichever comes first
+pub struct LazyChainHandle<T: ?Sized> {
+    domain: HyperlaneDomain,
+    component: &'static str,
+    handle: OnceCell<Arc<T>>,
+    build: Box<dyn Fn() -> BuildFuture<T> + Send + Sync>,
+    startup: ChainStartup,
+}
+
+impl<T: ?Sized> Debug for LazyChainHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyChainHandle")
+            .field("domain", &self.domain)
+            .field("component", &self.component)
+            .field("built", &self.handle.initialized())
+            .finish()
+    }
+}
+
+impl<T: ?Sized + Send + Sync + 'static> LazyChainHandle<T> {
+    /// A handle built by `build`, reported as a starting component of the
+    /// chain until it's built
+    pub fn new(
+        domain: HyperlaneDomain,
+        component: &'static str,
+        startup: ChainStartup,
+        build: impl Fn() -> BuildFuture<T> + Send + Sync + 'static,
+    ) -> Self {
+        startup.starting(&domain, component);
+        Self {
+            domain,
+            component,
+            handle: OnceCell::new(),
+            build: Box::new(build),
+            startup,
+        }
+    }
+
+    /// The contract, built first if it wasn't yet
+    pub async fn get(&self) -> ChainResult<Arc<T>> {
+        let handle = self
+            .handle
+            .get_or_try_init(|| async {
+                let handle = (self.build)().await?;
+                info!(domain = %self.domain, component = self.component, "Built chain component");
+                self.startup.started(&self.domain, self.component);
+                ChainResult::Ok(handle)
+            })
+            .await?;
+        Ok(handle.clone())
+    }
+
+    /// The contract, if it was built
+    pub fn built(&self) -> Option<&Arc<T>> {
+        self.handle.get()
+    }
+
+    /// Spawns a task that builds the contract, retrying until it succeeds
+    pub fn spawn_build(self: &Arc<Self>, retrier: Retrier) {
+        let span = info_span!("LazyChainHandle", domain = %self.domain, component = self.component);
+        let lazy_handle = self.clone();
+        tokio::spawn(
+            async move {
+                retrier
+                    .call_until_success(|| {
+                        let lazy_handle = lazy_handle.clone();
+                        Box::pin(async move { lazy_handle.get().await.map(|_| ()) })
+                    })
+                    .await;
+            }
+            .instrument(span),
+        );
+    }
+}
+
+/// Builds a contract of the chain in the background, retrying until it
+/// succeeds
+fn build_in_background<T: ?Sized + Send + Sync + 'static>(
+    component: &'static str,
+    conf: ChainConf,
+    metrics: Arc<CoreMetrics>,
+    startup: ChainStartup,
+    build: impl Fn(ChainConf, Arc<CoreMetrics>) -> BuildFuture<T> + Send + Sync + 'static,
+) -> Arc<LazyChainHandle<T>> {
+    warn!(
+        domain = %conf.domain,
+        component,
+        "Failed to build chain component, retrying in the background"
+    );
+    let retrier = Retrier::new(
+        CHAIN_COMPONENT_RETRY_POLICY,
+        &format!("relayer_build_{component}"),
+        &metrics,
+    );
+    let domain = conf.domain.clone();
+    let lazy_handle = Arc::new(LazyChainHandle::new(
+        domain,
+        component,
+        startup,
+        move || build(conf.clone(), metrics.clone()),
+    ));
+    lazy_handle.spawn_build(retrier);
+    lazy_handle
+}
+
+/// The mailbox of a destination that couldn't be built at startup, built in
+/// the background
+#[derive(Debug)]
+pub struct LazyMailbox {
+    address: H256,
+    provider: Arc<dyn HyperlaneProvider>,
+    mailbox: Arc<LazyChainHandle<dyn Mailbox>>,
+}
+
+impl LazyMailbox {
+    /// Builds the mailbox of the chain in the background. Fails if even the
+    /// provider of the chain can't be built, which doesn't need its RPC.
+    pub async fn build_in_background(
+        conf: &ChainConf,
+        metrics: Arc<CoreMetrics>,
+        startup: ChainStartup,
+    ) -> Result<Self> {
+        let provider = conf.build_provider(&metrics).await?;
+        let mailbox: Arc<LazyChainHandle<dyn Mailbox>> = build_in_background(
+            "mailbox",
+            conf.clone(),
+            metrics,
+            startup,
+            |conf, metrics| {
+                Box::pin(async move { Ok(Arc::from(conf.build_mailbox(&metrics).await?)) })
+            },
+        );
+        Ok(Self {
+            address: conf.addresses.mailbox,
+            provider: Arc::from(provider),
+            mailbox,
+        })
+    }
+}
+
+impl HyperlaneChain for LazyMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.mailbox.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        match self.mailbox.built() {
+            Some(mailbox) => mailbox.provider(),
+            None => Box::new(self.provider.clone()),
+        }
+    }
+}
+
+impl HyperlaneContract for LazyMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+#[async_trait]
+impl Mailbox for LazyMailbox {
+    async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        self.mailbox.get().await?.count(reorg_period).await
+    }
+
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        self.mailbox.get().await?.delivered(id).await
+    }
+
+    async fn delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {
+        self.mailbox.get().await?.delivered_batch(ids).await
+    }
+
+    async fn local_domain(&self) -> ChainResult<u32> {
+        self.mailbox.get().await?.local_domain().await
+    }
+
+    async fn default_ism(&self) -> ChainResult<H256> {
+        self.mailbox.get().await?.default_ism().await
+    }
+
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        self.mailbox.get().await?.recipient_ism(recipient).await
+    }
+
+    async fn recipient_ism_for_origin(&self, recipient: H256, origin: u32) -> ChainResult<H256> {
+        self.mailbox
+            .get()
+            .await?
+            .recipient_ism_for_origin(recipient, origin)
+            .await
+    }
+
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        self.mailbox
+            .get()
+            .await?
+            .process(message, metadata, tx_gas_limit)
+            .await
+    }
+
+    async fn process_batch(
+        &self,
+        messages: &[BatchItem<HyperlaneMessage>],
+    ) -> ChainResult<BatchResult> {
+        self.mailbox.get().await?.process_batch(messages).await
+    }
+
+    async fn try_process_batch<'a>(
+        &self,
+        ops: Vec<&'a QueueOperation>,
+    ) -> ChainResult<BatchResult> {
+        self.mailbox.get().await?.try_process_batch(ops).await
+    }
+
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        self.mailbox
+            .get()
+            .await?
+            .process_estimate_costs(message, metadata)
+            .await
+    }
+
+    /// Empty until the mailbox is built, since the calldata can't be built
+    /// without waiting for it
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+        self.mailbox
+            .built()
+            .map(|mailbox| mailbox.process_calldata(message, metadata))
+            .unwrap_or_default()
+    }
+
+    async fn pre_transaction_required(
+        &self,
+        message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<bool> {
+        self.mailbox
+            .get()
+            .await?
+            .pre_transaction_required(message, pre_transaction)
+            .await
+    }
+
+    async fn submit_pre_transaction(
+        &self,
+        message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<TxOutcome> {
+        self.mailbox
+            .get()
+            .await?
+            .submit_pre_transaction(message, pre_transaction)
+            .await
+    }
+
+    async fn awaiting_execution(&self, id: H256) -> ChainResult<bool> {
+        self.mailbox.get().await?.awaiting_execution(id).await
+    }
+
+    async fn execute(
+        &self,
+        message: &HyperlaneMessage,
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        self.mailbox
+            .get()
+            .await?
+            .execute(message, tx_gas_limit)
+            .await
+    }
+}
+
+/// The validator announce of an origin that couldn't be built at startup,
+/// built in the background
+#[derive(Debug)]
+pub struct LazyValidatorAnnounce {
+    address: H256,
+    provider: Arc<dyn HyperlaneProvider>,
+    validator_announce: Arc<LazyChainHandle<dyn ValidatorAnnounce>>,
+}
+
+impl LazyValidatorAnnounce {
+    /// Builds the validator announce of the chain in the background. Fails
+    /// if even the provider of the chain can't be built.
+    pub async fn build_in_background(
+        conf: &ChainConf,
+        metrics: Arc<CoreMetrics>,
+        startup: ChainStartup,
+    ) -> Result<Self> {
+        let provider = conf.build_provider(&metrics).await?;
+        let validator_announce: Arc<LazyChainHandle<dyn ValidatorAnnounce>> = build_in_background(
+            "validator_announce",
+            conf.clone(),
+            metrics,
+            startup,
+            |conf, metrics| {
+                Box::pin(
+                    async move { Ok(Arc::from(conf.build_validator_announce(&metrics).await?)) },
+                )
+            },
+        );
+        Ok(Self {
+            address: conf.addresses.validator_announce,
+            provider: Arc::from(provider),
+            validator_announce,
+        })
+    }
+}
+
+impl HyperlaneChain for LazyValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.validator_announce.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        match self.validator_announce.built() {
+            Some(validator_announce) => validator_announce.provider(),
+            None => Box::new(self.provider.clone()),
+        }
+    }
+}
+
+impl HyperlaneContract for LazyValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for LazyValidatorAnnounce {
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        self.validator_announce
+            .get()
+            .await?
+            .get_announced_storage_locations(validators)
+            .await
+    }
+
+    async fn announced_validators(&self) -> ChainResult<Vec<H256>> {
+        self.validator_announce
+            .get()
+            .await?
+            .announced_validators()
+            .await
+    }
+
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        self.validator_announce
+            .get()
+            .await?
+            .announce(announcement)
+            .await
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        self.validator_announce
+            .get()
+            .await
+            .ok()?
+            .announce_tokens_needed(announcement)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::opts;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_handle_is_built_once_the_chain_is_reachable() {
+        let health = Arc::new(AgentHealth::default());
+        let startup = ChainStartup::new(
+            health.clone(),
+            IntGaugeVec::new(opts!("components_starting", "help"), &["chain"]).unwrap(),
+        );
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let handle = LazyChainHandle::new(domain, "counter", startup.clone(), {
+            let attempts = attempts.clone();
+            move || -> BuildFuture<usize> {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    if attempt == 0 {
+                        eyre::bail!("connection refused");
+                    }
+                    Ok(Arc::new(attempt))
+                })
+            }
+        });
+
+        // The chain isn't ready until the handle is built
+        assert!(handle.get().await.is_err());
+        assert!(handle.built().is_none());
+        assert!(!health.report().ready);
+        assert_eq!(
+            startup
+                .components_starting
+                .with_label_values(&["arbitrum"])
+                .get(),
+            1
+        );
+
+        assert_eq!(*handle.get().await.unwrap(), 1);
+        // Once built, the handle isn't built again
+        assert_eq!(*handle.get().await.unwrap(), 1);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(health.report().ready);
+        assert_eq!(
+            startup
+                .components_starting
+                .with_label_values(&["arbitrum"])
+                .get(),
+            0
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Claims of the payments accumulated by the IGPs of the origins.
+//!
+//! An [`IgpClaimer`] periodically fetches the payments its origin's IGP
+//! holds, and sends them to the IGP's beneficiary once they reach the
+//! threshold configured for the origin. Claiming is permissionless, so the
+//! relayer only pays for the transaction, and the payments always go to the
+//! beneficiary set on the IGP.
+
+use std::time::Duration;
+
+use hyperlane_core::{
+    metrics::agent::u256_as_scaled_f64, HyperlaneChain, HyperlaneDomain, InterchainGasPaymaster,
+    U256,
+};
+use prometheus::{GaugeVec, IntCounterVec};
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+
+/// Metrics of the IGP payment claims, by origin
+#[derive(Debug, Clone)]
+pub struct IgpClaimMetrics {
+    /// The payments the IGP held when last checked, in the native token of
+    /// the origin
+    ///
+    /// Labels:
+    /// - `chain`: The origin of the IGP.
+    pub claimable_balance: GaugeVec,
+
+    /// Claims of the IGP payments
+    ///
+    /// Labels:
+    /// - `chain`: The origin of the IGP.
+    /// - `status`: `claimed`, `reverted` or `failed`.
+    pub claims: IntCounterVec,
+}
+
+/// Periodically claims the payments held by the IGP of an origin once they
+/// reach a threshold
+pub struct IgpClaimer {
+    domain: HyperlaneDomain,
+    igp: Box<dyn InterchainGasPaymaster>,
+    threshold: U256,
+    metrics: IgpClaimMetrics,
+}
+
+impl IgpClaimer {
+    pub fn new(
+        igp: Box<dyn InterchainGasPaymaster>,
+        threshold: U256,
+        metrics: IgpClaimMetrics,
+    ) -> Self {
+        Self {
+            domain: igp.domain().clone(),
+            igp,
+            threshold,
+            metrics,
+        }
+    }
+
+    /// Whether the claimable payments are worth a claim. Nothing is claimed
+    /// while the IGP holds no payments, even with a threshold of zero.
+    fn should_claim(&self, claimable: U256) -> bool {
+        !claimable.is_zero() && claimable >= self.threshold
+    }
+
+    async fn check(&self) {
+        let chain = self.domain.name();
+        let claimable = match self.igp.claimable_balance().await {
+            Ok(claimable) => claimable,
+            Err(err) => {
+                warn!(?err, chain, "Failed to fetch the claimable IGP payments");
+                return;
+            }
+        };
+        self.metrics
+            .claimable_balance
+            .with_label_values(&[chain])
+            .set(u256_as_scaled_f64(claimable, self.domain.domain_protocol()));
+        if !self.should_claim(claimable) {
+            return;
+        }
+
+        let status = match self.igp.claim().await {
+            Ok(outcome) if outcome.executed => {
+                info!(
+                    chain,
+                    %claimable,
+                    tx_id = ?outcome.transaction_id,
+                    "Claimed IGP payments"
+                );
+                "claimed"
+            }
+            Ok(outcome) => {
+                warn!(
+                    chain,
+                    %claimable,
+                    tx_id = ?outcome.transaction_id,
+                    "IGP claim transaction reverted"
+                );
+                "reverted"
+            }
+            // Retried at the next check
+            Err(err) => {
+                warn!(?err, chain, %claimable, "Failed to claim IGP payments");
+                "failed"
+            }
+        };
+        self.metrics
+            .claims
+            .with_label_values(&[chain, status])
+            .inc();
+    }
+
+    /// Checks the payments every `interval`
+    pub async fn run(self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            self.check().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use async_trait::async_trait;
+    use hyperlane_core::{
+        ChainResult, FixedPointNumber, HyperlaneContract, HyperlaneProvider, KnownHyperlaneDomain,
+        TxOutcome, H256, H512,
+    };
+    use prometheus::opts;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockIgp {
+        domain: HyperlaneDomain,
+        balance: Arc<AtomicU64>,
+        claims: Arc<AtomicUsize>,
+    }
+
+    impl MockIgp {
+        fn new() -> Self {
+            Self {
+                domain: HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
+                balance: Default::default(),
+                claims: Default::default(),
+            }
+        }
+    }
+
+    impl HyperlaneChain for MockIgp {
+        fn domain(&self) -> &HyperlaneDomain {
+            &self.domain
+        }
+
+        fn provider(&self) -> Box<dyn HyperlaneProvider> {
+            unimplemented!()
+        }
+    }
+
+    impl HyperlaneContract for MockIgp {
+        fn address(&self) -> H256 {
+            H256::zero()
+        }
+    }
+
+    #[async_trait]
+    impl InterchainGasPaymaster for MockIgp {
+        async fn claimable_balance(&self) -> ChainResult<U256> {
+            Ok(self.balance.load(Ordering::SeqCst).into())
+        }
+
+        async fn claim(&self) -> ChainResult<TxOutcome> {
+            self.claims.fetch_add(1, Ordering::SeqCst);
+            self.balance.store(0, Ordering::SeqCst);
+            Ok(TxOutcome {
+                transaction_id: H512::zero(),
+                executed: true,
+                gas_used: U256::zero(),
+                gas_price: FixedPointNumber::zero(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payments_are_claimed_above_threshold() {
+        let igp = MockIgp::new();
+        let (balance, claims) = (igp.balance.clone(), igp.claims.clone());
+        let metrics = IgpClaimMetrics {
+            claimable_balance: GaugeVec::new(opts!("claimable", "help"), &["chain"]).unwrap(),
+            claims: IntCounterVec::new(opts!("claims", "help"), &["chain", "status"]).unwrap(),
+        };
+        let claimer = IgpClaimer::new(Box::new(igp), U256::from(100), metrics.clone());
+
+        // Nothing to claim
+        claimer.check().await;
+        balance.store(99, Ordering::SeqCst);
+        claimer.check().await;
+        assert_eq!(claims.load(Ordering::SeqCst), 0);
+
+        balance.store(100, Ordering::SeqCst);
+        claimer.check().await;
+        assert_eq!(claims.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            metrics
+                .claims
+                .with_label_values(&["arbitrum", "claimed"])
+                .get(),
+            1
+        );
+        assert!(
+            metrics
+                .claimable_balance
+                .with_label_values(&["arbitrum"])
+                .get()
+                > 0.
+        );
+    }
+
+    #[test]
+    fn test_empty_igp_is_never_claimed() {
+        let claimer = IgpClaimer::new(
+            Box::new(MockIgp::new()),
+            U256::zero(),
+            IgpClaimMetrics {
+                claimable_balance: GaugeVec::new(opts!("claimable", "help"), &["chain"]).unwrap(),
+                claims: IntCounterVec::new(opts!("claims", "help"), &["chain", "status"]).unwrap(),
+            },
+        );
+        assert!(!claimer.should_claim(U256::zero()));
+        assert!(claimer.should_claim(U256::one()));
+    }
+}
@@ -0,0 +1,209 @@
+//! Checks of the values the IGPs of the origins quote gas payments with.
+//!
+//! An IGP quotes the gas payments for a destination with the gas price and
+//! token exchange rate its gas oracle holds for it. Once they go stale,
+//! senders underpay and their messages are held back by gas enforcement, or
+//! overpay. A [`GasOracleDriftChecker`] periodically compares the values of
+//! an origin's IGP to references: the current gas price of each destination
+//! and the configured token exchange rates.
+
+use std::{sync::Arc, time::Duration};
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider,
+    InterchainGasPaymaster, U256,
+};
+use prometheus::GaugeVec;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use crate::alerts::{Alert, AlertKind, AlertSink};
+
+/// Metrics of the gas oracles of the IGPs, by origin and destination
+#[derive(Debug, Clone)]
+pub struct GasOracleMetrics {
+    /// The values of the gas oracle of an origin's IGP for a destination
+    ///
+    /// Labels:
+    /// - `origin`: The origin of the IGP.
+    /// - `remote`: The destination the values are for.
+    /// - `value`: `gas_price` or `token_exchange_rate`.
+    pub value: GaugeVec,
+
+    /// The relative difference between the values and their references, only
+    /// set for the values that have one
+    ///
+    /// Labels: the same as `value`.
+    pub drift: GaugeVec,
+}
+
+/// What the gas oracle values for a destination are compared to
+pub struct GasOracleReference {
+    pub destination: HyperlaneDomain,
+    /// Provider of the destination, whose current gas price is the reference
+    /// gas price
+    pub provider: Box<dyn HyperlaneProvider>,
+    /// The reference token exchange rate, scaled like the oracle's
+    pub token_exchange_rate: Option<U256>,
+}
+
+/// Periodically checks the gas oracle of an origin's IGP for each destination
+pub struct GasOracleDriftChecker {
+    origin: HyperlaneDomain,
+    igp: Box<dyn InterchainGasPaymaster>,
+    references: Vec<GasOracleReference>,
+    threshold: f64,
+    metrics: GasOracleMetrics,
+    alert_sink: Option<Arc<AlertSink>>,
+}
+
+impl GasOracleDriftChecker {
+    pub fn new(
+        igp: Box<dyn InterchainGasPaymaster>,
+        references: Vec<GasOracleReference>,
+        threshold: f64,
+        metrics: GasOracleMetrics,
+        alert_sink: Option<Arc<AlertSink>>,
+    ) -> Self {
+        Self {
+            origin: igp.domain().clone(),
+            igp,
+            references,
+            threshold,
+            metrics,
+            alert_sink,
+        }
+    }
+
+    /// Checks the oracle values for every destination. Only fails if the
+    /// origin's IGP can't be read at all.
+    async fn check(&self) -> ChainResult<()> {
+        for reference in &self.references {
+            match self.check_destination(reference).await {
+                Err(ChainCommunicationError::GasOracleUnsupported) => {
+                    return Err(ChainCommunicationError::GasOracleUnsupported)
+                }
+                // Retried at the next check
+                Err(err) => warn!(
+                    ?err,
+                    origin = self.origin.name(),
+                    destination = reference.destination.name(),
+                    "Failed to read the gas oracle of the IGP"
+                ),
+                Ok(()) => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_destination(&self, reference: &GasOracleReference) -> ChainResult<()> {
+        let origin = self.origin.name();
+        let destination = reference.destination.name();
+        let Some(data) = self.igp.remote_gas_data(reference.destination.id()).await? else {
+            self.alert(format!(
+                "The IGP of {} has no gas oracle for {}, paying for the gas of messages to it reverts",
+                self.origin, reference.destination
+            ));
+            return Ok(());
+        };
+
+        let reference_gas_price = match reference.provider.get_chain_metrics().await {
+            Ok(metrics) => metrics.and_then(|metrics| metrics.min_gas_price),
+            Err(err) => {
+                debug!(?err, destination, "Failed to get the reference gas price");
+                None
+            }
+        };
+        let values = [
+            ("gas_price", data.gas_price, reference_gas_price),
+            (
+                "token_exchange_rate",
+                data.token_exchange_rate,
+                reference.token_exchange_rate,
+            ),
+        ];
+        for (name, value, reference_value) in values {
+            let labels = [origin, destination, name];
+            self.metrics
+                .value
+                .with_label_values(&labels)
+                .set(value.to_f64_lossy());
+            let Some(drift) = reference_value.and_then(|r| relative_drift(value, r)) else {
+                continue;
+            };
+            self.metrics.drift.with_label_values(&labels).set(drift);
+            if drift > self.threshold {
+                self.alert(format!(
+                    "The {name} of the IGP of {} for {} is {value}, {:.0}% off its reference of {}",
+                    self.origin,
+                    reference.destination,
+                    drift * 100.,
+                    reference_value.unwrap_or_default()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn alert(&self, summary: String) {
+        match &self.alert_sink {
+            Some(sink) => sink.alert(Alert {
+                kind: AlertKind::GasOracleDrift,
+                chain: self.origin.name().to_owned(),
+                summary,
+            }),
+            None => warn!(origin = self.origin.name(), %summary, "Gas oracle drifted"),
+        }
+    }
+
+    /// Checks the oracles every `interval`, until it turns out the origin's
+    /// IGP can't be read
+    pub async fn run(self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            if self.check().await.is_err() {
+                info!(
+                    origin = self.origin.name(),
+                    "The gas oracle of the IGP can't be read on this origin, not checking it"
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// How far `value` is from `reference`, relative to `reference`. There's no
+/// drift from a reference of zero, which is never a meaningful one.
+fn relative_drift(value: U256, reference: U256) -> Option<f64> {
+    if reference.is_zero() {
+        return None;
+    }
+    let difference = if value > reference {
+        value - reference
+    } else {
+        reference - value
+    };
+    Some(difference.to_f64_lossy() / reference.to_f64_lossy())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_drift_is_relative_to_the_reference() {
+        let reference = U256::from(100_000_000_000u64);
+        assert_eq!(relative_drift(reference, reference), Some(0.));
+        assert_eq!(
+            relative_drift(U256::from(150_000_000_000u64), reference),
+            Some(0.5)
+        );
+        assert_eq!(
+            relative_drift(U256::from(25_000_000_000u64), reference),
+            Some(0.75)
+        );
+        assert_eq!(relative_drift(U256::one(), U256::zero()), None);
+    }
+}
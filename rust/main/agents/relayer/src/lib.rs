@@ -1,10 +1,16 @@
 pub mod msg;
 
+mod alerts;
+mod chain_startup;
+mod igp_claim;
+mod igp_gas_oracle;
 mod merkle_tree;
 mod processor;
 mod prover;
 mod relayer;
 mod settings;
+mod status_webhook;
+mod supervisor;
 
 pub mod server;
 
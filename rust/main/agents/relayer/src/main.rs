@@ -16,6 +16,13 @@ use relayer::Relayer;
 #[cfg(feature = "memory-profiling")]
 mod memory_profiler;
 
+#[cfg(all(feature = "jemalloc", feature = "memory-profiling"))]
+compile_error!("The `jemalloc` and `memory-profiling` features both set the global allocator");
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
 #[tokio::main(flavor = "multi_thread", worker_threads = 20)]
 async fn main() -> Result<()> {
     // Logging is not initialised at this point, so, using `println!`
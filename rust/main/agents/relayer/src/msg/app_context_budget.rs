@@ -0,0 +1,159 @@
+use std::{collections::HashMap, sync::Arc};
+
+use prometheus::{GaugeVec, IntGaugeVec};
+use tracing::warn;
+
+use hyperlane_base::{
+    db::{DbResult, HyperlaneDb},
+    today, u256_as_f64, PeriodicSpend,
+};
+use hyperlane_core::{HyperlaneDomain, U256};
+
+/// Metrics of the spend of app contexts on a destination chain
+#[derive(Debug, Clone)]
+pub struct AppContextSpendMetrics {
+    /// Tokens spent today, by app context and destination
+    pub daily_spend: GaugeVec,
+    /// Whether the daily budget is exceeded, by app context and destination
+    pub budget_exceeded: IntGaugeVec,
+}
+
+/// Tracks how much each app context spends on delivering messages to a
+/// destination chain per day, and whether it has exceeded its daily budget.
+///
+/// Spend is denominated in the destination's native token, in its smallest
+/// unit, and persisted in the destination's database so it survives restarts.
+/// Days are UTC days since the unix epoch.
+pub struct AppContextSpendTracker {
+    destination: HyperlaneDomain,
+    spend: PeriodicSpend,
+    /// Daily budgets by app context. App contexts without a budget are only
+    /// tracked.
+    daily_budgets: HashMap<String, U256>,
+    metrics: AppContextSpendMetrics,
+}
+
+impl AppContextSpendTracker {
+    pub fn new(
+        destination: HyperlaneDomain,
+        db: Arc<dyn HyperlaneDb>,
+        daily_budgets: HashMap<String, U256>,
+        metrics: AppContextSpendMetrics,
+    ) -> Self {
+        Self {
+            destination,
+            spend: PeriodicSpend::new(db),
+            daily_budgets,
+            metrics,
+        }
+    }
+
+    /// Adds `tokens` to today's spend of the app context
+    pub fn record_spend(&self, app_context: &str, tokens: U256) -> DbResult<()> {
+        self.record_spend_on_day(app_context, tokens, today())
+    }
+
+    /// Whether the app context has spent more than its daily budget today
+    pub fn is_over_budget(&self, app_context: &str) -> bool {
+        self.is_over_budget_on_day(app_context, today())
+    }
+
+    fn record_spend_on_day(&self, app_context: &str, tokens: U256, day: u64) -> DbResult<()> {
+        let spend = self.spend.record_in_period(app_context, tokens, day)?;
+        self.metrics
+            .daily_spend
+            .with_label_values(&[app_context, self.destination.name()])
+            .set(u256_as_f64(spend));
+        Ok(())
+    }
+
+    fn is_over_budget_on_day(&self, app_context: &str, day: u64) -> bool {
+        let Some(budget) = self.daily_budgets.get(app_context) else {
+            return false;
+        };
+        let spend = match self.spend.spent_in_period(app_context, day) {
+            Ok(spend) => spend,
+            Err(err) => {
+                // Don't stop delivering messages because the spend can't be read
+                warn!(?err, app_context, "Error retrieving app context spend");
+                return false;
+            }
+        };
+        let over_budget = spend > *budget;
+        self.metrics
+            .budget_exceeded
+            .with_label_values(&[app_context, self.destination.name()])
+            .set(over_budget as i64);
+        over_budget
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::opts;
+
+    use super::*;
+
+    pub fn dummy_spend_metrics() -> AppContextSpendMetrics {
+        AppContextSpendMetrics {
+            daily_spend: GaugeVec::new(opts!("daily_spend", "help"), &["app_context", "remote"])
+                .unwrap(),
+            budget_exceeded: IntGaugeVec::new(
+                opts!("budget_exceeded", "help"),
+                &["app_context", "remote"],
+            )
+            .unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_app_context_over_budget() {
+        test_utils::run_test_db(|db| async move {
+            let destination = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+            let db = Arc::new(HyperlaneRocksDB::new(&destination, db));
+            let metrics = dummy_spend_metrics();
+            let tracker = AppContextSpendTracker::new(
+                destination,
+                db,
+                HashMap::from([("app".to_owned(), U256::from(100))]),
+                metrics.clone(),
+            );
+
+            tracker
+                .record_spend_on_day("app", U256::from(60), 1)
+                .unwrap();
+            assert!(!tracker.is_over_budget_on_day("app", 1));
+
+            tracker
+                .record_spend_on_day("app", U256::from(60), 1)
+                .unwrap();
+            assert!(tracker.is_over_budget_on_day("app", 1));
+            assert_eq!(
+                metrics
+                    .budget_exceeded
+                    .with_label_values(&["app", "arbitrum"])
+                    .get(),
+                1
+            );
+            assert_eq!(
+                metrics
+                    .daily_spend
+                    .with_label_values(&["app", "arbitrum"])
+                    .get(),
+                120.0
+            );
+
+            // Spend is reset every day
+            assert!(!tracker.is_over_budget_on_day("app", 2));
+
+            // App contexts without a budget are never over budget
+            tracker
+                .record_spend_on_day("other", U256::from(1000), 1)
+                .unwrap();
+            assert!(!tracker.is_over_budget_on_day("other", 1));
+        })
+        .await;
+    }
+}
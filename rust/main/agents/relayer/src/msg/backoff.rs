@@ -0,0 +1,229 @@
+//! How long messages wait before being retried after a failed attempt.
+//!
+//! Failures are grouped into classes that call for different retry rates:
+//! a missing gas payment may only be made hours later, while a checkpoint
+//! that isn't signed yet usually is within seconds. Each class has its own
+//! schedule, and delays can be jittered so that messages that failed
+//! together don't all come back at once.
+
+use std::{collections::HashMap, time::Duration};
+
+use hyperlane_core::ReprepareReason;
+
+/// What an attempt to deliver a message failed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[strum(serialize_all = "camelCase")]
+pub enum FailureClass {
+    /// The message didn't meet the gas payment requirement
+    GasUnderpayment,
+    /// The metadata the ISM needs isn't available yet, e.g. because
+    /// validators haven't signed the message's checkpoint
+    IsmNotReady,
+    /// The delivery reverted, onchain or in a classified simulation
+    Revert,
+    /// Anything else, e.g. an RPC error
+    Other,
+}
+
+impl FailureClass {
+    pub const ALL: [Self; 4] = [
+        Self::GasUnderpayment,
+        Self::IsmNotReady,
+        Self::Revert,
+        Self::Other,
+    ];
+
+    /// The class of the failure a message is reprepared for. Failed gas
+    /// estimations are only classified as reverts with pre-flight simulation
+    /// enabled.
+    pub fn of(reason: &ReprepareReason) -> Self {
+        match reason {
+            ReprepareReason::GasPaymentNotFound | ReprepareReason::GasPaymentRequirementNotMet => {
+                Self::GasUnderpayment
+            }
+            ReprepareReason::CouldNotFetchMetadata | ReprepareReason::IsmVerificationFailed => {
+                Self::IsmNotReady
+            }
+            ReprepareReason::RevertedOrReorged
+            | ReprepareReason::RecipientReverted
+            | ReprepareReason::SimulationOutOfGas
+            | ReprepareReason::ApplicationReport(_) => Self::Revert,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The delays between the attempts to deliver a message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BackoffSchedule {
+    /// Steps from 10 seconds up to hours, the schedule used for the classes
+    /// without a configured one
+    Stepped,
+    /// Starts at `initial` and is multiplied by `factor` on every retry, up
+    /// to `max`
+    Exponential {
+        initial: Duration,
+        factor: f64,
+        max: Duration,
+    },
+}
+
+impl BackoffSchedule {
+    /// The delay before the next attempt after `num_retries` failed ones, or
+    /// `None` if the message was retried too many times
+    fn delay(&self, num_retries: u32, max_retries: u32) -> Option<Duration> {
+        match self {
+            Self::Stepped => stepped_delay(num_retries, max_retries),
+            Self::Exponential { .. } if num_retries >= max_retries => None,
+            Self::Exponential {
+                initial,
+                factor,
+                max,
+            } => {
+                let exponent = i32::try_from(num_retries.saturating_sub(1)).unwrap_or(i32::MAX);
+                let delay = initial.as_secs_f64() * factor.powi(exponent);
+                // Also catches the delays that overflow to infinity
+                if delay >= max.as_secs_f64() {
+                    Some(*max)
+                } else {
+                    Some(Duration::from_secs_f64(delay))
+                }
+            }
+        }
+    }
+}
+
+fn stepped_delay(num_retries: u32, max_retries: u32) -> Option<Duration> {
+    Some(Duration::from_secs(match num_retries {
+        i if (1..10).contains(&i) => 10,
+        i if (10..15).contains(&i) => 90,
+        i if (15..25).contains(&i) => 60 * 2,
+        // linearly increase from 2min to ~25min, adding 1.5min for each additional attempt
+        i if (25..40).contains(&i) => (i as u64 - 23) * 90,
+        // wait 30min for the next 5 attempts
+        i if (40..45).contains(&i) => 60 * 30,
+        // wait 60min for the next 5 attempts
+        i if (45..50).contains(&i) => 60 * 60,
+        // linearly increase the backoff time, adding 1h for each additional attempt
+        i if (50..max_retries).contains(&i) => {
+            let hour: u64 = 60 * 60;
+            let two_hours: u64 = hour * 2;
+            // To be extra safe, `max` to make sure it's at least 2 hours.
+            let target = two_hours.max((num_retries - 49) as u64 * two_hours);
+            // Schedule it at some random point in the next 6 hours to
+            // avoid scheduling messages with the same # of retries
+            // at the exact same time and starve new messages.
+            target + (rand::random::<u64>() % (6 * hour))
+        }
+        _ => return None,
+    }))
+}
+
+/// The schedules messages are retried with, by failure class
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackoffPolicy {
+    /// The classes without a schedule use [`BackoffSchedule::Stepped`]
+    pub schedules: HashMap<FailureClass, BackoffSchedule>,
+    /// Up to which fraction of a delay it's randomly lengthened or shortened,
+    /// between 0 and 1
+    pub jitter: f64,
+}
+
+impl BackoffPolicy {
+    /// The delay before the next attempt after `num_retries` failed ones, the
+    /// last of which failed on a `class` failure. `None` if the message was
+    /// retried too many times.
+    pub fn delay(
+        &self,
+        class: FailureClass,
+        num_retries: u32,
+        max_retries: u32,
+    ) -> Option<Duration> {
+        let schedule = self
+            .schedules
+            .get(&class)
+            .unwrap_or(&BackoffSchedule::Stepped);
+        let delay = schedule.delay(num_retries, max_retries)?;
+        if self.jitter <= 0. {
+            return Some(delay);
+        }
+        // Uniformly in [-jitter, jitter)
+        let offset = (rand::random::<f64>() * 2. - 1.) * self.jitter.min(1.);
+        Some(delay.mul_f64(1. + offset))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exponential() -> BackoffSchedule {
+        BackoffSchedule::Exponential {
+            initial: Duration::from_secs(5),
+            factor: 2.,
+            max: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_exponential_schedule_is_capped() {
+        let delays: Vec<_> = (1..=6)
+            .map(|i| exponential().delay(i, 100).unwrap().as_secs())
+            .collect();
+        assert_eq!(delays, vec![5, 10, 20, 40, 60, 60]);
+        assert_eq!(
+            exponential().delay(u32::MAX - 1, u32::MAX),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(exponential().delay(100, 100), None);
+    }
+
+    #[test]
+    fn test_policy_uses_the_schedule_of_the_class() {
+        let policy = BackoffPolicy {
+            schedules: HashMap::from([(FailureClass::IsmNotReady, exponential())]),
+            jitter: 0.,
+        };
+        assert_eq!(
+            policy.delay(FailureClass::IsmNotReady, 3, 100),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            policy.delay(FailureClass::GasUnderpayment, 3, 100),
+            Some(Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let policy = BackoffPolicy {
+            schedules: HashMap::from([(FailureClass::Other, exponential())]),
+            jitter: 0.5,
+        };
+        for _ in 0..100 {
+            let delay = policy.delay(FailureClass::Other, 1, 100).unwrap();
+            assert!(delay >= Duration::from_secs_f64(2.5));
+            assert!(delay <= Duration::from_secs_f64(7.5));
+        }
+    }
+
+    #[test]
+    fn test_failures_are_classified() {
+        assert_eq!(
+            FailureClass::of(&ReprepareReason::GasPaymentNotFound),
+            FailureClass::GasUnderpayment
+        );
+        assert_eq!(
+            FailureClass::of(&ReprepareReason::CouldNotFetchMetadata),
+            FailureClass::IsmNotReady
+        );
+        assert_eq!(
+            FailureClass::of(&ReprepareReason::RevertedOrReorged),
+            FailureClass::Revert
+        );
+        assert_eq!(
+            FailureClass::of(&ReprepareReason::ErrorSubmitting),
+            FailureClass::Other
+        );
+    }
+}
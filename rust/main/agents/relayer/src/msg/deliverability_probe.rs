@@ -0,0 +1,137 @@
+use prometheus::{HistogramVec, IntCounterVec};
+use tracing::info;
+
+use hyperlane_base::u256_as_f64;
+use hyperlane_core::{PendingOperationResult, QueueOperation};
+
+/// Buckets of the gas estimated for delivering a message
+pub const PROBE_ESTIMATED_GAS_BUCKETS: &[f64] = &[
+    25_000.0,
+    50_000.0,
+    100_000.0,
+    200_000.0,
+    400_000.0,
+    800_000.0,
+    1_600_000.0,
+    3_200_000.0,
+    6_400_000.0,
+    12_800_000.0,
+];
+
+/// Metrics exported by the relayer in probe mode
+#[derive(Debug, Clone)]
+pub struct DeliverabilityProbeMetrics {
+    /// Prepare outcomes, by app context, destination and outcome
+    pub outcomes: IntCounterVec,
+    /// Gas estimated for delivering deliverable messages, by app context and
+    /// destination
+    pub estimated_gas: HistogramVec,
+}
+
+/// Records whether messages could be delivered when the relayer runs in probe
+/// mode.
+///
+/// In probe mode every message goes through the full prepare pipeline, i.e.
+/// the delivery status and gas payment checks, metadata building and gas
+/// estimation, but is never submitted. Operators can use it to evaluate a new
+/// route before funding a relayer key for it.
+#[derive(Debug)]
+pub struct DeliverabilityProbe {
+    metrics: DeliverabilityProbeMetrics,
+}
+
+impl DeliverabilityProbe {
+    pub fn new(metrics: DeliverabilityProbeMetrics) -> Self {
+        Self { metrics }
+    }
+
+    /// Records the result of preparing the operation. Operations that aren't
+    /// ready yet aren't recorded.
+    pub fn record(&self, op: &QueueOperation, result: &PendingOperationResult) {
+        let outcome = match result {
+            PendingOperationResult::Success => "deliverable".to_owned(),
+            PendingOperationResult::Reprepare(reason) => reason.to_string(),
+            PendingOperationResult::Confirm(_) => "already_delivered".to_owned(),
+            PendingOperationResult::Drop => "dropped".to_owned(),
+            PendingOperationResult::NotReady => return,
+        };
+        let (destination, app_context) = op.get_operation_labels();
+        let estimated_gas = op.get_tx_cost_estimate();
+        info!(
+            id = ?op.id(),
+            %destination,
+            %app_context,
+            %outcome,
+            ?estimated_gas,
+            retry_count = op.retry_count(),
+            "Probed message deliverability"
+        );
+        self.metrics
+            .outcomes
+            .with_label_values(&[&app_context, &destination, &outcome])
+            .inc();
+        if let (PendingOperationResult::Success, Some(gas)) = (result, estimated_gas) {
+            self.metrics
+                .estimated_gas
+                .with_label_values(&[&app_context, &destination])
+                .observe(u256_as_f64(gas));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain, ReprepareReason};
+    use prometheus::{core::Collector, histogram_opts, opts};
+
+    use crate::msg::op_queue::test::MockPendingOperation;
+
+    use super::*;
+
+    #[test]
+    fn test_records_prepare_outcomes() {
+        let metrics = DeliverabilityProbeMetrics {
+            outcomes: IntCounterVec::new(
+                opts!("outcomes", "help"),
+                &["app_context", "remote", "outcome"],
+            )
+            .unwrap(),
+            estimated_gas: HistogramVec::new(
+                histogram_opts!("estimated_gas", "help"),
+                &["app_context", "remote"],
+            )
+            .unwrap(),
+        };
+        let probe = DeliverabilityProbe::new(metrics.clone());
+        let op: QueueOperation = Box::new(MockPendingOperation::new(
+            0,
+            HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
+        ));
+
+        probe.record(&op, &PendingOperationResult::Success);
+        probe.record(
+            &op,
+            &PendingOperationResult::Reprepare(ReprepareReason::GasPaymentRequirementNotMet),
+        );
+        probe.record(
+            &op,
+            &PendingOperationResult::Reprepare(ReprepareReason::GasPaymentRequirementNotMet),
+        );
+        probe.record(&op, &PendingOperationResult::NotReady);
+
+        let outcome_count =
+            |outcome: &str| metrics.outcomes.with_label_values(&["", "", outcome]).get();
+        assert_eq!(outcome_count("deliverable"), 1);
+        assert_eq!(outcome_count("Gas payment requirement not met"), 2);
+        // Operations that aren't ready yet aren't recorded
+        assert_eq!(
+            metrics
+                .outcomes
+                .collect()
+                .iter()
+                .map(|family| family.get_metric().len())
+                .sum::<usize>(),
+            2
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Mutex;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, Mailbox, H256};
+use tokio::sync::oneshot;
+
+type PendingCheck = (H256, oneshot::Sender<Result<bool, String>>);
+
+/// Coalesces the delivery checks that the confirm stage runs concurrently for
+/// a destination into `Mailbox::delivered_batch` queries, so destinations
+/// that support batched queries are asked once per confirm batch instead of
+/// once per message.
+#[derive(Debug, Default)]
+pub struct DeliveryStatusBatcher {
+    pending: Mutex<Vec<PendingCheck>>,
+}
+
+impl DeliveryStatusBatcher {
+    /// Returns whether the message with `id` was delivered to `mailbox`
+    pub async fn delivered(&self, mailbox: &dyn Mailbox, id: H256) -> ChainResult<bool> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().push((id, sender));
+
+        // Let the other checks of the confirm batch enqueue themselves. Whichever
+        // check resumes first answers all of them, including this one.
+        tokio::task::yield_now().await;
+        let checks = std::mem::take(&mut *self.pending.lock().unwrap());
+        if !checks.is_empty() {
+            Self::check_batch(mailbox, checks).await;
+        }
+
+        receiver
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .map_err(|err| ChainCommunicationError::from_other_str(&err))
+    }
+
+    async fn check_batch(mailbox: &dyn Mailbox, checks: Vec<PendingCheck>) {
+        let ids: Vec<H256> = checks.iter().map(|(id, _)| *id).collect();
+        match mailbox.delivered_batch(&ids).await {
+            Ok(statuses) if statuses.len() == checks.len() => {
+                for ((_, sender), delivered) in checks.into_iter().zip(statuses) {
+                    let _ = sender.send(Ok(delivered));
+                }
+            }
+            Ok(statuses) => {
+                let err = format!(
+                    "Expected {} delivery statuses, got {}",
+                    checks.len(),
+                    statuses.len()
+                );
+                for (_, sender) in checks {
+                    let _ = sender.send(Err(err.clone()));
+                }
+            }
+            Err(err) => {
+                // Chain errors can't be cloned, so every check gets its message
+                let err = err.to_string();
+                for (_, sender) in checks {
+                    let _ = sender.send(Err(err.clone()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::future::join_all;
+    use hyperlane_test::mocks::MockMailboxContract;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_concurrent_checks_are_batched() {
+        let delivered_id = H256::random();
+        let undelivered_id = H256::random();
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__delivered_batch()
+            .times(1)
+            .returning(move |ids| Ok(ids.iter().map(|id| *id == delivered_id).collect()));
+
+        let batcher = DeliveryStatusBatcher::default();
+        let statuses = join_all([
+            batcher.delivered(&mailbox, delivered_id),
+            batcher.delivered(&mailbox, undelivered_id),
+            batcher.delivered(&mailbox, delivered_id),
+        ])
+        .await;
+
+        let statuses: Vec<bool> = statuses.into_iter().map(Result::unwrap).collect();
+        assert_eq!(statuses, vec![true, false, true]);
+    }
+}
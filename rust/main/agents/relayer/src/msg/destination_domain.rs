@@ -0,0 +1,43 @@
+use hyperlane_core::{ChainResult, Mailbox};
+use tokio::sync::OnceCell;
+
+/// Caches the domain id reported by a destination mailbox, so every message
+/// can be checked against it right before submission without querying the
+/// chain each time.
+///
+/// A mismatch means the destination's RPC or addresses point at the wrong
+/// network, and submitting there would waste gas or deliver to the wrong chain.
+#[derive(Debug, Default)]
+pub struct DestinationDomainCache {
+    local_domain: OnceCell<u32>,
+}
+
+impl DestinationDomainCache {
+    /// Returns the domain id of `mailbox`, fetching it if it isn't cached yet
+    pub async fn local_domain(&self, mailbox: &dyn Mailbox) -> ChainResult<u32> {
+        self.local_domain
+            .get_or_try_init(|| mailbox.local_domain())
+            .await
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_test::mocks::MockMailboxContract;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_domain_is_cached() {
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__local_domain()
+            .times(1)
+            .returning(|| Ok(1234));
+
+        let cache = DestinationDomainCache::default();
+        assert_eq!(cache.local_domain(&mailbox).await.unwrap(), 1234);
+        assert_eq!(cache.local_domain(&mailbox).await.unwrap(), 1234);
+    }
+}
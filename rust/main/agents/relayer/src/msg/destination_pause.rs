@@ -0,0 +1,184 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::H256;
+use prometheus::IntGauge;
+use tracing::{info, warn};
+
+/// How often a message is let through to check whether a paused destination
+/// was unpaused
+pub const PAUSE_RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Revert reasons of paused contracts, as they appear in the errors of gas
+/// estimations: the `Pausable: paused` error string of OpenZeppelin's
+/// `Pausable` before v5, also hex encoded
+const PAUSE_REVERT_REASONS: &[&str] = &["Pausable: paused", "5061757361626c653a20706175736564"];
+
+/// The revert data of OpenZeppelin's `EnforcedPause()` error since v5, which
+/// must be all of the revert data
+const ENFORCED_PAUSE_REVERT_DATA: &str = "0xd93c0665";
+
+/// Whether the error of a gas estimation is the revert of a paused contract,
+/// e.g. the destination mailbox, the recipient's ISM or the recipient itself
+pub fn is_pause_error(err: &impl Debug) -> bool {
+    let err = format!("{err:?}");
+    PAUSE_REVERT_REASONS
+        .iter()
+        .any(|reason| err.contains(reason))
+        || err
+            .match_indices(ENFORCED_PAUSE_REVERT_DATA)
+            .any(|(start, data)| {
+                !err[start + data.len()..].starts_with(|c: char| c.is_ascii_hexdigit())
+            })
+}
+
+/// Tracks which recipients of a destination can't be delivered to because a
+/// contract their deliveries go through is paused.
+///
+/// The revert of a gas estimation doesn't tell which contract is paused, and
+/// paused recipients, e.g. of warp routes, are much more common than paused
+/// mailboxes, so pauses are tracked per recipient. While a recipient is
+/// paused, its messages are deferred without counting as failed attempts,
+/// instead of each one building metadata and estimating gas only to be
+/// retried. Once every `PAUSE_RECHECK_INTERVAL`, a single message of the
+/// recipient is let through to check whether the pause was lifted.
+#[derive(Debug, Default)]
+pub struct DestinationPauseTracker {
+    paused: Mutex<HashMap<H256, PauseState>>,
+    /// The number of paused recipients
+    paused_gauge: Option<IntGauge>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PauseState {
+    since: Instant,
+    last_check: Instant,
+}
+
+impl DestinationPauseTracker {
+    pub fn new(paused_gauge: IntGauge) -> Self {
+        Self {
+            paused: Default::default(),
+            paused_gauge: Some(paused_gauge),
+        }
+    }
+
+    /// Whether a message to `recipient` should be deferred because it's
+    /// paused. Returns false for the message that checks whether it still is.
+    pub fn should_defer(&self, recipient: H256) -> bool {
+        self.should_defer_at(recipient, Instant::now())
+    }
+
+    /// Records that a delivery to `recipient` reverted because it's paused
+    pub fn mark_paused(&self, recipient: H256) {
+        self.mark_paused_at(recipient, Instant::now())
+    }
+
+    /// Records that a delivery to `recipient` could be simulated, so it isn't
+    /// paused
+    pub fn mark_unpaused(&self, recipient: H256) {
+        let mut paused = self.paused.lock().expect("pause state lock poisoned");
+        if let Some(pause) = paused.remove(&recipient) {
+            info!(
+                ?recipient,
+                paused_for = ?pause.since.elapsed(),
+                "Recipient is no longer paused, resuming deliveries"
+            );
+            self.set_gauge(paused.len());
+        }
+    }
+
+    fn should_defer_at(&self, recipient: H256, now: Instant) -> bool {
+        let mut paused = self.paused.lock().expect("pause state lock poisoned");
+        let Some(pause) = paused.get_mut(&recipient) else {
+            return false;
+        };
+        if now.saturating_duration_since(pause.last_check) < PAUSE_RECHECK_INTERVAL {
+            return true;
+        }
+        pause.last_check = now;
+        false
+    }
+
+    fn mark_paused_at(&self, recipient: H256, now: Instant) {
+        let mut paused = self.paused.lock().expect("pause state lock poisoned");
+        match paused.get_mut(&recipient) {
+            Some(pause) => pause.last_check = now,
+            None => {
+                warn!(
+                    ?recipient,
+                    "Recipient is paused, deferring deliveries until it is unpaused"
+                );
+                paused.insert(
+                    recipient,
+                    PauseState {
+                        since: now,
+                        last_check: now,
+                    },
+                );
+                self.set_gauge(paused.len());
+            }
+        }
+    }
+
+    fn set_gauge(&self, paused: usize) {
+        if let Some(gauge) = &self.paused_gauge {
+            gauge.set(paused as i64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pause_errors_are_detected() {
+        assert!(is_pause_error(&"execution reverted: Pausable: paused"));
+        assert!(is_pause_error(
+            &"Contract call reverted with data: 0x08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000105061757361626c653a2070617573656400000000000000000000000000000000"
+        ));
+        assert!(is_pause_error(
+            &"Contract call reverted with data: 0xd93c0665"
+        ));
+        assert!(is_pause_error(&"Revert(Bytes(0xd93c0665))"));
+        assert!(!is_pause_error(&"execution reverted: !threshold"));
+        // The selector within other revert data or hashes
+        assert!(!is_pause_error(
+            &"Contract call reverted with data: 0xd93c06650000000000000000000000000000000000000000000000000000000000000001"
+        ));
+        assert!(!is_pause_error(&"tx 0xabd93c0665 reverted"));
+    }
+
+    #[test]
+    fn test_paused_recipient_is_rechecked_periodically() {
+        let tracker = DestinationPauseTracker::default();
+        let recipient = H256::from_low_u64_be(1);
+        let other_recipient = H256::from_low_u64_be(2);
+        let start = Instant::now();
+        assert!(!tracker.should_defer_at(recipient, start));
+
+        tracker.mark_paused_at(recipient, start);
+        assert!(tracker.should_defer_at(recipient, start));
+        assert!(tracker.should_defer_at(recipient, start + PAUSE_RECHECK_INTERVAL / 2));
+        // Other recipients of the destination are still delivered to
+        assert!(!tracker.should_defer_at(other_recipient, start));
+
+        // One message checks whether the recipient is still paused, while
+        // the others keep waiting
+        let recheck = start + PAUSE_RECHECK_INTERVAL;
+        assert!(!tracker.should_defer_at(recipient, recheck));
+        assert!(tracker.should_defer_at(recipient, recheck));
+
+        // It still is, so the next check is an interval later
+        tracker.mark_paused_at(recipient, recheck);
+        assert!(tracker.should_defer_at(recipient, recheck + PAUSE_RECHECK_INTERVAL / 2));
+
+        tracker.mark_unpaused(recipient);
+        assert!(!tracker.should_defer_at(recipient, recheck + PAUSE_RECHECK_INTERVAL / 2));
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::{Debug, Display},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_base::settings::ChainConf;
+use hyperlane_core::{HyperlaneDomain, QueueOperation, H256, H512};
+use prometheus::{core::Collector, IntCounterVec};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+/// How many of the most recent failures are included in a bundle
+const MAX_RECENT_FAILURES: usize = 20;
+
+/// The stage of an operation's lifecycle it failed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStage {
+    Submit,
+    Confirm,
+}
+
+/// Collects the evidence needed to investigate a destination that keeps
+/// failing to deliver operations, so that incident response and bug reports
+/// don't start with gathering logs and metrics by hand.
+///
+/// Once `threshold` submissions in a row failed, i.e. operations that failed
+/// to be submitted or whose submission didn't confirm, a bundle is logged as
+/// a single JSON object. It holds the recent failures, the RPC request counts
+/// of the destination, the last successful delivery and a hash of the
+/// destination's config. Another bundle is only collected after the
+/// destination recovered.
+pub struct EvidenceCollector {
+    destination: HyperlaneDomain,
+    threshold: u32,
+    /// Hash of the destination's config, to tell whether it changed between
+    /// incidents
+    config_hash: H256,
+    /// RPC requests by provider, chain, method and status
+    rpc_request_count: Option<IntCounterVec>,
+    history: Mutex<FailureHistory>,
+}
+
+#[derive(Debug, Default)]
+struct FailureHistory {
+    consecutive_failures: u32,
+    recent_failures: VecDeque<FailedSubmission>,
+    last_success: Option<SuccessfulSubmission>,
+}
+
+/// Everything collected about a destination when it reached the threshold
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EvidenceBundle {
+    pub destination: String,
+    /// Unix timestamp of the collection, in seconds
+    pub collected_at: u64,
+    pub consecutive_failures: u32,
+    /// The most recent failures, oldest first
+    pub recent_failures: Vec<FailedSubmission>,
+    pub last_success: Option<SuccessfulSubmission>,
+    pub rpc_health: Vec<RpcHealth>,
+    pub config_hash: H256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailedSubmission {
+    pub id: H256,
+    pub stage: SubmissionStage,
+    pub reason: String,
+    pub retry_count: u32,
+    /// Unix timestamp of the failure, in seconds
+    pub failed_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SuccessfulSubmission {
+    pub id: H256,
+    pub tx_hash: Option<H512>,
+    /// Unix timestamp of the confirmation, in seconds
+    pub confirmed_at: u64,
+}
+
+/// The RPC requests made to a provider of the destination since startup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RpcHealth {
+    pub provider_node: String,
+    pub successes: u64,
+    pub failures: u64,
+}
+
+impl Debug for EvidenceCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // intentionally leaves out the metrics
+        f.debug_struct("EvidenceCollector")
+            .field("destination", &self.destination)
+            .field("threshold", &self.threshold)
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+impl EvidenceCollector {
+    pub fn new(
+        destination: HyperlaneDomain,
+        threshold: u32,
+        destination_conf: &ChainConf,
+        rpc_request_count: Option<IntCounterVec>,
+    ) -> Self {
+        Self {
+            destination,
+            threshold,
+            config_hash: config_hash(destination_conf),
+            rpc_request_count,
+            history: Default::default(),
+        }
+    }
+
+    /// Records that submitting the operation, or confirming its submission,
+    /// failed. Returns the bundle if this failure reached the threshold.
+    pub fn record_failure(
+        &self,
+        op: &QueueOperation,
+        stage: SubmissionStage,
+        reason: impl Display,
+    ) -> Option<EvidenceBundle> {
+        let bundle = {
+            let mut history = self.history.lock().expect("evidence lock poisoned");
+            history.consecutive_failures += 1;
+            if history.recent_failures.len() >= MAX_RECENT_FAILURES {
+                history.recent_failures.pop_front();
+            }
+            history.recent_failures.push_back(FailedSubmission {
+                id: op.id(),
+                stage,
+                reason: reason.to_string(),
+                retry_count: op.retry_count(),
+                failed_at: now(),
+            });
+            (history.consecutive_failures == self.threshold).then(|| self.bundle(&history))
+        }?;
+        match serde_json::to_string(&bundle) {
+            Ok(json) => error!(
+                destination = %self.destination,
+                consecutive_failures = bundle.consecutive_failures,
+                evidence_bundle = %json,
+                "Destination keeps failing to deliver operations, collected evidence bundle"
+            ),
+            Err(err) => error!(?err, ?bundle, "Failed to serialize evidence bundle"),
+        }
+        Some(bundle)
+    }
+
+    /// Records that the operation's submission was confirmed, which resets
+    /// the consecutive failures
+    pub fn record_success(&self, op: &QueueOperation) {
+        let mut history = self.history.lock().expect("evidence lock poisoned");
+        history.consecutive_failures = 0;
+        history.last_success = Some(SuccessfulSubmission {
+            id: op.id(),
+            tx_hash: op.submission_tx_hash(),
+            confirmed_at: now(),
+        });
+    }
+
+    fn bundle(&self, history: &FailureHistory) -> EvidenceBundle {
+        EvidenceBundle {
+            destination: self.destination.name().to_owned(),
+            collected_at: now(),
+            consecutive_failures: history.consecutive_failures,
+            recent_failures: history.recent_failures.iter().cloned().collect(),
+            last_success: history.last_success.clone(),
+            rpc_health: self.rpc_health(),
+            config_hash: self.config_hash,
+        }
+    }
+
+    /// Successful and failed RPC requests to the destination, by provider
+    fn rpc_health(&self) -> Vec<RpcHealth> {
+        let Some(request_count) = &self.rpc_request_count else {
+            return vec![];
+        };
+        let mut by_provider: BTreeMap<String, RpcHealth> = BTreeMap::new();
+        for family in request_count.collect() {
+            for metric in family.get_metric() {
+                let label = |name: &str| {
+                    metric
+                        .get_label()
+                        .iter()
+                        .find(|pair| pair.get_name() == name)
+                        .map(|pair| pair.get_value().to_owned())
+                        .unwrap_or_default()
+                };
+                if label("chain") != self.destination.name() {
+                    continue;
+                }
+                let provider_node = label("provider_node");
+                let health =
+                    by_provider
+                        .entry(provider_node.clone())
+                        .or_insert_with(|| RpcHealth {
+                            provider_node,
+                            successes: 0,
+                            failures: 0,
+                        });
+                let count = metric.get_counter().get_value() as u64;
+                if label("status") == "success" {
+                    health.successes += count;
+                } else {
+                    health.failures += count;
+                }
+            }
+        }
+        by_provider.into_values().collect()
+    }
+}
+
+/// Identifies the config of a chain, e.g. its RPCs and contract addresses,
+/// without revealing it
+fn config_hash(conf: &ChainConf) -> H256 {
+    H256::from_slice(&Sha256::digest(format!("{conf:?}").as_bytes()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{KnownHyperlaneDomain, ReprepareReason};
+    use prometheus::opts;
+
+    use crate::msg::op_queue::test::MockPendingOperation;
+
+    use super::*;
+
+    fn collector(threshold: u32) -> EvidenceCollector {
+        let destination = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let request_count = IntCounterVec::new(
+            opts!("request_count", "help"),
+            &["provider_node", "chain", "method", "status"],
+        )
+        .unwrap();
+        request_count
+            .with_label_values(&["rpc.example", "arbitrum", "eth_call", "success"])
+            .inc_by(3);
+        request_count
+            .with_label_values(&["rpc.example", "arbitrum", "eth_call", "failure"])
+            .inc_by(2);
+        request_count
+            .with_label_values(&["rpc.example", "ethereum", "eth_call", "failure"])
+            .inc();
+        EvidenceCollector {
+            destination,
+            threshold,
+            config_hash: H256::repeat_byte(1),
+            rpc_request_count: Some(request_count),
+            history: Default::default(),
+        }
+    }
+
+    fn op(seconds_to_next_attempt: u64) -> QueueOperation {
+        Box::new(MockPendingOperation::new(
+            seconds_to_next_attempt,
+            HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
+        ))
+    }
+
+    #[test]
+    fn test_bundle_is_collected_once_per_incident() {
+        let collector = collector(3);
+        let reason = ReprepareReason::ErrorSubmitting;
+
+        assert!(collector
+            .record_failure(&op(0), SubmissionStage::Submit, &reason)
+            .is_none());
+        let confirmed = op(1);
+        collector.record_success(&confirmed);
+        assert!(collector
+            .record_failure(&op(2), SubmissionStage::Submit, &reason)
+            .is_none());
+        assert!(collector
+            .record_failure(&op(3), SubmissionStage::Confirm, &reason)
+            .is_none());
+        let bundle = collector
+            .record_failure(&op(4), SubmissionStage::Submit, &reason)
+            .unwrap();
+        assert_eq!(bundle.destination, "arbitrum");
+        assert_eq!(bundle.consecutive_failures, 3);
+        // Failures before the last success are still included
+        assert_eq!(bundle.recent_failures.len(), 4);
+        assert_eq!(bundle.recent_failures[2].stage, SubmissionStage::Confirm);
+        assert_eq!(bundle.last_success.unwrap().id, confirmed.id());
+        assert_eq!(
+            bundle.rpc_health,
+            [RpcHealth {
+                provider_node: "rpc.example".to_owned(),
+                successes: 3,
+                failures: 2,
+            }]
+        );
+        assert_eq!(bundle.config_hash, H256::repeat_byte(1));
+
+        // Until the destination recovers, no other bundle is collected
+        assert!(collector
+            .record_failure(&op(5), SubmissionStage::Submit, &reason)
+            .is_none());
+    }
+}
@@ -0,0 +1,529 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_base::{
+    db::{HyperlaneRocksDB, DB},
+    u256_as_f64, PeriodicSpend,
+};
+use hyperlane_core::{FixedPointNumber, TxOutcome, U256, U512};
+use prometheus::GaugeVec;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::{msg::op_queue::OperationPriorityQueue, settings::FundingPoolConf};
+
+/// How often the allowances are rebalanced by the pending operations
+pub const FUNDING_POOL_REBALANCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Metrics of the funding pools, by pool and destination
+#[derive(Debug, Clone)]
+pub struct FundingPoolMetrics {
+    /// What the destination may spend in the current period, in pool units
+    pub allowance: GaugeVec,
+    /// What the destination spent in the current period, in pool units
+    pub spend: GaugeVec,
+}
+
+/// The gas the operations pending on a destination are expected to need
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PendingGas {
+    /// Sum of the gas estimates of the operations that were prepared
+    pub estimated: U256,
+    /// Operations without a gas estimate yet, which are assumed to need as
+    /// much gas as the last delivery to the destination
+    pub unestimated: usize,
+}
+
+/// Schedules the spend of destinations whose signers are funded by the same
+/// wallet, so that one busy destination doesn't drain the funds the others
+/// need.
+///
+/// Each pool has a budget per period, in a unit common to its destinations.
+/// What each destination spends is converted to it with the destination's
+/// exchange rate. The budget left in the period is periodically split
+/// between the destinations proportionally to their weight times the value
+/// of their pending operations, i.e. their gas at the price last paid on the
+/// destination. The average value of the destinations is added to each, so
+/// that idle destinations keep a share. A destination that spent its
+/// allowance isn't delivered to until the next rebalance gives it more, or
+/// until the next period.
+///
+/// The spend of each destination is persisted in its database, so a restart
+/// doesn't reset the period.
+pub struct FundingScheduler {
+    pools: Vec<FundingPool>,
+    /// The pool of each destination, by domain id
+    pool_by_destination: HashMap<u32, usize>,
+    metrics: FundingPoolMetrics,
+}
+
+struct FundingPool {
+    conf: FundingPoolConf,
+    /// What the spend of the pool is recorded as. Only the number of the
+    /// period is stored with it, so it includes the length of the period.
+    spender: String,
+    /// The spend of each destination, by domain id
+    spend: HashMap<u32, PeriodicSpend>,
+    state: Mutex<FundingPoolState>,
+}
+
+#[derive(Debug, Default)]
+struct FundingPoolState {
+    /// The period the spend is for, in periods since the unix epoch
+    period: u64,
+    spent: HashMap<u32, U256>,
+    allowances: HashMap<u32, U256>,
+    /// What a unit of gas last cost on each destination, in pool units
+    gas_prices: HashMap<u32, FixedPointNumber>,
+    /// The gas the last delivery to each destination used
+    gas_per_delivery: HashMap<u32, U256>,
+}
+
+impl std::fmt::Debug for FundingScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FundingScheduler")
+            .field(
+                "pools",
+                &self
+                    .pools
+                    .iter()
+                    .map(|pool| &pool.conf.name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl FundingScheduler {
+    pub fn new(confs: Vec<FundingPoolConf>, db: &DB, metrics: FundingPoolMetrics) -> Self {
+        let pool_by_destination = confs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, conf)| conf.members.keys().map(move |domain| (*domain, index)))
+            .collect();
+        let pools = confs
+            .into_iter()
+            .map(|conf| FundingPool {
+                spender: format!("funding_pool_{}_{}s", conf.name, conf.period.as_secs()),
+                spend: conf
+                    .members
+                    .iter()
+                    .map(|(destination, member)| {
+                        let db = HyperlaneRocksDB::new(&member.domain, db.clone());
+                        (*destination, PeriodicSpend::new(Arc::new(db)))
+                    })
+                    .collect(),
+                conf,
+                state: Default::default(),
+            })
+            .collect();
+        Self {
+            pools,
+            pool_by_destination,
+            metrics,
+        }
+    }
+
+    /// Whether the destination spent its allowance of its pool. Destinations
+    /// outside of pools never do.
+    pub fn is_exhausted(&self, destination: u32) -> bool {
+        self.is_exhausted_at(destination, now())
+    }
+
+    /// Adds what delivering an operation to the destination cost to its spend,
+    /// and notes the gas price paid to value its pending operations
+    pub fn record_spend(&self, destination: u32, outcome: &TxOutcome) {
+        self.record_spend_at(destination, outcome, now())
+    }
+
+    /// Splits the budget left in each pool by the value of the operations
+    /// pending on each destination
+    pub fn rebalance(&self, pending: &HashMap<u32, PendingGas>) {
+        self.rebalance_at(pending, now())
+    }
+
+    /// Spawns a tokio task that rebalances the pools every `interval` by the
+    /// operations in the prepare queues of the destinations
+    pub fn spawn(
+        self: Arc<Self>,
+        prepare_queues: HashMap<u32, OperationPriorityQueue>,
+        interval: Duration,
+    ) -> Instrumented<JoinHandle<()>> {
+        let prepare_queues: HashMap<u32, OperationPriorityQueue> = prepare_queues
+            .into_iter()
+            .filter(|(domain, _)| self.pool_by_destination.contains_key(domain))
+            .collect();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let mut pending = HashMap::with_capacity(prepare_queues.len());
+                for (domain, queue) in &prepare_queues {
+                    let mut gas = PendingGas::default();
+                    for Reverse(operation) in queue.lock().await.iter() {
+                        match operation.get_tx_cost_estimate() {
+                            Some(estimate) => {
+                                gas.estimated = gas.estimated.saturating_add(estimate)
+                            }
+                            None => gas.unestimated += 1,
+                        }
+                    }
+                    pending.insert(*domain, gas);
+                }
+                self.rebalance(&pending);
+            }
+        })
+        .instrument(info_span!("FundingScheduler"))
+    }
+
+    fn is_exhausted_at(&self, destination: u32, now: u64) -> bool {
+        let Some(pool) = self.pool(destination) else {
+            return false;
+        };
+        let state = pool.state(now, &self.metrics);
+        let spent = state.spent.get(&destination).copied().unwrap_or_default();
+        let allowance = state
+            .allowances
+            .get(&destination)
+            .copied()
+            .unwrap_or_default();
+        spent >= allowance
+    }
+
+    fn record_spend_at(&self, destination: u32, outcome: &TxOutcome, now: u64) {
+        let Some(pool) = self.pool(destination) else {
+            return;
+        };
+        let Some(member) = pool.conf.members.get(&destination) else {
+            return;
+        };
+        let gas_price = outcome.gas_price.clone() * member.exchange_rate.clone();
+        let pool_units: U256 = match FixedPointNumber::try_from(outcome.gas_used)
+            .and_then(|gas_used| (gas_used * gas_price.clone()).try_into())
+        {
+            Ok(pool_units) => pool_units,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    pool = %pool.conf.name,
+                    destination,
+                    ?outcome,
+                    "Failed to convert spend to pool units"
+                );
+                return;
+            }
+        };
+        let mut state = pool.state(now, &self.metrics);
+        state.gas_prices.insert(destination, gas_price);
+        state.gas_per_delivery.insert(destination, outcome.gas_used);
+        let spent = match pool.spend[&destination].record_in_period(
+            &pool.spender,
+            pool_units,
+            state.period,
+        ) {
+            Ok(spent) => spent,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    pool = %pool.conf.name,
+                    destination,
+                    "Failed to persist funding pool spend"
+                );
+                state
+                    .spent
+                    .get(&destination)
+                    .copied()
+                    .unwrap_or_default()
+                    .saturating_add(pool_units)
+            }
+        };
+        state.spent.insert(destination, spent);
+        self.metrics
+            .spend
+            .with_label_values(&[pool.conf.name.as_str(), member.domain.name()])
+            .set(u256_as_f64(spent));
+    }
+
+    fn rebalance_at(&self, pending: &HashMap<u32, PendingGas>, now: u64) {
+        for pool in &self.pools {
+            let mut state = pool.state(now, &self.metrics);
+            pool.allocate(&mut state, pending, &self.metrics);
+        }
+    }
+
+    fn pool(&self, destination: u32) -> Option<&FundingPool> {
+        self.pool_by_destination
+            .get(&destination)
+            .map(|index| &self.pools[*index])
+    }
+}
+
+impl FundingPool {
+    /// The state of the pool, with the spend of the period loaded from the
+    /// database if a new period started since it was last used
+    fn state(
+        &self,
+        now: u64,
+        metrics: &FundingPoolMetrics,
+    ) -> std::sync::MutexGuard<'_, FundingPoolState> {
+        let mut state = self.state.lock().expect("funding pool lock poisoned");
+        let period = now / self.conf.period.as_secs().max(1);
+        if state.period != period || state.allowances.is_empty() {
+            state.period = period;
+            state.spent = self.load_spent(period, metrics);
+            self.allocate(&mut state, &HashMap::new(), metrics);
+        }
+        state
+    }
+
+    fn load_spent(&self, period: u64, metrics: &FundingPoolMetrics) -> HashMap<u32, U256> {
+        self.conf
+            .members
+            .iter()
+            .map(|(destination, member)| {
+                let spent = self.spend[destination]
+                    .spent_in_period(&self.spender, period)
+                    .unwrap_or_else(|err| {
+                        warn!(
+                            ?err,
+                            pool = %self.conf.name,
+                            destination,
+                            "Failed to load funding pool spend"
+                        );
+                        U256::zero()
+                    });
+                metrics
+                    .spend
+                    .with_label_values(&[self.conf.name.as_str(), member.domain.name()])
+                    .set(u256_as_f64(spent));
+                (*destination, spent)
+            })
+            .collect()
+    }
+
+    /// Splits the budget left in the period between the destinations, by
+    /// their weight times the value of their pending operations plus the
+    /// average value
+    fn allocate(
+        &self,
+        state: &mut FundingPoolState,
+        pending: &HashMap<u32, PendingGas>,
+        metrics: &FundingPoolMetrics,
+    ) {
+        let spent = state
+            .spent
+            .values()
+            .fold(U256::zero(), |total, spent| total.saturating_add(*spent));
+        let remaining = self.conf.budget.saturating_sub(spent);
+        let values: HashMap<u32, U256> = self
+            .conf
+            .members
+            .keys()
+            .map(|destination| {
+                let pending = pending.get(destination).copied().unwrap_or_default();
+                (*destination, state.pending_value(*destination, pending))
+            })
+            .collect();
+        let total_value = values
+            .values()
+            .fold(U256::zero(), |total, value| total.saturating_add(*value));
+        // Without anything pending, the budget is split by weight
+        let average_value = (total_value / U256::from(values.len().max(1))).max(U256::one());
+        let demands: HashMap<u32, U256> = self
+            .conf
+            .members
+            .iter()
+            .map(|(destination, member)| {
+                let value = values[destination].saturating_add(average_value);
+                (
+                    *destination,
+                    U256::from(member.weight).saturating_mul(value),
+                )
+            })
+            .collect();
+        let total_demand = demands
+            .values()
+            .fold(U256::zero(), |total, demand| total.saturating_add(*demand));
+        if total_demand.is_zero() {
+            return;
+        }
+
+        for (destination, demand) in demands {
+            let member = &self.conf.members[&destination];
+            let share = U512::from(remaining) * U512::from(demand) / U512::from(total_demand);
+            let share = U256::try_from(share).unwrap_or(U256::MAX);
+            let allowance = state
+                .spent
+                .get(&destination)
+                .copied()
+                .unwrap_or_default()
+                .saturating_add(share);
+            state.allowances.insert(destination, allowance);
+            metrics
+                .allowance
+                .with_label_values(&[self.conf.name.as_str(), member.domain.name()])
+                .set(u256_as_f64(allowance));
+        }
+    }
+}
+
+impl FundingPoolState {
+    /// What the pending operations of the destination are expected to cost,
+    /// in pool units. Nothing until something was delivered to it, since its
+    /// gas price isn't known until then.
+    fn pending_value(&self, destination: u32, pending: PendingGas) -> U256 {
+        let Some(gas_price) = self.gas_prices.get(&destination) else {
+            return U256::zero();
+        };
+        let gas_per_delivery = self
+            .gas_per_delivery
+            .get(&destination)
+            .copied()
+            .unwrap_or_default();
+        let gas = pending
+            .estimated
+            .saturating_add(gas_per_delivery.saturating_mul(U256::from(pending.unestimated)));
+        FixedPointNumber::try_from(gas)
+            .and_then(|gas| (gas * gas_price.clone()).try_into())
+            .unwrap_or(U256::MAX)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneDomainType};
+    use prometheus::opts;
+
+    use crate::settings::FundingPoolMemberConf;
+
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn scheduler(db: &DB, budget: u64, weights: &[(u32, u32)]) -> FundingScheduler {
+        FundingScheduler::new(
+            vec![FundingPoolConf {
+                name: "treasury".to_owned(),
+                budget: budget.into(),
+                period: Duration::from_secs(DAY),
+                members: weights
+                    .iter()
+                    .map(|(destination, weight)| {
+                        (
+                            *destination,
+                            FundingPoolMemberConf {
+                                domain: HyperlaneDomain::Unknown {
+                                    domain_id: *destination,
+                                    domain_name: format!("chain{destination}"),
+                                    domain_type: HyperlaneDomainType::LocalTestChain,
+                                    domain_protocol: HyperlaneDomainProtocol::Ethereum,
+                                    domain_technical_stack: Default::default(),
+                                },
+                                weight: *weight,
+                                exchange_rate: FixedPointNumber::from(1),
+                            },
+                        )
+                    })
+                    .collect(),
+            }],
+            db,
+            FundingPoolMetrics {
+                allowance: GaugeVec::new(opts!("allowance", "help"), &["pool", "remote"]).unwrap(),
+                spend: GaugeVec::new(opts!("spend", "help"), &["pool", "remote"]).unwrap(),
+            },
+        )
+    }
+
+    fn outcome(gas_used: u64, gas_price: u64) -> TxOutcome {
+        TxOutcome {
+            transaction_id: Default::default(),
+            executed: true,
+            gas_used: gas_used.into(),
+            gas_price: FixedPointNumber::from(gas_price),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_budget_is_split_by_weight_and_pending_value() {
+        test_utils::run_test_db(|db| async move {
+            let scheduler = scheduler(&db, 1000, &[(1, 1), (2, 3)]);
+            let now = 10 * DAY;
+
+            // Without pending operations, the budget is split by weight
+            scheduler.record_spend_at(1, &outcome(249, 1), now);
+            assert!(!scheduler.is_exhausted_at(1, now));
+            scheduler.record_spend_at(1, &outcome(1, 1), now);
+            assert!(scheduler.is_exhausted_at(1, now));
+            assert!(!scheduler.is_exhausted_at(2, now));
+            scheduler.record_spend_at(2, &outcome(25, 2), now);
+
+            // The pending operations of the busy destination are worth
+            // (200 + 2 * 25) * 2 = 500, and the average is 250. The 700 left
+            // are split 1 * (0 + 250) vs 3 * (500 + 250).
+            scheduler.rebalance_at(
+                &HashMap::from([(
+                    2,
+                    PendingGas {
+                        estimated: U256::from(200),
+                        unestimated: 2,
+                    },
+                )]),
+                now,
+            );
+            scheduler.record_spend_at(1, &outcome(69, 1), now);
+            assert!(!scheduler.is_exhausted_at(1, now));
+            scheduler.record_spend_at(1, &outcome(1, 1), now);
+            assert!(scheduler.is_exhausted_at(1, now));
+            scheduler.record_spend_at(2, &outcome(314, 2), now);
+            assert!(!scheduler.is_exhausted_at(2, now));
+            scheduler.record_spend_at(2, &outcome(1, 2), now);
+            assert!(scheduler.is_exhausted_at(2, now));
+
+            // Nothing is left until the next period
+            scheduler.rebalance_at(&HashMap::new(), now);
+            assert!(scheduler.is_exhausted_at(1, now));
+            assert!(!scheduler.is_exhausted_at(1, now + DAY));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_spend_survives_restarts() {
+        test_utils::run_test_db(|db| async move {
+            let now = 10 * DAY;
+            scheduler(&db, 100, &[(1, 1)]).record_spend_at(1, &outcome(60, 1), now);
+
+            let scheduler = scheduler(&db, 100, &[(1, 1)]);
+            scheduler.record_spend_at(1, &outcome(39, 1), now);
+            assert!(!scheduler.is_exhausted_at(1, now));
+            scheduler.record_spend_at(1, &outcome(1, 1), now);
+            assert!(scheduler.is_exhausted_at(1, now));
+            assert!(!scheduler.is_exhausted_at(1, now + DAY));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_destinations_outside_of_pools_are_never_exhausted() {
+        test_utils::run_test_db(|db| async move {
+            let scheduler = scheduler(&db, 0, &[(1, 1)]);
+            assert!(scheduler.is_exhausted_at(1, DAY));
+            scheduler.record_spend_at(2, &outcome(1000, 1), DAY);
+            assert!(!scheduler.is_exhausted_at(2, DAY));
+        })
+        .await;
+    }
+}
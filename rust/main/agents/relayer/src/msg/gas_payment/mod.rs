@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    sync::{Arc, RwLock},
+};
 
 use async_trait::async_trait;
 use eyre::Result;
@@ -48,10 +51,18 @@ pub struct GasPaymentEnforcer {
     /// use a wild-card white list to ensure all messages fall into one
     /// policy or another. If a message matches multiple policies'
     /// whitelists, then whichever is first in the list will be used.
-    policies: Vec<(Box<dyn GasPaymentPolicy>, MatchingList)>,
+    /// Policies can be replaced at runtime, see `set_policies`.
+    policies: RwLock<Arc<GasPaymentPolicies>>,
+    /// If the origin's IGP charges in an ERC-20 token, how many units of the
+    /// native token a unit of the fee token is worth. Payments are converted
+    /// to the native token with it before the policies are evaluated, as the
+    /// policies are expressed in the native token.
+    fee_token_exchange_rate: RwLock<Option<FixedPointNumber>>,
     db: HyperlaneRocksDB,
 }
 
+type GasPaymentPolicies = Vec<(Box<dyn GasPaymentPolicy>, MatchingList)>;
+
 impl GasPaymentEnforcer {
     /// Note that `policy_configs` should not be empty. In the settings,
     /// a default of vec![GasPaymentEnforcementConf::default()] is used.
@@ -59,7 +70,38 @@ impl GasPaymentEnforcer {
         policy_configs: impl IntoIterator<Item = GasPaymentEnforcementConf>,
         db: HyperlaneRocksDB,
     ) -> Self {
-        let policies = policy_configs
+        Self {
+            policies: RwLock::new(Arc::new(Self::build_policies(policy_configs))),
+            fee_token_exchange_rate: RwLock::new(None),
+            db,
+        }
+    }
+
+    /// Replaces the policies used for messages that are checked from now on
+    pub fn set_policies(
+        &self,
+        policy_configs: impl IntoIterator<Item = GasPaymentEnforcementConf>,
+    ) {
+        let policies = Arc::new(Self::build_policies(policy_configs));
+        *self
+            .policies
+            .write()
+            .expect("gas payment policies lock poisoned") = policies;
+    }
+
+    /// Sets the exchange rate of the fee token of the origin's IGP, or `None`
+    /// if the IGP charges in the native token
+    pub fn set_fee_token_exchange_rate(&self, exchange_rate: Option<FixedPointNumber>) {
+        *self
+            .fee_token_exchange_rate
+            .write()
+            .expect("fee token exchange rate lock poisoned") = exchange_rate;
+    }
+
+    fn build_policies(
+        policy_configs: impl IntoIterator<Item = GasPaymentEnforcementConf>,
+    ) -> GasPaymentPolicies {
+        policy_configs
             .into_iter()
             .map(|cfg| {
                 let p: Box<dyn GasPaymentPolicy> = match cfg.policy {
@@ -74,9 +116,7 @@ impl GasPaymentEnforcer {
                 };
                 (p, cfg.matching_list)
             })
-            .collect();
-
-        Self { policies, db }
+            .collect()
     }
 }
 
@@ -89,10 +129,9 @@ impl GasPaymentEnforcer {
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<GasPolicyStatus> {
         let msg_id = message.id();
-        let gas_payment_key = GasPaymentKey {
-            message_id: msg_id,
-            destination: message.destination,
-        };
+        let gas_payment_key = Self::gas_payment_key(message);
+        // The sum of all payments made for the message, whichever transactions
+        // they were made in
         let current_payment_option = self
             .db
             .retrieve_gas_payment_by_gas_payment_key(gas_payment_key)?;
@@ -100,9 +139,15 @@ impl GasPaymentEnforcer {
             Some(payment) => payment,
             None => InterchainGasPayment::from_gas_payment_key(gas_payment_key),
         };
+        let current_payment = self.to_native_payment(current_payment)?;
         let current_expenditure = self.db.retrieve_gas_expenditure_by_message_id(msg_id)?;
 
-        for (policy, whitelist) in &self.policies {
+        let policies = self
+            .policies
+            .read()
+            .expect("gas payment policies lock poisoned")
+            .clone();
+        for (policy, whitelist) in policies.iter() {
             if !whitelist.msg_matches(message, true) {
                 trace!(
                     hyp_message=%message,
@@ -150,12 +195,45 @@ impl GasPaymentEnforcer {
 
         error!(
             hyp_message=%message,
-            policies=?self.policies,
+            ?policies,
             "No gas payment policy matched for message; consider adding a default policy to the end of the policies array which uses a wildcard whitelist."
         );
         Ok(GasPolicyStatus::PolicyNotMet)
     }
 
+    /// Returns the number of gas payments indexed for the message. This only
+    /// changes when a new payment is made, so it's a cheap way to tell whether
+    /// evaluating the policies again could have a different result.
+    pub fn payment_count(&self, message: &HyperlaneMessage) -> Result<u32> {
+        Ok(self
+            .db
+            .retrieve_gas_payment_count_by_gas_payment_key(Self::gas_payment_key(message))?)
+    }
+
+    /// Converts a payment made in the fee token of the origin's IGP to the
+    /// native token, rounding down
+    fn to_native_payment(&self, payment: InterchainGasPayment) -> Result<InterchainGasPayment> {
+        let exchange_rate = self
+            .fee_token_exchange_rate
+            .read()
+            .expect("fee token exchange rate lock poisoned")
+            .clone();
+        let Some(exchange_rate) = exchange_rate else {
+            return Ok(payment);
+        };
+        Ok(InterchainGasPayment {
+            payment: (FixedPointNumber::try_from(payment.payment)? * exchange_rate).try_into()?,
+            ..payment
+        })
+    }
+
+    fn gas_payment_key(message: &HyperlaneMessage) -> GasPaymentKey {
+        GasPaymentKey {
+            message_id: message.id(),
+            destination: message.destination,
+        }
+    }
+
     pub fn record_tx_outcome(&self, message: &HyperlaneMessage, outcome: TxOutcome) -> Result<()> {
         // This log is required in E2E, hence the use of a `const`
         debug!(
@@ -180,8 +258,8 @@ mod test {
 
     use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
     use hyperlane_core::{
-        HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, LogMeta, TxCostEstimate, H160,
-        H256, U256,
+        FixedPointNumber, HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, LogMeta,
+        TxCostEstimate, H160, H256, U256,
     };
 
     use super::GasPaymentEnforcer;
@@ -375,6 +453,136 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_fee_token_payment_is_converted() {
+        #[allow(unused_must_use)]
+        test_utils::run_test_db(|db| async move {
+            let msg = HyperlaneMessage {
+                destination: 123,
+                ..HyperlaneMessage::default()
+            };
+
+            let hyperlane_db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("test_fee_token_payment_is_converted"),
+                db,
+            );
+
+            let enforcer = GasPaymentEnforcer::new(
+                vec![GasPaymentEnforcementConf {
+                    policy: GasPaymentEnforcementPolicy::Minimum {
+                        payment: U256::from(1000),
+                    },
+                    matching_list: MatchingList::default(),
+                }],
+                hyperlane_db.clone(),
+            );
+
+            hyperlane_db.process_gas_payment(
+                InterchainGasPayment {
+                    message_id: msg.id(),
+                    destination: msg.destination,
+                    payment: U256::from(100),
+                    gas_amount: U256::one(),
+                },
+                &LogMeta::random(),
+            );
+
+            // In the native token, the payment is below the minimum
+            assert_eq!(
+                enforcer
+                    .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default())
+                    .await
+                    .unwrap(),
+                GasPolicyStatus::PolicyNotMet
+            );
+
+            // A unit of the fee token is worth 10.5 units of the native token
+            enforcer.set_fee_token_exchange_rate(Some(FixedPointNumber::from_str("10.5").unwrap()));
+            assert_eq!(
+                enforcer
+                    .message_meets_gas_payment_requirement(&msg, &TxCostEstimate::default())
+                    .await
+                    .unwrap(),
+                GasPolicyStatus::PolicyMet(U256::zero())
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_payments_in_separate_transactions_are_summed() {
+        test_utils::run_test_db(|db| async move {
+            let msg = HyperlaneMessage {
+                destination: 123,
+                ..HyperlaneMessage::default()
+            };
+
+            let hyperlane_db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain(
+                    "test_payments_in_separate_transactions_are_summed",
+                ),
+                db,
+            );
+            let enforcer = GasPaymentEnforcer::new(
+                vec![GasPaymentEnforcementConf {
+                    policy: GasPaymentEnforcementPolicy::OnChainFeeQuoting {
+                        gas_fraction_numerator: 1,
+                        gas_fraction_denominator: 1,
+                    },
+                    matching_list: MatchingList::default(),
+                }],
+                hyperlane_db.clone(),
+            );
+            let tx_cost_estimate = TxCostEstimate {
+                gas_limit: U256::from(300),
+                ..TxCostEstimate::default()
+            };
+            let payment = InterchainGasPayment {
+                message_id: msg.id(),
+                destination: msg.destination,
+                payment: U256::from(100),
+                gas_amount: U256::from(100),
+            };
+
+            // The initial payment and a top-up, each paid in its own transaction
+            let initial_payment_meta = LogMeta::random();
+            assert!(hyperlane_db
+                .process_gas_payment(payment, &initial_payment_meta)
+                .unwrap());
+            assert!(hyperlane_db
+                .process_gas_payment(payment, &LogMeta::random())
+                .unwrap());
+            // Indexing a payment again doesn't count it twice
+            assert!(!hyperlane_db
+                .process_gas_payment(payment, &initial_payment_meta)
+                .unwrap());
+
+            assert_eq!(enforcer.payment_count(&msg).unwrap(), 2);
+            assert_eq!(
+                enforcer
+                    .message_meets_gas_payment_requirement(&msg, &tx_cost_estimate)
+                    .await
+                    .unwrap(),
+                GasPolicyStatus::PolicyNotMet
+            );
+
+            // A second top-up covers the rest of the estimate
+            assert!(hyperlane_db
+                .process_gas_payment(payment, &LogMeta::random())
+                .unwrap());
+
+            assert_eq!(enforcer.payment_count(&msg).unwrap(), 3);
+            assert_eq!(
+                enforcer
+                    .message_meets_gas_payment_requirement(&msg, &tx_cost_estimate)
+                    .await
+                    .unwrap(),
+                GasPolicyStatus::PolicyMet(U256::from(300))
+            );
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn test_non_empty_matching_list() {
         test_utils::run_test_db(|db| async move {
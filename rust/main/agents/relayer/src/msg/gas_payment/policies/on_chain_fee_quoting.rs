@@ -45,7 +45,7 @@ impl GasPaymentPolicy for GasPaymentPolicyOnChainFeeQuoting {
         current_expenditure: &InterchainGasExpenditure,
         tx_cost_estimate: &TxCostEstimate,
     ) -> Result<Option<U256>> {
-        let fractional_gas_estimate = (tx_cost_estimate.enforceable_gas_limit()
+        let fractional_gas_estimate = (tx_cost_estimate.enforceable_gas_limit()?
             * self.fractional_numerator)
             / self.fractional_denominator;
         let gas_amount = current_payment
@@ -90,6 +90,8 @@ mod test {
         gas_limit: U256([2000, 0, 0, 0]), // MIN * 2
         gas_price: U256([100001, 0, 0, 0]).try_into().unwrap(),
         l2_gas_limit: None,
+        l1_data_fee: None,
+        l1_blob_base_fee: None,
     });
 
     #[test]
@@ -206,6 +208,8 @@ mod test {
             gas_limit: MIN * 100, // Large gas limit
             gas_price: COST_ESTIMATE.gas_price.clone(),
             l2_gas_limit: Some(MIN * 2),
+            l1_data_fee: None,
+            l1_blob_base_fee: None,
         };
 
         // First ensure that if l2_gas_limit is None, because of the high gas limit,
@@ -218,6 +222,8 @@ mod test {
                     &current_expenditure(0),
                     &TxCostEstimate {
                         l2_gas_limit: None,
+                        l1_data_fee: None,
+                        l1_blob_base_fee: None,
                         ..tx_cost_estimate.clone()
                     }
                 )
@@ -239,4 +245,43 @@ mod test {
             Some(tx_cost_estimate.gas_limit),
         );
     }
+
+    #[tokio::test]
+    async fn test_l1_data_fee() {
+        let policy = GasPaymentPolicyOnChainFeeQuoting::default();
+        let message = HyperlaneMessage::default();
+
+        // The L1 data fee is worth as much as the gas limit
+        let tx_cost_estimate = TxCostEstimate {
+            l1_data_fee: Some(U256::from(100001) * MIN * 2),
+            ..COST_ESTIMATE.clone()
+        };
+
+        // Without the fee, paying for the minimum would be enough
+        assert_eq!(
+            policy
+                .message_meets_gas_payment_requirement(
+                    &message,
+                    &current_payment(MIN),
+                    &current_expenditure(0),
+                    &tx_cost_estimate,
+                )
+                .await
+                .unwrap(),
+            None
+        );
+        // The gas worth of the fee is required too, but not used as gas limit
+        assert_eq!(
+            policy
+                .message_meets_gas_payment_requirement(
+                    &message,
+                    &current_payment(MIN * 2),
+                    &current_expenditure(0),
+                    &tx_cost_estimate,
+                )
+                .await
+                .unwrap(),
+            Some(tx_cost_estimate.gas_limit),
+        );
+    }
 }
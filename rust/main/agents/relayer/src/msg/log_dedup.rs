@@ -0,0 +1,201 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::H256;
+use tracing::warn;
+
+/// Deduplicates warnings that are repeatedly logged for the same message,
+/// e.g. by messages stuck in a retry loop.
+///
+/// The first occurrence of a warning for a message within a window is logged
+/// in full. Later occurrences within the same window are only counted and
+/// periodically reported as a single summary line.
+#[derive(Debug)]
+pub struct LogDeduplicator {
+    window: Duration,
+    state: Mutex<DeduplicatorState>,
+}
+
+#[derive(Debug, Default)]
+struct DeduplicatorState {
+    /// Warnings whose window hasn't elapsed yet, keyed by (message id, warning code)
+    active: HashMap<(H256, String), Occurrences>,
+    /// Summaries of windows that elapsed but weren't reported yet
+    elapsed: Vec<WarningSummary>,
+}
+
+#[derive(Debug)]
+struct Occurrences {
+    window_start: Instant,
+    /// Number of occurrences in the window, including the one that was logged
+    count: u64,
+}
+
+/// A warning that occurred more than once for a message within a window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarningSummary {
+    pub message_id: H256,
+    pub code: String,
+    pub occurrences: u64,
+}
+
+impl LogDeduplicator {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            state: Default::default(),
+        }
+    }
+
+    /// The window within which repeated warnings are deduplicated
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Records an occurrence of the warning `code` for a message.
+    /// Returns true if the warning should be logged in full.
+    pub fn observe(&self, message_id: H256, code: &str) -> bool {
+        self.observe_at(message_id, code, Instant::now())
+    }
+
+    fn observe_at(&self, message_id: H256, code: &str, now: Instant) -> bool {
+        let mut state = self.state.lock().expect("log deduplicator lock poisoned");
+        let key = (message_id, code.to_owned());
+        if let Some(occurrences) = state.active.get_mut(&key) {
+            if now.duration_since(occurrences.window_start) < self.window {
+                occurrences.count += 1;
+                return false;
+            }
+        }
+        let previous = state.active.insert(
+            key,
+            Occurrences {
+                window_start: now,
+                count: 1,
+            },
+        );
+        if let Some(previous) = previous.filter(|o| o.count > 1) {
+            state.elapsed.push(WarningSummary {
+                message_id,
+                code: code.to_owned(),
+                occurrences: previous.count,
+            });
+        }
+        true
+    }
+
+    /// Removes all warnings whose window elapsed, returning a summary for those
+    /// that occurred more than once.
+    pub fn take_summaries(&self) -> Vec<WarningSummary> {
+        self.take_summaries_at(Instant::now())
+    }
+
+    fn take_summaries_at(&self, now: Instant) -> Vec<WarningSummary> {
+        let mut state = self.state.lock().expect("log deduplicator lock poisoned");
+        let mut summaries = std::mem::take(&mut state.elapsed);
+        let window = self.window;
+        state.active.retain(|(message_id, code), occurrences| {
+            if now.duration_since(occurrences.window_start) < window {
+                return true;
+            }
+            if occurrences.count > 1 {
+                summaries.push(WarningSummary {
+                    message_id: *message_id,
+                    code: code.clone(),
+                    occurrences: occurrences.count,
+                });
+            }
+            false
+        });
+        summaries
+    }
+
+    /// Logs a summary line for every warning that was deduplicated in an
+    /// elapsed window.
+    pub fn log_summaries(&self) {
+        for summary in self.take_summaries() {
+            warn!(
+                id = ?summary.message_id,
+                occurrences = summary.occurrences,
+                "Message {:?}: {}, {} occurrences in last {:?}",
+                summary.message_id,
+                summary.code,
+                summary.occurrences,
+                self.window,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_only_first_occurrence_in_window_is_logged() {
+        let dedup = LogDeduplicator::new(WINDOW);
+        let id = H256::random();
+        let start = Instant::now();
+
+        assert!(dedup.observe_at(id, "Gas payment requirement not met", start));
+        assert!(!dedup.observe_at(id, "Gas payment requirement not met", start));
+        assert!(!dedup.observe_at(id, "Gas payment requirement not met", start + WINDOW / 2));
+        // A different code or message is tracked separately
+        assert!(dedup.observe_at(id, "Error building metadata", start));
+        assert!(dedup.observe_at(H256::random(), "Gas payment requirement not met", start));
+        // Once the window elapsed, the warning is logged again
+        assert!(dedup.observe_at(id, "Gas payment requirement not met", start + WINDOW));
+    }
+
+    #[test]
+    fn test_summaries_of_elapsed_windows() {
+        let dedup = LogDeduplicator::new(WINDOW);
+        let repeated = H256::random();
+        let single = H256::random();
+        let start = Instant::now();
+
+        for _ in 0..124 {
+            dedup.observe_at(repeated, "Gas payment requirement not met", start);
+        }
+        dedup.observe_at(single, "Gas payment requirement not met", start);
+
+        // Nothing is summarized before the window elapsed
+        assert!(dedup.take_summaries_at(start + WINDOW / 2).is_empty());
+
+        assert_eq!(
+            dedup.take_summaries_at(start + WINDOW),
+            vec![WarningSummary {
+                message_id: repeated,
+                code: "Gas payment requirement not met".to_owned(),
+                occurrences: 124,
+            }]
+        );
+        // Elapsed windows are only reported once
+        assert!(dedup.take_summaries_at(start + WINDOW * 2).is_empty());
+    }
+
+    #[test]
+    fn test_window_rolled_over_by_new_occurrence_is_summarized() {
+        let dedup = LogDeduplicator::new(WINDOW);
+        let id = H256::random();
+        let start = Instant::now();
+
+        dedup.observe_at(id, "Error building metadata", start);
+        dedup.observe_at(id, "Error building metadata", start);
+        assert!(dedup.observe_at(id, "Error building metadata", start + WINDOW));
+
+        assert_eq!(
+            dedup.take_summaries_at(start + WINDOW),
+            vec![WarningSummary {
+                message_id: id,
+                code: "Error building metadata".to_owned(),
+                occurrences: 2,
+            }]
+        );
+    }
+}
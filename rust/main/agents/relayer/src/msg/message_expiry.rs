@@ -0,0 +1,253 @@
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_base::db::HyperlaneDb;
+use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, H256};
+use prometheus::IntCounterVec;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::settings::MessageExpiryConf;
+
+/// How many expired messages are listed through the API
+const MAX_LISTED_EXPIRED_MESSAGES: usize = 1000;
+
+/// Moves messages that stay undelivered for longer than their TTL to a
+/// dead-letter state, for apps that want stale messages dropped rather than
+/// retried until `max_retries`.
+///
+/// The TTL of a message is the one of the first configured list it matches,
+/// counted from when the relayer first queued it. That time is persisted, so
+/// restarts don't extend it. Expired messages are persisted as such and never
+/// queued again. They're counted in a metric and the most recent ones are
+/// listed through the API.
+#[derive(Debug)]
+pub struct MessageExpiry {
+    confs: Vec<MessageExpiryConf>,
+    /// Expired messages, by origin and destination
+    expired_count: IntCounterVec,
+    expired: Mutex<VecDeque<ExpiredMessage>>,
+}
+
+/// A message in the dead-letter state, as listed through the API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExpiredMessage {
+    pub id: H256,
+    pub origin_domain_id: u32,
+    pub destination_domain_id: u32,
+    pub nonce: u32,
+    pub sender: H256,
+    pub recipient: H256,
+    /// Unix timestamp the message expired at, in seconds
+    pub expired_at: u64,
+}
+
+impl MessageExpiry {
+    pub fn new(confs: Vec<MessageExpiryConf>, expired_count: IntCounterVec) -> Self {
+        Self {
+            confs,
+            expired_count,
+            expired: Default::default(),
+        }
+    }
+
+    /// When the message expires, as a unix timestamp in seconds. `None` if
+    /// it doesn't match any list. The first time this is called for a
+    /// message, the current time is persisted as the time it was first seen.
+    pub fn expires_at(&self, db: &dyn HyperlaneDb, message: &HyperlaneMessage) -> Option<u64> {
+        self.expires_at_from(db, message, now())
+    }
+
+    fn expires_at_from(
+        &self,
+        db: &dyn HyperlaneDb,
+        message: &HyperlaneMessage,
+        now: u64,
+    ) -> Option<u64> {
+        let ttl = self
+            .confs
+            .iter()
+            .find(|conf| conf.matching_list.msg_matches(message, true))?
+            .ttl;
+        let id = message.id();
+        let first_seen_at = match db.retrieve_message_first_seen_at_by_message_id(&id) {
+            Ok(Some(first_seen_at)) => first_seen_at,
+            result => {
+                if let Err(err) = result {
+                    warn!(?id, ?err, "Failed to read when message was first seen");
+                }
+                if let Err(err) = db.store_message_first_seen_at_by_message_id(&id, &now) {
+                    warn!(?id, ?err, "Failed to persist when message was first seen");
+                }
+                now
+            }
+        };
+        Some(first_seen_at.saturating_add(ttl.as_secs()))
+    }
+
+    /// Whether the message already expired, in which case it's listed again,
+    /// e.g. after a restart
+    pub fn is_dead_lettered(&self, db: &dyn HyperlaneDb, message: &HyperlaneMessage) -> bool {
+        match db.retrieve_message_expired_at_by_message_id(&message.id()) {
+            Ok(Some(expired_at)) => {
+                self.list(message, expired_at);
+                true
+            }
+            Ok(None) => false,
+            Err(err) => {
+                warn!(id = ?message.id(), ?err, "Failed to read whether message expired");
+                false
+            }
+        }
+    }
+
+    /// Moves the message to the dead-letter state
+    pub fn dead_letter(
+        &self,
+        db: &dyn HyperlaneDb,
+        destination: &HyperlaneDomain,
+        message: &HyperlaneMessage,
+    ) {
+        let expired_at = now();
+        if let Err(err) = db.store_message_expired_at_by_message_id(&message.id(), &expired_at) {
+            warn!(id = ?message.id(), ?err, "Failed to persist that message expired");
+        }
+        self.expired_count
+            .with_label_values(&[db.domain().name(), destination.name()])
+            .inc();
+        self.list(message, expired_at);
+    }
+
+    /// The most recently expired messages, oldest first
+    pub fn expired_messages(&self) -> Vec<ExpiredMessage> {
+        let mut messages: Vec<_> = self
+            .expired
+            .lock()
+            .expect("message expiry lock poisoned")
+            .iter()
+            .cloned()
+            .collect();
+        messages.sort_by_key(|message| message.expired_at);
+        messages
+    }
+
+    fn list(&self, message: &HyperlaneMessage, expired_at: u64) {
+        let id = message.id();
+        let mut expired = self.expired.lock().expect("message expiry lock poisoned");
+        if expired.iter().any(|expired| expired.id == id) {
+            return;
+        }
+        if expired.len() >= MAX_LISTED_EXPIRED_MESSAGES {
+            expired.pop_front();
+        }
+        expired.push_back(ExpiredMessage {
+            id,
+            origin_domain_id: message.origin,
+            destination_domain_id: message.destination,
+            nonce: message.nonce,
+            sender: message.sender,
+            recipient: message.recipient,
+            expired_at,
+        });
+    }
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::opts;
+
+    use crate::settings::matching_list::MatchingList;
+
+    use super::*;
+
+    fn message_expiry() -> MessageExpiry {
+        let to_arbitrum: MatchingList =
+            serde_json::from_str(r#"[{"destinationdomain": 42161}]"#).unwrap();
+        MessageExpiry::new(
+            vec![
+                MessageExpiryConf {
+                    matching_list: to_arbitrum,
+                    ttl: Duration::from_secs(60),
+                },
+                MessageExpiryConf {
+                    matching_list: MatchingList::default(),
+                    ttl: Duration::from_secs(3600),
+                },
+            ],
+            IntCounterVec::new(opts!("expired", "help"), &["origin", "remote"]).unwrap(),
+        )
+    }
+
+    fn message(nonce: u32, destination: u32) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            origin: 1,
+            destination,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ttl_counts_from_first_seen() {
+        test_utils::run_test_db(|db| async move {
+            let origin = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+            let db = HyperlaneRocksDB::new(&origin, db);
+            let expiry = message_expiry();
+
+            // The first matching list applies
+            let to_arbitrum = message(0, 42161);
+            assert_eq!(expiry.expires_at_from(&db, &to_arbitrum, 1000), Some(1060));
+            let other = message(1, 10);
+            assert_eq!(expiry.expires_at_from(&db, &other, 1000), Some(4600));
+
+            // Seeing a message again, e.g. after a restart, doesn't extend its TTL
+            assert_eq!(expiry.expires_at_from(&db, &to_arbitrum, 2000), Some(1060));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_expired_messages_stay_dead_lettered() {
+        test_utils::run_test_db(|db| async move {
+            let origin = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+            let destination = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+            let db = HyperlaneRocksDB::new(&origin, db);
+            let message = message(0, 42161);
+
+            let expiry = message_expiry();
+            assert!(!expiry.is_dead_lettered(&db, &message));
+            expiry.dead_letter(&db, &destination, &message);
+            assert_eq!(
+                expiry
+                    .expired_count
+                    .with_label_values(&["ethereum", "arbitrum"])
+                    .get(),
+                1
+            );
+            let listed = expiry.expired_messages();
+            assert_eq!(listed.len(), 1);
+            assert_eq!(listed[0].id, message.id());
+            assert_eq!(listed[0].destination_domain_id, 42161);
+
+            // After a restart, the message is still expired and listed again
+            let restarted = message_expiry();
+            assert!(restarted.is_dead_lettered(&db, &message));
+            assert_eq!(restarted.expired_messages(), listed);
+        })
+        .await;
+    }
+}
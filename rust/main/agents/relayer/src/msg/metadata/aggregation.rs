@@ -10,6 +10,7 @@ use tracing::{info, instrument};
 use hyperlane_core::{HyperlaneMessage, InterchainSecurityModule, ModuleType, H256, U256};
 
 use super::{MessageMetadataBuilder, Metadata, MetadataBuilder};
+use crate::msg::simulation_limiter::SimulationLimiter;
 
 /// Bytes used to store one member of the (start, end) range tuple
 /// Copied from `AggregationIsmMetadata.sol`
@@ -87,17 +88,64 @@ impl AggregationIsmMetadataBuilder {
         cheapest.into_iter().map(|(meta, _)| meta).collect()
     }
 
+    fn n_smallest_metas(
+        mut metas_and_gas: Vec<(SubModuleMetadata, U256)>,
+        n: usize,
+    ) -> Vec<SubModuleMetadata> {
+        // Sort by metadata length in ascending order, preferring cheaper metadata of equal length
+        metas_and_gas.sort_by(|(meta_1, gas_1), (meta_2, gas_2)| {
+            (meta_1.metadata.len(), gas_1).cmp(&(meta_2.metadata.len(), gas_2))
+        });
+        let mut smallest: Vec<_> = metas_and_gas[..n].into();
+        // Sort by index in ascending order, to match the order expected by the smart contract
+        smallest.sort_by(|(meta_1, _), (meta_2, _)| meta_1.index.cmp(&meta_2.index));
+        smallest.into_iter().map(|(meta, _)| meta).collect()
+    }
+
+    /// The size of the metadata `format_metadata` produces for `metadatas`
+    fn formatted_metadata_size(metadatas: &[SubModuleMetadata], ism_count: usize) -> usize {
+        let metadatas_size: usize = metadatas.iter().map(|meta| meta.metadata.len()).sum();
+        METADATA_RANGE_SIZE * 2 * ism_count + metadatas_size
+    }
+
+    /// Picks the cheapest `n` metadatas, unless they exceed the destination's max
+    /// metadata size, in which case the smallest `n` metadatas are picked instead.
+    fn select_metas(
+        metas_and_gas: Vec<(SubModuleMetadata, U256)>,
+        n: usize,
+        ism_count: usize,
+        max_metadata_size: Option<usize>,
+    ) -> Vec<SubModuleMetadata> {
+        let Some(max_metadata_size) = max_metadata_size else {
+            return Self::n_cheapest_metas(metas_and_gas, n);
+        };
+        let cheapest = Self::n_cheapest_metas(metas_and_gas.clone(), n);
+        let cheapest_size = Self::formatted_metadata_size(&cheapest, ism_count);
+        if cheapest_size <= max_metadata_size {
+            return cheapest;
+        }
+        let smallest = Self::n_smallest_metas(metas_and_gas, n);
+        info!(
+            %cheapest_size,
+            smallest_size = Self::formatted_metadata_size(&smallest, ism_count),
+            %max_metadata_size,
+            "Cheapest sub-module metadata combination exceeds max metadata size, using smallest combination instead"
+        );
+        smallest
+    }
+
     async fn cheapest_valid_metas(
         sub_modules: Vec<IsmAndMetadata>,
         message: &HyperlaneMessage,
         threshold: usize,
+        ism_count: usize,
+        max_metadata_size: Option<usize>,
         err_isms: Vec<(H256, Option<ModuleType>)>,
+        simulation_limiter: &SimulationLimiter,
     ) -> Option<Vec<SubModuleMetadata>> {
-        let gas_cost_results: Vec<_> = join_all(
-            sub_modules
-                .iter()
-                .map(|module| module.ism.dry_run_verify(message, &(module.meta.metadata))),
-        )
+        let gas_cost_results: Vec<_> = join_all(sub_modules.iter().map(|module| {
+            simulation_limiter.run(module.ism.dry_run_verify(message, &(module.meta.metadata)))
+        }))
         .await;
         // Filter out the ISMs with a gas cost estimate
         let metas_and_gas: Vec<_> = sub_modules
@@ -111,7 +159,12 @@ impl AggregationIsmMetadataBuilder {
             info!(?err_isms, %metas_and_gas_count, %threshold, message_id=?message.id(), "Could not fetch all metadata, ISM metadata count did not reach aggregation threshold");
             return None;
         }
-        Some(Self::n_cheapest_metas(metas_and_gas, threshold))
+        Some(Self::select_metas(
+            metas_and_gas,
+            threshold,
+            ism_count,
+            max_metadata_size,
+        ))
     }
 }
 
@@ -122,7 +175,11 @@ impl MetadataBuilder for AggregationIsmMetadataBuilder {
     async fn build(&self, ism_address: H256, message: &HyperlaneMessage) -> eyre::Result<Metadata> {
         const CTX: &str = "When fetching AggregationIsm metadata";
         let ism = self.build_aggregation_ism(ism_address).await.context(CTX)?;
-        let (ism_addresses, threshold) = ism.modules_and_threshold(message).await.context(CTX)?;
+        let (ism_addresses, threshold) = self
+            .simulation_limiter()
+            .run(ism.modules_and_threshold(message))
+            .await
+            .context(CTX)?;
         let threshold = threshold as usize;
 
         let sub_modules_and_metas = join_all(
@@ -162,12 +219,19 @@ impl MetadataBuilder for AggregationIsmMetadataBuilder {
                 },
                 Err(_) => Either::Right((*ism_address, None)),
             });
-        let maybe_aggregation_metadata =
-            Self::cheapest_valid_metas(ok_sub_modules, message, threshold, err_sub_modules)
-                .await
-                .map_or(Metadata::CouldNotFetch, |mut metas| {
-                    Metadata::Found(Self::format_metadata(&mut metas, ism_addresses.len()))
-                });
+        let maybe_aggregation_metadata = Self::cheapest_valid_metas(
+            ok_sub_modules,
+            message,
+            threshold,
+            ism_addresses.len(),
+            self.max_metadata_size(),
+            err_sub_modules,
+            self.simulation_limiter(),
+        )
+        .await
+        .map_or(Metadata::CouldNotFetch, |mut metas| {
+            Metadata::Found(Self::format_metadata(&mut metas, ism_addresses.len()))
+        });
         Ok(maybe_aggregation_metadata)
     }
 }
@@ -270,4 +334,35 @@ mod test {
             ]
         )
     }
+
+    #[test]
+    fn test_select_metas_falls_back_to_smallest_metas() {
+        let metas_and_gas = vec![
+            (SubModuleMetadata::new(0, vec![0; 100]), U256::from(1)),
+            (SubModuleMetadata::new(1, vec![0; 10]), U256::from(3)),
+            (SubModuleMetadata::new(2, vec![0; 20]), U256::from(2)),
+        ];
+        // Range tuples for 3 ISMs take 24 bytes
+        let cheapest = vec![
+            SubModuleMetadata::new(0, vec![0; 100]),
+            SubModuleMetadata::new(2, vec![0; 20]),
+        ];
+        let smallest = vec![
+            SubModuleMetadata::new(1, vec![0; 10]),
+            SubModuleMetadata::new(2, vec![0; 20]),
+        ];
+
+        assert_eq!(
+            AggregationIsmMetadataBuilder::select_metas(metas_and_gas.clone(), 2, 3, None),
+            cheapest
+        );
+        assert_eq!(
+            AggregationIsmMetadataBuilder::select_metas(metas_and_gas.clone(), 2, 3, Some(144)),
+            cheapest
+        );
+        assert_eq!(
+            AggregationIsmMetadataBuilder::select_metas(metas_and_gas, 2, 3, Some(143)),
+            smallest
+        );
+    }
 }
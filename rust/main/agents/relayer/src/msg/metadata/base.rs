@@ -2,10 +2,8 @@
 #![allow(clippy::unnecessary_get_then_check)] // TODO: `rustc` 1.80.1 clippy issue
 
 use std::{
-    collections::HashMap,
     fmt::Debug,
     ops::Deref,
-    str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -14,9 +12,10 @@ use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::metadata::{
         multisig::{MerkleRootMultisigMetadataBuilder, MessageIdMultisigMetadataBuilder},
-        AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, NullMetadataBuilder,
-        RoutingIsmMetadataBuilder,
+        AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, NativeBridgeIsmMetadataBuilder,
+        NullMetadataBuilder, RoutingIsmMetadataBuilder,
     },
+    msg::simulation_limiter::SimulationLimiter,
     settings::matching_list::MatchingList,
 };
 use async_trait::async_trait;
@@ -27,17 +26,17 @@ use hyperlane_base::{
     settings::CheckpointSyncerBuildError,
 };
 use hyperlane_base::{
-    settings::{ChainConf, CheckpointSyncerConf},
-    CheckpointSyncer, CoreMetrics, MultisigCheckpointSyncer,
+    settings::ChainConf, CheckpointBatchCache, CoreMetrics, MultisigCheckpointSyncer,
+    ValidatorAnnounceIndex,
 };
 use hyperlane_core::{
     accumulator::merkle::Proof, AggregationIsm, CcipReadIsm, Checkpoint, HyperlaneDomain,
-    HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm, RoutingIsm,
-    ValidatorAnnounce, H160, H256,
+    HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm, NativeBridgeIsm,
+    RoutingIsm, H256,
 };
 
 use tokio::sync::RwLock;
-use tracing::{debug, info, instrument, warn};
+use tracing::{debug, info, instrument};
 
 #[derive(Debug, thiserror::Error)]
 pub enum MetadataBuilderError {
@@ -249,8 +248,9 @@ impl MessageMetadataBuilder {
             .await
             .context("When building ISM")?;
 
-        let module_type = ism
-            .module_type()
+        let module_type = self
+            .simulation_limiter()
+            .run(ism.module_type())
             .await
             .context("When fetching module type")?;
         let cloned = self.clone_with_incremented_depth()?;
@@ -266,6 +266,9 @@ impl MessageMetadataBuilder {
             ModuleType::Aggregation => Box::new(AggregationIsmMetadataBuilder::new(cloned)),
             ModuleType::Null => Box::new(NullMetadataBuilder::new()),
             ModuleType::CcipRead => Box::new(CcipReadIsmMetadataBuilder::new(cloned)),
+            ModuleType::ArbL2ToL1 | ModuleType::OpL2ToL1 => {
+                Box::new(NativeBridgeIsmMetadataBuilder::new(cloned, module_type))
+            }
             _ => return Err(MetadataBuilderError::UnsupportedModuleType(module_type).into()),
         };
         let meta = metadata_builder
@@ -284,14 +287,20 @@ impl MessageMetadataBuilder {
 #[allow(clippy::too_many_arguments)]
 #[derive(new)]
 pub struct BaseMetadataBuilder {
-    origin_domain: HyperlaneDomain,
+    origin_chain_setup: ChainConf,
     destination_chain_setup: ChainConf,
     origin_prover_sync: Arc<RwLock<MerkleTreeBuilder>>,
-    origin_validator_announce: Arc<dyn ValidatorAnnounce>,
-    allow_local_checkpoint_syncers: bool,
+    /// Storage locations announced by the origin's validators
+    origin_validator_announce_index: Arc<ValidatorAnnounceIndex>,
     metrics: Arc<CoreMetrics>,
     db: HyperlaneRocksDB,
     app_context_classifier: IsmAwareAppContextClassifier,
+    /// Max size in bytes of the metadata the destination chain accepts, if any
+    max_metadata_size: Option<usize>,
+    /// Limits the ISM calls made to the destination chain, which are simulated
+    simulation_limiter: Arc<SimulationLimiter>,
+    /// Checkpoint batches fetched from validators, shared by all builders
+    checkpoint_batch_cache: Arc<CheckpointBatchCache>,
     #[new(value = "13")]
     max_depth: u32,
 }
@@ -300,21 +309,31 @@ impl Debug for BaseMetadataBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "BaseMetadataBuilder {{ origin_domain: {:?} destination_chain_setup: {:?}, validator_announce: {:?} }}",
-            self.origin_domain, self.destination_chain_setup, self.origin_validator_announce
+            "BaseMetadataBuilder {{ origin_chain_setup: {:?} destination_chain_setup: {:?}, validator_announce: {:?} }}",
+            self.origin_chain_setup,
+            self.destination_chain_setup,
+            self.origin_validator_announce_index.validator_announce()
         )
     }
 }
 
 impl BaseMetadataBuilder {
     pub fn origin_domain(&self) -> &HyperlaneDomain {
-        &self.origin_domain
+        &self.origin_chain_setup.domain
     }
 
     pub fn destination_domain(&self) -> &HyperlaneDomain {
         &self.destination_chain_setup.domain
     }
 
+    pub fn max_metadata_size(&self) -> Option<usize> {
+        self.max_metadata_size
+    }
+
+    pub fn simulation_limiter(&self) -> &SimulationLimiter {
+        &self.simulation_limiter
+    }
+
     pub async fn get_proof(&self, leaf_index: u32, checkpoint: Checkpoint) -> Result<Proof> {
         const CTX: &str = "When fetching message proof";
         let proof = self
@@ -345,6 +364,11 @@ impl BaseMetadataBuilder {
         Ok(merkle_leaf)
     }
 
+    pub fn get_dispatched_block_number(&self, nonce: u32) -> Result<Option<u64>> {
+        let block_number = self.db.retrieve_dispatched_block_number_by_nonce(&nonce)?;
+        Ok(block_number)
+    }
+
     pub async fn build_ism(&self, address: H256) -> Result<Box<dyn InterchainSecurityModule>> {
         self.destination_chain_setup
             .build_ism(address, &self.metrics)
@@ -375,88 +399,42 @@ impl BaseMetadataBuilder {
             .await
     }
 
+    pub async fn build_native_bridge_ism(
+        &self,
+        address: H256,
+        module_type: ModuleType,
+    ) -> Result<Box<dyn NativeBridgeIsm>> {
+        self.destination_chain_setup
+            .build_native_bridge_ism(
+                address,
+                module_type,
+                &self.origin_chain_setup,
+                &self.metrics,
+            )
+            .await
+    }
+
     pub async fn build_checkpoint_syncer(
         &self,
         message: &HyperlaneMessage,
         validators: &[H256],
         app_context: Option<String>,
     ) -> Result<MultisigCheckpointSyncer, CheckpointSyncerBuildError> {
-        let storage_locations = self
-            .origin_validator_announce
-            .get_announced_storage_locations(validators)
-            .await?;
-
         debug!(
             hyp_message=?message,
             ?validators,
             validators_len = ?validators.len(),
-            ?storage_locations,
-            storage_locations_len = ?storage_locations.len(),
-            "List of validators and their storage locations for message");
-
-        // Only use the most recently announced location for now.
-        let mut checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>> = HashMap::new();
-        for (&validator, validator_storage_locations) in validators.iter().zip(storage_locations) {
-            debug!(hyp_message=?message, ?validator, ?validator_storage_locations, "Validator and its storage locations for message");
-            for storage_location in validator_storage_locations.iter().rev() {
-                let Ok(config) = CheckpointSyncerConf::from_str(storage_location) else {
-                    debug!(
-                        ?validator,
-                        ?storage_location,
-                        "Could not parse checkpoint syncer config for validator"
-                    );
-                    continue;
-                };
-
-                // If this is a LocalStorage based checkpoint syncer and it's not
-                // allowed, ignore it
-                if !self.allow_local_checkpoint_syncers
-                    && matches!(config, CheckpointSyncerConf::LocalStorage { .. })
-                {
-                    debug!(
-                        ?config,
-                        "Ignoring disallowed LocalStorage based checkpoint syncer"
-                    );
-                    continue;
-                }
-
-                match config.build_and_validate(None).await {
-                    Ok(checkpoint_syncer) => {
-                        // found the syncer for this validator
-                        checkpoint_syncers.insert(validator.into(), checkpoint_syncer.into());
-                        break;
-                    }
-                    Err(CheckpointSyncerBuildError::ReorgEvent(reorg_event)) => {
-                        // If a reorg event has been posted to a checkpoint syncer,
-                        // we refuse to build
-                        return Err(CheckpointSyncerBuildError::ReorgEvent(reorg_event));
-                    }
-                    Err(err) => {
-                        debug!(
-                            error=%err,
-                            ?config,
-                            ?validator,
-                            "Error when loading checkpoint syncer; will attempt to use the next config"
-                        );
-                    }
-                }
-            }
-            if checkpoint_syncers.get(&validator.into()).is_none() {
-                if validator_storage_locations.is_empty() {
-                    warn!(?validator, "Validator has not announced any storage locations; see https://docs.hyperlane.xyz/docs/operators/validators/announcing-your-validator");
-                } else {
-                    warn!(
-                        ?validator,
-                        ?validator_storage_locations,
-                        "No valid checkpoint syncer configs for validator"
-                    );
-                }
-            }
-        }
+            "Building checkpoint syncers of validators for message");
+        let checkpoint_syncers = self
+            .origin_validator_announce_index
+            .checkpoint_syncers(validators)
+            .await?;
         Ok(MultisigCheckpointSyncer::new(
             checkpoint_syncers,
             self.metrics.clone(),
             app_context,
+            self.origin_chain_setup.hash_algorithm,
+            self.checkpoint_batch_cache.clone(),
         ))
     }
 }
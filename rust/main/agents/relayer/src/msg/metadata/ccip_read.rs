@@ -32,8 +32,9 @@ impl MetadataBuilder for CcipReadIsmMetadataBuilder {
         const CTX: &str = "When fetching CcipRead metadata";
         let ism = self.build_ccip_read_ism(ism_address).await.context(CTX)?;
 
-        let response = ism
-            .get_offchain_verify_info(RawHyperlaneMessage::from(message).to_vec())
+        let response = self
+            .simulation_limiter()
+            .run(ism.get_offchain_verify_info(RawHyperlaneMessage::from(message).to_vec()))
             .await;
         let info: OffchainLookup = match response {
             Ok(_) => {
@@ -2,6 +2,7 @@ mod aggregation;
 mod base;
 mod ccip_read;
 mod multisig;
+mod native_bridge;
 mod null_metadata;
 mod routing;
 
@@ -11,5 +12,6 @@ pub(crate) use base::{
     MessageMetadataBuilder, Metadata, MetadataBuilder,
 };
 use ccip_read::CcipReadIsmMetadataBuilder;
+use native_bridge::NativeBridgeIsmMetadataBuilder;
 use null_metadata::NullMetadataBuilder;
 use routing::RoutingIsmMetadataBuilder;
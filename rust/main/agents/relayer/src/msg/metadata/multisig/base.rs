@@ -102,8 +102,10 @@ impl<T: MultisigIsmMetadataBuilder> MetadataBuilder for T {
             .await
             .context(CTX)?;
 
-        let (validators, threshold) = multisig_ism
-            .validators_and_threshold(message)
+        let (validators, threshold) = self
+            .as_ref()
+            .simulation_limiter()
+            .run(multisig_ism.validators_and_threshold(message))
             .await
             .context(CTX)?;
 
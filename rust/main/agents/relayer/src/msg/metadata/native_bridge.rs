@@ -0,0 +1,52 @@
+#![allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+
+use async_trait::async_trait;
+use derive_more::Deref;
+use derive_new::new;
+use eyre::Context;
+use hyperlane_core::{HyperlaneMessage, ModuleType, H256};
+use tracing::{info, instrument};
+
+use super::{base::MessageMetadataBuilder, Metadata, MetadataBuilder};
+
+/// Builds the metadata of ISMs that are verified through the native bridge of
+/// an L2 origin, which is the proof that executes the bridge withdrawal
+/// carrying the message id
+#[derive(Clone, Debug, new, Deref)]
+pub struct NativeBridgeIsmMetadataBuilder {
+    #[deref]
+    base: MessageMetadataBuilder,
+    module_type: ModuleType,
+}
+
+#[async_trait]
+impl MetadataBuilder for NativeBridgeIsmMetadataBuilder {
+    #[instrument(err, skip(self, message))]
+    async fn build(&self, ism_address: H256, message: &HyperlaneMessage) -> eyre::Result<Metadata> {
+        const CTX: &str = "When fetching native bridge metadata";
+        let ism = self
+            .build_native_bridge_ism(ism_address, self.module_type)
+            .await
+            .context(CTX)?;
+
+        // Anyone can execute the withdrawal, after which the message id is
+        // already verified and no metadata is needed
+        if ism.is_verified(message).await.context(CTX)? {
+            info!("Message is already verified through the bridge");
+            return Ok(Metadata::Found(vec![]));
+        }
+
+        let Some(dispatched_block) = self
+            .get_dispatched_block_number(message.nonce)
+            .context(CTX)?
+        else {
+            info!("Block the message was dispatched in is unknown");
+            return Ok(Metadata::CouldNotFetch);
+        };
+        let metadata = ism
+            .withdrawal_metadata(message, dispatched_block)
+            .await
+            .context(CTX)?;
+        Ok(metadata.map_or(Metadata::CouldNotFetch, Metadata::Found))
+    }
+}
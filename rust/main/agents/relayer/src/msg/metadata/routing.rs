@@ -19,7 +19,11 @@ impl MetadataBuilder for RoutingIsmMetadataBuilder {
     async fn build(&self, ism_address: H256, message: &HyperlaneMessage) -> eyre::Result<Metadata> {
         const CTX: &str = "When fetching RoutingIsm metadata";
         let ism = self.build_routing_ism(ism_address).await.context(CTX)?;
-        let module = ism.route(message).await.context(CTX)?;
+        let module = self
+            .simulation_limiter()
+            .run(ism.route(message))
+            .await
+            .context(CTX)?;
         self.base.build(module, message).await.context(CTX)
     }
 }
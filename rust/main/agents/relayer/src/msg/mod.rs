@@ -27,12 +27,31 @@
 //!   - FallbackProviderSubmitter (Serialized, but if some RPC provider sucks,
 //!   switch everyone to new one)
 
+pub(crate) mod app_context_budget;
+pub(crate) mod backoff;
 pub(crate) mod blacklist;
+pub(crate) mod deliverability_probe;
+pub(crate) mod delivery_status;
+pub(crate) mod destination_domain;
+pub(crate) mod destination_pause;
+pub(crate) mod evidence_bundle;
+pub(crate) mod funding_pool;
 pub(crate) mod gas_payment;
+pub(crate) mod log_dedup;
+pub(crate) mod message_expiry;
 pub(crate) mod metadata;
 pub(crate) mod op_queue;
 pub(crate) mod op_submitter;
+pub(crate) mod pre_transaction;
+pub(crate) mod preflight;
 pub(crate) mod processor;
+pub(crate) mod recipient_code_hash;
+pub(crate) mod remote_filter_list;
+pub(crate) mod route_matrix;
+pub(crate) mod runtime_config;
+pub(crate) mod shadow_mode;
+pub(crate) mod simulation_limiter;
+pub(crate) mod unknown_destination;
 
 pub mod pending_message;
 
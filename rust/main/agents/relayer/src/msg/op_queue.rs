@@ -166,7 +166,7 @@ pub mod test {
     use hyperlane_core::{
         HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneDomainTechnicalStack,
         HyperlaneDomainType, HyperlaneMessage, KnownHyperlaneDomain, PendingOperationResult,
-        TryBatchAs, TxOutcome, H256, U256,
+        ReprepareReason, TryBatchAs, TxOutcome, H256, U256,
     };
     use serde::Serialize;
     use std::{
@@ -185,6 +185,11 @@ pub mod test {
         recipient_address: H256,
         seconds_to_next_attempt: u64,
         destination_domain: HyperlaneDomain,
+        #[serde(skip)]
+        retries: u32,
+        /// If set, preparing the operation fails for this reason
+        #[serde(skip)]
+        prepare_failure: Option<ReprepareReason>,
     }
 
     impl MockPendingOperation {
@@ -197,6 +202,8 @@ pub mod test {
                 sender_address: H256::random(),
                 recipient_address: H256::random(),
                 origin_domain_id: 0,
+                retries: 0,
+                prepare_failure: None,
             }
         }
 
@@ -215,6 +222,8 @@ pub mod test {
                     domain_protocol: HyperlaneDomainProtocol::Ethereum,
                     domain_technical_stack: HyperlaneDomainTechnicalStack::Other,
                 },
+                retries: 0,
+                prepare_failure: None,
             }
         }
 
@@ -239,6 +248,13 @@ pub mod test {
             }
         }
 
+        pub fn with_prepare_failure(self, reason: ReprepareReason) -> Self {
+            Self {
+                prepare_failure: Some(reason),
+                ..self
+            }
+        }
+
         pub fn with_origin_domain(self, domain: HyperlaneDomain) -> Self {
             let domain_id = match domain {
                 HyperlaneDomain::Known(d) => d as u32,
@@ -309,7 +325,10 @@ pub mod test {
         }
 
         async fn prepare(&mut self) -> PendingOperationResult {
-            todo!()
+            match &self.prepare_failure {
+                Some(reason) => PendingOperationResult::Reprepare(reason.clone()),
+                None => todo!(),
+            }
         }
 
         /// Submit this operation to the blockchain and report if it was successful
@@ -323,7 +342,7 @@ pub mod test {
         }
 
         fn get_tx_cost_estimate(&self) -> Option<U256> {
-            todo!()
+            None
         }
 
         /// This will be called after the operation has been submitted and is
@@ -349,12 +368,16 @@ pub mod test {
             )
         }
 
-        fn set_next_attempt_after(&mut self, _delay: Duration) {
-            todo!()
+        fn set_next_attempt_after(&mut self, delay: Duration) {
+            self.seconds_to_next_attempt = delay.as_secs();
         }
 
-        fn set_retries(&mut self, _retries: u32) {
-            todo!()
+        fn retry_count(&self) -> u32 {
+            self.retries
+        }
+
+        fn set_retries(&mut self, retries: u32) {
+            self.retries = retries;
         }
     }
 
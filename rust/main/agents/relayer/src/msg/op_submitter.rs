@@ -2,7 +2,7 @@
 #![allow(clippy::doc_lazy_continuation)] // TODO: `rustc` 1.80.1 clippy issue
 
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use derive_new::new;
 use futures::future::join_all;
@@ -31,8 +31,13 @@ use hyperlane_core::{
     PendingOperationResult, QueueOperation, TxOutcome,
 };
 
+use crate::alerts::AlertSink;
+use crate::msg::deliverability_probe::DeliverabilityProbe;
+use crate::msg::evidence_bundle::{EvidenceCollector, SubmissionStage};
 use crate::msg::pending_message::CONFIRM_DELAY;
+use crate::msg::shadow_mode::{ShadowMode, SHADOW_MODE_RECHECK_INTERVAL};
 use crate::server::MessageRetryRequest;
+use crate::settings::ParkingLotConf;
 
 use super::op_queue::OpQueue;
 use super::op_queue::OperationPriorityQueue;
@@ -41,7 +46,11 @@ use super::op_queue::OperationPriorityQueue;
 /// based on how many queues exist in each OpSubmitter.
 /// This value needs to be manually updated if we ever
 /// update the number of queues an OpSubmitter has.
-pub const SUBMITTER_QUEUE_COUNT: usize = 3;
+pub const SUBMITTER_QUEUE_COUNT: usize = 4;
+
+/// How often the parked queue is checked for operations that are due to be
+/// prepared again.
+const PARKED_QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 /// SerialSubmitter accepts operations over a channel. It is responsible for
 /// executing the right strategy to deliver those messages to the destination
@@ -62,6 +71,20 @@ pub const SUBMITTER_QUEUE_COUNT: usize = 3;
 /// retained within the SerialSubmitter, and will eventually be retried
 /// according to our prioritization rule.
 ///
+/// Operations which keep failing to prepare can optionally be moved to a
+/// parked queue once they exceed a retry threshold, so that a single
+/// permanently-reverting operation doesn't consume prepare cycles forever.
+/// Parked operations are moved back to the prepare queue on a slow schedule.
+///
+/// In probe mode, operations go through the prepare queue as usual but are
+/// never submitted. The outcome of preparing them is recorded instead, and
+/// operations that would have been submitted are dropped.
+///
+/// In shadow mode, operations are never submitted either. Instead of being
+/// dropped, prepared operations are prepared again after a while, until
+/// another relayer delivers them, so that the outcome of preparing them can
+/// be compared with their delivery.
+///
 /// Finally, the SerialSubmitter ensures that message delivery is robust to
 /// destination chain reorgs prior to committing delivery status to
 /// HyperlaneRocksDB.
@@ -102,9 +125,21 @@ pub struct SerialSubmitter {
     max_batch_size: u32,
     /// tokio task monitor
     task_monitor: TaskMonitor,
+    /// Config for parking operations that repeatedly fail to prepare
+    parking_lot: Option<ParkingLotConf>,
+    /// Sink for alerts about saturated queues and repeatedly failing operations
+    alert_sink: Option<Arc<AlertSink>>,
+    /// If set, operations are only prepared, never submitted
+    probe: Option<Arc<DeliverabilityProbe>>,
+    /// If set, operations are only prepared until another relayer delivers
+    /// them, never submitted
+    shadow_mode: Option<Arc<ShadowMode>>,
+    /// If set, evidence is collected when submissions keep failing
+    evidence: Option<Arc<EvidenceCollector>>,
     prepare_queue: OpQueue,
     submit_queue: OpQueue,
     confirm_queue: OpQueue,
+    parked_queue: OpQueue,
 }
 
 impl SerialSubmitter {
@@ -115,6 +150,11 @@ impl SerialSubmitter {
         metrics: SerialSubmitterMetrics,
         max_batch_size: u32,
         task_monitor: TaskMonitor,
+        parking_lot: Option<ParkingLotConf>,
+        alert_sink: Option<Arc<AlertSink>>,
+        probe: Option<Arc<DeliverabilityProbe>>,
+        shadow_mode: Option<Arc<ShadowMode>>,
+        evidence: Option<Arc<EvidenceCollector>>,
     ) -> Self {
         let prepare_queue = OpQueue::new(
             metrics.submitter_queue_length.clone(),
@@ -131,6 +171,11 @@ impl SerialSubmitter {
             "confirm_queue".to_string(),
             Arc::new(Mutex::new(retry_op_transmitter.subscribe())),
         );
+        let parked_queue = OpQueue::new(
+            metrics.submitter_queue_length.clone(),
+            "parked_queue".to_string(),
+            Arc::new(Mutex::new(retry_op_transmitter.subscribe())),
+        );
 
         Self {
             domain,
@@ -138,9 +183,15 @@ impl SerialSubmitter {
             metrics,
             max_batch_size,
             task_monitor,
+            parking_lot,
+            alert_sink,
+            probe,
+            shadow_mode,
+            evidence,
             prepare_queue,
             submit_queue,
             confirm_queue,
+            parked_queue,
         }
     }
 
@@ -148,6 +199,10 @@ impl SerialSubmitter {
         self.prepare_queue.queue.clone()
     }
 
+    pub async fn parked_queue(&self) -> OperationPriorityQueue {
+        self.parked_queue.queue.clone()
+    }
+
     pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
         let span = info_span!("SerialSubmitter", destination=%self.domain);
         let task_monitor = self.task_monitor.clone();
@@ -164,9 +219,15 @@ impl SerialSubmitter {
             rx: rx_prepare,
             max_batch_size,
             task_monitor,
+            parking_lot,
+            alert_sink,
+            probe,
+            shadow_mode,
+            evidence,
             prepare_queue,
             submit_queue,
             confirm_queue,
+            parked_queue,
         } = self;
 
         let tasks = [
@@ -181,6 +242,21 @@ impl SerialSubmitter {
                     prepare_queue.clone(),
                     submit_queue.clone(),
                     confirm_queue.clone(),
+                    parked_queue.clone(),
+                    parking_lot,
+                    alert_sink,
+                    probe,
+                    shadow_mode,
+                    max_batch_size,
+                    metrics.clone(),
+                ),
+            )),
+            tokio::spawn(TaskMonitor::instrument(
+                &task_monitor,
+                parked_task(
+                    domain.clone(),
+                    prepare_queue.clone(),
+                    parked_queue,
                     max_batch_size,
                     metrics.clone(),
                 ),
@@ -193,6 +269,7 @@ impl SerialSubmitter {
                     submit_queue,
                     confirm_queue.clone(),
                     max_batch_size,
+                    evidence.clone(),
                     metrics.clone(),
                 ),
             )),
@@ -203,6 +280,7 @@ impl SerialSubmitter {
                     prepare_queue,
                     confirm_queue,
                     max_batch_size,
+                    evidence,
                     metrics,
                 ),
             )),
@@ -235,18 +313,28 @@ async fn receive_task(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all, fields(%domain))]
 async fn prepare_task(
     domain: HyperlaneDomain,
     mut prepare_queue: OpQueue,
     submit_queue: OpQueue,
     confirm_queue: OpQueue,
+    parked_queue: OpQueue,
+    parking_lot: Option<ParkingLotConf>,
+    alert_sink: Option<Arc<AlertSink>>,
+    probe: Option<Arc<DeliverabilityProbe>>,
+    shadow_mode: Option<Arc<ShadowMode>>,
     max_batch_size: u32,
     metrics: SerialSubmitterMetrics,
 ) {
     // Prepare at most `max_batch_size` ops at a time to avoid getting rate-limited
     let ops_to_prepare = max_batch_size as usize;
     loop {
+        if let Some(alert_sink) = &alert_sink {
+            let queue_length = prepare_queue.queue.lock().await.len();
+            alert_sink.check_queue_length(&domain, queue_length);
+        }
         // Pop messages here according to the configured batch.
         let mut batch = prepare_queue.pop_many(ops_to_prepare).await;
         if batch.is_empty() {
@@ -272,8 +360,30 @@ async fn prepare_task(
             })
             .count();
         let batch_len = batch.len();
-        for (op, prepare_result) in batch.into_iter().zip(res.into_iter()) {
+        for (mut op, prepare_result) in batch.into_iter().zip(res.into_iter()) {
+            if let Some(probe) = &probe {
+                probe.record(&op, &prepare_result);
+            }
+            if let Some(shadow_mode) = &shadow_mode {
+                shadow_mode.record(&op, &prepare_result);
+            }
             match prepare_result {
+                PendingOperationResult::Success if probe.is_some() => {
+                    debug!(?op, "Operation prepared, dropping it in probe mode");
+                    metrics.ops_prepared.inc();
+                    op.decrement_metric_if_exists();
+                }
+                PendingOperationResult::Success if shadow_mode.is_some() => {
+                    debug!(
+                        ?op,
+                        "Operation prepared, preparing it again later in shadow mode"
+                    );
+                    metrics.ops_prepared.inc();
+                    op.set_next_attempt_after(SHADOW_MODE_RECHECK_INTERVAL);
+                    prepare_queue
+                        .push(op, Some(PendingOperationStatus::ReadyToSubmit))
+                        .await;
+                }
                 PendingOperationResult::Success => {
                     debug!(?op, "Operation prepared");
                     metrics.ops_prepared.inc();
@@ -287,9 +397,24 @@ async fn prepare_task(
                 }
                 PendingOperationResult::Reprepare(reason) => {
                     metrics.ops_failed.inc();
-                    prepare_queue
-                        .push(op, Some(PendingOperationStatus::Retry(reason)))
-                        .await;
+                    if let Some(alert_sink) = &alert_sink {
+                        alert_sink.check_operation_failures(
+                            &domain,
+                            op.id(),
+                            op.retry_count(),
+                            &reason,
+                        );
+                    }
+                    match parking_lot {
+                        Some(conf) if op.retry_count() >= conf.prepare_failure_threshold => {
+                            park_op(op, reason, &parked_queue, conf, &metrics).await;
+                        }
+                        _ => {
+                            prepare_queue
+                                .push(op, Some(PendingOperationStatus::Retry(reason)))
+                                .await;
+                        }
+                    }
                 }
                 PendingOperationResult::Drop => {
                     metrics.ops_dropped.inc();
@@ -310,6 +435,62 @@ async fn prepare_task(
     }
 }
 
+async fn park_op(
+    mut op: QueueOperation,
+    reason: ReprepareReason,
+    parked_queue: &OpQueue,
+    parking_lot: ParkingLotConf,
+    metrics: &SerialSubmitterMetrics,
+) {
+    warn!(
+        ?op,
+        retry_count = op.retry_count(),
+        retry_interval = ?parking_lot.retry_interval,
+        "Parking operation after repeated prepare failures"
+    );
+    op.set_next_attempt_after(parking_lot.retry_interval);
+    parked_queue
+        .push(op, Some(PendingOperationStatus::Retry(reason)))
+        .await;
+    metrics.ops_parked.inc();
+}
+
+#[instrument(skip_all, fields(%domain))]
+async fn parked_task(
+    domain: HyperlaneDomain,
+    prepare_queue: OpQueue,
+    mut parked_queue: OpQueue,
+    max_batch_size: u32,
+    metrics: SerialSubmitterMetrics,
+) {
+    let recv_limit = max_batch_size as usize;
+    loop {
+        // The queue is ordered by `next_attempt_after`, so the operations due
+        // the soonest are popped first.
+        let batch = parked_queue.pop_many(recv_limit).await;
+        let mut unparked_count = 0;
+        for op in batch {
+            debug_assert_eq!(*op.destination_domain(), domain);
+            let is_due = op
+                .next_attempt_after()
+                .map(|a| Instant::now() >= a)
+                .unwrap_or(true);
+            if is_due {
+                debug!(?op, "Moving parked operation back to the prepare queue");
+                metrics.ops_unparked.inc();
+                unparked_count += 1;
+                prepare_queue.push(op, None).await;
+            } else {
+                parked_queue.push(op, None).await;
+            }
+        }
+        if unparked_count == 0 {
+            // Either the queue is empty or nothing is due yet
+            sleep(PARKED_QUEUE_POLL_INTERVAL).await;
+        }
+    }
+}
+
 #[instrument(skip_all, fields(%domain))]
 async fn submit_task(
     domain: HyperlaneDomain,
@@ -317,6 +498,7 @@ async fn submit_task(
     mut submit_queue: OpQueue,
     mut confirm_queue: OpQueue,
     max_batch_size: u32,
+    evidence: Option<Arc<EvidenceCollector>>,
     metrics: SerialSubmitterMetrics,
 ) {
     let recv_limit = max_batch_size as usize;
@@ -331,25 +513,45 @@ async fn submit_task(
             }
             std::cmp::Ordering::Equal => {
                 let op = batch.pop().unwrap();
-                submit_single_operation(op, &mut prepare_queue, &mut confirm_queue, &metrics).await;
+                submit_single_operation(
+                    op,
+                    &mut prepare_queue,
+                    &mut confirm_queue,
+                    evidence.as_deref(),
+                    &metrics,
+                )
+                .await;
             }
             std::cmp::Ordering::Greater => {
                 OperationBatch::new(batch, domain.clone())
-                    .submit(&mut prepare_queue, &mut confirm_queue, &metrics)
+                    .submit(
+                        &mut prepare_queue,
+                        &mut confirm_queue,
+                        evidence.as_deref(),
+                        &metrics,
+                    )
                     .await;
             }
         }
     }
 }
 
-#[instrument(skip(prepare_queue, confirm_queue, metrics), ret, level = "debug")]
+#[instrument(
+    skip(prepare_queue, confirm_queue, evidence, metrics),
+    ret,
+    level = "debug"
+)]
 async fn submit_single_operation(
     mut op: QueueOperation,
     prepare_queue: &mut OpQueue,
     confirm_queue: &mut OpQueue,
+    evidence: Option<&EvidenceCollector>,
     metrics: &SerialSubmitterMetrics,
 ) {
     let status = op.submit().await;
+    if let (Some(evidence), PendingOperationResult::Reprepare(reason)) = (evidence, &status) {
+        evidence.record_failure(&op, SubmissionStage::Submit, reason);
+    }
     match status {
         PendingOperationResult::Reprepare(reprepare_reason) => {
             prepare_queue
@@ -408,6 +610,7 @@ async fn confirm_task(
     prepare_queue: OpQueue,
     mut confirm_queue: OpQueue,
     max_batch_size: u32,
+    evidence: Option<Arc<EvidenceCollector>>,
     metrics: SerialSubmitterMetrics,
 ) {
     let recv_limit = max_batch_size as usize;
@@ -427,6 +630,7 @@ async fn confirm_task(
                 domain.clone(),
                 prepare_queue.clone(),
                 confirm_queue.clone(),
+                evidence.clone(),
                 metrics.clone(),
             )
         });
@@ -449,12 +653,22 @@ async fn confirm_operation(
     domain: HyperlaneDomain,
     prepare_queue: OpQueue,
     confirm_queue: OpQueue,
+    evidence: Option<Arc<EvidenceCollector>>,
     metrics: SerialSubmitterMetrics,
 ) -> PendingOperationResult {
     trace!(?op, "Confirming operation");
     debug_assert_eq!(*op.destination_domain(), domain);
 
     let operation_result = op.confirm().await;
+    if let Some(evidence) = &evidence {
+        match &operation_result {
+            PendingOperationResult::Success => evidence.record_success(&op),
+            PendingOperationResult::Reprepare(reason) => {
+                evidence.record_failure(&op, SubmissionStage::Confirm, reason);
+            }
+            _ => {}
+        }
+    }
     match &operation_result {
         PendingOperationResult::Success => {
             debug!(?op, "Operation confirmed");
@@ -492,6 +706,8 @@ pub struct SerialSubmitterMetrics {
     ops_confirmed: IntCounter,
     ops_failed: IntCounter,
     ops_dropped: IntCounter,
+    ops_parked: IntCounter,
+    ops_unparked: IntCounter,
 }
 
 impl SerialSubmitterMetrics {
@@ -514,6 +730,12 @@ impl SerialSubmitterMetrics {
             ops_dropped: metrics
                 .operations_processed_count()
                 .with_label_values(&["dropped", destination]),
+            ops_parked: metrics
+                .operations_processed_count()
+                .with_label_values(&["parked", destination]),
+            ops_unparked: metrics
+                .operations_processed_count()
+                .with_label_values(&["unparked", destination]),
         }
     }
 }
@@ -530,6 +752,7 @@ impl OperationBatch {
         self,
         prepare_queue: &mut OpQueue,
         confirm_queue: &mut OpQueue,
+        evidence: Option<&EvidenceCollector>,
         metrics: &SerialSubmitterMetrics,
     ) {
         let excluded_ops = match self.try_submit_as_batch(metrics).await {
@@ -545,7 +768,7 @@ impl OperationBatch {
         if !excluded_ops.is_empty() {
             warn!(excluded_ops=?excluded_ops, "Either operations reverted in the batch or the txid wasn't included. Falling back to serial submission.");
             OperationBatch::new(excluded_ops, self.domain)
-                .submit_serially(prepare_queue, confirm_queue, metrics)
+                .submit_serially(prepare_queue, confirm_queue, evidence, metrics)
                 .await;
         }
     }
@@ -614,10 +837,139 @@ impl OperationBatch {
         self,
         prepare_queue: &mut OpQueue,
         confirm_queue: &mut OpQueue,
+        evidence: Option<&EvidenceCollector>,
         metrics: &SerialSubmitterMetrics,
     ) {
         for op in self.operations.into_iter() {
-            submit_single_operation(op, prepare_queue, confirm_queue, metrics).await;
+            submit_single_operation(op, prepare_queue, confirm_queue, evidence, metrics).await;
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::Registry;
+
+    use super::*;
+    use crate::msg::op_queue::test::{dummy_metrics_and_label, MockPendingOperation};
+
+    fn dummy_queue(broadcaster: &Sender<MessageRetryRequest>) -> OpQueue {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        )
+    }
+
+    fn dummy_submitter_metrics(domain: &HyperlaneDomain) -> SerialSubmitterMetrics {
+        let core_metrics = CoreMetrics::new("relayer", 4000, Registry::new()).unwrap();
+        SerialSubmitterMetrics::new(&core_metrics, domain)
+    }
+
+    /// Waits for a task under test to leave `len` operations in `queue`
+    async fn wait_for_queue_len(queue: &OpQueue, len: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while queue.queue.lock().await.len() != len {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("timed out waiting for queue length");
+    }
+
+    #[tokio::test]
+    async fn test_ops_that_keep_failing_to_prepare_are_parked() {
+        let domain: HyperlaneDomain = KnownHyperlaneDomain::Arbitrum.into();
+        let broadcaster = Sender::new(10);
+        let prepare_queue = dummy_queue(&broadcaster);
+        let parked_queue = dummy_queue(&broadcaster);
+        let metrics = dummy_submitter_metrics(&domain);
+        let parking_lot = ParkingLotConf {
+            prepare_failure_threshold: 3,
+            retry_interval: Duration::from_secs(60),
+        };
+
+        let mut below_threshold = MockPendingOperation::new(0, domain.clone())
+            .with_prepare_failure(ReprepareReason::ErrorEstimatingGas);
+        below_threshold.set_retries(2);
+        let mut at_threshold = MockPendingOperation::new(0, domain.clone())
+            .with_prepare_failure(ReprepareReason::ErrorEstimatingGas);
+        at_threshold.set_retries(3);
+        let below_threshold_id = below_threshold.id();
+        let at_threshold_id = at_threshold.id();
+        prepare_queue.push(Box::new(below_threshold), None).await;
+        prepare_queue.push(Box::new(at_threshold), None).await;
+
+        let task = tokio::spawn(prepare_task(
+            domain.clone(),
+            prepare_queue.clone(),
+            dummy_queue(&broadcaster),
+            dummy_queue(&broadcaster),
+            parked_queue.clone(),
+            Some(parking_lot),
+            None,
+            None,
+            None,
+            10,
+            metrics.clone(),
+        ));
+        // Failing ops are only prepared once before the task backs off
+        wait_for_queue_len(&parked_queue, 1).await;
+        wait_for_queue_len(&prepare_queue, 1).await;
+        task.abort();
+
+        let prepare_ids = prepare_queue
+            .queue
+            .lock()
+            .await
+            .iter()
+            .map(|op| op.0.id())
+            .collect::<Vec<_>>();
+        assert_eq!(prepare_ids, vec![below_threshold_id]);
+
+        let parked = parked_queue.queue.lock().await;
+        assert_eq!(parked.len(), 1);
+        let parked_op = &parked.peek().unwrap().0;
+        assert_eq!(parked_op.id(), at_threshold_id);
+        // Parked ops wait for the retry interval before being prepared again
+        assert!(parked_op.next_attempt_after().unwrap() > Instant::now() + Duration::from_secs(59));
+        assert_eq!(metrics.ops_parked.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_parked_ops_are_prepared_again_once_due() {
+        let domain: HyperlaneDomain = KnownHyperlaneDomain::Arbitrum.into();
+        let broadcaster = Sender::new(10);
+        let prepare_queue = dummy_queue(&broadcaster);
+        let parked_queue = dummy_queue(&broadcaster);
+        let metrics = dummy_submitter_metrics(&domain);
+
+        let due = MockPendingOperation::new(0, domain.clone());
+        let not_due = MockPendingOperation::new(60, domain.clone());
+        let due_id = due.id();
+        let not_due_id = not_due.id();
+        parked_queue.push(Box::new(due), None).await;
+        parked_queue.push(Box::new(not_due), None).await;
+
+        let task = tokio::spawn(parked_task(
+            domain.clone(),
+            prepare_queue.clone(),
+            parked_queue.clone(),
+            10,
+            metrics.clone(),
+        ));
+        wait_for_queue_len(&prepare_queue, 1).await;
+        wait_for_queue_len(&parked_queue, 1).await;
+        task.abort();
+
+        let prepare_queue = prepare_queue.queue.lock().await;
+        assert_eq!(prepare_queue.len(), 1);
+        assert_eq!(prepare_queue.peek().unwrap().0.id(), due_id);
+        let parked_queue = parked_queue.queue.lock().await;
+        assert_eq!(parked_queue.len(), 1);
+        assert_eq!(parked_queue.peek().unwrap().0.id(), not_due_id);
+        assert_eq!(metrics.ops_unparked.get(), 1);
+    }
+}
@@ -3,7 +3,7 @@
 use std::{
     fmt::{Debug, Formatter},
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
@@ -13,19 +13,36 @@ use prometheus::{IntCounter, IntGauge};
 use serde::Serialize;
 use tracing::{debug, error, info, info_span, instrument, trace, warn, Instrument, Level};
 
-use hyperlane_base::{db::HyperlaneDb, CoreMetrics};
+use hyperlane_base::{
+    db::{HyperlaneDb, DB},
+    CoreMetrics, PersistentIntCounter, SignerBalanceFloors,
+};
 use hyperlane_core::{
     gas_used_by_operation, BatchItem, ChainCommunicationError, ChainResult, ConfirmReason,
-    HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, MessageSubmissionData,
-    PendingOperation, PendingOperationResult, PendingOperationStatus, ReprepareReason, TryBatchAs,
-    TxOutcome, H256, U256,
+    FixedPointNumber, HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox,
+    MessageSubmissionData, PendingOperation, PendingOperationResult, PendingOperationStatus,
+    ReprepareReason, TryBatchAs, TxOutcome, H256, H512, U256,
 };
 use hyperlane_operation_verifier::ApplicationOperationVerifier;
 
 use super::{
+    app_context_budget::AppContextSpendTracker,
+    backoff::{BackoffPolicy, FailureClass},
+    delivery_status::DeliveryStatusBatcher,
+    destination_domain::DestinationDomainCache,
+    destination_pause::{is_pause_error, DestinationPauseTracker},
+    funding_pool::FundingScheduler,
     gas_payment::{GasPaymentEnforcer, GasPolicyStatus},
+    log_dedup::LogDeduplicator,
+    message_expiry::MessageExpiry,
     metadata::{BaseMetadataBuilder, MessageMetadataBuilder, Metadata, MetadataBuilder},
+    pre_transaction::{PendingPreTransaction, PreTransactionHooks},
+    preflight::{PreflightSimulation, SimulationFailure},
+    recipient_code_hash::RecipientCodeHashFilter,
+    runtime_config::RuntimeConfig,
+    simulation_limiter::SimulationLimiter,
 };
+use crate::status_webhook::{MessageStatusEvent, MessageStatusNotification, MessageStatusNotifier};
 
 /// a default of 66 is picked, so messages are retried for 2 weeks (period confirmed by @nambrot) before being skipped.
 /// See this PR for why 66 retries means 2 weeks:
@@ -41,6 +58,31 @@ pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
 
 pub const RETRIEVED_MESSAGE_LOG: &str = "Message status retrieved from db";
 
+/// How long to wait before checking again whether a message's origin or
+/// destination chain is still paused
+const PAUSED_CHAIN_DELAY: Duration = Duration::from_secs(30);
+
+/// How long to wait before checking again whether a message's app context is
+/// still over its daily budget
+const OVER_BUDGET_DELAY: Duration = Duration::from_secs(60 * 5);
+
+/// How long to wait before checking again whether a destination got more of
+/// its funding pool's budget, which is rebalanced every few seconds
+const FUNDING_POOL_EXHAUSTED_DELAY: Duration = Duration::from_secs(60);
+
+/// How long to wait before checking again whether a new gas payment was made
+/// for a message that didn't meet the gas payment requirement. This is only a
+/// database lookup, so it is done much more often than the message is retried.
+const GAS_PAYMENT_RECHECK_DELAY: Duration = Duration::from_secs(30);
+
+/// The least time to wait before retrying a message whose simulated delivery
+/// ran out of gas, which rarely resolves quickly
+const OUT_OF_GAS_DELAY: Duration = Duration::from_secs(60 * 10);
+
+/// The least time to wait before checking again whether a message's recipient
+/// is allowed, e.g. after it was upgraded or the relayer's lists changed
+const RECIPIENT_CODE_HASH_NOT_ALLOWED_DELAY: Duration = Duration::from_secs(60 * 60);
+
 /// The message context contains the links needed to submit a message. Each
 /// instance is for a unique origin -> destination pairing.
 pub struct MessageContext {
@@ -60,6 +102,47 @@ pub struct MessageContext {
     pub metrics: MessageSubmissionMetrics,
     /// Application operation verifier
     pub application_operation_verifier: Option<Arc<dyn ApplicationOperationVerifier>>,
+    /// If set, repeated warnings for the same message are deduplicated
+    pub log_deduplicator: Option<Arc<LogDeduplicator>>,
+    /// If set, messages are only delivered to recipients whose code hash is
+    /// allowed by the filter
+    pub recipient_code_hash_filter: Option<Arc<RecipientCodeHashFilter>>,
+    /// Config that may be changed at runtime, e.g. to pause chains
+    pub runtime_config: Arc<RuntimeConfig>,
+    /// Tracks the spend of app contexts on the destination against their
+    /// daily budgets
+    pub app_context_spend_tracker: Arc<AppContextSpendTracker>,
+    /// The domain id reported by the destination mailbox, which messages are
+    /// checked against before submission
+    pub destination_domain_cache: Arc<DestinationDomainCache>,
+    /// Batches the delivery checks of the confirm stage for the destination
+    pub delivery_status_batcher: Arc<DeliveryStatusBatcher>,
+    /// Limits the simulations run against the destination, shared with the
+    /// metadata builder
+    pub simulation_limiter: Arc<SimulationLimiter>,
+    /// Which recipients of the destination can't be delivered to because a
+    /// contract is paused
+    pub destination_pause: Arc<DestinationPauseTracker>,
+    /// If set, failed simulations of deliveries to the destination are
+    /// classified, counted and retried by class
+    pub preflight_simulation: Option<Arc<PreflightSimulation>>,
+    /// If set, the transactions messages require to land on the destination
+    /// before they are delivered
+    pub pre_transaction_hooks: Option<Arc<PreTransactionHooks>>,
+    /// If set, messages aren't delivered while the signer balance on the
+    /// destination is below its floor
+    pub signer_balance_floors: Option<Arc<SignerBalanceFloors>>,
+    /// If set, the status changes of messages are pushed to a webhook
+    pub message_status_notifier: Option<Arc<MessageStatusNotifier>>,
+    /// If set, messages that stay undelivered for longer than their TTL are
+    /// moved to a dead-letter state
+    pub message_expiry: Option<Arc<MessageExpiry>>,
+    /// If set, messages aren't delivered to destinations that spent their
+    /// allowance of the funding pool they share with other destinations
+    pub funding_scheduler: Option<Arc<FundingScheduler>>,
+    /// How long messages wait before being retried, depending on what their
+    /// last attempt failed on
+    pub backoff_policy: Arc<BackoffPolicy>,
 }
 
 /// A message that the submitter can and should try to submit.
@@ -74,6 +157,10 @@ pub struct PendingMessage {
     max_retries: u32,
     #[new(default)]
     submitted: bool,
+    /// Whether a two-phase destination mailbox enqueued the message, which
+    /// is then delivered once executed
+    #[new(default)]
+    enqueued: bool,
     #[new(default)]
     #[serde(skip_serializing)]
     submission_data: Option<Box<MessageSubmissionData>>,
@@ -94,6 +181,33 @@ pub struct PendingMessage {
     #[new(default)]
     #[serde(skip_serializing)]
     metric: Option<Arc<IntGauge>>,
+    #[new(default)]
+    #[serde(skip_serializing)]
+    awaited_gas_payment: Option<AwaitedGasPayment>,
+    /// The pre-transactions of the message, once they were looked up
+    #[new(default)]
+    #[serde(skip_serializing)]
+    pre_transactions: Option<Vec<PendingPreTransaction>>,
+    /// Whether the webhook was notified that the gas payment is too low since
+    /// the message was last prepared, so it's only notified once
+    #[new(default)]
+    #[serde(skip_serializing)]
+    gas_underpaid_notified: bool,
+    /// When the message expires, as a unix timestamp in seconds, if it
+    /// matches a message expiry policy
+    #[new(default)]
+    expires_at: Option<u64>,
+}
+
+/// The gas payments of a message that didn't meet the gas payment
+/// requirement, so it can be evaluated again as soon as a top-up is made
+/// instead of only when it is retried.
+#[derive(Debug, Clone, Copy)]
+struct AwaitedGasPayment {
+    /// Number of payments made for the message when it was evaluated
+    num_payments: u32,
+    /// When the message is retried regardless of new payments
+    retry_after: Option<Instant>,
 }
 
 impl Debug for PendingMessage {
@@ -198,13 +312,89 @@ impl PendingOperation for PendingMessage {
         self.app_context.clone()
     }
 
-    #[instrument(skip(self), fields(id=?self.id()), level = "debug")]
+    // Not at debug level, so that the records of the metadata builders have
+    // the correlation id at the default log level
+    #[instrument(skip(self), fields(id=?self.id(), correlation_id=?self.id()))]
     async fn prepare(&mut self) -> PendingOperationResult {
         if !self.is_ready() {
             trace!("Message is not ready to be submitted yet");
             return PendingOperationResult::NotReady;
         }
 
+        if let Some(result) = self.check_expiry() {
+            return result;
+        }
+
+        // Messages of paused chains are kept in the queue until the chain is
+        // unpaused, without counting as a failed attempt.
+        if self.ctx.runtime_config.is_paused(&self.message) {
+            debug!("Origin or destination chain is paused, not preparing message");
+            self.set_next_attempt_after(PAUSED_CHAIN_DELAY);
+            return PendingOperationResult::NotReady;
+        }
+
+        // The same goes for recipients whose deliveries revert because a
+        // contract is paused on-chain, except for one message every so often
+        // that checks whether it still is.
+        if self
+            .ctx
+            .destination_pause
+            .should_defer(self.message.recipient)
+        {
+            debug!("Recipient is paused, not preparing message");
+            self.set_next_attempt_after(PAUSED_CHAIN_DELAY);
+            return PendingOperationResult::NotReady;
+        }
+
+        // And for destinations the signer can't pay for until it's topped up
+        if self
+            .ctx
+            .signer_balance_floors
+            .as_ref()
+            .is_some_and(|floors| floors.is_below_floor(self.message.destination))
+        {
+            debug!("Signer balance on the destination is below its floor, not preparing message");
+            self.set_next_attempt_after(PAUSED_CHAIN_DELAY);
+            return PendingOperationResult::NotReady;
+        }
+
+        // And for destinations that spent their share of a shared funding wallet
+        if self
+            .ctx
+            .funding_scheduler
+            .as_ref()
+            .is_some_and(|scheduler| scheduler.is_exhausted(self.message.destination))
+        {
+            debug!("Destination spent its funding pool allowance, not preparing message");
+            self.set_next_attempt_after(FUNDING_POOL_EXHAUSTED_DELAY);
+            return PendingOperationResult::NotReady;
+        }
+
+        // Like paused chains, messages of app contexts that are over budget are
+        // kept in the queue until the budget resets.
+        if let Some(app_context) = &self.app_context {
+            if self
+                .ctx
+                .app_context_spend_tracker
+                .is_over_budget(app_context)
+            {
+                warn!(
+                    app_context,
+                    "App context exceeded its daily spend budget, not preparing message"
+                );
+                self.set_next_attempt_after(OVER_BUDGET_DELAY);
+                return PendingOperationResult::NotReady;
+            }
+        }
+
+        if let Some(result) = self.check_new_gas_payment() {
+            return result;
+        }
+
+        if let Some(result) = self.check_destination_domain().await {
+            return result;
+        }
+
         // If the message has already been processed, e.g. due to another relayer having
         // already processed, then mark it as already-processed, and move on to
         // the next tick.
@@ -226,6 +416,26 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted);
         }
 
+        // A two-phase mailbox may have enqueued it already, e.g. before a
+        // restart, in which case only its execution is left
+        match self
+            .ctx
+            .destination_mailbox
+            .awaiting_execution(self.message.id())
+            .await
+        {
+            Ok(true) => {
+                debug!("Message was already enqueued, marking as submitted.");
+                self.submitted = true;
+                self.set_next_attempt_after(CONFIRM_DELAY);
+                return PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted);
+            }
+            Ok(false) => {}
+            Err(err) => {
+                return self.on_reprepare(Some(err), ReprepareReason::ErrorCheckingDeliveryStatus);
+            }
+        }
+
         let provider = self.ctx.destination_mailbox.provider();
 
         // We cannot deliver to an address that is not a contract so check and drop if it isn't.
@@ -243,13 +453,46 @@ impl PendingOperation for PendingMessage {
                 recipient=?self.message.recipient,
                 "Dropping message because recipient is not a contract"
             );
+            self.notify_status(
+                MessageStatusEvent::Dropped,
+                None,
+                Some("recipient is not a contract"),
+            );
             return PendingOperationResult::Drop;
         }
 
+        if let Some(filter) = &self.ctx.recipient_code_hash_filter {
+            match filter.is_allowed(&*provider, &self.message.recipient).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    // Recipients can be upgraded and the lists changed, so the
+                    // message is kept and checked again much later
+                    info!(
+                        recipient=?self.message.recipient,
+                        "Recipient code hash is not allowed, not preparing message"
+                    );
+                    let result = self.on_reprepare::<ChainCommunicationError>(
+                        None,
+                        ReprepareReason::RecipientCodeHashNotAllowed,
+                    );
+                    let min_next_attempt = Instant::now() + RECIPIENT_CODE_HASH_NOT_ALLOWED_DELAY;
+                    self.next_attempt_after = Some(
+                        self.next_attempt_after
+                            .map_or(min_next_attempt, |next| next.max(min_next_attempt)),
+                    );
+                    return result;
+                }
+                Err(err) => {
+                    return self
+                        .on_reprepare(Some(err), ReprepareReason::ErrorFetchingRecipientCodeHash);
+                }
+            }
+        }
+
         let ism_address = match self
             .ctx
             .destination_mailbox
-            .recipient_ism(self.message.recipient)
+            .recipient_ism_for_origin(self.message.recipient, self.message.origin)
             .await
         {
             Ok(ism_address) => ism_address,
@@ -283,6 +526,17 @@ impl PendingOperation for PendingMessage {
 
         let metadata_bytes = match metadata {
             Metadata::Found(metadata_bytes) => {
+                // Submitting a transaction the destination doesn't accept would just waste gas
+                if let Some(max_metadata_size) = self.ctx.metadata_builder.max_metadata_size() {
+                    if metadata_bytes.len() > max_metadata_size {
+                        warn!(
+                            metadata_size = metadata_bytes.len(),
+                            max_metadata_size, "Metadata exceeds max metadata size of destination"
+                        );
+                        return self
+                            .on_reprepare::<String>(None, ReprepareReason::MetadataExceedsMaxSize);
+                    }
+                }
                 self.metadata = Some(metadata_bytes.clone());
                 metadata_bytes
             }
@@ -296,26 +550,52 @@ impl PendingOperation for PendingMessage {
             }
         };
 
+        // Delivering the message would fail without the transactions it
+        // requires, so they are submitted before it is simulated
+        if let Some(result) = self.run_pre_transactions().await {
+            return result;
+        }
+
         // Estimate transaction costs for the process call. If there are issues, it's
         // likely that gas estimation has failed because the message is
         // reverting. This is defined behavior, so we just log the error and
         // move onto the next tick.
         let tx_cost_estimate = match self
             .ctx
-            .destination_mailbox
-            .process_estimate_costs(&self.message, &metadata_bytes)
+            .simulation_limiter
+            .run(
+                self.ctx
+                    .destination_mailbox
+                    .process_estimate_costs(&self.message, &metadata_bytes),
+            )
             .await
         {
-            Ok(tx_cost_estimate) => tx_cost_estimate,
+            Ok(tx_cost_estimate) => {
+                self.ctx
+                    .destination_pause
+                    .mark_unpaused(self.message.recipient);
+                tx_cost_estimate
+            }
+            Err(err) if is_pause_error(&err) => {
+                debug!(error = ?err, "Recipient is paused, not preparing message");
+                self.ctx
+                    .destination_pause
+                    .mark_paused(self.message.recipient);
+                self.set_next_attempt_after(PAUSED_CHAIN_DELAY);
+                return PendingOperationResult::NotReady;
+            }
             Err(err) => {
-                let reason = self
-                    .clarify_reason(ReprepareReason::ErrorEstimatingGas)
-                    .await
-                    .unwrap_or(ReprepareReason::ErrorEstimatingGas);
-                return self.on_reprepare(Some(err), reason);
+                return self.on_simulation_failure(err).await;
             }
         };
 
+        // Counted before the evaluation, so that a payment made while the policies
+        // are evaluated isn't missed
+        let num_payments = self
+            .ctx
+            .origin_gas_payment_enforcer
+            .payment_count(&self.message);
+
         // If the gas payment requirement hasn't been met, move to the next tick.
         let gas_limit = match self
             .ctx
@@ -331,11 +611,17 @@ impl PendingOperation for PendingMessage {
 
         let gas_limit = match gas_limit {
             GasPolicyStatus::NoPaymentFound => {
-                return self.on_reprepare::<String>(None, ReprepareReason::GasPaymentNotFound)
+                let result = self.on_reprepare::<String>(None, ReprepareReason::GasPaymentNotFound);
+                self.await_gas_payment(num_payments);
+                self.notify_gas_underpaid();
+                return result;
             }
             GasPolicyStatus::PolicyNotMet => {
-                return self
-                    .on_reprepare::<String>(None, ReprepareReason::GasPaymentRequirementNotMet)
+                let result =
+                    self.on_reprepare::<String>(None, ReprepareReason::GasPaymentRequirementNotMet);
+                self.await_gas_payment(num_payments);
+                self.notify_gas_underpaid();
+                return result;
             }
             GasPolicyStatus::PolicyMet(gas_limit) => gas_limit,
         };
@@ -344,6 +630,7 @@ impl PendingOperation for PendingMessage {
         debug!(
             ?gas_limit,
             ?tx_cost_estimate,
+            total_cost = ?tx_cost_estimate.total_cost().ok(),
             "Gas payment requirement met, ready to process message"
         );
 
@@ -358,10 +645,12 @@ impl PendingOperation for PendingMessage {
             metadata: metadata_bytes,
             gas_limit,
         }));
+        self.gas_underpaid_notified = false;
+        self.notify_status(MessageStatusEvent::Prepared, None, None);
         PendingOperationResult::Success
     }
 
-    #[instrument(skip(self), fields(id=?self.id(), domain=%self.destination_domain()))]
+    #[instrument(skip(self), fields(id=?self.id(), correlation_id=?self.id(), domain=%self.destination_domain()))]
     async fn submit(&mut self) -> PendingOperationResult {
         if self.submitted {
             // this message has already been submitted, possibly not by us
@@ -373,20 +662,33 @@ impl PendingOperation for PendingMessage {
             .clone()
             .expect("Pending message must be prepared before it can be submitted");
 
+        // Checked again right before submission, since the message may have
+        // been prepared before the destination mailbox domain was known
+        if let Some(result) = self.check_destination_domain().await {
+            return result;
+        }
+
         // To avoid spending gas on a tx that will revert, dry-run just before submitting.
         if let Some(metadata) = self.metadata.as_ref() {
-            if self
+            if let Err(err) = self
                 .ctx
-                .destination_mailbox
-                .process_estimate_costs(&self.message, metadata)
+                .simulation_limiter
+                .run(
+                    self.ctx
+                        .destination_mailbox
+                        .process_estimate_costs(&self.message, metadata),
+                )
                 .await
-                .is_err()
             {
-                let reason = self
-                    .clarify_reason(ReprepareReason::ErrorEstimatingGas)
-                    .await
-                    .unwrap_or(ReprepareReason::ErrorEstimatingGas);
-                return self.on_reprepare::<String>(None, reason);
+                // Not a failed attempt, the message is deferred by `prepare`
+                // until the recipient is unpaused
+                if is_pause_error(&err) {
+                    self.ctx
+                        .destination_pause
+                        .mark_paused(self.message.recipient);
+                    return PendingOperationResult::Reprepare(ReprepareReason::DestinationPaused);
+                }
+                return self.on_simulation_failure(err).await;
             }
         }
 
@@ -399,7 +701,9 @@ impl PendingOperation for PendingMessage {
             .await;
         match tx_outcome {
             Ok(outcome) => {
+                let tx_hash = outcome.transaction_id;
                 self.set_operation_outcome(outcome, state.gas_limit);
+                self.notify_status(MessageStatusEvent::Submitted, Some(tx_hash), None);
                 PendingOperationResult::Confirm(ConfirmReason::SubmittedBySelf)
             }
             Err(e) => {
@@ -417,6 +721,13 @@ impl PendingOperation for PendingMessage {
         self.submission_data.as_ref().map(|d| d.gas_limit)
     }
 
+    fn submission_tx_hash(&self) -> Option<H512> {
+        self.submission_outcome
+            .as_ref()
+            .map(|outcome| outcome.transaction_id)
+    }
+
+    #[instrument(skip(self), fields(id=?self.id(), correlation_id=?self.id(), domain=%self.destination_domain()))]
     async fn confirm(&mut self) -> PendingOperationResult {
         if !self.is_ready() {
             return PendingOperationResult::NotReady;
@@ -424,8 +735,8 @@ impl PendingOperation for PendingMessage {
 
         let is_delivered = match self
             .ctx
-            .destination_mailbox
-            .delivered(self.message.id())
+            .delivery_status_batcher
+            .delivered(&*self.ctx.destination_mailbox, self.message.id())
             .await
         {
             Ok(is_delivered) => is_delivered,
@@ -443,8 +754,30 @@ impl PendingOperation for PendingMessage {
                 submission=?self.submission_outcome,
                 "Message successfully processed"
             );
+            self.notify_status(
+                MessageStatusEvent::Delivered,
+                self.submission_outcome
+                    .as_ref()
+                    .map(|outcome| outcome.transaction_id),
+                None,
+            );
             PendingOperationResult::Success
         } else {
+            match self
+                .ctx
+                .destination_mailbox
+                .awaiting_execution(self.message.id())
+                .await
+            {
+                Ok(true) => return self.execute_enqueued().await,
+                Ok(false) => {}
+                Err(err) => {
+                    return self.on_reconfirm(
+                        Some(err),
+                        "Error checking whether message awaits execution",
+                    );
+                }
+            }
             let span = info_span!(
                 "Error: Transaction attempting to process message either reverted or was reorged",
                 tx_outcome=?self.submission_outcome,
@@ -489,6 +822,14 @@ impl PendingOperation for PendingMessage {
         {
             error!(error=?e, "Error when recording tx outcome");
         }
+        if let Some(app_context) = &self.app_context {
+            if let Err(e) = self.record_app_context_spend(app_context, &operation_outcome) {
+                error!(error=?e, app_context, "Error when recording app context spend");
+            }
+        }
+        if let Some(scheduler) = &self.ctx.funding_scheduler {
+            scheduler.record_spend(self.message.destination, &operation_outcome);
+        }
         // set the outcome in `Self` as well, for later logging
         self.set_submission_outcome(operation_outcome);
         debug!(
@@ -512,6 +853,10 @@ impl PendingOperation for PendingMessage {
         self.reset_attempts();
     }
 
+    fn retry_count(&self) -> u32 {
+        self.num_retries
+    }
+
     fn set_retries(&mut self, retries: u32) {
         self.set_retries(retries);
     }
@@ -540,19 +885,52 @@ impl PendingMessage {
         max_retries: u32,
     ) -> Option<Self> {
         let num_retries = Self::get_retries_or_skip(ctx.origin_db.clone(), &message, max_retries)?;
+        let expires_at = match &ctx.message_expiry {
+            Some(expiry) if expiry.is_dead_lettered(&*ctx.origin_db, &message) => {
+                trace!(message_id = ?message.id(), "Message expired, skipping");
+                return None;
+            }
+            Some(expiry) => expiry.expires_at(&*ctx.origin_db, &message),
+            None => None,
+        };
         let message_status = Self::get_message_status(ctx.origin_db.clone(), &message);
         let mut pending_message = Self::new(message, ctx, message_status, app_context, max_retries);
+        pending_message.expires_at = expires_at;
         if num_retries > 0 {
-            let next_attempt_after = Self::next_attempt_after(num_retries, max_retries);
+            // What the last attempt failed on isn't persisted
+            let next_attempt_after = Self::next_attempt_after(
+                &pending_message.ctx.backoff_policy,
+                num_retries,
+                max_retries,
+            );
             pending_message.num_retries = num_retries;
             pending_message.next_attempt_after = next_attempt_after;
         }
         Some(pending_message)
     }
 
-    fn next_attempt_after(num_retries: u32, max_retries: u32) -> Option<Instant> {
-        PendingMessage::calculate_msg_backoff(num_retries, max_retries, None)
-            .map(|dur| Instant::now() + dur)
+    /// Adds the destination tokens spent on delivering the message to the
+    /// spend of its app context
+    fn record_app_context_spend(&self, app_context: &str, outcome: &TxOutcome) -> Result<()> {
+        self.ctx
+            .app_context_spend_tracker
+            .record_spend(app_context, tokens_used(outcome)?)?;
+        Ok(())
+    }
+
+    fn next_attempt_after(
+        policy: &BackoffPolicy,
+        num_retries: u32,
+        max_retries: u32,
+    ) -> Option<Instant> {
+        PendingMessage::calculate_msg_backoff(
+            policy,
+            FailureClass::Other,
+            num_retries,
+            max_retries,
+            None,
+        )
+        .map(|dur| Instant::now() + dur)
     }
 
     fn get_retries_or_skip(
@@ -613,31 +991,250 @@ impl PendingMessage {
         PendingOperationStatus::FirstPrepareAttempt
     }
 
+    /// Moves the message to the dead-letter state once it expired. Messages
+    /// that don't match any expiry policy never expire.
+    fn check_expiry(&self) -> Option<PendingOperationResult> {
+        let expiry = self.ctx.message_expiry.as_ref()?;
+        let expires_at = self.expires_at?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_secs())
+            .unwrap_or_default();
+        if now < expires_at {
+            return None;
+        }
+        warn!(
+            expires_at,
+            num_retries = self.num_retries,
+            "Message expired before it was delivered, dropping it"
+        );
+        expiry.dead_letter(
+            &*self.ctx.origin_db,
+            self.ctx.destination_mailbox.domain(),
+            &self.message,
+        );
+        self.notify_status(MessageStatusEvent::Dropped, None, Some("message expired"));
+        Some(PendingOperationResult::Drop)
+    }
+
+    /// Refuses to submit the message if the destination mailbox reports a
+    /// different domain than the message destination, which means the
+    /// destination's RPC or addresses point at the wrong network.
+    async fn check_destination_domain(&mut self) -> Option<PendingOperationResult> {
+        let mailbox_domain = match self
+            .ctx
+            .destination_domain_cache
+            .local_domain(&*self.ctx.destination_mailbox)
+            .await
+        {
+            Ok(mailbox_domain) => mailbox_domain,
+            Err(err) => {
+                return Some(
+                    self.on_reprepare(Some(err), ReprepareReason::ErrorFetchingDestinationDomain),
+                );
+            }
+        };
+        if mailbox_domain == self.message.destination {
+            return None;
+        }
+        error!(
+            destination = self.message.destination,
+            mailbox_domain,
+            "Destination mailbox is on a different domain than the message destination, refusing to submit"
+        );
+        Some(self.on_reprepare::<String>(None, ReprepareReason::DestinationDomainMismatch))
+    }
+
     fn on_reprepare<E: Debug>(
         &mut self,
         err: Option<E>,
         reason: ReprepareReason,
     ) -> PendingOperationResult {
-        self.inc_attempts();
+        self.inc_attempts(FailureClass::of(&reason));
         self.submitted = false;
-        if let Some(e) = err {
-            warn!(error = ?e, "Repreparing message: {}", reason.clone());
-        } else {
-            warn!("Repreparing message: {}", reason.clone());
+        let level = self.retry_log_level(&reason.to_string());
+        match err {
+            Some(e) if level == Level::WARN => {
+                warn!(error = ?e, "Repreparing message: {}", reason.clone())
+            }
+            Some(e) => debug!(error = ?e, "Repreparing message: {}", reason.clone()),
+            None if level == Level::WARN => warn!("Repreparing message: {}", reason.clone()),
+            None => debug!("Repreparing message: {}", reason.clone()),
         }
         PendingOperationResult::Reprepare(reason)
     }
 
+    /// Reprepares the message after simulating its delivery failed. With
+    /// pre-flight simulation enabled, the failure is classified so that
+    /// messages running out of gas are retried less often, and ISM and
+    /// recipient failures are reported as such.
+    async fn on_simulation_failure(
+        &mut self,
+        err: ChainCommunicationError,
+    ) -> PendingOperationResult {
+        let failure = match &self.ctx.preflight_simulation {
+            Some(preflight_simulation) => preflight_simulation.classify(&err),
+            None => SimulationFailure::Other,
+        };
+        let reason = failure.reprepare_reason();
+        let reason = match failure {
+            // Applications may explain why their recipient reverted
+            SimulationFailure::RecipientReverted | SimulationFailure::Other => {
+                self.clarify_reason(reason.clone()).await.unwrap_or(reason)
+            }
+            SimulationFailure::IsmVerificationFailed | SimulationFailure::OutOfGas => reason,
+        };
+        let result = self.on_reprepare(Some(err), reason);
+        if failure == SimulationFailure::OutOfGas {
+            let min_next_attempt = Instant::now() + OUT_OF_GAS_DELAY;
+            self.next_attempt_after = Some(
+                self.next_attempt_after
+                    .map_or(min_next_attempt, |next| next.max(min_next_attempt)),
+            );
+        }
+        result
+    }
+
+    /// Submits the pre-transactions of the message that aren't confirmed yet,
+    /// in order. Returns `Some` if one of them failed, in which case the
+    /// message is retried when the pre-transaction is due again. Only once
+    /// the retry policy of the pre-transaction gives up does the failure
+    /// count as a failed attempt of the message.
+    async fn run_pre_transactions(&mut self) -> Option<PendingOperationResult> {
+        let hooks = self.ctx.pre_transaction_hooks.clone()?;
+        let mut pre_transactions = self
+            .pre_transactions
+            .take()
+            .unwrap_or_else(|| hooks.for_message(&self.message));
+        let mut result = None;
+        for pre_transaction in pre_transactions.iter_mut() {
+            let Err(err) = pre_transaction
+                .run(&*self.ctx.destination_mailbox, &self.message)
+                .await
+            else {
+                continue;
+            };
+            let reason = ReprepareReason::PreTransactionFailed;
+            result = Some(match pre_transaction.on_failure() {
+                Some(delay) => {
+                    if self.retry_log_level(&reason.to_string()) == Level::WARN {
+                        warn!(
+                            error = ?err,
+                            pre_transaction = %pre_transaction.pre_transaction(),
+                            ?delay,
+                            "Pre-transaction failed, retrying"
+                        );
+                    } else {
+                        debug!(
+                            error = ?err,
+                            pre_transaction = %pre_transaction.pre_transaction(),
+                            ?delay,
+                            "Pre-transaction failed, retrying"
+                        );
+                    }
+                    self.set_next_attempt_after(delay);
+                    PendingOperationResult::Reprepare(reason)
+                }
+                None => self.on_reprepare(Some(err), reason),
+            });
+            break;
+        }
+        self.pre_transactions = Some(pre_transactions);
+        result
+    }
+
     fn on_reconfirm<E: Debug>(&mut self, err: Option<E>, reason: &str) -> PendingOperationResult {
-        self.inc_attempts();
-        if let Some(e) = err {
-            warn!(error = ?e, id = ?self.id(), "Reconfirming message: {}", reason);
-        } else {
-            warn!(id = ?self.id(), "Reconfirming message: {}", reason);
+        self.inc_attempts(FailureClass::Other);
+        let level = self.retry_log_level(reason);
+        match err {
+            Some(e) if level == Level::WARN => {
+                warn!(error = ?e, id = ?self.id(), "Reconfirming message: {}", reason)
+            }
+            Some(e) => debug!(error = ?e, id = ?self.id(), "Reconfirming message: {}", reason),
+            None if level == Level::WARN => {
+                warn!(id = ?self.id(), "Reconfirming message: {}", reason)
+            }
+            None => debug!(id = ?self.id(), "Reconfirming message: {}", reason),
         }
         PendingOperationResult::NotReady
     }
 
+    /// Repeated retry warnings are demoted to debug if log deduplication is
+    /// enabled. Only `WARN` and `DEBUG` are returned, since the callers pick
+    /// the macro to log with, which needs a constant level.
+    fn retry_log_level(&self, code: &str) -> Level {
+        match &self.ctx.log_deduplicator {
+            Some(dedup) if !dedup.observe(self.message.id(), code) => Level::DEBUG,
+            _ => Level::WARN,
+        }
+    }
+
+    fn notify_status(
+        &self,
+        event: MessageStatusEvent,
+        tx_hash: Option<H512>,
+        reason: Option<&str>,
+    ) {
+        if let Some(notifier) = &self.ctx.message_status_notifier {
+            notifier.notify(MessageStatusNotification::new(
+                event,
+                &self.message,
+                tx_hash,
+                reason.map(str::to_owned),
+            ));
+        }
+    }
+
+    /// Notifies that the gas payment is too low, unless it was already
+    /// notified since the message was last prepared
+    fn notify_gas_underpaid(&mut self) {
+        if !self.gas_underpaid_notified {
+            self.gas_underpaid_notified = true;
+            self.notify_status(MessageStatusEvent::GasUnderpaid, None, None);
+        }
+    }
+
+    /// Executes the message after a two-phase mailbox enqueued it. The next
+    /// confirmation checks whether it was delivered by the execution.
+    async fn execute_enqueued(&mut self) -> PendingOperationResult {
+        if !self.enqueued {
+            self.enqueued = true;
+            self.ctx.metrics.enqueued.inc();
+            info!(
+                submission=?self.submission_outcome,
+                "Message enqueued, awaiting execution"
+            );
+        }
+        let outcome = match self
+            .ctx
+            .destination_mailbox
+            .execute(&self.message, None)
+            .await
+        {
+            Ok(outcome) => outcome,
+            Err(err) => return self.on_reconfirm(Some(err), "Error executing enqueued message"),
+        };
+        // The relayer pays for the execution too, so it's charged to the
+        // sender's gas payment like the delivery
+        if let Err(e) = self
+            .ctx
+            .origin_gas_payment_enforcer
+            .record_tx_outcome(&self.message, outcome.clone())
+        {
+            error!(error=?e, "Error when recording execution tx outcome");
+        }
+        if !outcome.executed {
+            return self.on_reconfirm(
+                Some(outcome.transaction_id),
+                "Execution of enqueued message reverted",
+            );
+        }
+        info!(tx_id=?outcome.transaction_id, "Executed enqueued message");
+        self.ctx.metrics.executed.inc();
+        self.set_next_attempt_after(CONFIRM_DELAY);
+        PendingOperationResult::Confirm(ConfirmReason::ExecutionSubmitted)
+    }
+
     fn is_ready(&self) -> bool {
         self.next_attempt_after
             .map(|a| Instant::now() >= a)
@@ -657,18 +1254,92 @@ impl PendingMessage {
             .store_processed_by_nonce(&self.message.nonce, &true)?;
         self.ctx.metrics.update_nonce(&self.message);
         self.ctx.metrics.messages_processed.inc();
+        if let Some(outcome) = &self.submission_outcome {
+            self.ctx
+                .metrics
+                .gas_used
+                .inc_by(outcome.gas_used.try_into().unwrap_or(u64::MAX));
+        }
         Ok(())
     }
 
     fn reset_attempts(&mut self) {
         self.next_attempt_after = None;
         self.last_attempted_at = Instant::now();
+        self.awaited_gas_payment = None;
+    }
+
+    /// Schedules checking for new gas payments well before the message is
+    /// retried, after it didn't meet the gas payment requirement
+    fn await_gas_payment(&mut self, num_payments: Result<u32>) {
+        let num_payments = match num_payments {
+            Ok(num_payments) => num_payments,
+            Err(err) => {
+                // The message is just retried with the regular backoff
+                warn!(?err, "Error counting gas payments for message");
+                return;
+            }
+        };
+        let awaited = AwaitedGasPayment {
+            num_payments,
+            retry_after: self.next_attempt_after,
+        };
+        self.awaited_gas_payment = Some(awaited);
+        self.schedule_gas_payment_recheck(awaited);
+    }
+
+    /// Returns `Some` if the message is still waiting for a gas payment, i.e.
+    /// no payment was made since the gas payment requirement was last
+    /// evaluated and the message isn't due to be retried yet.
+    fn check_new_gas_payment(&mut self) -> Option<PendingOperationResult> {
+        let awaited = self.awaited_gas_payment.take()?;
+        if awaited
+            .retry_after
+            .map_or(true, |retry_after| Instant::now() >= retry_after)
+        {
+            return None;
+        }
+        match self
+            .ctx
+            .origin_gas_payment_enforcer
+            .payment_count(&self.message)
+        {
+            Ok(num_payments) if num_payments == awaited.num_payments => {
+                trace!(num_payments, "No new gas payment for message");
+                self.awaited_gas_payment = Some(awaited);
+                self.schedule_gas_payment_recheck(awaited);
+                Some(PendingOperationResult::NotReady)
+            }
+            Ok(num_payments) => {
+                debug!(
+                    num_payments,
+                    previous_num_payments = awaited.num_payments,
+                    "New gas payment for message, evaluating gas payment requirement again"
+                );
+                None
+            }
+            Err(err) => {
+                warn!(?err, "Error counting gas payments for message");
+                None
+            }
+        }
+    }
+
+    fn schedule_gas_payment_recheck(&mut self, awaited: AwaitedGasPayment) {
+        let recheck_at = Instant::now() + GAS_PAYMENT_RECHECK_DELAY;
+        self.next_attempt_after = Some(
+            awaited
+                .retry_after
+                .map_or(recheck_at, |retry_after| retry_after.min(recheck_at)),
+        );
     }
 
-    fn inc_attempts(&mut self) {
+    fn inc_attempts(&mut self, class: FailureClass) {
         self.set_retries(self.num_retries + 1);
         self.last_attempted_at = Instant::now();
         self.next_attempt_after = PendingMessage::calculate_msg_backoff(
+            &self.ctx.backoff_policy,
+            class,
             self.num_retries,
             self.max_retries,
             Some(self.message.id()),
@@ -692,48 +1363,34 @@ impl PendingMessage {
     }
 
     /// Get duration we should wait before re-attempting to deliver a message
-    /// given the number of retries.
+    /// given the number of retries and what the last attempt failed on.
     /// `pub(crate)` for testing purposes
     pub(crate) fn calculate_msg_backoff(
+        policy: &BackoffPolicy,
+        class: FailureClass,
         num_retries: u32,
         max_retries: u32,
         message_id: Option<H256>,
     ) -> Option<Duration> {
-        Some(Duration::from_secs(match num_retries {
-            i if i < 1 => return None,
-            i if (1..10).contains(&i) => 10,
-            i if (10..15).contains(&i) => 90,
-            i if (15..25).contains(&i) => 60 * 2,
-            // linearly increase from 2min to ~25min, adding 1.5min for each additional attempt
-            i if (25..40).contains(&i) => (i as u64 - 23) * 90,
-            // wait 30min for the next 5 attempts
-            i if (40..45).contains(&i) => 60 * 30,
-            // wait 60min for the next 5 attempts
-            i if (45..50).contains(&i) => 60 * 60,
-            // linearly increase the backoff time, adding 1h for each additional attempt
-            i if (50..max_retries).contains(&i) => {
-                let hour: u64 = 60 * 60;
-                let two_hours: u64 = hour * 2;
-                // To be extra safe, `max` to make sure it's at least 2 hours.
-                let target = two_hours.max((num_retries - 49) as u64 * two_hours);
-                // Schedule it at some random point in the next 6 hours to
-                // avoid scheduling messages with the same # of retries
-                // at the exact same time and starve new messages.
-                target + (rand::random::<u64>() % (6 * hour))
-            }
-            // after `max_message_retries`, the message is considered undeliverable
-            // and the backoff is set as far into the future as possible
-            _ => {
-                if let Some(message_id) = message_id {
-                    warn!(
-                        message_id = ?message_id,
-                        ?max_retries,
-                        "Message has been retried too many times, skipping",
-                    );
-                }
-                chrono::Duration::weeks(10).num_seconds() as u64
-            }
-        }))
+        if num_retries < 1 {
+            return None;
+        }
+        // after `max_message_retries`, the message is considered undeliverable
+        // and the backoff is set as far into the future as possible
+        Some(
+            policy
+                .delay(class, num_retries, max_retries)
+                .unwrap_or_else(|| {
+                    if let Some(message_id) = message_id {
+                        warn!(
+                            message_id = ?message_id,
+                            ?max_retries,
+                            "Message has been retried too many times, skipping",
+                        );
+                    }
+                    Duration::from_secs(chrono::Duration::weeks(10).num_seconds() as u64)
+                }),
+        )
     }
 
     async fn clarify_reason(&self, reason: ReprepareReason) -> Option<ReprepareReason> {
@@ -755,11 +1412,21 @@ impl PendingMessage {
     }
 }
 
+/// The destination tokens spent by a transaction
+fn tokens_used(outcome: &TxOutcome) -> Result<U256> {
+    let tokens_used =
+        (FixedPointNumber::try_from(outcome.gas_used)? * outcome.gas_price.clone()).try_into()?;
+    Ok(tokens_used)
+}
+
 #[derive(Debug)]
 pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
     pub last_known_nonce: IntGauge,
-    pub messages_processed: IntCounter,
+    pub messages_processed: PersistentIntCounter,
+    pub gas_used: PersistentIntCounter,
+    pub enqueued: IntCounter,
+    pub executed: IntCounter,
 }
 
 impl MessageSubmissionMetrics {
@@ -767,18 +1434,45 @@ impl MessageSubmissionMetrics {
         metrics: &CoreMetrics,
         origin: &HyperlaneDomain,
         destination: &HyperlaneDomain,
+        persistent_metrics_db: Option<DB>,
     ) -> Self {
         let origin = origin.name();
         let destination = destination.name();
+        let labels = [origin, destination];
         Self {
             last_known_nonce: metrics.last_known_message_nonce().with_label_values(&[
                 "message_processed",
                 origin,
                 destination,
             ]),
-            messages_processed: metrics
-                .messages_processed_count()
-                .with_label_values(&[origin, destination]),
+            messages_processed: PersistentIntCounter::new(
+                metrics
+                    .messages_processed_count()
+                    .with_label_values(&labels),
+                persistent_metrics_db.clone(),
+                metrics.agent_name(),
+                "messages_processed_count",
+                &labels,
+            ),
+            gas_used: PersistentIntCounter::new(
+                metrics
+                    .messages_processed_gas_used()
+                    .with_label_values(&labels),
+                persistent_metrics_db,
+                metrics.agent_name(),
+                "messages_processed_gas_used",
+                &labels,
+            ),
+            enqueued: metrics.message_delivery_phases().with_label_values(&[
+                origin,
+                destination,
+                "enqueued",
+            ]),
+            executed: metrics.message_delivery_phases().with_label_values(&[
+                origin,
+                destination,
+                "executed",
+            ]),
         }
     }
 
@@ -800,9 +1494,12 @@ mod test {
     };
 
     use hyperlane_base::db::*;
-    use hyperlane_core::*;
+    use hyperlane_core::{accumulator::incremental::IncrementalMerkle, *};
 
-    use crate::msg::pending_message::DEFAULT_MAX_MESSAGE_RETRIES;
+    use crate::msg::{
+        backoff::{BackoffPolicy, FailureClass},
+        pending_message::DEFAULT_MAX_MESSAGE_RETRIES,
+    };
 
     use super::PendingMessage;
 
@@ -891,6 +1588,24 @@ mod test {
                 &self,
                 message_id: &H256,
             ) -> DbResult<Option<u32>>;
+            fn store_message_first_seen_at_by_message_id(
+                &self,
+                message_id: &H256,
+                timestamp: &u64,
+            ) -> DbResult<()>;
+            fn retrieve_message_first_seen_at_by_message_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<u64>>;
+            fn store_message_expired_at_by_message_id(
+                &self,
+                message_id: &H256,
+                timestamp: &u64,
+            ) -> DbResult<()>;
+            fn retrieve_message_expired_at_by_message_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<u64>>;
             fn store_merkle_tree_insertion_by_leaf_index(
                 &self,
                 leaf_index: &u32,
@@ -917,6 +1632,10 @@ mod test {
             ) -> DbResult<Option<u64>>;
             fn store_highest_seen_message_nonce_number(&self, nonce: &u32) -> DbResult<()>;
             fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+            fn store_merkle_tree_snapshot(&self, tree: &IncrementalMerkle) -> DbResult<()>;
+            fn retrieve_merkle_tree_snapshot(&self) -> DbResult<Option<IncrementalMerkle>>;
+            fn store_spend_in_period(&self, spender: &str, period: u64, spend: &U256) -> DbResult<()>;
+            fn retrieve_spend_in_period(&self, spender: &str, period: u64) -> DbResult<Option<U256>>;
 
         }
     }
@@ -935,6 +1654,7 @@ mod test {
 
         // this is really an overflow check
         let next_prepare_attempt = PendingMessage::next_attempt_after(
+            &BackoffPolicy::default(),
             DEFAULT_MAX_MESSAGE_RETRIES,
             DEFAULT_MAX_MESSAGE_RETRIES,
         )
@@ -981,8 +1701,14 @@ mod test {
 
         // Intentionally only up to 50 because after that we add some randomness that'll cause this test to flake
         for i in 0..=50 {
-            let backoff_duration = PendingMessage::calculate_msg_backoff(i, u32::MAX, None)
-                .unwrap_or(Duration::from_secs(0));
+            let backoff_duration = PendingMessage::calculate_msg_backoff(
+                &BackoffPolicy::default(),
+                FailureClass::Other,
+                i,
+                u32::MAX,
+                None,
+            )
+            .unwrap_or(Duration::from_secs(0));
             // Uncomment to show the impact of changes to the backoff duration:
 
             // println!(
@@ -0,0 +1,360 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use hyperlane_base::{today, Retrier, RetryBackoff, RetryMetrics};
+use hyperlane_core::{
+    rpc_clients::RetryPolicy, ChainCommunicationError, ChainResult, HyperlaneMessage, Mailbox,
+    PreTransaction,
+};
+use tracing::{debug, info};
+
+use crate::settings::PreTransactionConf;
+
+/// Failed attempts of a pre-transaction are retried on their own schedule,
+/// without counting as failed attempts of the message. Once the policy gives
+/// up, the message is retried with its regular backoff, after which the
+/// pre-transaction starts over.
+const PRE_TRANSACTION_RETRY_POLICY: RetryPolicy =
+    RetryPolicy::exponential(Duration::from_secs(10), Duration::from_secs(60 * 10), 8);
+
+/// The pre-transactions configured for the messages of the relayer, i.e. the
+/// transactions that are submitted and confirmed on the destination before a
+/// message matching their config is processed.
+#[derive(Debug)]
+pub struct PreTransactionHooks {
+    confs: Vec<PreTransactionConf>,
+    /// The daily limit of each config, if it has one
+    limits: Vec<Option<Arc<DailyLimit>>>,
+    metrics: RetryMetrics,
+}
+
+impl PreTransactionHooks {
+    pub fn new(confs: Vec<PreTransactionConf>, metrics: RetryMetrics) -> Self {
+        let limits = confs
+            .iter()
+            .map(|conf| conf.max_per_day.map(|max| Arc::new(DailyLimit::new(max))))
+            .collect();
+        Self {
+            confs,
+            limits,
+            metrics,
+        }
+    }
+
+    /// The pre-transactions required by `message`, in the order they're
+    /// submitted
+    pub fn for_message(&self, message: &HyperlaneMessage) -> Vec<PendingPreTransaction> {
+        self.confs
+            .iter()
+            .zip(&self.limits)
+            .filter(|(conf, _)| conf.matching_list.msg_matches(message, true))
+            .flat_map(|(conf, limit)| conf.transactions.iter().map(move |tx| (tx, limit)))
+            .map(|(pre_transaction, limit)| {
+                let retrier = Retrier::with_metrics(
+                    PRE_TRANSACTION_RETRY_POLICY,
+                    &format!("relayer_pre_transaction_{}", pre_transaction.kind()),
+                    self.metrics.clone(),
+                );
+                PendingPreTransaction::new(pre_transaction.clone(), retrier, limit.clone())
+            })
+            .collect()
+    }
+}
+
+/// Caps how many pre-transactions of a config are submitted per UTC day,
+/// since the relayer pays for them, including the rent of the accounts they
+/// create, without the gas payments of the messages covering it. The count
+/// is kept in memory, so a restart resets it.
+#[derive(Debug)]
+struct DailyLimit {
+    max: u32,
+    /// The day the count is for, and how many were submitted on it
+    submitted: Mutex<(u64, u32)>,
+}
+
+impl DailyLimit {
+    fn new(max: u32) -> Self {
+        Self {
+            max,
+            submitted: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Takes one of the submissions left on `day`, or returns false if there
+    /// are none. Submissions count whether they land or not.
+    fn try_take_on_day(&self, day: u64) -> bool {
+        let mut submitted = self
+            .submitted
+            .lock()
+            .expect("pre-transaction limit lock poisoned");
+        if submitted.0 != day {
+            *submitted = (day, 0);
+        }
+        if submitted.1 >= self.max {
+            return false;
+        }
+        submitted.1 += 1;
+        true
+    }
+}
+
+/// A pre-transaction of a pending message, tracked as a sub-operation of the
+/// message with its own retries
+#[derive(Debug, Clone)]
+pub struct PendingPreTransaction {
+    pre_transaction: PreTransaction,
+    retrier: Retrier,
+    backoff: RetryBackoff,
+    /// Shared by the pre-transactions of the same config
+    limit: Option<Arc<DailyLimit>>,
+    confirmed: bool,
+}
+
+impl PendingPreTransaction {
+    fn new(
+        pre_transaction: PreTransaction,
+        retrier: Retrier,
+        limit: Option<Arc<DailyLimit>>,
+    ) -> Self {
+        Self {
+            pre_transaction,
+            backoff: retrier.backoff(),
+            retrier,
+            limit,
+            confirmed: false,
+        }
+    }
+
+    pub fn pre_transaction(&self) -> &PreTransaction {
+        &self.pre_transaction
+    }
+
+    /// Whether the pre-transaction landed, or turned out not to be required
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed
+    }
+
+    /// Submits the pre-transaction on the destination unless it isn't
+    /// required, e.g. because the account it creates already exists, and
+    /// waits for it to be confirmed
+    pub async fn run(
+        &mut self,
+        mailbox: &dyn Mailbox,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<()> {
+        if self.confirmed {
+            return Ok(());
+        }
+        self.submit_if_required(mailbox, message).await?;
+        self.backoff.succeeded();
+        self.confirmed = true;
+        Ok(())
+    }
+
+    async fn submit_if_required(
+        &self,
+        mailbox: &dyn Mailbox,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<()> {
+        if !mailbox
+            .pre_transaction_required(message, &self.pre_transaction)
+            .await?
+        {
+            debug!(pre_transaction = %self.pre_transaction, "Pre-transaction isn't required");
+            return Ok(());
+        }
+        if let Some(limit) = &self.limit {
+            if !limit.try_take_on_day(today()) {
+                return Err(ChainCommunicationError::CustomError(format!(
+                    "Daily limit of {} submissions reached for pre-transaction {}",
+                    limit.max, self.pre_transaction
+                )));
+            }
+        }
+        let outcome = mailbox
+            .submit_pre_transaction(message, &self.pre_transaction)
+            .await?;
+        if !outcome.executed {
+            return Err(ChainCommunicationError::CustomError(format!(
+                "Pre-transaction {} reverted in transaction {:?}",
+                self.pre_transaction, outcome.transaction_id
+            )));
+        }
+        info!(
+            pre_transaction = %self.pre_transaction,
+            tx_id = ?outcome.transaction_id,
+            "Pre-transaction confirmed"
+        );
+        Ok(())
+    }
+
+    /// Records a failed attempt, returning how long to wait before the next
+    /// one, or None if its retry policy gave up. In that case, the next
+    /// attempt starts a new round of retries.
+    pub fn on_failure(&mut self) -> Option<Duration> {
+        let delay = self.backoff.failed();
+        if delay.is_none() {
+            self.backoff = self.retrier.backoff();
+        }
+        delay
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{TxOutcome, H256, H512, U256};
+    use hyperlane_test::mocks::MockMailboxContract;
+    use prometheus::{opts, IntCounterVec};
+
+    use super::*;
+    use crate::settings::matching_list::MatchingList;
+
+    const DESTINATION: u32 = 2;
+
+    fn retry_metrics() -> RetryMetrics {
+        RetryMetrics {
+            attempts: IntCounterVec::new(opts!("attempts", "attempts"), &["call_site", "outcome"])
+                .unwrap(),
+            exhausted: IntCounterVec::new(opts!("exhausted", "exhausted"), &["call_site"]).unwrap(),
+        }
+    }
+
+    fn create_ata() -> PreTransaction {
+        PreTransaction::CreateAssociatedTokenAccount {
+            mint: H256::repeat_byte(1),
+        }
+    }
+
+    fn hooks() -> PreTransactionHooks {
+        hooks_with_limit(None)
+    }
+
+    fn hooks_with_limit(max_per_day: Option<u32>) -> PreTransactionHooks {
+        let matching_list: MatchingList = serde_json::from_value(serde_json::json!([{
+            "destinationdomain": DESTINATION
+        }]))
+        .unwrap();
+        PreTransactionHooks::new(
+            vec![PreTransactionConf {
+                matching_list,
+                transactions: vec![create_ata()],
+                max_per_day,
+            }],
+            retry_metrics(),
+        )
+    }
+
+    fn outcome(executed: bool) -> TxOutcome {
+        TxOutcome {
+            transaction_id: H512::zero(),
+            executed,
+            gas_used: U256::zero(),
+            gas_price: U256::zero().try_into().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_pre_transactions_of_matching_messages() {
+        let hooks = hooks();
+        let message = HyperlaneMessage {
+            destination: DESTINATION,
+            ..Default::default()
+        };
+        let pre_transactions = hooks.for_message(&message);
+        assert_eq!(pre_transactions.len(), 1);
+        assert_eq!(pre_transactions[0].pre_transaction(), &create_ata());
+
+        let other_message = HyperlaneMessage {
+            destination: DESTINATION + 1,
+            ..Default::default()
+        };
+        assert!(hooks.for_message(&other_message).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pre_transaction_is_submitted_once_when_required() {
+        let message = HyperlaneMessage {
+            destination: DESTINATION,
+            ..Default::default()
+        };
+        let mut pre_transaction = hooks().for_message(&message).remove(0);
+
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__pre_transaction_required()
+            .returning(|_, _| Ok(true));
+        let mut reverted = true;
+        mailbox
+            .expect__submit_pre_transaction()
+            .times(2)
+            .returning(move |_, _| {
+                let outcome = outcome(!reverted);
+                reverted = false;
+                Ok(outcome)
+            });
+
+        // A reverted pre-transaction is retried
+        assert!(pre_transaction.run(&mailbox, &message).await.is_err());
+        assert!(pre_transaction.on_failure().is_some());
+        assert!(!pre_transaction.is_confirmed());
+
+        pre_transaction.run(&mailbox, &message).await.unwrap();
+        assert!(pre_transaction.is_confirmed());
+        // Confirmed pre-transactions aren't submitted again
+        pre_transaction.run(&mailbox, &message).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pre_transaction_not_required_is_skipped() {
+        let message = HyperlaneMessage {
+            destination: DESTINATION,
+            ..Default::default()
+        };
+        let mut pre_transaction = hooks().for_message(&message).remove(0);
+
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__pre_transaction_required()
+            .returning(|_, _| Ok(false));
+        mailbox.expect__submit_pre_transaction().never();
+
+        pre_transaction.run(&mailbox, &message).await.unwrap();
+        assert!(pre_transaction.is_confirmed());
+    }
+
+    #[tokio::test]
+    async fn test_pre_transactions_over_the_daily_limit_are_not_submitted() {
+        let hooks = hooks_with_limit(Some(1));
+        let message = HyperlaneMessage {
+            destination: DESTINATION,
+            ..Default::default()
+        };
+        let mut first = hooks.for_message(&message).remove(0);
+        let mut second = hooks.for_message(&message).remove(0);
+
+        let mut mailbox = MockMailboxContract::new();
+        mailbox
+            .expect__pre_transaction_required()
+            .returning(|_, _| Ok(true));
+        mailbox
+            .expect__submit_pre_transaction()
+            .times(1)
+            .returning(|_, _| Ok(outcome(true)));
+
+        first.run(&mailbox, &message).await.unwrap();
+        // The limit is shared by the messages of the config
+        assert!(second.run(&mailbox, &message).await.is_err());
+        assert!(!second.is_confirmed());
+    }
+
+    #[test]
+    fn test_daily_limit_resets_every_day() {
+        let limit = DailyLimit::new(2);
+        assert!(limit.try_take_on_day(1));
+        assert!(limit.try_take_on_day(1));
+        assert!(!limit.try_take_on_day(1));
+        assert!(limit.try_take_on_day(2));
+    }
+}
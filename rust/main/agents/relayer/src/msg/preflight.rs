@@ -0,0 +1,151 @@
+use std::fmt::Debug;
+
+use hyperlane_core::ReprepareReason;
+use prometheus::IntCounterVec;
+
+/// Revert reasons of the mailbox rejecting the metadata, as they appear in the
+/// errors of gas estimations: the `require` message of `Mailbox.process`, also
+/// hex encoded.
+const ISM_VERIFICATION_FAILED_MARKERS: &[&str] = &[
+    "Mailbox: ISM verification failed",
+    "4d61696c626f783a2049534d20766572696669636174696f6e206661696c6564",
+];
+
+/// Errors of simulations that ran out of gas, or compute units on Sealevel.
+/// Matched case-insensitively.
+const OUT_OF_GAS_MARKERS: &[&str] = &[
+    "out of gas",
+    "outofgas",
+    "gas required exceeds allowance",
+    "computationalbudgetexceeded",
+];
+
+/// Reverts of the mailbox itself, e.g. because the message was already
+/// delivered, which aren't the recipient's doing
+const MAILBOX_REVERT_MARKER: &str = "Mailbox: ";
+
+/// Why simulating the delivery of a message failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationFailure {
+    /// The recipient's ISM rejected the metadata
+    IsmVerificationFailed,
+    /// The recipient reverted while handling the message
+    RecipientReverted,
+    /// The delivery ran out of gas
+    OutOfGas,
+    /// Anything else, e.g. an RPC error
+    Other,
+}
+
+impl SimulationFailure {
+    /// Classifies the error of a simulated delivery
+    pub fn classify(err: &impl Debug) -> Self {
+        let err = format!("{err:?}");
+        let lowercase = err.to_lowercase();
+        if ISM_VERIFICATION_FAILED_MARKERS
+            .iter()
+            .any(|marker| err.contains(marker))
+        {
+            Self::IsmVerificationFailed
+        } else if OUT_OF_GAS_MARKERS
+            .iter()
+            .any(|marker| lowercase.contains(marker))
+        {
+            Self::OutOfGas
+        } else if err.contains(MAILBOX_REVERT_MARKER) {
+            Self::Other
+        } else if lowercase.contains("revert") {
+            Self::RecipientReverted
+        } else {
+            Self::Other
+        }
+    }
+
+    /// The reason the message is reprepared for
+    pub fn reprepare_reason(&self) -> ReprepareReason {
+        match self {
+            Self::IsmVerificationFailed => ReprepareReason::IsmVerificationFailed,
+            Self::RecipientReverted => ReprepareReason::RecipientReverted,
+            Self::OutOfGas => ReprepareReason::SimulationOutOfGas,
+            Self::Other => ReprepareReason::ErrorEstimatingGas,
+        }
+    }
+
+    fn as_label(&self) -> &'static str {
+        match self {
+            Self::IsmVerificationFailed => "ism_verification_failed",
+            Self::RecipientReverted => "recipient_reverted",
+            Self::OutOfGas => "out_of_gas",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Classifies the failed delivery simulations of a destination, so that
+/// each class is counted and retried on its own terms instead of all being
+/// reprepared as a failed gas estimation.
+#[derive(Debug)]
+pub struct PreflightSimulation {
+    destination: String,
+    /// Failed simulations by destination and class
+    failures: IntCounterVec,
+}
+
+impl PreflightSimulation {
+    pub fn new(destination: &str, failures: IntCounterVec) -> Self {
+        Self {
+            destination: destination.to_owned(),
+            failures,
+        }
+    }
+
+    /// Classifies and counts the error of a simulated delivery
+    pub fn classify(&self, err: &impl Debug) -> SimulationFailure {
+        let failure = SimulationFailure::classify(err);
+        self.failures
+            .with_label_values(&[&self.destination, failure.as_label()])
+            .inc();
+        failure
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_simulation_failures_are_classified() {
+        assert_eq!(
+            SimulationFailure::classify(&"execution reverted: Mailbox: ISM verification failed"),
+            SimulationFailure::IsmVerificationFailed
+        );
+        assert_eq!(
+            SimulationFailure::classify(
+                &"Contract call reverted with data: 0x08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000204d61696c626f783a2049534d20766572696669636174696f6e206661696c6564"
+            ),
+            SimulationFailure::IsmVerificationFailed
+        );
+        assert_eq!(
+            SimulationFailure::classify(&"execution reverted: !collateral"),
+            SimulationFailure::RecipientReverted
+        );
+        assert_eq!(
+            SimulationFailure::classify(&"gas required exceeds allowance (30000000)"),
+            SimulationFailure::OutOfGas
+        );
+        assert_eq!(
+            SimulationFailure::classify(
+                &"Error in simulation result: Some(InstructionError(1, ComputationalBudgetExceeded))"
+            ),
+            SimulationFailure::OutOfGas
+        );
+        assert_eq!(
+            SimulationFailure::classify(&"execution reverted: Mailbox: already delivered"),
+            SimulationFailure::Other
+        );
+        assert_eq!(
+            SimulationFailure::classify(&"connection refused"),
+            SimulationFailure::Other
+        );
+    }
+}
@@ -14,24 +14,24 @@ use hyperlane_base::{
     db::{HyperlaneDb, HyperlaneRocksDB},
     CoreMetrics,
 };
-use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation};
+use hyperlane_core::{HyperlaneChain, HyperlaneDomain, HyperlaneMessage, QueueOperation};
 use prometheus::IntGauge;
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, instrument, trace};
 
-use super::{blacklist::AddressBlacklist, metadata::AppContextClassifier, pending_message::*};
+use super::{
+    metadata::AppContextClassifier, pending_message::*, route_matrix::RouteMatrix,
+    runtime_config::RuntimeConfig, unknown_destination::UnknownDestinationTracker,
+};
 use crate::{processor::ProcessorExt, settings::matching_list::MatchingList};
 
 /// Finds unprocessed messages from an origin and submits then through a channel
 /// for to the appropriate destination.
 #[allow(clippy::too_many_arguments)]
 pub struct MessageProcessor {
-    /// A matching list of messages that should be whitelisted.
-    message_whitelist: Arc<MatchingList>,
-    /// A matching list of messages that should be blacklisted.
-    message_blacklist: Arc<MatchingList>,
-    /// Addresses that messages may not interact with.
-    address_blacklist: Arc<AddressBlacklist>,
+    /// Whitelist and blacklists of messages and addresses, which may be
+    /// changed at runtime.
+    runtime_config: Arc<RuntimeConfig>,
     metrics: MessageProcessorMetrics,
     /// channel for each destination chain to send operations (i.e. message
     /// submissions) to
@@ -40,7 +40,13 @@ pub struct MessageProcessor {
     destination_ctxs: HashMap<u32, Arc<MessageContext>>,
     metric_app_contexts: Vec<(MatchingList, String)>,
     nonce_iterator: ForwardBackwardIterator,
+    origin: HyperlaneDomain,
+    db: Arc<dyn HyperlaneDb>,
     max_retries: u32,
+    /// Handles messages to destinations without a send channel
+    unknown_destinations: Arc<UnknownDestinationTracker>,
+    /// Routes that messages are delivered on
+    route_matrix: Arc<RouteMatrix>,
 }
 
 #[derive(Debug)]
@@ -231,8 +237,8 @@ impl Debug for MessageProcessor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "MessageProcessor {{ message_whitelist: {:?}, message_blacklist: {:?}, address_blacklist: {:?}, nonce_iterator: {:?}}}",
-            self.message_whitelist, self.message_blacklist, self.address_blacklist, self.nonce_iterator
+            "MessageProcessor {{ runtime_config: {:?}, nonce_iterator: {:?}}}",
+            self.runtime_config, self.nonce_iterator
         )
     }
 }
@@ -241,7 +247,7 @@ impl Debug for MessageProcessor {
 impl ProcessorExt for MessageProcessor {
     /// The domain this processor is getting messages from.
     fn domain(&self) -> &HyperlaneDomain {
-        self.nonce_iterator.high_nonce_iter.db.domain()
+        &self.origin
     }
 
     /// One round of processing, extracted from infinite work loop for
@@ -261,19 +267,24 @@ impl ProcessorExt for MessageProcessor {
             let destination = msg.destination;
 
             // Skip if not whitelisted.
-            if !self.message_whitelist.msg_matches(&msg, true) {
-                debug!(?msg, whitelist=?self.message_whitelist, "Message not whitelisted, skipping");
+            let message_whitelist = self.runtime_config.message_whitelist();
+            if !message_whitelist.msg_matches(&msg, true) {
+                debug!(?msg, whitelist=?message_whitelist, "Message not whitelisted, skipping");
                 return Ok(());
             }
 
             // Skip if the message is blacklisted
-            if self.message_blacklist.msg_matches(&msg, false) {
-                debug!(?msg, blacklist=?self.message_blacklist, "Message blacklisted, skipping");
+            let message_blacklist = self.runtime_config.message_blacklist();
+            if message_blacklist.msg_matches(&msg, false) {
+                debug!(?msg, blacklist=?message_blacklist, "Message blacklisted, skipping");
                 return Ok(());
             }
 
             // Skip if the message involves a blacklisted address
-            if let Some(blacklisted_address) = self.address_blacklist.find_blacklisted_address(&msg)
+            if let Some(blacklisted_address) = self
+                .runtime_config
+                .address_blacklist()
+                .find_blacklisted_address(&msg)
             {
                 debug!(
                     ?msg,
@@ -285,7 +296,18 @@ impl ProcessorExt for MessageProcessor {
 
             // Skip if the message is intended for a destination we do not service
             if !self.send_channels.contains_key(&destination) {
-                debug!(?msg, "Message destined for unknown domain, skipping");
+                self.unknown_destinations.record(&*self.db, &msg);
+                return Ok(());
+            }
+
+            // Skip if the route of the message is disabled
+            let destination_ctx = &self.destination_ctxs[&destination];
+            if !self.route_matrix.is_enabled(self.origin.id(), destination) {
+                self.route_matrix.record_skipped(
+                    &self.origin,
+                    destination_ctx.destination_mailbox.domain(),
+                    &msg,
+                );
                 return Ok(());
             }
 
@@ -298,7 +320,7 @@ impl ProcessorExt for MessageProcessor {
             // Finally, build the submit arg and dispatch it to the submitter.
             let pending_msg = PendingMessage::maybe_from_persisted_retries(
                 msg,
-                self.destination_ctxs[&destination].clone(),
+                destination_ctx.clone(),
                 app_context,
                 self.max_retries,
             );
@@ -316,25 +338,28 @@ impl MessageProcessor {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: HyperlaneRocksDB,
-        message_whitelist: Arc<MatchingList>,
-        message_blacklist: Arc<MatchingList>,
-        address_blacklist: Arc<AddressBlacklist>,
+        runtime_config: Arc<RuntimeConfig>,
         metrics: MessageProcessorMetrics,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
         destination_ctxs: HashMap<u32, Arc<MessageContext>>,
         metric_app_contexts: Vec<(MatchingList, String)>,
         max_retries: u32,
+        unknown_destinations: Arc<UnknownDestinationTracker>,
+        route_matrix: Arc<RouteMatrix>,
     ) -> Self {
+        let db = Arc::new(db) as Arc<dyn HyperlaneDb>;
         Self {
-            message_whitelist,
-            message_blacklist,
-            address_blacklist,
+            runtime_config,
             metrics,
             send_channels,
             destination_ctxs,
             metric_app_contexts,
-            nonce_iterator: ForwardBackwardIterator::new(Arc::new(db) as Arc<dyn HyperlaneDb>),
+            origin: db.domain().clone(),
+            nonce_iterator: ForwardBackwardIterator::new(db.clone()),
+            db,
             max_retries,
+            unknown_destinations,
+            route_matrix,
         }
     }
 
@@ -391,7 +416,7 @@ impl MessageProcessorMetrics {
 mod test {
     use std::time::Instant;
 
-    use prometheus::{IntCounter, Registry};
+    use prometheus::{IntCounter, IntCounterVec};
     use tokio::{
         sync::{
             mpsc::{self, UnboundedReceiver},
@@ -407,10 +432,13 @@ mod test {
             InterchainGasPaymentData,
         },
         settings::{ChainConf, ChainConnectionConf, Settings},
+        test_utils::dummy_core_metrics,
+        PersistentIntCounter, ValidatorAnnounceIndex,
     };
     use hyperlane_core::{
-        test_utils::dummy_domain, GasPaymentKey, InterchainGasPayment, InterchainGasPaymentMeta,
-        MerkleTreeInsertion, PendingOperationStatus, H256,
+        accumulator::incremental::IncrementalMerkle, test_utils::dummy_domain, GasPaymentKey,
+        InterchainGasPayment, InterchainGasPaymentMeta, MerkleTreeInsertion, NativeToken,
+        PendingOperationStatus, H256, U256,
     };
     use hyperlane_operation_verifier::{
         ApplicationOperationVerifier, ApplicationOperationVerifierReport,
@@ -420,6 +448,8 @@ mod test {
     use crate::{
         merkle_tree::builder::MerkleTreeBuilder,
         msg::{
+            app_context_budget::{test::dummy_spend_metrics, AppContextSpendTracker},
+            backoff::{BackoffPolicy, FailureClass},
             gas_payment::GasPaymentEnforcer,
             metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
         },
@@ -458,7 +488,22 @@ mod test {
     fn dummy_submission_metrics() -> MessageSubmissionMetrics {
         MessageSubmissionMetrics {
             last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
-            messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+            messages_processed: PersistentIntCounter::new(
+                IntCounter::new("message_processed_gauge", "help string").unwrap(),
+                None,
+                "relayer",
+                "message_processed_gauge",
+                &[],
+            ),
+            gas_used: PersistentIntCounter::new(
+                IntCounter::new("message_gas_used_gauge", "help string").unwrap(),
+                None,
+                "relayer",
+                "message_gas_used_gauge",
+                &[],
+            ),
+            enqueued: IntCounter::new("message_enqueued_counter", "help string").unwrap(),
+            executed: IntCounter::new("message_executed_counter", "help string").unwrap(),
         }
     }
 
@@ -466,6 +511,7 @@ mod test {
         ChainConf {
             domain: domain.clone(),
             signer: Default::default(),
+            additional_signers: Default::default(),
             reorg_period: Default::default(),
             addresses: Default::default(),
             connection: ChainConnectionConf::Ethereum(hyperlane_ethereum::ConnectionConf {
@@ -474,9 +520,19 @@ mod test {
                 },
                 transaction_overrides: Default::default(),
                 operation_batch: Default::default(),
+                native_token: NativeToken {
+                    decimals: 18,
+                    ..Default::default()
+                },
+                rpc_rate_limiter: None,
+                fee_history_cache: None,
+                log_query_batcher: None,
+                storage_locations_cache: None,
+                read_batcher: None,
             }),
             metrics_conf: Default::default(),
             index: Default::default(),
+            hash_algorithm: Default::default(),
         }
     }
 
@@ -494,17 +550,25 @@ mod test {
             destination_domain.name().to_owned(),
             dummy_chain_conf(destination_domain),
         );
+        let origin_chain_conf = settings.chain_setup(origin_domain).unwrap();
         let destination_chain_conf = settings.chain_setup(destination_domain).unwrap();
-        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let core_metrics = dummy_core_metrics("dummy_relayer");
         BaseMetadataBuilder::new(
-            origin_domain.clone(),
+            origin_chain_conf.clone(),
             destination_chain_conf.clone(),
             Arc::new(RwLock::new(MerkleTreeBuilder::new())),
-            Arc::new(MockValidatorAnnounceContract::default()),
-            false,
+            Arc::new(ValidatorAnnounceIndex::new(
+                Arc::new(MockValidatorAnnounceContract::default()),
+                db.clone(),
+                Duration::ZERO,
+                false,
+            )),
             Arc::new(core_metrics),
             db.clone(),
             IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
+            None,
+            Default::default(),
+            Default::default(),
         )
     }
 
@@ -522,6 +586,26 @@ mod test {
             transaction_gas_limit: Default::default(),
             metrics: dummy_submission_metrics(),
             application_operation_verifier: Some(Arc::new(DummyApplicationOperationVerifier {})),
+            log_deduplicator: None,
+            recipient_code_hash_filter: None,
+            runtime_config: Default::default(),
+            app_context_spend_tracker: Arc::new(AppContextSpendTracker::new(
+                destination_domain.clone(),
+                Arc::new(db.clone()),
+                Default::default(),
+                dummy_spend_metrics(),
+            )),
+            destination_domain_cache: Default::default(),
+            delivery_status_batcher: Default::default(),
+            simulation_limiter: Default::default(),
+            destination_pause: Default::default(),
+            preflight_simulation: None,
+            pre_transaction_hooks: None,
+            signer_balance_floors: None,
+            message_status_notifier: None,
+            message_expiry: None,
+            funding_scheduler: None,
+            backoff_policy: Default::default(),
         });
 
         let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
@@ -529,13 +613,27 @@ mod test {
             MessageProcessor::new(
                 db.clone(),
                 Default::default(),
-                Default::default(),
-                Default::default(),
                 dummy_processor_metrics(origin_domain.id()),
                 HashMap::from([(destination_domain.id(), send_channel)]),
                 HashMap::from([(destination_domain.id(), message_context)]),
                 vec![],
                 DEFAULT_MAX_MESSAGE_RETRIES,
+                Arc::new(UnknownDestinationTracker::new(
+                    Default::default(),
+                    IntCounterVec::new(
+                        prometheus::opts!("dummy_unknown_destination_messages", "help string"),
+                        &["origin", "remote"],
+                    )
+                    .unwrap(),
+                )),
+                Arc::new(RouteMatrix::new(
+                    HashMap::new(),
+                    IntCounterVec::new(
+                        prometheus::opts!("dummy_route_disabled_messages", "help string"),
+                        &["origin", "remote"],
+                    )
+                    .unwrap(),
+                )),
             ),
             receive_channel,
         )
@@ -586,10 +684,21 @@ mod test {
         db: &HyperlaneRocksDB,
         num_operations: usize,
     ) -> Vec<QueueOperation> {
-        let (message_processor, mut receive_channel) =
+        let (message_processor, receive_channel) =
             dummy_message_processor(origin_domain, destination_domain, db);
+        collect_first_n_operations(message_processor, receive_channel, num_operations).await
+    }
 
-        let processor = Processor::new(Box::new(message_processor), TaskMonitor::new());
+    async fn collect_first_n_operations(
+        message_processor: MessageProcessor,
+        mut receive_channel: UnboundedReceiver<QueueOperation>,
+        num_operations: usize,
+    ) -> Vec<QueueOperation> {
+        let processor = Processor::new(
+            Box::new(message_processor),
+            TaskMonitor::new(),
+            IntCounter::new("dummy_panics", "help string").unwrap(),
+        );
         let process_fut = processor.spawn();
         let mut pending_messages = vec![];
         let pending_message_accumulator = async {
@@ -724,6 +833,24 @@ mod test {
                 &self,
                 message_id: &H256,
             ) -> DbResult<Option<u32>>;
+            fn store_message_first_seen_at_by_message_id(
+                &self,
+                message_id: &H256,
+                timestamp: &u64,
+            ) -> DbResult<()>;
+            fn retrieve_message_first_seen_at_by_message_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<u64>>;
+            fn store_message_expired_at_by_message_id(
+                &self,
+                message_id: &H256,
+                timestamp: &u64,
+            ) -> DbResult<()>;
+            fn retrieve_message_expired_at_by_message_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<u64>>;
 
             fn store_merkle_tree_insertion_by_leaf_index(
                 &self,
@@ -761,6 +888,10 @@ mod test {
 
             /// Retrieve the nonce of the highest processed message we're aware of
             fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+            fn store_merkle_tree_snapshot(&self, tree: &IncrementalMerkle) -> DbResult<()>;
+            fn retrieve_merkle_tree_snapshot(&self) -> DbResult<Option<IncrementalMerkle>>;
+            fn store_spend_in_period(&self, spender: &str, period: u64, spend: &U256) -> DbResult<()>;
+            fn retrieve_spend_in_period(&self, spender: &str, period: u64) -> DbResult<Option<U256>>;
 
         }
     }
@@ -810,6 +941,8 @@ mod test {
                 .for_each(|(pm, expected_retries)| {
                     // Round up the actual backoff because it was calculated with an `Instant::now()` that was a fraction of a second ago
                     let expected_backoff = PendingMessage::calculate_msg_backoff(
+                        &BackoffPolicy::default(),
+                        FailureClass::Other,
                         *expected_retries,
                         DEFAULT_MAX_MESSAGE_RETRIES,
                         None,
@@ -0,0 +1,151 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::{ChainResult, HyperlaneProvider, H256};
+
+/// How long a recipient's code hash is used for before it's fetched again, so
+/// that upgraded recipients are picked up
+const CODE_HASH_TTL: Duration = Duration::from_secs(60 * 10);
+
+/// Filters message recipients by the hash of their code on the destination
+/// chain, so that operators can refuse to deliver to known-malicious or
+/// gas-griefing recipient implementations.
+///
+/// Code hashes are cached per recipient for up to `CODE_HASH_TTL`. On chains
+/// whose provider can't fetch code hashes, all recipients are allowed.
+#[derive(Debug)]
+pub struct RecipientCodeHashFilter {
+    /// If set, only recipients whose code hash is in this list are allowed
+    allowlist: Option<HashSet<H256>>,
+    /// Recipients whose code hash is in this list are never allowed
+    denylist: HashSet<H256>,
+    /// Code hashes by recipient
+    code_hashes: Mutex<HashMap<H256, CachedCodeHash>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedCodeHash {
+    fetched_at: Instant,
+    /// None if the chain doesn't support code hashes
+    code_hash: Option<H256>,
+}
+
+impl CachedCodeHash {
+    fn is_fresh(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.fetched_at) < CODE_HASH_TTL
+    }
+}
+
+impl RecipientCodeHashFilter {
+    pub fn new(allowlist: Option<HashSet<H256>>, denylist: HashSet<H256>) -> Self {
+        Self {
+            allowlist,
+            denylist,
+            code_hashes: Default::default(),
+        }
+    }
+
+    /// Returns whether messages may be delivered to `recipient`, fetching its
+    /// code hash from `provider` if it isn't cached or the cached one expired.
+    pub async fn is_allowed(
+        &self,
+        provider: &dyn HyperlaneProvider,
+        recipient: &H256,
+    ) -> ChainResult<bool> {
+        let code_hash = self.code_hash(provider, recipient).await?;
+        Ok(self.is_code_hash_allowed(code_hash.as_ref()))
+    }
+
+    async fn code_hash(
+        &self,
+        provider: &dyn HyperlaneProvider,
+        recipient: &H256,
+    ) -> ChainResult<Option<H256>> {
+        if let Some(code_hash) = self.cached_code_hash(recipient) {
+            return Ok(code_hash);
+        }
+        let code_hash = provider.get_code_hash(recipient).await?;
+        self.code_hashes
+            .lock()
+            .expect("recipient code hash cache lock poisoned")
+            .insert(
+                *recipient,
+                CachedCodeHash {
+                    fetched_at: Instant::now(),
+                    code_hash,
+                },
+            );
+        Ok(code_hash)
+    }
+
+    fn cached_code_hash(&self, recipient: &H256) -> Option<Option<H256>> {
+        self.code_hashes
+            .lock()
+            .expect("recipient code hash cache lock poisoned")
+            .get(recipient)
+            .filter(|cached| cached.is_fresh(Instant::now()))
+            .map(|cached| cached.code_hash)
+    }
+
+    fn is_code_hash_allowed(&self, code_hash: Option<&H256>) -> bool {
+        let Some(code_hash) = code_hash else {
+            return true;
+        };
+        if self.denylist.contains(code_hash) {
+            return false;
+        }
+        self.allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.contains(code_hash))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_denylist() {
+        let denied = H256::random();
+        let filter = RecipientCodeHashFilter::new(None, HashSet::from([denied]));
+
+        assert!(!filter.is_code_hash_allowed(Some(&denied)));
+        assert!(filter.is_code_hash_allowed(Some(&H256::random())));
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let allowed = H256::random();
+        let denied = H256::random();
+        let filter = RecipientCodeHashFilter::new(
+            Some(HashSet::from([allowed, denied])),
+            HashSet::from([denied]),
+        );
+
+        assert!(filter.is_code_hash_allowed(Some(&allowed)));
+        assert!(!filter.is_code_hash_allowed(Some(&H256::random())));
+        // The denylist takes precedence over the allowlist
+        assert!(!filter.is_code_hash_allowed(Some(&denied)));
+    }
+
+    #[test]
+    fn test_cached_code_hashes_expire() {
+        let now = Instant::now();
+        let cached = CachedCodeHash {
+            fetched_at: now,
+            code_hash: Some(H256::random()),
+        };
+        assert!(cached.is_fresh(now + CODE_HASH_TTL - Duration::from_secs(1)));
+        assert!(!cached.is_fresh(now + CODE_HASH_TTL));
+    }
+
+    #[test]
+    fn test_unsupported_chain_allows_all() {
+        let filter = RecipientCodeHashFilter::new(Some(HashSet::new()), HashSet::new());
+
+        assert!(filter.is_code_hash_allowed(None));
+    }
+}
@@ -0,0 +1,409 @@
+use std::{sync::Arc, time::Duration};
+
+use convert_case::Case;
+use ethers::types::{Address, Signature};
+use eyre::{eyre, Context, Result};
+use hyperlane_base::{db::DB, settings::parser::recase_json_value};
+use hyperlane_core::{Decode, Encode, H160};
+use prometheus::IntCounterVec;
+use reqwest::{header, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use super::runtime_config::RuntimeConfig;
+use crate::settings::matching_list::MatchingList;
+
+const OUTCOME_UPDATED: &str = "updated";
+const OUTCOME_UNCHANGED: &str = "unchanged";
+const OUTCOME_ERROR: &str = "error";
+const OUTCOME_REJECTED: &str = "rejected";
+
+const REMOTE_FILTER_LIST_VERSION: &str = "remote_filter_list_version_";
+
+/// The message filters that can be fetched from a URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum FilterListKind {
+    Whitelist,
+    Blacklist,
+}
+
+/// A filter list as served by its URL: a JSON payload and the signature of
+/// its exact bytes
+#[derive(Debug, Deserialize)]
+struct SignedFilterList {
+    payload: String,
+    /// EIP-191 signature of the payload by the configured signer
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FilterListPayload {
+    /// Which filter the list is for, so that a signed whitelist can't be
+    /// served as the blacklist or the other way around
+    kind: FilterListKind,
+    /// Increases with every published version of the list, so that an older
+    /// signed list can't be served again to roll back a newer one
+    version: u64,
+    list: Value,
+}
+
+/// Keeps a message filter of the relayer in sync with a list published at a
+/// URL, so that filters shared by a fleet of relayers can be managed in one
+/// place.
+///
+/// The URL serves `{"payload": "...", "signature": "0x..."}`, where the
+/// payload is the JSON `{"kind": "blacklist", "version": 1, "list": [...]}`
+/// and the signature is the EIP-191 signature of the payload, e.g. made with
+/// `cast wallet sign`.
+///
+/// The URL is polled with the ETag of the last response, so unchanged lists
+/// aren't downloaded again. Fetched lists are only applied if they are signed
+/// by the configured signer, are of the expected kind and are not older than
+/// the applied one. The applied version is stored in the agent database, so
+/// older lists are still rejected after a restart.
+#[derive(Debug)]
+pub struct RemoteFilterList {
+    kind: FilterListKind,
+    url: String,
+    signer: H160,
+    poll_interval: Duration,
+    client: reqwest::Client,
+    runtime_config: Arc<RuntimeConfig>,
+    /// Fetches of the list by list and outcome
+    fetches: IntCounterVec,
+    /// Stores the applied version, if set
+    db: Option<DB>,
+    etag: Option<String>,
+    version: Option<u64>,
+}
+
+impl RemoteFilterList {
+    pub fn new(
+        kind: FilterListKind,
+        url: String,
+        signer: H160,
+        poll_interval: Duration,
+        runtime_config: Arc<RuntimeConfig>,
+        fetches: IntCounterVec,
+        db: Option<DB>,
+    ) -> Self {
+        let mut list = Self {
+            kind,
+            url,
+            signer,
+            poll_interval,
+            client: reqwest::Client::new(),
+            runtime_config,
+            fetches,
+            db,
+            etag: None,
+            version: None,
+        };
+        list.version = list.retrieve_version();
+        list
+    }
+
+    /// Polls the URL forever, applying new versions of the list
+    pub async fn run(mut self) {
+        loop {
+            self.poll().await;
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    async fn poll(&mut self) {
+        let outcome = match self.fetch().await {
+            Ok(None) => OUTCOME_UNCHANGED,
+            Ok(Some(signed)) => match self.verify(&signed) {
+                Ok((version, list)) => {
+                    info!(
+                        kind = %self.kind,
+                        url = %self.url,
+                        version,
+                        %list,
+                        "Applying filter list fetched from URL"
+                    );
+                    self.version = Some(version);
+                    self.store_version(version);
+                    self.runtime_config.set_remote_filter_list(self.kind, list);
+                    OUTCOME_UPDATED
+                }
+                Err(err) => {
+                    warn!(?err, kind = %self.kind, url = %self.url, "Rejected filter list fetched from URL");
+                    OUTCOME_REJECTED
+                }
+            },
+            Err(err) => {
+                warn!(?err, kind = %self.kind, url = %self.url, "Failed to fetch filter list");
+                OUTCOME_ERROR
+            }
+        };
+        self.fetches
+            .with_label_values(&[&self.kind.to_string(), outcome])
+            .inc();
+    }
+
+    /// Fetches the list, or returns None if it didn't change since the last
+    /// fetch
+    async fn fetch(&mut self) -> Result<Option<SignedFilterList>> {
+        let mut request = self.client.get(&self.url);
+        if let Some(etag) = &self.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_owned);
+        let signed = response.json().await?;
+        // Only remember the ETag of lists that were received, so a list that
+        // fails to download is fetched again in full
+        self.etag = etag;
+        Ok(Some(signed))
+    }
+
+    /// Checks the signature and version of a fetched list, returning its
+    /// version and parsed list if it should be applied
+    fn verify(&self, signed: &SignedFilterList) -> Result<(u64, MatchingList)> {
+        let signature: Signature = signed
+            .signature
+            .parse()
+            .context("Invalid filter list signature")?;
+        signature
+            .verify(signed.payload.as_str(), Address::from(self.signer))
+            .context("Filter list isn't signed by the configured signer")?;
+
+        let payload: FilterListPayload =
+            serde_json::from_str(&signed.payload).context("Invalid filter list payload")?;
+        if payload.kind != self.kind {
+            return Err(eyre!(
+                "Filter list is a {}, expected a {}",
+                payload.kind,
+                self.kind
+            ));
+        }
+        if let Some(version) = self.version.filter(|version| payload.version < *version) {
+            return Err(eyre!(
+                "Filter list version {} is older than the applied version {version}",
+                payload.version
+            ));
+        }
+        let list = serde_json::from_value(recase_json_value(payload.list, Case::Flat))
+            .context("Invalid matching list")?;
+        Ok((payload.version, list))
+    }
+
+    fn version_key(&self) -> Vec<u8> {
+        format!("{REMOTE_FILTER_LIST_VERSION}{}", self.kind).into_bytes()
+    }
+
+    /// The version applied by a previous run of the agent, if any
+    fn retrieve_version(&self) -> Option<u64> {
+        let db = self.db.as_ref()?;
+        match db.retrieve(&self.version_key()) {
+            Ok(value) => value.and_then(|value| match u64::read_from(&mut value.as_slice()) {
+                Ok(version) => Some(version),
+                Err(err) => {
+                    warn!(?err, kind = %self.kind, "Failed to decode stored filter list version");
+                    None
+                }
+            }),
+            Err(err) => {
+                warn!(?err, kind = %self.kind, "Failed to retrieve stored filter list version");
+                None
+            }
+        }
+    }
+
+    fn store_version(&self, version: u64) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        if let Err(err) = db.store(&self.version_key(), &version.to_vec()) {
+            warn!(?err, kind = %self.kind, "Failed to store filter list version");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{extract::State, http::HeaderMap, response::IntoResponse, routing::get, Router};
+    use ethers::signers::{LocalWallet, Signer};
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::HyperlaneMessage;
+    use prometheus::{opts, IntCounterVec};
+
+    use super::*;
+
+    const BLOCKED_ORIGIN: u32 = 1;
+
+    async fn signed_list(wallet: &LocalWallet, version: u64) -> SignedFilterList {
+        signed_list_of_kind(wallet, FilterListKind::Blacklist, version).await
+    }
+
+    async fn signed_list_of_kind(
+        wallet: &LocalWallet,
+        kind: FilterListKind,
+        version: u64,
+    ) -> SignedFilterList {
+        let payload = serde_json::json!({
+            "kind": kind.to_string(),
+            "version": version,
+            "list": [{ "originDomain": BLOCKED_ORIGIN }],
+        })
+        .to_string();
+        let signature = wallet.sign_message(&payload).await.unwrap();
+        SignedFilterList {
+            payload,
+            signature: format!("0x{signature}"),
+        }
+    }
+
+    fn remote_list(url: String, signer: H160) -> RemoteFilterList {
+        remote_list_with_db(url, signer, None)
+    }
+
+    fn remote_list_with_db(url: String, signer: H160, db: Option<DB>) -> RemoteFilterList {
+        RemoteFilterList::new(
+            FilterListKind::Blacklist,
+            url,
+            signer,
+            Duration::from_secs(60),
+            Default::default(),
+            IntCounterVec::new(opts!("fetches", "fetches"), &["list", "outcome"]).unwrap(),
+            db,
+        )
+    }
+
+    fn fetches(list: &RemoteFilterList, outcome: &str) -> u64 {
+        list.fetches
+            .with_label_values(&["blacklist", outcome])
+            .get()
+    }
+
+    #[tokio::test]
+    async fn test_verify_checks_signer_and_version() {
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        let mut list = remote_list(String::new(), wallet.address().into());
+
+        let (version, matching_list) = list.verify(&signed_list(&wallet, 2).await).unwrap();
+        assert_eq!(version, 2);
+        let message = HyperlaneMessage {
+            origin: BLOCKED_ORIGIN,
+            ..Default::default()
+        };
+        assert!(matching_list.msg_matches(&message, false));
+
+        // Lists older than the applied one are rejected
+        list.version = Some(3);
+        assert!(list.verify(&signed_list(&wallet, 2).await).is_err());
+        assert!(list.verify(&signed_list(&wallet, 3).await).is_ok());
+
+        // As are lists signed by anyone else
+        let other_wallet = LocalWallet::new(&mut rand::thread_rng());
+        assert!(list.verify(&signed_list(&other_wallet, 4).await).is_err());
+
+        // Or whose payload was tampered with
+        let mut tampered = signed_list(&wallet, 4).await;
+        tampered.payload = tampered.payload.replace("\"version\":4", "\"version\":5");
+        assert!(list.verify(&tampered).is_err());
+
+        // Or that are signed for the other filter
+        let whitelist = signed_list_of_kind(&wallet, FilterListKind::Whitelist, 4).await;
+        assert!(list.verify(&whitelist).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_applied_version_survives_restart() {
+        test_utils::run_test_db(|db| async move {
+            let wallet = LocalWallet::new(&mut rand::thread_rng());
+            let list =
+                remote_list_with_db(String::new(), wallet.address().into(), Some(db.clone()));
+            assert_eq!(list.version, None);
+            list.store_version(3);
+
+            let restarted =
+                remote_list_with_db(String::new(), wallet.address().into(), Some(db.clone()));
+            assert_eq!(restarted.version, Some(3));
+            assert!(restarted.verify(&signed_list(&wallet, 2).await).is_err());
+            assert!(restarted.verify(&signed_list(&wallet, 3).await).is_ok());
+        })
+        .await;
+    }
+
+    #[derive(Clone)]
+    struct ServedList {
+        body: String,
+        etag: &'static str,
+        requests: Arc<AtomicUsize>,
+    }
+
+    async fn serve_list(State(served): State<ServedList>, headers: HeaderMap) -> impl IntoResponse {
+        served.requests.fetch_add(1, Ordering::SeqCst);
+        if headers
+            .get(header::IF_NONE_MATCH)
+            .is_some_and(|etag| etag == served.etag)
+        {
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+        ([(header::ETAG, served.etag)], served.body).into_response()
+    }
+
+    #[tokio::test]
+    async fn test_list_is_polled_with_etag() {
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        let signed = signed_list(&wallet, 1).await;
+        let served = ServedList {
+            body: serde_json::json!({
+                "payload": signed.payload,
+                "signature": signed.signature,
+            })
+            .to_string(),
+            etag: "\"v1\"",
+            requests: Default::default(),
+        };
+        let requests = served.requests.clone();
+        let app = Router::new()
+            .route("/blacklist.json", get(serve_list))
+            .with_state(served);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let mut list = remote_list(
+            format!("http://{addr}/blacklist.json"),
+            wallet.address().into(),
+        );
+        let message = HyperlaneMessage {
+            origin: BLOCKED_ORIGIN,
+            ..Default::default()
+        };
+        assert!(!list
+            .runtime_config
+            .message_blacklist()
+            .msg_matches(&message, false));
+
+        list.poll().await;
+        assert_eq!(fetches(&list, OUTCOME_UPDATED), 1);
+        assert!(list
+            .runtime_config
+            .message_blacklist()
+            .msg_matches(&message, false));
+
+        list.poll().await;
+        assert_eq!(fetches(&list, OUTCOME_UNCHANGED), 1);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
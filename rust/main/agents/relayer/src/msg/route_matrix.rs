@@ -0,0 +1,152 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use hyperlane_core::{HyperlaneDomain, HyperlaneMessage};
+use prometheus::IntCounterVec;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+/// Enables or disables the delivery of messages on specific origin to
+/// destination routes, without changing the message filters.
+///
+/// Routes are enabled unless they're disabled in the settings or through the
+/// API. Changes made through the API last until the settings are reloaded or
+/// the relayer restarts.
+///
+/// Messages of disabled routes are skipped like messages that don't pass the
+/// message filters. So messages skipped while their route was disabled are
+/// only picked up again when the relayer restarts.
+#[derive(Debug)]
+pub struct RouteMatrix {
+    /// Routes with an explicit state, by origin and destination domain id
+    routes: RwLock<HashMap<(u32, u32), bool>>,
+    /// Messages skipped because their route is disabled, by origin and
+    /// destination
+    skipped_messages: IntCounterVec,
+}
+
+/// The state of a route, as listed and set through the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteState {
+    pub origin: u32,
+    pub destination: u32,
+    pub enabled: bool,
+}
+
+impl RouteMatrix {
+    pub fn new(routes: HashMap<(u32, u32), bool>, skipped_messages: IntCounterVec) -> Self {
+        Self {
+            routes: RwLock::new(routes),
+            skipped_messages,
+        }
+    }
+
+    /// Whether messages from `origin` to `destination` are delivered
+    pub fn is_enabled(&self, origin: u32, destination: u32) -> bool {
+        self.routes
+            .read()
+            .expect("route matrix lock poisoned")
+            .get(&(origin, destination))
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// Counts a message skipped because its route is disabled
+    pub fn record_skipped(
+        &self,
+        origin: &HyperlaneDomain,
+        destination: &HyperlaneDomain,
+        message: &HyperlaneMessage,
+    ) {
+        debug!(?message, "Route of message is disabled, skipping");
+        self.skipped_messages
+            .with_label_values(&[origin.name(), destination.name()])
+            .inc();
+    }
+
+    /// Enables or disables a route
+    pub fn set(&self, route: RouteState) {
+        info!(?route, "Setting route state");
+        self.routes
+            .write()
+            .expect("route matrix lock poisoned")
+            .insert((route.origin, route.destination), route.enabled);
+    }
+
+    /// Replaces the state of all routes, discarding the changes made through
+    /// the API
+    pub fn replace(&self, routes: HashMap<(u32, u32), bool>) {
+        *self.routes.write().expect("route matrix lock poisoned") = routes;
+    }
+
+    /// The routes with an explicit state, sorted by origin and destination
+    pub fn routes(&self) -> Vec<RouteState> {
+        let mut routes: Vec<_> = self
+            .routes
+            .read()
+            .expect("route matrix lock poisoned")
+            .iter()
+            .map(|(&(origin, destination), &enabled)| RouteState {
+                origin,
+                destination,
+                enabled,
+            })
+            .collect();
+        routes.sort_by_key(|route| (route.origin, route.destination));
+        routes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::opts;
+
+    use super::*;
+
+    #[test]
+    fn test_routes_are_enabled_unless_disabled() {
+        let origin = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+        let destination = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let matrix = RouteMatrix::new(
+            HashMap::from([((origin.id(), destination.id()), false)]),
+            IntCounterVec::new(opts!("skipped", "help"), &["origin", "remote"]).unwrap(),
+        );
+        let message = HyperlaneMessage {
+            origin: origin.id(),
+            destination: destination.id(),
+            ..Default::default()
+        };
+
+        assert!(!matrix.is_enabled(origin.id(), destination.id()));
+        // Only the configured direction is disabled
+        assert!(matrix.is_enabled(destination.id(), origin.id()));
+
+        matrix.record_skipped(&origin, &destination, &message);
+        assert_eq!(
+            matrix
+                .skipped_messages
+                .with_label_values(&[origin.name(), destination.name()])
+                .get(),
+            1
+        );
+
+        matrix.set(RouteState {
+            origin: origin.id(),
+            destination: destination.id(),
+            enabled: true,
+        });
+        assert!(matrix.is_enabled(origin.id(), destination.id()));
+
+        // Reloaded settings discard the changes made through the API
+        matrix.replace(HashMap::from([((origin.id(), destination.id()), false)]));
+        assert!(!matrix.is_enabled(origin.id(), destination.id()));
+        assert_eq!(
+            matrix.routes(),
+            vec![RouteState {
+                origin: origin.id(),
+                destination: destination.id(),
+                enabled: false,
+            }]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+use hyperlane_core::HyperlaneMessage;
+
+use super::{blacklist::AddressBlacklist, remote_filter_list::FilterListKind};
+use crate::settings::matching_list::MatchingList;
+
+/// The parts of the relayer's configuration that can be changed while it is
+/// running, by updating the config files and sending the relayer a SIGHUP.
+/// Message filters fetched from a URL take precedence over the configured
+/// ones, and are only changed by fetching new versions of them.
+///
+/// Each value is swapped out as a whole, so readers always see a consistent
+/// version of it.
+#[derive(Debug, Default)]
+pub struct RuntimeConfig {
+    message_whitelist: RwLock<Arc<MatchingList>>,
+    message_blacklist: RwLock<Arc<MatchingList>>,
+    remote_message_whitelist: RwLock<Option<Arc<MatchingList>>>,
+    remote_message_blacklist: RwLock<Option<Arc<MatchingList>>>,
+    address_blacklist: RwLock<Arc<AddressBlacklist>>,
+    /// Domain ids of chains that no messages are delivered to or from
+    paused_chains: RwLock<Arc<HashSet<u32>>>,
+}
+
+impl RuntimeConfig {
+    pub fn new(
+        message_whitelist: MatchingList,
+        message_blacklist: MatchingList,
+        address_blacklist: AddressBlacklist,
+        paused_chains: HashSet<u32>,
+    ) -> Self {
+        let config = Self::default();
+        config.update(
+            message_whitelist,
+            message_blacklist,
+            address_blacklist,
+            paused_chains,
+        );
+        config
+    }
+
+    /// Replaces all values with the given ones
+    pub fn update(
+        &self,
+        message_whitelist: MatchingList,
+        message_blacklist: MatchingList,
+        address_blacklist: AddressBlacklist,
+        paused_chains: HashSet<u32>,
+    ) {
+        replace(&self.message_whitelist, message_whitelist);
+        replace(&self.message_blacklist, message_blacklist);
+        replace(&self.address_blacklist, address_blacklist);
+        replace(&self.paused_chains, paused_chains);
+    }
+
+    /// Replaces a message filter with one fetched from a URL
+    pub fn set_remote_filter_list(&self, kind: FilterListKind, list: MatchingList) {
+        let lock = match kind {
+            FilterListKind::Whitelist => &self.remote_message_whitelist,
+            FilterListKind::Blacklist => &self.remote_message_blacklist,
+        };
+        *lock.write().expect("runtime config lock poisoned") = Some(Arc::new(list));
+    }
+
+    /// Filter for what messages to relay
+    pub fn message_whitelist(&self) -> Arc<MatchingList> {
+        read_remote(&self.remote_message_whitelist).unwrap_or_else(|| read(&self.message_whitelist))
+    }
+
+    /// Filter for what messages to block
+    pub fn message_blacklist(&self) -> Arc<MatchingList> {
+        read_remote(&self.remote_message_blacklist).unwrap_or_else(|| read(&self.message_blacklist))
+    }
+
+    /// Filter for what addresses to block interactions with
+    pub fn address_blacklist(&self) -> Arc<AddressBlacklist> {
+        read(&self.address_blacklist)
+    }
+
+    /// Whether the origin or destination chain of the message is paused
+    pub fn is_paused(&self, message: &HyperlaneMessage) -> bool {
+        let paused_chains = read(&self.paused_chains);
+        paused_chains.contains(&message.origin) || paused_chains.contains(&message.destination)
+    }
+}
+
+fn read<T>(lock: &RwLock<Arc<T>>) -> Arc<T> {
+    lock.read().expect("runtime config lock poisoned").clone()
+}
+
+fn read_remote<T>(lock: &RwLock<Option<Arc<T>>>) -> Option<Arc<T>> {
+    lock.read().expect("runtime config lock poisoned").clone()
+}
+
+fn replace<T>(lock: &RwLock<Arc<T>>, value: T) {
+    *lock.write().expect("runtime config lock poisoned") = Arc::new(value);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_update_pauses_chains() {
+        let config = RuntimeConfig::default();
+        let message = HyperlaneMessage {
+            origin: 1,
+            destination: 2,
+            ..Default::default()
+        };
+        assert!(!config.is_paused(&message));
+
+        config.update(
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            HashSet::from([2]),
+        );
+        assert!(config.is_paused(&message));
+
+        config.update(
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            HashSet::from([3]),
+        );
+        assert!(!config.is_paused(&message));
+    }
+
+    #[test]
+    fn test_remote_filter_list_takes_precedence() {
+        let config = RuntimeConfig::default();
+        let message = HyperlaneMessage {
+            destination: 2,
+            ..Default::default()
+        };
+        config.update(
+            Default::default(),
+            MatchingList::with_destination_domain(2),
+            Default::default(),
+            Default::default(),
+        );
+        assert!(config.message_blacklist().msg_matches(&message, false));
+
+        config.set_remote_filter_list(
+            FilterListKind::Blacklist,
+            MatchingList::with_destination_domain(3),
+        );
+        assert!(!config.message_blacklist().msg_matches(&message, false));
+
+        // Reloading the configured lists doesn't undo the fetched one
+        config.update(
+            Default::default(),
+            MatchingList::with_destination_domain(2),
+            Default::default(),
+            Default::default(),
+        );
+        assert!(!config.message_blacklist().msg_matches(&message, false));
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+use hyperlane_core::{ConfirmReason, PendingOperationResult, QueueOperation, H256, U256};
+use prometheus::IntCounterVec;
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// How long a message prepared in shadow mode waits before being prepared
+/// again, to tell whether another relayer delivered it
+pub const SHADOW_MODE_RECHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How many divergences are kept in the report of each destination
+const MAX_REPORTED_DIVERGENCES: usize = 100;
+
+/// Compares the outcome of preparing messages to destinations in shadow mode
+/// with their actual delivery, e.g. by a production relayer.
+///
+/// In shadow mode, messages to a destination go through the full prepare
+/// pipeline, i.e. the delivery status and gas payment checks, metadata
+/// building and gas estimation, but are never submitted. They're prepared
+/// again until another relayer delivers them. A message delivered by another
+/// relayer which shadow mode couldn't prepare is a divergence, which points
+/// to an issue with the chain integration. Operators can run a newly
+/// integrated chain in shadow mode alongside production before enabling
+/// delivery on it.
+#[derive(Debug)]
+pub struct ShadowMode {
+    /// The last outcome of preparing each message that isn't delivered yet
+    outcomes: Mutex<HashMap<H256, PrepareOutcome>>,
+    reports: Mutex<HashMap<String, ShadowModeReport>>,
+    /// Divergences, by destination and kind
+    divergences: IntCounterVec,
+}
+
+#[derive(Debug, Clone)]
+enum PrepareOutcome {
+    Deliverable { estimated_gas: Option<U256> },
+    Failed { reason: String },
+}
+
+/// The outcomes of shadow mode for a destination, as reported through the API
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ShadowModeReport {
+    pub destination: String,
+    /// Messages prepared successfully at least once
+    pub deliverable: u64,
+    /// Messages that failed to prepare at least once
+    pub failed_to_prepare: u64,
+    /// Messages dropped while preparing them
+    pub dropped: u64,
+    /// Messages delivered by another relayer after shadow mode prepared them
+    pub matched: u64,
+    /// Messages delivered by another relayer before shadow mode prepared them
+    pub unverified: u64,
+    /// The most recent divergences
+    pub divergences: VecDeque<ShadowModeDivergence>,
+}
+
+/// A message whose delivery diverged from the outcome of preparing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShadowModeDivergence {
+    pub id: H256,
+    pub kind: DivergenceKind,
+    /// Why preparing the message failed
+    pub reason: String,
+    pub retry_count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// Another relayer delivered a message whose last prepare attempt failed
+    DeliveredButNotPrepared,
+}
+
+impl DivergenceKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::DeliveredButNotPrepared => "delivered_but_not_prepared",
+        }
+    }
+}
+
+impl ShadowMode {
+    pub fn new(divergences: IntCounterVec) -> Self {
+        Self {
+            outcomes: Default::default(),
+            reports: Default::default(),
+            divergences,
+        }
+    }
+
+    /// Records the result of preparing the operation. Operations that aren't
+    /// ready yet aren't recorded.
+    pub fn record(&self, op: &QueueOperation, result: &PendingOperationResult) {
+        let id = op.id();
+        let destination = op.destination_domain().name().to_owned();
+        let mut outcomes = self.outcomes.lock().expect("shadow mode lock poisoned");
+        let mut reports = self.reports.lock().expect("shadow mode lock poisoned");
+        let report = reports
+            .entry(destination.clone())
+            .or_insert_with(|| ShadowModeReport {
+                destination: destination.clone(),
+                ..Default::default()
+            });
+        match result {
+            PendingOperationResult::Success => {
+                let estimated_gas = op.get_tx_cost_estimate();
+                info!(?id, %destination, ?estimated_gas, "Prepared message in shadow mode");
+                let previous = outcomes.insert(id, PrepareOutcome::Deliverable { estimated_gas });
+                if !matches!(previous, Some(PrepareOutcome::Deliverable { .. })) {
+                    report.deliverable += 1;
+                }
+            }
+            PendingOperationResult::Reprepare(reason) => {
+                let previous = outcomes.insert(
+                    id,
+                    PrepareOutcome::Failed {
+                        reason: reason.to_string(),
+                    },
+                );
+                if !matches!(previous, Some(PrepareOutcome::Failed { .. })) {
+                    report.failed_to_prepare += 1;
+                }
+            }
+            PendingOperationResult::Drop => {
+                outcomes.remove(&id);
+                report.dropped += 1;
+            }
+            PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted) => {
+                match outcomes.remove(&id) {
+                    Some(PrepareOutcome::Deliverable { estimated_gas }) => {
+                        info!(
+                            ?id,
+                            %destination,
+                            ?estimated_gas,
+                            "Message prepared in shadow mode was delivered"
+                        );
+                        report.matched += 1;
+                    }
+                    Some(PrepareOutcome::Failed { reason }) => {
+                        let divergence = ShadowModeDivergence {
+                            id,
+                            kind: DivergenceKind::DeliveredButNotPrepared,
+                            reason,
+                            retry_count: op.retry_count(),
+                        };
+                        warn!(?divergence, %destination, "Shadow mode diverged from delivery");
+                        self.divergences
+                            .with_label_values(&[&destination, divergence.kind.as_str()])
+                            .inc();
+                        if report.divergences.len() >= MAX_REPORTED_DIVERGENCES {
+                            report.divergences.pop_front();
+                        }
+                        report.divergences.push_back(divergence);
+                    }
+                    None => report.unverified += 1,
+                }
+            }
+            PendingOperationResult::Confirm(_) | PendingOperationResult::NotReady => {}
+        }
+    }
+
+    /// The report of each destination in shadow mode, sorted by destination
+    pub fn reports(&self) -> Vec<ShadowModeReport> {
+        let mut reports: Vec<_> = self
+            .reports
+            .lock()
+            .expect("shadow mode lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        reports.sort_by(|a, b| a.destination.cmp(&b.destination));
+        reports
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain, ReprepareReason};
+    use prometheus::opts;
+
+    use crate::msg::op_queue::test::MockPendingOperation;
+
+    use super::*;
+
+    #[test]
+    fn test_divergences_are_reported() {
+        let shadow_mode = ShadowMode::new(
+            IntCounterVec::new(opts!("divergences", "help"), &["remote", "kind"]).unwrap(),
+        );
+        let destination = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let prepared: QueueOperation = Box::new(MockPendingOperation::new(0, destination.clone()));
+        let failed: QueueOperation = Box::new(MockPendingOperation::new(1, destination.clone()));
+        let delivered = PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted);
+
+        shadow_mode.record(&prepared, &PendingOperationResult::Success);
+        shadow_mode.record(&prepared, &PendingOperationResult::Success);
+        shadow_mode.record(&prepared, &delivered);
+        shadow_mode.record(
+            &failed,
+            &PendingOperationResult::Reprepare(ReprepareReason::ErrorBuildingMetadata),
+        );
+        shadow_mode.record(&failed, &delivered);
+
+        let reports = shadow_mode.reports();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.destination, destination.name());
+        assert_eq!(report.deliverable, 1);
+        assert_eq!(report.failed_to_prepare, 1);
+        assert_eq!(report.matched, 1);
+        assert_eq!(
+            report.divergences,
+            [ShadowModeDivergence {
+                id: failed.id(),
+                kind: DivergenceKind::DeliveredButNotPrepared,
+                reason: ReprepareReason::ErrorBuildingMetadata.to_string(),
+                retry_count: 0,
+            }]
+        );
+        assert_eq!(
+            shadow_mode
+                .divergences
+                .with_label_values(&[destination.name(), "delivered_but_not_prepared"])
+                .get(),
+            1
+        );
+    }
+}
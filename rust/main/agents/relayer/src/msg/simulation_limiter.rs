@@ -0,0 +1,87 @@
+use std::{future::Future, time::Instant};
+
+use prometheus::Histogram;
+use tokio::sync::Semaphore;
+
+/// Buckets of the time spent waiting for a simulation permit, in seconds
+pub const SIMULATION_PERMIT_WAIT_SECONDS_BUCKETS: &[f64] =
+    &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Limits how many simulations, i.e. `eth_call`s, gas estimations and
+/// `simulateTransaction`s, run against a destination chain at the same time.
+///
+/// Metadata building and gas estimation of all origins share the limiter of
+/// their destination, so clearing a backlog doesn't flood its RPCs.
+#[derive(Debug, Default)]
+pub struct SimulationLimiter {
+    /// None if the number of simulations isn't limited
+    limit: Option<SimulationLimit>,
+}
+
+#[derive(Debug)]
+struct SimulationLimit {
+    permits: Semaphore,
+    /// Time spent waiting for a permit
+    wait_seconds: Histogram,
+}
+
+impl SimulationLimiter {
+    pub fn new(max_concurrent_simulations: Option<usize>, wait_seconds: Histogram) -> Self {
+        Self {
+            limit: max_concurrent_simulations.map(|permits| SimulationLimit {
+                permits: Semaphore::new(permits),
+                wait_seconds,
+            }),
+        }
+    }
+
+    /// Runs the simulation once a permit is available
+    pub async fn run<F: Future>(&self, simulation: F) -> F::Output {
+        let Some(limit) = &self.limit else {
+            return simulation.await;
+        };
+        let start = Instant::now();
+        let _permit = limit
+            .permits
+            .acquire()
+            .await
+            .expect("Simulation semaphore is never closed");
+        limit.wait_seconds.observe(start.elapsed().as_secs_f64());
+        simulation.await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use futures::future::join_all;
+    use prometheus::HistogramOpts;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_simulations_are_limited() {
+        let wait_seconds =
+            Histogram::with_opts(HistogramOpts::new("wait_seconds", "help")).unwrap();
+        let limiter = SimulationLimiter::new(Some(2), wait_seconds.clone());
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        join_all((0..6).map(|_| {
+            limiter.run(async {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        }))
+        .await;
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        assert_eq!(wait_seconds.get_sample_count(), 6);
+    }
+}
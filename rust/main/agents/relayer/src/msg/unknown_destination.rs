@@ -0,0 +1,283 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use hyperlane_base::db::HyperlaneDb;
+use hyperlane_core::{HyperlaneMessage, H256};
+use prometheus::IntCounterVec;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::{msg::message_expiry::now, settings::UnknownDestinationPolicy};
+
+/// How many messages are parked at most. Beyond that, the messages closest
+/// to expiring are dropped first.
+const MAX_PARKED_MESSAGES: usize = 10_000;
+
+/// Handles messages dispatched to domains the relayer doesn't deliver to,
+/// according to the configured policy.
+///
+/// Depending on the policy, these messages are counted by destination, and
+/// parked in memory until their TTL elapses so they can be listed through the
+/// API. This lets operators tell apps that dispatch to a wrong domain apart
+/// from chains the relayer is missing.
+///
+/// The TTL counts from when the message was first seen, which is persisted,
+/// so restarts don't extend it. Once it elapsed, the message is marked as
+/// processed, so it's no longer revisited.
+#[derive(Debug)]
+pub struct UnknownDestinationTracker {
+    policy: UnknownDestinationPolicy,
+    /// Messages by origin and destination domain id
+    messages_count: IntCounterVec,
+    parked: Mutex<HashMap<H256, ParkedMessage>>,
+}
+
+#[derive(Debug)]
+struct ParkedMessage {
+    origin: String,
+    message: HyperlaneMessage,
+    /// Unix timestamp in seconds
+    expires_at: u64,
+}
+
+/// A parked message to an unknown destination, as listed through the API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownDestinationMessage {
+    pub id: H256,
+    pub origin: String,
+    pub destination_domain_id: u32,
+    pub nonce: u32,
+    pub sender: H256,
+    pub recipient: H256,
+    pub seconds_until_expiry: u64,
+}
+
+impl UnknownDestinationTracker {
+    pub fn new(policy: UnknownDestinationPolicy, messages_count: IntCounterVec) -> Self {
+        Self {
+            policy,
+            messages_count,
+            parked: Default::default(),
+        }
+    }
+
+    /// Handles a message whose destination the relayer doesn't deliver to.
+    /// `db` is the database of the message's origin.
+    pub fn record(&self, db: &dyn HyperlaneDb, message: &HyperlaneMessage) {
+        self.record_at(db, message, now())
+    }
+
+    fn record_at(&self, db: &dyn HyperlaneDb, message: &HyperlaneMessage, now: u64) {
+        debug!(
+            ?message,
+            policy = ?self.policy,
+            "Message destined for unknown domain, skipping"
+        );
+        let ttl = match self.policy {
+            UnknownDestinationPolicy::Ignore => return,
+            UnknownDestinationPolicy::Count => None,
+            UnknownDestinationPolicy::Park { ttl } => Some(ttl),
+        };
+        let origin = db.domain().name();
+        self.messages_count
+            .with_label_values(&[origin, &message.destination.to_string()])
+            .inc();
+        let Some(ttl) = ttl else {
+            return;
+        };
+
+        let id = message.id();
+        let expires_at = first_seen_at(db, &id, now).saturating_add(ttl.as_secs());
+        if expires_at <= now {
+            debug!(
+                ?id,
+                "Parked message to unknown domain expired, marking as processed"
+            );
+            if let Err(err) = db.store_processed_by_nonce(&message.nonce, &true) {
+                warn!(?id, ?err, "Failed to mark expired message as processed");
+            }
+            return;
+        }
+
+        let mut parked = self
+            .parked
+            .lock()
+            .expect("unknown destination lock poisoned");
+        parked.retain(|_, parked| parked.expires_at > now);
+        if parked.len() >= MAX_PARKED_MESSAGES && !parked.contains_key(&id) {
+            let closest_to_expiry = parked
+                .iter()
+                .min_by_key(|(_, parked)| parked.expires_at)
+                .map(|(id, _)| *id);
+            if let Some(closest_to_expiry) = closest_to_expiry {
+                parked.remove(&closest_to_expiry);
+            }
+        }
+        parked.insert(
+            id,
+            ParkedMessage {
+                origin: origin.to_owned(),
+                message: message.clone(),
+                expires_at,
+            },
+        );
+    }
+
+    /// The parked messages whose TTL hasn't elapsed yet, oldest first
+    pub fn parked_messages(&self) -> Vec<UnknownDestinationMessage> {
+        self.parked_messages_at(now())
+    }
+
+    fn parked_messages_at(&self, now: u64) -> Vec<UnknownDestinationMessage> {
+        let mut parked = self
+            .parked
+            .lock()
+            .expect("unknown destination lock poisoned");
+        parked.retain(|_, parked| parked.expires_at > now);
+        let mut messages = parked
+            .iter()
+            .map(|(id, parked)| UnknownDestinationMessage {
+                id: *id,
+                origin: parked.origin.clone(),
+                destination_domain_id: parked.message.destination,
+                nonce: parked.message.nonce,
+                sender: parked.message.sender,
+                recipient: parked.message.recipient,
+                seconds_until_expiry: parked.expires_at.saturating_sub(now),
+            })
+            .collect::<Vec<_>>();
+        messages.sort_by_key(|message| message.seconds_until_expiry);
+        messages
+    }
+}
+
+/// When the message was first seen, persisting `now` if it wasn't seen before
+fn first_seen_at(db: &dyn HyperlaneDb, id: &H256, now: u64) -> u64 {
+    match db.retrieve_message_first_seen_at_by_message_id(id) {
+        Ok(Some(first_seen_at)) => first_seen_at,
+        result => {
+            if let Err(err) = result {
+                warn!(?id, ?err, "Failed to read when message was first seen");
+            }
+            if let Err(err) = db.store_message_first_seen_at_by_message_id(id, &now) {
+                warn!(?id, ?err, "Failed to persist when message was first seen");
+            }
+            now
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain};
+    use prometheus::opts;
+
+    use super::*;
+
+    fn tracker(policy: UnknownDestinationPolicy) -> UnknownDestinationTracker {
+        UnknownDestinationTracker::new(
+            policy,
+            IntCounterVec::new(opts!("count", "help"), &["origin", "remote"]).unwrap(),
+        )
+    }
+
+    fn message(nonce: u32) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            destination: 1234,
+            ..Default::default()
+        }
+    }
+
+    fn count(tracker: &UnknownDestinationTracker) -> u64 {
+        tracker
+            .messages_count
+            .with_label_values(&["arbitrum", "1234"])
+            .get()
+    }
+
+    fn origin_db(db: hyperlane_base::db::DB) -> HyperlaneRocksDB {
+        HyperlaneRocksDB::new(&HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum), db)
+    }
+
+    #[tokio::test]
+    async fn test_ignore_and_count_dont_park() {
+        test_utils::run_test_db(|db| async move {
+            let db = origin_db(db);
+
+            let ignoring = tracker(UnknownDestinationPolicy::Ignore);
+            ignoring.record(&db, &message(0));
+            assert_eq!(count(&ignoring), 0);
+            assert!(ignoring.parked_messages().is_empty());
+
+            let counting = tracker(UnknownDestinationPolicy::Count);
+            counting.record(&db, &message(0));
+            counting.record(&db, &message(1));
+            assert_eq!(count(&counting), 2);
+            assert!(counting.parked_messages().is_empty());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_parked_messages_expire() {
+        test_utils::run_test_db(|db| async move {
+            let db = origin_db(db);
+            let tracker = tracker(UnknownDestinationPolicy::Park {
+                ttl: Duration::from_secs(60),
+            });
+            let start = 1000;
+            tracker.record_at(&db, &message(0), start);
+            tracker.record_at(&db, &message(1), start + 30);
+            // Seeing a message again doesn't extend its TTL
+            tracker.record_at(&db, &message(0), start + 40);
+            assert_eq!(count(&tracker), 3);
+
+            let parked = tracker.parked_messages_at(start + 50);
+            assert_eq!(
+                parked
+                    .iter()
+                    .map(|m| (m.nonce, m.seconds_until_expiry))
+                    .collect::<Vec<_>>(),
+                vec![(0, 10), (1, 40)]
+            );
+            assert_eq!(parked[0].id, message(0).id());
+            assert_eq!(parked[0].origin, "arbitrum");
+            assert_eq!(parked[0].destination_domain_id, 1234);
+
+            let parked = tracker.parked_messages_at(start + 60);
+            assert_eq!(parked.iter().map(|m| m.nonce).collect::<Vec<_>>(), vec![1]);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_expiry_survives_restarts() {
+        test_utils::run_test_db(|db| async move {
+            let db = origin_db(db);
+            let policy = UnknownDestinationPolicy::Park {
+                ttl: Duration::from_secs(60),
+            };
+            let start = 1000;
+            tracker(policy).record_at(&db, &message(0), start);
+
+            // After a restart, the message keeps its original expiry
+            let restarted = tracker(policy);
+            restarted.record_at(&db, &message(0), start + 40);
+            assert_eq!(
+                restarted.parked_messages_at(start + 40)[0].seconds_until_expiry,
+                20
+            );
+            assert_eq!(db.retrieve_processed_by_nonce(&0).unwrap(), None);
+
+            // Once it expired, it's marked as processed rather than parked
+            let restarted = tracker(policy);
+            restarted.record_at(&db, &message(0), start + 60);
+            assert!(restarted.parked_messages_at(start + 60).is_empty());
+            assert_eq!(db.retrieve_processed_by_nonce(&0).unwrap(), Some(true));
+        })
+        .await;
+    }
+}
@@ -1,12 +1,16 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, panic::AssertUnwindSafe};
 
 use async_trait::async_trait;
 use derive_new::new;
 use eyre::Result;
+use futures_util::FutureExt;
 use hyperlane_core::HyperlaneDomain;
+use prometheus::IntCounter;
 use tokio::task::JoinHandle;
 use tokio_metrics::TaskMonitor;
-use tracing::{instrument, warn};
+use tracing::{error, instrument, warn};
+
+use crate::supervisor::{panic_message, RESTART_DELAY};
 
 #[async_trait]
 pub trait ProcessorExt: Send + Debug {
@@ -22,6 +26,9 @@ pub trait ProcessorExt: Send + Debug {
 pub struct Processor {
     ticker: Box<dyn ProcessorExt>,
     task_monitor: TaskMonitor,
+    /// Counts the ticks that panicked, after which the processor carries on
+    /// with the next tick instead of stopping
+    panics: IntCounter,
 }
 
 impl Processor {
@@ -35,9 +42,20 @@ impl Processor {
     #[instrument(ret, skip(self), level = "info", fields(domain=%self.ticker.domain()))]
     async fn main_loop(mut self) {
         loop {
-            if let Err(err) = self.ticker.tick().await {
-                warn!(error=%err, "Error in processor tick");
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            match AssertUnwindSafe(self.ticker.tick()).catch_unwind().await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    warn!(error=%err, "Error in processor tick");
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                }
+                Err(panic) => {
+                    self.panics.inc();
+                    error!(
+                        panic = panic_message(&*panic),
+                        "Processor tick panicked, carrying on with the next one"
+                    );
+                    tokio::time::sleep(RESTART_DELAY).await;
+                }
             }
         }
     }
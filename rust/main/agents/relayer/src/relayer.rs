@@ -2,16 +2,21 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{Debug, Formatter},
     sync::Arc,
+    time::Duration,
 };
 
 use async_trait::async_trait;
 use derive_more::AsRef;
 use eyre::Result;
-use futures_util::future::try_join_all;
+use futures_util::{
+    future::{try_join_all, BoxFuture},
+    FutureExt,
+};
+use prometheus::IntCounter;
 use tokio::{
     sync::{
         broadcast::Sender as BroadcastSender,
-        mpsc::{self, Receiver as MpscReceiver, UnboundedSender},
+        mpsc::{self, Receiver as MpscReceiver, UnboundedReceiver, UnboundedSender},
         RwLock,
     },
     task::JoinHandle,
@@ -21,40 +26,74 @@ use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument
 
 use hyperlane_base::{
     broadcast::BroadcastMpscSender,
-    db::{HyperlaneRocksDB, DB},
+    db::HyperlaneRocksDB,
     metrics::{AgentMetrics, ChainSpecificMetricsUpdater},
-    settings::{ChainConf, IndexSettings},
-    AgentMetadata, BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics,
-    HyperlaneAgentCore, RuntimeMetrics, SyncOptions,
+    self_test_fetch_checkpoint, self_test_sign_checkpoint,
+    settings::{reload_settings_on_sighup, ChainConf, ChainConnectionConf, IndexSettings},
+    AgentMetadata, BalanceMonitor, BaseAgent, ChainMetrics, CheckpointBatchCache,
+    ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore, Retrier, RuntimeMetrics,
+    SelfTestReport, SignerBalanceFloors, SyncOptions, ValidatorAnnounceIndex,
+    BALANCE_MONITOR_INTERVAL,
 };
 use hyperlane_core::{
-    rpc_clients::call_and_retry_n_times, ChainCommunicationError, ContractSyncCursor,
-    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, Mailbox, MerkleTreeInsertion,
-    QueueOperation, ValidatorAnnounce, H512, U256,
+    rpc_clients::RetryPolicy, ContractSyncCursor, HyperlaneDomain, HyperlaneMessage,
+    InterchainGasPayment, Mailbox, MerkleTreeInsertion, QueueOperation, ValidatorAnnounce, H512,
+    U256,
 };
+use hyperlane_ethereum::Signers;
 use hyperlane_operation_verifier::ApplicationOperationVerifier;
 
 use crate::{
+    alerts::AlertSink,
+    chain_startup::{
+        ChainStartup, LazyMailbox, LazyValidatorAnnounce, CHAIN_COMPONENT_RETRY_POLICY,
+    },
+    igp_claim::{IgpClaimMetrics, IgpClaimer},
+    igp_gas_oracle::{GasOracleDriftChecker, GasOracleMetrics, GasOracleReference},
     merkle_tree::builder::MerkleTreeBuilder,
     msg::{
+        app_context_budget::{AppContextSpendMetrics, AppContextSpendTracker},
         blacklist::AddressBlacklist,
+        deliverability_probe::{
+            DeliverabilityProbe, DeliverabilityProbeMetrics, PROBE_ESTIMATED_GAS_BUCKETS,
+        },
+        delivery_status::DeliveryStatusBatcher,
+        destination_domain::DestinationDomainCache,
+        destination_pause::DestinationPauseTracker,
+        evidence_bundle::EvidenceCollector,
+        funding_pool::{FundingPoolMetrics, FundingScheduler, FUNDING_POOL_REBALANCE_INTERVAL},
         gas_payment::GasPaymentEnforcer,
+        log_dedup::LogDeduplicator,
+        message_expiry::MessageExpiry,
         metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
         op_submitter::{SerialSubmitter, SerialSubmitterMetrics},
         pending_message::{MessageContext, MessageSubmissionMetrics},
+        pre_transaction::PreTransactionHooks,
+        preflight::PreflightSimulation,
         processor::{MessageProcessor, MessageProcessorMetrics},
+        recipient_code_hash::RecipientCodeHashFilter,
+        remote_filter_list::{FilterListKind, RemoteFilterList},
+        route_matrix::RouteMatrix,
+        runtime_config::RuntimeConfig,
+        shadow_mode::ShadowMode,
+        simulation_limiter::{SimulationLimiter, SIMULATION_PERMIT_WAIT_SECONDS_BUCKETS},
+        unknown_destination::UnknownDestinationTracker,
     },
     server::{self as relayer_server},
-    settings::{matching_list::MatchingList, RelayerSettings},
+    settings::{matching_list::MatchingList, GasOracleDriftConf, ParkingLotConf, RelayerSettings},
+    status_webhook::MessageStatusNotifier,
 };
 use crate::{
     merkle_tree::processor::{MerkleTreeProcessor, MerkleTreeProcessorMetrics},
     processor::ProcessorExt,
 };
-use crate::{processor::Processor, server::ENDPOINT_MESSAGES_QUEUE_SIZE};
+use crate::{processor::Processor, server::ENDPOINT_MESSAGES_QUEUE_SIZE, supervisor::supervise};
 
 const CURSOR_BUILDING_ERROR: &str = "Error building cursor for origin";
-const CURSOR_INSTANTIATION_ATTEMPTS: usize = 10;
+/// Retries of building the cursor of a sync, before reporting a critical
+/// error and starting over
+const CURSOR_INSTANTIATION_RETRY_POLICY: RetryPolicy =
+    RetryPolicy::exponential(Duration::from_secs(2), Duration::from_secs(30), 10);
 
 #[derive(Debug, Hash, PartialEq, Eq, Copy, Clone)]
 struct ContextKey {
@@ -78,14 +117,56 @@ pub struct Relayer {
     prover_syncs: HashMap<HyperlaneDomain, Arc<RwLock<MerkleTreeBuilder>>>,
     merkle_tree_hook_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<MerkleTreeInsertion>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
-    message_whitelist: Arc<MatchingList>,
-    message_blacklist: Arc<MatchingList>,
-    address_blacklist: Arc<AddressBlacklist>,
+    /// Config that can be reloaded at runtime by sending the relayer a SIGHUP
+    runtime_config: Arc<RuntimeConfig>,
+    /// Message filters kept in sync with lists published at URLs
+    remote_filter_lists: Vec<RemoteFilterList>,
+    gas_payment_enforcers: HashMap<HyperlaneDomain, Arc<GasPaymentEnforcer>>,
     transaction_gas_limit: Option<U256>,
     skip_transaction_gas_limit_for: HashSet<u32>,
     allow_local_checkpoint_syncers: bool,
     metric_app_contexts: Vec<(MatchingList, String)>,
     max_retries: u32,
+    parking_lot: Option<ParkingLotConf>,
+    log_deduplicator: Option<Arc<LogDeduplicator>>,
+    alert_sink: Option<Arc<AlertSink>>,
+    /// Set in probe mode, in which messages are prepared but never submitted
+    deliverability_probe: Option<Arc<DeliverabilityProbe>>,
+    /// Destination domain ids in shadow mode
+    shadow_chains: HashSet<u32>,
+    /// Set if any destination is in shadow mode, in which messages are
+    /// prepared until another relayer delivers them but never submitted
+    shadow_mode: Option<Arc<ShadowMode>>,
+    /// Handles messages to destinations the relayer doesn't deliver to
+    unknown_destinations: Arc<UnknownDestinationTracker>,
+    /// Set if any message expiry policy is configured
+    message_expiry: Option<Arc<MessageExpiry>>,
+    /// After how many consecutive failed submissions to a destination an
+    /// evidence bundle is collected, if set
+    evidence_bundle_failure_threshold: Option<u32>,
+    /// Floors of the signer balance by destination domain id
+    signer_balance_floor_confs: HashMap<u32, U256>,
+    /// Which destinations have a signer balance below its floor
+    signer_balance_floors: Arc<SignerBalanceFloors>,
+    /// Which origin to destination routes messages are delivered on
+    route_matrix: Arc<RouteMatrix>,
+    /// The payments held by the IGPs from which they're claimed, by origin
+    /// domain id
+    igp_claim_thresholds: HashMap<u32, U256>,
+    /// How often the claimable IGP payments are checked
+    igp_claim_interval: Duration,
+    igp_claim_metrics: IgpClaimMetrics,
+    /// Checks of the gas oracles of the origins' IGPs, if enabled
+    gas_oracle_drift: Option<GasOracleDriftConf>,
+    gas_oracle_metrics: GasOracleMetrics,
+    /// Storage locations announced by the validators of each origin
+    validator_announce_indexes: HashMap<HyperlaneDomain, Arc<ValidatorAnnounceIndex>>,
+    /// How often the announced storage locations are indexed, if enabled
+    validator_announce_index_interval: Option<Duration>,
+    /// Set if any funding pool is configured
+    funding_scheduler: Option<Arc<FundingScheduler>>,
+    /// The components of each chain that didn't start yet
+    chain_startup: ChainStartup,
     core_metrics: Arc<CoreMetrics>,
     // TODO: decide whether to consolidate `agent_metrics` and `chain_metrics` into a single struct
     // or move them in `core_metrics`, like the validator metrics
@@ -100,12 +181,10 @@ impl Debug for Relayer {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, message_whitelist: {:?}, message_blacklist: {:?}, address_blacklist: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?} }}",
+            "Relayer {{ origin_chains: {:?}, destination_chains: {:?}, runtime_config: {:?}, transaction_gas_limit: {:?}, skip_transaction_gas_limit_for: {:?}, allow_local_checkpoint_syncers: {:?} }}",
             self.origin_chains,
             self.destination_chains,
-            self.message_whitelist,
-            self.message_blacklist,
-            self.address_blacklist,
+            self.runtime_config,
             self.transaction_gas_limit,
             self.skip_transaction_gas_limit_for,
             self.allow_local_checkpoint_syncers
@@ -132,22 +211,45 @@ impl BaseAgent for Relayer {
     where
         Self: Sized,
     {
+        settings
+            .verify_contract_deployments(
+                settings.origin_chains.union(&settings.destination_chains),
+                &core_metrics,
+            )
+            .await?;
         let core = settings.build_hyperlane_core(core_metrics.clone());
-        let db = DB::from_path(&settings.db)?;
+        let db = settings.open_db(&settings.db)?;
+        core_metrics.health().set_db(db.clone());
         let dbs = settings
             .origin_chains
             .iter()
             .map(|origin| (origin.clone(), HyperlaneRocksDB::new(origin, db.clone())))
             .collect::<HashMap<_, _>>();
+        let persistent_metrics_db = settings.persistent_metrics.then(|| db.clone());
 
         let application_operation_verifiers =
             Self::build_application_operation_verifiers(&settings, &core_metrics, &chain_metrics)
                 .await;
 
-        let mailboxes = Self::build_mailboxes(&settings, &core_metrics, &chain_metrics).await;
+        let chain_startup = ChainStartup::new(
+            core_metrics.health(),
+            core_metrics.new_int_gauge(
+                "chain_components_starting",
+                "Components of a chain that didn't start yet, e.g. contracts that couldn't be built or cursors that couldn't be instantiated because its RPC is unreachable",
+                &["chain"],
+            )?,
+        );
 
-        let validator_announces =
-            Self::build_validator_announces(&settings, &core_metrics, &chain_metrics).await;
+        let mailboxes =
+            Self::build_mailboxes(&settings, &core_metrics, &chain_metrics, &chain_startup).await;
+
+        let validator_announces = Self::build_validator_announces(
+            &settings,
+            &core_metrics,
+            &chain_metrics,
+            &chain_startup,
+        )
+        .await;
 
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&core_metrics));
 
@@ -196,20 +298,55 @@ impl BaseAgent for Relayer {
             .map(|(k, v)| (k, v as _))
             .collect();
 
-        let message_whitelist = Arc::new(settings.whitelist);
-        let message_blacklist = Arc::new(settings.blacklist);
-        let address_blacklist = Arc::new(AddressBlacklist::new(settings.address_blacklist));
         let skip_transaction_gas_limit_for = settings.skip_transaction_gas_limit_for;
         let transaction_gas_limit = settings.transaction_gas_limit;
 
         info!(
-            %message_whitelist,
-            %message_blacklist,
-            ?address_blacklist,
+            message_whitelist = %settings.whitelist,
+            message_blacklist = %settings.blacklist,
+            remote_filter_lists = ?settings.remote_filter_lists,
+            address_blacklist = ?settings.address_blacklist,
+            paused_chains = ?settings.paused_chains,
             ?transaction_gas_limit,
             ?skip_transaction_gas_limit_for,
+            route_matrix = ?settings.route_matrix,
             "Whitelist configuration"
         );
+        let runtime_config = Arc::new(RuntimeConfig::new(
+            settings.whitelist,
+            settings.blacklist,
+            AddressBlacklist::new(settings.address_blacklist),
+            settings.paused_chains,
+        ));
+        let remote_filter_lists = match &settings.remote_filter_lists {
+            Some(conf) => {
+                let fetches = core_metrics.new_int_counter(
+                    "remote_filter_list_fetches_total",
+                    "Fetches of message filter lists from their URL, by list and outcome",
+                    &["list", "outcome"],
+                )?;
+                [
+                    (FilterListKind::Whitelist, &conf.whitelist_url),
+                    (FilterListKind::Blacklist, &conf.blacklist_url),
+                ]
+                .into_iter()
+                .filter_map(|(kind, url)| {
+                    url.clone().map(|url| {
+                        RemoteFilterList::new(
+                            kind,
+                            url,
+                            conf.signer,
+                            conf.poll_interval,
+                            runtime_config.clone(),
+                            fetches.clone(),
+                            Some(db.clone()),
+                        )
+                    })
+                })
+                .collect()
+            }
+            None => vec![],
+        };
 
         // provers by origin chain
         let prover_syncs = settings
@@ -223,7 +360,11 @@ impl BaseAgent for Relayer {
             })
             .collect::<HashMap<_, _>>();
 
-        info!(gas_enforcement_policies=?settings.gas_payment_enforcement, "Gas enforcement configuration");
+        info!(
+            gas_enforcement_policies=?settings.gas_payment_enforcement,
+            igp_fee_token_exchange_rates=?settings.igp_fee_token_exchange_rates,
+            "Gas enforcement configuration"
+        );
 
         // need one of these per origin chain due to the database scoping even though
         // the config itself is the same
@@ -231,14 +372,214 @@ impl BaseAgent for Relayer {
             .origin_chains
             .iter()
             .map(|domain| {
-                (
-                    domain.clone(),
-                    Arc::new(GasPaymentEnforcer::new(
-                        settings.gas_payment_enforcement.clone(),
-                        dbs.get(domain).unwrap().clone(),
-                    )),
+                let enforcer = GasPaymentEnforcer::new(
+                    settings.gas_payment_enforcement.clone(),
+                    dbs.get(domain).unwrap().clone(),
+                );
+                enforcer.set_fee_token_exchange_rate(
+                    settings
+                        .igp_fee_token_exchange_rates
+                        .get(&domain.id())
+                        .cloned(),
+                );
+                (domain.clone(), Arc::new(enforcer))
+            })
+            .collect();
+
+        let log_deduplicator = settings
+            .log_deduplication_window
+            .map(|window| Arc::new(LogDeduplicator::new(window)));
+
+        let alert_sink = settings
+            .alerts
+            .clone()
+            .map(|conf| Arc::new(AlertSink::new(conf)));
+
+        let message_status_notifier = settings.message_status_webhook.clone().map(|conf| {
+            info!(url = %conf.url, "Sending message status changes to webhook");
+            Arc::new(MessageStatusNotifier::new(conf))
+        });
+
+        let deliverability_probe = if settings.probe_mode {
+            warn!("Running in probe mode, messages are prepared but never submitted");
+            Some(Arc::new(DeliverabilityProbe::new(
+                DeliverabilityProbeMetrics {
+                    outcomes: core_metrics.new_int_counter(
+                        "deliverability_probe_outcomes",
+                        "Outcomes of preparing messages in probe mode, e.g. `deliverable` or the reason they can't be delivered",
+                        &["app_context", "remote", "outcome"],
+                    )?,
+                    estimated_gas: core_metrics.new_histogram(
+                        "deliverability_probe_estimated_gas",
+                        "Gas estimated for delivering deliverable messages in probe mode",
+                        &["app_context", "remote"],
+                        PROBE_ESTIMATED_GAS_BUCKETS.to_vec(),
+                    )?,
+                },
+            )))
+        } else {
+            None
+        };
+
+        let shadow_mode = if settings.shadow_chains.is_empty() {
+            None
+        } else {
+            warn!(
+                shadow_chains = ?settings.shadow_chains,
+                "Running destinations in shadow mode, messages to them are prepared but never submitted"
+            );
+            Some(Arc::new(ShadowMode::new(core_metrics.new_int_counter(
+                "shadow_mode_divergences",
+                "Messages whose delivery diverged from the outcome of preparing them in shadow mode, by destination and kind",
+                &["remote", "kind"],
+            )?)))
+        };
+
+        let unknown_destinations = Arc::new(UnknownDestinationTracker::new(
+            settings.unknown_destination_policy,
+            core_metrics.new_int_counter(
+                "unknown_destination_messages",
+                "Messages to destinations the relayer doesn't deliver to, by destination domain id",
+                &["origin", "remote"],
+            )?,
+        ));
+
+        let message_expiry = if settings.message_expiry.is_empty() {
+            None
+        } else {
+            Some(Arc::new(MessageExpiry::new(
+                settings.message_expiry.clone(),
+                core_metrics.new_int_counter(
+                    "messages_expired",
+                    "Messages moved to the dead-letter state because they weren't delivered before their TTL elapsed",
+                    &["origin", "remote"],
+                )?,
+            )))
+        };
+
+        let app_context_spend_metrics = AppContextSpendMetrics {
+            daily_spend: core_metrics.new_gauge(
+                "app_context_daily_spend",
+                "Destination native tokens spent today on delivering the messages of an app context",
+                &["app_context", "remote"],
+            )?,
+            budget_exceeded: core_metrics.new_int_gauge(
+                "app_context_budget_exceeded",
+                "Whether an app context spent more than its daily budget today, pausing delivery of its messages",
+                &["app_context", "remote"],
+            )?,
+        };
+
+        let simulation_permit_wait_seconds = core_metrics.new_histogram(
+            "simulation_permit_wait_seconds",
+            "Time spent waiting for a permit to run a simulation against a destination, e.g. an ISM call or a gas estimation",
+            &["remote"],
+            SIMULATION_PERMIT_WAIT_SECONDS_BUCKETS.to_vec(),
+        )?;
+
+        let destination_paused = core_metrics.new_int_gauge(
+            "destination_paused",
+            "Number of recipients on a destination whose deliveries are deferred because a contract they go through is paused",
+            &["remote"],
+        )?;
+
+        let preflight_simulation_failures = settings
+            .preflight_simulation
+            .then(|| {
+                core_metrics.new_int_counter(
+                    "preflight_simulation_failures",
+                    "Failed simulations of message deliveries by class: `ism_verification_failed`, `recipient_reverted`, `out_of_gas` or `other`",
+                    &["remote", "class"],
                 )
             })
+            .transpose()?;
+
+        let pre_transaction_hooks = (!settings.pre_transactions.is_empty()).then(|| {
+            Arc::new(PreTransactionHooks::new(
+                settings.pre_transactions.clone(),
+                core_metrics.retry_metrics(),
+            ))
+        });
+
+        let signer_balance_floors = Arc::new(SignerBalanceFloors::default());
+        let halted_below_floor = settings
+            .halt_below_signer_balance_floor
+            .then(|| signer_balance_floors.clone());
+
+        let route_matrix = Arc::new(RouteMatrix::new(
+            settings.route_matrix,
+            core_metrics.new_int_counter(
+                "route_disabled_skipped_messages",
+                "Messages skipped because their route is disabled, by origin and destination",
+                &["origin", "remote"],
+            )?,
+        ));
+
+        let igp_claim_metrics = IgpClaimMetrics {
+            claimable_balance: core_metrics.new_gauge(
+                "igp_claimable_balance",
+                "Payments held by the IGP of an origin when last checked, in its native token",
+                &["chain"],
+            )?,
+            claims: core_metrics.new_int_counter(
+                "igp_claims",
+                "Claims of the payments held by the IGP of an origin, by status: `claimed`, `reverted` or `failed`",
+                &["chain", "status"],
+            )?,
+        };
+
+        let gas_oracle_metrics = GasOracleMetrics {
+            value: core_metrics.new_gauge(
+                "igp_gas_oracle_value",
+                "Values of the gas oracle of an origin's IGP for a destination, by value: `gas_price` or `token_exchange_rate`",
+                &["origin", "remote", "value"],
+            )?,
+            drift: core_metrics.new_gauge(
+                "igp_gas_oracle_drift",
+                "Relative difference between the values of the gas oracle of an origin's IGP for a destination and their references",
+                &["origin", "remote", "value"],
+            )?,
+        };
+
+        let funding_scheduler = if settings.funding_pools.is_empty() {
+            None
+        } else {
+            Some(Arc::new(FundingScheduler::new(
+                settings.funding_pools.clone(),
+                &db,
+                FundingPoolMetrics {
+                    allowance: core_metrics.new_gauge(
+                        "funding_pool_allowance",
+                        "What a destination may spend in the current period of its funding pool, in pool units",
+                        &["pool", "remote"],
+                    )?,
+                    spend: core_metrics.new_gauge(
+                        "funding_pool_spend",
+                        "What a destination spent in the current period of its funding pool, in pool units",
+                        &["pool", "remote"],
+                    )?,
+                },
+            )))
+        };
+
+        let backoff_policy = Arc::new(settings.message_backoff.clone());
+
+        // Validators' checkpoint batches are fetched once for all origins and destinations
+        let checkpoint_batch_cache = Arc::new(CheckpointBatchCache::default());
+
+        // The storage locations announced on an origin are shared by all
+        // destinations
+        let validator_announce_indexes: HashMap<_, _> = validator_announces
+            .iter()
+            .map(|(origin, validator_announce)| {
+                let index = ValidatorAnnounceIndex::new(
+                    validator_announce.clone(),
+                    dbs.get(origin).unwrap().clone(),
+                    settings.storage_locations_ttl,
+                    settings.allow_local_checkpoint_syncers,
+                );
+                (origin.clone(), Arc::new(index))
+            })
             .collect();
 
         let mut msg_ctxs = HashMap::new();
@@ -255,23 +596,82 @@ impl BaseAgent for Relayer {
                     transaction_gas_limit
                 };
 
+            let max_metadata_size = settings
+                .metadata_size_limits
+                .get(&destination.id())
+                .copied();
+
             let application_operation_verifier = application_operation_verifiers.get(destination);
 
+            // Spend is tracked per destination, since it's denominated in the destination's token
+            let app_context_spend_tracker = Arc::new(AppContextSpendTracker::new(
+                destination.clone(),
+                Arc::new(HyperlaneRocksDB::new(destination, db.clone())),
+                settings
+                    .app_context_budgets
+                    .get(&destination.id())
+                    .cloned()
+                    .unwrap_or_default(),
+                app_context_spend_metrics.clone(),
+            ));
+
+            // The destination mailbox domain is fetched once and shared by all origins
+            let destination_domain_cache = Arc::new(DestinationDomainCache::default());
+
+            // Delivery checks of all origins are confirmed in the same queue, so
+            // they are batched together
+            let delivery_status_batcher = Arc::new(DeliveryStatusBatcher::default());
+
+            // Simulations of all origins hit the same destination RPCs, so they
+            // share the limit
+            let simulation_limiter = Arc::new(SimulationLimiter::new(
+                settings
+                    .max_concurrent_simulations
+                    .get(&destination.id())
+                    .copied(),
+                simulation_permit_wait_seconds.with_label_values(&[destination.name()]),
+            ));
+
+            // A paused destination is paused for all origins
+            let destination_pause = Arc::new(DestinationPauseTracker::new(
+                destination_paused.with_label_values(&[destination.name()]),
+            ));
+
+            let preflight_simulation = preflight_simulation_failures.as_ref().map(|failures| {
+                Arc::new(PreflightSimulation::new(
+                    destination.name(),
+                    failures.clone(),
+                ))
+            });
+
+            // Code hashes are cached per destination, so the filter is shared by all origins
+            let recipient_code_hash_filter = (settings.recipient_code_hash_allowlist.is_some()
+                || !settings.recipient_code_hash_denylist.is_empty())
+            .then(|| {
+                Arc::new(RecipientCodeHashFilter::new(
+                    settings.recipient_code_hash_allowlist.clone(),
+                    settings.recipient_code_hash_denylist.clone(),
+                ))
+            });
+
             // only iterate through origin chains that were successfully instantiated
-            for (origin, validator_announce) in validator_announces.iter() {
+            for (origin, validator_announce_index) in validator_announce_indexes.iter() {
                 let db = dbs.get(origin).unwrap().clone();
+                let origin_chain_setup = core.settings.chain_setup(origin).unwrap().clone();
                 let metadata_builder = BaseMetadataBuilder::new(
-                    origin.clone(),
+                    origin_chain_setup,
                     destination_chain_setup.clone(),
                     prover_syncs[origin].clone(),
-                    validator_announce.clone(),
-                    settings.allow_local_checkpoint_syncers,
+                    validator_announce_index.clone(),
                     core.metrics.clone(),
                     db,
                     IsmAwareAppContextClassifier::new(
                         dest_mailbox.clone(),
                         settings.metric_app_contexts.clone(),
                     ),
+                    max_metadata_size,
+                    simulation_limiter.clone(),
+                    checkpoint_batch_cache.clone(),
                 );
 
                 msg_ctxs.insert(
@@ -285,8 +685,28 @@ impl BaseAgent for Relayer {
                         metadata_builder: Arc::new(metadata_builder),
                         origin_gas_payment_enforcer: gas_payment_enforcers[origin].clone(),
                         transaction_gas_limit,
-                        metrics: MessageSubmissionMetrics::new(&core_metrics, origin, destination),
+                        metrics: MessageSubmissionMetrics::new(
+                            &core_metrics,
+                            origin,
+                            destination,
+                            persistent_metrics_db.clone(),
+                        ),
                         application_operation_verifier: application_operation_verifier.cloned(),
+                        log_deduplicator: log_deduplicator.clone(),
+                        recipient_code_hash_filter: recipient_code_hash_filter.clone(),
+                        runtime_config: runtime_config.clone(),
+                        app_context_spend_tracker: app_context_spend_tracker.clone(),
+                        destination_domain_cache: destination_domain_cache.clone(),
+                        delivery_status_batcher: delivery_status_batcher.clone(),
+                        simulation_limiter: simulation_limiter.clone(),
+                        destination_pause: destination_pause.clone(),
+                        preflight_simulation: preflight_simulation.clone(),
+                        pre_transaction_hooks: pre_transaction_hooks.clone(),
+                        signer_balance_floors: halted_below_floor.clone(),
+                        message_status_notifier: message_status_notifier.clone(),
+                        message_expiry: message_expiry.clone(),
+                        funding_scheduler: funding_scheduler.clone(),
+                        backoff_policy: backoff_policy.clone(),
                     }),
                 );
             }
@@ -302,14 +722,35 @@ impl BaseAgent for Relayer {
             interchain_gas_payment_syncs,
             prover_syncs,
             merkle_tree_hook_syncs,
-            message_whitelist,
-            message_blacklist,
-            address_blacklist,
+            runtime_config,
+            remote_filter_lists,
+            gas_payment_enforcers,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers: settings.allow_local_checkpoint_syncers,
             metric_app_contexts: settings.metric_app_contexts,
             max_retries: settings.max_retries,
+            parking_lot: settings.parking_lot,
+            log_deduplicator,
+            alert_sink,
+            deliverability_probe,
+            shadow_chains: settings.shadow_chains,
+            shadow_mode,
+            unknown_destinations,
+            message_expiry,
+            evidence_bundle_failure_threshold: settings.evidence_bundle_failure_threshold,
+            signer_balance_floor_confs: settings.signer_balance_floors,
+            signer_balance_floors,
+            route_matrix,
+            igp_claim_thresholds: settings.igp_claim_thresholds,
+            igp_claim_interval: settings.igp_claim_interval,
+            igp_claim_metrics,
+            gas_oracle_drift: settings.gas_oracle_drift,
+            validator_announce_indexes,
+            validator_announce_index_interval: settings.validator_announce_index_interval,
+            gas_oracle_metrics,
+            funding_scheduler,
+            chain_startup,
             core_metrics,
             agent_metrics,
             chain_metrics,
@@ -337,6 +778,7 @@ impl BaseAgent for Relayer {
         // send channels by destination chain
         let mut send_channels = HashMap::with_capacity(self.destination_chains.len());
         let mut prep_queues = HashMap::with_capacity(self.destination_chains.len());
+        let mut parked_queues = HashMap::with_capacity(self.destination_chains.len());
         for (dest_domain, dest_conf) in &self.destination_chains {
             let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
             send_channels.insert(dest_domain.id(), send_channel);
@@ -352,8 +794,23 @@ impl BaseAgent for Relayer {
                     .map(|c| c.max_batch_size)
                     .unwrap_or(1),
                 task_monitor.clone(),
+                self.parking_lot,
+                self.alert_sink.clone(),
+                self.deliverability_probe.clone(),
+                self.shadow_mode
+                    .clone()
+                    .filter(|_| self.shadow_chains.contains(&dest_domain.id())),
+                self.evidence_bundle_failure_threshold.map(|threshold| {
+                    Arc::new(EvidenceCollector::new(
+                        dest_domain.clone(),
+                        threshold,
+                        dest_conf,
+                        self.core.metrics.client_metrics().request_count,
+                    ))
+                }),
             );
             prep_queues.insert(dest_domain.id(), serial_submitter.prepare_queue().await);
+            parked_queues.insert(dest_domain.id(), serial_submitter.parked_queue().await);
 
             tasks.push(self.run_destination_submitter(
                 dest_domain,
@@ -373,37 +830,104 @@ impl BaseAgent for Relayer {
                 panic!("Error creating metrics updater for destination {dest_domain}")
             });
             tasks.push(metrics_updater.spawn());
+
+            if let Some(alert_sink) = &self.alert_sink {
+                let chain_checks = alert_sink
+                    .clone()
+                    .spawn_chain_checks(dest_conf, Self::AGENT_NAME.to_string(), &self.core_metrics)
+                    .await
+                    .unwrap_or_else(|_| {
+                        panic!("Error creating alert checks for destination {dest_domain}")
+                    });
+                tasks.extend(chain_checks);
+            }
+
+            if let Some(floor) = self.signer_balance_floor_confs.get(&dest_domain.id()) {
+                let balance_monitor = BalanceMonitor::new(
+                    dest_conf,
+                    Self::AGENT_NAME.to_string(),
+                    *floor,
+                    self.signer_balance_floors.clone(),
+                    &self.core_metrics,
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    panic!("Error creating balance monitor for destination {dest_domain}")
+                });
+                match balance_monitor {
+                    Some(balance_monitor) => {
+                        tasks.push(balance_monitor.spawn(BALANCE_MONITOR_INTERVAL))
+                    }
+                    None => warn!(
+                        destination = %dest_domain,
+                        "No signer on the destination, its balance floor is ignored"
+                    ),
+                }
+            }
+        }
+
+        for origin in &self.origin_chains {
+            if let Some(threshold) = self.igp_claim_thresholds.get(&origin.id()) {
+                tasks.push(self.run_igp_claimer(origin, *threshold));
+            }
+        }
+
+        if let Some(conf) = &self.gas_oracle_drift {
+            for origin in &self.origin_chains {
+                tasks.push(self.run_gas_oracle_drift_checker(origin, conf));
+            }
+        }
+
+        if let Some(interval) = self.validator_announce_index_interval {
+            for (origin, index) in &self.validator_announce_indexes {
+                let span = info_span!("ValidatorAnnounceIndex", chain = %origin);
+                tasks.push(tokio::spawn(index.clone().run(interval)).instrument(span));
+            }
         }
 
+        // The syncs instantiate their cursors in their own task, so that an
+        // unreachable origin doesn't hold up the others
         for origin in &self.origin_chains {
             self.chain_metrics.set_critical_error(origin.name(), false);
             let maybe_broadcaster = self
                 .message_syncs
                 .get(origin)
                 .and_then(|sync| sync.get_broadcaster());
-            tasks.push(self.run_message_sync(origin, task_monitor.clone()).await);
-            tasks.push(
-                self.run_interchain_gas_payment_sync(
-                    origin,
-                    BroadcastMpscSender::map_get_receiver(maybe_broadcaster.as_ref()).await,
-                    task_monitor.clone(),
-                )
-                .await,
-            );
+            tasks.push(self.run_message_sync(origin, task_monitor.clone()));
+            tasks.push(self.run_interchain_gas_payment_sync(
+                origin,
+                maybe_broadcaster.clone(),
+                BroadcastMpscSender::map_get_receiver(maybe_broadcaster.as_ref()).await,
+                task_monitor.clone(),
+            ));
+            tasks.push(self.run_merkle_tree_hook_syncs(
+                origin,
+                maybe_broadcaster.clone(),
+                BroadcastMpscSender::map_get_receiver(maybe_broadcaster.as_ref()).await,
+                task_monitor.clone(),
+            ));
+        }
+        if let Some(funding_scheduler) = &self.funding_scheduler {
             tasks.push(
-                self.run_merkle_tree_hook_syncs(
-                    origin,
-                    BroadcastMpscSender::map_get_receiver(maybe_broadcaster.as_ref()).await,
-                    task_monitor.clone(),
-                )
-                .await,
+                funding_scheduler
+                    .clone()
+                    .spawn(prep_queues.clone(), FUNDING_POOL_REBALANCE_INTERVAL),
             );
         }
         // run server
-        let custom_routes = relayer_server::Server::new(self.destination_chains.len())
+        let mut relayer_server = relayer_server::Server::new(self.destination_chains.len())
             .with_op_retry(sender.clone())
             .with_message_queue(prep_queues)
-            .routes();
+            .with_parked_queue(parked_queues)
+            .with_unknown_destinations(self.unknown_destinations.clone())
+            .with_route_matrix(self.route_matrix.clone());
+        if let Some(shadow_mode) = &self.shadow_mode {
+            relayer_server = relayer_server.with_shadow_mode(shadow_mode.clone());
+        }
+        if let Some(message_expiry) = &self.message_expiry {
+            relayer_server = relayer_server.with_message_expiry(message_expiry.clone());
+        }
+        let custom_routes = relayer_server.routes();
 
         let server = self
             .core
@@ -425,6 +949,34 @@ impl BaseAgent for Relayer {
             tasks.push(self.run_merkle_tree_processor(origin, task_monitor.clone()));
         }
 
+        if let Some(log_deduplicator) = self.log_deduplicator.clone() {
+            let summaries_task =
+                self.run_log_deduplication_summaries(log_deduplicator, task_monitor.clone());
+            tasks.push(summaries_task);
+        }
+
+        for remote_filter_list in self.remote_filter_lists.drain(..) {
+            let remote_filter_list_task = tokio::spawn(TaskMonitor::instrument(
+                &task_monitor,
+                remote_filter_list.run(),
+            ))
+            .instrument(info_span!("RemoteFilterList"));
+            tasks.push(remote_filter_list_task);
+        }
+
+        match reload_settings_on_sighup::<RelayerSettings>() {
+            Ok((reloaded_settings, reloader_task)) => {
+                tasks.push(reloader_task.instrument(info_span!("SettingsReloader")));
+                tasks.push(
+                    self.run_reloaded_settings_applier(reloaded_settings, task_monitor.clone()),
+                );
+            }
+            Err(err) => error!(
+                ?err,
+                "Failed to listen for SIGHUP, settings can't be reloaded"
+            ),
+        }
+
         tasks.push(self.runtime_metrics.spawn());
 
         if let Err(err) = try_join_all(tasks).await {
@@ -434,66 +986,134 @@ impl BaseAgent for Relayer {
             );
         }
     }
+
+    /// Signs the synthetic checkpoint with the signer of every EVM
+    /// destination, then fetches a synthetic checkpoint and recovers its
+    /// signer like validators' checkpoints are
+    async fn self_test(self) -> SelfTestReport {
+        let mut report = SelfTestReport::default();
+
+        let mut destinations: Vec<_> = self.destination_chains.iter().collect();
+        destinations.sort_by_key(|(domain, _)| domain.id());
+        for (domain, conf) in destinations {
+            if !matches!(conf.connection, ChainConnectionConf::Ethereum(_)) {
+                continue;
+            }
+            let Some(signer_conf) = &conf.signer else {
+                continue;
+            };
+            let result = async {
+                let signer: Signers = signer_conf.build().await?;
+                self_test_sign_checkpoint(&signer, domain).await
+            }
+            .await;
+            report.record(&format!("signer_{}", domain.name()), result);
+        }
+
+        if let Some(origin) = self.origin_chains.iter().min_by_key(|domain| domain.id()) {
+            report.record("checkpoint_fetch", self_test_fetch_checkpoint(origin).await);
+        }
+        report
+    }
 }
 
 impl Relayer {
-    fn record_critical_error(
+    /// Makes the futures that instantiate the cursor of a sync of the origin,
+    /// retrying until it succeeds, one for every time the sync is started.
+    /// The cursor is reported as a starting component of the origin until
+    /// then, and as a critical error whenever the retry policy gives up on it.
+    fn instantiate_cursor_with_retries<T: 'static>(
         &self,
         origin: &HyperlaneDomain,
-        err: ChainCommunicationError,
-        message: &str,
-    ) {
-        error!(?err, origin=?origin, "{message}");
-        self.chain_metrics.set_critical_error(origin.name(), true);
-    }
-
-    async fn instantiate_cursor_with_retries<T: 'static>(
         contract_sync: Arc<dyn ContractSyncer<T>>,
         index_settings: IndexSettings,
-    ) -> Result<Box<dyn ContractSyncCursor<T>>, ChainCommunicationError> {
-        call_and_retry_n_times(
-            || {
-                let contract_sync = contract_sync.clone();
-                let index_settings = index_settings.clone();
-                Box::pin(async move {
-                    let cursor = contract_sync.cursor(index_settings).await?;
-                    Ok(cursor)
-                })
-            },
-            CURSOR_INSTANTIATION_ATTEMPTS,
-        )
-        .await
+        label: &str,
+    ) -> impl Fn() -> BoxFuture<'static, Box<dyn ContractSyncCursor<T>>> + Send + 'static {
+        let retrier = Retrier::new(
+            CURSOR_INSTANTIATION_RETRY_POLICY,
+            &format!("relayer_cursor_{label}"),
+            &self.core_metrics,
+        );
+        let component = format!("{label}_cursor");
+        let origin = origin.clone();
+        let chain_startup = self.chain_startup.clone();
+        let chain_metrics = self.chain_metrics.clone();
+        chain_startup.starting(&origin, &component);
+        move || {
+            let retrier = retrier.clone();
+            let contract_sync = contract_sync.clone();
+            let index_settings = index_settings.clone();
+            let origin = origin.clone();
+            let component = component.clone();
+            let chain_startup = chain_startup.clone();
+            let chain_metrics = chain_metrics.clone();
+            async move {
+                loop {
+                    let cursor_instantiation_result = retrier
+                        .call(|| {
+                            let contract_sync = contract_sync.clone();
+                            let index_settings = index_settings.clone();
+                            Box::pin(async move {
+                                let cursor = contract_sync.cursor(index_settings).await?;
+                                Ok(cursor)
+                            })
+                        })
+                        .await;
+                    match cursor_instantiation_result {
+                        Ok(cursor) => {
+                            chain_startup.started(&origin, &component);
+                            return cursor;
+                        }
+                        Err(err) => {
+                            error!(?err, origin=?origin, "{CURSOR_BUILDING_ERROR}");
+                            chain_metrics.set_critical_error(origin.name(), true);
+                        }
+                    }
+                }
+            }
+            .boxed()
+        }
+    }
+
+    /// Counts the panics of a task of the origin
+    fn task_panics(&self, task: &str, origin: &HyperlaneDomain) -> IntCounter {
+        self.core_metrics
+            .task_panics()
+            .with_label_values(&[task, origin.name()])
     }
 
-    async fn run_message_sync(
+    fn run_message_sync(
         &self,
         origin: &HyperlaneDomain,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
         let contract_sync = self.message_syncs.get(origin).unwrap().clone();
-        let cursor_instantiation_result =
-            Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings.clone())
-                .await;
-        let cursor = match cursor_instantiation_result {
-            Ok(cursor) => cursor,
-            Err(err) => {
-                self.record_critical_error(origin, err, CURSOR_BUILDING_ERROR);
-                return tokio::spawn(async {}).instrument(info_span!("MessageSync"));
-            }
-        };
+        let label = "dispatched_messages";
+        let instantiate_cursor = self.instantiate_cursor_with_retries(
+            origin,
+            contract_sync.clone(),
+            index_settings,
+            label,
+        );
+        let panics = self.task_panics(label, origin);
         let origin_name = origin.name().to_string();
         tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
-            let label = "dispatched_messages";
-            contract_sync.clone().sync(label, cursor.into()).await;
+            supervise(label, panics, || {
+                let cursor = instantiate_cursor();
+                let contract_sync = contract_sync.clone();
+                async move { contract_sync.sync(label, cursor.await.into()).await }
+            })
+            .await;
             info!(chain = origin_name, label, "contract sync task exit");
         }))
         .instrument(info_span!("MessageSync"))
     }
 
-    async fn run_interchain_gas_payment_sync(
+    fn run_interchain_gas_payment_sync(
         &self,
         origin: &HyperlaneDomain,
+        tx_id_broadcaster: Option<BroadcastMpscSender<H512>>,
         tx_id_receiver: Option<MpscReceiver<H512>>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
@@ -503,58 +1123,200 @@ impl Relayer {
             .get(origin)
             .unwrap()
             .clone();
-        let cursor_instantiation_result =
-            Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings.clone())
-                .await;
-        let cursor = match cursor_instantiation_result {
-            Ok(cursor) => cursor,
-            Err(err) => {
-                self.record_critical_error(origin, err, CURSOR_BUILDING_ERROR);
-                return tokio::spawn(async {}).instrument(info_span!("IgpSync"));
-            }
-        };
+        let label = "gas_payments";
+        let instantiate_cursor = self.instantiate_cursor_with_retries(
+            origin,
+            contract_sync.clone(),
+            index_settings,
+            label,
+        );
+        let panics = self.task_panics(label, origin);
         let origin_name = origin.name().to_string();
         tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
-            let label = "gas_payments";
-            contract_sync
-                .clone()
-                .sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
-                .await;
+            let mut tx_id_receiver = Some(tx_id_receiver);
+            supervise(label, panics, || {
+                let cursor = instantiate_cursor();
+                let contract_sync = contract_sync.clone();
+                let tx_id_receiver = tx_id_receiver.take();
+                let tx_id_broadcaster = tx_id_broadcaster.clone();
+                async move {
+                    let tx_id_receiver = match tx_id_receiver {
+                        Some(tx_id_receiver) => tx_id_receiver,
+                        None => {
+                            BroadcastMpscSender::map_get_receiver(tx_id_broadcaster.as_ref()).await
+                        }
+                    };
+                    contract_sync
+                        .sync(label, SyncOptions::new(Some(cursor.await), tx_id_receiver))
+                        .await
+                }
+            })
+            .await;
             info!(chain = origin_name, label, "contract sync task exit");
         }))
         .instrument(info_span!("IgpSync"))
     }
 
-    async fn run_merkle_tree_hook_syncs(
+    fn run_merkle_tree_hook_syncs(
         &self,
         origin: &HyperlaneDomain,
+        tx_id_broadcaster: Option<BroadcastMpscSender<H512>>,
         tx_id_receiver: Option<MpscReceiver<H512>>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index.clone();
         let contract_sync = self.merkle_tree_hook_syncs.get(origin).unwrap().clone();
-        let cursor_instantiation_result =
-            Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings.clone())
-                .await;
-        let cursor = match cursor_instantiation_result {
-            Ok(cursor) => cursor,
-            Err(err) => {
-                self.record_critical_error(origin, err, CURSOR_BUILDING_ERROR);
-                return tokio::spawn(async {}).instrument(info_span!("MerkleTreeHookSync"));
-            }
-        };
+        let label = "merkle_tree_hook";
+        let instantiate_cursor = self.instantiate_cursor_with_retries(
+            origin,
+            contract_sync.clone(),
+            index_settings,
+            label,
+        );
+        let panics = self.task_panics(label, origin);
         let origin_name = origin.name().to_string();
         tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
-            let label = "merkle_tree_hook";
-            contract_sync
-                .clone()
-                .sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
-                .await;
+            let mut tx_id_receiver = Some(tx_id_receiver);
+            supervise(label, panics, || {
+                let cursor = instantiate_cursor();
+                let contract_sync = contract_sync.clone();
+                let tx_id_receiver = tx_id_receiver.take();
+                let tx_id_broadcaster = tx_id_broadcaster.clone();
+                async move {
+                    let tx_id_receiver = match tx_id_receiver {
+                        Some(tx_id_receiver) => tx_id_receiver,
+                        None => {
+                            BroadcastMpscSender::map_get_receiver(tx_id_broadcaster.as_ref()).await
+                        }
+                    };
+                    contract_sync
+                        .sync(label, SyncOptions::new(Some(cursor.await), tx_id_receiver))
+                        .await
+                }
+            })
+            .await;
             info!(chain = origin_name, label, "contract sync task exit");
         }))
         .instrument(info_span!("MerkleTreeHookSync"))
     }
 
+    /// Builds the IGP of the origin in the background, retrying until it
+    /// succeeds, then claims its payments
+    fn run_igp_claimer(
+        &self,
+        origin: &HyperlaneDomain,
+        threshold: U256,
+    ) -> Instrumented<JoinHandle<()>> {
+        let conf = self
+            .core
+            .settings
+            .chain_setup(origin)
+            .unwrap_or_else(|_| panic!("No chain setup for origin {origin}"))
+            .clone();
+        let core_metrics = self.core_metrics.clone();
+        let retrier = Retrier::new(
+            CHAIN_COMPONENT_RETRY_POLICY,
+            "relayer_build_igp",
+            &core_metrics,
+        );
+        let igp_claim_metrics = self.igp_claim_metrics.clone();
+        let igp_claim_interval = self.igp_claim_interval;
+        let chain_startup = self.chain_startup.clone();
+        let origin = origin.clone();
+        let span = info_span!("IgpClaimer", chain = %origin);
+        chain_startup.starting(&origin, "igp_claimer");
+        tokio::spawn(async move {
+            let igp = retrier
+                .call_until_success(|| {
+                    let conf = conf.clone();
+                    let core_metrics = core_metrics.clone();
+                    Box::pin(async move {
+                        Ok(conf.build_interchain_gas_paymaster(&core_metrics).await?)
+                    })
+                })
+                .await;
+            chain_startup.started(&origin, "igp_claimer");
+            IgpClaimer::new(igp, threshold, igp_claim_metrics)
+                .run(igp_claim_interval)
+                .await;
+        })
+        .instrument(span)
+    }
+
+    /// Builds the IGP of the origin and the providers of the destinations in
+    /// the background, retrying until it succeeds, then checks the IGP's gas
+    /// oracle for each destination
+    fn run_gas_oracle_drift_checker(
+        &self,
+        origin: &HyperlaneDomain,
+        conf: &GasOracleDriftConf,
+    ) -> Instrumented<JoinHandle<()>> {
+        let origin_conf = self
+            .core
+            .settings
+            .chain_setup(origin)
+            .unwrap_or_else(|_| panic!("No chain setup for origin {origin}"))
+            .clone();
+        let destinations: Vec<_> = self
+            .destination_chains
+            .iter()
+            .filter(|(destination, _)| *destination != origin)
+            .map(|(destination, dest_conf)| {
+                let token_exchange_rate = conf
+                    .token_exchange_rates
+                    .get(&(origin.id(), destination.id()))
+                    .copied();
+                (destination.clone(), dest_conf.clone(), token_exchange_rate)
+            })
+            .collect();
+        let core_metrics = self.core_metrics.clone();
+        let retrier = Retrier::new(
+            CHAIN_COMPONENT_RETRY_POLICY,
+            "relayer_build_gas_oracle_drift_checker",
+            &core_metrics,
+        );
+        let gas_oracle_metrics = self.gas_oracle_metrics.clone();
+        let alert_sink = self.alert_sink.clone();
+        let (threshold, interval) = (conf.threshold, conf.interval);
+        let chain_startup = self.chain_startup.clone();
+        let origin = origin.clone();
+        let span = info_span!("GasOracleDriftChecker", chain = %origin);
+        chain_startup.starting(&origin, "gas_oracle_drift_checker");
+        tokio::spawn(async move {
+            let igp = retrier
+                .call_until_success(|| {
+                    let conf = origin_conf.clone();
+                    let core_metrics = core_metrics.clone();
+                    Box::pin(async move {
+                        Ok(conf.build_interchain_gas_paymaster(&core_metrics).await?)
+                    })
+                })
+                .await;
+            let references = retrier
+                .call_until_success(|| {
+                    let destinations = destinations.clone();
+                    let core_metrics = core_metrics.clone();
+                    Box::pin(async move {
+                        let mut references = Vec::with_capacity(destinations.len());
+                        for (destination, dest_conf, token_exchange_rate) in destinations {
+                            references.push(GasOracleReference {
+                                destination,
+                                provider: dest_conf.build_provider(&core_metrics).await?,
+                                token_exchange_rate,
+                            });
+                        }
+                        Ok(references)
+                    })
+                })
+                .await;
+            chain_startup.started(&origin, "gas_oracle_drift_checker");
+            GasOracleDriftChecker::new(igp, references, threshold, gas_oracle_metrics, alert_sink)
+                .run(interval)
+                .await;
+        })
+        .instrument(span)
+    }
+
     fn run_message_processor(
         &self,
         origin: &HyperlaneDomain,
@@ -583,18 +1345,22 @@ impl Relayer {
 
         let message_processor = MessageProcessor::new(
             self.dbs.get(origin).unwrap().clone(),
-            self.message_whitelist.clone(),
-            self.message_blacklist.clone(),
-            self.address_blacklist.clone(),
+            self.runtime_config.clone(),
             metrics,
             send_channels,
             destination_ctxs,
             self.metric_app_contexts.clone(),
             self.max_retries,
+            self.unknown_destinations.clone(),
+            self.route_matrix.clone(),
         );
 
         let span = info_span!("MessageProcessor", origin=%message_processor.domain());
-        let processor = Processor::new(Box::new(message_processor), task_monitor.clone());
+        let processor = Processor::new(
+            Box::new(message_processor),
+            task_monitor.clone(),
+            self.task_panics("message_processor", origin),
+        );
 
         processor.spawn().instrument(span)
     }
@@ -612,10 +1378,74 @@ impl Relayer {
         );
 
         let span = info_span!("MerkleTreeProcessor", origin=%merkle_tree_processor.domain());
-        let processor = Processor::new(Box::new(merkle_tree_processor), task_monitor.clone());
+        let processor = Processor::new(
+            Box::new(merkle_tree_processor),
+            task_monitor.clone(),
+            self.task_panics("merkle_tree_processor", origin),
+        );
         processor.spawn().instrument(span)
     }
 
+    fn run_log_deduplication_summaries(
+        &self,
+        log_deduplicator: Arc<LogDeduplicator>,
+        task_monitor: TaskMonitor,
+    ) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("LogDeduplicationSummaries");
+        tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+            let mut interval = tokio::time::interval(log_deduplicator.window());
+            loop {
+                interval.tick().await;
+                log_deduplicator.log_summaries();
+            }
+        }))
+        .instrument(span)
+    }
+
+    /// Applies the runtime-configurable parts of reloaded settings. Changes to
+    /// any other settings require a restart.
+    fn run_reloaded_settings_applier(
+        &self,
+        mut reloaded_settings: UnboundedReceiver<RelayerSettings>,
+        task_monitor: TaskMonitor,
+    ) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("ReloadedSettingsApplier");
+        let runtime_config = self.runtime_config.clone();
+        let gas_payment_enforcers = self.gas_payment_enforcers.clone();
+        let route_matrix = self.route_matrix.clone();
+        tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+            while let Some(settings) = reloaded_settings.recv().await {
+                info!(
+                    message_whitelist = %settings.whitelist,
+                    message_blacklist = %settings.blacklist,
+                    address_blacklist = ?settings.address_blacklist,
+                    paused_chains = ?settings.paused_chains,
+                    gas_enforcement_policies = ?settings.gas_payment_enforcement,
+                    igp_fee_token_exchange_rates = ?settings.igp_fee_token_exchange_rates,
+                    route_matrix = ?settings.route_matrix,
+                    "Applying reloaded settings"
+                );
+                for (domain, enforcer) in gas_payment_enforcers.iter() {
+                    enforcer.set_policies(settings.gas_payment_enforcement.clone());
+                    enforcer.set_fee_token_exchange_rate(
+                        settings
+                            .igp_fee_token_exchange_rates
+                            .get(&domain.id())
+                            .cloned(),
+                    );
+                }
+                runtime_config.update(
+                    settings.whitelist,
+                    settings.blacklist,
+                    AddressBlacklist::new(settings.address_blacklist),
+                    settings.paused_chains,
+                );
+                route_matrix.replace(settings.route_matrix);
+            }
+        }))
+        .instrument(span)
+    }
+
     #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(skip(self, serial_submitter))]
     fn run_destination_submitter(
@@ -639,51 +1469,96 @@ impl Relayer {
     }
 
     /// Helper function to build and return a hashmap of mailboxes.
-    /// Any chains that fail to build mailbox will not be included
-    /// in the hashmap. Errors will be logged and chain metrics
-    /// will be updated for chains that fail to build mailbox.
+    /// Mailboxes that fail to build, e.g. because the chain is unreachable,
+    /// are built in the background instead. Chains without a setup, or whose
+    /// provider can't be built either, will not be included in the hashmap.
+    /// Errors will be logged and chain metrics will be updated for them.
     pub async fn build_mailboxes(
         settings: &RelayerSettings,
-        core_metrics: &CoreMetrics,
+        core_metrics: &Arc<CoreMetrics>,
         chain_metrics: &ChainMetrics,
+        chain_startup: &ChainStartup,
     ) -> HashMap<HyperlaneDomain, Arc<dyn Mailbox>> {
-        settings
+        let mut mailboxes = HashMap::new();
+        for (origin, mailbox_res) in settings
             .build_mailboxes(settings.destination_chains.iter(), core_metrics)
             .await
-            .into_iter()
-            .filter_map(|(origin, mailbox_res)| match mailbox_res {
-                Ok(mailbox) => Some((origin, mailbox)),
+        {
+            let mailbox_res = match mailbox_res {
+                Ok(mailbox) => Ok(mailbox),
+                Err(err) => match settings.chain_setup(&origin) {
+                    Ok(conf) => {
+                        warn!(?err, origin=?origin, "Error when building mailbox");
+                        LazyMailbox::build_in_background(
+                            conf,
+                            core_metrics.clone(),
+                            chain_startup.clone(),
+                        )
+                        .await
+                        .map(|mailbox| Arc::new(mailbox) as Arc<dyn Mailbox>)
+                    }
+                    Err(_) => Err(err),
+                },
+            };
+            match mailbox_res {
+                Ok(mailbox) => {
+                    mailboxes.insert(origin, mailbox);
+                }
                 Err(err) => {
                     error!(?err, origin=?origin, "Critical error when building mailbox");
                     chain_metrics.set_critical_error(origin.name(), true);
-                    None
                 }
-            })
-            .collect()
+            }
+        }
+        mailboxes
     }
 
     /// Helper function to build and return a hashmap of validator announces.
-    /// Any chains that fail to build validator announce will not be included
-    /// in the hashmap. Errors will be logged and chain metrics
-    /// will be updated for chains that fail to build validator announce.
+    /// Validator announces that fail to build, e.g. because the chain is
+    /// unreachable, are built in the background instead. Chains without a
+    /// setup, or whose provider can't be built either, will not be included
+    /// in the hashmap. Errors will be logged and chain metrics will be
+    /// updated for them.
     pub async fn build_validator_announces(
         settings: &RelayerSettings,
-        core_metrics: &CoreMetrics,
+        core_metrics: &Arc<CoreMetrics>,
         chain_metrics: &ChainMetrics,
+        chain_startup: &ChainStartup,
     ) -> HashMap<HyperlaneDomain, Arc<dyn ValidatorAnnounce>> {
-        settings
+        let mut validator_announces = HashMap::new();
+        for (origin, validator_announce_res) in settings
             .build_validator_announces(settings.origin_chains.iter(), core_metrics)
             .await
-            .into_iter()
-            .filter_map(|(origin, mailbox_res)| match mailbox_res {
-                Ok(mailbox) => Some((origin, mailbox)),
+        {
+            let validator_announce_res = match validator_announce_res {
+                Ok(validator_announce) => Ok(validator_announce),
+                Err(err) => match settings.chain_setup(&origin) {
+                    Ok(conf) => {
+                        warn!(?err, origin=?origin, "Error when building validator announce");
+                        LazyValidatorAnnounce::build_in_background(
+                            conf,
+                            core_metrics.clone(),
+                            chain_startup.clone(),
+                        )
+                        .await
+                        .map(|validator_announce| {
+                            Arc::new(validator_announce) as Arc<dyn ValidatorAnnounce>
+                        })
+                    }
+                    Err(_) => Err(err),
+                },
+            };
+            match validator_announce_res {
+                Ok(validator_announce) => {
+                    validator_announces.insert(origin, validator_announce);
+                }
                 Err(err) => {
                     error!(?err, origin=?origin, "Critical error when building validator announce");
                     chain_metrics.set_critical_error(origin.name(), true);
-                    None
                 }
-            })
-            .collect()
+            }
+        }
+        validator_announces
     }
 
     /// Helper function to build and return a hashmap of application operation verifiers.
@@ -716,9 +1591,14 @@ mod test {
     use std::{
         collections::{HashMap, HashSet},
         path::PathBuf,
+        sync::Arc,
+        time::Duration,
     };
 
-    use crate::settings::{matching_list::MatchingList, RelayerSettings};
+    use crate::{
+        chain_startup::ChainStartup,
+        settings::{matching_list::MatchingList, RelayerSettings},
+    };
     use ethers::utils::hex;
     use ethers_prometheus::middleware::PrometheusMiddlewareConf;
     use hyperlane_base::{
@@ -731,7 +1611,7 @@ mod test {
     };
     use hyperlane_core::{
         config::OperationBatchConfig, HyperlaneDomain, IndexMode, KnownHyperlaneDomain,
-        ReorgPeriod, H256,
+        NativeToken, ReorgPeriod, H256,
     };
     use hyperlane_ethereum as h_eth;
     use prometheus::{opts, IntGaugeVec, Registry};
@@ -746,6 +1626,7 @@ mod test {
             ChainConf {
                 domain: HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
                 signer: None,
+                additional_signers: vec![],
                 reorg_period: ReorgPeriod::None,
                 addresses: CoreContractAddresses {
                     mailbox: H256::from_slice(
@@ -791,6 +1672,15 @@ mod test {
                         batch_contract_address: None,
                         max_batch_size: 1,
                     },
+                    native_token: NativeToken {
+                        decimals: 18,
+                        ..Default::default()
+                    },
+                    rpc_rate_limiter: None,
+                    fee_history_cache: None,
+                    log_query_batcher: None,
+                    storage_locations_cache: None,
+                    read_batcher: None,
                 }),
                 metrics_conf: PrometheusMiddlewareConf {
                     contracts: HashMap::new(),
@@ -800,7 +1690,9 @@ mod test {
                     from: 0,
                     chunk_size: 1,
                     mode: IndexMode::Block,
+                    block_time: None,
                 },
+                hash_algorithm: Default::default(),
             },
         )];
 
@@ -808,7 +1700,14 @@ mod test {
             base: Settings {
                 chains: chains.into_iter().collect(),
                 metrics_port: 5000,
+                persistent_metrics: false,
+                enable_profiling: false,
+                db_backup_dir: None,
+                restore_db_from: None,
                 tracing: TracingConfig::default(),
+                self_test: false,
+                verify_deployments: false,
+                histogram_buckets: HashMap::new(),
             },
             db: PathBuf::new(),
             origin_chains: [
@@ -828,22 +1727,58 @@ mod test {
             gas_payment_enforcement: Vec::new(),
             whitelist: MatchingList::default(),
             blacklist: MatchingList::default(),
+            remote_filter_lists: None,
             address_blacklist: Vec::new(),
             transaction_gas_limit: None,
             skip_transaction_gas_limit_for: HashSet::new(),
+            paused_chains: HashSet::new(),
+            metadata_size_limits: HashMap::new(),
+            max_concurrent_simulations: HashMap::new(),
             allow_local_checkpoint_syncers: true,
             metric_app_contexts: Vec::new(),
+            app_context_budgets: HashMap::new(),
             max_retries: 1,
+            parking_lot: None,
+            log_deduplication_window: None,
+            alerts: None,
+            message_status_webhook: None,
+            recipient_code_hash_allowlist: None,
+            recipient_code_hash_denylist: HashSet::new(),
+            probe_mode: false,
+            preflight_simulation: false,
+            unknown_destination_policy: Default::default(),
+            pre_transactions: vec![],
+            signer_balance_floors: HashMap::new(),
+            halt_below_signer_balance_floor: false,
+            route_matrix: HashMap::new(),
+            igp_fee_token_exchange_rates: HashMap::new(),
+            shadow_chains: HashSet::new(),
+            message_expiry: vec![],
+            evidence_bundle_failure_threshold: None,
+            message_backoff: Default::default(),
+            igp_claim_thresholds: HashMap::new(),
+            igp_claim_interval: Duration::from_secs(60),
+            funding_pools: vec![],
+            gas_oracle_drift: None,
+            storage_locations_ttl: Duration::from_secs(600),
+            validator_announce_index_interval: None,
         }
     }
 
+    fn test_chain_startup(core_metrics: &CoreMetrics) -> ChainStartup {
+        ChainStartup::new(
+            core_metrics.health(),
+            IntGaugeVec::new(opts!("chain_components_starting", "help"), &["chain"]).unwrap(),
+        )
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_failed_build_mailboxes() {
         let settings = generate_test_relayer_settings();
 
         let registry = Registry::new();
-        let core_metrics = CoreMetrics::new("relayer", 4000, registry).unwrap();
+        let core_metrics = Arc::new(CoreMetrics::new("relayer", 4000, registry).unwrap());
         let chain_metrics = ChainMetrics {
             block_height: IntGaugeVec::new(
                 opts!("block_height", BLOCK_HEIGHT_HELP),
@@ -858,7 +1793,11 @@ mod test {
             .unwrap(),
         };
 
-        let mailboxes = Relayer::build_mailboxes(&settings, &core_metrics, &chain_metrics).await;
+        let chain_startup = test_chain_startup(&core_metrics);
+
+        let mailboxes =
+            Relayer::build_mailboxes(&settings, &core_metrics, &chain_metrics, &chain_startup)
+                .await;
 
         assert_eq!(mailboxes.len(), 1);
         assert!(mailboxes.contains_key(&HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum)));
@@ -891,7 +1830,7 @@ mod test {
         let settings = generate_test_relayer_settings();
 
         let registry = Registry::new();
-        let core_metrics = CoreMetrics::new("relayer", 4000, registry).unwrap();
+        let core_metrics = Arc::new(CoreMetrics::new("relayer", 4000, registry).unwrap());
         let chain_metrics = ChainMetrics {
             block_height: IntGaugeVec::new(
                 opts!("block_height", BLOCK_HEIGHT_HELP),
@@ -906,8 +1845,15 @@ mod test {
             .unwrap(),
         };
 
-        let mailboxes =
-            Relayer::build_validator_announces(&settings, &core_metrics, &chain_metrics).await;
+        let chain_startup = test_chain_startup(&core_metrics);
+
+        let mailboxes = Relayer::build_validator_announces(
+            &settings,
+            &core_metrics,
+            &chain_metrics,
+            &chain_startup,
+        )
+        .await;
 
         assert_eq!(mailboxes.len(), 1);
         assert!(mailboxes.contains_key(&HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum)));
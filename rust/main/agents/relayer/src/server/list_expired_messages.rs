@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing, Json, Router};
+use derive_new::new;
+
+use crate::msg::message_expiry::{ExpiredMessage, MessageExpiry};
+
+const LIST_EXPIRED_MESSAGES_API_BASE: &str = "/list_expired_messages";
+
+/// Lists the most recent messages moved to the dead-letter state because
+/// they weren't delivered before their TTL elapsed
+#[derive(new, Clone)]
+pub struct ListExpiredMessagesApi {
+    message_expiry: Arc<MessageExpiry>,
+}
+
+async fn list_expired_messages(
+    State(message_expiry): State<Arc<MessageExpiry>>,
+) -> Json<Vec<ExpiredMessage>> {
+    Json(message_expiry.expired_messages())
+}
+
+impl ListExpiredMessagesApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_expired_messages))
+            .with_state(self.message_expiry.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (LIST_EXPIRED_MESSAGES_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::http::StatusCode;
+    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
+    use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain};
+    use prometheus::{opts, IntCounterVec};
+
+    use crate::settings::MessageExpiryConf;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_expired_messages() {
+        test_utils::run_test_db(|db| async move {
+            let db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum), db);
+            let message_expiry = Arc::new(MessageExpiry::new(
+                vec![MessageExpiryConf {
+                    matching_list: Default::default(),
+                    ttl: Duration::from_secs(60),
+                }],
+                IntCounterVec::new(opts!("expired", "help"), &["origin", "remote"]).unwrap(),
+            ));
+            let message = HyperlaneMessage {
+                nonce: 7,
+                destination: 42161,
+                ..Default::default()
+            };
+            message_expiry.dead_letter(
+                &db,
+                &HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
+                &message,
+            );
+
+            let (path, router) = ListExpiredMessagesApi::new(message_expiry).get_route();
+            let app = Router::new().nest(path, router);
+            let server =
+                axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+            let addr = server.local_addr();
+            tokio::spawn(server);
+
+            let response = reqwest::get(format!("http://{}{}", addr, path))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let listed: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(listed.as_array().unwrap().len(), 1);
+            assert_eq!(listed[0]["id"], serde_json::json!(message.id()));
+            assert_eq!(listed[0]["destination_domain_id"], 42161);
+            assert_eq!(listed[0]["nonce"], 7);
+        })
+        .await;
+    }
+}
@@ -11,6 +11,7 @@ use std::collections::HashMap;
 use crate::msg::op_queue::OperationPriorityQueue;
 
 const LIST_OPERATIONS_API_BASE: &str = "/list_operations";
+const LIST_PARKED_OPERATIONS_API_BASE: &str = "/list_parked_operations";
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
 pub struct ListOperationsRequest {
@@ -77,6 +78,12 @@ impl ListOperationsApi {
     pub fn get_route(&self) -> (&'static str, Router) {
         (LIST_OPERATIONS_API_BASE, self.router())
     }
+
+    /// Same as `get_route`, but for queues of operations that were parked
+    /// after repeatedly failing to prepare
+    pub fn get_parked_route(&self) -> (&'static str, Router) {
+        (LIST_PARKED_OPERATIONS_API_BASE, self.router())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing, Router};
+use derive_new::new;
+
+use crate::msg::unknown_destination::UnknownDestinationTracker;
+
+const LIST_UNKNOWN_DESTINATION_MESSAGES_API_BASE: &str = "/list_unknown_destination_messages";
+
+/// Lists the messages to unknown destinations that are parked. Only the
+/// `park` unknown destination policy parks messages.
+#[derive(new, Clone)]
+pub struct ListUnknownDestinationMessagesApi {
+    unknown_destinations: Arc<UnknownDestinationTracker>,
+}
+
+async fn list_unknown_destination_messages(
+    State(unknown_destinations): State<Arc<UnknownDestinationTracker>>,
+) -> String {
+    match serde_json::to_string_pretty(&unknown_destinations.parked_messages()) {
+        Ok(s) => s,
+        Err(e) => format!("Error formatting messages: {}", e),
+    }
+}
+
+impl ListUnknownDestinationMessagesApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_unknown_destination_messages))
+            .with_state(self.unknown_destinations.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (LIST_UNKNOWN_DESTINATION_MESSAGES_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::http::StatusCode;
+    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
+    use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain};
+    use prometheus::{opts, IntCounterVec};
+
+    use crate::settings::UnknownDestinationPolicy;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_unknown_destination_messages() {
+        test_utils::run_test_db(|db| async move {
+            let db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum), db);
+            let unknown_destinations = Arc::new(UnknownDestinationTracker::new(
+                UnknownDestinationPolicy::Park {
+                    ttl: Duration::from_secs(3600),
+                },
+                IntCounterVec::new(opts!("count", "help"), &["origin", "remote"]).unwrap(),
+            ));
+            let message = HyperlaneMessage {
+                nonce: 7,
+                destination: 1234,
+                ..Default::default()
+            };
+            unknown_destinations.record(&db, &message);
+
+            let (path, router) =
+                ListUnknownDestinationMessagesApi::new(unknown_destinations).get_route();
+            let app = Router::new().nest(path, router);
+            let server =
+                axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+            let addr = server.local_addr();
+            tokio::spawn(server);
+
+            let response = reqwest::get(format!("http://{}{}", addr, path))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let listed: serde_json::Value = response.json().await.unwrap();
+            assert_eq!(listed.as_array().unwrap().len(), 1);
+            assert_eq!(listed[0]["id"], serde_json::json!(message.id()));
+            assert_eq!(listed[0]["origin"], "arbitrum");
+            assert_eq!(listed[0]["destination_domain_id"], 1234);
+            assert_eq!(listed[0]["nonce"], 7);
+        })
+        .await;
+    }
+}
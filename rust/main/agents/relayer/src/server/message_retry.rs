@@ -49,7 +49,7 @@ async fn retry_message(
 
     // Create a channel that can hold each chain's SerialSubmitter
     // message retry responses.
-    // 3 queues for each chain (prepare, submit, confirm)
+    // 4 queues for each chain (prepare, submit, confirm, parked)
     let (transmitter, mut receiver) =
         mpsc::channel(SUBMITTER_QUEUE_COUNT * state.destination_chains);
     state
@@ -1,17 +1,28 @@
 use axum::Router;
 use derive_new::new;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::broadcast::Sender;
 
-use crate::msg::op_queue::OperationPriorityQueue;
+use crate::msg::{
+    message_expiry::MessageExpiry, op_queue::OperationPriorityQueue, route_matrix::RouteMatrix,
+    shadow_mode::ShadowMode, unknown_destination::UnknownDestinationTracker,
+};
 
 pub const ENDPOINT_MESSAGES_QUEUE_SIZE: usize = 100;
 
+pub use list_expired_messages::*;
 pub use list_messages::*;
+pub use list_unknown_destinations::*;
 pub use message_retry::*;
+pub use route_matrix::*;
+pub use shadow_mode_report::*;
 
+mod list_expired_messages;
 mod list_messages;
+mod list_unknown_destinations;
 mod message_retry;
+mod route_matrix;
+mod shadow_mode_report;
 
 #[derive(new)]
 pub struct Server {
@@ -20,6 +31,16 @@ pub struct Server {
     retry_transmitter: Option<Sender<MessageRetryRequest>>,
     #[new(default)]
     op_queues: Option<HashMap<u32, OperationPriorityQueue>>,
+    #[new(default)]
+    parked_queues: Option<HashMap<u32, OperationPriorityQueue>>,
+    #[new(default)]
+    unknown_destinations: Option<Arc<UnknownDestinationTracker>>,
+    #[new(default)]
+    route_matrix: Option<Arc<RouteMatrix>>,
+    #[new(default)]
+    shadow_mode: Option<Arc<ShadowMode>>,
+    #[new(default)]
+    message_expiry: Option<Arc<MessageExpiry>>,
 }
 
 impl Server {
@@ -33,6 +54,37 @@ impl Server {
         self
     }
 
+    pub fn with_parked_queue(
+        mut self,
+        parked_queues: HashMap<u32, OperationPriorityQueue>,
+    ) -> Self {
+        self.parked_queues = Some(parked_queues);
+        self
+    }
+
+    pub fn with_unknown_destinations(
+        mut self,
+        unknown_destinations: Arc<UnknownDestinationTracker>,
+    ) -> Self {
+        self.unknown_destinations = Some(unknown_destinations);
+        self
+    }
+
+    pub fn with_route_matrix(mut self, route_matrix: Arc<RouteMatrix>) -> Self {
+        self.route_matrix = Some(route_matrix);
+        self
+    }
+
+    pub fn with_shadow_mode(mut self, shadow_mode: Arc<ShadowMode>) -> Self {
+        self.shadow_mode = Some(shadow_mode);
+        self
+    }
+
+    pub fn with_message_expiry(mut self, message_expiry: Arc<MessageExpiry>) -> Self {
+        self.message_expiry = Some(message_expiry);
+        self
+    }
+
     /// Returns a vector of agent-specific endpoint routes to be served.
     /// Can be extended with additional routes and feature flags to enable/disable individually.
     pub fn routes(self) -> Vec<(&'static str, Router)> {
@@ -43,6 +95,21 @@ impl Server {
         if let Some(op_queues) = self.op_queues {
             routes.push(ListOperationsApi::new(op_queues).get_route());
         }
+        if let Some(parked_queues) = self.parked_queues {
+            routes.push(ListOperationsApi::new(parked_queues).get_parked_route());
+        }
+        if let Some(unknown_destinations) = self.unknown_destinations {
+            routes.push(ListUnknownDestinationMessagesApi::new(unknown_destinations).get_route());
+        }
+        if let Some(route_matrix) = self.route_matrix {
+            routes.push(RouteMatrixApi::new(route_matrix).get_route());
+        }
+        if let Some(shadow_mode) = self.shadow_mode {
+            routes.push(ShadowModeReportApi::new(shadow_mode).get_route());
+        }
+        if let Some(message_expiry) = self.message_expiry {
+            routes.push(ListExpiredMessagesApi::new(message_expiry).get_route());
+        }
 
         routes
     }
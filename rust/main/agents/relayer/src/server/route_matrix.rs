@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing, Json, Router};
+use derive_new::new;
+
+use crate::msg::route_matrix::{RouteMatrix, RouteState};
+
+const ROUTE_MATRIX_API_BASE: &str = "/route_matrix";
+
+/// Lists the routes with an explicit state, and enables or disables routes
+/// until the settings are reloaded or the relayer restarts.
+///
+/// Routes are set by POSTing e.g. `{"origin": 1, "destination": 10, "enabled": false}`
+/// with domain ids.
+#[derive(new, Clone)]
+pub struct RouteMatrixApi {
+    route_matrix: Arc<RouteMatrix>,
+}
+
+async fn list_routes(State(route_matrix): State<Arc<RouteMatrix>>) -> Json<Vec<RouteState>> {
+    Json(route_matrix.routes())
+}
+
+async fn set_route(
+    State(route_matrix): State<Arc<RouteMatrix>>,
+    Json(route): Json<RouteState>,
+) -> Json<Vec<RouteState>> {
+    route_matrix.set(route);
+    Json(route_matrix.routes())
+}
+
+impl RouteMatrixApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_routes).post(set_route))
+            .with_state(self.route_matrix.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (ROUTE_MATRIX_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::http::StatusCode;
+    use prometheus::{opts, IntCounterVec};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_set_route() {
+        let route_matrix = Arc::new(RouteMatrix::new(
+            HashMap::new(),
+            IntCounterVec::new(opts!("skipped", "help"), &["origin", "remote"]).unwrap(),
+        ));
+
+        let (path, router) = RouteMatrixApi::new(route_matrix.clone()).get_route();
+        let app = Router::new().nest(path, router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let disabled = RouteState {
+            origin: 1,
+            destination: 10,
+            enabled: false,
+        };
+        let response = reqwest::Client::new()
+            .post(format!("http://{}{}", addr, path))
+            .json(&disabled)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!route_matrix.is_enabled(1, 10));
+
+        let routes: Vec<RouteState> = reqwest::get(format!("http://{}{}", addr, path))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(routes, vec![disabled]);
+    }
+}
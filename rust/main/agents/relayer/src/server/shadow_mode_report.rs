@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing, Json, Router};
+use derive_new::new;
+
+use crate::msg::shadow_mode::{ShadowMode, ShadowModeReport};
+
+const SHADOW_MODE_REPORT_API_BASE: &str = "/shadow_mode_report";
+
+/// Reports, for each destination in shadow mode, the outcomes of preparing
+/// messages and the messages whose delivery diverged from them
+#[derive(new, Clone)]
+pub struct ShadowModeReportApi {
+    shadow_mode: Arc<ShadowMode>,
+}
+
+async fn get_reports(State(shadow_mode): State<Arc<ShadowMode>>) -> Json<Vec<ShadowModeReport>> {
+    Json(shadow_mode.reports())
+}
+
+impl ShadowModeReportApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(get_reports))
+            .with_state(self.shadow_mode.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (SHADOW_MODE_REPORT_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use hyperlane_core::{
+        ConfirmReason, HyperlaneDomain, KnownHyperlaneDomain, PendingOperationResult,
+        QueueOperation, ReprepareReason,
+    };
+    use prometheus::{opts, IntCounterVec};
+
+    use crate::msg::op_queue::test::MockPendingOperation;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_shadow_mode_report() {
+        let shadow_mode = Arc::new(ShadowMode::new(
+            IntCounterVec::new(opts!("divergences", "help"), &["remote", "kind"]).unwrap(),
+        ));
+        let op: QueueOperation = Box::new(MockPendingOperation::new(
+            0,
+            HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
+        ));
+        shadow_mode.record(
+            &op,
+            &PendingOperationResult::Reprepare(ReprepareReason::ErrorEstimatingGas),
+        );
+        shadow_mode.record(
+            &op,
+            &PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted),
+        );
+
+        let (path, router) = ShadowModeReportApi::new(shadow_mode).get_route();
+        let app = Router::new().nest(path, router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let response = reqwest::get(format!("http://{}{}", addr, path))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let reports: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(reports[0]["destination"], "arbitrum");
+        assert_eq!(reports[0]["failed_to_prepare"], 1);
+        assert_eq!(
+            reports[0]["divergences"][0]["id"],
+            serde_json::json!(op.id())
+        );
+        assert_eq!(
+            reports[0]["divergences"][0]["kind"],
+            "delivered_but_not_prepared"
+        );
+    }
+}
@@ -4,7 +4,11 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
 
 use convert_case::Case;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
@@ -17,17 +21,54 @@ use hyperlane_base::{
         Settings,
     },
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, U256};
+use hyperlane_core::{
+    cfg_unwrap_all, config::*, FixedPointNumber, HyperlaneDomain, PreTransaction, H160, H256, U256,
+};
 use itertools::Itertools;
 use serde::Deserialize;
 use serde_json::Value;
 
 use crate::{
-    msg::pending_message::DEFAULT_MAX_MESSAGE_RETRIES, settings::matching_list::MatchingList,
+    msg::{
+        backoff::{BackoffPolicy, BackoffSchedule, FailureClass},
+        pending_message::DEFAULT_MAX_MESSAGE_RETRIES,
+    },
+    settings::matching_list::MatchingList,
 };
 
 pub mod matching_list;
 
+/// Default interval between attempts for parked operations, in seconds
+const DEFAULT_PARKED_RETRY_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Default time messages to unknown destinations stay parked, in seconds
+const DEFAULT_UNKNOWN_DESTINATION_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Default interval between polls of remote filter lists, in seconds
+const DEFAULT_FILTER_LIST_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Default minimum time between two alerts of the same kind for the same
+/// chain, in seconds
+const DEFAULT_ALERT_RATE_LIMIT_SECS: u64 = 60 * 60;
+
+/// Default number of attempts to send a message status to the webhook
+const DEFAULT_MESSAGE_STATUS_WEBHOOK_MAX_ATTEMPTS: usize = 5;
+
+/// Default interval between checks of the claimable IGP payments, in seconds
+const DEFAULT_IGP_CLAIM_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Default interval between checks of the gas oracles of the IGPs, in seconds
+const DEFAULT_GAS_ORACLE_DRIFT_INTERVAL_SECS: u64 = 10 * 60;
+
+/// Default period after which the spend of funding pools is reset, in seconds
+const DEFAULT_FUNDING_POOL_PERIOD_SECS: u64 = 24 * 60 * 60;
+
+/// Default time the storage locations announced by validators are used for
+/// before they're fetched again, in seconds
+const DEFAULT_STORAGE_LOCATIONS_TTL_SECS: u64 = 10 * 60;
+
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
 /// Settings for `Relayer`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
 pub struct RelayerSettings {
@@ -41,7 +82,8 @@ pub struct RelayerSettings {
     pub db: PathBuf,
     /// The chain to relay messages from
     pub origin_chains: HashSet<HyperlaneDomain>,
-    /// Chains to relay messages to
+    /// Chains to relay messages to. These are the submit-capable chains, they
+    /// all have a signer.
     pub destination_chains: HashSet<HyperlaneDomain>,
     /// The gas payment enforcement policies
     pub gas_payment_enforcement: Vec<GasPaymentEnforcementConf>,
@@ -49,6 +91,9 @@ pub struct RelayerSettings {
     pub whitelist: MatchingList,
     /// Filter for what messages to block.
     pub blacklist: MatchingList,
+    /// If set, the whitelist and/or blacklist are fetched from URLs and kept
+    /// in sync with them, taking precedence over the configured ones.
+    pub remote_filter_lists: Option<RemoteFilterListsConf>,
     /// Filter for what addresses to block interactions with.
     /// This is intentionally not an H256 to allow for addresses of any length without
     /// adding any padding.
@@ -58,13 +103,265 @@ pub struct RelayerSettings {
     pub transaction_gas_limit: Option<U256>,
     /// List of domain ids to skip transaction gas for.
     pub skip_transaction_gas_limit_for: HashSet<u32>,
+    /// Domain ids of chains that no messages are delivered to or from. Messages
+    /// of paused chains stay queued until the chain is unpaused.
+    pub paused_chains: HashSet<u32>,
+    /// Max size in bytes of the ISM metadata by destination domain id, for
+    /// chains that limit the size of transactions.
+    pub metadata_size_limits: HashMap<u32, usize>,
+    /// Max number of simulations, e.g. ISM calls and gas estimations, run
+    /// against a destination at the same time, by destination domain id.
+    /// Unlimited for destinations that aren't configured.
+    pub max_concurrent_simulations: HashMap<u32, usize>,
     /// If true, allows local storage based checkpoint syncers.
     /// Not intended for production use.
     pub allow_local_checkpoint_syncers: bool,
     /// App contexts used for metrics.
     pub metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Daily spend budgets of app contexts by destination domain id, in the
+    /// destination's native token. Messages of an app context that exceeded
+    /// its budget are not delivered until the next day.
+    pub app_context_budgets: HashMap<u32, HashMap<String, U256>>,
     /// Maximum number of retries per operation
     pub max_retries: u32,
+    /// If set, operations that keep failing to prepare are moved out of the
+    /// prepare queue and retried on a slower schedule.
+    pub parking_lot: Option<ParkingLotConf>,
+    /// If set, repeated warnings for the same message are only logged once
+    /// per window, with a summary of the suppressed occurrences.
+    pub log_deduplication_window: Option<Duration>,
+    /// If set, messages are only delivered to recipients whose code hash on the
+    /// destination is in this list.
+    pub recipient_code_hash_allowlist: Option<HashSet<H256>>,
+    /// Messages are never delivered to recipients whose code hash on the
+    /// destination is in this list.
+    pub recipient_code_hash_denylist: HashSet<H256>,
+    /// If set, critical conditions are sent to these webhooks.
+    pub alerts: Option<AlertConf>,
+    /// If set, the status changes of messages are POSTed to this webhook.
+    pub message_status_webhook: Option<MessageStatusWebhookConf>,
+    /// If true, messages are prepared, i.e. their metadata is built and their
+    /// delivery is estimated, but they are never submitted.
+    pub probe_mode: bool,
+    /// If true, failed simulations of deliveries are classified as ISM
+    /// verification failures, recipient reverts or running out of gas, each
+    /// counted and retried by class.
+    pub preflight_simulation: bool,
+    /// How messages to destinations the relayer doesn't deliver to are handled
+    pub unknown_destination_policy: UnknownDestinationPolicy,
+    /// Transactions submitted on the destination before the messages that
+    /// require them are delivered, e.g. to create the token account of the
+    /// recipient of a transfer
+    pub pre_transactions: Vec<PreTransactionConf>,
+    /// Floors of the signer balance by destination domain id, in the
+    /// destination's native token. The balance of each destination with a
+    /// floor is monitored.
+    pub signer_balance_floors: HashMap<u32, U256>,
+    /// If true, no messages are delivered to destinations whose signer
+    /// balance is below its floor. They stay queued until it's topped up.
+    pub halt_below_signer_balance_floor: bool,
+    /// Routes explicitly enabled or disabled, by origin and destination
+    /// domain id. Routes that aren't listed are enabled.
+    pub route_matrix: HashMap<(u32, u32), bool>,
+    /// For origins whose IGP charges in an ERC-20 token instead of the native
+    /// token, how many units of the native token a unit of the fee token is
+    /// worth, by origin domain id. Both are in their smallest denomination.
+    pub igp_fee_token_exchange_rates: HashMap<u32, FixedPointNumber>,
+    /// Destination domain ids in shadow mode, where messages are prepared
+    /// until another relayer delivers them but never submitted, and the
+    /// outcomes are compared with their delivery
+    pub shadow_chains: HashSet<u32>,
+    /// How long the messages matching each list are retried before they're
+    /// moved to a dead-letter state. The first matching list applies, and
+    /// messages that don't match any list are retried until `max_retries`.
+    pub message_expiry: Vec<MessageExpiryConf>,
+    /// After how many consecutive failed submissions to a destination an
+    /// evidence bundle is collected. Unset or 0 disables the collection.
+    pub evidence_bundle_failure_threshold: Option<u32>,
+    /// How long messages wait before being retried, by what their last
+    /// attempt failed on
+    pub message_backoff: BackoffPolicy,
+    /// The payments held by the IGP of an origin from which they're claimed
+    /// to its beneficiary, by origin domain id, in the origin's native token.
+    /// Only the IGPs of origins with a threshold are claimed.
+    pub igp_claim_thresholds: HashMap<u32, U256>,
+    /// How often the claimable IGP payments are checked
+    pub igp_claim_interval: Duration,
+    /// Destinations whose signers are funded by the same wallet, whose spend
+    /// is scheduled so that one of them can't drain the funds of the others
+    pub funding_pools: Vec<FundingPoolConf>,
+    /// Checks of the values the IGPs of the origins quote gas payments with.
+    /// Disabled if unset.
+    pub gas_oracle_drift: Option<GasOracleDriftConf>,
+    /// How long the storage locations announced by validators are used for
+    /// before they're fetched again
+    pub storage_locations_ttl: Duration,
+    /// How often the storage locations of all the validators announced on
+    /// each origin are indexed. Disabled if unset, in which case they're only
+    /// fetched for the validators of the messages.
+    pub validator_announce_index_interval: Option<Duration>,
+}
+
+/// How messages dispatched to a destination the relayer doesn't deliver to
+/// are handled. They are never delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownDestinationPolicy {
+    /// Skip them
+    #[default]
+    Ignore,
+    /// Skip them, counting them by origin and destination in a metric
+    Count,
+    /// Count them, and keep them in memory for `ttl` so they can be listed
+    /// through the API. The TTL counts from when they were first seen, and
+    /// once it elapsed they're marked as processed.
+    Park { ttl: Duration },
+}
+
+/// Pre-transactions of the messages that match a list
+#[derive(Debug, Clone)]
+pub struct PreTransactionConf {
+    /// The messages that require the pre-transactions
+    pub matching_list: MatchingList,
+    /// The pre-transactions, submitted in order
+    pub transactions: Vec<PreTransaction>,
+    /// The most pre-transactions of this config submitted per UTC day, across
+    /// all messages, or no limit if None. The relayer pays for them, e.g. the
+    /// rent of the associated token accounts they create, which the gas
+    /// payments of the messages don't cover.
+    pub max_per_day: Option<u32>,
+}
+
+/// Expiry of the messages that match a list
+#[derive(Debug, Clone)]
+pub struct MessageExpiryConf {
+    /// The messages that expire
+    pub matching_list: MatchingList,
+    /// How long after the relayer first queued a message it expires
+    pub ttl: Duration,
+}
+
+/// Destinations whose signers are funded by the same wallet
+#[derive(Debug, Clone)]
+pub struct FundingPoolConf {
+    /// Name of the pool, used in metrics
+    pub name: String,
+    /// What the destinations may spend in a period, in a unit common to them
+    pub budget: U256,
+    /// How often the spend is reset
+    pub period: Duration,
+    /// The destinations of the pool, by domain id
+    pub members: HashMap<u32, FundingPoolMemberConf>,
+}
+
+/// A destination of a funding pool
+#[derive(Debug, Clone)]
+pub struct FundingPoolMemberConf {
+    pub domain: HyperlaneDomain,
+    /// Share of the budget relative to the other destinations, before it's
+    /// scaled by the value of their pending operations
+    pub weight: u32,
+    /// How many pool units a unit of the destination's native token is
+    /// worth, both in their smallest denomination
+    pub exchange_rate: FixedPointNumber,
+}
+
+/// Config for checking that the gas oracles of the IGPs of the origins don't
+/// drift from reference values
+#[derive(Debug, Clone)]
+pub struct GasOracleDriftConf {
+    /// The relative difference between an oracle value and its reference
+    /// above which it has drifted, e.g. 0.5 for 50%
+    pub threshold: f64,
+    /// How often the oracles are checked
+    pub interval: Duration,
+    /// The token exchange rates the oracles are compared to, by origin and
+    /// destination domain id, scaled like the oracles' (1e10)
+    pub token_exchange_rates: HashMap<(u32, u32), U256>,
+}
+
+/// Config for fetching message filters from URLs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteFilterListsConf {
+    /// HTTPS URL the whitelist is published at
+    pub whitelist_url: Option<String>,
+    /// HTTPS URL the blacklist is published at
+    pub blacklist_url: Option<String>,
+    /// Address that must have signed the fetched lists
+    pub signer: H160,
+    /// How often the URLs are polled for new versions of the lists
+    pub poll_interval: Duration,
+}
+
+/// Config for parking operations that repeatedly fail to prepare
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParkingLotConf {
+    /// Number of retries after which an operation that fails to prepare is parked
+    pub prepare_failure_threshold: u32,
+    /// How long a parked operation waits before being prepared again
+    pub retry_interval: Duration,
+}
+
+/// Config for alerting webhooks about critical conditions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertConf {
+    /// Webhooks alerts are sent to
+    pub webhooks: Vec<AlertWebhookConf>,
+    /// Minimum time between two alerts of the same kind for the same chain
+    pub rate_limit: Duration,
+    /// Alert when the relayer's signer balance falls below these amounts,
+    /// by destination domain id
+    pub signer_balance_thresholds: HashMap<u32, U256>,
+    /// Alert when a destination's prepare queue holds this many operations
+    pub queue_saturation_threshold: Option<usize>,
+    /// Alert when an operation failed this many times in a row
+    pub operation_failure_threshold: Option<u32>,
+    /// Alert when a destination produced no block for this long
+    pub halted_chain_timeout: Option<Duration>,
+}
+
+/// Config for notifying a webhook of the status changes of messages
+#[derive(Clone, PartialEq, Eq)]
+pub struct MessageStatusWebhookConf {
+    pub url: String,
+    /// If set, notifications are signed with HMAC-SHA256 keyed by this secret
+    pub secret: Option<String>,
+    /// Attempts to send a notification before giving up on it
+    pub max_attempts: usize,
+}
+
+impl std::fmt::Debug for MessageStatusWebhookConf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The secret is left out so it isn't logged
+        f.debug_struct("MessageStatusWebhookConf")
+            .field("url", &self.url)
+            .field("max_attempts", &self.max_attempts)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A webhook alerts are sent to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertWebhookConf {
+    pub kind: AlertWebhookKind,
+    pub url: String,
+    /// Only alerts of at least this severity are sent to the webhook
+    pub min_severity: AlertSeverity,
+}
+
+/// The service behind a webhook, which determines the format of the alerts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertWebhookKind {
+    /// A Slack incoming webhook
+    Slack,
+    /// The PagerDuty Events API v2
+    PagerDuty { routing_key: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum AlertSeverity {
+    Warning,
+    Critical,
 }
 
 /// Config for gas payment enforcement
@@ -233,6 +530,7 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .get_opt_key("blacklist")
             .and_then(parse_matching_list)
             .unwrap_or_default();
+        let remote_filter_lists = parse_remote_filter_lists_conf(&p, &mut err);
 
         let address_blacklist = p
             .chain(&mut err)
@@ -255,12 +553,231 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .map(|v| v.split(',').collect())
             .unwrap_or_default();
 
+        let read_only_chain_names: HashSet<&str> = p
+            .chain(&mut err)
+            .get_opt_key("readOnlyChains")
+            .parse_string()
+            .map(|v| v.split(',').collect())
+            .unwrap_or_default();
+
+        let shadow_chain_names: HashSet<&str> = p
+            .chain(&mut err)
+            .get_opt_key("shadowChains")
+            .parse_string()
+            .map(|v| v.split(',').collect())
+            .unwrap_or_default();
+
+        let paused_chain_names: HashSet<&str> = p
+            .chain(&mut err)
+            .get_opt_key("pausedChains")
+            .parse_string()
+            .map(|v| v.split(',').collect())
+            .unwrap_or_default();
+
+        let raw_metadata_size_limits: Vec<(String, usize)> = p
+            .get_opt_key("metadataSizeLimits")
+            .take_config_err_flat(&mut err)
+            .and_then(|limits| limits.into_obj_iter().take_config_err(&mut err))
+            .map(|itr| {
+                itr.filter_map(|(chain, limit)| {
+                    limit
+                        .chain(&mut err)
+                        .parse_u64()
+                        .end()
+                        .map(|limit| (chain, limit as usize))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let raw_max_concurrent_simulations = parse_max_concurrent_simulations(&p, &mut err);
+
+        let raw_alert_balance_thresholds: Vec<(String, U256)> = p
+            .get_opt_key("alertSignerBalanceThresholds")
+            .take_config_err_flat(&mut err)
+            .and_then(|thresholds| thresholds.into_obj_iter().take_config_err(&mut err))
+            .map(|itr| {
+                itr.filter_map(|(chain, threshold)| {
+                    threshold
+                        .chain(&mut err)
+                        .parse_u256()
+                        .end()
+                        .map(|threshold| (chain, threshold))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let raw_signer_balance_floors: Vec<(String, U256)> = p
+            .get_opt_key("signerBalanceFloors")
+            .take_config_err_flat(&mut err)
+            .and_then(|floors| floors.into_obj_iter().take_config_err(&mut err))
+            .map(|itr| {
+                itr.filter_map(|(chain, floor)| {
+                    floor
+                        .chain(&mut err)
+                        .parse_u256()
+                        .end()
+                        .map(|floor| (chain, floor))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let raw_igp_claim_thresholds: Vec<(String, U256)> = p
+            .get_opt_key("igpClaimThresholds")
+            .take_config_err_flat(&mut err)
+            .and_then(|thresholds| thresholds.into_obj_iter().take_config_err(&mut err))
+            .map(|itr| {
+                itr.filter_map(|(chain, threshold)| {
+                    threshold
+                        .chain(&mut err)
+                        .parse_u256()
+                        .end()
+                        .map(|threshold| (chain, threshold))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let igp_claim_interval = p
+            .chain(&mut err)
+            .get_opt_key("igpClaimInterval")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_IGP_CLAIM_INTERVAL_SECS));
+
+        let storage_locations_ttl = p
+            .chain(&mut err)
+            .get_opt_key("storageLocationsTtl")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_STORAGE_LOCATIONS_TTL_SECS));
+
+        let validator_announce_index_interval = p
+            .chain(&mut err)
+            .get_opt_key("validatorAnnounceIndexInterval")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs);
+
+        let raw_igp_fee_token_exchange_rates: Vec<(String, FixedPointNumber)> = p
+            .get_opt_key("igpFeeTokenExchangeRates")
+            .take_config_err_flat(&mut err)
+            .and_then(|rates| rates.into_obj_iter().take_config_err(&mut err))
+            .map(|itr| {
+                itr.filter_map(|(chain, rate)| {
+                    rate.chain(&mut err)
+                        .parse_from_str("Invalid IGP fee token exchange rate")
+                        .end()
+                        .map(|rate| (chain, rate))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let mut raw_route_matrix: Vec<(String, String, bool)> = Vec::new();
+        if let Some(origins) = p
+            .get_opt_key("routeMatrix")
+            .take_config_err_flat(&mut err)
+            .and_then(|origins| origins.into_obj_iter().take_config_err(&mut err))
+        {
+            for (origin, destinations) in origins {
+                let Some(destinations) = destinations.into_obj_iter().take_config_err(&mut err)
+                else {
+                    continue;
+                };
+                for (destination, enabled) in destinations {
+                    if let Some(enabled) = enabled.chain(&mut err).parse_bool().end() {
+                        raw_route_matrix.push((origin.clone(), destination, enabled));
+                    }
+                }
+            }
+        }
+
+        let halt_below_signer_balance_floor = p
+            .chain(&mut err)
+            .get_opt_key("haltBelowSignerBalanceFloor")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let recipient_code_hash_allowlist = p
+            .chain(&mut err)
+            .get_opt_key("recipientCodeHashAllowlist")
+            .parse_string()
+            .end()
+            .map(|str| {
+                parse_code_hash_list(str, &mut err, || &p.cwp + "recipient_code_hash_allowlist")
+            });
+
+        let recipient_code_hash_denylist = p
+            .chain(&mut err)
+            .get_opt_key("recipientCodeHashDenylist")
+            .parse_string()
+            .end()
+            .map(|str| {
+                parse_code_hash_list(str, &mut err, || &p.cwp + "recipient_code_hash_denylist")
+            })
+            .unwrap_or_default();
+
         let allow_local_checkpoint_syncers = p
             .chain(&mut err)
             .get_opt_key("allowLocalCheckpointSyncers")
             .parse_bool()
             .unwrap_or(false);
 
+        let probe_mode = p
+            .chain(&mut err)
+            .get_opt_key("probeMode")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let preflight_simulation = p
+            .chain(&mut err)
+            .get_opt_key("preflightSimulation")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let unknown_destination_ttl = p
+            .chain(&mut err)
+            .get_opt_key("unknownDestinationTtl")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_UNKNOWN_DESTINATION_TTL_SECS));
+
+        let unknown_destination_policy = match p
+            .chain(&mut err)
+            .get_opt_key("unknownDestinationPolicy")
+            .parse_string()
+            .end()
+        {
+            None | Some("ignore") => Some(UnknownDestinationPolicy::Ignore),
+            Some("count") => Some(UnknownDestinationPolicy::Count),
+            Some("park") => Some(UnknownDestinationPolicy::Park {
+                ttl: unknown_destination_ttl,
+            }),
+            Some(other) => Err(eyre!(
+                "Invalid unknown destination policy `{other}`, expected `ignore`, `count` or `park`"
+            ))
+            .take_err(&mut err, || cwp + "unknown_destination_policy"),
+        }
+        .unwrap_or_default();
+
+        let pre_transactions = parse_pre_transactions(&p, &mut err);
+
+        let message_expiry = parse_message_expiry(&p, &mut err);
+
+        let evidence_bundle_failure_threshold = p
+            .chain(&mut err)
+            .get_opt_key("evidenceBundleFailureThreshold")
+            .parse_u32()
+            .end()
+            .filter(|threshold| *threshold > 0);
+
+        let message_backoff = parse_message_backoff(&p, &mut err);
+
         cfg_unwrap_all!(cwp, err: [base]);
 
         let skip_transaction_gas_limit_for = skip_transaction_gas_limit_for_names
@@ -274,6 +791,50 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .map(|d| d.id())
             .collect();
 
+        let paused_chains = paused_chain_names
+            .into_iter()
+            .filter_map(|chain| {
+                base.lookup_domain(chain)
+                    .context("Missing configuration for a chain in `pausedChains`")
+                    .into_config_result(|| cwp + "paused_chains")
+                    .take_config_err(&mut err)
+            })
+            .map(|d| d.id())
+            .collect();
+
+        let shadow_chains = shadow_chain_names
+            .into_iter()
+            .filter_map(|chain| {
+                base.lookup_domain(chain)
+                    .context("Missing configuration for a chain in `shadowChains`")
+                    .into_config_result(|| cwp + "shadow_chains")
+                    .take_config_err(&mut err)
+            })
+            .map(|d| d.id())
+            .collect();
+
+        let metadata_size_limits = raw_metadata_size_limits
+            .into_iter()
+            .filter_map(|(chain, limit)| {
+                base.lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `metadataSizeLimits`")
+                    .into_config_result(|| cwp + "metadata_size_limits")
+                    .take_config_err(&mut err)
+                    .map(|d| (d.id(), limit))
+            })
+            .collect();
+
+        let max_concurrent_simulations = raw_max_concurrent_simulations
+            .into_iter()
+            .filter_map(|(chain, limit)| {
+                base.lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `maxConcurrentSimulations`")
+                    .into_config_result(|| cwp + "max_concurrent_simulations")
+                    .take_config_err(&mut err)
+                    .map(|d| (d.id(), limit))
+            })
+            .collect();
+
         let relay_chains: HashSet<HyperlaneDomain> = relay_chain_names
             .unwrap_or_default()
             .into_iter()
@@ -285,6 +846,47 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             })
             .collect();
 
+        let read_only_chains: HashSet<HyperlaneDomain> = read_only_chain_names
+            .into_iter()
+            .filter_map(|chain| {
+                base.lookup_domain(chain)
+                    .context("Missing configuration for a chain in `readOnlyChains`")
+                    .into_config_result(|| cwp + "read_only_chains")
+                    .take_config_err(&mut err)
+            })
+            .collect();
+        for chain in read_only_chains.difference(&relay_chains) {
+            err.push(
+                cwp + "read_only_chains",
+                eyre!(
+                    "Read-only chain `{}` isn't in `relayChains`; read-only chains are chains the relayer relays messages from",
+                    chain.name()
+                ),
+            );
+        }
+
+        // Chains in `readOnlyChains` are only relayed from, never submitted
+        // to, so they don't need a signer
+        let destination_chains: HashSet<HyperlaneDomain> = relay_chains
+            .difference(&read_only_chains)
+            .cloned()
+            .collect();
+        for chain in &destination_chains {
+            let has_signer = base
+                .chains
+                .get(chain.name())
+                .map_or(true, |conf| conf.signer.is_some());
+            if !has_signer {
+                err.push(
+                    cwp + "chains" + chain.name() + "signer",
+                    eyre!(
+                        "Chain `{}` has no signer, but the relayer submits messages to it; configure a signer, or list it in `readOnlyChains` if it's only relayed from",
+                        chain.name()
+                    ),
+                );
+            }
+        }
+
         let (raw_metric_app_contexts_path, raw_metric_app_contexts) = p
             .get_opt_key("metricAppContexts")
             .take_config_err_flat(&mut err)
@@ -311,30 +913,704 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             })
             .unwrap_or_default();
 
+        let (raw_app_context_budgets_path, raw_app_context_budgets) = p
+            .get_opt_key("appContextBudgets")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "app_context_budgets", Value::Array(vec![])));
+
+        let mut app_context_budgets: HashMap<u32, HashMap<String, U256>> = HashMap::new();
+        if let Some(budgets) =
+            ValueParser::new(raw_app_context_budgets_path, &raw_app_context_budgets)
+                .into_array_iter()
+                .take_config_err(&mut err)
+        {
+            for budget in budgets {
+                let app_context = budget
+                    .chain(&mut err)
+                    .get_key("appContext")
+                    .parse_string()
+                    .end();
+                let destination = budget
+                    .chain(&mut err)
+                    .get_key("destinationChain")
+                    .parse_string()
+                    .end()
+                    .and_then(|chain| {
+                        base.lookup_domain(chain)
+                            .context("Missing configuration for a chain in `appContextBudgets`")
+                            .into_config_result(|| &budget.cwp + "destination_chain")
+                            .take_config_err(&mut err)
+                    });
+                let daily_budget = budget
+                    .chain(&mut err)
+                    .get_key("dailyBudget")
+                    .parse_u256()
+                    .end();
+                if let (Some(app_context), Some(destination), Some(daily_budget)) =
+                    (app_context, destination, daily_budget)
+                {
+                    app_context_budgets
+                        .entry(destination.id())
+                        .or_default()
+                        .insert(app_context.to_owned(), daily_budget);
+                }
+            }
+        }
+
+        let funding_pools = parse_funding_pools(&p, &base, &mut err);
+
+        let gas_oracle_drift = parse_gas_oracle_drift_conf(&p, &base, &mut err);
+
         let max_message_retries = p
             .chain(&mut err)
             .get_opt_key("maxMessageRetries")
             .parse_u32()
             .unwrap_or(DEFAULT_MAX_MESSAGE_RETRIES);
 
+        let park_after_prepare_failures = p
+            .chain(&mut err)
+            .get_opt_key("parkAfterPrepareFailures")
+            .parse_u32()
+            .end();
+
+        let parked_retry_interval = p
+            .chain(&mut err)
+            .get_opt_key("parkedRetryInterval")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_PARKED_RETRY_INTERVAL_SECS));
+
+        let parking_lot =
+            park_after_prepare_failures.map(|prepare_failure_threshold| ParkingLotConf {
+                prepare_failure_threshold,
+                retry_interval: parked_retry_interval,
+            });
+
+        let log_deduplication_window = p
+            .chain(&mut err)
+            .get_opt_key("logDeduplicationWindow")
+            .parse_u64()
+            .end()
+            // A window of zero disables deduplication
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs);
+
+        let signer_balance_thresholds = raw_alert_balance_thresholds
+            .into_iter()
+            .filter_map(|(chain, threshold)| {
+                base.lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `alertSignerBalanceThresholds`")
+                    .into_config_result(|| cwp + "alert_signer_balance_thresholds")
+                    .take_config_err(&mut err)
+                    .map(|d| (d.id(), threshold))
+            })
+            .collect();
+        let alerts = parse_alert_conf(&p, signer_balance_thresholds, &mut err);
+        let message_status_webhook = parse_message_status_webhook_conf(&p, &mut err);
+
+        let signer_balance_floors = raw_signer_balance_floors
+            .into_iter()
+            .filter_map(|(chain, floor)| {
+                base.lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `signerBalanceFloors`")
+                    .into_config_result(|| cwp + "signer_balance_floors")
+                    .take_config_err(&mut err)
+                    .map(|d| (d.id(), floor))
+            })
+            .collect();
+
+        let igp_claim_thresholds = raw_igp_claim_thresholds
+            .into_iter()
+            .filter_map(|(chain, threshold)| {
+                base.lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `igpClaimThresholds`")
+                    .into_config_result(|| cwp + "igp_claim_thresholds")
+                    .take_config_err(&mut err)
+                    .map(|d| (d.id(), threshold))
+            })
+            .collect();
+
+        let igp_fee_token_exchange_rates = raw_igp_fee_token_exchange_rates
+            .into_iter()
+            .filter_map(|(chain, rate)| {
+                base.lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `igpFeeTokenExchangeRates`")
+                    .into_config_result(|| cwp + "igp_fee_token_exchange_rates")
+                    .take_config_err(&mut err)
+                    .map(|d| (d.id(), rate))
+            })
+            .collect();
+
+        let route_matrix = raw_route_matrix
+            .into_iter()
+            .filter_map(|(origin, destination, enabled)| {
+                let origin = base
+                    .lookup_domain(&origin)
+                    .context("Missing configuration for an origin chain in `routeMatrix`")
+                    .into_config_result(|| cwp + "route_matrix")
+                    .take_config_err(&mut err)?;
+                let destination = base
+                    .lookup_domain(&destination)
+                    .context("Missing configuration for a destination chain in `routeMatrix`")
+                    .into_config_result(|| cwp + "route_matrix")
+                    .take_config_err(&mut err)?;
+                Some(((origin.id(), destination.id()), enabled))
+            })
+            .collect();
+
         err.into_result(RelayerSettings {
             base,
             db,
-            origin_chains: relay_chains.clone(),
-            destination_chains: relay_chains,
+            origin_chains: relay_chains,
+            destination_chains,
             gas_payment_enforcement,
             whitelist,
             blacklist,
+            remote_filter_lists,
             address_blacklist,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
+            paused_chains,
+            metadata_size_limits,
+            max_concurrent_simulations,
             allow_local_checkpoint_syncers,
             metric_app_contexts,
+            app_context_budgets,
             max_retries: max_message_retries,
+            parking_lot,
+            log_deduplication_window,
+            recipient_code_hash_allowlist,
+            recipient_code_hash_denylist,
+            alerts,
+            message_status_webhook,
+            probe_mode,
+            preflight_simulation,
+            unknown_destination_policy,
+            pre_transactions,
+            signer_balance_floors,
+            halt_below_signer_balance_floor,
+            route_matrix,
+            igp_fee_token_exchange_rates,
+            shadow_chains,
+            message_expiry,
+            evidence_bundle_failure_threshold,
+            message_backoff,
+            igp_claim_thresholds,
+            igp_claim_interval,
+            funding_pools,
+            gas_oracle_drift,
+            storage_locations_ttl,
+            validator_announce_index_interval,
         })
     }
 }
 
+/// Alerting is enabled by configuring at least one webhook
+fn parse_alert_conf(
+    p: &ValueParser,
+    signer_balance_thresholds: HashMap<u32, U256>,
+    err: &mut ConfigParsingError,
+) -> Option<AlertConf> {
+    let (raw_webhooks_path, raw_webhooks) = p
+        .get_opt_key("alertWebhooks")
+        .take_config_err_flat(err)
+        .and_then(parse_json_array)?;
+
+    let webhooks: Vec<AlertWebhookConf> = ValueParser::new(raw_webhooks_path, &raw_webhooks)
+        .into_array_iter()
+        .take_config_err(err)
+        .map(|itr| {
+            itr.filter_map(|webhook| {
+                let kind = match webhook.chain(err).get_key("type").parse_string().end() {
+                    Some("slack") => Some(AlertWebhookKind::Slack),
+                    Some("pagerDuty") => webhook
+                        .chain(err)
+                        .get_key("routingKey")
+                        .parse_string()
+                        .end()
+                        .map(|routing_key| AlertWebhookKind::PagerDuty {
+                            routing_key: routing_key.to_owned(),
+                        }),
+                    Some(other) => Err(eyre!("Unknown alert webhook type `{other}`"))
+                        .take_err(err, || &webhook.cwp + "type"),
+                    None => None,
+                };
+                let url = match kind {
+                    Some(AlertWebhookKind::PagerDuty { .. }) => webhook
+                        .chain(err)
+                        .get_opt_key("url")
+                        .parse_string()
+                        .unwrap_or(PAGERDUTY_EVENTS_URL),
+                    _ => webhook.chain(err).get_key("url").parse_string().end()?,
+                };
+                let min_severity = match webhook
+                    .chain(err)
+                    .get_opt_key("minSeverity")
+                    .parse_string()
+                    .end()
+                {
+                    None | Some("warning") => Some(AlertSeverity::Warning),
+                    Some("critical") => Some(AlertSeverity::Critical),
+                    Some(other) => Err(eyre!("Unknown alert severity `{other}`"))
+                        .take_err(err, || &webhook.cwp + "min_severity"),
+                };
+                Some(AlertWebhookConf {
+                    kind: kind?,
+                    url: url.to_owned(),
+                    min_severity: min_severity?,
+                })
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+
+    let rate_limit = p
+        .chain(err)
+        .get_opt_key("alertRateLimit")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(DEFAULT_ALERT_RATE_LIMIT_SECS));
+
+    let queue_saturation_threshold = p
+        .chain(err)
+        .get_opt_key("alertQueueSaturationThreshold")
+        .parse_u64()
+        .end()
+        .map(|threshold| threshold as usize);
+
+    let operation_failure_threshold = p
+        .chain(err)
+        .get_opt_key("alertOperationFailureThreshold")
+        .parse_u32()
+        .end();
+
+    let halted_chain_timeout = p
+        .chain(err)
+        .get_opt_key("alertHaltedChainTimeout")
+        .parse_u64()
+        .end()
+        .map(Duration::from_secs);
+
+    Some(AlertConf {
+        webhooks,
+        rate_limit,
+        signer_balance_thresholds,
+        queue_saturation_threshold,
+        operation_failure_threshold,
+        halted_chain_timeout,
+    })
+}
+
+/// Message status notifications are enabled by configuring the URL of the
+/// webhook
+fn parse_message_status_webhook_conf(
+    p: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<MessageStatusWebhookConf> {
+    let url = p
+        .chain(err)
+        .get_opt_key("messageStatusWebhookUrl")
+        .parse_string()
+        .end()
+        .and_then(|url| {
+            reqwest::Url::parse(url)
+                .context("Invalid message status webhook URL")
+                .map(|_| url.to_owned())
+                .take_err(err, || &p.cwp + "message_status_webhook_url")
+        })?;
+    let secret = p
+        .chain(err)
+        .get_opt_key("messageStatusWebhookSecret")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let max_attempts = p
+        .chain(err)
+        .get_opt_key("messageStatusWebhookMaxAttempts")
+        .parse_u32()
+        .map(|attempts| attempts as usize)
+        .unwrap_or(DEFAULT_MESSAGE_STATUS_WEBHOOK_MAX_ATTEMPTS);
+
+    Some(MessageStatusWebhookConf {
+        url,
+        secret,
+        max_attempts,
+    })
+}
+
+/// Remote filter lists are enabled by configuring the URL of at least one
+/// list
+fn parse_remote_filter_lists_conf(
+    p: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<RemoteFilterListsConf> {
+    let mut parse_url = |key: &str, path: &str| {
+        p.chain(err)
+            .get_opt_key(key)
+            .parse_string()
+            .end()
+            .and_then(|url| {
+                reqwest::Url::parse(url)
+                    .context("Invalid filter list URL")
+                    .and_then(|parsed| match parsed.scheme() {
+                        "https" => Ok(url.to_owned()),
+                        scheme => Err(eyre!(
+                            "Filter lists must be fetched over HTTPS, not `{scheme}`"
+                        )),
+                    })
+                    .take_err(err, || &p.cwp + path)
+            })
+    };
+    let whitelist_url = parse_url("whitelistUrl", "whitelist_url");
+    let blacklist_url = parse_url("blacklistUrl", "blacklist_url");
+    if whitelist_url.is_none() && blacklist_url.is_none() {
+        return None;
+    }
+
+    let signer = p
+        .chain(err)
+        .get_key("filterListSigner")
+        .parse_address_hash()
+        .end()
+        .map(H160::from)?;
+    let poll_interval = p
+        .chain(err)
+        .get_opt_key("filterListPollInterval")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(DEFAULT_FILTER_LIST_POLL_INTERVAL_SECS));
+
+    Some(RemoteFilterListsConf {
+        whitelist_url,
+        blacklist_url,
+        signer,
+        poll_interval,
+    })
+}
+
+fn parse_pre_transactions(
+    p: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Vec<PreTransactionConf> {
+    let Some((raw_path, raw)) = p
+        .get_opt_key("preTransactions")
+        .take_config_err_flat(err)
+        .and_then(parse_json_array)
+    else {
+        return vec![];
+    };
+
+    ValueParser::new(raw_path, &raw)
+        .into_array_iter()
+        .take_config_err(err)
+        .map(|itr| {
+            itr.filter_map(|conf| {
+                let matching_list = conf
+                    .chain(err)
+                    .get_key("matchingList")
+                    .and_then(parse_matching_list)
+                    .end()?;
+                let transactions = conf
+                    .chain(err)
+                    .get_key("transactions")
+                    .into_array_iter()?
+                    .filter_map(|tx| parse_pre_transaction(&tx, err))
+                    .collect();
+                let max_per_day = conf.chain(err).get_opt_key("maxPerDay").parse_u32().end();
+                Some(PreTransactionConf {
+                    matching_list,
+                    transactions,
+                    max_per_day,
+                })
+            })
+            .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the max number of concurrent simulations by chain name. A limit
+/// of 0 is rejected, since no simulation could ever run.
+fn parse_max_concurrent_simulations(
+    p: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Vec<(String, usize)> {
+    p.get_opt_key("maxConcurrentSimulations")
+        .take_config_err_flat(err)
+        .and_then(|limits| limits.into_obj_iter().take_config_err(err))
+        .map(|itr| {
+            itr.filter_map(|(chain, limit)| {
+                let value = limit.chain(err).parse_u64().end()?;
+                if value == 0 {
+                    Err::<(), eyre::Report>(eyre!(
+                        "The max number of concurrent simulations must be at least 1"
+                    ))
+                    .take_err(err, || limit.cwp.clone());
+                    return None;
+                }
+                Some((chain, value as usize))
+            })
+            .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_message_expiry(p: &ValueParser, err: &mut ConfigParsingError) -> Vec<MessageExpiryConf> {
+    let Some((raw_path, raw)) = p
+        .get_opt_key("messageExpiry")
+        .take_config_err_flat(err)
+        .and_then(parse_json_array)
+    else {
+        return vec![];
+    };
+
+    ValueParser::new(raw_path, &raw)
+        .into_array_iter()
+        .take_config_err(err)
+        .map(|itr| {
+            itr.filter_map(|conf| {
+                let matching_list = conf
+                    .chain(err)
+                    .get_opt_key("matchingList")
+                    .and_then(parse_matching_list)
+                    .unwrap_or_default();
+                let ttl = conf
+                    .chain(err)
+                    .get_key("ttl")
+                    .parse_u64()
+                    .end()
+                    .map(Duration::from_secs)?;
+                Some(MessageExpiryConf { matching_list, ttl })
+            })
+            .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The message backoff is an object with an optional `jitter`, and a schedule
+/// for any of the failure classes `gasUnderpayment`, `ismNotReady`, `revert`
+/// and `other`. A schedule has an `initial` and a `max` delay in seconds, and
+/// the `factor` the delay is multiplied by on every retry, defaulting to 2.
+fn parse_message_backoff(p: &ValueParser, err: &mut ConfigParsingError) -> BackoffPolicy {
+    let Some(conf) = p.get_opt_key("messageBackoff").take_config_err_flat(err) else {
+        return BackoffPolicy::default();
+    };
+    let jitter = conf
+        .chain(err)
+        .get_opt_key("jitter")
+        .parse_f64()
+        .end()
+        .unwrap_or(0.);
+    if !(0. ..=1.).contains(&jitter) {
+        Err::<(), eyre::Report>(eyre!("The backoff jitter must be between 0 and 1"))
+            .take_err(err, || &conf.cwp + "jitter");
+    }
+
+    let mut schedules = HashMap::new();
+    for class in FailureClass::ALL {
+        let Some(schedule) = conf
+            .get_opt_key(&class.to_string())
+            .take_config_err_flat(err)
+        else {
+            continue;
+        };
+        let initial = schedule.chain(err).get_key("initial").parse_u64().end();
+        let max = schedule.chain(err).get_key("max").parse_u64().end();
+        let factor = schedule
+            .chain(err)
+            .get_opt_key("factor")
+            .parse_f64()
+            .end()
+            .unwrap_or(2.);
+        if factor < 1. {
+            Err::<(), eyre::Report>(eyre!("A backoff factor can't be lower than 1"))
+                .take_err(err, || &schedule.cwp + "factor");
+            continue;
+        }
+        if let (Some(initial), Some(max)) = (initial, max) {
+            schedules.insert(
+                class,
+                BackoffSchedule::Exponential {
+                    initial: Duration::from_secs(initial),
+                    factor,
+                    max: Duration::from_secs(max.max(initial)),
+                },
+            );
+        }
+    }
+
+    BackoffPolicy { schedules, jitter }
+}
+
+/// Funding pools are a list of objects with a `name`, a `budget`, a `period`
+/// in seconds, and `chains`, a map from destination chain name to its
+/// `weight` and `exchangeRate`, both defaulting to 1
+fn parse_funding_pools(
+    p: &ValueParser,
+    base: &Settings,
+    err: &mut ConfigParsingError,
+) -> Vec<FundingPoolConf> {
+    let Some((raw_path, raw)) = p
+        .get_opt_key("fundingPools")
+        .take_config_err_flat(err)
+        .and_then(parse_json_array)
+    else {
+        return vec![];
+    };
+
+    let mut pooled_destinations = HashSet::new();
+    ValueParser::new(raw_path, &raw)
+        .into_array_iter()
+        .take_config_err(err)
+        .map(|itr| {
+            itr.filter_map(|pool| {
+                let name = pool.chain(err).get_key("name").parse_string().end();
+                let budget = pool.chain(err).get_key("budget").parse_u256().end();
+                let period = pool
+                    .chain(err)
+                    .get_opt_key("period")
+                    .parse_u64()
+                    .end()
+                    .map(Duration::from_secs)
+                    .unwrap_or(Duration::from_secs(DEFAULT_FUNDING_POOL_PERIOD_SECS));
+                let members: HashMap<u32, FundingPoolMemberConf> = pool
+                    .chain(err)
+                    .get_key("chains")
+                    .into_obj_iter()?
+                    .filter_map(|(chain, member)| {
+                        let domain = base
+                            .lookup_domain(&chain)
+                            .context("Missing configuration for a chain in `fundingPools`")
+                            .into_config_result(|| member.cwp.clone())
+                            .take_config_err(err)?;
+                        if !pooled_destinations.insert(domain.id()) {
+                            Err::<(), eyre::Report>(eyre!(
+                                "A destination can only be in one funding pool"
+                            ))
+                            .take_err(err, || member.cwp.clone());
+                            return None;
+                        }
+                        let weight = member
+                            .chain(err)
+                            .get_opt_key("weight")
+                            .parse_u32()
+                            .end()
+                            .unwrap_or(1);
+                        let exchange_rate = member
+                            .chain(err)
+                            .get_opt_key("exchangeRate")
+                            .parse_from_str("Invalid funding pool exchange rate")
+                            .end()
+                            .unwrap_or_else(|| FixedPointNumber::from(1));
+                        Some((
+                            domain.id(),
+                            FundingPoolMemberConf {
+                                domain,
+                                weight,
+                                exchange_rate,
+                            },
+                        ))
+                    })
+                    .collect();
+                Some(FundingPoolConf {
+                    name: name?.to_owned(),
+                    budget: budget?,
+                    period,
+                    members,
+                })
+            })
+            .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The gas oracle drift check is an object with a `threshold`, an `interval`
+/// in seconds, and `tokenExchangeRates`, a map from origin chain name to a
+/// map from destination chain name to the reference exchange rate. It's
+/// enabled by setting the threshold.
+fn parse_gas_oracle_drift_conf(
+    p: &ValueParser,
+    base: &Settings,
+    err: &mut ConfigParsingError,
+) -> Option<GasOracleDriftConf> {
+    let conf = p
+        .get_opt_key("igpGasOracleDrift")
+        .take_config_err_flat(err)?;
+    let threshold = conf.chain(err).get_key("threshold").parse_f64().end()?;
+    let interval = conf
+        .chain(err)
+        .get_opt_key("interval")
+        .parse_u64()
+        .end()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(DEFAULT_GAS_ORACLE_DRIFT_INTERVAL_SECS));
+
+    let mut token_exchange_rates = HashMap::new();
+    let origins = conf
+        .get_opt_key("tokenExchangeRates")
+        .take_config_err_flat(err)
+        .and_then(|origins| origins.into_obj_iter().take_config_err(err));
+    for (origin, destinations) in origins.into_iter().flatten() {
+        let Some(origin_domain) = base
+            .lookup_domain(&origin)
+            .context("Missing configuration for an origin chain in `igpGasOracleDrift`")
+            .into_config_result(|| destinations.cwp.clone())
+            .take_config_err(err)
+        else {
+            continue;
+        };
+        let Some(destinations) = destinations.into_obj_iter().take_config_err(err) else {
+            continue;
+        };
+        for (destination, rate) in destinations {
+            let destination_domain = base
+                .lookup_domain(&destination)
+                .context("Missing configuration for a destination chain in `igpGasOracleDrift`")
+                .into_config_result(|| rate.cwp.clone())
+                .take_config_err(err);
+            let rate = rate.chain(err).parse_u256().end();
+            if let (Some(destination_domain), Some(rate)) = (destination_domain, rate) {
+                token_exchange_rates.insert((origin_domain.id(), destination_domain.id()), rate);
+            }
+        }
+    }
+
+    Some(GasOracleDriftConf {
+        threshold,
+        interval,
+        token_exchange_rates,
+    })
+}
+
+fn parse_pre_transaction(p: &ValueParser, err: &mut ConfigParsingError) -> Option<PreTransaction> {
+    match p.chain(err).get_key("type").parse_string().end()? {
+        "createAssociatedTokenAccount" => p
+            .chain(err)
+            .get_key("mint")
+            .parse_address_hash()
+            .end()
+            .map(|mint| PreTransaction::CreateAssociatedTokenAccount { mint }),
+        "call" => {
+            let to = p.chain(err).get_key("to").parse_address_hash().end();
+            let data = p
+                .chain(err)
+                .get_key("data")
+                .parse_string()
+                .end()
+                .and_then(|data| {
+                    hex::decode(data.trim_start_matches("0x"))
+                        .context("Invalid pre-transaction calldata")
+                        .take_err(err, || &p.cwp + "data")
+                });
+            Some(PreTransaction::Call { to: to?, data: data? })
+        }
+        other => Err(eyre!(
+            "Unknown pre-transaction type `{other}`, expected `createAssociatedTokenAccount` or `call`"
+        ))
+        .take_err(err, || &p.cwp + "type"),
+    }
+}
+
 fn parse_json_array(p: ValueParser) -> Option<(ConfigPath, Value)> {
     let mut err = ConfigParsingError::default();
 
@@ -387,10 +1663,26 @@ fn parse_address_list(
         .collect_vec()
 }
 
+fn parse_code_hash_list(
+    str: &str,
+    err: &mut ConfigParsingError,
+    err_path: impl Fn() -> ConfigPath,
+) -> HashSet<H256> {
+    parse_address_list(str, err, &err_path)
+        .into_iter()
+        .filter_map(|bytes| {
+            (bytes.len() == H256::len_bytes())
+                .then(|| H256::from_slice(&bytes))
+                .ok_or_else(|| eyre!("Expected a 32 byte code hash"))
+                .take_err(err, &err_path)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use hyperlane_core::H160;
+    use hyperlane_core::{HyperlaneMessage, H160};
 
     #[test]
     fn test_parse_address_blacklist() {
@@ -419,4 +1711,109 @@ mod test {
         assert_eq!(res, vec![valid_address1, valid_address2]);
         assert!(!err.is_ok());
     }
+
+    #[test]
+    fn test_parse_code_hash_list() {
+        let code_hash1 = H256::random();
+        let code_hash2 = H256::random();
+
+        let input = format!("{code_hash1:?}, {}", hex::encode(code_hash2));
+        let mut err = ConfigParsingError::default();
+        let res = parse_code_hash_list(&input, &mut err, ConfigPath::default);
+        assert_eq!(res, HashSet::from([code_hash1, code_hash2]));
+        assert!(err.is_ok());
+
+        // A value that isn't 32 bytes long
+        let input = format!("{code_hash1:?}, 0x1234");
+        let mut err = ConfigParsingError::default();
+        let res = parse_code_hash_list(&input, &mut err, ConfigPath::default);
+        assert_eq!(res, HashSet::from([code_hash1]));
+        assert!(!err.is_ok());
+    }
+    #[test]
+    fn test_parse_pre_transactions() {
+        let to = H256::random();
+        let raw = serde_json::json!({
+            "pretransactions": [{
+                "matchinglist": [{ "destinationdomain": 1399811149 }],
+                "maxperday": 100,
+                "transactions": [
+                    {
+                        "type": "createAssociatedTokenAccount",
+                        "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+                    },
+                    { "type": "call", "to": format!("{to:?}"), "data": "0x1234" },
+                    { "type": "transfer" }
+                ]
+            }]
+        });
+        let mut err = ConfigParsingError::default();
+        let confs =
+            parse_pre_transactions(&ValueParser::new(ConfigPath::default(), &raw), &mut err);
+
+        assert_eq!(confs.len(), 1);
+        assert_eq!(confs[0].max_per_day, Some(100));
+        assert_eq!(confs[0].transactions.len(), 2);
+        assert!(matches!(
+            confs[0].transactions[0],
+            PreTransaction::CreateAssociatedTokenAccount { .. }
+        ));
+        assert_eq!(
+            confs[0].transactions[1],
+            PreTransaction::Call {
+                to,
+                data: vec![0x12, 0x34]
+            }
+        );
+        // The unknown pre-transaction type is reported
+        assert!(!err.is_ok());
+    }
+
+    #[test]
+    fn test_parse_message_expiry() {
+        let raw = serde_json::json!({
+            "messageexpiry": [
+                { "matchinglist": [{ "destinationdomain": 1399811149 }], "ttl": 3600 },
+                { "ttl": "86400" },
+                { "matchinglist": [{ "origindomain": 1 }] }
+            ]
+        });
+        let mut err = ConfigParsingError::default();
+        let confs = parse_message_expiry(&ValueParser::new(ConfigPath::default(), &raw), &mut err);
+
+        assert_eq!(confs.len(), 2);
+        assert_eq!(confs[0].ttl, Duration::from_secs(3600));
+        let message = HyperlaneMessage {
+            destination: 1399811149,
+            ..Default::default()
+        };
+        assert!(confs[0].matching_list.msg_matches(&message, true));
+        // Without a matching list, all messages expire
+        assert_eq!(confs[1].ttl, Duration::from_secs(24 * 60 * 60));
+        assert!(confs[1]
+            .matching_list
+            .msg_matches(&HyperlaneMessage::default(), true));
+        // The policy without a TTL is reported
+        assert!(!err.is_ok());
+    }
+
+    #[test]
+    fn test_parse_max_concurrent_simulations() {
+        let raw = serde_json::json!({
+            "maxconcurrentsimulations": { "ethereum": 8, "arbitrum": "4", "optimism": 0 }
+        });
+        let mut err = ConfigParsingError::default();
+        let mut limits = parse_max_concurrent_simulations(
+            &ValueParser::new(ConfigPath::default(), &raw),
+            &mut err,
+        );
+        limits.sort();
+
+        assert_eq!(
+            limits,
+            vec![("arbitrum".to_owned(), 4), ("ethereum".to_owned(), 8)]
+        );
+        // The limit of 0 is reported
+        assert!(!err.is_ok());
+    }
 }
@@ -0,0 +1,238 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ethers::utils::hex;
+use hmac::{Hmac, Mac};
+use hyperlane_core::{rpc_clients::RetryPolicy, HyperlaneMessage, H256, H512};
+use serde::Serialize;
+use sha2::Sha256;
+use tracing::{debug, warn};
+
+use crate::settings::MessageStatusWebhookConf;
+
+/// Header holding the hex encoded HMAC-SHA256 of the request body, keyed by
+/// the configured secret
+pub const SIGNATURE_HEADER: &str = "X-Hyperlane-Signature";
+
+/// A change of status of a message, reported to the webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStatusEvent {
+    /// The message's metadata was built and its gas payment requirement met
+    Prepared,
+    /// A transaction delivering the message was submitted
+    Submitted,
+    /// The message was delivered on the destination
+    Delivered,
+    /// The message is never delivered, e.g. because its recipient isn't a
+    /// contract
+    Dropped,
+    /// The message's gas payment doesn't meet the gas payment requirement, so
+    /// it waits for a top-up
+    GasUnderpaid,
+}
+
+/// The body POSTed to the webhook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageStatusNotification {
+    pub event: MessageStatusEvent,
+    pub message_id: H256,
+    pub origin: u32,
+    pub destination: u32,
+    pub nonce: u32,
+    /// The transaction that submitted or delivered the message, if any
+    pub tx_hash: Option<H512>,
+    /// Why the message was dropped
+    pub reason: Option<String>,
+    /// Unix timestamp of the event, in seconds
+    pub timestamp: u64,
+}
+
+impl MessageStatusNotification {
+    pub fn new(
+        event: MessageStatusEvent,
+        message: &HyperlaneMessage,
+        tx_hash: Option<H512>,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            event,
+            message_id: message.id(),
+            origin: message.origin,
+            destination: message.destination,
+            nonce: message.nonce,
+            tx_hash,
+            reason,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since_epoch| since_epoch.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Pushes the status changes of messages to a webhook, so that app teams can
+/// follow their messages without polling the explorer.
+///
+/// Notifications are sent in the background and retried with backoff. When a
+/// secret is configured, the body is signed with HMAC-SHA256 in the
+/// [`SIGNATURE_HEADER`] header so the receiver can authenticate it.
+#[derive(Debug)]
+pub struct MessageStatusNotifier {
+    conf: MessageStatusWebhookConf,
+    client: reqwest::Client,
+    retry_policy: RetryPolicy,
+}
+
+impl MessageStatusNotifier {
+    pub fn new(conf: MessageStatusWebhookConf) -> Self {
+        let retry_policy = RetryPolicy::exponential(
+            Duration::from_secs(1),
+            Duration::from_secs(60),
+            conf.max_attempts,
+        );
+        Self {
+            conf,
+            client: reqwest::Client::new(),
+            retry_policy,
+        }
+    }
+
+    /// Sends the notification in the background
+    pub fn notify(&self, notification: MessageStatusNotification) {
+        let body = match serde_json::to_vec(&notification) {
+            Ok(body) => body,
+            Err(err) => {
+                warn!(?err, ?notification, "Failed to serialize message status");
+                return;
+            }
+        };
+        let signature = self
+            .conf
+            .secret
+            .as_ref()
+            .map(|secret| sign(secret.as_bytes(), &body));
+        let client = self.client.clone();
+        let url = self.conf.url.clone();
+        let retry_policy = self.retry_policy;
+        tokio::spawn(async move {
+            let mut failures = 0;
+            loop {
+                let mut request = client
+                    .post(&url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body.clone());
+                if let Some(signature) = &signature {
+                    request = request.header(SIGNATURE_HEADER, signature);
+                }
+                let result = request
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                match result {
+                    Ok(_) => {
+                        debug!(?notification, "Sent message status to webhook");
+                        return;
+                    }
+                    Err(err) => {
+                        failures += 1;
+                        if !retry_policy.should_retry(failures) {
+                            warn!(
+                                ?err,
+                                ?notification,
+                                failures,
+                                "Failed to send message status to webhook, giving up"
+                            );
+                            return;
+                        }
+                        debug!(?err, failures, "Failed to send message status to webhook");
+                        tokio::time::sleep(retry_policy.delay(failures)).await;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// The hex encoded HMAC-SHA256 of `body` keyed by `secret`
+fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use axum::{extract::State, http::HeaderMap, http::StatusCode, routing, Router};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_notifications_are_retried_and_signed() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let app = Router::new()
+            .route(
+                "/",
+                routing::post(
+                    |State((attempts, tx)): State<(
+                        Arc<AtomicUsize>,
+                        mpsc::UnboundedSender<(HeaderMap, Vec<u8>)>,
+                    )>,
+                     headers: HeaderMap,
+                     body: axum::body::Bytes| async move {
+                        // The first attempt fails
+                        if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                            return StatusCode::INTERNAL_SERVER_ERROR;
+                        }
+                        tx.send((headers, body.to_vec())).unwrap();
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state((attempts.clone(), tx));
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let mut notifier = MessageStatusNotifier::new(MessageStatusWebhookConf {
+            url: format!("http://{addr}/"),
+            secret: Some("secret".to_owned()),
+            max_attempts: 3,
+        });
+        notifier.retry_policy = RetryPolicy::fixed(Duration::from_millis(10), 3);
+        let message = HyperlaneMessage::default();
+        notifier.notify(MessageStatusNotification::new(
+            MessageStatusEvent::GasUnderpaid,
+            &message,
+            None,
+            None,
+        ));
+
+        let (headers, body) = rx.recv().await.unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            sign(b"secret", &body)
+        );
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["event"], "gas_underpaid");
+        assert_eq!(body["messageId"], format!("{:?}", message.id()));
+    }
+}
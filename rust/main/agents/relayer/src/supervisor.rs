@@ -0,0 +1,88 @@
+//! Keeps the tasks of an origin running when one of them panics.
+//!
+//! A relayer relays from all of its origins in a single process, and its
+//! tasks are joined together, so a panic in a task of one origin, e.g. on an
+//! unexpected response of its RPC, would stop the relaying from every origin.
+//! Supervised tasks are counted and started again instead.
+
+use std::{any::Any, future::Future, panic::AssertUnwindSafe, time::Duration};
+
+use futures_util::FutureExt;
+use prometheus::IntCounter;
+use tracing::error;
+
+/// How long a task that panicked waits before it's started again, so that
+/// one that keeps panicking doesn't spin
+pub const RESTART_DELAY: Duration = Duration::from_secs(10);
+
+/// Runs the tasks made by `make_task` one after the other, making a new one
+/// whenever the last one panicked, until one returns
+pub async fn supervise<F, Fut>(task: &str, panics: IntCounter, make_task: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    supervise_with_delay(task, panics, RESTART_DELAY, make_task).await
+}
+
+async fn supervise_with_delay<F, Fut>(
+    task: &str,
+    panics: IntCounter,
+    restart_delay: Duration,
+    mut make_task: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    while let Err(panic) = AssertUnwindSafe(make_task()).catch_unwind().await {
+        panics.inc();
+        error!(
+            task,
+            panic = panic_message(&*panic),
+            "Task panicked, restarting it"
+        );
+        tokio::time::sleep(restart_delay).await;
+    }
+}
+
+/// The message a task panicked with
+pub fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_panicked_tasks_are_restarted() {
+        let panics = IntCounter::new("panics", "help string").unwrap();
+        let mut runs = 0;
+        supervise_with_delay("test", panics.clone(), Duration::ZERO, || {
+            runs += 1;
+            let run = runs;
+            async move {
+                if run < 3 {
+                    panic!("run {run} panicked");
+                }
+            }
+        })
+        .await;
+        assert_eq!(runs, 3);
+        assert_eq!(panics.get(), 2);
+    }
+
+    #[test]
+    fn test_panic_messages() {
+        let message = std::panic::catch_unwind(|| panic!("static")).unwrap_err();
+        assert_eq!(panic_message(&*message), "static");
+        let message = std::panic::catch_unwind(|| panic!("formatted {}", 1)).unwrap_err();
+        assert_eq!(panic_message(&*message), "formatted 1");
+    }
+}
@@ -13,6 +13,11 @@ mod m20230309_000003_create_table_transaction;
 mod m20230309_000004_create_table_delivered_message;
 mod m20230309_000004_create_table_gas_payment;
 mod m20230309_000005_create_table_message;
+mod m20250315_000001_create_table_chain_registry;
+mod m20250401_000001_create_table_mailbox_config_change;
+mod m20250415_000001_add_environment;
+mod m20250501_000001_add_invalidated;
+mod m20250515_000001_create_view_message_payment_summary;
 
 pub struct Migrator;
 
@@ -30,6 +35,11 @@ impl MigratorTrait for Migrator {
             Box::new(m20230309_000004_create_table_gas_payment::Migration),
             Box::new(m20230309_000004_create_table_delivered_message::Migration),
             Box::new(m20230309_000005_create_table_message::Migration),
+            Box::new(m20250315_000001_create_table_chain_registry::Migration),
+            Box::new(m20250401_000001_create_table_mailbox_config_change::Migration),
+            Box::new(m20250415_000001_add_environment::Migration),
+            Box::new(m20250501_000001_add_invalidated::Migration),
+            Box::new(m20250515_000001_create_view_message_payment_summary::Migration),
         ]
     }
 }
@@ -79,4 +79,6 @@ pub enum Cursor {
     TimeCreated,
     /// Height of the last block read for finality
     Height,
+    /// Environment the chain is scraped for
+    Environment,
 }
@@ -131,4 +131,8 @@ pub enum DeliveredMessage {
     DestinationTxId,
     /// Sequence when message was delivered
     Sequence,
+    /// Environment the delivery was scraped for
+    Environment,
+    /// Whether the delivery was scraped from a block that was reorged out
+    Invalidated,
 }
@@ -194,6 +194,10 @@ pub enum GasPayment {
     /// Sequence of this payment for indexing by agent. It can be null if agent
     /// does not use sequence-aware indexing.
     Sequence,
+    /// Environment the payment was scraped for
+    Environment,
+    /// Whether the payment was scraped from a block that was reorged out
+    Invalidated,
 }
 
 #[derive(Iden)]
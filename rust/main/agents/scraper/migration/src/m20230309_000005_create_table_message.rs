@@ -283,4 +283,8 @@ pub enum Message {
     OriginMailbox,
     /// Transaction this message was dispatched in on the origin chain.
     OriginTxId,
+    /// Environment the message was scraped for, e.g. `mainnet` or `testnet`
+    Environment,
+    /// Whether the message was scraped from a block that was reorged out
+    Invalidated,
 }
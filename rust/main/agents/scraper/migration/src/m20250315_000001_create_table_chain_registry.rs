@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000001_create_table_domain::Domain;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChainRegistry::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainRegistry::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainRegistry::TimeCreated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(
+                        ColumnDef::new(ChainRegistry::Domain)
+                            .unsigned()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ChainRegistry::ChainName).text().not_null())
+                    .col(
+                        ColumnDef::new(ChainRegistry::FromBlock)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(ChainRegistry::Domain)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChainRegistry::Table).to_owned())
+            .await
+    }
+}
+
+/// Chains the scraper picks up at runtime, in addition to the ones it is
+/// configured to scrape.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum ChainRegistry {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time when the record was created
+    TimeCreated,
+    /// Hyperlane domain ID of the chain to scrape
+    Domain,
+    /// Name of the chain in the agent configuration, whose connection settings
+    /// are used to scrape it
+    ChainName,
+    /// Block height to start scraping from
+    FromBlock,
+    /// Environment to scrape the chain for. Null to use the environment the
+    /// scraper configures for the chain.
+    Environment,
+}
@@ -0,0 +1,125 @@
+use sea_orm_migration::prelude::*;
+
+use crate::l20230309_types::*;
+use crate::m20230309_000001_create_table_domain::Domain;
+use crate::m20230309_000003_create_table_transaction::Transaction;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MailboxConfigChange::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MailboxConfigChange::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MailboxConfigChange::TimeCreated)
+                            .timestamp()
+                            .not_null()
+                            .default("NOW()"),
+                    )
+                    .col(
+                        ColumnDef::new(MailboxConfigChange::Domain)
+                            .unsigned()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new_with_type(MailboxConfigChange::Mailbox, Address).not_null())
+                    .col(ColumnDef::new(MailboxConfigChange::Kind).text().not_null())
+                    .col(ColumnDef::new_with_type(
+                        MailboxConfigChange::PreviousValue,
+                        Address,
+                    ))
+                    .col(ColumnDef::new_with_type(
+                        MailboxConfigChange::NewValue,
+                        Address,
+                    ))
+                    .col(
+                        ColumnDef::new(MailboxConfigChange::TxId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MailboxConfigChange::LogIndex)
+                            .big_unsigned()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(MailboxConfigChange::TxId)
+                            .to(Transaction::Table, Transaction::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(MailboxConfigChange::Domain)
+                            .to(Domain::Table, Domain::Id),
+                    )
+                    .index(
+                        Index::create()
+                            // don't need domain because TxId includes it
+                            .col(MailboxConfigChange::TxId)
+                            .col(MailboxConfigChange::LogIndex)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .table(MailboxConfigChange::Table)
+                    .name("mailbox_config_change_domain_id_idx")
+                    .col(MailboxConfigChange::Domain)
+                    .col(MailboxConfigChange::Id)
+                    .index_type(IndexType::BTree)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MailboxConfigChange::Table).to_owned())
+            .await
+    }
+}
+
+/// Configuration changes of the mailboxes, e.g. for security monitoring to
+/// alert on unexpected changes.
+///
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum MailboxConfigChange {
+    Table,
+    /// Unique database ID
+    Id,
+    /// Time of record creation
+    TimeCreated,
+    /// Domain ID of the chain the mailbox is on
+    Domain,
+    /// Address of the mailbox
+    Mailbox,
+    /// Kind of the change, i.e. `default_ism_set` or `ownership_transferred`
+    Kind,
+    /// Configured address before the change, e.g. the previous default ISM.
+    /// Null if there was none, e.g. if the mailbox had no owner.
+    PreviousValue,
+    /// Configured address after the change, e.g. the new owner. Null if there
+    /// is none, e.g. if ownership was renounced.
+    NewValue,
+    /// Transaction the change was made in
+    TxId,
+    /// Used to disambiguate multiple changes made in the same transaction
+    LogIndex,
+    /// Environment the change was scraped for
+    Environment,
+}
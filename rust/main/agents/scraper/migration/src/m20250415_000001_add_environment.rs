@@ -0,0 +1,193 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000003_create_table_cursor::Cursor;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000004_create_table_gas_payment::GasPayment;
+use crate::m20230309_000005_create_table_message::Message;
+use crate::m20250315_000001_create_table_chain_registry::ChainRegistry;
+use crate::m20250401_000001_create_table_mailbox_config_change::MailboxConfigChange;
+
+/// Environment of the rows scraped before environments were introduced, and
+/// of the chains a scraper doesn't configure an environment for
+const DEFAULT_ENVIRONMENT: &str = "default";
+
+/// The view of messages before the environment was added to it, which the
+/// new view extends
+const MESSAGE_VIEW_WITHOUT_ENVIRONMENT: &str = "message_view_without_environment";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Events are tagged with the environment they were scraped for, so
+        // one database can serve several explorers. Blocks, transactions and
+        // domains are chain data shared by all environments.
+        add_environment(manager, Message::Table, Message::Environment).await?;
+        add_environment(
+            manager,
+            DeliveredMessage::Table,
+            DeliveredMessage::Environment,
+        )
+        .await?;
+        add_environment(manager, GasPayment::Table, GasPayment::Environment).await?;
+        add_environment(
+            manager,
+            MailboxConfigChange::Table,
+            MailboxConfigChange::Environment,
+        )
+        .await?;
+        add_environment(manager, Cursor::Table, Cursor::Environment).await?;
+
+        // Retention policies delete the events of an environment by age
+        create_environment_index(
+            manager,
+            Message::Table,
+            Message::Environment,
+            Message::TimeCreated,
+        )
+        .await?;
+        create_environment_index(
+            manager,
+            DeliveredMessage::Table,
+            DeliveredMessage::Environment,
+            DeliveredMessage::TimeCreated,
+        )
+        .await?;
+        create_environment_index(
+            manager,
+            GasPayment::Table,
+            GasPayment::Environment,
+            GasPayment::TimeCreated,
+        )
+        .await?;
+        create_environment_index(
+            manager,
+            MailboxConfigChange::Table,
+            MailboxConfigChange::Environment,
+            MailboxConfigChange::TimeCreated,
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChainRegistry::Table)
+                    .add_column(ColumnDef::new(ChainRegistry::Environment).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Explorers read messages through the view, so it exposes their
+        // environment too
+        let sql = format!(
+            r#"
+            ALTER VIEW "{msg_table}_view" RENAME TO "{base_view}";
+            CREATE VIEW "{msg_table}_view" AS
+            SELECT
+                "base".*,
+                "msg"."{msg_env}" AS "environment"
+            FROM "{base_view}" AS "base"
+                INNER JOIN "{msg_table}"
+                    AS "msg"
+                    ON "msg"."{msg_id}" = "base"."id";
+            "#,
+            msg_table = Message::Table.to_string(),
+            msg_id = Message::Id.to_string(),
+            msg_env = Message::Environment.to_string(),
+            base_view = MESSAGE_VIEW_WITHOUT_ENVIRONMENT,
+        );
+        manager.get_connection().execute_unprepared(&sql).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = format!(
+            r#"
+            DROP VIEW IF EXISTS "{msg_table}_view";
+            ALTER VIEW "{base_view}" RENAME TO "{msg_table}_view";
+            "#,
+            msg_table = Message::Table.to_string(),
+            base_view = MESSAGE_VIEW_WITHOUT_ENVIRONMENT,
+        );
+        manager.get_connection().execute_unprepared(&sql).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ChainRegistry::Table)
+                    .drop_column(ChainRegistry::Environment)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Dropping the columns drops their indexes too
+        drop_environment(manager, Cursor::Table, Cursor::Environment).await?;
+        drop_environment(
+            manager,
+            MailboxConfigChange::Table,
+            MailboxConfigChange::Environment,
+        )
+        .await?;
+        drop_environment(manager, GasPayment::Table, GasPayment::Environment).await?;
+        drop_environment(
+            manager,
+            DeliveredMessage::Table,
+            DeliveredMessage::Environment,
+        )
+        .await?;
+        drop_environment(manager, Message::Table, Message::Environment).await
+    }
+}
+
+async fn add_environment(
+    manager: &SchemaManager<'_>,
+    table: impl IntoIden,
+    column: impl IntoIden,
+) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(table)
+                .add_column(
+                    ColumnDef::new(column)
+                        .text()
+                        .not_null()
+                        .default(DEFAULT_ENVIRONMENT),
+                )
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_environment(
+    manager: &SchemaManager<'_>,
+    table: impl IntoIden,
+    column: impl IntoIden,
+) -> Result<(), DbErr> {
+    manager
+        .alter_table(Table::alter().table(table).drop_column(column).to_owned())
+        .await
+}
+
+async fn create_environment_index<T: Iden + 'static>(
+    manager: &SchemaManager<'_>,
+    table: T,
+    environment: T,
+    time_created: T,
+) -> Result<(), DbErr> {
+    let name = format!("{}_environment_time_created_idx", table.to_string());
+    manager
+        .create_index(
+            Index::create()
+                .table(table)
+                .name(&name)
+                .col(environment)
+                .col(time_created)
+                .to_owned(),
+        )
+        .await
+}
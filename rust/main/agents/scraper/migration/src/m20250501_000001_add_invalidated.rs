@@ -0,0 +1,96 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000004_create_table_gas_payment::GasPayment;
+use crate::m20230309_000005_create_table_message::Message;
+
+/// The view of messages before the invalidation flag was added to it, which
+/// the new view extends
+const MESSAGE_VIEW_WITHOUT_INVALIDATED: &str = "message_view_without_invalidated";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Events scraped from blocks that were later reorged out are flagged
+        // until they're scraped again from the canonical chain
+        add_invalidated(manager, Message::Table, Message::Invalidated).await?;
+        add_invalidated(
+            manager,
+            DeliveredMessage::Table,
+            DeliveredMessage::Invalidated,
+        )
+        .await?;
+        add_invalidated(manager, GasPayment::Table, GasPayment::Invalidated).await?;
+
+        let sql = format!(
+            r#"
+            ALTER VIEW "{msg_table}_view" RENAME TO "{base_view}";
+            CREATE VIEW "{msg_table}_view" AS
+            SELECT
+                "base".*,
+                "msg"."{msg_invalidated}" AS "invalidated"
+            FROM "{base_view}" AS "base"
+                INNER JOIN "{msg_table}"
+                    AS "msg"
+                    ON "msg"."{msg_id}" = "base"."id";
+            "#,
+            msg_table = Message::Table.to_string(),
+            msg_id = Message::Id.to_string(),
+            msg_invalidated = Message::Invalidated.to_string(),
+            base_view = MESSAGE_VIEW_WITHOUT_INVALIDATED,
+        );
+        manager.get_connection().execute_unprepared(&sql).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = format!(
+            r#"
+            DROP VIEW IF EXISTS "{msg_table}_view";
+            ALTER VIEW "{base_view}" RENAME TO "{msg_table}_view";
+            "#,
+            msg_table = Message::Table.to_string(),
+            base_view = MESSAGE_VIEW_WITHOUT_INVALIDATED,
+        );
+        manager.get_connection().execute_unprepared(&sql).await?;
+
+        drop_invalidated(manager, GasPayment::Table, GasPayment::Invalidated).await?;
+        drop_invalidated(
+            manager,
+            DeliveredMessage::Table,
+            DeliveredMessage::Invalidated,
+        )
+        .await?;
+        drop_invalidated(manager, Message::Table, Message::Invalidated).await
+    }
+}
+
+async fn add_invalidated(
+    manager: &SchemaManager<'_>,
+    table: impl IntoIden,
+    column: impl IntoIden,
+) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(table)
+                .add_column(ColumnDef::new(column).boolean().not_null().default(false))
+                .to_owned(),
+        )
+        .await
+}
+
+async fn drop_invalidated(
+    manager: &SchemaManager<'_>,
+    table: impl IntoIden,
+    column: impl IntoIden,
+) -> Result<(), DbErr> {
+    manager
+        .alter_table(Table::alter().table(table).drop_column(column).to_owned())
+        .await
+}
@@ -0,0 +1,156 @@
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000002_create_table_block::Block;
+use crate::m20230309_000003_create_table_transaction::Transaction;
+use crate::m20230309_000004_create_table_delivered_message::DeliveredMessage;
+use crate::m20230309_000004_create_table_gas_payment::GasPayment;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Payments are aggregated per message and environment across all
+        // payment transactions and IGPs, and joined with the delivery
+        // transaction (if any) so the amount paid can be compared to what
+        // delivering the message cost. Payments and deliveries invalidated by
+        // a reorg are left out until they're scraped again.
+        let sql = format!(
+            r#"
+            CREATE VIEW "{mps_table}" AS
+            SELECT
+                "gp"."{mps_mid}",
+                "gp"."{mps_env}",
+                "gp"."{mps_origin}",
+                "gp"."{mps_dest}",
+                "gp"."{mps_num_payments}",
+                "gp"."{mps_total_payment}",
+                "gp"."{mps_total_gas_amount}",
+                "gp"."{mps_first_payment_at}",
+                "gp"."{mps_last_payment_at}",
+
+                "dmsg"."{dmsg_id}" IS NOT NULL AS "{mps_is_delivered}",
+                "dest_tx"."{tx_gas_used}" AS "{mps_dest_gas_used}",
+                "dest_tx"."{tx_egp}" AS "{mps_dest_egp}",
+                "dest_tx"."{tx_gas_used}" * "dest_tx"."{tx_egp}" AS "{mps_dest_cost}"
+            FROM (
+                SELECT
+                    "payment"."{gp_mid}" AS "{mps_mid}",
+                    "payment"."{gp_env}" AS "{mps_env}",
+                    MIN("payment"."{gp_origin}") AS "{mps_origin}",
+                    MIN("payment"."{gp_dest}") AS "{mps_dest}",
+                    COUNT("payment"."{gp_id}") AS "{mps_num_payments}",
+                    SUM("payment"."{gp_payment}") AS "{mps_total_payment}",
+                    SUM("payment"."{gp_gas_amount}") AS "{mps_total_gas_amount}",
+                    MIN("payment_block"."{block_timestamp}") AS "{mps_first_payment_at}",
+                    MAX("payment_block"."{block_timestamp}") AS "{mps_last_payment_at}"
+                FROM "{gp_table}" AS "payment"
+                    LEFT JOIN "{tx_table}"
+                        AS "payment_tx"
+                        ON "payment_tx"."{tx_id}" = "payment"."{gp_tx_id}"
+                    LEFT JOIN "{block_table}"
+                        AS "payment_block"
+                        ON "payment_block"."{block_id}" = "payment_tx"."{tx_block_id}"
+                WHERE NOT "payment"."{gp_invalidated}"
+                GROUP BY "payment"."{gp_mid}", "payment"."{gp_env}"
+            ) AS "gp"
+                LEFT JOIN "{dmsg_table}"
+                    AS "dmsg"
+                    ON "dmsg"."{dmsg_mid}" = "gp"."{mps_mid}"
+                        AND "dmsg"."{dmsg_env}" = "gp"."{mps_env}"
+                        AND NOT "dmsg"."{dmsg_invalidated}"
+                LEFT JOIN "{tx_table}"
+                    AS "dest_tx"
+                    ON "dest_tx"."{tx_id}" = "dmsg"."{dmsg_dti}"
+            "#,
+            mps_table = MessagePaymentSummary::Table.to_string(),
+            mps_mid = MessagePaymentSummary::MsgId.to_string(),
+            mps_env = MessagePaymentSummary::Environment.to_string(),
+            mps_origin = MessagePaymentSummary::Origin.to_string(),
+            mps_dest = MessagePaymentSummary::Destination.to_string(),
+            mps_num_payments = MessagePaymentSummary::NumPayments.to_string(),
+            mps_total_payment = MessagePaymentSummary::TotalPayment.to_string(),
+            mps_total_gas_amount = MessagePaymentSummary::TotalGasAmount.to_string(),
+            mps_first_payment_at = MessagePaymentSummary::FirstPaymentAt.to_string(),
+            mps_last_payment_at = MessagePaymentSummary::LastPaymentAt.to_string(),
+            mps_is_delivered = MessagePaymentSummary::IsDelivered.to_string(),
+            mps_dest_gas_used = MessagePaymentSummary::DestinationTxGasUsed.to_string(),
+            mps_dest_egp = MessagePaymentSummary::DestinationTxEffectiveGasPrice.to_string(),
+            mps_dest_cost = MessagePaymentSummary::DestinationTxCost.to_string(),
+            gp_table = GasPayment::Table.to_string(),
+            gp_id = GasPayment::Id.to_string(),
+            gp_mid = GasPayment::MsgId.to_string(),
+            gp_env = GasPayment::Environment.to_string(),
+            gp_invalidated = GasPayment::Invalidated.to_string(),
+            gp_origin = GasPayment::Origin.to_string(),
+            gp_dest = GasPayment::Destination.to_string(),
+            gp_payment = GasPayment::Payment.to_string(),
+            gp_gas_amount = GasPayment::GasAmount.to_string(),
+            gp_tx_id = GasPayment::TxId.to_string(),
+            tx_table = Transaction::Table.to_string(),
+            tx_id = Transaction::Id.to_string(),
+            tx_block_id = Transaction::BlockId.to_string(),
+            tx_gas_used = Transaction::GasUsed.to_string(),
+            tx_egp = Transaction::EffectiveGasPrice.to_string(),
+            block_table = Block::Table.to_string(),
+            block_id = Block::Id.to_string(),
+            block_timestamp = Block::Timestamp.to_string(),
+            dmsg_table = DeliveredMessage::Table.to_string(),
+            dmsg_id = DeliveredMessage::Id.to_string(),
+            dmsg_mid = DeliveredMessage::MsgId.to_string(),
+            dmsg_env = DeliveredMessage::Environment.to_string(),
+            dmsg_invalidated = DeliveredMessage::Invalidated.to_string(),
+            dmsg_dti = DeliveredMessage::DestinationTxId.to_string(),
+        );
+
+        manager.get_connection().execute_unprepared(&sql).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"DROP VIEW IF EXISTS "{}""#,
+                MessagePaymentSummary::Table.to_string()
+            ))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum MessagePaymentSummary {
+    Table,
+    /// Unique id of the message which was paid for
+    MsgId,
+    /// Environment the payments were scraped for
+    Environment,
+    /// Domain ID of the chain the payments were made on
+    Origin,
+    /// Domain ID of the chain the payments were made for
+    Destination,
+    /// Number of payments made for the message
+    NumPayments,
+    /// Total amount of native tokens paid on the origin chain
+    TotalPayment,
+    /// Total amount of destination gas paid for
+    TotalGasAmount,
+    /// Block timestamp of the earliest payment
+    FirstPaymentAt,
+    /// Block timestamp of the latest payment
+    LastPaymentAt,
+    /// Whether the message has been delivered
+    IsDelivered,
+    /// Gas used by the delivery transaction. Note that if the message was
+    /// delivered in a batch, this is the gas used by the whole batch.
+    DestinationTxGasUsed,
+    /// Effective gas price of the delivery transaction
+    DestinationTxEffectiveGasPrice,
+    /// Cost of the delivery transaction in destination native tokens
+    DestinationTxCost,
+}
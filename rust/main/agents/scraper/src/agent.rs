@@ -1,19 +1,33 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use derive_more::AsRef;
-use futures::future::try_join_all;
-use hyperlane_core::{Delivery, HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, H512};
+use futures::{future::try_join_all, stream::FuturesUnordered, StreamExt};
+use hyperlane_core::{
+    Delivery, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneLogStore, HyperlaneMessage,
+    IndexMode, Indexer, InterchainGasPayment, MailboxConfigChange, H512,
+};
+use prometheus::IntCounterVec;
 use tokio::{sync::mpsc::Receiver as MpscReceiver, task::JoinHandle};
-use tracing::{info, info_span, instrument::Instrumented, trace, Instrument};
+use tracing::{error, info, info_span, instrument::Instrumented, trace, warn, Instrument};
 
 use hyperlane_base::{
-    broadcast::BroadcastMpscSender, metrics::AgentMetrics, settings::IndexSettings, AgentMetadata,
-    BaseAgent, ChainMetrics, ChainSpecificMetricsUpdater, ContractSyncMetrics, ContractSyncer,
-    CoreMetrics, HyperlaneAgentCore, RuntimeMetrics, SyncOptions,
+    broadcast::BroadcastMpscSender,
+    metrics::AgentMetrics,
+    settings::{DeliveryIndexer, IgpIndexer, IndexSettings, MessageIndexer, TryFromWithMetrics},
+    AgentMetadata, BaseAgent, ChainMetrics, ChainSpecificMetricsUpdater, ContractSyncMetrics,
+    ContractSyncer, CoreMetrics, HyperlaneAgentCore, RuntimeMetrics, SyncOptions,
 };
 
-use crate::{db::ScraperDb, settings::ScraperSettings, store::HyperlaneDbStore};
+use crate::{date_time, db::ScraperDb, settings::ScraperSettings, store::HyperlaneDbStore};
+
+/// How often the events that outlived the retention period of their
+/// environment are deleted
+const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 /// A message explorer scraper agent
 #[derive(Debug, AsRef)]
@@ -23,11 +37,14 @@ pub struct Scraper {
     core: HyperlaneAgentCore,
     contract_sync_metrics: Arc<ContractSyncMetrics>,
     scrapers: HashMap<u32, ChainScraper>,
+    db: ScraperDb,
     settings: ScraperSettings,
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
     runtime_metrics: RuntimeMetrics,
+    /// Scraped blocks reorged out of the chain past its reorg period, by chain
+    reorged_blocks: IntCounterVec,
 }
 
 #[derive(Debug)]
@@ -37,6 +54,13 @@ struct ChainScraper {
     domain: HyperlaneDomain,
 }
 
+/// The indexers scraping the events of reorged blocks again
+struct ReorgIndexers {
+    messages: MessageIndexer,
+    deliveries: DeliveryIndexer,
+    payments: IgpIndexer,
+}
+
 #[async_trait]
 impl BaseAgent for Scraper {
     const AGENT_NAME: &'static str = "scraper";
@@ -54,6 +78,9 @@ impl BaseAgent for Scraper {
     where
         Self: Sized,
     {
+        settings
+            .verify_contract_deployments(settings.chains_to_scrape.iter(), &metrics)
+            .await?;
         let db = ScraperDb::connect(&settings.db).await?;
         let core = settings.build_hyperlane_core(metrics.clone());
 
@@ -65,15 +92,23 @@ impl BaseAgent for Scraper {
 
         trace!(domain_count = scrapers.len(), "Created scrapers");
 
+        let reorged_blocks = metrics.new_int_counter(
+            "reorged_blocks",
+            "Scraped blocks that were reorged out of the chain past its reorg period",
+            &["chain"],
+        )?;
+
         Ok(Self {
             core,
             contract_sync_metrics,
             scrapers,
+            db,
             settings,
             core_metrics: metrics,
             agent_metrics,
             chain_metrics,
             runtime_metrics,
+            reorged_blocks,
         })
     }
 
@@ -91,48 +126,31 @@ impl BaseAgent for Scraper {
         tasks.push(server_task);
 
         for scraper in self.scrapers.values() {
-            let chain_conf = match self.settings.chain_setup(&scraper.domain) {
-                Ok(s) => s,
-                Err(err) => {
-                    tracing::error!(?err, ?scraper.domain, "Failed to get chain config");
-                    self.chain_metrics
-                        .set_critical_error(scraper.domain.name(), true);
-                    continue;
-                }
-            };
+            tasks.extend(self.run_chain_scraper(scraper).await);
+        }
+        tasks.push(self.runtime_metrics.spawn());
 
-            let metrics_updater = match ChainSpecificMetricsUpdater::new(
-                chain_conf,
-                self.core_metrics.clone(),
-                self.agent_metrics.clone(),
-                self.chain_metrics.clone(),
-                Self::AGENT_NAME.to_string(),
-            )
-            .await
-            {
-                Ok(metrics_updater) => metrics_updater,
-                Err(err) => {
-                    tracing::error!(?err, ?scraper.domain, "Failed to build metrics updater");
-                    self.chain_metrics
-                        .set_critical_error(scraper.domain.name(), true);
-                    continue;
-                }
-            };
+        if !self.settings.retention_periods.is_empty() {
+            let retention_task = tokio::spawn(Self::delete_expired_events(
+                self.db.clone(),
+                self.settings.retention_periods.clone(),
+            ))
+            .instrument(info_span!("Retention"));
+            tasks.push(retention_task);
+        }
 
-            match self.scrape(scraper).await {
-                Ok(scraper_task) => {
-                    tasks.push(scraper_task);
-                }
-                Err(err) => {
-                    tracing::error!(?err, ?scraper.domain, "Failed to scrape domain");
-                    self.chain_metrics
-                        .set_critical_error(scraper.domain.name(), true);
-                    continue;
-                }
-            }
-            tasks.push(metrics_updater.spawn());
+        if let Some(polling_interval) = self.settings.chain_registry_polling_interval {
+            let scraped_domains = self.scrapers.keys().copied().collect();
+            let scraper = Arc::new(self);
+            let registry_task = tokio::spawn(async move {
+                scraper
+                    .poll_chain_registry(polling_interval, scraped_domains)
+                    .await
+            })
+            .instrument(info_span!("ChainRegistry"));
+            tasks.push(registry_task);
         }
-        tasks.push(self.runtime_metrics.spawn());
+
         if let Err(err) = try_join_all(tasks).await {
             tracing::error!(error = ?err, "Scraper task panicked");
         }
@@ -140,6 +158,100 @@ impl BaseAgent for Scraper {
 }
 
 impl Scraper {
+    /// Spawns the tasks scraping a chain and updating its metrics. Failures
+    /// are reported with the chain's critical error metric.
+    async fn run_chain_scraper(&self, scraper: &ChainScraper) -> Vec<Instrumented<JoinHandle<()>>> {
+        let chain_conf = match self.settings.chain_setup(&scraper.domain) {
+            Ok(s) => s,
+            Err(err) => {
+                tracing::error!(?err, ?scraper.domain, "Failed to get chain config");
+                self.chain_metrics
+                    .set_critical_error(scraper.domain.name(), true);
+                return vec![];
+            }
+        };
+
+        let metrics_updater = match ChainSpecificMetricsUpdater::new(
+            chain_conf,
+            self.core_metrics.clone(),
+            self.agent_metrics.clone(),
+            self.chain_metrics.clone(),
+            Self::AGENT_NAME.to_string(),
+        )
+        .await
+        {
+            Ok(metrics_updater) => metrics_updater,
+            Err(err) => {
+                tracing::error!(?err, ?scraper.domain, "Failed to build metrics updater");
+                self.chain_metrics
+                    .set_critical_error(scraper.domain.name(), true);
+                return vec![];
+            }
+        };
+
+        match self.scrape(scraper).await {
+            Ok(scraper_task) => vec![scraper_task, metrics_updater.spawn()],
+            Err(err) => {
+                tracing::error!(?err, ?scraper.domain, "Failed to scrape domain");
+                self.chain_metrics
+                    .set_critical_error(scraper.domain.name(), true);
+                vec![]
+            }
+        }
+    }
+
+    /// Periodically starts scraping the chains added to the chain registry
+    /// table, so chains can be onboarded without redeploying the scraper.
+    async fn poll_chain_registry(
+        &self,
+        polling_interval: Duration,
+        mut scraped_domains: HashSet<u32>,
+    ) {
+        let mut tasks = FuturesUnordered::new();
+        let mut interval = tokio::time::interval(polling_interval);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let scrapers = Self::build_registered_chain_scrapers(
+                        &self.settings,
+                        self.core_metrics.clone(),
+                        &self.chain_metrics,
+                        self.db.clone(),
+                        &mut scraped_domains,
+                    )
+                    .await;
+                    for scraper in scrapers {
+                        tasks.extend(self.run_chain_scraper(&scraper).await);
+                    }
+                }
+                Some(result) = tasks.next() => {
+                    // If any of the tasks panic, we want to propagate it
+                    result.expect("Registered chain scraper task panicked");
+                }
+            }
+        }
+    }
+
+    /// Periodically deletes the events that were scraped longer ago than the
+    /// retention period of their environment.
+    async fn delete_expired_events(db: ScraperDb, retention_periods: HashMap<String, Duration>) {
+        let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+        loop {
+            interval.tick().await;
+            for (environment, retention_period) in &retention_periods {
+                let cutoff = date_time::now() - *retention_period;
+                match db
+                    .with_environment(environment)
+                    .delete_events_scraped_before(cutoff)
+                    .await
+                {
+                    Ok(deleted) => info!(environment, deleted, "Deleted expired events"),
+                    Err(err) => warn!(environment, ?err, "Failed to delete expired events"),
+                }
+            }
+        }
+    }
+
     /// Sync contract data and other blockchain with the current chain state.
     /// This will spawn long-running contract sync tasks
     async fn scrape(&self, scraper: &ChainScraper) -> eyre::Result<Instrumented<JoinHandle<()>>> {
@@ -147,7 +259,7 @@ impl Scraper {
         let index_settings = scraper.index_settings.clone();
         let domain = scraper.domain.clone();
 
-        let mut tasks = Vec::with_capacity(2);
+        let mut tasks = Vec::with_capacity(4);
         let (message_indexer, maybe_broadcaster) = self
             .build_message_indexer(
                 domain.clone(),
@@ -172,16 +284,46 @@ impl Scraper {
 
         let gas_payment_indexer = self
             .build_interchain_gas_payment_indexer(
-                domain,
+                domain.clone(),
                 self.core_metrics.clone(),
                 self.contract_sync_metrics.clone(),
-                store,
+                store.clone(),
                 index_settings.clone(),
                 BroadcastMpscSender::<H512>::map_get_receiver(maybe_broadcaster.as_ref()).await,
             )
             .await?;
         tasks.push(gas_payment_indexer);
 
+        if let Some(reorg_check_interval) = self.settings.reorg_check_interval {
+            // Events are only scraped again by block range
+            if matches!(index_settings.mode, IndexMode::Block) {
+                let reorg_watcher = self
+                    .build_reorg_watcher(
+                        domain.clone(),
+                        store.clone(),
+                        index_settings.chunk_size,
+                        reorg_check_interval,
+                    )
+                    .await?;
+                tasks.push(reorg_watcher);
+            }
+        }
+
+        // Only Sealevel mailboxes log their config changes in a way that can
+        // be indexed
+        if domain.domain_protocol() == HyperlaneDomainProtocol::Sealevel {
+            let config_change_indexer = self
+                .build_mailbox_config_change_indexer(
+                    domain,
+                    self.core_metrics.clone(),
+                    self.contract_sync_metrics.clone(),
+                    store,
+                    index_settings.clone(),
+                )
+                .await?;
+            tasks.push(config_change_indexer);
+        }
+
         Ok(tokio::spawn(async move {
             // If any of the tasks panic, we want to propagate it, so we unwrap
             try_join_all(tasks).await.unwrap();
@@ -189,14 +331,125 @@ impl Scraper {
         .instrument(info_span!("Scraper Tasks")))
     }
 
+    /// Spawns a task periodically checking the recently scraped blocks of the
+    /// chain for reorgs deeper than its reorg period
+    async fn build_reorg_watcher(
+        &self,
+        domain: HyperlaneDomain,
+        store: HyperlaneDbStore,
+        chunk_size: u32,
+        check_interval: Duration,
+    ) -> eyre::Result<Instrumented<JoinHandle<()>>> {
+        let chain_setup = self.settings.chain_setup(&domain)?;
+        let indexers = ReorgIndexers {
+            messages: MessageIndexer::try_from_with_metrics(chain_setup, &self.core_metrics, true)
+                .await?,
+            deliveries: DeliveryIndexer::try_from_with_metrics(
+                chain_setup,
+                &self.core_metrics,
+                true,
+            )
+            .await?,
+            payments: IgpIndexer::try_from_with_metrics(chain_setup, &self.core_metrics, true)
+                .await?,
+        };
+        let reorged_blocks = self.reorged_blocks.with_label_values(&[domain.name()]);
+        Ok(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+            loop {
+                interval.tick().await;
+                match Self::rescrape_reorged_blocks(&store, &indexers, chunk_size).await {
+                    Ok(reorged) => reorged_blocks.inc_by(reorged as u64),
+                    Err(err) => warn!(?err, "Failed to check scraped blocks for reorgs"),
+                }
+            }
+        })
+        .instrument(info_span!("ReorgWatcher", chain=%domain.name())))
+    }
+
+    /// Invalidates the events scraped from blocks that were reorged out past
+    /// the reorg period, and scrapes the events from the lowest reorged block
+    /// up to the finalized block again. Events scraped again are valid again,
+    /// while events that aren't on the canonical chain anymore stay invalid.
+    ///
+    /// Returns the number of reorged blocks.
+    async fn rescrape_reorged_blocks(
+        store: &HyperlaneDbStore,
+        indexers: &ReorgIndexers,
+        chunk_size: u32,
+    ) -> eyre::Result<usize> {
+        let finalized_height = indexers.messages.get_finalized_block_number().await?;
+        let reorged_blocks = store
+            .invalidate_reorged_blocks(finalized_height.into())
+            .await?;
+        let Some(lowest_height) = reorged_blocks.iter().map(|block| block.height).min() else {
+            return Ok(0);
+        };
+
+        let mut from = u32::try_from(lowest_height)?;
+        info!(
+            from,
+            finalized_height, "Scraping events of reorged blocks again"
+        );
+        while from <= finalized_height {
+            let to = from
+                .saturating_add(chunk_size.max(1) - 1)
+                .min(finalized_height);
+            let range = from..=to;
+            store
+                .store_logs(&indexers.messages.fetch_logs_in_range(range.clone()).await?)
+                .await?;
+            store
+                .store_logs(
+                    &indexers
+                        .deliveries
+                        .fetch_logs_in_range(range.clone())
+                        .await?,
+                )
+                .await?;
+            store
+                .store_logs(&indexers.payments.fetch_logs_in_range(range).await?)
+                .await?;
+            if to == finalized_height {
+                break;
+            }
+            from = to + 1;
+        }
+        Ok(reorged_blocks.len())
+    }
+
+    /// Builds a scraper for the domain, writing to `scraper_db` in the
+    /// environment the domain is scraped for. `from_block` overrides the
+    /// configured height to start indexing at.
+    ///
+    /// Fails if the domain was already scraped for another environment, since
+    /// its events are unique across environments and would be overwritten.
     async fn build_chain_scraper(
         domain: &HyperlaneDomain,
         settings: &ScraperSettings,
         metrics: Arc<CoreMetrics>,
         scraper_db: ScraperDb,
+        from_block: Option<u32>,
     ) -> eyre::Result<ChainScraper> {
-        info!(domain = domain.name(), "create chain scraper for domain");
+        info!(
+            domain = domain.name(),
+            environment = scraper_db.environment(),
+            "create chain scraper for domain"
+        );
+        let other_environments = scraper_db.other_scraped_environments(domain.id()).await?;
+        if !other_environments.is_empty() {
+            eyre::bail!(
+                "Domain {} is already scraped for environments {:?}, not {}",
+                domain.name(),
+                other_environments,
+                scraper_db.environment()
+            );
+        }
         let chain_setup = settings.chain_setup(domain)?;
+        let mut index_settings = chain_setup.index.clone();
+        if let Some(from_block) = from_block {
+            index_settings.from = from_block;
+        }
         info!(domain = domain.name(), "create HyperlaneProvider");
         let provider = settings
             .build_provider(domain, &metrics.clone())
@@ -209,13 +462,13 @@ impl Scraper {
             chain_setup.addresses.mailbox,
             chain_setup.addresses.interchain_gas_paymaster,
             provider,
-            &chain_setup.index.clone(),
+            &index_settings,
         )
         .await?;
         Ok(ChainScraper {
             domain: domain.clone(),
             store,
-            index_settings: chain_setup.index.clone(),
+            index_settings,
         })
     }
 
@@ -228,8 +481,14 @@ impl Scraper {
         let mut scrapers: HashMap<u32, ChainScraper> = HashMap::new();
 
         for domain in settings.chains_to_scrape.iter() {
-            match Self::build_chain_scraper(domain, settings, metrics.clone(), scraper_db.clone())
-                .await
+            match Self::build_chain_scraper(
+                domain,
+                settings,
+                metrics.clone(),
+                scraper_db.with_environment(settings.chain_environment(domain)),
+                None,
+            )
+            .await
             {
                 Ok(scraper) => {
                     info!(domain = domain.name(), "insert chain scraper");
@@ -248,6 +507,81 @@ impl Scraper {
         scrapers
     }
 
+    /// Builds scrapers for the chains in the chain registry that aren't
+    /// scraped yet, and adds them to `scraped_domains`. Chains that fail to
+    /// build are retried the next time. Chains registered for an environment
+    /// this scraper doesn't scrape are left to the scraper that does.
+    async fn build_registered_chain_scrapers(
+        settings: &ScraperSettings,
+        metrics: Arc<CoreMetrics>,
+        chain_metrics: &ChainMetrics,
+        scraper_db: ScraperDb,
+        scraped_domains: &mut HashSet<u32>,
+    ) -> Vec<ChainScraper> {
+        let registered_chains = match scraper_db.retrieve_registered_chains().await {
+            Ok(registered_chains) => registered_chains,
+            Err(err) => {
+                warn!(?err, "Failed to retrieve registered chains");
+                return vec![];
+            }
+        };
+
+        let mut scrapers = vec![];
+        for chain in registered_chains {
+            if scraped_domains.contains(&chain.domain) {
+                continue;
+            }
+            if let Some(environment) = &chain.environment {
+                if !settings.scrapes_environment(environment) {
+                    trace!(?chain, "Skipping chain registered for another environment");
+                    continue;
+                }
+            }
+            let domain = match settings.lookup_domain(&chain.chain_name) {
+                Ok(domain) if domain.id() == chain.domain => domain,
+                Ok(domain) => {
+                    error!(
+                        ?chain,
+                        configured_domain = domain.id(),
+                        "Registered chain's domain doesn't match its configuration"
+                    );
+                    chain_metrics.set_critical_error(&chain.chain_name, true);
+                    continue;
+                }
+                Err(err) => {
+                    error!(?chain, ?err, "Missing configuration for registered chain");
+                    chain_metrics.set_critical_error(&chain.chain_name, true);
+                    continue;
+                }
+            };
+            let environment = chain
+                .environment
+                .as_deref()
+                .unwrap_or_else(|| settings.chain_environment(&domain));
+            match Self::build_chain_scraper(
+                &domain,
+                settings,
+                metrics.clone(),
+                scraper_db.with_environment(environment),
+                Some(chain.from_block),
+            )
+            .await
+            {
+                Ok(scraper) => {
+                    info!(?chain, "Start scraping registered chain");
+                    chain_metrics.set_critical_error(&chain.chain_name, false);
+                    scraped_domains.insert(chain.domain);
+                    scrapers.push(scraper);
+                }
+                Err(err) => {
+                    warn!(?chain, ?err, "Failed to build registered chain scraper");
+                    chain_metrics.set_critical_error(&chain.chain_name, true);
+                }
+            }
+        }
+        scrapers
+    }
+
     async fn build_message_indexer(
         &self,
         domain: HyperlaneDomain,
@@ -359,6 +693,41 @@ impl Scraper {
         })
         .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label)))
     }
+
+    async fn build_mailbox_config_change_indexer(
+        &self,
+        domain: HyperlaneDomain,
+        metrics: Arc<CoreMetrics>,
+        contract_sync_metrics: Arc<ContractSyncMetrics>,
+        store: HyperlaneDbStore,
+        index_settings: IndexSettings,
+    ) -> eyre::Result<Instrumented<JoinHandle<()>>> {
+        let sync = self
+            .as_ref()
+            .settings
+            .watermark_contract_sync::<MailboxConfigChange, _>(
+                &domain,
+                &metrics.clone(),
+                &contract_sync_metrics.clone(),
+                Arc::new(store.clone()),
+                true,
+            )
+            .await
+            .map_err(|err| {
+                tracing::error!(?err, ?domain, "Error syncing contract");
+                err
+            })?;
+
+        let label = "mailbox_config_change";
+        let cursor = sync.cursor(index_settings.clone()).await.map_err(|err| {
+            tracing::error!(?err, ?domain, "Error getting cursor");
+            err
+        })?;
+        Ok(tokio::spawn(
+            async move { sync.sync(label, SyncOptions::new(Some(cursor), None)).await },
+        )
+        .instrument(info_span!("ChainContractSync", chain=%domain.name(), event=label)))
+    }
 }
 
 #[cfg(test)]
@@ -367,17 +736,19 @@ mod test {
 
     use ethers::utils::hex;
     use ethers_prometheus::middleware::PrometheusMiddlewareConf;
-    use prometheus::{opts, IntGaugeVec, Registry};
+    use prometheus::{opts, IntGaugeVec};
     use reqwest::Url;
 
     use hyperlane_base::{
         settings::{
             ChainConf, ChainConnectionConf, CoreContractAddresses, Settings, TracingConfig,
         },
+        test_utils::dummy_core_metrics,
         BLOCK_HEIGHT_HELP, BLOCK_HEIGHT_LABELS, CRITICAL_ERROR_HELP, CRITICAL_ERROR_LABELS,
     };
     use hyperlane_core::{
-        config::OperationBatchConfig, IndexMode, KnownHyperlaneDomain, ReorgPeriod, H256,
+        config::OperationBatchConfig, IndexMode, KnownHyperlaneDomain, NativeToken, ReorgPeriod,
+        H256,
     };
     use hyperlane_ethereum as h_eth;
     use sea_orm::{DatabaseBackend, MockDatabase};
@@ -390,6 +761,7 @@ mod test {
             ChainConf {
                 domain: HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
                 signer: None,
+                additional_signers: vec![],
                 reorg_period: ReorgPeriod::None,
                 addresses: CoreContractAddresses {
                     mailbox: H256::from_slice(
@@ -435,6 +807,15 @@ mod test {
                         batch_contract_address: None,
                         max_batch_size: 1,
                     },
+                    native_token: NativeToken {
+                        decimals: 18,
+                        ..Default::default()
+                    },
+                    rpc_rate_limiter: None,
+                    fee_history_cache: None,
+                    log_query_batcher: None,
+                    storage_locations_cache: None,
+                    read_batcher: None,
                 }),
                 metrics_conf: PrometheusMiddlewareConf {
                     contracts: HashMap::new(),
@@ -444,7 +825,9 @@ mod test {
                     from: 0,
                     chunk_size: 1,
                     mode: IndexMode::Block,
+                    block_time: None,
                 },
+                hash_algorithm: Default::default(),
             },
         )];
 
@@ -452,21 +835,27 @@ mod test {
             base: Settings {
                 chains: chains.into_iter().collect(),
                 metrics_port: 5000,
+                persistent_metrics: false,
+                enable_profiling: false,
+                db_backup_dir: None,
+                restore_db_from: None,
                 tracing: TracingConfig::default(),
+                self_test: false,
+                verify_deployments: false,
+                histogram_buckets: HashMap::new(),
             },
             db: String::new(),
             chains_to_scrape: vec![],
+            chain_registry_polling_interval: None,
+            environment: "default".to_owned(),
+            chain_environments: HashMap::new(),
+            retention_periods: HashMap::new(),
+            reorg_check_interval: None,
         }
     }
 
-    #[tokio::test]
-    #[tracing_test::traced_test]
-    async fn test_failed_build_chain_scrapers() {
-        let mut settings = generate_test_scraper_settings();
-
-        let registry = Registry::new();
-        let core_metrics = CoreMetrics::new("scraper", 4000, registry).unwrap();
-        let chain_metrics = ChainMetrics {
+    fn dummy_chain_metrics() -> ChainMetrics {
+        ChainMetrics {
             block_height: IntGaugeVec::new(
                 opts!("block_height", BLOCK_HEIGHT_HELP),
                 BLOCK_HEIGHT_LABELS,
@@ -478,7 +867,16 @@ mod test {
                 CRITICAL_ERROR_LABELS,
             )
             .unwrap(),
-        };
+        }
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_failed_build_chain_scrapers() {
+        let mut settings = generate_test_scraper_settings();
+
+        let core_metrics = dummy_core_metrics("scraper");
+        let chain_metrics = dummy_chain_metrics();
 
         // set the chains we want to scrape
         settings.chains_to_scrape = vec![
@@ -487,12 +885,15 @@ mod test {
         ];
 
         // Create MockDatabase with mock query results
-        let db = MockDatabase::new(DatabaseBackend::Postgres).append_query_results([
-            // First query result
-            vec![[("height", sea_orm::Value::BigInt(Some(100)))]
-                .into_iter()
-                .collect::<BTreeMap<_, _>>()],
-        ]);
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Arbitrum wasn't scraped for other environments
+            .append_query_results([Vec::<BTreeMap<&str, sea_orm::Value>>::new()])
+            .append_query_results([
+                // First query result
+                vec![[("height", sea_orm::Value::BigInt(Some(100)))]
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>()],
+            ]);
         let scraper_db = ScraperDb::with_connection(db.into_connection());
 
         let scrapers = Scraper::build_chain_scrapers(
@@ -520,4 +921,120 @@ mod test {
             .unwrap();
         assert_eq!(metric.get(), 1);
     }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_chains_scraped_for_another_environment_are_not_scraped() {
+        let mut settings = generate_test_scraper_settings();
+        settings.chains_to_scrape = vec![HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum)];
+
+        let core_metrics = dummy_core_metrics("scraper");
+        let chain_metrics = dummy_chain_metrics();
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres).append_query_results([vec![[(
+            "environment",
+            sea_orm::Value::String(Some(Box::new("testnet".to_owned()))),
+        )]
+        .into_iter()
+        .collect::<BTreeMap<_, _>>()]]);
+        let scraper_db = ScraperDb::with_connection(db.into_connection());
+
+        let scrapers = Scraper::build_chain_scrapers(
+            &settings,
+            Arc::new(core_metrics),
+            &chain_metrics,
+            scraper_db,
+        )
+        .await;
+
+        assert!(scrapers.is_empty());
+        let metric = chain_metrics
+            .critical_error
+            .get_metric_with_label_values(&["arbitrum"])
+            .unwrap();
+        assert_eq!(metric.get(), 1);
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_build_registered_chain_scrapers() {
+        let mut settings = generate_test_scraper_settings();
+        settings
+            .chain_environments
+            .insert("arbitrum".to_owned(), "mainnet".to_owned());
+        let core_metrics = Arc::new(dummy_core_metrics("scraper"));
+        let chain_metrics = dummy_chain_metrics();
+
+        let registered_chain = |domain: KnownHyperlaneDomain,
+                                chain_name: &str,
+                                from_block,
+                                environment: Option<&str>| {
+            [
+                ("id", sea_orm::Value::BigInt(Some(domain as i64))),
+                (
+                    "time_created",
+                    sea_orm::Value::TimeDateTime(Some(Box::new(date_time::now()))),
+                ),
+                ("domain", sea_orm::Value::Int(Some(domain as i32))),
+                (
+                    "chain_name",
+                    sea_orm::Value::String(Some(Box::new(chain_name.to_owned()))),
+                ),
+                ("from_block", sea_orm::Value::BigInt(Some(from_block))),
+                (
+                    "environment",
+                    sea_orm::Value::String(environment.map(|env| Box::new(env.to_owned()))),
+                ),
+            ]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>()
+        };
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![
+                registered_chain(KnownHyperlaneDomain::Arbitrum, "arbitrum", 100, None),
+                // Not configured, so it can't be scraped
+                registered_chain(KnownHyperlaneDomain::Ethereum, "ethereum", 5, None),
+                // Left to the scraper of its environment
+                registered_chain(KnownHyperlaneDomain::Sepolia, "sepolia", 5, Some("testnet")),
+            ]])
+            .append_query_results([Vec::<BTreeMap<&str, sea_orm::Value>>::new()])
+            .append_query_results([vec![[("height", sea_orm::Value::BigInt(Some(100)))]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()]]);
+        let scraper_db = ScraperDb::with_connection(db.into_connection());
+
+        let mut scraped_domains = HashSet::new();
+        let scrapers = Scraper::build_registered_chain_scrapers(
+            &settings,
+            core_metrics,
+            &chain_metrics,
+            scraper_db,
+            &mut scraped_domains,
+        )
+        .await;
+
+        assert_eq!(scrapers.len(), 1);
+        assert_eq!(
+            scrapers[0].domain,
+            HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum)
+        );
+        // The registered start block overrides the configured one
+        assert_eq!(scrapers[0].index_settings.from, 100);
+        assert_eq!(scrapers[0].store.db.environment(), "mainnet");
+        assert_eq!(
+            scraped_domains,
+            HashSet::from([KnownHyperlaneDomain::Arbitrum as u32])
+        );
+
+        let metric = chain_metrics
+            .critical_error
+            .get_metric_with_label_values(&["ethereum"])
+            .unwrap();
+        assert_eq!(metric.get(), 1);
+        let metric = chain_metrics
+            .critical_error
+            .get_metric_with_label_values(&["sepolia"])
+            .unwrap();
+        assert_eq!(metric.get(), 0);
+    }
 }
@@ -1,7 +1,7 @@
 use eyre::{Context, Result};
 use sea_orm::{
-    prelude::*, ActiveValue::*, DbErr, EntityTrait, FromQueryResult, Insert, QueryResult,
-    QuerySelect,
+    prelude::*, sea_query::Expr, ActiveValue::*, DbErr, EntityTrait, FromQueryResult, Insert,
+    QueryOrder, QueryResult, QuerySelect,
 };
 use tracing::{debug, trace};
 
@@ -32,6 +32,15 @@ impl FromQueryResult for BasicBlock {
     }
 }
 
+/// A scraped block, as checked against the chain for reorgs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrapedBlock {
+    /// the database id of this block
+    pub id: i64,
+    pub hash: H256,
+    pub height: u64,
+}
+
 impl ScraperDb {
     /// Retrieves the block number for a given block database ID
     pub async fn retrieve_block_number(&self, block_id: i64) -> Result<Option<u64>> {
@@ -44,7 +53,7 @@ impl ScraperDb {
             .select_only()
             .column_as(block::Column::Height, QueryAs::Height)
             .into_values::<i64, QueryAs>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
         match block_height {
             Some(height) => Ok(Some(height.try_into()?)),
@@ -67,7 +76,7 @@ impl ScraperDb {
             .column_as(block::Column::Id, "id")
             .column_as(block::Column::Hash, "hash")
             .into_model::<BasicBlock>()
-            .all(&self.0)
+            .all(&self.conn)
             .await
             .context("When querying blocks")?;
 
@@ -101,7 +110,7 @@ impl ScraperDb {
                     .do_nothing()
                     .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await
         {
             Ok(_) => Ok(()),
@@ -109,4 +118,53 @@ impl ScraperDb {
             Err(e) => Err(e).context("When inserting blocks"),
         }
     }
+
+    /// Retrieves the `limit` highest blocks of the domain at or below
+    /// `max_height`, highest first
+    pub async fn retrieve_recent_blocks(
+        &self,
+        domain: u32,
+        max_height: u64,
+        limit: u64,
+    ) -> Result<Vec<ScrapedBlock>> {
+        let blocks = block::Entity::find()
+            .filter(block::Column::Domain.eq(domain))
+            .filter(block::Column::Height.lte(max_height as i64))
+            .order_by_desc(block::Column::Height)
+            .limit(limit)
+            .all(&self.conn)
+            .await
+            .context("When querying recent blocks")?;
+        blocks
+            .into_iter()
+            .map(|block| {
+                Ok(ScrapedBlock {
+                    id: block.id,
+                    hash: H256::from_slice(&block.hash),
+                    height: block.height.try_into()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces a block that was reorged out of the chain with the canonical
+    /// block at its height. The block keeps its database id, so the
+    /// transactions scraped from it still reference it until they're scraped
+    /// again.
+    pub async fn replace_reorged_block(&self, block_id: i64, canonical: &BlockInfo) -> Result<()> {
+        block::Entity::update_many()
+            .col_expr(
+                block::Column::Hash,
+                Expr::value(address_to_bytes(&canonical.hash)),
+            )
+            .col_expr(
+                block::Column::Timestamp,
+                Expr::value(date_time::from_unix_timestamp_s(canonical.timestamp)),
+            )
+            .filter(block::Column::Id.eq(block_id))
+            .exec(&self.conn)
+            .await
+            .context("When replacing reorged block")?;
+        Ok(())
+    }
 }
@@ -27,11 +27,18 @@ pub struct BlockCursor {
     db: DbConn,
     /// The hyperlane domain this block cursor is for.
     domain: u32,
+    /// The environment the domain is scraped for.
+    environment: String,
     inner: RwLock<BlockCursorInner>,
 }
 
 impl BlockCursor {
-    async fn new(db: DbConn, domain: u32, default_height: u64) -> Result<Self> {
+    async fn new(
+        db: DbConn,
+        domain: u32,
+        environment: String,
+        default_height: u64,
+    ) -> Result<Self> {
         #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
         enum QueryAs {
             Height,
@@ -39,6 +46,7 @@ impl BlockCursor {
 
         let height = (cursor::Entity::find())
             .filter(cursor::Column::Domain.eq(domain))
+            .filter(cursor::Column::Environment.eq(environment.as_str()))
             .order_by(cursor::Column::Height, Order::Desc)
             .select_only()
             .column_as(cursor::Column::Height, QueryAs::Height)
@@ -59,6 +67,7 @@ impl BlockCursor {
         Ok(Self {
             db,
             domain,
+            environment,
             inner: RwLock::new(BlockCursorInner {
                 height,
                 last_saved_at: Instant::now(),
@@ -88,6 +97,7 @@ impl BlockCursor {
                 domain: ActiveValue::Set(self.domain as i32),
                 time_created: ActiveValue::NotSet,
                 height: ActiveValue::Set(height as i64),
+                environment: ActiveValue::Set(self.environment.clone()),
             };
             debug!(?model, "Inserting cursor");
             if let Err(e) = Insert::one(model).exec(&self.db).await {
@@ -101,6 +111,27 @@ impl BlockCursor {
 
 impl ScraperDb {
     pub async fn block_cursor(&self, domain: u32, default_height: u64) -> Result<BlockCursor> {
-        BlockCursor::new(self.clone_connection(), domain, default_height).await
+        BlockCursor::new(
+            self.clone_connection(),
+            domain,
+            self.environment.clone(),
+            default_height,
+        )
+        .await
+    }
+
+    /// The environments other than this one that `domain` has been scraped
+    /// for. The events of a domain are stored once across environments, so a
+    /// domain must only ever be scraped for one of them.
+    pub async fn other_scraped_environments(&self, domain: u32) -> Result<Vec<String>> {
+        Ok(cursor::Entity::find()
+            .select_only()
+            .column(cursor::Column::Environment)
+            .distinct()
+            .filter(cursor::Column::Domain.eq(domain))
+            .filter(cursor::Column::Environment.ne(self.environment.as_str()))
+            .into_tuple::<String>()
+            .all(&self.conn)
+            .await?)
     }
 }
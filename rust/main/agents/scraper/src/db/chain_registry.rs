@@ -0,0 +1,42 @@
+use eyre::Result;
+use sea_orm::{prelude::*, QueryOrder};
+use tracing::instrument;
+
+use crate::db::ScraperDb;
+
+use super::generated::chain_registry;
+
+/// A chain to scrape that was added to the chain registry table at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredChain {
+    /// Hyperlane domain ID of the chain
+    pub domain: u32,
+    /// Name of the chain in the agent configuration
+    pub chain_name: String,
+    /// Block height to start scraping from
+    pub from_block: u32,
+    /// Environment to scrape the chain for, if it differs from the one the
+    /// scraper configures for it
+    pub environment: Option<String>,
+}
+
+impl ScraperDb {
+    /// Get all chains in the chain registry
+    #[instrument(skip(self))]
+    pub async fn retrieve_registered_chains(&self) -> Result<Vec<RegisteredChain>> {
+        chain_registry::Entity::find()
+            .order_by_asc(chain_registry::Column::Id)
+            .all(&self.conn)
+            .await?
+            .into_iter()
+            .map(|chain| {
+                Ok(RegisteredChain {
+                    domain: chain.domain.try_into()?,
+                    chain_name: chain.chain_name,
+                    from_block: chain.from_block.try_into()?,
+                    environment: chain.environment,
+                })
+            })
+            .collect()
+    }
+}
@@ -20,6 +20,8 @@ pub struct Model {
     pub destination_mailbox: Vec<u8>,
     pub destination_tx_id: i64,
     pub sequence: Option<i64>,
+    pub environment: String,
+    pub invalidated: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -31,6 +33,8 @@ pub enum Column {
     DestinationMailbox,
     DestinationTxId,
     Sequence,
+    Environment,
+    Invalidated,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -62,6 +66,8 @@ impl ColumnTrait for Column {
             Self::DestinationMailbox => ColumnType::Binary(BlobSize::Blob(None)).def(),
             Self::DestinationTxId => ColumnType::BigInteger.def(),
             Self::Sequence => ColumnType::BigInteger.def().null(),
+            Self::Environment => ColumnType::Text.def(),
+            Self::Invalidated => ColumnType::Boolean.def(),
         }
     }
 }
@@ -50,9 +50,11 @@ impl PrimaryKeyTrait for PrimaryKey {
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
     Block,
+    ChainRegistry,
     Cursor,
     DeliveredMessage,
     GasPayment,
+    MailboxConfigChange,
     Message,
 }
 
@@ -76,9 +78,13 @@ impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
             Self::Block => Entity::has_many(super::block::Entity).into(),
+            Self::ChainRegistry => Entity::has_many(super::chain_registry::Entity).into(),
             Self::Cursor => Entity::has_many(super::cursor::Entity).into(),
             Self::DeliveredMessage => Entity::has_many(super::delivered_message::Entity).into(),
             Self::GasPayment => Entity::has_many(super::gas_payment::Entity).into(),
+            Self::MailboxConfigChange => {
+                Entity::has_many(super::mailbox_config_change::Entity).into()
+            }
             Self::Message => Entity::has_many(super::message::Entity).into(),
         }
     }
@@ -90,6 +96,12 @@ impl Related<super::block::Entity> for Entity {
     }
 }
 
+impl Related<super::chain_registry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChainRegistry.def()
+    }
+}
+
 impl Related<super::cursor::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Cursor.def()
@@ -108,6 +120,12 @@ impl Related<super::gas_payment::Entity> for Entity {
     }
 }
 
+impl Related<super::mailbox_config_change::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MailboxConfigChange.def()
+    }
+}
+
 impl Related<super::message::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Message.def()
@@ -25,6 +25,8 @@ pub struct Model {
     pub destination: i32,
     pub interchain_gas_paymaster: Vec<u8>,
     pub sequence: Option<i64>,
+    pub environment: String,
+    pub invalidated: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -41,6 +43,8 @@ pub enum Column {
     Destination,
     InterchainGasPaymaster,
     Sequence,
+    Environment,
+    Invalidated,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -80,6 +84,8 @@ impl ColumnTrait for Column {
                 ColumnType::Binary(sea_orm::sea_query::BlobSize::Blob(None)).def()
             }
             Self::Sequence => ColumnType::BigInteger.def().null(),
+            Self::Environment => ColumnType::Text.def(),
+            Self::Invalidated => ColumnType::Boolean.def(),
         }
     }
 }
@@ -3,9 +3,11 @@
 pub mod prelude;
 
 pub mod block;
+pub mod chain_registry;
 pub mod cursor;
 pub mod delivered_message;
 pub mod domain;
 pub mod gas_payment;
+pub mod mailbox_config_change;
 pub mod message;
 pub mod transaction;
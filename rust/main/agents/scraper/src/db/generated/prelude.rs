@@ -1,8 +1,8 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.3
 #[allow(unused_imports)]
 pub use super::{
-    block::Entity as Block, cursor::Entity as Cursor,
+    block::Entity as Block, chain_registry::Entity as ChainRegistry, cursor::Entity as Cursor,
     delivered_message::Entity as DeliveredMessage, domain::Entity as Domain,
-    gas_payment::Entity as GasPayment, message::Entity as Message,
-    transaction::Entity as Transaction,
+    gas_payment::Entity as GasPayment, mailbox_config_change::Entity as MailboxConfigChange,
+    message::Entity as Message, transaction::Entity as Transaction,
 };
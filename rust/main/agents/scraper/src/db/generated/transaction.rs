@@ -66,6 +66,7 @@ pub enum Relation {
     Block,
     DeliveredMessage,
     GasPayment,
+    MailboxConfigChange,
     Message,
 }
 
@@ -101,6 +102,9 @@ impl RelationTrait for Relation {
                 .into(),
             Self::DeliveredMessage => Entity::has_many(super::delivered_message::Entity).into(),
             Self::GasPayment => Entity::has_many(super::gas_payment::Entity).into(),
+            Self::MailboxConfigChange => {
+                Entity::has_many(super::mailbox_config_change::Entity).into()
+            }
             Self::Message => Entity::has_many(super::message::Entity).into(),
         }
     }
@@ -124,6 +128,12 @@ impl Related<super::gas_payment::Entity> for Entity {
     }
 }
 
+impl Related<super::mailbox_config_change::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MailboxConfigChange.def()
+    }
+}
+
 impl Related<super::message::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Message.def()
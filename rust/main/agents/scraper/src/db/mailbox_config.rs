@@ -0,0 +1,81 @@
+use eyre::Result;
+use itertools::Itertools;
+use sea_orm::{prelude::*, ActiveValue::*, Insert};
+use tracing::{debug, instrument};
+
+use hyperlane_core::{address_to_bytes, LogMeta, MailboxConfigChange, H256};
+use migration::OnConflict;
+
+use crate::date_time;
+use crate::db::ScraperDb;
+
+use super::generated::mailbox_config_change;
+
+#[derive(Debug)]
+pub struct StorableMailboxConfigChange<'a> {
+    pub change: &'a MailboxConfigChange,
+    pub meta: &'a LogMeta,
+    /// The database id of the transaction the change was made in
+    pub txn_id: i64,
+}
+
+impl ScraperDb {
+    /// Store mailbox config changes. Changes that were already stored are
+    /// skipped.
+    #[instrument(skip_all)]
+    pub async fn store_mailbox_config_changes(
+        &self,
+        domain: u32,
+        mailbox: &H256,
+        changes: &[StorableMailboxConfigChange<'_>],
+    ) -> Result<u64> {
+        let mailbox = address_to_bytes(mailbox);
+        let models = changes
+            .iter()
+            .map(|storable| mailbox_config_change::ActiveModel {
+                id: NotSet,
+                time_created: Set(date_time::now()),
+                domain: Unchanged(domain as i32),
+                mailbox: Unchanged(mailbox.clone()),
+                kind: Set(storable.change.kind().to_owned()),
+                previous_value: Set(storable
+                    .change
+                    .previous_value()
+                    .map(|value| address_to_bytes(&value))),
+                new_value: Set(storable
+                    .change
+                    .new_value()
+                    .map(|value| address_to_bytes(&value))),
+                tx_id: Unchanged(storable.txn_id),
+                log_index: Unchanged(storable.meta.log_index.as_u64() as i64),
+                environment: Set(self.environment.clone()),
+            })
+            .collect_vec();
+
+        debug!(?models, "Writing mailbox config changes to database");
+
+        if models.is_empty() {
+            debug!("Wrote zero new mailbox config changes to database");
+            return Ok(0);
+        }
+
+        let stored = Insert::many(models)
+            .on_conflict(
+                OnConflict::columns([
+                    // don't need domain because TxId includes it
+                    mailbox_config_change::Column::TxId,
+                    mailbox_config_change::Column::LogIndex,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&self.conn)
+            .await?;
+
+        debug!(
+            changes = stored,
+            "Wrote new mailbox config changes to database"
+        );
+        Ok(stored)
+    }
+}
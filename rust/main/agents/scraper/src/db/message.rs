@@ -47,7 +47,8 @@ impl ScraperDb {
                     .eq(address_to_bytes(destination_mailbox)),
             )
             .filter(delivered_message::Column::Sequence.eq(sequence))
-            .one(&self.0)
+            .filter(delivered_message::Column::Environment.eq(self.environment()))
+            .one(&self.conn)
             .await?
         {
             let delivery = H256::from_slice(&delivery.msg_id);
@@ -72,7 +73,8 @@ impl ScraperDb {
                     .eq(address_to_bytes(destination_mailbox)),
             )
             .filter(delivered_message::Column::Sequence.eq(sequence))
-            .one(&self.0)
+            .filter(delivered_message::Column::Environment.eq(self.environment()))
+            .one(&self.conn)
             .await?
         {
             let txn_id = delivery.destination_tx_id;
@@ -88,8 +90,9 @@ impl ScraperDb {
             .column_as(delivered_message::Column::Id.max(), "max_id")
             .filter(delivered_message::Column::Domain.eq(domain))
             .filter(delivered_message::Column::DestinationMailbox.eq(destination_mailbox))
+            .filter(delivered_message::Column::Environment.eq(self.environment()))
             .into_tuple::<Option<i64>>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
 
         Ok(result
@@ -110,7 +113,8 @@ impl ScraperDb {
             .filter(delivered_message::Column::Domain.eq(domain))
             .filter(delivered_message::Column::DestinationMailbox.eq(destination_mailbox))
             .filter(delivered_message::Column::Id.gt(prev_id))
-            .count(&self.0)
+            .filter(delivered_message::Column::Environment.eq(self.environment()))
+            .count(&self.conn)
             .await?)
     }
 
@@ -139,6 +143,8 @@ impl ScraperDb {
                 destination_mailbox: Unchanged(destination_mailbox.clone()),
                 destination_tx_id: Set(delivery.txn_id),
                 sequence: Set(delivery.sequence),
+                environment: Set(self.environment.clone()),
+                invalidated: Set(false),
             })
             .collect_vec();
 
@@ -155,10 +161,11 @@ impl ScraperDb {
                     .update_columns([
                         delivered_message::Column::TimeCreated,
                         delivered_message::Column::DestinationTxId,
+                        delivered_message::Column::Invalidated,
                     ])
                     .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await?;
 
         let new_deliveries_count = self
@@ -188,7 +195,8 @@ impl ScraperDb {
             .filter(message::Column::Origin.eq(origin_domain))
             .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
             .filter(message::Column::Nonce.eq(nonce))
-            .one(&self.0)
+            .filter(message::Column::Environment.eq(self.environment()))
+            .one(&self.conn)
             .await?
         {
             Ok(Some(HyperlaneMessage {
@@ -223,11 +231,12 @@ impl ScraperDb {
             .filter(message::Column::Origin.eq(origin_domain))
             .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
             .filter(message::Column::Nonce.eq(nonce))
+            .filter(message::Column::Environment.eq(self.environment()))
             .select_only()
             .column_as(message::Column::OriginTxId.max(), QueryAs::Nonce)
             .group_by(message::Column::Origin)
             .into_values::<i64, QueryAs>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
         Ok(tx_id)
     }
@@ -238,8 +247,9 @@ impl ScraperDb {
             .column_as(message::Column::Id.max(), "max_id")
             .filter(message::Column::Origin.eq(domain))
             .filter(message::Column::OriginMailbox.eq(origin_mailbox))
+            .filter(message::Column::Environment.eq(self.environment()))
             .into_tuple::<Option<i64>>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
 
         Ok(result
@@ -260,7 +270,8 @@ impl ScraperDb {
             .filter(message::Column::Origin.eq(domain))
             .filter(message::Column::OriginMailbox.eq(origin_mailbox))
             .filter(message::Column::Id.gt(prev_id))
-            .count(&self.0)
+            .filter(message::Column::Environment.eq(self.environment()))
+            .count(&self.conn)
             .await?)
     }
 
@@ -295,6 +306,8 @@ impl ScraperDb {
                 }),
                 origin_mailbox: Unchanged(origin_mailbox.clone()),
                 origin_tx_id: Set(storable.txn_id),
+                environment: Set(self.environment.clone()),
+                invalidated: Set(false),
             })
             .collect_vec();
 
@@ -319,10 +332,11 @@ impl ScraperDb {
                     message::Column::Recipient,
                     message::Column::MsgBody,
                     message::Column::OriginTxId,
+                    message::Column::Invalidated,
                 ])
                 .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await?;
 
         let new_dispatch_count = self
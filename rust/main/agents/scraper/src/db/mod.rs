@@ -1,6 +1,8 @@
 pub use block::*;
 pub use block_cursor::BlockCursor;
+pub use chain_registry::*;
 use eyre::Result;
+pub use mailbox_config::*;
 pub use message::*;
 pub use payment::*;
 use sea_orm::{Database, DatabaseConnection, DbConn};
@@ -13,29 +15,62 @@ mod generated;
 // These modules implement additional functionality for the ScraperDb
 mod block;
 mod block_cursor;
+mod chain_registry;
+mod mailbox_config;
 mod message;
 mod payment;
+mod reorg;
+mod retention;
 mod txn;
 
+/// Environment of the chains a scraper doesn't configure an environment for,
+/// and of the data scraped before environments were introduced
+pub const DEFAULT_ENVIRONMENT: &str = "default";
+
 /// Database interface to the message explorer database for the scraper. This is
 /// focused on writing data to the database.
+///
+/// The events it writes and reads are those of a single environment, e.g.
+/// `mainnet` or `testnet`, so one database can serve several explorers.
 #[derive(Debug)]
-pub struct ScraperDb(DbConn);
+pub struct ScraperDb {
+    conn: DbConn,
+    environment: String,
+}
 
 impl ScraperDb {
     #[instrument]
     pub async fn connect(url: &str) -> Result<Self> {
         let db = Database::connect(url).await?;
-        Ok(Self(db))
+        Ok(Self {
+            conn: db,
+            environment: DEFAULT_ENVIRONMENT.to_owned(),
+        })
     }
 
     #[cfg(test)]
     pub fn with_connection(db: DbConn) -> Self {
-        Self(db)
+        Self {
+            conn: db,
+            environment: DEFAULT_ENVIRONMENT.to_owned(),
+        }
+    }
+
+    /// The same database, scoped to the events of `environment`
+    pub fn with_environment(&self, environment: &str) -> Self {
+        Self {
+            conn: self.clone_connection(),
+            environment: environment.to_owned(),
+        }
+    }
+
+    /// The environment the events written and read are tagged with
+    pub fn environment(&self) -> &str {
+        &self.environment
     }
 
     pub fn clone_connection(&self) -> DbConn {
-        match &self.0 {
+        match &self.conn {
             DatabaseConnection::SqlxPostgresPoolConnection(conn) => {
                 DatabaseConnection::SqlxPostgresPoolConnection(conn.clone())
             }
@@ -52,7 +87,6 @@ impl ScraperDb {
 /// So we have to implement our own clone instead of #[derive(Clone)]
 impl Clone for ScraperDb {
     fn clone(&self) -> Self {
-        let conn = self.clone_connection();
-        Self(conn)
+        self.with_environment(&self.environment)
     }
 }
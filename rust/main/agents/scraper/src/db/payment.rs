@@ -37,7 +37,8 @@ impl ScraperDb {
                     .eq(address_to_bytes(interchain_gas_paymaster)),
             )
             .filter(gas_payment::Column::Sequence.eq(sequence))
-            .one(&self.0)
+            .filter(gas_payment::Column::Environment.eq(self.environment()))
+            .one(&self.conn)
             .await?
         {
             let payment = InterchainGasPayment {
@@ -67,7 +68,8 @@ impl ScraperDb {
                     .eq(address_to_bytes(interchain_gas_paymaster)),
             )
             .filter(gas_payment::Column::Sequence.eq(sequence))
-            .one(&self.0)
+            .filter(gas_payment::Column::Environment.eq(self.environment()))
+            .one(&self.conn)
             .await?
         {
             let txn_id = payment.tx_id;
@@ -103,6 +105,8 @@ impl ScraperDb {
                 destination: Set(storable.payment.destination as i32),
                 interchain_gas_paymaster: Set(interchain_gas_paymaster.clone()),
                 sequence: Set(storable.sequence),
+                environment: Set(self.environment.clone()),
+                invalidated: Set(false),
             })
             .collect_vec();
 
@@ -129,10 +133,11 @@ impl ScraperDb {
                     gas_payment::Column::Destination,
                     gas_payment::Column::InterchainGasPaymaster,
                     gas_payment::Column::Sequence,
+                    gas_payment::Column::Invalidated,
                 ])
                 .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await?;
 
         let new_payments_count = self
@@ -151,8 +156,9 @@ impl ScraperDb {
             .select_only()
             .column_as(gas_payment::Column::Id.max(), "max_id")
             .filter(gas_payment::Column::Domain.eq(domain))
+            .filter(gas_payment::Column::Environment.eq(self.environment()))
             .into_tuple::<Option<i64>>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
 
         Ok(result
@@ -167,7 +173,8 @@ impl ScraperDb {
         Ok(gas_payment::Entity::find()
             .filter(gas_payment::Column::Domain.eq(domain))
             .filter(gas_payment::Column::Id.gt(prev_id))
-            .count(&self.0)
+            .filter(gas_payment::Column::Environment.eq(self.environment()))
+            .count(&self.conn)
             .await?)
     }
 }
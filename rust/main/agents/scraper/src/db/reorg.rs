@@ -0,0 +1,54 @@
+use eyre::Result;
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, Query},
+};
+use tracing::{debug, instrument};
+
+use crate::db::ScraperDb;
+
+use super::generated::{delivered_message, gas_payment, message, transaction};
+
+impl ScraperDb {
+    /// Marks the events of the environment scraped from the transactions of
+    /// the block as invalid, because the block was reorged out of the chain.
+    /// Events scraped again from the canonical chain are valid again.
+    ///
+    /// Returns the number of invalidated events.
+    #[instrument(skip(self), fields(environment = self.environment()))]
+    pub async fn invalidate_events_in_block(&self, block_id: i64) -> Result<u64> {
+        let txns_in_block = Query::select()
+            .column(transaction::Column::Id)
+            .from(transaction::Entity)
+            .and_where(transaction::Column::BlockId.eq(block_id))
+            .to_owned();
+
+        let messages = message::Entity::update_many()
+            .col_expr(message::Column::Invalidated, Expr::value(true))
+            .filter(message::Column::Environment.eq(self.environment()))
+            .filter(message::Column::OriginTxId.in_subquery(txns_in_block.clone()))
+            .exec(&self.conn)
+            .await?
+            .rows_affected;
+        let deliveries = delivered_message::Entity::update_many()
+            .col_expr(delivered_message::Column::Invalidated, Expr::value(true))
+            .filter(delivered_message::Column::Environment.eq(self.environment()))
+            .filter(delivered_message::Column::DestinationTxId.in_subquery(txns_in_block.clone()))
+            .exec(&self.conn)
+            .await?
+            .rows_affected;
+        let payments = gas_payment::Entity::update_many()
+            .col_expr(gas_payment::Column::Invalidated, Expr::value(true))
+            .filter(gas_payment::Column::Environment.eq(self.environment()))
+            .filter(gas_payment::Column::TxId.in_subquery(txns_in_block))
+            .exec(&self.conn)
+            .await?
+            .rows_affected;
+
+        debug!(
+            messages,
+            deliveries, payments, "Invalidated events of reorged block"
+        );
+        Ok(messages + deliveries + payments)
+    }
+}
@@ -0,0 +1,48 @@
+use eyre::Result;
+use sea_orm::prelude::*;
+use tracing::{debug, instrument};
+
+use crate::db::ScraperDb;
+
+use super::generated::{delivered_message, gas_payment, mailbox_config_change, message};
+
+impl ScraperDb {
+    /// Delete the events of the environment that were last scraped before
+    /// `cutoff`. Blocks and transactions are shared by all environments, so
+    /// they are kept.
+    ///
+    /// Returns the number of deleted events.
+    #[instrument(skip(self), fields(environment = self.environment()))]
+    pub async fn delete_events_scraped_before(&self, cutoff: TimeDateTime) -> Result<u64> {
+        let messages = message::Entity::delete_many()
+            .filter(message::Column::Environment.eq(self.environment()))
+            .filter(message::Column::TimeCreated.lt(cutoff))
+            .exec(&self.conn)
+            .await?
+            .rows_affected;
+        let deliveries = delivered_message::Entity::delete_many()
+            .filter(delivered_message::Column::Environment.eq(self.environment()))
+            .filter(delivered_message::Column::TimeCreated.lt(cutoff))
+            .exec(&self.conn)
+            .await?
+            .rows_affected;
+        let payments = gas_payment::Entity::delete_many()
+            .filter(gas_payment::Column::Environment.eq(self.environment()))
+            .filter(gas_payment::Column::TimeCreated.lt(cutoff))
+            .exec(&self.conn)
+            .await?
+            .rows_affected;
+        let config_changes = mailbox_config_change::Entity::delete_many()
+            .filter(mailbox_config_change::Column::Environment.eq(self.environment()))
+            .filter(mailbox_config_change::Column::TimeCreated.lt(cutoff))
+            .exec(&self.conn)
+            .await?
+            .rows_affected;
+
+        debug!(
+            messages,
+            deliveries, payments, config_changes, "Deleted expired events from database"
+        );
+        Ok(messages + deliveries + payments + config_changes)
+    }
+}
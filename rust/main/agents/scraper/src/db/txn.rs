@@ -3,8 +3,10 @@ use std::collections::HashMap;
 use derive_more::Deref;
 use eyre::{eyre, Context, Result};
 use sea_orm::{
-    prelude::*, sea_query::OnConflict, ActiveValue::*, DeriveColumn, EnumIter, Insert, NotSet,
-    QuerySelect,
+    prelude::*,
+    sea_query::{Expr, OnConflict},
+    ActiveValue::*,
+    DeriveColumn, EnumIter, Insert, NotSet, QuerySelect,
 };
 use tracing::{debug, instrument, trace};
 
@@ -32,7 +34,7 @@ impl ScraperDb {
             .select_only()
             .column_as(transaction::Column::BlockId, QueryAs::BlockId)
             .into_values::<i64, QueryAs>()
-            .one(&self.0)
+            .one(&self.conn)
             .await?;
         Ok(block_id)
     }
@@ -56,7 +58,7 @@ impl ScraperDb {
             .column_as(transaction::Column::Id, QueryAs::Id)
             .column_as(transaction::Column::Hash, QueryAs::Hash)
             .into_values::<(i64, Vec<u8>), QueryAs>()
-            .all(&self.0)
+            .all(&self.conn)
             .await
             .context("When querying transactions")?
             .into_iter()
@@ -67,6 +69,50 @@ impl ScraperDb {
         Ok(txns)
     }
 
+    /// Lookup transactions and find their ids and the ids of the blocks they
+    /// were scraped from. Any transactions which are not found be excluded
+    /// from the hashmap.
+    pub async fn get_txn_ids_and_block_ids(
+        &self,
+        hashes: impl Iterator<Item = &H512>,
+    ) -> Result<HashMap<H512, (i64, i64)>> {
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+        enum QueryAs {
+            Id,
+            BlockId,
+            Hash,
+        }
+
+        let txns = transaction::Entity::find()
+            .filter(transaction::Column::Hash.is_in(hashes.map(h512_to_bytes)))
+            .select_only()
+            .column_as(transaction::Column::Id, QueryAs::Id)
+            .column_as(transaction::Column::BlockId, QueryAs::BlockId)
+            .column_as(transaction::Column::Hash, QueryAs::Hash)
+            .into_values::<(i64, i64, Vec<u8>), QueryAs>()
+            .all(&self.conn)
+            .await
+            .context("When querying transactions")?
+            .into_iter()
+            .map(|(id, block_id, hash)| Ok((bytes_to_h512(&hash), (id, block_id))))
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        trace!(?txns, "Queried transaction and block ids for hashes");
+        Ok(txns)
+    }
+
+    /// Moves a transaction to the block it's included in, e.g. after the
+    /// block it was scraped from was reorged out of the chain
+    pub async fn update_txn_block_id(&self, txn_id: i64, block_id: i64) -> Result<()> {
+        transaction::Entity::update_many()
+            .col_expr(transaction::Column::BlockId, Expr::value(block_id))
+            .filter(transaction::Column::Id.eq(txn_id))
+            .exec(&self.conn)
+            .await
+            .context("When updating transaction block")?;
+        Ok(())
+    }
+
     /// Store a new transaction into the database (or update an existing one).
     #[instrument(skip_all)]
     pub async fn store_txns(&self, txns: impl Iterator<Item = StorableTxn>) -> Result<()> {
@@ -109,7 +155,7 @@ impl ScraperDb {
                     .do_nothing()
                     .to_owned(),
             )
-            .exec(&self.0)
+            .exec(&self.conn)
             .await
         {
             Ok(_) => Ok(()),
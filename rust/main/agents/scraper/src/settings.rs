@@ -4,7 +4,11 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, default::Default};
+use std::{
+    collections::{HashMap, HashSet},
+    default::Default,
+    time::Duration,
+};
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
 use eyre::Context;
@@ -19,6 +23,11 @@ use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain};
 use serde::Deserialize;
 use serde_json::Value;
 
+use crate::db::DEFAULT_ENVIRONMENT;
+
+/// Seconds in a day, the unit of retention periods
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
 /// Settings for `Scraper`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
 pub struct ScraperSettings {
@@ -30,6 +39,42 @@ pub struct ScraperSettings {
 
     pub db: String,
     pub chains_to_scrape: Vec<HyperlaneDomain>,
+    /// How often to check the chain registry table for chains to start
+    /// scraping. The registry is not used if unset.
+    pub chain_registry_polling_interval: Option<Duration>,
+    /// Environment the events of the scraped chains are tagged with, e.g.
+    /// `mainnet`, unless `chain_environments` overrides it
+    pub environment: String,
+    /// Environment of the chains scraped for another environment than
+    /// `environment`, by chain name. A chain is only ever scraped for one
+    /// environment, so its environment can't change once it was scraped.
+    pub chain_environments: HashMap<String, String>,
+    /// How long the events of an environment are kept after they were
+    /// scraped, by environment. Events of other environments are kept
+    /// forever.
+    pub retention_periods: HashMap<String, Duration>,
+    /// How often the recently scraped blocks are checked for reorgs deeper
+    /// than the reorg period of their chain. Blocks aren't checked if unset.
+    pub reorg_check_interval: Option<Duration>,
+}
+
+impl ScraperSettings {
+    /// The environment the events of the chain are tagged with
+    pub fn chain_environment(&self, domain: &HyperlaneDomain) -> &str {
+        self.chain_environments
+            .get(domain.name())
+            .unwrap_or(&self.environment)
+    }
+
+    /// Whether any chain is scraped for the environment, i.e. whether chains
+    /// registered for it are scraped by this scraper
+    pub fn scrapes_environment(&self, environment: &str) -> bool {
+        self.environment == environment
+            || self
+                .chain_environments
+                .values()
+                .any(|chain_environment| chain_environment == environment)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,9 +100,23 @@ impl FromRawConf<RawScraperSettings> for ScraperSettings {
             .end()
             .map(|s| s.split(',').collect());
 
+        let chain_registry_polling_interval = p
+            .chain(&mut err)
+            .get_opt_key("chainRegistryPollingInterval")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs);
+
+        // Chains in the chain registry can be any configured chain, so all of
+        // them are parsed if the registry is used
+        let chain_filter = if chain_registry_polling_interval.is_some() {
+            None
+        } else {
+            chains_names_to_scrape.as_ref()
+        };
         let base = p
             .parse_from_raw_config::<Settings, RawAgentConf, Option<&HashSet<&str>>>(
-                chains_names_to_scrape.as_ref(),
+                chain_filter,
                 "Parsing base config",
             )
             .take_config_err(&mut err);
@@ -83,12 +142,62 @@ impl FromRawConf<RawScraperSettings> for ScraperSettings {
             Default::default()
         };
 
+        let environment = p
+            .chain(&mut err)
+            .get_opt_key("environment")
+            .parse_string()
+            .unwrap_or(DEFAULT_ENVIRONMENT)
+            .to_owned();
+
+        let chain_environments: HashMap<String, String> = p
+            .get_opt_key("chainEnvironments")
+            .take_config_err_flat(&mut err)
+            .and_then(|environments| environments.into_obj_iter().take_config_err(&mut err))
+            .map(|itr| {
+                itr.filter_map(|(chain, environment)| {
+                    environment
+                        .chain(&mut err)
+                        .parse_string()
+                        .end()
+                        .map(|environment| (chain, environment.to_owned()))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let retention_periods: HashMap<String, Duration> = p
+            .get_opt_key("retentionDays")
+            .take_config_err_flat(&mut err)
+            .and_then(|periods| periods.into_obj_iter().take_config_err(&mut err))
+            .map(|itr| {
+                itr.filter_map(|(environment, days)| {
+                    days.chain(&mut err)
+                        .parse_u64()
+                        .end()
+                        .map(|days| (environment, Duration::from_secs(days * SECONDS_PER_DAY)))
+                })
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let reorg_check_interval = p
+            .chain(&mut err)
+            .get_opt_key("reorgCheckInterval")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs);
+
         cfg_unwrap_all!(&p.cwp, err: [base, db]);
 
         err.into_result(Self {
             base,
             db,
             chains_to_scrape,
+            chain_registry_polling_interval,
+            environment,
+            chain_environments,
+            retention_periods,
+            reorg_check_interval,
         })
     }
 }
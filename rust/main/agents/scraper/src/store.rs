@@ -2,5 +2,7 @@ pub use storage::HyperlaneDbStore;
 
 mod deliveries;
 mod dispatches;
+mod mailbox_config;
 mod payments;
+mod reorg;
 mod storage;
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use eyre::Result;
+use itertools::Itertools;
+use tracing::debug;
+
+use hyperlane_core::{HyperlaneLogStore, Indexed, LogMeta, MailboxConfigChange, H512};
+
+use crate::db::StorableMailboxConfigChange;
+use crate::store::storage::{HyperlaneDbStore, TxnWithId};
+
+#[async_trait]
+impl HyperlaneLogStore<MailboxConfigChange> for HyperlaneDbStore {
+    /// Store mailbox config changes into the database.
+    /// We store only changes from blocks and transactions which we could
+    /// successfully insert into database.
+    async fn store_logs(&self, changes: &[(Indexed<MailboxConfigChange>, LogMeta)]) -> Result<u32> {
+        if changes.is_empty() {
+            return Ok(0);
+        }
+        let txns: HashMap<H512, TxnWithId> = self
+            .ensure_blocks_and_txns(changes.iter().map(|r| &r.1))
+            .await?
+            .map(|t| (t.hash, t))
+            .collect();
+        let storable = changes
+            .iter()
+            .filter_map(|(change, meta)| {
+                txns.get(&meta.transaction_id)
+                    .map(|txn| StorableMailboxConfigChange {
+                        change: change.inner(),
+                        meta,
+                        txn_id: txn.id,
+                    })
+            })
+            .collect_vec();
+
+        debug!(
+            domain = self.domain.id(),
+            mailbox_address = ?self.mailbox_address,
+            ?storable,
+            "storable mailbox config changes",
+        );
+
+        let stored = self
+            .db
+            .store_mailbox_config_changes(self.domain.id(), &self.mailbox_address, &storable)
+            .await?;
+        Ok(stored as u32)
+    }
+}
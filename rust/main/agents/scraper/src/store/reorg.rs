@@ -0,0 +1,76 @@
+use eyre::Result;
+use tracing::{debug, warn};
+
+use hyperlane_core::H256;
+
+use crate::store::storage::HyperlaneDbStore;
+
+/// How many of the highest scraped blocks at or below the finalized block are
+/// checked against the chain. Blocks are only scraped if they include events,
+/// so this bounds the number of blocks fetched per check rather than the
+/// height range checked.
+const REORG_CHECK_BLOCKS: u64 = 64;
+
+/// A scraped block that was reorged out of the chain
+#[derive(Debug, Clone)]
+pub(crate) struct ReorgedBlock {
+    pub height: u64,
+    pub scraped_hash: H256,
+    pub canonical_hash: H256,
+    /// The number of events scraped from the block which were invalidated
+    pub invalidated_events: u64,
+}
+
+impl HyperlaneDbStore {
+    /// Compares the hashes of the most recently scraped blocks at or below
+    /// `finalized_height` with the chain's. A block whose hash changed was
+    /// reorged out even though it was past the chain's reorg period. The
+    /// events scraped from it are invalidated and it's replaced by the
+    /// canonical block at its height, so the events can be scraped again.
+    pub(crate) async fn invalidate_reorged_blocks(
+        &self,
+        finalized_height: u64,
+    ) -> Result<Vec<ReorgedBlock>> {
+        let blocks = self
+            .db
+            .retrieve_recent_blocks(self.domain.id(), finalized_height, REORG_CHECK_BLOCKS)
+            .await?;
+
+        let mut reorged_blocks = vec![];
+        for block in blocks {
+            let canonical = match self.provider.get_block_by_height(block.height).await {
+                Ok(canonical) => canonical,
+                Err(err) => {
+                    warn!(?block, ?err, "Failed to fetch block to check for reorgs");
+                    continue;
+                }
+            };
+            if canonical.hash == block.hash {
+                continue;
+            }
+
+            let invalidated_events = self.db.invalidate_events_in_block(block.id).await?;
+            self.db.replace_reorged_block(block.id, &canonical).await?;
+            let reorged_block = ReorgedBlock {
+                height: block.height,
+                scraped_hash: block.hash,
+                canonical_hash: canonical.hash,
+                invalidated_events,
+            };
+            warn!(
+                domain = self.domain.name(),
+                ?reorged_block,
+                "Scraped block was reorged out past the reorg period"
+            );
+            reorged_blocks.push(reorged_block);
+        }
+
+        if reorged_blocks.is_empty() {
+            debug!(
+                domain = self.domain.name(),
+                finalized_height, "No reorged blocks found"
+            );
+        }
+        Ok(reorged_blocks)
+    }
+}
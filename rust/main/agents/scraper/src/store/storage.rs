@@ -114,17 +114,21 @@ impl HyperlaneDbStore {
             .collect();
 
         let db_txns = if !txns.is_empty() {
-            self.db.get_txn_ids(txns.keys()).await?
+            self.db.get_txn_ids_and_block_ids(txns.keys()).await?
         } else {
             HashMap::new()
         };
-        for (hash, id) in db_txns {
-            // insert the txn id now that we have it to the Option value in txns
-            let _ = txns
+        for (hash, (id, stored_block_id)) in db_txns {
+            let (txn_id, block_id) = txns
                 .get_mut(&hash)
-                .expect("We found a txn that we did not request")
-                .0
-                .insert(id);
+                .expect("We found a txn that we did not request");
+            // A transaction scraped again after a reorg can be included in
+            // another block than the one it was first scraped from
+            if stored_block_id != *block_id {
+                self.db.update_txn_block_id(id, *block_id).await?;
+            }
+            // insert the txn id now that we have it to the Option value in txns
+            let _ = txn_id.insert(id);
         }
 
         // insert any txns that were not known and get their IDs
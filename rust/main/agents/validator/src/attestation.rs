@@ -0,0 +1,223 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::{eyre, Result};
+use prometheus::{Gauge, IntCounterVec, IntGauge};
+use tokio::time::sleep;
+use tracing::{debug, error, info, warn};
+
+use hyperlane_base::{
+    db::HyperlaneDb, today, u256_as_f64, CheckpointSyncer, CoreMetrics, PeriodicSpend,
+};
+use hyperlane_core::{
+    CheckpointAttestation, FixedPointNumber, HyperlaneDomain, SignedCheckpointWithMessageId,
+    TxOutcome, U256,
+};
+
+/// What attestations spend is recorded as in the daily spend of the origin
+const SPENDER: &str = "checkpoint_attestation";
+
+const OUTCOME_SUCCESS: &str = "success";
+const OUTCOME_FAILED: &str = "failed";
+const OUTCOME_OVER_BUDGET: &str = "over_budget";
+
+/// Submits every `interval`-th checkpoint written by the validator to an
+/// attestation contract on the origin chain, so that its liveness can be
+/// verified on chain in addition to its checkpoint syncer.
+///
+/// Only the latest checkpoint due for attestation is submitted, older ones
+/// that were missed, e.g. while the validator was down, aren't backfilled.
+pub(crate) struct CheckpointAttester {
+    /// How often to check for new checkpoints
+    poll_interval: Duration,
+    /// Every checkpoint whose index is a multiple of this is submitted
+    interval: u32,
+    contract: Arc<dyn CheckpointAttestation>,
+    checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    /// The most native tokens attestations may spend per UTC day, in the
+    /// smallest unit
+    max_daily_spend: Option<U256>,
+    /// Persisted, so that a restart doesn't reset the spend of the day
+    spend: PeriodicSpend,
+    metrics: CheckpointAttestationMetrics,
+}
+
+impl CheckpointAttester {
+    pub(crate) fn new(
+        poll_interval: Duration,
+        interval: u32,
+        max_daily_spend: Option<U256>,
+        contract: Arc<dyn CheckpointAttestation>,
+        checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        db: Arc<dyn HyperlaneDb>,
+        metrics: CheckpointAttestationMetrics,
+    ) -> Self {
+        Self {
+            poll_interval,
+            interval,
+            contract,
+            checkpoint_syncer,
+            max_daily_spend,
+            spend: PeriodicSpend::new(db),
+            metrics,
+        }
+    }
+
+    pub(crate) async fn run(self) {
+        let mut last_attested: Option<u32> = None;
+        loop {
+            match self.attest_latest_checkpoint(last_attested).await {
+                Ok(Some(index)) => last_attested = Some(index),
+                Ok(None) => {}
+                Err(err) => warn!(?err, "Failed to attest checkpoint"),
+            }
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Submits the latest checkpoint due for attestation if it's newer than
+    /// `last_attested`. Returns the index of the checkpoint if it no longer
+    /// needs to be submitted, either because it was or because it was skipped.
+    async fn attest_latest_checkpoint(&self, last_attested: Option<u32>) -> Result<Option<u32>> {
+        let Some(latest_index) = self.checkpoint_syncer.latest_index().await? else {
+            return Ok(None);
+        };
+        let index = latest_index - latest_index % self.interval;
+        if last_attested.is_some_and(|last_attested| last_attested >= index) {
+            return Ok(None);
+        }
+        let checkpoint = self
+            .checkpoint_syncer
+            .fetch_checkpoint(index)
+            .await?
+            .ok_or_else(|| eyre!("Checkpoint {index} is missing from the checkpoint syncer"))?;
+
+        let max_cost = self
+            .contract
+            .attest_checkpoint_max_cost(&checkpoint)
+            .await?;
+        let day = today();
+        let spent_today = self.spend.spent_in_period(SPENDER, day)?;
+        if !is_within_budget(spent_today, max_cost, self.max_daily_spend) {
+            warn!(
+                index,
+                ?max_cost,
+                ?spent_today,
+                "Skipping checkpoint attestation, which would exceed the daily spend limit"
+            );
+            self.record_outcome(OUTCOME_OVER_BUDGET);
+            return Ok(Some(index));
+        }
+
+        match self.submit(&checkpoint, day).await {
+            Ok(()) => Ok(Some(index)),
+            Err(err) => {
+                self.record_outcome(OUTCOME_FAILED);
+                Err(err)
+            }
+        }
+    }
+
+    async fn submit(&self, checkpoint: &SignedCheckpointWithMessageId, day: u64) -> Result<()> {
+        let index = checkpoint.value.index;
+        debug!(index, "Submitting checkpoint attestation");
+        let outcome = self.contract.attest_checkpoint(checkpoint).await?;
+        self.record_spend(&outcome, day);
+        if !outcome.executed {
+            error!(
+                index,
+                txid = ?outcome.transaction_id,
+                "Checkpoint attestation transaction reverted"
+            );
+            self.record_outcome(OUTCOME_FAILED);
+            return Ok(());
+        }
+        info!(index, txid = ?outcome.transaction_id, "Attested checkpoint");
+        self.record_outcome(OUTCOME_SUCCESS);
+        self.metrics.latest_attested_checkpoint.set(index as i64);
+        Ok(())
+    }
+
+    fn record_spend(&self, outcome: &TxOutcome, day: u64) {
+        let cost = FixedPointNumber::try_from(outcome.gas_used)
+            .map(|gas_used| gas_used * outcome.gas_price.clone())
+            .and_then(TryInto::<U256>::try_into);
+        match cost {
+            Ok(cost) => match self.spend.record_in_period(SPENDER, cost, day) {
+                Ok(spent) => self.metrics.daily_spend.set(u256_as_f64(spent)),
+                Err(err) => {
+                    warn!(?err, %cost, "Failed to record the cost of the checkpoint attestation")
+                }
+            },
+            Err(err) => warn!(
+                ?err,
+                "Failed to compute the cost of the checkpoint attestation"
+            ),
+        }
+    }
+
+    fn record_outcome(&self, outcome: &str) {
+        self.metrics
+            .attestations
+            .with_label_values(&[&self.metrics.chain, outcome])
+            .inc();
+    }
+}
+
+/// Whether spending `tokens` on top of `spent` stays within `max`
+fn is_within_budget(spent: U256, tokens: U256, max: Option<U256>) -> bool {
+    max.map_or(true, |max| spent.saturating_add(tokens) <= max)
+}
+
+/// Metrics of the checkpoint attestations of a validator
+pub(crate) struct CheckpointAttestationMetrics {
+    chain: String,
+    /// Index of the latest checkpoint submitted to the attestation contract
+    latest_attested_checkpoint: IntGauge,
+    /// Attestations by outcome
+    attestations: IntCounterVec,
+    /// Native tokens spent on attestations today
+    daily_spend: Gauge,
+}
+
+impl CheckpointAttestationMetrics {
+    pub(crate) fn new(metrics: &CoreMetrics, origin: &HyperlaneDomain) -> Result<Self> {
+        let chain = origin.name();
+        let latest_attested_checkpoint = metrics.new_int_gauge(
+            "latest_attested_checkpoint",
+            "Index of the latest checkpoint submitted to the attestation contract",
+            &["chain"],
+        )?;
+        let daily_spend = metrics.new_gauge(
+            "checkpoint_attestation_daily_spend",
+            "Native tokens spent on checkpoint attestations during the current UTC day, in the \
+             smallest unit",
+            &["chain"],
+        )?;
+        Ok(Self {
+            chain: chain.to_owned(),
+            latest_attested_checkpoint: latest_attested_checkpoint.with_label_values(&[chain]),
+            attestations: metrics.new_int_counter(
+                "checkpoint_attestations",
+                "Checkpoints submitted to the attestation contract, by outcome: `success`, \
+                 `failed` or `over_budget` if skipped because of the daily spend limit",
+                &["chain", "outcome"],
+            )?,
+            daily_spend: daily_spend.with_label_values(&[chain]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_daily_budget() {
+        let max = Some(U256::from(100));
+        assert!(is_within_budget(U256::from(60), U256::from(40), max));
+        assert!(!is_within_budget(U256::from(60), U256::from(41), max));
+
+        // Without a limit, anything can be spent
+        assert!(is_within_budget(U256::MAX, U256::MAX, None));
+    }
+}
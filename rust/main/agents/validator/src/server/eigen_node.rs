@@ -148,14 +148,13 @@ mod tests {
 
     use super::*;
     use axum::http::StatusCode;
-    use prometheus::Registry;
+    use hyperlane_base::test_utils::dummy_core_metrics;
 
     const PARTIALLY_HEALTHY_OBSERVED_CHECKPOINT: i64 = 34;
     const HEALTHY_OBSERVED_CHECKPOINT: i64 = 42;
 
     async fn setup_test_server() -> (reqwest::Client, SocketAddr, Arc<CoreMetrics>) {
-        let core_metrics =
-            Arc::new(CoreMetrics::new("dummy_validator", 37582, Registry::new()).unwrap());
+        let core_metrics = Arc::new(dummy_core_metrics("dummy_validator"));
         // Initialize the Prometheus registry
         core_metrics
             .latest_checkpoint()
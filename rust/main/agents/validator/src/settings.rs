@@ -16,10 +16,15 @@ use hyperlane_base::{
     },
 };
 use hyperlane_core::{
-    cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol, ReorgPeriod,
+    cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol, ReorgPeriod, H256, U256,
 };
 use serde::Deserialize;
 use serde_json::Value;
+use url::Url;
+
+/// By default, every this many checkpoints is submitted to the attestation
+/// contract
+const DEFAULT_CHECKPOINT_ATTESTATION_INTERVAL: u32 = 100;
 
 /// Settings for `Validator`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
@@ -42,6 +47,36 @@ pub struct ValidatorSettings {
     pub reorg_period: ReorgPeriod,
     /// How frequently to check for new checkpoints
     pub interval: Duration,
+    /// Whether to submit the validator announcement with the origin chain
+    /// signer if the storage location isn't announced yet
+    pub auto_announce: bool,
+    /// Whether to also write each completed batch of checkpoints as a single
+    /// gzipped file, which relayers fetch instead of individual checkpoints
+    pub checkpoint_batches: bool,
+    /// If set, only this many of the latest checkpoints signed at once are
+    /// written individually. The others are held until their batch is
+    /// complete and only written in it, which saves most writes on origins
+    /// with many messages, or written individually after ten minutes. Only
+    /// used with `checkpoint_batches`.
+    pub latest_individual_checkpoints: Option<u32>,
+    /// If set, checkpoints are also submitted to an attestation contract on
+    /// the origin chain
+    pub checkpoint_attestation: Option<CheckpointAttestationConf>,
+    /// If set, checkpoints are only signed once the merkle tree hook read
+    /// through this RPC of the origin chain agrees with them
+    pub quorum_rpc_url: Option<Url>,
+}
+
+/// Configuration of the submission of checkpoints to an attestation contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointAttestationConf {
+    /// Address of the attestation contract on the origin chain
+    pub address: H256,
+    /// Every checkpoint whose index is a multiple of this is submitted
+    pub interval: u32,
+    /// Maximum amount of native tokens spent on submissions per UTC day, or
+    /// no limit if None
+    pub max_daily_spend: Option<U256>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -116,6 +151,36 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .map(Duration::from_secs)
             .unwrap_or(Duration::from_secs(5));
 
+        let auto_announce = p
+            .chain(&mut err)
+            .get_opt_key("autoAnnounce")
+            .parse_bool()
+            .unwrap_or(true);
+
+        let checkpoint_batches = p
+            .chain(&mut err)
+            .get_opt_key("checkpointBatches")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let latest_individual_checkpoints = p
+            .chain(&mut err)
+            .get_opt_key("latestIndividualCheckpoints")
+            .parse_u32()
+            .end();
+
+        let checkpoint_attestation = p
+            .chain(&mut err)
+            .get_opt_key("checkpointAttestation")
+            .and_then(parse_checkpoint_attestation)
+            .end();
+
+        let quorum_rpc_url = p
+            .chain(&mut err)
+            .get_opt_key("quorumRpcUrl")
+            .parse_from_str("Invalid quorum RPC url")
+            .end();
+
         cfg_unwrap_all!(cwp, err: [origin_chain_name]);
 
         let reorg_period = p
@@ -145,10 +210,48 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             checkpoint_syncer,
             reorg_period,
             interval,
+            auto_announce,
+            checkpoint_batches,
+            latest_individual_checkpoints,
+            checkpoint_attestation,
+            quorum_rpc_url,
         })
     }
 }
 
+/// Expects ValidatorAgentConfig.checkpointAttestation
+fn parse_checkpoint_attestation(
+    attestation: ValueParser,
+) -> ConfigResult<CheckpointAttestationConf> {
+    let mut err = ConfigParsingError::default();
+    let address = attestation
+        .chain(&mut err)
+        .get_key("address")
+        .parse_address_hash()
+        .end();
+    let interval = attestation
+        .chain(&mut err)
+        .get_opt_key("interval")
+        .parse_u32()
+        .unwrap_or(DEFAULT_CHECKPOINT_ATTESTATION_INTERVAL);
+    if interval == 0 {
+        Err::<(), _>(eyre!("Checkpoint attestation interval must be positive"))
+            .take_err(&mut err, || &attestation.cwp + "interval");
+    }
+    let max_daily_spend = attestation
+        .chain(&mut err)
+        .get_opt_key("maxDailySpend")
+        .parse_u256()
+        .end();
+
+    cfg_unwrap_all!(&attestation.cwp, err: [address]);
+    err.into_result(CheckpointAttestationConf {
+        address,
+        interval,
+        max_daily_spend,
+    })
+}
+
 /// Expects ValidatorAgentConfig.checkpointSyncer
 fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncerConf> {
     let mut err = ConfigParsingError::default();
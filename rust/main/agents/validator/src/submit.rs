@@ -1,39 +1,79 @@
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::vec;
 
-use prometheus::IntGauge;
+use eyre::{eyre, Result};
+use prometheus::{IntCounter, IntGauge};
 use tokio::time::sleep;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use hyperlane_base::db::HyperlaneDb;
-use hyperlane_base::{CheckpointSyncer, CoreMetrics};
-use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
+use hyperlane_base::{
+    checkpoint_batch_start, CheckpointSyncer, CoreMetrics, Retrier, RetryMetrics,
+    CHECKPOINT_BATCH_SIZE,
+};
+use hyperlane_core::rpc_clients::{RetryPolicy, RPC_RETRY_SLEEP_DURATION};
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
-    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSignerExt,
+    HashAlgorithm, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSignerExt,
 };
 use hyperlane_core::{ChainResult, MerkleTreeHook, ReorgEvent, ReorgPeriod};
 use hyperlane_ethereum::SingletonSignerHandle;
 
+/// Retries of fetching the latest checkpoint from the merkle tree hook
+const LATEST_CHECKPOINT_RETRY_POLICY: RetryPolicy =
+    RetryPolicy::unbounded_with_cap(RPC_RETRY_SLEEP_DURATION, Duration::from_secs(30));
+
+/// Retries of writing to the checkpoint store. Certain checkpoint stores rate
+/// limit very aggressively, so writes are retried indefinitely and back off
+/// further than RPC calls.
+const CHECKPOINT_STORE_RETRY_POLICY: RetryPolicy =
+    RetryPolicy::unbounded_with_cap(RPC_RETRY_SLEEP_DURATION, Duration::from_secs(60));
+
+/// The longest checkpoints are held for their batch. Messages can only be
+/// relayed with the checkpoint at their own index, so on origins that take
+/// longer to dispatch a batch of messages the held checkpoints are written
+/// individually instead.
+const MAX_CHECKPOINT_BATCH_HOLD: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Clone)]
 pub(crate) struct ValidatorSubmitter {
     interval: Duration,
     reorg_period: ReorgPeriod,
     signer: SingletonSignerHandle,
+    /// The hash function the origin chain's contracts compute checkpoint
+    /// digests with
+    hash_algorithm: HashAlgorithm,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    /// Whether to also write completed batches of checkpoints
+    checkpoint_batches: bool,
+    /// If set, only this many of the latest checkpoints submitted at once
+    /// are written individually, and the others only in their batch
+    latest_individual_checkpoints: Option<u32>,
+    /// The checkpoints only written in their batch, held until it's complete
+    /// or for at most `max_batch_hold`, with when they were first held
+    held_for_batches: Arc<Mutex<BTreeMap<u32, (CheckpointWithMessageId, Instant)>>>,
+    max_batch_hold: Duration,
+    /// If set, checkpoints are only signed once a second RPC agrees with them
+    rpc_quorum: Option<RpcQuorum>,
     db: Arc<dyn HyperlaneDb>,
     metrics: ValidatorSubmitterMetrics,
 }
 
 impl ValidatorSubmitter {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         interval: Duration,
         reorg_period: ReorgPeriod,
         merkle_tree_hook: Arc<dyn MerkleTreeHook>,
         signer: SingletonSignerHandle,
+        hash_algorithm: HashAlgorithm,
         checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+        checkpoint_batches: bool,
+        latest_individual_checkpoints: Option<u32>,
+        rpc_quorum: Option<RpcQuorum>,
         db: Arc<dyn HyperlaneDb>,
         metrics: ValidatorSubmitterMetrics,
     ) -> Self {
@@ -42,7 +82,13 @@ impl ValidatorSubmitter {
             interval,
             merkle_tree_hook,
             signer,
+            hash_algorithm,
             checkpoint_syncer,
+            checkpoint_batches,
+            latest_individual_checkpoints,
+            held_for_batches: Default::default(),
+            max_batch_hold: MAX_CHECKPOINT_BATCH_HOLD,
+            rpc_quorum,
             db,
             metrics,
         }
@@ -57,12 +103,31 @@ impl ValidatorSubmitter {
         }
     }
 
+    fn retrier(&self, policy: RetryPolicy, call_site: &str) -> Retrier {
+        Retrier::with_metrics(policy, call_site, self.metrics.retries.clone())
+    }
+
+    /// Whether the checkpoint may be signed, i.e. there's no quorum RPC or
+    /// it agrees with the checkpoint
+    pub(crate) async fn quorum_agrees(&self, checkpoint: &Checkpoint) -> bool {
+        match &self.rpc_quorum {
+            Some(rpc_quorum) => {
+                rpc_quorum.check(checkpoint, &self.reorg_period).await == QuorumCheck::Agreed
+            }
+            None => true,
+        }
+    }
+
     /// Submits signed checkpoints from index 0 until the target checkpoint (inclusive).
+    /// If a merkle tree snapshot was persisted by a previous run, only the checkpoints
+    /// after it are submitted.
     /// Runs idly forever once the target checkpoint is reached to avoid exiting the task.
     pub(crate) async fn backfill_checkpoint_submitter(self, target_checkpoint: Checkpoint) {
-        let mut tree = IncrementalMerkle::default();
+        let mut tree = self.restore_merkle_tree_snapshot(&target_checkpoint);
+        let start_count = tree.count();
         self.submit_checkpoints_until_correctness_checkpoint(&mut tree, &target_checkpoint)
             .await;
+        self.store_merkle_tree_snapshot(&tree, start_count);
 
         info!(
             ?target_checkpoint,
@@ -72,6 +137,7 @@ impl ValidatorSubmitter {
 
     /// Submits signed checkpoints indefinitely, starting from the `tree`.
     pub(crate) async fn checkpoint_submitter(self, mut tree: IncrementalMerkle) {
+        let start_count = tree.count();
         // How often to log checkpoint info - once every minute
         let checkpoint_info_log_period = Duration::from_secs(60);
         // The instant in which we last logged checkpoint info, if at all
@@ -91,12 +157,17 @@ impl ValidatorSubmitter {
 
         loop {
             // Lag by reorg period because this is our correctness checkpoint.
-            let latest_checkpoint = call_and_retry_indefinitely(|| {
-                let merkle_tree_hook = self.merkle_tree_hook.clone();
-                let reorg_period = self.reorg_period.clone();
-                Box::pin(async move { merkle_tree_hook.latest_checkpoint(&reorg_period).await })
-            })
-            .await;
+            let latest_checkpoint = self
+                .retrier(
+                    LATEST_CHECKPOINT_RETRY_POLICY,
+                    "validator_latest_checkpoint",
+                )
+                .call_until_success(|| {
+                    let merkle_tree_hook = self.merkle_tree_hook.clone();
+                    let reorg_period = self.reorg_period.clone();
+                    Box::pin(async move { merkle_tree_hook.latest_checkpoint(&reorg_period).await })
+                })
+                .await;
 
             self.metrics
                 .latest_checkpoint_observed
@@ -124,8 +195,16 @@ impl ValidatorSubmitter {
                 sleep(self.interval).await;
                 continue;
             }
+            // Nothing was ingested into the tree yet, so checkpoints the
+            // quorum RPC didn't agree with are signed once it agrees with a
+            // later one
+            if !self.quorum_agrees(&latest_checkpoint).await {
+                sleep(self.interval).await;
+                continue;
+            }
             self.submit_checkpoints_until_correctness_checkpoint(&mut tree, &latest_checkpoint)
                 .await;
+            self.store_merkle_tree_snapshot(&tree, start_count);
 
             self.metrics
                 .latest_checkpoint_processed
@@ -135,6 +214,72 @@ impl ValidatorSubmitter {
         }
     }
 
+    /// Returns the latest persisted merkle tree snapshot if it doesn't exceed the
+    /// target checkpoint, or an empty tree otherwise.
+    fn restore_merkle_tree_snapshot(&self, target_checkpoint: &Checkpoint) -> IncrementalMerkle {
+        match self.db.retrieve_merkle_tree_snapshot() {
+            Ok(Some(snapshot)) if !tree_exceeds_checkpoint(target_checkpoint, &snapshot) => {
+                info!(
+                    snapshot_count = snapshot.count(),
+                    ?target_checkpoint,
+                    "Restored merkle tree from snapshot"
+                );
+                snapshot
+            }
+            Ok(Some(snapshot)) => {
+                warn!(
+                    snapshot_count = snapshot.count(),
+                    ?target_checkpoint,
+                    "Merkle tree snapshot is ahead of target checkpoint, rebuilding tree from index 0"
+                );
+                IncrementalMerkle::default()
+            }
+            Ok(None) => IncrementalMerkle::default(),
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to retrieve merkle tree snapshot, rebuilding tree from index 0"
+                );
+                IncrementalMerkle::default()
+            }
+        }
+    }
+
+    /// Persists the tree as the latest merkle tree snapshot, which a restarted
+    /// validator resumes the backfill from.
+    ///
+    /// All checkpoints up to a snapshot must have been submitted. The tree is
+    /// only stored if it was built up from an empty tree or from a count the
+    /// previous snapshot already reaches, and if it's ahead of that snapshot.
+    /// Nor is it while checkpoints are held for their batch, which a
+    /// restarted validator must submit again.
+    fn store_merkle_tree_snapshot(&self, tree: &IncrementalMerkle, start_count: usize) {
+        if !self
+            .held_for_batches
+            .lock()
+            .expect("held checkpoints lock poisoned")
+            .is_empty()
+        {
+            return;
+        }
+        let snapshot_count = match self.db.retrieve_merkle_tree_snapshot() {
+            Ok(snapshot) => snapshot
+                .map(|snapshot| snapshot.count())
+                .unwrap_or_default(),
+            Err(err) => {
+                warn!(?err, "Failed to retrieve merkle tree snapshot");
+                return;
+            }
+        };
+        if (start_count > 0 && snapshot_count < start_count) || snapshot_count >= tree.count() {
+            return;
+        }
+        match self.db.store_merkle_tree_snapshot(tree) {
+            Ok(()) => debug!(count = tree.count(), "Stored merkle tree snapshot"),
+            Err(err) => warn!(?err, "Failed to store merkle tree snapshot"),
+        }
+    }
+
     /// Submits signed checkpoints relating to the given tree until the correctness checkpoint (inclusive).
     /// Only submits the signed checkpoints once the correctness checkpoint is reached.
     async fn submit_checkpoints_until_correctness_checkpoint(
@@ -247,6 +392,10 @@ impl ValidatorSubmitter {
                 "Signed all queued checkpoints until index"
             );
         }
+
+        // Also on ticks without new checkpoints, as origins with few messages
+        // may not complete a batch for a long time
+        self.write_overdue_held_checkpoints().await;
     }
 
     async fn sign_and_submit_checkpoint(
@@ -261,7 +410,10 @@ impl ValidatorSubmitter {
             debug!(index = checkpoint.index, "Checkpoint already submitted");
             return Ok(());
         }
-        let signed_checkpoint = self.signer.sign(checkpoint).await?;
+        let signed_checkpoint = self
+            .signer
+            .sign_with_hasher(checkpoint, &self.hash_algorithm)
+            .await?;
         self.checkpoint_syncer
             .write_checkpoint(&signed_checkpoint)
             .await?;
@@ -274,26 +426,47 @@ impl ValidatorSubmitter {
     }
 
     /// Signs and submits any previously unsubmitted checkpoints.
-    async fn sign_and_submit_checkpoints(&self, checkpoints: Vec<CheckpointWithMessageId>) {
+    async fn sign_and_submit_checkpoints(&self, mut checkpoints: Vec<CheckpointWithMessageId>) {
+        let first_checkpoint = checkpoints.as_slice()[0];
         let last_checkpoint = checkpoints.as_slice()[checkpoints.len() - 1];
+        if let Some(latest_individual_checkpoints) = self.latest_individual_checkpoints() {
+            let individual = checkpoints.split_off(
+                checkpoints
+                    .len()
+                    .saturating_sub(latest_individual_checkpoints as usize),
+            );
+            self.hold_for_batches(std::mem::replace(&mut checkpoints, individual));
+        }
+
         // Submits checkpoints to the store in reverse order. This speeds up processing historic checkpoints (those before the validator is spun up),
         // since those are the most likely to make messages become processable.
         // A side effect is that new checkpoints will also be submitted in reverse order.
         for queued_checkpoint in checkpoints.into_iter().rev() {
-            // certain checkpoint stores rate limit very aggressively, so we retry indefinitely
-            call_and_retry_indefinitely(|| {
-                let self_clone = self.clone();
-                Box::pin(async move {
-                    self_clone
-                        .sign_and_submit_checkpoint(queued_checkpoint)
-                        .await?;
-                    Ok(())
+            self.retrier(CHECKPOINT_STORE_RETRY_POLICY, "validator_submit_checkpoint")
+                .call_until_success(|| {
+                    let self_clone = self.clone();
+                    Box::pin(async move {
+                        self_clone
+                            .sign_and_submit_checkpoint(queued_checkpoint)
+                            .await?;
+                        Ok(())
+                    })
                 })
-            })
-            .await;
+                .await;
+        }
+
+        // Batches may be the only copy of the checkpoints held for them, so
+        // they're written before the latest index covers these
+        if self.checkpoint_batches {
+            self.write_completed_checkpoint_batches(first_checkpoint.index, last_checkpoint.index)
+                .await;
         }
 
-        call_and_retry_indefinitely(|| {
+        self.retrier(
+            CHECKPOINT_STORE_RETRY_POLICY,
+            "validator_update_latest_index",
+        )
+        .call_until_success(|| {
             let self_clone = self.clone();
             Box::pin(async move {
                 self_clone
@@ -305,6 +478,144 @@ impl ValidatorSubmitter {
         })
         .await;
     }
+
+    /// How many of the latest checkpoints submitted at once are written
+    /// individually, if the others are only written in their batch
+    fn latest_individual_checkpoints(&self) -> Option<u32> {
+        self.latest_individual_checkpoints
+            .filter(|_| self.checkpoint_batches)
+    }
+
+    fn hold_for_batches(&self, checkpoints: Vec<CheckpointWithMessageId>) {
+        let now = Instant::now();
+        let mut held = self
+            .held_for_batches
+            .lock()
+            .expect("held checkpoints lock poisoned");
+        for checkpoint in checkpoints {
+            held.entry(checkpoint.index).or_insert((checkpoint, now));
+        }
+    }
+
+    /// Writes the checkpoints held for their batch for longer than the
+    /// maximum hold individually, and stops holding them
+    async fn write_overdue_held_checkpoints(&self) {
+        let overdue: Vec<_> = self
+            .held_for_batches
+            .lock()
+            .expect("held checkpoints lock poisoned")
+            .values()
+            .filter(|(_, held_since)| held_since.elapsed() >= self.max_batch_hold)
+            .map(|(checkpoint, _)| *checkpoint)
+            .collect();
+        if overdue.is_empty() {
+            return;
+        }
+        info!(
+            count = overdue.len(),
+            "Writing checkpoints held for their batch for too long individually"
+        );
+        // In reverse order, like all other checkpoints
+        for checkpoint in overdue.into_iter().rev() {
+            self.retrier(CHECKPOINT_STORE_RETRY_POLICY, "validator_submit_checkpoint")
+                .call_until_success(|| {
+                    let self_clone = self.clone();
+                    Box::pin(async move {
+                        self_clone.sign_and_submit_checkpoint(checkpoint).await?;
+                        Ok(())
+                    })
+                })
+                .await;
+            self.held_for_batches
+                .lock()
+                .expect("held checkpoints lock poisoned")
+                .remove(&checkpoint.index);
+        }
+    }
+
+    /// The checkpoints held for the batch starting at `start_index`
+    fn held_for_batch(&self, start_index: u32) -> Vec<CheckpointWithMessageId> {
+        self.held_for_batches
+            .lock()
+            .expect("held checkpoints lock poisoned")
+            .range(start_index..start_index + CHECKPOINT_BATCH_SIZE)
+            .map(|(_, (checkpoint, _))| *checkpoint)
+            .collect()
+    }
+
+    fn release_held_for_batch(&self, start_index: u32) {
+        self.held_for_batches
+            .lock()
+            .expect("held checkpoints lock poisoned")
+            .retain(|index, _| checkpoint_batch_start(*index) != start_index);
+    }
+
+    /// Writes the batches of checkpoints completed by submitting the
+    /// checkpoints from `first_index` to `last_index`, and those of the
+    /// checkpoints still held for a completed batch.
+    async fn write_completed_checkpoint_batches(&self, first_index: u32, last_index: u32) {
+        let first_held_index = self
+            .held_for_batches
+            .lock()
+            .expect("held checkpoints lock poisoned")
+            .keys()
+            .next()
+            .copied();
+        let mut start_index =
+            checkpoint_batch_start(first_held_index.map_or(first_index, |i| i.min(first_index)));
+        while let Some(end_index) = start_index
+            .checked_add(CHECKPOINT_BATCH_SIZE - 1)
+            .filter(|end_index| *end_index <= last_index)
+        {
+            // Batches of individually written checkpoints are an optimization
+            // for readers, which fall back to these. Batches of held
+            // checkpoints are attempted again with the next ones submitted.
+            if let Err(err) = self.write_checkpoint_batch(start_index).await {
+                warn!(?err, start_index, "Failed to write checkpoint batch");
+            }
+            start_index = end_index + 1;
+        }
+    }
+
+    async fn write_checkpoint_batch(&self, start_index: u32) -> Result<()> {
+        if self
+            .checkpoint_syncer
+            .fetch_checkpoint_batch(start_index)
+            .await?
+            .is_some()
+        {
+            debug!(start_index, "Checkpoint batch already written");
+            self.release_held_for_batch(start_index);
+            return Ok(());
+        }
+        let mut held: HashMap<_, _> = self
+            .held_for_batch(start_index)
+            .into_iter()
+            .map(|checkpoint| (checkpoint.index, checkpoint))
+            .collect();
+        let mut checkpoints = Vec::with_capacity(CHECKPOINT_BATCH_SIZE as usize);
+        for index in start_index..start_index + CHECKPOINT_BATCH_SIZE {
+            let checkpoint = match held.remove(&index) {
+                Some(checkpoint) => {
+                    self.signer
+                        .sign_with_hasher(checkpoint, &self.hash_algorithm)
+                        .await?
+                }
+                None => self
+                    .checkpoint_syncer
+                    .fetch_checkpoint(index)
+                    .await?
+                    .ok_or_else(|| eyre!("Missing checkpoint {index} of the batch"))?,
+            };
+            checkpoints.push(checkpoint);
+        }
+        self.checkpoint_syncer
+            .write_checkpoint_batch(&checkpoints)
+            .await?;
+        self.release_held_for_batch(start_index);
+        info!(start_index, "Wrote checkpoint batch");
+        Ok(())
+    }
 }
 
 /// Returns whether the tree exceeds the checkpoint.
@@ -314,10 +625,78 @@ fn tree_exceeds_checkpoint(checkpoint: &Checkpoint, tree: &IncrementalMerkle) ->
     checkpoint.index + 1 < tree.count() as u32
 }
 
+/// Cross-checks checkpoints with the merkle tree hook as read through a
+/// second, independent RPC of the origin chain, so that a single faulty or
+/// malicious RPC can't get the validator to sign an invalid checkpoint.
+#[derive(Clone, Debug)]
+pub(crate) struct RpcQuorum {
+    merkle_tree_hook: Arc<dyn MerkleTreeHook>,
+    /// Checkpoints the second RPC disagreed with
+    mismatches: IntCounter,
+}
+
+/// The outcome of cross-checking a checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QuorumCheck {
+    /// The second RPC returns the same checkpoint
+    Agreed,
+    /// The second RPC returns another checkpoint at the same index
+    Disagreed,
+    /// The second RPC is at another index, or failed to respond
+    Inconclusive,
+}
+
+impl RpcQuorum {
+    pub(crate) fn new(merkle_tree_hook: Arc<dyn MerkleTreeHook>, mismatches: IntCounter) -> Self {
+        Self {
+            merkle_tree_hook,
+            mismatches,
+        }
+    }
+
+    /// Compares the checkpoint with the latest checkpoint of the second RPC,
+    /// lagging by the same reorg period
+    pub(crate) async fn check(
+        &self,
+        checkpoint: &Checkpoint,
+        reorg_period: &ReorgPeriod,
+    ) -> QuorumCheck {
+        let quorum_checkpoint = match self.merkle_tree_hook.latest_checkpoint(reorg_period).await {
+            Ok(quorum_checkpoint) => quorum_checkpoint,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to fetch the latest checkpoint from the quorum RPC"
+                );
+                return QuorumCheck::Inconclusive;
+            }
+        };
+        if quorum_checkpoint.index != checkpoint.index {
+            debug!(
+                ?checkpoint,
+                ?quorum_checkpoint,
+                "Quorum RPC is at another checkpoint index, waiting for the RPCs to converge"
+            );
+            QuorumCheck::Inconclusive
+        } else if quorum_checkpoint != *checkpoint {
+            error!(
+                ?checkpoint,
+                ?quorum_checkpoint,
+                "Quorum RPC disagrees with the checkpoint, refusing to sign it"
+            );
+            self.mismatches.inc();
+            QuorumCheck::Disagreed
+        } else {
+            QuorumCheck::Agreed
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct ValidatorSubmitterMetrics {
     latest_checkpoint_observed: IntGauge,
     latest_checkpoint_processed: IntGauge,
+    retries: RetryMetrics,
 }
 
 impl ValidatorSubmitterMetrics {
@@ -330,6 +709,7 @@ impl ValidatorSubmitterMetrics {
             latest_checkpoint_processed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_processed", chain_name]),
+            retries: metrics.retry_metrics(),
         }
     }
 }
@@ -341,15 +721,16 @@ mod test {
     use eyre::Result;
     use hyperlane_base::{
         db::{DbResult, HyperlaneDb, InterchainGasExpenditureData, InterchainGasPaymentData},
+        test_utils::{dummy_core_metrics, InMemoryCheckpointSyncer},
         AgentMetadata,
     };
     use hyperlane_core::{
-        test_utils::dummy_domain, GasPaymentKey, HyperlaneChain, HyperlaneContract,
-        HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, InterchainGasPayment,
-        InterchainGasPaymentMeta, MerkleTreeHook, MerkleTreeInsertion, PendingOperationStatus,
-        ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId, H160, H256,
+        test_utils::dummy_domain, ChainCommunicationError, GasPaymentKey, HyperlaneChain,
+        HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider,
+        InterchainGasPayment, InterchainGasPaymentMeta, MerkleTreeHook, MerkleTreeInsertion,
+        PendingOperationStatus, ReorgEvent, Signature, SignedAnnouncement,
+        SignedCheckpointWithMessageId, SignedType, H160, H256, U256,
     };
-    use prometheus::Registry;
     use std::{fmt::Debug, sync::Arc, time::Duration};
     use tokio::sync::mpsc;
 
@@ -438,6 +819,24 @@ mod test {
                 &self,
                 message_id: &H256,
             ) -> DbResult<Option<u32>>;
+            fn store_message_first_seen_at_by_message_id(
+                &self,
+                message_id: &H256,
+                timestamp: &u64,
+            ) -> DbResult<()>;
+            fn retrieve_message_first_seen_at_by_message_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<u64>>;
+            fn store_message_expired_at_by_message_id(
+                &self,
+                message_id: &H256,
+                timestamp: &u64,
+            ) -> DbResult<()>;
+            fn retrieve_message_expired_at_by_message_id(
+                &self,
+                message_id: &H256,
+            ) -> DbResult<Option<u64>>;
             fn store_merkle_tree_insertion_by_leaf_index(
                 &self,
                 leaf_index: &u32,
@@ -464,6 +863,10 @@ mod test {
             ) -> DbResult<Option<u64>>;
             fn store_highest_seen_message_nonce_number(&self, nonce: &u32) -> DbResult<()>;
             fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+            fn store_merkle_tree_snapshot(&self, tree: &IncrementalMerkle) -> DbResult<()>;
+            fn retrieve_merkle_tree_snapshot(&self) -> DbResult<Option<IncrementalMerkle>>;
+            fn store_spend_in_period(&self, spender: &str, period: u64, spend: &U256) -> DbResult<()>;
+            fn retrieve_spend_in_period(&self, spender: &str, period: u64) -> DbResult<Option<U256>>;
 
         }
     }
@@ -519,7 +922,7 @@ mod test {
 
     fn dummy_metrics() -> ValidatorSubmitterMetrics {
         let origin_domain = dummy_domain(0, "dummy_origin_domain");
-        let core_metrics = CoreMetrics::new("dummy_relayer", 37582, Registry::new()).unwrap();
+        let core_metrics = dummy_core_metrics("dummy_validator");
         ValidatorSubmitterMetrics::new(&core_metrics, &origin_domain)
     }
 
@@ -626,7 +1029,11 @@ mod test {
             ReorgPeriod::from_blocks(expected_reorg_period),
             Arc::new(mock_merkle_tree_hook),
             dummy_singleton_handle(),
+            HashAlgorithm::default(),
             Arc::new(mock_checkpoint_syncer),
+            false,
+            None,
+            None,
             Arc::new(db),
             dummy_metrics(),
         );
@@ -648,4 +1055,334 @@ mod test {
             )
             .await;
     }
+
+    fn dummy_tree(count: usize) -> IncrementalMerkle {
+        let mut tree = IncrementalMerkle::default();
+        for _ in 0..count {
+            tree.ingest(H256::random());
+        }
+        tree
+    }
+
+    fn dummy_validator_submitter(db: MockDb) -> ValidatorSubmitter {
+        ValidatorSubmitter::new(
+            Duration::from_secs(1),
+            ReorgPeriod::from_blocks(0),
+            Arc::new(MockMerkleTreeHook::new()),
+            dummy_singleton_handle(),
+            HashAlgorithm::default(),
+            Arc::new(MockCheckpointSyncer::new()),
+            false,
+            None,
+            None,
+            Arc::new(db),
+            dummy_metrics(),
+        )
+    }
+
+    #[test]
+    fn merkle_tree_snapshot_is_only_stored_over_contiguous_range() {
+        // The tip submitter started at count 5, but the backfill only reached count 3
+        let mut db = MockDb::new();
+        db.expect_retrieve_merkle_tree_snapshot()
+            .returning(|| Ok(Some(dummy_tree(3))));
+        db.expect_store_merkle_tree_snapshot().never();
+        dummy_validator_submitter(db).store_merkle_tree_snapshot(&dummy_tree(7), 5);
+
+        // The backfill reached the count the tip submitter started at
+        let mut db = MockDb::new();
+        db.expect_retrieve_merkle_tree_snapshot()
+            .returning(|| Ok(Some(dummy_tree(5))));
+        db.expect_store_merkle_tree_snapshot()
+            .withf(|tree| tree.count() == 7)
+            .once()
+            .returning(|_| Ok(()));
+        dummy_validator_submitter(db).store_merkle_tree_snapshot(&dummy_tree(7), 5);
+
+        // A tree built up from index 0 can always be stored, unless it's behind the snapshot
+        let mut db = MockDb::new();
+        db.expect_retrieve_merkle_tree_snapshot()
+            .returning(|| Ok(Some(dummy_tree(9))));
+        db.expect_store_merkle_tree_snapshot().never();
+        dummy_validator_submitter(db).store_merkle_tree_snapshot(&dummy_tree(7), 0);
+    }
+
+    #[test]
+    fn merkle_tree_snapshot_ahead_of_target_is_not_restored() {
+        let snapshot = dummy_tree(5);
+        let target_checkpoint = |tree: &IncrementalMerkle| Checkpoint {
+            root: tree.root(),
+            index: tree.index(),
+            merkle_tree_hook_address: H256::from_low_u64_be(0),
+            mailbox_domain: 0,
+        };
+
+        let mut db = MockDb::new();
+        let snapshot_clone = snapshot.clone();
+        db.expect_retrieve_merkle_tree_snapshot()
+            .returning(move || Ok(Some(snapshot_clone.clone())));
+        let submitter = dummy_validator_submitter(db);
+
+        assert_eq!(
+            submitter.restore_merkle_tree_snapshot(&target_checkpoint(&dummy_tree(7))),
+            snapshot
+        );
+        assert_eq!(
+            submitter.restore_merkle_tree_snapshot(&target_checkpoint(&snapshot)),
+            snapshot
+        );
+        assert_eq!(
+            submitter.restore_merkle_tree_snapshot(&target_checkpoint(&dummy_tree(4))),
+            IncrementalMerkle::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn completed_checkpoint_batches_are_written() {
+        let checkpoint_syncer = Arc::new(InMemoryCheckpointSyncer::default());
+        for index in 0..2500 {
+            let checkpoint = SignedType {
+                value: CheckpointWithMessageId {
+                    checkpoint: Checkpoint {
+                        root: H256::from_low_u64_be(index as u64),
+                        index,
+                        merkle_tree_hook_address: H256::from_low_u64_be(0),
+                        mailbox_domain: 0,
+                    },
+                    message_id: H256::from_low_u64_be(index as u64),
+                },
+                signature: Signature {
+                    r: U256::one(),
+                    s: U256::one(),
+                    v: 27,
+                },
+            };
+            checkpoint_syncer
+                .write_checkpoint(&checkpoint)
+                .await
+                .unwrap();
+        }
+        let submitter = ValidatorSubmitter::new(
+            Duration::from_secs(1),
+            ReorgPeriod::from_blocks(0),
+            Arc::new(MockMerkleTreeHook::new()),
+            dummy_singleton_handle(),
+            HashAlgorithm::default(),
+            checkpoint_syncer.clone(),
+            true,
+            None,
+            None,
+            Arc::new(MockDb::new()),
+            dummy_metrics(),
+        );
+
+        // Submitting checkpoints 500 to 2499 completes the first two batches
+        submitter
+            .write_completed_checkpoint_batches(500, 2499)
+            .await;
+
+        let first_batch = checkpoint_syncer
+            .fetch_checkpoint_batch(0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first_batch.len(), CHECKPOINT_BATCH_SIZE as usize);
+        assert_eq!(first_batch[999].value.index, 999);
+        assert!(checkpoint_syncer
+            .fetch_checkpoint_batch(CHECKPOINT_BATCH_SIZE)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(checkpoint_syncer
+            .fetch_checkpoint_batch(2 * CHECKPOINT_BATCH_SIZE)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn older_checkpoints_are_only_written_in_their_batch() {
+        use ethers::signers::LocalWallet;
+        use hyperlane_ethereum::{Signers, SingletonSigner};
+
+        let wallet: LocalWallet =
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+                .parse()
+                .unwrap();
+        let (signer, signer_handle) = SingletonSigner::new(Signers::Local(wallet));
+        tokio::spawn(signer.run());
+        let checkpoint_syncer = Arc::new(InMemoryCheckpointSyncer::default());
+        let submitter = ValidatorSubmitter::new(
+            Duration::from_secs(1),
+            ReorgPeriod::from_blocks(0),
+            Arc::new(MockMerkleTreeHook::new()),
+            signer_handle,
+            HashAlgorithm::default(),
+            checkpoint_syncer.clone(),
+            true,
+            Some(2),
+            None,
+            Arc::new(MockDb::new()),
+            dummy_metrics(),
+        );
+        let checkpoints = |indexes: std::ops::RangeInclusive<u32>| {
+            indexes
+                .map(|index| CheckpointWithMessageId {
+                    checkpoint: Checkpoint {
+                        root: H256::from_low_u64_be(index as u64),
+                        index,
+                        merkle_tree_hook_address: H256::zero(),
+                        mailbox_domain: 0,
+                    },
+                    message_id: H256::from_low_u64_be(index as u64),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The batch isn't complete, so all but the latest two are held
+        submitter
+            .sign_and_submit_checkpoints(checkpoints(0..=997))
+            .await;
+        assert!(checkpoint_syncer
+            .fetch_checkpoint(995)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(checkpoint_syncer
+            .fetch_checkpoint(996)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(checkpoint_syncer.latest_index().await.unwrap(), Some(997));
+        assert_eq!(submitter.held_for_batch(0).len(), 996);
+
+        // Completing it writes the held checkpoints along with the others
+        submitter
+            .sign_and_submit_checkpoints(checkpoints(998..=1002))
+            .await;
+        let batch = checkpoint_syncer
+            .fetch_checkpoint_batch(0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch[500].value.index, 500);
+        assert!(submitter.held_for_batch(0).is_empty());
+        assert!(checkpoint_syncer
+            .fetch_checkpoint(999)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(checkpoint_syncer
+            .fetch_checkpoint(1001)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(submitter.held_for_batch(CHECKPOINT_BATCH_SIZE).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn checkpoints_held_for_too_long_are_written_individually() {
+        use ethers::signers::LocalWallet;
+        use hyperlane_ethereum::{Signers, SingletonSigner};
+
+        let wallet: LocalWallet =
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+                .parse()
+                .unwrap();
+        let (signer, signer_handle) = SingletonSigner::new(Signers::Local(wallet));
+        tokio::spawn(signer.run());
+        let checkpoint_syncer = Arc::new(InMemoryCheckpointSyncer::default());
+        let mut submitter = ValidatorSubmitter::new(
+            Duration::from_secs(1),
+            ReorgPeriod::from_blocks(0),
+            Arc::new(MockMerkleTreeHook::new()),
+            signer_handle,
+            HashAlgorithm::default(),
+            checkpoint_syncer.clone(),
+            true,
+            Some(2),
+            None,
+            Arc::new(MockDb::new()),
+            dummy_metrics(),
+        );
+        let checkpoints = (0..10)
+            .map(|index| CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    root: H256::from_low_u64_be(index as u64),
+                    index,
+                    merkle_tree_hook_address: H256::zero(),
+                    mailbox_domain: 0,
+                },
+                message_id: H256::from_low_u64_be(index as u64),
+            })
+            .collect::<Vec<_>>();
+
+        // A low traffic origin is far from completing the batch
+        submitter.sign_and_submit_checkpoints(checkpoints).await;
+        assert_eq!(submitter.held_for_batch(0).len(), 8);
+        submitter.write_overdue_held_checkpoints().await;
+        assert_eq!(submitter.held_for_batch(0).len(), 8);
+
+        // Until the checkpoints were held for too long
+        submitter.max_batch_hold = Duration::ZERO;
+        submitter.write_overdue_held_checkpoints().await;
+        assert!(submitter.held_for_batch(0).is_empty());
+        for index in 0..10 {
+            assert!(checkpoint_syncer
+                .fetch_checkpoint(index)
+                .await
+                .unwrap()
+                .is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rpc_quorum_only_agrees_on_the_same_checkpoint() {
+        let checkpoint = |index: u32, root: H256| Checkpoint {
+            root,
+            index,
+            merkle_tree_hook_address: H256::zero(),
+            mailbox_domain: 0,
+        };
+        let quorum_checkpoint = checkpoint(10, H256::repeat_byte(1));
+
+        // The quorum RPC fails after three calls
+        let mut calls = 0;
+        let mut mock_merkle_tree_hook = MockMerkleTreeHook::new();
+        mock_merkle_tree_hook
+            .expect_latest_checkpoint()
+            .returning(move |_| {
+                calls += 1;
+                if calls <= 3 {
+                    Ok(quorum_checkpoint)
+                } else {
+                    Err(ChainCommunicationError::from_other_str("RPC is down"))
+                }
+            });
+        let mismatches = IntCounter::new("mismatches", "help").unwrap();
+        let rpc_quorum = RpcQuorum::new(Arc::new(mock_merkle_tree_hook), mismatches.clone());
+        let reorg_period = ReorgPeriod::from_blocks(1);
+
+        assert_eq!(
+            rpc_quorum.check(&quorum_checkpoint, &reorg_period).await,
+            QuorumCheck::Agreed
+        );
+        assert_eq!(
+            rpc_quorum
+                .check(&checkpoint(10, H256::repeat_byte(2)), &reorg_period)
+                .await,
+            QuorumCheck::Disagreed
+        );
+        assert_eq!(
+            rpc_quorum
+                .check(&checkpoint(9, H256::repeat_byte(2)), &reorg_period)
+                .await,
+            QuorumCheck::Inconclusive
+        );
+        assert_eq!(
+            rpc_quorum.check(&quorum_checkpoint, &reorg_period).await,
+            QuorumCheck::Inconclusive
+        );
+        assert_eq!(mismatches.get(), 1);
+    }
 }
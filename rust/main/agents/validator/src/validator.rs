@@ -3,31 +3,33 @@ use std::{sync::Arc, time::Duration};
 use crate::server as validator_server;
 use async_trait::async_trait;
 use derive_more::AsRef;
-use eyre::Result;
+use eyre::{eyre, Context, Result};
 
 use futures_util::future::try_join_all;
 use tokio::{task::JoinHandle, time::sleep};
 use tracing::{error, info, info_span, instrument::Instrumented, warn, Instrument};
 
 use hyperlane_base::{
-    db::{HyperlaneDb, HyperlaneRocksDB, DB},
+    db::{HyperlaneDb, HyperlaneRocksDB},
     metrics::AgentMetrics,
+    self_test_read_checkpoint, self_test_sign_checkpoint, self_test_write_checkpoint,
     settings::ChainConf,
     AgentMetadata, BaseAgent, ChainMetrics, ChainSpecificMetricsUpdater, CheckpointSyncer,
     ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore, RuntimeMetrics,
-    SequencedDataContractSync,
+    SelfTestReport, SequencedDataContractSync,
 };
 
 use hyperlane_core::{
     Announcement, ChainResult, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneSigner,
-    HyperlaneSignerExt, Mailbox, MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod, TxOutcome,
-    ValidatorAnnounce, H256, U256,
+    HyperlaneSignerExt, Mailbox, MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod, SignedType,
+    TxOutcome, ValidatorAnnounce, H256, U256,
 };
 use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 
 use crate::{
+    attestation::{CheckpointAttestationMetrics, CheckpointAttester},
     settings::ValidatorSettings,
-    submit::{ValidatorSubmitter, ValidatorSubmitterMetrics},
+    submit::{RpcQuorum, ValidatorSubmitter, ValidatorSubmitterMetrics},
 };
 
 /// A validator agent
@@ -47,7 +49,14 @@ pub struct Validator {
     signer_instance: Option<Box<SingletonSigner>>,
     reorg_period: ReorgPeriod,
     interval: Duration,
+    auto_announce: bool,
+    checkpoint_batches: bool,
+    latest_individual_checkpoints: Option<u32>,
+    /// Set if checkpoints are cross-checked with a second RPC before signing
+    rpc_quorum: Option<RpcQuorum>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
+    // temporary holder until `run` is called
+    checkpoint_attester: Option<CheckpointAttester>,
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
     chain_metrics: ChainMetrics,
@@ -73,7 +82,11 @@ impl BaseAgent for Validator {
     where
         Self: Sized,
     {
-        let db = DB::from_path(&settings.db)?;
+        settings
+            .verify_contract_deployments(std::iter::once(&settings.origin_chain), &metrics)
+            .await?;
+        let db = settings.open_db(&settings.db)?;
+        metrics.health().set_db(db.clone());
         let msg_db = HyperlaneRocksDB::new(&settings.origin_chain, db);
 
         // Intentionally using hyperlane_ethereum for the validator's signer
@@ -82,7 +95,7 @@ impl BaseAgent for Validator {
         let core = settings.build_hyperlane_core(metrics.clone());
         // Be extra sure to panic checkpoint syncer fails, which indicates
         // a fatal startup error.
-        let checkpoint_syncer = settings
+        let checkpoint_syncer: Arc<dyn CheckpointSyncer> = settings
             .checkpoint_syncer
             .build_and_validate(None)
             .await
@@ -101,12 +114,63 @@ impl BaseAgent for Validator {
             .build_validator_announce(&settings.origin_chain, &metrics)
             .await?;
 
+        let checkpoint_attester = match &settings.checkpoint_attestation {
+            Some(conf) => {
+                let contract = settings
+                    .chain_setup(&settings.origin_chain)?
+                    .build_checkpoint_attestation(conf.address, &metrics)
+                    .await?;
+                Some(CheckpointAttester::new(
+                    settings.interval,
+                    conf.interval,
+                    conf.max_daily_spend,
+                    contract.into(),
+                    checkpoint_syncer.clone(),
+                    Arc::new(msg_db.clone()),
+                    CheckpointAttestationMetrics::new(&metrics, &settings.origin_chain)?,
+                ))
+            }
+            None => None,
+        };
+
         let origin_chain_conf = core
             .settings
             .chain_setup(&settings.origin_chain)
             .unwrap()
             .clone();
 
+        let rpc_quorum = match &settings.quorum_rpc_url {
+            Some(url) => {
+                let connection = origin_chain_conf
+                    .connection
+                    .with_rpc_url(url.clone())
+                    .ok_or_else(|| {
+                        eyre!(
+                            "Quorum RPC checks aren't supported on {} chains",
+                            settings.origin_chain.domain_protocol()
+                        )
+                    })?;
+                let quorum_chain_conf = ChainConf {
+                    connection,
+                    ..origin_chain_conf.clone()
+                };
+                let merkle_tree_hook = quorum_chain_conf
+                    .build_merkle_tree_hook(&metrics)
+                    .await
+                    .context("Building the quorum RPC merkle tree hook")?;
+                let mismatches = metrics.new_int_counter(
+                    "rpc_quorum_mismatches",
+                    "Checkpoints the validator refused to sign because the quorum RPC disagreed with them",
+                    &["chain"],
+                )?;
+                Some(RpcQuorum::new(
+                    merkle_tree_hook.into(),
+                    mismatches.with_label_values(&[settings.origin_chain.name()]),
+                ))
+            }
+            None => None,
+        };
+
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
 
         let merkle_tree_hook_sync = settings
@@ -132,7 +196,12 @@ impl BaseAgent for Validator {
             signer_instance: Some(Box::new(signer_instance)),
             reorg_period: settings.reorg_period,
             interval: settings.interval,
+            auto_announce: settings.auto_announce,
+            checkpoint_batches: settings.checkpoint_batches,
+            latest_individual_checkpoints: settings.latest_individual_checkpoints,
+            rpc_quorum,
             checkpoint_syncer,
+            checkpoint_attester,
             agent_metrics,
             chain_metrics,
             core_metrics: metrics,
@@ -205,6 +274,12 @@ impl BaseAgent for Validator {
                     for checkpoint_sync_task in self.run_checkpoint_submitters().await {
                         tasks.push(checkpoint_sync_task);
                     }
+                    if let Some(checkpoint_attester) = self.checkpoint_attester.take() {
+                        tasks.push(
+                            tokio::spawn(checkpoint_attester.run())
+                                .instrument(info_span!("CheckpointAttester")),
+                        );
+                    }
                     break;
                 }
                 _ => {
@@ -220,6 +295,42 @@ impl BaseAgent for Validator {
             error!(?err, "One of the validator tasks returned an error");
         }
     }
+
+    /// Signs the synthetic checkpoint, writes it to the checkpoint syncer and
+    /// reads it back. It never overwrites a real checkpoint.
+    async fn self_test(mut self) -> SelfTestReport {
+        let mut report = SelfTestReport::default();
+
+        // The signer handle only signs while the signer runs
+        if let Some(signer_instance) = self.signer_instance.take() {
+            tokio::spawn(signer_instance.run());
+        }
+
+        let Some(signed) = report.record(
+            "signer",
+            self_test_sign_checkpoint(&self.signer, &self.origin_chain).await,
+        ) else {
+            report.skip("checkpoint_syncer_write", "signer");
+            report.skip("checkpoint_syncer_read", "signer");
+            return report;
+        };
+
+        let written = report.record(
+            "checkpoint_syncer_write",
+            self_test_write_checkpoint(&*self.checkpoint_syncer, &signed).await,
+        );
+        if written.is_none() {
+            report.skip("checkpoint_syncer_read", "checkpoint_syncer_write");
+            return report;
+        }
+
+        report.record(
+            "checkpoint_syncer_read",
+            self_test_read_checkpoint(&*self.checkpoint_syncer, &signed, self.signer.eth_address())
+                .await,
+        );
+        report
+    }
 }
 
 impl Validator {
@@ -251,21 +362,33 @@ impl Validator {
             self.reorg_period.clone(),
             self.merkle_tree_hook.clone(),
             self.signer.clone(),
+            self.origin_chain_conf.hash_algorithm,
             self.checkpoint_syncer.clone(),
+            self.checkpoint_batches,
+            self.latest_individual_checkpoints,
+            self.rpc_quorum.clone(),
             Arc::new(self.db.clone()) as Arc<dyn HyperlaneDb>,
             ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain),
         );
 
-        let tip_tree = self
-            .merkle_tree_hook
-            .tree(&self.reorg_period)
-            .await
-            .expect("failed to get merkle tree");
-        // This function is only called after we have already checked that the
-        // merkle tree hook has count > 0, but we assert to be extra sure this is
-        // the case.
-        assert!(tip_tree.count() > 0, "merkle tree is empty");
-        let backfill_target = submitter.checkpoint(&tip_tree);
+        // The backfill target is signed without further checks, so it's only
+        // taken once the quorum RPC agrees with it, if any
+        let (tip_tree, backfill_target) = loop {
+            let tip_tree = self
+                .merkle_tree_hook
+                .tree(&self.reorg_period)
+                .await
+                .expect("failed to get merkle tree");
+            // This function is only called after we have already checked that the
+            // merkle tree hook has count > 0, but we assert to be extra sure this is
+            // the case.
+            assert!(tip_tree.count() > 0, "merkle tree is empty");
+            let backfill_target = submitter.checkpoint(&tip_tree);
+            if submitter.quorum_agrees(&backfill_target).await {
+                break (tip_tree, backfill_target);
+            }
+            sleep(self.interval).await;
+        };
 
         let backfill_submitter = submitter.clone();
 
@@ -335,7 +458,10 @@ impl Validator {
             mailbox_domain: self.mailbox.domain().id(),
             storage_location: announcement_location.clone(),
         };
-        let signed_announcement = self.signer.sign(announcement.clone()).await?;
+        let signed_announcement = self
+            .signer
+            .sign_with_hasher(announcement.clone(), &self.origin_chain_conf.hash_algorithm)
+            .await?;
         self.checkpoint_syncer
             .write_announcement(&signed_announcement)
             .await?;
@@ -366,33 +492,14 @@ impl Validator {
                     "Validator has not announced signature storage location"
                 );
 
-                if let Some(chain_signer) = self.core.settings.chains[self.origin_chain.name()]
-                    .chain_signer()
-                    .await?
-                {
-                    let chain_signer = chain_signer.address_string();
-                    info!(eth_validator_address=?announcement.validator, ?chain_signer, "Attempting self announce");
-                    let balance_delta = self
-                        .validator_announce
-                        .announce_tokens_needed(signed_announcement.clone())
-                        .await
-                        .unwrap_or_default();
-                    if balance_delta > U256::zero() {
-                        warn!(
-                            tokens_needed=%balance_delta,
-                            eth_validator_address=?announcement.validator,
-                            ?chain_signer,
-                            "Please send tokens to your chain signer address to announce",
-                        );
-                    } else {
-                        let result = self
-                            .validator_announce
-                            .announce(signed_announcement.clone())
-                            .await;
-                        Self::log_on_announce_failure(result, &chain_signer);
-                    }
+                if self.auto_announce {
+                    self.submit_announcement(&signed_announcement).await?;
                 } else {
-                    warn!(origin_chain=%self.origin_chain, "Cannot announce validator without a signer; make sure a signer is set for the origin chain");
+                    warn!(
+                        eth_validator_address=?announcement.validator,
+                        ?announcement_location,
+                        "Auto announce is disabled; waiting for the signature storage location to be announced",
+                    );
                 }
 
                 sleep(self.interval).await;
@@ -400,4 +507,42 @@ impl Validator {
         }
         Ok(())
     }
+
+    /// Submits the validator announcement with the origin chain signer, if
+    /// it holds enough tokens to pay for the transaction.
+    async fn submit_announcement(
+        &self,
+        signed_announcement: &SignedType<Announcement>,
+    ) -> Result<()> {
+        let Some(chain_signer) = self.core.settings.chains[self.origin_chain.name()]
+            .chain_signer()
+            .await?
+        else {
+            warn!(origin_chain=%self.origin_chain, "Cannot announce validator without a signer; make sure a signer is set for the origin chain");
+            return Ok(());
+        };
+        let chain_signer = chain_signer.address_string();
+        let validator = signed_announcement.value.validator;
+        info!(eth_validator_address=?validator, ?chain_signer, "Attempting self announce");
+        let balance_delta = self
+            .validator_announce
+            .announce_tokens_needed(signed_announcement.clone())
+            .await
+            .unwrap_or_default();
+        if balance_delta > U256::zero() {
+            warn!(
+                tokens_needed=%balance_delta,
+                eth_validator_address=?validator,
+                ?chain_signer,
+                "Please send tokens to your chain signer address to announce",
+            );
+        } else {
+            let result = self
+                .validator_announce
+                .announce(signed_announcement.clone())
+                .await;
+            Self::log_on_announce_failure(result, &chain_signer);
+        }
+        Ok(())
+    }
 }
@@ -106,6 +106,23 @@ impl Mailbox for CosmosMailbox {
         Ok(delivered.delivered)
     }
 
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn local_domain(&self) -> ChainResult<u32> {
+        let payload = payloads::mailbox::LocalDomainRequest {
+            local_domain: general::EmptyStruct {},
+        };
+
+        let data = self
+            .provider
+            .grpc()
+            .wasm_query(GeneralMailboxQuery { mailbox: payload }, None)
+            .await?;
+        let response: payloads::mailbox::LocalDomainResponse = serde_json::from_slice(&data)?;
+
+        Ok(response.local_domain)
+    }
+
     #[instrument(err, ret, skip(self))]
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
     async fn default_ism(&self) -> ChainResult<H256> {
@@ -201,6 +218,8 @@ impl Mailbox for CosmosMailbox {
             gas_limit: gas_limit.into(),
             gas_price: self.provider.grpc().gas_price(),
             l2_gas_limit: None,
+            l1_data_fee: None,
+            l1_blob_base_fee: None,
         };
 
         Ok(result)
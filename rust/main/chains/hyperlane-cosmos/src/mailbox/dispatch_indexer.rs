@@ -156,9 +156,15 @@ impl SequenceAwareIndexer<HyperlaneMessage> for CosmosMailboxDispatchIndexer {
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&self).await?;
 
-        let sequence = self.mailbox.nonce_at_block(Some(tip.into())).await?;
+        let sequence = self.sequence_count_at_block(tip).await?;
 
-        Ok((Some(sequence), tip))
+        Ok((sequence, tip))
+    }
+
+    async fn sequence_count_at_block(&self, block: u32) -> ChainResult<Option<u32>> {
+        let sequence = self.mailbox.nonce_at_block(Some(block.into())).await?;
+
+        Ok(Some(sequence))
     }
 }
 
@@ -354,12 +354,18 @@ impl Indexer<MerkleTreeInsertion> for CosmosMerkleTreeHookIndexer {
 impl SequenceAwareIndexer<MerkleTreeInsertion> for CosmosMerkleTreeHookIndexer {
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = self.get_finalized_block_number().await?;
+        let sequence = self.sequence_count_at_block(tip).await?;
+
+        Ok((sequence, tip))
+    }
+
+    async fn sequence_count_at_block(&self, block: u32) -> ChainResult<Option<u32>> {
         let sequence = self
             .merkle_tree_hook
-            .count_at_block(Some(tip.into()))
+            .count_at_block(Some(block.into()))
             .await?;
 
-        Ok((Some(sequence), tip))
+        Ok(Some(sequence))
     }
 }
 
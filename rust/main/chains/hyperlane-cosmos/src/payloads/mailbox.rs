@@ -28,6 +28,11 @@ pub struct RecipientIsmRequestInner {
     pub recipient_addr: String, // hexbinary
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocalDomainRequest {
+    pub local_domain: EmptyStruct,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DefaultIsmRequest {
     pub default_ism: EmptyStruct,
@@ -65,6 +70,11 @@ pub struct NonceResponse {
     pub nonce: u32,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LocalDomainResponse {
+    pub local_domain: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DefaultIsmResponse {
     pub default_ism: String, // hexbineary
@@ -153,7 +153,8 @@ impl WasmGrpcProvider {
                     .map_err(Into::<HyperlaneCosmosError>::into)
             })
             .collect();
-        let mut builder = FallbackProvider::builder();
+        let mut builder =
+            FallbackProvider::builder().with_rate_limiter(conf.get_rpc_rate_limiter());
         builder = builder.add_providers(channels?);
         let fallback_provider = builder.build();
         let provider = CosmosFallbackProvider::new(fallback_provider);
@@ -68,6 +68,7 @@ fn provider(address: &str) -> WasmGrpcProvider {
                 decimals: 6,
                 denom: "untrn".to_owned(),
             },
+            None,
         ),
         CosmosAmount {
             denom: "untrn".to_owned(),
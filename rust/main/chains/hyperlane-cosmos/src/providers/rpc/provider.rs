@@ -99,7 +99,8 @@ impl CosmosWasmRpcProvider {
             .iter()
             .map(CosmosRpcClient::new)
             .collect::<Result<Vec<_>, _>>()?;
-        let mut builder = FallbackProvider::builder();
+        let mut builder =
+            FallbackProvider::builder().with_rate_limiter(conf.get_rpc_rate_limiter());
         builder = builder.add_providers(providers);
         let fallback_provider = builder.build();
         let provider = CosmosFallbackProvider::new(fallback_provider);
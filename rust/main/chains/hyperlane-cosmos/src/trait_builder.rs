@@ -4,7 +4,8 @@ use derive_new::new;
 use url::Url;
 
 use hyperlane_core::{
-    config::OperationBatchConfig, ChainCommunicationError, FixedPointNumber, NativeToken,
+    config::OperationBatchConfig, rpc_clients::RpcRateLimiter, ChainCommunicationError,
+    FixedPointNumber, NativeToken,
 };
 
 /// Cosmos connection configuration
@@ -32,6 +33,8 @@ pub struct ConnectionConf {
     pub operation_batch: OperationBatchConfig,
     /// Native Token
     native_token: NativeToken,
+    /// Limits the rate of requests to the GRPC and RPC urls
+    rpc_rate_limiter: Option<RpcRateLimiter>,
 }
 
 /// Untyped cosmos amount
@@ -123,6 +126,11 @@ impl ConnectionConf {
         self.contract_address_bytes
     }
 
+    /// Get the rate limiter of requests to the GRPC and RPC urls
+    pub fn get_rpc_rate_limiter(&self) -> Option<RpcRateLimiter> {
+        self.rpc_rate_limiter.clone()
+    }
+
     /// Create a new connection configuration
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -135,6 +143,7 @@ impl ConnectionConf {
         contract_address_bytes: usize,
         operation_batch: OperationBatchConfig,
         native_token: NativeToken,
+        rpc_rate_limiter: Option<RpcRateLimiter>,
     ) -> Self {
         Self {
             grpc_urls,
@@ -146,6 +155,7 @@ impl ConnectionConf {
             contract_address_bytes,
             operation_batch,
             native_token,
+            rpc_rate_limiter,
         }
     }
 }
@@ -1,10 +1,13 @@
 use ethers::providers::Middleware;
 use ethers_core::types::{BlockId, BlockNumber};
 use hyperlane_core::{
-    config::OperationBatchConfig, ChainCommunicationError, ChainResult, ReorgPeriod, U256,
+    config::OperationBatchConfig, rpc_clients::RpcRateLimiter, ChainCommunicationError,
+    ChainResult, NativeToken, ReorgPeriod, U256,
 };
 use url::Url;
 
+use crate::{FeeHistoryCache, LogQueryBatcher, ReadBatcher, StorageLocationsCache};
+
 /// Ethereum RPC connection configuration
 #[derive(Debug, Clone)]
 pub enum RpcConnectionConf {
@@ -39,6 +42,25 @@ pub struct ConnectionConf {
     pub transaction_overrides: TransactionOverrides,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Native token of the chain. Gas prices and fees of chains whose native
+    /// token doesn't have 18 decimals are converted to 18 decimals, like on
+    /// other protocols.
+    pub native_token: NativeToken,
+    /// Limits the rate of requests to the chain's HTTP RPCs. Shared by all
+    /// providers built from this config.
+    pub rpc_rate_limiter: Option<RpcRateLimiter>,
+    /// If set, the fee history used to estimate EIP-1559 fees is cached and
+    /// shared by all the transactions submitted to the chain
+    pub fee_history_cache: Option<FeeHistoryCache>,
+    /// If set, the mailbox, merkle tree hook and IGP indexers of the chain
+    /// share their `eth_getLogs` queries
+    pub log_query_batcher: Option<LogQueryBatcher>,
+    /// If set, the storage locations announced by validators are cached
+    /// instead of being queried for every message's metadata
+    pub storage_locations_cache: Option<StorageLocationsCache>,
+    /// If set, the reads of the ISMs and mailbox made while building message
+    /// metadata are batched into Multicall3 calls
+    pub read_batcher: Option<ReadBatcher>,
 }
 
 /// Ethereum transaction overrides.
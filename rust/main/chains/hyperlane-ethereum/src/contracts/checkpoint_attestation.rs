@@ -0,0 +1,186 @@
+#![allow(clippy::enum_variant_names)]
+#![allow(missing_docs)]
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use ethers_contract::builders::ContractCall;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, CheckpointAttestation, ContractLocator, HyperlaneAbi,
+    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider,
+    SignedCheckpointWithMessageId, TxOutcome, H256, U256,
+};
+use tracing::instrument;
+
+use crate::{
+    interfaces::i_checkpoint_attestation::{
+        ICheckpointAttestation as EthereumCheckpointAttestationInternal, ICHECKPOINTATTESTATION_ABI,
+    },
+    tx::{fill_tx_gas_params, report_tx},
+    BuildableWithProvider, ConnectionConf, EthereumProvider, TransactionInclusionWatcher,
+};
+
+impl<M> std::fmt::Display for EthereumCheckpointAttestationInternal<M>
+where
+    M: Middleware,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+pub struct CheckpointAttestationBuilder {
+    pub inclusion_watcher: TransactionInclusionWatcher,
+}
+
+#[async_trait]
+impl BuildableWithProvider for CheckpointAttestationBuilder {
+    type Output = Box<dyn CheckpointAttestation>;
+    const NEEDS_SIGNER: bool = true;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumCheckpointAttestation::new(
+            Arc::new(provider),
+            conn,
+            locator,
+            self.inclusion_watcher.clone(),
+        ))
+    }
+}
+
+/// A reference to a checkpoint attestation contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumCheckpointAttestation<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumCheckpointAttestationInternal<M>>,
+    domain: HyperlaneDomain,
+    provider: Arc<M>,
+    conn: ConnectionConf,
+    inclusion_watcher: TransactionInclusionWatcher,
+}
+
+impl<M> EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to a checkpoint attestation contract at a specific
+    /// Ethereum address on some chain
+    pub fn new(
+        provider: Arc<M>,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+        inclusion_watcher: TransactionInclusionWatcher,
+    ) -> Self {
+        Self {
+            contract: Arc::new(EthereumCheckpointAttestationInternal::new(
+                locator.address,
+                provider.clone(),
+            )),
+            domain: locator.domain.clone(),
+            provider,
+            conn: conn.clone(),
+            inclusion_watcher,
+        }
+    }
+
+    /// Returns a ContractCall that submits the signed checkpoint, with gas
+    /// estimated
+    async fn attest_checkpoint_contract_call(
+        &self,
+        checkpoint: &SignedCheckpointWithMessageId,
+    ) -> ChainResult<ContractCall<M, ()>> {
+        let serialized_signature: [u8; 65] = checkpoint.signature.into();
+        let tx = self.contract.attest_checkpoint(
+            checkpoint.value.merkle_tree_hook_address.into(),
+            checkpoint.value.root.into(),
+            checkpoint.value.index,
+            checkpoint.value.message_id.into(),
+            serialized_signature.into(),
+        );
+        fill_tx_gas_params(
+            tx,
+            self.provider.clone(),
+            &self.conn.transaction_overrides,
+            self.conn.fee_history_cache.as_ref(),
+            &self.domain,
+        )
+        .await
+    }
+}
+
+impl<M> HyperlaneChain for EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> H256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> CheckpointAttestation for EthereumCheckpointAttestation<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn attest_checkpoint(
+        &self,
+        checkpoint: &SignedCheckpointWithMessageId,
+    ) -> ChainResult<TxOutcome> {
+        let contract_call = self.attest_checkpoint_contract_call(checkpoint).await?;
+        let receipt = report_tx(
+            contract_call,
+            self.provider.clone(),
+            &self.inclusion_watcher,
+        )
+        .await?;
+        Ok(receipt.into())
+    }
+
+    async fn attest_checkpoint_max_cost(
+        &self,
+        checkpoint: &SignedCheckpointWithMessageId,
+    ) -> ChainResult<U256> {
+        let contract_call = self.attest_checkpoint_contract_call(checkpoint).await?;
+        contract_call.tx.max_cost().map(Into::into).ok_or_else(|| {
+            ChainCommunicationError::from_other_str(
+                "Unable to get the max cost of the checkpoint attestation",
+            )
+        })
+    }
+}
+
+pub struct EthereumCheckpointAttestationAbi;
+
+impl HyperlaneAbi for EthereumCheckpointAttestationAbi {
+    const SELECTOR_SIZE_BYTES: usize = 4;
+
+    fn fn_map() -> HashMap<Vec<u8>, &'static str> {
+        crate::extract_fn_map(&ICHECKPOINTATTESTATION_ABI)
+    }
+}
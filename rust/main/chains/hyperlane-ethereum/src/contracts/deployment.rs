@@ -0,0 +1,348 @@
+//! Verification that the contracts configured for a chain are deployed at
+//! their addresses.
+//!
+//! Calls to an address without the expected contract only revert, which
+//! agents would otherwise retry forever. Agents verify the addresses at
+//! startup instead, by checking that they hold bytecode and probing view
+//! functions of the contract they are configured as.
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use ethers_contract::ContractError;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneDomain, H160, H256,
+};
+use tracing::{debug, instrument};
+
+use crate::{
+    interfaces::{
+        i_interchain_security_module::IInterchainSecurityModule, i_mailbox::IMailbox,
+        i_validator_announce::IValidatorAnnounce, interchain_gas_paymaster::InterchainGasPaymaster,
+        merkle_tree_hook::MerkleTreeHook,
+    },
+    BuildableWithProvider, ConnectionConf,
+};
+
+/// A contract an address is configured as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfiguredContract {
+    /// The mailbox. Verifying it also verifies its default ISM.
+    Mailbox,
+    /// The interchain gas paymaster
+    InterchainGasPaymaster,
+    /// The merkle tree hook
+    MerkleTreeHook,
+    /// The validator announce
+    ValidatorAnnounce,
+    /// An interchain security module
+    InterchainSecurityModule,
+}
+
+impl std::fmt::Display for ConfiguredContract {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Mailbox => "mailbox",
+            Self::InterchainGasPaymaster => "interchain gas paymaster",
+            Self::MerkleTreeHook => "merkle tree hook",
+            Self::ValidatorAnnounce => "validator announce",
+            Self::InterchainSecurityModule => "interchain security module",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Why an address doesn't hold the contract it's configured as
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DeploymentMismatch {
+    /// Nothing is deployed at the address
+    #[error("No {contract} is deployed at {address:?}, the address has no bytecode")]
+    NoBytecode {
+        /// The contract the address is configured as
+        contract: ConfiguredContract,
+        /// The address
+        address: H160,
+    },
+    /// The contract at the address doesn't implement a view function of the
+    /// contract it's configured as
+    #[error(
+        "The contract at {address:?} isn't a {contract}, calling `{function}` failed: {reason}"
+    )]
+    MissingFunction {
+        /// The contract the address is configured as
+        contract: ConfiguredContract,
+        /// The address
+        address: H160,
+        /// The view function that was probed
+        function: &'static str,
+        /// Why the call failed
+        reason: String,
+    },
+    /// The mailbox at the address is the one of another domain
+    #[error("The mailbox at {address:?} is the one of domain {actual}, not of domain {expected}")]
+    WrongDomain {
+        /// The address
+        address: H160,
+        /// The domain of the chain
+        expected: u32,
+        /// The local domain of the mailbox
+        actual: u32,
+    },
+}
+
+/// Verifies that contracts are deployed at the addresses configured for them
+#[async_trait]
+pub trait DeploymentVerifier: Send + Sync + Debug {
+    /// Whether `address` holds a `contract`, and why not if it doesn't.
+    /// Errors are failures to find out, e.g. of the RPC.
+    async fn verify(
+        &self,
+        contract: ConfiguredContract,
+        address: H256,
+    ) -> ChainResult<Option<DeploymentMismatch>>;
+}
+
+/// Builds a [`DeploymentVerifier`] for an Ethereum chain
+pub struct DeploymentVerifierBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for DeploymentVerifierBuilder {
+    type Output = Box<dyn DeploymentVerifier>;
+    const NEEDS_SIGNER: bool = false;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        _conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumDeploymentVerifier::new(
+            Arc::new(provider),
+            locator.domain.clone(),
+        ))
+    }
+}
+
+/// Verifies the deployment of contracts on an Ethereum chain
+#[derive(Debug)]
+pub struct EthereumDeploymentVerifier<M> {
+    provider: Arc<M>,
+    domain: HyperlaneDomain,
+}
+
+/// Why verifying a contract stopped
+enum VerificationFailure {
+    Mismatch(DeploymentMismatch),
+    Chain(ChainCommunicationError),
+}
+
+impl From<DeploymentMismatch> for VerificationFailure {
+    fn from(mismatch: DeploymentMismatch) -> Self {
+        Self::Mismatch(mismatch)
+    }
+}
+
+impl From<ChainCommunicationError> for VerificationFailure {
+    fn from(err: ChainCommunicationError) -> Self {
+        Self::Chain(err)
+    }
+}
+
+impl<M> EthereumDeploymentVerifier<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a verifier of the contracts deployed on the domain's chain
+    pub fn new(provider: Arc<M>, domain: HyperlaneDomain) -> Self {
+        Self { provider, domain }
+    }
+
+    async fn verify_contract(
+        &self,
+        contract: ConfiguredContract,
+        address: H160,
+    ) -> Result<(), VerificationFailure> {
+        self.verify_bytecode(contract, address).await?;
+        let provider = self.provider.clone();
+        match contract {
+            ConfiguredContract::Mailbox => {
+                let mailbox = IMailbox::new(address, provider);
+                let local_domain = probe(
+                    contract,
+                    address,
+                    "localDomain()",
+                    mailbox.local_domain().call().await,
+                )?;
+                if local_domain != self.domain.id() {
+                    return Err(DeploymentMismatch::WrongDomain {
+                        address,
+                        expected: self.domain.id(),
+                        actual: local_domain,
+                    }
+                    .into());
+                }
+                let default_ism = probe(
+                    contract,
+                    address,
+                    "defaultIsm()",
+                    mailbox.default_ism().call().await,
+                )?;
+                self.verify_ism(default_ism).await?;
+            }
+            ConfiguredContract::InterchainGasPaymaster => {
+                let igp = InterchainGasPaymaster::new(address, provider);
+                probe(
+                    contract,
+                    address,
+                    "beneficiary()",
+                    igp.beneficiary().call().await,
+                )?;
+            }
+            ConfiguredContract::MerkleTreeHook => {
+                let merkle_tree_hook = MerkleTreeHook::new(address, provider);
+                probe(
+                    contract,
+                    address,
+                    "count()",
+                    merkle_tree_hook.count().call().await,
+                )?;
+            }
+            ConfiguredContract::ValidatorAnnounce => {
+                let validator_announce = IValidatorAnnounce::new(address, provider);
+                probe(
+                    contract,
+                    address,
+                    "getAnnouncedValidators()",
+                    validator_announce.get_announced_validators().call().await,
+                )?;
+            }
+            ConfiguredContract::InterchainSecurityModule => self.probe_ism(address).await?,
+        }
+        debug!(%contract, ?address, "Verified contract deployment");
+        Ok(())
+    }
+
+    async fn verify_bytecode(
+        &self,
+        contract: ConfiguredContract,
+        address: H160,
+    ) -> Result<(), VerificationFailure> {
+        let code = self
+            .provider
+            .get_code(address, None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        if code.is_empty() {
+            return Err(DeploymentMismatch::NoBytecode { contract, address }.into());
+        }
+        Ok(())
+    }
+
+    /// Verifies an ISM the mailbox points to
+    async fn verify_ism(&self, address: H160) -> Result<(), VerificationFailure> {
+        self.verify_bytecode(ConfiguredContract::InterchainSecurityModule, address)
+            .await?;
+        self.probe_ism(address).await
+    }
+
+    async fn probe_ism(&self, address: H160) -> Result<(), VerificationFailure> {
+        let ism = IInterchainSecurityModule::new(address, self.provider.clone());
+        probe(
+            ConfiguredContract::InterchainSecurityModule,
+            address,
+            "moduleType()",
+            ism.module_type().call().await,
+        )?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<M> DeploymentVerifier for EthereumDeploymentVerifier<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(skip(self), fields(domain = %self.domain))]
+    async fn verify(
+        &self,
+        contract: ConfiguredContract,
+        address: H256,
+    ) -> ChainResult<Option<DeploymentMismatch>> {
+        match self.verify_contract(contract, address.into()).await {
+            Ok(()) => Ok(None),
+            Err(VerificationFailure::Mismatch(mismatch)) => Ok(Some(mismatch)),
+            Err(VerificationFailure::Chain(err)) => Err(err),
+        }
+    }
+}
+
+/// The result of a view call, or the mismatch its failure reveals
+fn probe<T, M: Middleware>(
+    contract: ConfiguredContract,
+    address: H160,
+    function: &'static str,
+    result: Result<T, ContractError<M>>,
+) -> Result<T, VerificationFailure> {
+    result.map_err(|err| {
+        if is_missing_function(&err) {
+            DeploymentMismatch::MissingFunction {
+                contract,
+                address,
+                function,
+                reason: err.to_string(),
+            }
+            .into()
+        } else {
+            ChainCommunicationError::from(err).into()
+        }
+    })
+}
+
+/// Whether a call failed because the contract doesn't implement the function,
+/// i.e. it reverted or returned something else than the function's outputs,
+/// rather than because of the RPC
+fn is_missing_function<M: Middleware>(err: &ContractError<M>) -> bool {
+    match err {
+        ContractError::DecodingError(_)
+        | ContractError::AbiError(_)
+        | ContractError::DetokenizationError(_) => true,
+        err => is_revert(&err.to_string()),
+    }
+}
+
+fn is_revert(err: &str) -> bool {
+    err.to_lowercase().contains("revert")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reverts_are_told_apart_from_rpc_errors() {
+        assert!(is_revert(
+            "(code: 3, message: execution reverted, data: None)"
+        ));
+        assert!(is_revert("Execution Reverted"));
+        assert!(!is_revert(
+            "error sending request for url: connection refused"
+        ));
+        assert!(!is_revert("(code: -32005, message: rate limit exceeded)"));
+    }
+
+    #[test]
+    fn test_mismatches_are_descriptive() {
+        let address = H160::repeat_byte(0xab);
+        let mismatch = DeploymentMismatch::NoBytecode {
+            contract: ConfiguredContract::InterchainGasPaymaster,
+            address,
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            format!(
+                "No interchain gas paymaster is deployed at {address:?}, the address has no bytecode"
+            )
+        );
+    }
+}
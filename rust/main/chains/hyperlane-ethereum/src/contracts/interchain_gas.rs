@@ -9,9 +9,10 @@ use async_trait::async_trait;
 use ethers::prelude::Middleware;
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
 use hyperlane_core::{
-    ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster, InterchainGasPayment, LogMeta,
-    SequenceAwareIndexer, H160, H256, H512,
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer,
+    InterchainGasPaymaster, InterchainGasPayment, LogMeta, RemoteGasData, SequenceAwareIndexer,
+    TxOutcome, H160, H256, H512, U256,
 };
 use tracing::instrument;
 
@@ -20,7 +21,12 @@ use crate::interfaces::i_interchain_gas_paymaster::{
     GasPaymentFilter, IInterchainGasPaymaster as EthereumInterchainGasPaymasterInternal,
     IINTERCHAINGASPAYMASTER_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod};
+use crate::interfaces::interchain_gas_paymaster::InterchainGasPaymaster as EthereumInterchainGasPaymasterClaim;
+use crate::tx::{fill_tx_gas_params, report_tx};
+use crate::{
+    BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod, LogQueryBatcher,
+    TransactionInclusionWatcher,
+};
 
 impl<M> Display for EthereumInterchainGasPaymasterInternal<M>
 where
@@ -44,13 +50,14 @@ impl BuildableWithProvider for InterchainGasPaymasterIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumInterchainGasPaymasterIndexer::new(
             Arc::new(provider),
             locator,
             self.reorg_period,
+            conn.log_query_batcher.clone(),
         ))
     }
 }
@@ -64,6 +71,7 @@ where
     contract: Arc<EthereumInterchainGasPaymasterInternal<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    log_query_batcher: Option<LogQueryBatcher>,
 }
 
 impl<M> EthereumInterchainGasPaymasterIndexer<M>
@@ -75,14 +83,20 @@ where
         provider: Arc<M>,
         locator: &ContractLocator,
         reorg_period: EthereumReorgPeriod,
+        log_query_batcher: Option<LogQueryBatcher>,
     ) -> Self {
+        let contract = Arc::new(EthereumInterchainGasPaymasterInternal::new(
+            locator.address,
+            provider.clone(),
+        ));
+        if let Some(batcher) = &log_query_batcher {
+            batcher.register::<GasPaymentFilter>(contract.address());
+        }
         Self {
-            contract: Arc::new(EthereumInterchainGasPaymasterInternal::new(
-                locator.address,
-                provider.clone(),
-            )),
+            contract,
             provider,
             reorg_period,
+            log_query_batcher,
         }
     }
 }
@@ -99,13 +113,23 @@ where
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
-        let events = self
-            .contract
-            .gas_payment_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
-            .await?;
+        let events: Vec<(GasPaymentFilter, LogMeta)> = match &self.log_query_batcher {
+            Some(batcher) => {
+                batcher
+                    .query(&*self.provider, self.contract.address(), range)
+                    .await?
+            }
+            None => self
+                .contract
+                .gas_payment_filter()
+                .from_block(*range.start())
+                .to_block(*range.end())
+                .query_with_meta()
+                .await?
+                .into_iter()
+                .map(|(log, log_meta)| (log, log_meta.into()))
+                .collect(),
+        };
 
         Ok(events
             .into_iter()
@@ -117,7 +141,7 @@ where
                         payment: log.payment.into(),
                         gas_amount: log.gas_amount.into(),
                     }),
-                    log_meta.into(),
+                    log_meta,
                 )
             })
             .collect())
@@ -177,22 +201,27 @@ where
     }
 }
 
-pub struct InterchainGasPaymasterBuilder {}
+pub struct InterchainGasPaymasterBuilder {
+    pub inclusion_watcher: TransactionInclusionWatcher,
+}
 
 #[async_trait]
 impl BuildableWithProvider for InterchainGasPaymasterBuilder {
     type Output = Box<dyn InterchainGasPaymaster>;
-    const NEEDS_SIGNER: bool = false;
+    // Claiming the payments sends a transaction
+    const NEEDS_SIGNER: bool = true;
 
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumInterchainGasPaymaster::new(
             Arc::new(provider),
+            conn,
             locator,
+            self.inclusion_watcher.clone(),
         ))
     }
 }
@@ -204,7 +233,12 @@ where
     M: Middleware,
 {
     contract: Arc<EthereumInterchainGasPaymasterInternal<M>>,
+    /// The functions of the IGP implementation, rather than of its interface
+    claim_contract: Arc<EthereumInterchainGasPaymasterClaim<M>>,
     domain: HyperlaneDomain,
+    provider: Arc<M>,
+    conn: ConnectionConf,
+    inclusion_watcher: TransactionInclusionWatcher,
 }
 
 impl<M> EthereumInterchainGasPaymaster<M>
@@ -213,13 +247,25 @@ where
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
-    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+        inclusion_watcher: TransactionInclusionWatcher,
+    ) -> Self {
         Self {
             contract: Arc::new(EthereumInterchainGasPaymasterInternal::new(
                 locator.address,
-                provider,
+                provider.clone(),
+            )),
+            claim_contract: Arc::new(EthereumInterchainGasPaymasterClaim::new(
+                locator.address,
+                provider.clone(),
             )),
             domain: locator.domain.clone(),
+            provider,
+            conn: conn.clone(),
+            inclusion_watcher,
         }
     }
 }
@@ -250,7 +296,68 @@ where
 }
 
 #[async_trait]
-impl<M> InterchainGasPaymaster for EthereumInterchainGasPaymaster<M> where M: Middleware + 'static {}
+impl<M> InterchainGasPaymaster for EthereumInterchainGasPaymaster<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn claimable_balance(&self) -> ChainResult<U256> {
+        // The IGP holds nothing but the payments
+        let balance = self
+            .provider
+            .get_balance(self.contract.address(), None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(balance.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn claim(&self) -> ChainResult<TxOutcome> {
+        // `claim()` is permissionless, the payments always go to the beneficiary
+        let contract_call = fill_tx_gas_params(
+            self.claim_contract.claim(),
+            self.provider.clone(),
+            &self.conn.transaction_overrides,
+            self.conn.fee_history_cache.as_ref(),
+            &self.domain,
+        )
+        .await?;
+        let receipt = report_tx(
+            contract_call,
+            self.provider.clone(),
+            &self.inclusion_watcher,
+        )
+        .await?;
+        Ok(receipt.into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn remote_gas_data(&self, destination: u32) -> ChainResult<Option<RemoteGasData>> {
+        let (gas_oracle, _gas_overhead) = self
+            .claim_contract
+            .destination_gas_configs(destination)
+            .call()
+            .await?;
+        if gas_oracle.is_zero() {
+            return Ok(None);
+        }
+        // Read through the IGP rather than the oracle, to get the values
+        // payments are actually quoted with
+        let (token_exchange_rate, gas_price) = self
+            .claim_contract
+            .get_exchange_rate_and_gas_price(destination)
+            .call()
+            .await?;
+        Ok(Some(RemoteGasData {
+            gas_oracle: gas_oracle.into(),
+            token_exchange_rate: token_exchange_rate.into(),
+            gas_price: gas_price.into(),
+        }))
+    }
+}
 
 pub struct EthereumInterchainGasPaymasterAbi;
 
@@ -3,6 +3,7 @@
 
 use std::collections::HashMap;
 use std::ops::{Mul, RangeInclusive};
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -19,10 +20,11 @@ use itertools::Itertools;
 use tracing::instrument;
 
 use hyperlane_core::{
-    utils::bytes_to_hex, BatchItem, ChainCommunicationError, ChainResult, ContractLocator,
-    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
-    RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H160, H256, U256,
+    utils::{bytes_to_hex, to_atto},
+    BatchItem, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProtocolError,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, PreTransaction, RawHyperlaneMessage,
+    SequenceAwareIndexer, TxCostEstimate, TxOutcome, H160, H256, U256,
 };
 
 use crate::error::HyperlaneEthereumError;
@@ -31,10 +33,12 @@ use crate::interfaces::i_mailbox::{
     IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI,
 };
 use crate::interfaces::mailbox::DispatchFilter;
+use crate::interfaces::optimism_gas_price_oracle::OptimismGasPriceOracle;
+use crate::read_batcher::batched_call;
 use crate::tx::{call_with_reorg_period, fill_tx_gas_params, report_tx};
 use crate::{
-    BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod,
-    TransactionOverrides,
+    BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod, FeeHistoryCache,
+    LogQueryBatcher, TransactionInclusionWatcher, TransactionOverrides,
 };
 
 use super::multicall::{self, build_multicall};
@@ -61,13 +65,14 @@ impl BuildableWithProvider for SequenceIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumMailboxIndexer::new(
             Arc::new(provider),
             locator,
             self.reorg_period,
+            conn.log_query_batcher.clone(),
         ))
     }
 }
@@ -87,10 +92,12 @@ impl BuildableWithProvider for DeliveryIndexerBuilder {
         _conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
+        // Deliveries aren't part of the batched queries
         Box::new(EthereumMailboxIndexer::new(
             Arc::new(provider),
             locator,
             self.reorg_period,
+            None,
         ))
     }
 }
@@ -104,6 +111,9 @@ where
     contract: Arc<EthereumMailboxInternal<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    /// If set, dispatches are fetched in queries shared with the indexers of
+    /// the chain's other contracts
+    log_query_batcher: Option<LogQueryBatcher>,
 }
 
 impl<M> EthereumMailboxIndexer<M>
@@ -115,15 +125,20 @@ where
         provider: Arc<M>,
         locator: &ContractLocator,
         reorg_period: EthereumReorgPeriod,
+        log_query_batcher: Option<LogQueryBatcher>,
     ) -> Self {
         let contract = Arc::new(EthereumMailboxInternal::new(
             locator.address,
             provider.clone(),
         ));
+        if let Some(batcher) = &log_query_batcher {
+            batcher.register::<DispatchFilter>(contract.address());
+        }
         Self {
             contract,
             provider,
             reorg_period,
+            log_query_batcher,
         }
     }
 
@@ -149,20 +164,26 @@ where
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
-        let mut events: Vec<(Indexed<HyperlaneMessage>, LogMeta)> = self
-            .contract
-            .dispatch_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
-            .await?
+        let dispatches: Vec<(DispatchFilter, LogMeta)> = match &self.log_query_batcher {
+            Some(batcher) => {
+                batcher
+                    .query(&*self.provider, self.contract.address(), range)
+                    .await?
+            }
+            None => self
+                .contract
+                .dispatch_filter()
+                .from_block(*range.start())
+                .to_block(*range.end())
+                .query_with_meta()
+                .await?
+                .into_iter()
+                .map(|(event, meta)| (event, meta.into()))
+                .collect(),
+        };
+        let mut events: Vec<(Indexed<HyperlaneMessage>, LogMeta)> = dispatches
             .into_iter()
-            .map(|(event, meta)| {
-                (
-                    HyperlaneMessage::from(event.message.to_vec()).into(),
-                    meta.into(),
-                )
-            })
+            .map(|(event, meta)| (HyperlaneMessage::from(event.message.to_vec()).into(), meta))
             .collect();
 
         events.sort_by(|a, b| a.0.inner().nonce.cmp(&b.0.inner().nonce));
@@ -203,8 +224,14 @@ where
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
-        let sequence = self.contract.nonce().block(u64::from(tip)).call().await?;
-        Ok((Some(sequence), tip))
+        let sequence =
+            SequenceAwareIndexer::<HyperlaneMessage>::sequence_count_at_block(self, tip).await?;
+        Ok((sequence, tip))
+    }
+
+    async fn sequence_count_at_block(&self, block: u32) -> ChainResult<Option<u32>> {
+        let sequence = self.contract.nonce().block(u64::from(block)).call().await?;
+        Ok(Some(sequence))
     }
 }
 
@@ -250,7 +277,9 @@ where
     }
 }
 
-pub struct MailboxBuilder {}
+pub struct MailboxBuilder {
+    pub inclusion_watcher: TransactionInclusionWatcher,
+}
 
 #[async_trait]
 impl BuildableWithProvider for MailboxBuilder {
@@ -263,7 +292,12 @@ impl BuildableWithProvider for MailboxBuilder {
         conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMailbox::new(Arc::new(provider), conn, locator))
+        Box::new(EthereumMailbox::new(
+            Arc::new(provider),
+            conn,
+            locator,
+            self.inclusion_watcher.clone(),
+        ))
     }
 }
 
@@ -277,7 +311,9 @@ where
     domain: HyperlaneDomain,
     provider: Arc<M>,
     arbitrum_node_interface: Option<Arc<ArbitrumNodeInterface<M>>>,
+    optimism_gas_price_oracle: Option<Arc<OptimismGasPriceOracle<M>>>,
     conn: ConnectionConf,
+    inclusion_watcher: TransactionInclusionWatcher,
 }
 
 impl<M> EthereumMailbox<M>
@@ -286,7 +322,12 @@ where
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
-    pub fn new(provider: Arc<M>, conn: &ConnectionConf, locator: &ContractLocator) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+        inclusion_watcher: TransactionInclusionWatcher,
+    ) -> Self {
         // Arbitrum Nitro based chains are a special case for transaction cost estimation.
         // The gas amount that eth_estimateGas returns considers both L1 and L2 gas costs.
         // We use the NodeInterface, found at address(0xC8), to isolate the L2 gas costs.
@@ -298,6 +339,16 @@ where
             ))
         });
 
+        // OP Stack chains charge a fee for posting the transaction data to L1 on
+        // top of the L2 gas. It's quoted by the GasPriceOracle predeploy.
+        // See https://docs.optimism.io/stack/transactions/fees#l1-data-fee
+        let optimism_gas_price_oracle = locator.domain.is_op_stack().then(|| {
+            Arc::new(OptimismGasPriceOracle::new(
+                H160::from_str("0x420000000000000000000000000000000000000F").unwrap(),
+                provider.clone(),
+            ))
+        });
+
         Self {
             contract: Arc::new(EthereumMailboxInternal::new(
                 locator.address,
@@ -306,10 +357,18 @@ where
             domain: locator.domain.clone(),
             provider,
             arbitrum_node_interface,
+            optimism_gas_price_oracle,
             conn: conn.clone(),
+            inclusion_watcher,
         }
     }
 
+    /// Makes a read of the mailbox, batched with the chain's other reads if
+    /// they're batched
+    async fn batched_call<D: Detokenize>(&self, call: ContractCall<M, D>) -> ChainResult<D> {
+        batched_call(self.conn.read_batcher.as_ref(), self.provider.clone(), call).await
+    }
+
     /// Returns a ContractCall that processes the provided message.
     async fn process_contract_call(
         &self,
@@ -327,6 +386,18 @@ where
         self.add_gas_overrides(tx).await
     }
 
+    /// Returns a ContractCall that calls `to` with `data`. The contract
+    /// bindings only build calls to the mailbox, so a mailbox call is
+    /// retargeted.
+    async fn raw_contract_call(&self, to: H256, data: &[u8]) -> ChainResult<ContractCall<M, ()>> {
+        let mut tx = self
+            .contract
+            .process(Default::default(), Default::default());
+        tx.tx.set_to(ethers::types::Address::from(to));
+        tx.tx.set_data(data.to_vec().into());
+        self.add_gas_overrides(tx).await
+    }
+
     async fn add_gas_overrides<D: Detokenize>(
         &self,
         tx: ContractCall<M, D>,
@@ -335,6 +406,7 @@ where
             tx,
             self.provider.clone(),
             &self.conn.transaction_overrides.clone(),
+            self.conn.fee_history_cache.as_ref(),
             &self.domain,
         )
         .await
@@ -384,11 +456,27 @@ where
             call,
             provider: self.provider.clone(),
             transaction_overrides: self.conn.transaction_overrides.clone(),
+            fee_history_cache: self.conn.fee_history_cache.clone(),
             domain: self.domain.clone(),
+            inclusion_watcher: self.inclusion_watcher.clone(),
+            native_token_decimals: self.conn.native_token.decimals,
         }
     }
 }
 
+/// Converts an amount of the native token to 18 decimals, so fees are
+/// comparable across chains whose native tokens have other decimals
+fn fee_to_atto(amount: U256, decimals: u32) -> ChainResult<U256> {
+    to_atto(amount, decimals).ok_or(ChainCommunicationError::CustomError(
+        "Overflow in calculating fees".to_owned(),
+    ))
+}
+
+fn tx_outcome_to_atto(mut outcome: TxOutcome, decimals: u32) -> ChainResult<TxOutcome> {
+    outcome.gas_price = fee_to_atto(outcome.gas_price.try_into()?, decimals)?.try_into()?;
+    Ok(outcome)
+}
+
 #[derive(new)]
 pub struct BatchSimulation<M> {
     pub call: Option<SubmittableBatch<M>>,
@@ -421,20 +509,29 @@ pub struct SubmittableBatch<M> {
     pub call: ContractCall<M, Vec<MulticallResult>>,
     provider: Arc<M>,
     transaction_overrides: TransactionOverrides,
+    fee_history_cache: Option<FeeHistoryCache>,
     domain: HyperlaneDomain,
+    inclusion_watcher: TransactionInclusionWatcher,
+    native_token_decimals: u32,
 }
 
 impl<M: Middleware + 'static> SubmittableBatch<M> {
     pub async fn submit(self) -> ChainResult<TxOutcome> {
         let call_with_gas_overrides = fill_tx_gas_params(
             self.call,
-            self.provider,
+            self.provider.clone(),
             &self.transaction_overrides,
+            self.fee_history_cache.as_ref(),
             &self.domain,
         )
         .await?;
-        let outcome = report_tx(call_with_gas_overrides).await?;
-        Ok(outcome.into())
+        let outcome = report_tx(
+            call_with_gas_overrides,
+            self.provider,
+            &self.inclusion_watcher,
+        )
+        .await?;
+        tx_outcome_to_atto(outcome.into(), self.native_token_decimals)
     }
 }
 
@@ -478,20 +575,23 @@ where
 
     #[instrument(skip(self))]
     async fn delivered(&self, id: H256) -> ChainResult<bool> {
-        Ok(self.contract.delivered(id.into()).call().await?)
+        self.batched_call(self.contract.delivered(id.into())).await
+    }
+
+    #[instrument(skip(self))]
+    async fn local_domain(&self) -> ChainResult<u32> {
+        Ok(self.contract.local_domain().call().await?)
     }
 
     #[instrument(skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
-        Ok(self.contract.default_ism().call().await?.into())
+        Ok(self.batched_call(self.contract.default_ism()).await?.into())
     }
 
     #[instrument(skip(self))]
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
         Ok(self
-            .contract
-            .recipient_ism(recipient.into())
-            .call()
+            .batched_call(self.contract.recipient_ism(recipient.into()))
             .await?
             .into())
     }
@@ -506,8 +606,13 @@ where
         let contract_call = self
             .process_contract_call(message, metadata, tx_gas_limit)
             .await?;
-        let receipt = report_tx(contract_call).await?;
-        Ok(receipt.into())
+        let receipt = report_tx(
+            contract_call,
+            self.provider.clone(),
+            &self.inclusion_watcher,
+        )
+        .await?;
+        tx_outcome_to_atto(receipt.into(), self.conn.native_token.decimals)
     }
 
     #[instrument(skip(self, ops), fields(size=%ops.len()))]
@@ -576,6 +681,28 @@ where
             None
         };
 
+        let (l1_data_fee, l1_blob_base_fee) = match &self.optimism_gas_price_oracle {
+            Some(gas_price_oracle) => {
+                let fee = gas_price_oracle
+                    .get_l1_fee(contract_call.tx.rlp())
+                    .call()
+                    .await?;
+                // Since Ecotone, the data is posted in blobs and the L1 data fee
+                // is priced from the L1 blob base fee. Oracles predating Ecotone
+                // don't have `isEcotone`.
+                let blob_base_fee = if gas_price_oracle.is_ecotone().call().await.unwrap_or(false) {
+                    Some(gas_price_oracle.blob_base_fee().call().await?.into())
+                } else {
+                    None
+                };
+                (
+                    Some(fee_to_atto(fee.into(), self.conn.native_token.decimals)?),
+                    blob_base_fee,
+                )
+            }
+            None => (None, None),
+        };
+
         let gas_price: U256 = self
             .provider
             .get_gas_price()
@@ -585,8 +712,10 @@ where
 
         Ok(TxCostEstimate {
             gas_limit: gas_limit.into(),
-            gas_price: gas_price.try_into()?,
+            gas_price: fee_to_atto(gas_price, self.conn.native_token.decimals)?.try_into()?,
             l2_gas_limit: l2_gas_limit.map(|v| v.into()),
+            l1_data_fee,
+            l1_blob_base_fee,
         })
     }
 
@@ -598,6 +727,41 @@ where
 
         AbiEncode::encode(process_call)
     }
+
+    async fn pre_transaction_required(
+        &self,
+        _message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<bool> {
+        match pre_transaction {
+            // Whether a call still has to be made can't be known in general
+            PreTransaction::Call { .. } => Ok(true),
+            _ => Err(ChainCommunicationError::PreTransactionUnsupported(
+                pre_transaction.kind(),
+            )),
+        }
+    }
+
+    #[instrument(skip(self), fields(msg=%message))]
+    async fn submit_pre_transaction(
+        &self,
+        message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<TxOutcome> {
+        let PreTransaction::Call { to, data } = pre_transaction else {
+            return Err(ChainCommunicationError::PreTransactionUnsupported(
+                pre_transaction.kind(),
+            ));
+        };
+        let contract_call = self.raw_contract_call(*to, data).await?;
+        let receipt = report_tx(
+            contract_call,
+            self.provider.clone(),
+            &self.inclusion_watcher,
+        )
+        .await?;
+        tx_outcome_to_atto(receipt.into(), self.conn.native_token.decimals)
+    }
 }
 
 pub struct EthereumMailboxAbi;
@@ -615,22 +779,26 @@ mod test {
     use std::{str::FromStr, sync::Arc};
 
     use ethers::{
+        abi::Token,
         providers::{MockProvider, Provider},
-        types::{Block, Transaction, U256 as EthersU256},
+        types::{Block, Bytes, Transaction, U256 as EthersU256},
     };
 
     use hyperlane_core::{
         ContractLocator, HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain, Mailbox,
-        TxCostEstimate, H160, H256, U256,
+        NativeToken, SequenceAwareIndexer, TxCostEstimate, H160, H256, U256,
     };
 
     use crate::{
-        contracts::EthereumMailbox, tx::apply_gas_estimate_buffer, ConnectionConf,
-        RpcConnectionConf,
+        contracts::{EthereumMailbox, EthereumMailboxIndexer},
+        tx::apply_gas_estimate_buffer,
+        tx_inclusion::test::dummy_watcher,
+        ConnectionConf, EthereumReorgPeriod, RpcConnectionConf,
     };
 
     fn get_test_mailbox(
         domain: HyperlaneDomain,
+        native_token_decimals: u32,
     ) -> (
         EthereumMailbox<Provider<Arc<MockProvider>>>,
         Arc<MockProvider>,
@@ -643,6 +811,15 @@ mod test {
             },
             transaction_overrides: Default::default(),
             operation_batch: Default::default(),
+            native_token: NativeToken {
+                decimals: native_token_decimals,
+                ..Default::default()
+            },
+            rpc_rate_limiter: None,
+            fee_history_cache: None,
+            log_query_batcher: None,
+            storage_locations_cache: None,
+            read_batcher: None,
         };
 
         let mailbox = EthereumMailbox::new(
@@ -653,15 +830,43 @@ mod test {
                 // Address doesn't matter because we're using a MockProvider
                 address: H256::default(),
             },
+            dummy_watcher(),
         );
         (mailbox, mock_provider)
     }
 
+    #[tokio::test]
+    async fn test_sequence_count_at_block() {
+        let mock_provider = Arc::new(MockProvider::new());
+        let indexer = EthereumMailboxIndexer::new(
+            Arc::new(Provider::new(mock_provider.clone())),
+            &ContractLocator {
+                domain: &HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum),
+                address: H256::default(),
+            },
+            EthereumReorgPeriod::Blocks(1),
+            None,
+        );
+
+        // eth_call of `nonce()` at the block
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Uint(
+                EthersU256::from(5),
+            )])))
+            .unwrap();
+
+        let sequence =
+            SequenceAwareIndexer::<HyperlaneMessage>::sequence_count_at_block(&indexer, 100)
+                .await
+                .unwrap();
+        assert_eq!(sequence, Some(5));
+    }
+
     #[tokio::test]
     async fn test_process_estimate_costs_sets_l2_gas_limit_for_arbitrum() {
         let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::PlumeTestnet);
         // An Arbitrum Nitro chain
-        let (mailbox, mock_provider) = get_test_mailbox(domain.clone());
+        let (mailbox, mock_provider) = get_test_mailbox(domain.clone(), 18);
 
         let message = HyperlaneMessage::default();
         let metadata: Vec<u8> = vec![];
@@ -714,6 +919,8 @@ mod test {
                 gas_limit: estimated_gas_limit,
                 gas_price: gas_price.try_into().unwrap(),
                 l2_gas_limit: Some(l2_gas_limit),
+                l1_data_fee: None,
+                l1_blob_base_fee: None,
             },
         );
     }
@@ -721,7 +928,7 @@ mod test {
     #[tokio::test]
     async fn test_tx_gas_limit_caps_at_block_gas_limit() {
         let (mailbox, mock_provider) =
-            get_test_mailbox(HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum));
+            get_test_mailbox(HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum), 18);
 
         let message = HyperlaneMessage::default();
         let metadata: Vec<u8> = vec![];
@@ -762,7 +969,113 @@ mod test {
                 gas_limit: latest_block_gas_limit,
                 gas_price: gas_price.try_into().unwrap(),
                 l2_gas_limit: None,
+                l1_data_fee: None,
+                l1_blob_base_fee: None,
+            },
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_estimate_costs_sets_l1_data_fee_for_op_stack() {
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Optimism);
+        let (mailbox, mock_provider) = get_test_mailbox(domain.clone(), 18);
+
+        let message = HyperlaneMessage::default();
+        let metadata: Vec<u8> = vec![];
+
+        assert!(mailbox.optimism_gas_price_oracle.is_some());
+
+        // The MockProvider responses we push are processed in LIFO
+        // order, so we start with the final RPCs and work toward the first
+        // RPCs
+
+        // RPC 6: eth_gasPrice by process_estimate_costs
+        let gas_price: U256 =
+            EthersU256::from(ethers::utils::parse_units("1", "gwei").unwrap()).into();
+        mock_provider.push(gas_price).unwrap();
+
+        // RPC 5: eth_call to the GasPriceOracle's blobBaseFee function by process_estimate_costs
+        let blob_base_fee = U256::from(3_000_000_000u64);
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Uint(
+                blob_base_fee.into(),
+            )])))
+            .unwrap();
+
+        // RPC 4: eth_call to the GasPriceOracle's isEcotone function by process_estimate_costs
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Bool(true)])))
+            .unwrap();
+
+        // RPC 3: eth_call to the GasPriceOracle's getL1Fee function by process_estimate_costs
+        let l1_data_fee = U256::from(50_000_000_000_000u64);
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Uint(
+                l1_data_fee.into(),
+            )])))
+            .unwrap();
+
+        let latest_block: Block<Transaction> = Block {
+            gas_limit: ethers::types::U256::MAX,
+            ..Block::<Transaction>::default()
+        };
+        // RPC 2: eth_getBlockByNumber from the fill_tx_gas_params call in process_contract_call
+        // to get the latest block gas limit and for eip 1559 fee estimation
+        mock_provider.push(latest_block).unwrap();
+
+        // RPC 1: eth_estimateGas from the estimate_gas call in process_contract_call
+        let gas_limit = U256::from(1000000u32);
+        mock_provider.push(gas_limit).unwrap();
+
+        let tx_cost_estimate = mailbox
+            .process_estimate_costs(&message, &metadata)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            tx_cost_estimate,
+            TxCostEstimate {
+                gas_limit: apply_gas_estimate_buffer(gas_limit, &domain).unwrap(),
+                gas_price: gas_price.try_into().unwrap(),
+                l2_gas_limit: None,
+                l1_data_fee: Some(l1_data_fee),
+                l1_blob_base_fee: Some(blob_base_fee),
             },
         );
     }
+
+    #[tokio::test]
+    async fn test_process_estimate_costs_converts_gas_price_to_18_decimals() {
+        // A chain whose native token has 6 decimals
+        let (mailbox, mock_provider) =
+            get_test_mailbox(HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum), 6);
+
+        let message = HyperlaneMessage::default();
+        let metadata: Vec<u8> = vec![];
+
+        // RPC 3: eth_gasPrice by process_estimate_costs
+        let gas_price = U256::from(15u32);
+        mock_provider.push(gas_price).unwrap();
+
+        let latest_block: Block<Transaction> = Block {
+            gas_limit: ethers::types::U256::MAX,
+            ..Block::<Transaction>::default()
+        };
+        // RPC 2: eth_getBlockByNumber from the fill_tx_gas_params call in process_contract_call
+        mock_provider.push(latest_block).unwrap();
+
+        // RPC 1: eth_estimateGas from the estimate_gas call in process_contract_call
+        mock_provider.push(U256::from(1000000u32)).unwrap();
+
+        let tx_cost_estimate = mailbox
+            .process_estimate_costs(&message, &metadata)
+            .await
+            .unwrap();
+
+        let expected_gas_price = U256::from(15u32) * U256::exp10(12);
+        assert_eq!(
+            tx_cost_estimate.gas_price,
+            expected_gas_price.try_into().unwrap()
+        );
+    }
 }
@@ -18,7 +18,9 @@ use crate::interfaces::merkle_tree_hook::{
     InsertedIntoTreeFilter, MerkleTreeHook as MerkleTreeHookContract, Tree,
 };
 use crate::tx::call_with_reorg_period;
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod};
+use crate::{
+    BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod, LogQueryBatcher,
+};
 
 use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number};
 
@@ -68,13 +70,14 @@ impl BuildableWithProvider for MerkleTreeHookIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumMerkleTreeHookIndexer::new(
             Arc::new(provider),
             locator,
             self.reorg_period,
+            conn.log_query_batcher.clone(),
         ))
     }
 }
@@ -88,6 +91,7 @@ where
     contract: Arc<MerkleTreeHookContract<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    log_query_batcher: Option<LogQueryBatcher>,
 }
 
 impl<M> EthereumMerkleTreeHookIndexer<M>
@@ -99,14 +103,20 @@ where
         provider: Arc<M>,
         locator: &ContractLocator,
         reorg_period: EthereumReorgPeriod,
+        log_query_batcher: Option<LogQueryBatcher>,
     ) -> Self {
+        let contract = Arc::new(MerkleTreeHookContract::new(
+            locator.address,
+            provider.clone(),
+        ));
+        if let Some(batcher) = &log_query_batcher {
+            batcher.register::<InsertedIntoTreeFilter>(contract.address());
+        }
         Self {
-            contract: Arc::new(MerkleTreeHookContract::new(
-                locator.address,
-                provider.clone(),
-            )),
+            contract,
             provider,
             reorg_period,
+            log_query_batcher,
         }
     }
 }
@@ -123,20 +133,30 @@ where
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
-        let events = self
-            .contract
-            .inserted_into_tree_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
-            .await?;
+        let events: Vec<(InsertedIntoTreeFilter, LogMeta)> = match &self.log_query_batcher {
+            Some(batcher) => {
+                batcher
+                    .query(&*self.provider, self.contract.address(), range)
+                    .await?
+            }
+            None => self
+                .contract
+                .inserted_into_tree_filter()
+                .from_block(*range.start())
+                .to_block(*range.end())
+                .query_with_meta()
+                .await?
+                .into_iter()
+                .map(|(log, log_meta)| (log, log_meta.into()))
+                .collect(),
+        };
 
         let logs = events
             .into_iter()
             .map(|(log, log_meta)| {
                 (
                     MerkleTreeInsertion::new(log.index, H256::from(log.message_id)).into(),
-                    log_meta.into(),
+                    log_meta,
                 )
             })
             .collect();
@@ -185,8 +205,13 @@ where
     // `SequenceAwareIndexer` and `Indexer`.
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = self.get_finalized_block_number().await?;
-        let sequence = self.contract.count().block(u64::from(tip)).call().await?;
-        Ok((Some(sequence), tip))
+        let sequence = self.sequence_count_at_block(tip).await?;
+        Ok((sequence, tip))
+    }
+
+    async fn sequence_count_at_block(&self, block: u32) -> ChainResult<Option<u32>> {
+        let sequence = self.contract.count().block(u64::from(block)).call().await?;
+        Ok(Some(sequence))
     }
 }
 
@@ -1,7 +1,13 @@
-pub use {interchain_gas::*, mailbox::*, merkle_tree_hook::*, validator_announce::*};
+pub use {
+    checkpoint_attestation::*, deployment::*, interchain_gas::*, mailbox::*, merkle_tree_hook::*,
+    validator_announce::*,
+};
 
+pub(crate) use multicall::MULTICALL3_ADDRESS;
 pub(crate) use utils::get_finalized_block_number;
 
+mod checkpoint_attestation;
+mod deployment;
 mod interchain_gas;
 mod mailbox;
 mod merkle_tree_hook;
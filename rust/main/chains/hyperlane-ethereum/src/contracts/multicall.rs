@@ -11,6 +11,9 @@ use crate::{ConnectionConf, EthereumProvider};
 
 const ALLOW_BATCH_FAILURES: bool = true;
 
+/// The address Multicall3 is deployed at on most chains
+pub(crate) const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
 /// Conservative estimate picked by subtracting the gas used by individual calls from the total cost of `aggregate3`
 /// based on:
 /// - https://dashboard.tenderly.co/shared/simulation/63e85ac7-3ea9-475c-8218-a7c1dd508366/gas-usage
@@ -25,7 +28,7 @@ pub async fn build_multicall<M: Middleware + 'static>(
     let address = conn
         .operation_batch
         .batch_contract_address
-        .unwrap_or(hex_or_base58_to_h256("0xcA11bde05977b3631167028862bE2a173976CA11").unwrap());
+        .unwrap_or(hex_or_base58_to_h256(MULTICALL3_ADDRESS).unwrap());
     let ethereum_provider = EthereumProvider::new(provider.clone(), domain);
     if !ethereum_provider.is_contract(&address).await? {
         return Err(eyre::eyre!("Multicall contract not found at address"));
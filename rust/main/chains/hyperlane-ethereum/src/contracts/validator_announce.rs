@@ -17,7 +17,7 @@ use crate::{
         IValidatorAnnounce as EthereumValidatorAnnounceInternal, IVALIDATORANNOUNCE_ABI,
     },
     tx::{fill_tx_gas_params, report_tx},
-    BuildableWithProvider, ConnectionConf, EthereumProvider,
+    BuildableWithProvider, ConnectionConf, EthereumProvider, TransactionInclusionWatcher,
 };
 
 impl<M> std::fmt::Display for EthereumValidatorAnnounceInternal<M>
@@ -29,7 +29,9 @@ where
     }
 }
 
-pub struct ValidatorAnnounceBuilder {}
+pub struct ValidatorAnnounceBuilder {
+    pub inclusion_watcher: TransactionInclusionWatcher,
+}
 
 #[async_trait]
 impl BuildableWithProvider for ValidatorAnnounceBuilder {
@@ -46,6 +48,7 @@ impl BuildableWithProvider for ValidatorAnnounceBuilder {
             Arc::new(provider),
             conn,
             locator,
+            self.inclusion_watcher.clone(),
         ))
     }
 }
@@ -60,6 +63,7 @@ where
     domain: HyperlaneDomain,
     provider: Arc<M>,
     conn: ConnectionConf,
+    inclusion_watcher: TransactionInclusionWatcher,
 }
 
 impl<M> EthereumValidatorAnnounce<M>
@@ -68,7 +72,12 @@ where
 {
     /// Create a reference to a ValidatoAnnounce contract at a specific Ethereum
     /// address on some chain
-    pub fn new(provider: Arc<M>, conn: &ConnectionConf, locator: &ContractLocator) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+        inclusion_watcher: TransactionInclusionWatcher,
+    ) -> Self {
         Self {
             contract: Arc::new(EthereumValidatorAnnounceInternal::new(
                 locator.address,
@@ -77,6 +86,7 @@ where
             domain: locator.domain.clone(),
             provider,
             conn: conn.clone(),
+            inclusion_watcher,
         }
     }
 
@@ -96,10 +106,23 @@ where
             tx,
             self.provider.clone(),
             &self.conn.transaction_overrides,
+            self.conn.fee_history_cache.as_ref(),
             &self.domain,
         )
         .await
     }
+
+    /// Fetches the storage locations of all the validators with a single call
+    async fn fetch_storage_locations(&self, validators: &[H256]) -> ChainResult<Vec<Vec<String>>> {
+        let storage_locations = self
+            .contract
+            .get_announced_storage_locations(
+                validators.iter().map(|v| H160::from(*v).into()).collect(),
+            )
+            .call()
+            .await?;
+        Ok(storage_locations)
+    }
 }
 
 impl<M> HyperlaneChain for EthereumValidatorAnnounce<M>
@@ -136,14 +159,21 @@ where
         &self,
         validators: &[H256],
     ) -> ChainResult<Vec<Vec<String>>> {
-        let storage_locations = self
-            .contract
-            .get_announced_storage_locations(
-                validators.iter().map(|v| H160::from(*v).into()).collect(),
-            )
-            .call()
-            .await?;
-        Ok(storage_locations)
+        match &self.conn.storage_locations_cache {
+            Some(cache) => {
+                cache
+                    .storage_locations(validators, |validators| async move {
+                        self.fetch_storage_locations(&validators).await
+                    })
+                    .await
+            }
+            None => self.fetch_storage_locations(validators).await,
+        }
+    }
+
+    async fn announced_validators(&self) -> ChainResult<Vec<H256>> {
+        let validators = self.contract.get_announced_validators().call().await?;
+        Ok(validators.into_iter().map(Into::into).collect())
     }
 
     #[instrument(ret, skip(self))]
@@ -172,7 +202,12 @@ where
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
     async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
         let contract_call = self.announce_contract_call(announcement).await?;
-        let receipt = report_tx(contract_call).await?;
+        let receipt = report_tx(
+            contract_call,
+            self.provider.clone(),
+            &self.inclusion_watcher,
+        )
+        .await?;
         Ok(receipt.into())
     }
 }
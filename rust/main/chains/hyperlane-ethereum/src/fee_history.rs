@@ -0,0 +1,154 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ethers_core::{
+    types::{BlockNumber, U256 as EthersU256, U64},
+    utils::{EIP1559_FEE_ESTIMATION_PAST_BLOCKS, EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE},
+};
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use tokio::sync::Mutex;
+use tracing::trace;
+
+use crate::Middleware;
+
+/// Caches the `eth_feeHistory` of a chain, so that the transactions submitted
+/// to the chain around the same time share a single request and get
+/// consistent fees.
+///
+/// A cached fee history is used until it's older than the TTL, or until a
+/// transaction is estimated against a newer block than the one it was fetched
+/// at. Clones share the same cache, so a single cache can be handed to every
+/// contract built for the chain.
+#[derive(Debug, Clone)]
+pub struct FeeHistoryCache {
+    ttl: Duration,
+    // An async lock, so that concurrent misses wait for a single request
+    cached: Arc<Mutex<Option<CachedFeeHistory>>>,
+}
+
+#[derive(Debug)]
+struct CachedFeeHistory {
+    /// The latest block when the fee history was fetched
+    block_number: U64,
+    fetched_at: Instant,
+    reward: Vec<Vec<EthersU256>>,
+}
+
+impl CachedFeeHistory {
+    fn is_fresh(&self, latest_block_number: U64, ttl: Duration, now: Instant) -> bool {
+        self.block_number == latest_block_number
+            && now.saturating_duration_since(self.fetched_at) < ttl
+    }
+}
+
+impl FeeHistoryCache {
+    /// Create an empty cache whose fee histories are used for up to `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cached: Default::default(),
+        }
+    }
+
+    /// How long a fee history is used for at most
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The priority fee rewards of the recent blocks, as of the block
+    /// `latest_block_number`. Only fetched if the cached ones are stale.
+    pub(crate) async fn rewards<M: Middleware>(
+        &self,
+        provider: &M,
+        latest_block_number: Option<U64>,
+    ) -> ChainResult<Vec<Vec<EthersU256>>> {
+        // Without the number of the latest block, there's no telling whether
+        // the cached fee history is from a previous block
+        let Some(latest_block_number) = latest_block_number else {
+            return fetch_fee_history_rewards(provider).await;
+        };
+
+        let mut cached = self.cached.lock().await;
+        if let Some(cached) = cached
+            .as_ref()
+            .filter(|cached| cached.is_fresh(latest_block_number, self.ttl, Instant::now()))
+        {
+            trace!(block_number = %cached.block_number, "Using cached fee history");
+            return Ok(cached.reward.clone());
+        }
+
+        let reward = fetch_fee_history_rewards(provider).await?;
+        *cached = Some(CachedFeeHistory {
+            block_number: latest_block_number,
+            fetched_at: Instant::now(),
+            reward: reward.clone(),
+        });
+        Ok(reward)
+    }
+}
+
+/// Fetches the priority fee rewards of the recent blocks the EIP-1559 fee
+/// estimator expects
+pub(crate) async fn fetch_fee_history_rewards<M: Middleware>(
+    provider: &M,
+) -> ChainResult<Vec<Vec<EthersU256>>> {
+    let fee_history = provider
+        .fee_history(
+            EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
+            BlockNumber::Latest,
+            &[EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
+        )
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    Ok(fee_history.reward)
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::providers::{MockProvider, Provider};
+    use ethers::types::FeeHistory;
+
+    use super::*;
+
+    fn fee_history(reward: u64) -> FeeHistory {
+        FeeHistory {
+            base_fee_per_gas: vec![],
+            gas_used_ratio: vec![],
+            oldest_block: Default::default(),
+            reward: vec![vec![reward.into()]],
+        }
+    }
+
+    #[test]
+    fn test_cached_fee_history_expires() {
+        let now = Instant::now();
+        let cached = CachedFeeHistory {
+            block_number: 10.into(),
+            fetched_at: now,
+            reward: vec![],
+        };
+        let ttl = Duration::from_secs(5);
+        assert!(cached.is_fresh(10.into(), ttl, now + Duration::from_secs(4)));
+        assert!(!cached.is_fresh(10.into(), ttl, now + ttl));
+        // A new head invalidates the cached fee history before the TTL
+        assert!(!cached.is_fresh(11.into(), ttl, now));
+    }
+
+    #[tokio::test]
+    async fn test_fee_history_is_fetched_once_per_block() {
+        let mock_provider = MockProvider::new();
+        let provider = Provider::new(mock_provider.clone());
+        let cache = FeeHistoryCache::new(Duration::from_secs(60));
+
+        // Any request beyond the pushed responses fails
+        mock_provider.push(fee_history(1)).unwrap();
+        let rewards = cache.rewards(&provider, Some(10.into())).await.unwrap();
+        assert_eq!(rewards, vec![vec![1.into()]]);
+        let rewards = cache.rewards(&provider, Some(10.into())).await.unwrap();
+        assert_eq!(rewards, vec![vec![1.into()]]);
+
+        mock_provider.push(fee_history(2)).unwrap();
+        let rewards = cache.rewards(&provider, Some(11.into())).await.unwrap();
+        assert_eq!(rewards, vec![vec![2.into()]]);
+    }
+}
@@ -16,7 +16,8 @@ use hyperlane_core::{
 use crate::interfaces::i_aggregation_ism::{
     IAggregationIsm as EthereumAggregationIsmInternal, IAGGREGATIONISM_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::read_batcher::batched_call;
+use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, ReadBatcher};
 
 pub struct AggregationIsmBuilder {}
 
@@ -28,10 +29,14 @@ impl BuildableWithProvider for AggregationIsmBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumAggregationIsm::new(Arc::new(provider), locator))
+        Box::new(EthereumAggregationIsm::new(
+            Arc::new(provider),
+            locator,
+            conn.read_batcher.clone(),
+        ))
     }
 }
 
@@ -43,6 +48,7 @@ where
 {
     contract: Arc<EthereumAggregationIsmInternal<M>>,
     domain: HyperlaneDomain,
+    read_batcher: Option<ReadBatcher>,
 }
 
 impl<M> EthereumAggregationIsm<M>
@@ -51,13 +57,18 @@ where
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
-    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        read_batcher: Option<ReadBatcher>,
+    ) -> Self {
         Self {
             contract: Arc::new(EthereumAggregationIsmInternal::new(
                 locator.address,
                 provider,
             )),
             domain: locator.domain.clone(),
+            read_batcher,
         }
     }
 }
@@ -98,11 +109,11 @@ where
         &self,
         message: &HyperlaneMessage,
     ) -> ChainResult<(Vec<H256>, u8)> {
-        let (isms, threshold) = self
+        let call = self
             .contract
-            .modules_and_threshold(RawHyperlaneMessage::from(message).to_vec().into())
-            .call()
-            .await?;
+            .modules_and_threshold(RawHyperlaneMessage::from(message).to_vec().into());
+        let (isms, threshold) =
+            batched_call(self.read_batcher.as_ref(), self.contract.client(), call).await?;
         let isms_h256 = isms.iter().map(|address| (*address).into()).collect();
         Ok((isms_h256, threshold))
     }
@@ -20,7 +20,8 @@ use crate::interfaces::i_interchain_security_module::{
     IInterchainSecurityModule as EthereumInterchainSecurityModuleInternal,
     IINTERCHAINSECURITYMODULE_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::read_batcher::batched_call;
+use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, ReadBatcher};
 
 pub struct InterchainSecurityModuleBuilder {}
 
@@ -32,12 +33,13 @@ impl BuildableWithProvider for InterchainSecurityModuleBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
         Box::new(EthereumInterchainSecurityModule::new(
             Arc::new(provider),
             locator,
+            conn.read_batcher.clone(),
         ))
     }
 }
@@ -50,6 +52,7 @@ where
 {
     contract: Arc<EthereumInterchainSecurityModuleInternal<M>>,
     domain: HyperlaneDomain,
+    read_batcher: Option<ReadBatcher>,
 }
 
 impl<M> EthereumInterchainSecurityModule<M>
@@ -58,13 +61,18 @@ where
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
-    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        read_batcher: Option<ReadBatcher>,
+    ) -> Self {
         Self {
             contract: Arc::new(EthereumInterchainSecurityModuleInternal::new(
                 locator.address,
                 provider,
             )),
             domain: locator.domain.clone(),
+            read_batcher,
         }
     }
 }
@@ -101,7 +109,12 @@ where
 {
     #[instrument]
     async fn module_type(&self) -> ChainResult<ModuleType> {
-        let module = self.contract.module_type().call().await?;
+        let module = batched_call(
+            self.read_batcher.as_ref(),
+            self.contract.client(),
+            self.contract.module_type(),
+        )
+        .await?;
         if let Some(module_type) = ModuleType::from_u8(module) {
             Ok(module_type)
         } else {
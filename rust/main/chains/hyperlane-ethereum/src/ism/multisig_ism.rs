@@ -16,7 +16,8 @@ use hyperlane_core::{
 use crate::interfaces::i_multisig_ism::{
     IMultisigIsm as EthereumMultisigIsmInternal, IMULTISIGISM_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::read_batcher::batched_call;
+use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, ReadBatcher};
 
 impl<M> std::fmt::Display for EthereumMultisigIsmInternal<M>
 where
@@ -37,10 +38,14 @@ impl BuildableWithProvider for MultisigIsmBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMultisigIsm::new(Arc::new(provider), locator))
+        Box::new(EthereumMultisigIsm::new(
+            Arc::new(provider),
+            locator,
+            conn.read_batcher.clone(),
+        ))
     }
 }
 
@@ -52,6 +57,7 @@ where
 {
     contract: Arc<EthereumMultisigIsmInternal<M>>,
     domain: HyperlaneDomain,
+    read_batcher: Option<ReadBatcher>,
 }
 
 impl<M> EthereumMultisigIsm<M>
@@ -60,10 +66,15 @@ where
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
-    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        read_batcher: Option<ReadBatcher>,
+    ) -> Self {
         Self {
             contract: Arc::new(EthereumMultisigIsmInternal::new(locator.address, provider)),
             domain: locator.domain.clone(),
+            read_batcher,
         }
     }
 }
@@ -104,11 +115,11 @@ where
         &self,
         message: &HyperlaneMessage,
     ) -> ChainResult<(Vec<H256>, u8)> {
-        let (validator_addresses, threshold) = self
+        let call = self
             .contract
-            .validators_and_threshold(RawHyperlaneMessage::from(message).to_vec().into())
-            .call()
-            .await?;
+            .validators_and_threshold(RawHyperlaneMessage::from(message).to_vec().into());
+        let (validator_addresses, threshold) =
+            batched_call(self.read_batcher.as_ref(), self.contract.client(), call).await?;
         let validators: Vec<H256> = validator_addresses.iter().map(|&x| H256::from(x)).collect();
         Ok((validators, threshold))
     }
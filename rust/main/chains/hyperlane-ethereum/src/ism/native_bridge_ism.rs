@@ -0,0 +1,447 @@
+#![allow(missing_docs)]
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::{
+    abi::{encode, Token},
+    providers::{Http, Middleware, Provider},
+    types::{Address, H160, H256, U64},
+};
+use serde::Deserialize;
+use tracing::{info, instrument};
+use url::Url;
+
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, NativeBridgeIsm, RawHyperlaneMessage,
+    H256 as HyperlaneH256,
+};
+
+use crate::interfaces::{
+    arbitrum_bridge_ism::ArbitrumBridgeIsm as EthereumArbitrumBridgeIsmInternal,
+    arbitrum_node_interface::ArbitrumNodeInterface,
+    arbitrum_outbox::{ArbitrumOutbox, SendRootUpdatedFilter},
+    arbitrum_sys::{ArbitrumSys, L2ToL1TxFilter},
+    optimism_bridge_ism::OptimismBridgeIsm as EthereumOptimismBridgeIsmInternal,
+    optimism_message_passer::{MessagePassedFilter, OptimismMessagePasser},
+    optimism_portal::OptimismPortal,
+};
+use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, RpcConnectionConf};
+
+/// Address of the ArbSys precompile on Arbitrum Nitro chains
+const ARB_SYS_ADDRESS: u64 = 0x64;
+/// Address of the NodeInterface precompile on Arbitrum Nitro chains
+const ARBITRUM_NODE_INTERFACE_ADDRESS: u64 = 0xC8;
+/// Address of the L2ToL1MessagePasser predeploy on OP Stack chains
+const OP_L2_TO_L1_MESSAGE_PASSER_ADDRESS: &str = "0x4200000000000000000000000000000000000016";
+/// How many destination blocks to search for the latest send root the
+/// Arbitrum outbox confirmed. Send roots are confirmed about every hour.
+const SEND_ROOT_LOOKBACK_BLOCKS: u64 = 10_000;
+
+/// Builds a provider for the L2 origin chain, which withdrawals are read from.
+/// Like the TypeScript SDK, this uses the first configured RPC url only.
+fn origin_provider(origin: &ConnectionConf) -> Arc<Provider<Http>> {
+    let url: Url = match &origin.rpc_connection {
+        RpcConnectionConf::HttpQuorum { urls } | RpcConnectionConf::HttpFallback { urls } => {
+            urls[0].clone()
+        }
+        RpcConnectionConf::Http { url } | RpcConnectionConf::Ws { url } => url.clone(),
+    };
+    Arc::new(Provider::new(Http::new(url)))
+}
+
+/// Whether the bridge message `data` carries the message id
+fn carries_message_id(data: &[u8], message_id: HyperlaneH256) -> bool {
+    data.windows(32)
+        .any(|window| window == message_id.as_bytes())
+}
+
+pub struct ArbL2ToL1IsmBuilder {
+    /// Connection to the Arbitrum chain the messages are sent from
+    pub origin: ConnectionConf,
+}
+
+#[async_trait]
+impl BuildableWithProvider for ArbL2ToL1IsmBuilder {
+    type Output = Box<dyn NativeBridgeIsm>;
+    const NEEDS_SIGNER: bool = false;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        _conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumArbL2ToL1Ism::new(
+            Arc::new(provider),
+            origin_provider(&self.origin),
+            locator,
+        ))
+    }
+}
+
+/// A reference to an ArbL2ToL1Ism contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumArbL2ToL1Ism<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumArbitrumBridgeIsmInternal<M>>,
+    origin_provider: Arc<Provider<Http>>,
+    domain: HyperlaneDomain,
+}
+
+impl<M> EthereumArbL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to an ArbL2ToL1Ism at a specific Ethereum address on
+    /// some chain, which verifies messages from the `origin_provider` chain
+    pub fn new(
+        provider: Arc<M>,
+        origin_provider: Arc<Provider<Http>>,
+        locator: &ContractLocator,
+    ) -> Self {
+        Self {
+            contract: Arc::new(EthereumArbitrumBridgeIsmInternal::new(
+                locator.address,
+                provider,
+            )),
+            origin_provider,
+            domain: locator.domain.clone(),
+        }
+    }
+
+    /// Finds the L2ToL1Tx sent to this ISM in `block` that carries the
+    /// message id
+    async fn find_withdrawal(
+        &self,
+        message: &HyperlaneMessage,
+        block: u64,
+    ) -> ChainResult<Option<L2ToL1TxFilter>> {
+        let arb_sys = ArbitrumSys::new(
+            H160::from_low_u64_be(ARB_SYS_ADDRESS),
+            self.origin_provider.clone(),
+        );
+        let withdrawals = arb_sys
+            .event::<L2ToL1TxFilter>()
+            .topic1(H256::from(self.contract.address()))
+            .from_block(block)
+            .to_block(block)
+            .query()
+            .await?;
+        let message_id = message.id();
+        Ok(withdrawals
+            .into_iter()
+            .find(|withdrawal| carries_message_id(&withdrawal.data, message_id)))
+    }
+
+    /// The number of L2ToL1Txs that the latest send root confirmed by the
+    /// outbox includes
+    async fn confirmed_send_count(&self) -> ChainResult<Option<u64>> {
+        let outbox = ArbitrumOutbox::new(
+            self.contract.arb_outbox().call().await?,
+            self.contract.client(),
+        );
+        let tip = self.contract.client().get_block_number().await?.as_u64();
+        let send_roots = outbox
+            .event::<SendRootUpdatedFilter>()
+            .from_block(tip.saturating_sub(SEND_ROOT_LOOKBACK_BLOCKS))
+            .to_block(tip)
+            .query()
+            .await?;
+        let Some(send_root) = send_roots.last() else {
+            return Ok(None);
+        };
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ArbitrumBlock {
+            send_count: U64,
+        }
+        let block: Option<ArbitrumBlock> = self
+            .origin_provider
+            .request(
+                "eth_getBlockByHash",
+                (H256::from(send_root.block_hash), false),
+            )
+            .await?;
+        Ok(block.map(|block| block.send_count.as_u64()))
+    }
+}
+
+impl<M> HyperlaneChain for EthereumArbL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumArbL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> HyperlaneH256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> NativeBridgeIsm for EthereumArbL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err, skip(self, message))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn is_verified(&self, message: &HyperlaneMessage) -> ChainResult<bool> {
+        let verified = self
+            .contract
+            .is_verified(RawHyperlaneMessage::from(message).into())
+            .call()
+            .await?;
+        Ok(verified)
+    }
+
+    #[instrument(err, skip(self, message))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn withdrawal_metadata(
+        &self,
+        message: &HyperlaneMessage,
+        dispatched_block: u64,
+    ) -> ChainResult<Option<Vec<u8>>> {
+        let Some(withdrawal) = self.find_withdrawal(message, dispatched_block).await? else {
+            info!("No L2ToL1Tx carrying the message id was found in the dispatch block");
+            return Ok(None);
+        };
+        let position = withdrawal.position.as_u64();
+        let Some(send_count) = self.confirmed_send_count().await? else {
+            info!("The Arbitrum outbox didn't confirm any send root recently");
+            return Ok(None);
+        };
+        if send_count <= position {
+            info!(
+                position,
+                send_count, "The L2ToL1Tx isn't confirmed by the Arbitrum outbox yet"
+            );
+            return Ok(None);
+        }
+
+        let node_interface = ArbitrumNodeInterface::new(
+            H160::from_low_u64_be(ARBITRUM_NODE_INTERFACE_ADDRESS),
+            self.origin_provider.clone(),
+        );
+        let (_send, _root, proof) = node_interface
+            .construct_outbox_proof(send_count, position)
+            .call()
+            .await?;
+
+        // The arguments of the outbox's `executeTransaction`
+        let metadata = encode(&[
+            Token::Array(
+                proof
+                    .into_iter()
+                    .map(|node| Token::FixedBytes(node.to_vec()))
+                    .collect(),
+            ),
+            Token::Uint(withdrawal.position),
+            Token::Address(withdrawal.caller),
+            Token::Address(withdrawal.destination),
+            Token::Uint(withdrawal.arb_block_num),
+            Token::Uint(withdrawal.eth_block_num),
+            Token::Uint(withdrawal.timestamp),
+            Token::Uint(withdrawal.callvalue),
+            Token::Bytes(withdrawal.data.to_vec()),
+        ]);
+        Ok(Some(metadata))
+    }
+}
+
+pub struct OpL2ToL1IsmBuilder {
+    /// Connection to the OP Stack chain the messages are sent from
+    pub origin: ConnectionConf,
+}
+
+#[async_trait]
+impl BuildableWithProvider for OpL2ToL1IsmBuilder {
+    type Output = Box<dyn NativeBridgeIsm>;
+    const NEEDS_SIGNER: bool = false;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        _conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumOpL2ToL1Ism::new(
+            Arc::new(provider),
+            origin_provider(&self.origin),
+            locator,
+        ))
+    }
+}
+
+/// A reference to an OPL2ToL1Ism contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumOpL2ToL1Ism<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumOptimismBridgeIsmInternal<M>>,
+    origin_provider: Arc<Provider<Http>>,
+    domain: HyperlaneDomain,
+}
+
+impl<M> EthereumOpL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to an OPL2ToL1Ism at a specific Ethereum address on
+    /// some chain, which verifies messages from the `origin_provider` chain
+    pub fn new(
+        provider: Arc<M>,
+        origin_provider: Arc<Provider<Http>>,
+        locator: &ContractLocator,
+    ) -> Self {
+        Self {
+            contract: Arc::new(EthereumOptimismBridgeIsmInternal::new(
+                locator.address,
+                provider,
+            )),
+            origin_provider,
+            domain: locator.domain.clone(),
+        }
+    }
+
+    /// Finds the withdrawal initiated in `block` that carries the message id
+    async fn find_withdrawal(
+        &self,
+        message: &HyperlaneMessage,
+        block: u64,
+    ) -> ChainResult<Option<MessagePassedFilter>> {
+        let message_passer = OptimismMessagePasser::new(
+            OP_L2_TO_L1_MESSAGE_PASSER_ADDRESS
+                .parse::<Address>()
+                .expect("valid address"),
+            self.origin_provider.clone(),
+        );
+        let withdrawals = message_passer
+            .event::<MessagePassedFilter>()
+            .from_block(block)
+            .to_block(block)
+            .query()
+            .await?;
+        let message_id = message.id();
+        Ok(withdrawals
+            .into_iter()
+            .find(|withdrawal| carries_message_id(&withdrawal.data, message_id)))
+    }
+}
+
+impl<M> HyperlaneChain for EthereumOpL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumOpL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> HyperlaneH256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> NativeBridgeIsm for EthereumOpL2ToL1Ism<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err, skip(self, message))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn is_verified(&self, message: &HyperlaneMessage) -> ChainResult<bool> {
+        let verified = self
+            .contract
+            .is_verified(RawHyperlaneMessage::from(message).into())
+            .call()
+            .await?;
+        Ok(verified)
+    }
+
+    #[instrument(err, skip(self, message))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn withdrawal_metadata(
+        &self,
+        message: &HyperlaneMessage,
+        dispatched_block: u64,
+    ) -> ChainResult<Option<Vec<u8>>> {
+        let Some(withdrawal) = self.find_withdrawal(message, dispatched_block).await? else {
+            info!("No withdrawal carrying the message id was found in the dispatch block");
+            return Ok(None);
+        };
+
+        // The withdrawal has to be proven against an L2 output root before the
+        // ISM can finalize it
+        let portal =
+            OptimismPortal::new(self.contract.portal().call().await?, self.contract.client());
+        let (_output_root, proven_at, _output_index) = portal
+            .proven_withdrawals(withdrawal.withdrawal_hash)
+            .call()
+            .await?;
+        if proven_at == 0 {
+            info!(
+                withdrawal_hash = ?H256::from(withdrawal.withdrawal_hash),
+                "The withdrawal isn't proven on the OptimismPortal yet"
+            );
+            return Ok(None);
+        }
+
+        // The portal's `WithdrawalTransaction`
+        let metadata = encode(&[Token::Tuple(vec![
+            Token::Uint(withdrawal.nonce),
+            Token::Address(withdrawal.sender),
+            Token::Address(withdrawal.target),
+            Token::Uint(withdrawal.value),
+            Token::Uint(withdrawal.gas_limit),
+            Token::Bytes(withdrawal.data.to_vec()),
+        ])]);
+        Ok(Some(metadata))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_carries_message_id() {
+        let message_id = HyperlaneH256::repeat_byte(0xab);
+        let mut data = vec![0u8; 4];
+        data.extend_from_slice(message_id.as_bytes());
+        data.extend_from_slice(&[0u8; 32]);
+
+        assert!(carries_message_id(&data, message_id));
+        assert!(!carries_message_id(&data[..35], message_id));
+        assert!(!carries_message_id(&data, HyperlaneH256::repeat_byte(0xcd)));
+    }
+}
@@ -16,7 +16,8 @@ use hyperlane_core::{
 use crate::interfaces::i_routing_ism::{
     IRoutingIsm as EthereumRoutingIsmInternal, IROUTINGISM_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::read_batcher::batched_call;
+use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, ReadBatcher};
 
 pub struct RoutingIsmBuilder {}
 
@@ -28,10 +29,14 @@ impl BuildableWithProvider for RoutingIsmBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumRoutingIsm::new(Arc::new(provider), locator))
+        Box::new(EthereumRoutingIsm::new(
+            Arc::new(provider),
+            locator,
+            conn.read_batcher.clone(),
+        ))
     }
 }
 
@@ -43,6 +48,7 @@ where
 {
     contract: Arc<EthereumRoutingIsmInternal<M>>,
     domain: HyperlaneDomain,
+    read_batcher: Option<ReadBatcher>,
 }
 
 impl<M> EthereumRoutingIsm<M>
@@ -51,10 +57,15 @@ where
 {
     /// Create a reference to a mailbox at a specific Ethereum address on some
     /// chain
-    pub fn new(provider: Arc<M>, locator: &ContractLocator) -> Self {
+    pub fn new(
+        provider: Arc<M>,
+        locator: &ContractLocator,
+        read_batcher: Option<ReadBatcher>,
+    ) -> Self {
         Self {
             contract: Arc::new(EthereumRoutingIsmInternal::new(locator.address, provider)),
             domain: locator.domain.clone(),
+            read_batcher,
         }
     }
 }
@@ -92,11 +103,10 @@ where
     #[instrument(err, skip(self, message))]
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
     async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
-        let ism = self
+        let call = self
             .contract
-            .route(RawHyperlaneMessage::from(message).to_vec().into())
-            .call()
-            .await?;
+            .route(RawHyperlaneMessage::from(message).to_vec().into());
+        let ism = batched_call(self.read_batcher.as_ref(), self.contract.client(), call).await?;
         Ok(ism.into())
     }
 }
@@ -8,19 +8,29 @@ use std::collections::HashMap;
 use ethers::abi::FunctionExt;
 use ethers::prelude::{abi, Lazy, Middleware};
 
-pub use self::{config::*, contracts::*, ism::*, rpc_clients::*, signer::*};
+pub use self::{
+    config::*, contracts::*, fee_history::FeeHistoryCache, ism::*,
+    log_query_batcher::LogQueryBatcher, nonce::*, read_batcher::ReadBatcher, rpc_clients::*,
+    signer::*, storage_locations_cache::StorageLocationsCache, tx_inclusion::*,
+};
 
 /// Hyperlane Application specific functionality
 pub mod application;
 mod config;
 mod contracts;
 mod error;
+mod fee_history;
 mod interfaces;
 mod ism;
+mod log_query_batcher;
+mod nonce;
+mod read_batcher;
 /// Ethers JSONRPC Client implementations
 mod rpc_clients;
 mod signer;
+mod storage_locations_cache;
 mod tx;
+mod tx_inclusion;
 
 fn extract_fn_map(abi: &'static Lazy<abi::Abi>) -> HashMap<Vec<u8>, &'static str> {
     abi.functions()
@@ -0,0 +1,196 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, RwLock};
+
+use ethers::abi::RawLog;
+use ethers::types::{Filter, Log, H160 as EthersH160, H256 as EthersH256};
+use ethers_contract::{EthEvent, LogMeta as EthersLogMeta};
+use hyperlane_core::{ChainCommunicationError, ChainResult, LogMeta};
+use tokio::sync::Mutex;
+use tracing::trace;
+
+use crate::Middleware;
+
+/// How many block ranges the logs are kept for. The indexers of a chain
+/// usually query the same ranges at about the same time, so only the most
+/// recent ones are worth keeping.
+const CACHED_RANGES: usize = 16;
+
+/// Shares the `eth_getLogs` queries of the indexers of several contracts of a
+/// chain, e.g. the mailbox, merkle tree hook and IGP.
+///
+/// Each indexer registers the contract address and event it indexes. The logs
+/// of a block range are then fetched in a single query for every registered
+/// address and event, and demultiplexed by address and event signature. An
+/// indexer querying a range another one just queried gets its logs from the
+/// cache instead of making its own request. Clones share the same
+/// registrations and cache.
+#[derive(Debug, Clone, Default)]
+pub struct LogQueryBatcher {
+    /// The address and event signature of each registered indexer
+    filters: Arc<RwLock<BTreeSet<(EthersH160, EthersH256)>>>,
+    // An async lock, so that concurrent misses wait for a single request
+    cached: Arc<Mutex<VecDeque<CachedLogs>>>,
+}
+
+#[derive(Debug)]
+struct CachedLogs {
+    range: RangeInclusive<u32>,
+    /// The filters the logs were fetched for
+    filters: BTreeSet<(EthersH160, EthersH256)>,
+    logs: Arc<Vec<Log>>,
+}
+
+impl CachedLogs {
+    fn covers(&self, range: &RangeInclusive<u32>, filter: &(EthersH160, EthersH256)) -> bool {
+        self.range == *range && self.filters.contains(filter)
+    }
+}
+
+impl LogQueryBatcher {
+    /// Include the events `T` emitted by `address` in the batched queries
+    pub(crate) fn register<T: EthEvent>(&self, address: EthersH160) {
+        self.filters
+            .write()
+            .expect("RwLock poisoned")
+            .insert((address, T::signature()));
+    }
+
+    /// The events `T` emitted by `address` in the block range, inclusive.
+    /// Only fetched if no batched query already covered them.
+    pub(crate) async fn query<T: EthEvent, M: Middleware>(
+        &self,
+        provider: &M,
+        address: EthersH160,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(T, LogMeta)>> {
+        let filter = (address, T::signature());
+        let mut cached = self.cached.lock().await;
+        let logs = match cached.iter().find(|cached| cached.covers(&range, &filter)) {
+            Some(cached) => {
+                trace!(?range, "Using batched logs");
+                cached.logs.clone()
+            }
+            None => {
+                let mut filters = self.filters.read().expect("RwLock poisoned").clone();
+                // In case the indexer wasn't registered
+                filters.insert(filter);
+                let logs = Arc::new(fetch_logs(provider, &filters, &range).await?);
+                if cached.len() >= CACHED_RANGES {
+                    cached.pop_front();
+                }
+                cached.push_back(CachedLogs {
+                    range,
+                    filters,
+                    logs: logs.clone(),
+                });
+                logs
+            }
+        };
+        drop(cached);
+        Ok(demultiplex(&logs, filter))
+    }
+}
+
+/// Fetches the logs of every filter in a single query
+async fn fetch_logs<M: Middleware>(
+    provider: &M,
+    filters: &BTreeSet<(EthersH160, EthersH256)>,
+    range: &RangeInclusive<u32>,
+) -> ChainResult<Vec<Log>> {
+    let addresses: BTreeSet<_> = filters.iter().map(|(address, _)| *address).collect();
+    let topics: BTreeSet<_> = filters.iter().map(|(_, topic)| *topic).collect();
+    let filter = Filter::new()
+        .address(addresses.into_iter().collect::<Vec<_>>())
+        .topic0(topics.into_iter().collect::<Vec<_>>())
+        .from_block(*range.start())
+        .to_block(*range.end());
+    provider
+        .get_logs(&filter)
+        .await
+        .map_err(ChainCommunicationError::from_other)
+}
+
+/// The logs of the events `T` emitted by the filter's address, decoded
+fn demultiplex<T: EthEvent>(logs: &[Log], filter: (EthersH160, EthersH256)) -> Vec<(T, LogMeta)> {
+    logs.iter()
+        // The query also matches the events of one contract emitted by the
+        // others, so both the address and the signature are checked
+        .filter(|log| log.address == filter.0 && log.topics.first() == Some(&filter.1))
+        .filter_map(|log| {
+            let raw_log = RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            };
+            let event = T::decode_log(&raw_log).ok()?;
+            Some((event, EthersLogMeta::from(log).into()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::providers::{MockProvider, Provider};
+    use ethers::types::U64;
+
+    use crate::interfaces::merkle_tree_hook::InsertedIntoTreeFilter;
+
+    use super::*;
+
+    fn inserted_into_tree_log(address: EthersH160, index: u32) -> Log {
+        Log {
+            address,
+            topics: vec![InsertedIntoTreeFilter::signature()],
+            data: ethers::abi::encode(&[
+                ethers::abi::Token::FixedBytes(vec![1; 32]),
+                ethers::abi::Token::Uint(index.into()),
+            ])
+            .into(),
+            block_number: Some(U64::from(10)),
+            block_hash: Some(Default::default()),
+            transaction_hash: Some(Default::default()),
+            transaction_index: Some(Default::default()),
+            log_index: Some(Default::default()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logs_are_fetched_once_per_range() {
+        let mock_provider = MockProvider::new();
+        let provider = Provider::new(mock_provider.clone());
+        let batcher = LogQueryBatcher::default();
+        let merkle_tree_hook = EthersH160::from_low_u64_be(1);
+        let other = EthersH160::from_low_u64_be(2);
+        batcher.register::<InsertedIntoTreeFilter>(merkle_tree_hook);
+        batcher.register::<InsertedIntoTreeFilter>(other);
+
+        // Any request beyond the pushed responses fails
+        mock_provider
+            .push(vec![
+                inserted_into_tree_log(merkle_tree_hook, 0),
+                inserted_into_tree_log(other, 7),
+            ])
+            .unwrap();
+        let events = batcher
+            .query::<InsertedIntoTreeFilter, _>(&provider, merkle_tree_hook, 1..=10)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0.index, 0);
+        let events = batcher
+            .query::<InsertedIntoTreeFilter, _>(&provider, other, 1..=10)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0.index, 7);
+
+        // Another range is fetched again
+        mock_provider.push(Vec::<Log>::new()).unwrap();
+        let events = batcher
+            .query::<InsertedIntoTreeFilter, _>(&provider, other, 11..=20)
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,565 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ethers::types::{
+    transaction::eip2718::TypedTransaction, BlockNumber, Eip1559TransactionRequest, Transaction,
+    TransactionRequest,
+};
+use ethers_core::types::{H160, U256 as EthersU256};
+use prometheus::{IntCounterVec, IntGaugeVec};
+use tracing::{debug, info, warn};
+
+use crate::Middleware;
+
+/// Expected label names for the `stuck_nonce_seconds` metric.
+pub const STUCK_NONCE_SECONDS_LABELS: &[&str] = &["chain"];
+/// Help string for the metric.
+pub const STUCK_NONCE_SECONDS_HELP: &str = "Seconds the lowest unmined nonce of the signer \
+    has been stuck, or 0 if none is";
+
+/// Expected label names for the `nonce_replacements_total` metric.
+pub const NONCE_REPLACEMENTS_TOTAL_LABELS: &[&str] = &["chain", "reason"];
+/// Help string for the metric.
+pub const NONCE_REPLACEMENTS_TOTAL_HELP: &str = "Number of transactions sent to unblock a \
+    nonce, either `stuck` behind an unmined transaction or left as a `gap` by a dropped one";
+
+const REASON_STUCK: &str = "stuck";
+const REASON_GAP: &str = "gap";
+
+/// Nonces are checked at most this often, to limit the number of RPC calls
+/// made before each submission
+const NONCE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// How long the lowest unmined nonce can stay unmined before it is replaced,
+/// and the minimum time between two replacements of the same nonce
+const STUCK_NONCE_THRESHOLD: Duration = Duration::from_secs(5 * 60);
+/// Each replacement bumps the fees of the last transaction sent with the
+/// nonce by this many percent. Nodes only accept replacements that bump both
+/// fees by at least 10%. If the fees aren't known, e.g. for a gap, each
+/// replacement bumps the gas price by this many more percent instead.
+const REPLACEMENT_FEE_BUMP_PERCENT: u64 = 50;
+/// After this many replacements of a nonce, it is left for operators to
+/// investigate
+const MAX_REPLACEMENTS: u32 = 5;
+/// Gas used by a plain transfer
+const SELF_TRANSFER_GAS: u64 = 21_000;
+
+/// Detects and repairs nonces of the signer that block its transactions.
+///
+/// The lowest unmined nonce of the signer is stuck if it stays unmined for
+/// too long, either because the transaction using it is underpriced, or
+/// because no transaction uses it, e.g. after it was dropped from the mempool
+/// or after a crash, leaving a gap before the transactions with higher
+/// nonces. It is repaired by sending a transfer of nothing to the signer
+/// itself with this nonce, with bumped fees.
+#[derive(Clone, Debug)]
+pub(crate) struct NonceMonitor {
+    chain: String,
+    stuck_nonce_seconds: IntGaugeVec,
+    replacements: IntCounterVec,
+    state: Arc<Mutex<NonceState>>,
+}
+
+#[derive(Debug, Default)]
+struct NonceState {
+    last_check: Option<Instant>,
+    /// Highest nonce of the transactions broadcast by the signer, or pending
+    /// in the node's mempool, so that it's known again after a restart
+    highest_sent: Option<EthersU256>,
+    /// Fees of the last transaction sent with each nonce that isn't mined
+    sent_fees: BTreeMap<EthersU256, TxFees>,
+    stuck: Option<StuckNonce>,
+}
+
+/// The fees of a transaction sent by the signer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TxFees {
+    Legacy {
+        gas_price: EthersU256,
+    },
+    Eip1559 {
+        max_fee: EthersU256,
+        max_priority_fee: EthersU256,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StuckNonce {
+    nonce: EthersU256,
+    since: Instant,
+    replacements: u32,
+    last_replacement: Option<Instant>,
+}
+
+/// A transaction to send to unblock a nonce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NonceReplacement {
+    nonce: EthersU256,
+    /// 1 for the first replacement of the nonce
+    attempt: u32,
+    reason: &'static str,
+    /// Fees of the last transaction sent with the nonce, if known
+    fees: Option<TxFees>,
+}
+
+impl NonceMonitor {
+    pub(crate) fn new(
+        chain: String,
+        stuck_nonce_seconds: IntGaugeVec,
+        replacements: IntCounterVec,
+    ) -> Self {
+        Self {
+            chain,
+            stuck_nonce_seconds,
+            replacements,
+            state: Default::default(),
+        }
+    }
+
+    /// Records the nonce and fees of a transaction broadcast by the signer
+    pub(crate) fn record_sent(&self, tx: &Transaction) {
+        let mut state = self.state.lock().expect("nonce state lock poisoned");
+        state.record_sent(tx.nonce, TxFees::of(tx));
+    }
+
+    /// Checks the nonce at the next submission regardless of when it was
+    /// last checked, e.g. after a nonce error
+    pub(crate) fn expedite(&self) {
+        self.state
+            .lock()
+            .expect("nonce state lock poisoned")
+            .last_check = None;
+    }
+
+    /// Checks whether the signer's lowest unmined nonce is stuck, and sends a
+    /// replacement transaction if it has been stuck for too long.
+    pub(crate) async fn check_and_repair<M>(&self, provider: &M)
+    where
+        M: Middleware + 'static,
+    {
+        let Some(sender) = provider.default_sender() else {
+            return;
+        };
+        {
+            let mut state = self.state.lock().expect("nonce state lock poisoned");
+            if state
+                .last_check
+                .is_some_and(|last_check| last_check.elapsed() < NONCE_CHECK_INTERVAL)
+            {
+                return;
+            }
+            state.last_check = Some(Instant::now());
+        }
+
+        let (mined, pending) = match get_nonces(provider, sender).await {
+            Ok(nonces) => nonces,
+            Err(err) => {
+                debug!(?sender, ?err, "Failed to get nonces of the signer");
+                return;
+            }
+        };
+
+        let now = Instant::now();
+        let replacement = {
+            let mut state = self.state.lock().expect("nonce state lock poisoned");
+            let replacement = state.assess(mined, pending, now);
+            let stuck_for = state
+                .stuck
+                .map(|stuck| now.saturating_duration_since(stuck.since))
+                .unwrap_or_default();
+            self.stuck_nonce_seconds
+                .with_label_values(&[&self.chain])
+                .set(stuck_for.as_secs() as i64);
+            replacement
+        };
+        if let Some(replacement) = replacement {
+            self.send_replacement(provider, sender, replacement).await;
+        }
+    }
+
+    async fn send_replacement<M>(&self, provider: &M, sender: H160, replacement: NonceReplacement)
+    where
+        M: Middleware + 'static,
+    {
+        let gas_price = match provider.get_gas_price().await {
+            Ok(gas_price) => gas_price,
+            Err(err) => {
+                warn!(?err, "Failed to get gas price to replace stuck nonce");
+                return;
+            }
+        };
+        let fees = match replacement.fees {
+            Some(fees) => fees.bumped(gas_price),
+            None => TxFees::Legacy {
+                gas_price: bump(
+                    gas_price,
+                    REPLACEMENT_FEE_BUMP_PERCENT * replacement.attempt as u64,
+                ),
+            },
+        };
+
+        warn!(
+            ?sender,
+            nonce = ?replacement.nonce,
+            attempt = replacement.attempt,
+            reason = replacement.reason,
+            ?fees,
+            "Replacing stuck nonce"
+        );
+        self.replacements
+            .with_label_values(&[&self.chain, replacement.reason])
+            .inc();
+        match provider
+            .send_transaction(fees.self_transfer(sender, replacement.nonce), None)
+            .await
+        {
+            Ok(pending) => {
+                info!(tx_hash = ?*pending, "Sent nonce replacement");
+                self.state
+                    .lock()
+                    .expect("nonce state lock poisoned")
+                    .record_sent(replacement.nonce, Some(fees));
+            }
+            Err(err) => warn!(?err, "Failed to send nonce replacement"),
+        }
+    }
+}
+
+impl TxFees {
+    fn of(tx: &Transaction) -> Option<Self> {
+        match (
+            tx.max_fee_per_gas,
+            tx.max_priority_fee_per_gas,
+            tx.gas_price,
+        ) {
+            (Some(max_fee), Some(max_priority_fee), _) => Some(Self::Eip1559 {
+                max_fee,
+                max_priority_fee,
+            }),
+            (_, _, Some(gas_price)) => Some(Self::Legacy { gas_price }),
+            _ => None,
+        }
+    }
+
+    /// The fees of a transaction replacing one with these fees, paying at
+    /// least the current gas price
+    fn bumped(self, gas_price: EthersU256) -> Self {
+        match self {
+            Self::Legacy { gas_price: sent } => Self::Legacy {
+                gas_price: bump(sent, REPLACEMENT_FEE_BUMP_PERCENT).max(gas_price),
+            },
+            Self::Eip1559 {
+                max_fee,
+                max_priority_fee,
+            } => {
+                let max_priority_fee = bump(max_priority_fee, REPLACEMENT_FEE_BUMP_PERCENT);
+                Self::Eip1559 {
+                    max_fee: bump(max_fee, REPLACEMENT_FEE_BUMP_PERCENT)
+                        .max(gas_price)
+                        .max(max_priority_fee),
+                    max_priority_fee,
+                }
+            }
+        }
+    }
+
+    /// A transfer of nothing from the sender to itself with these fees
+    fn self_transfer(self, sender: H160, nonce: EthersU256) -> TypedTransaction {
+        match self {
+            Self::Legacy { gas_price } => TransactionRequest::new()
+                .from(sender)
+                .to(sender)
+                .value(0)
+                .nonce(nonce)
+                .gas(SELF_TRANSFER_GAS)
+                .gas_price(gas_price)
+                .into(),
+            Self::Eip1559 {
+                max_fee,
+                max_priority_fee,
+            } => Eip1559TransactionRequest::new()
+                .from(sender)
+                .to(sender)
+                .value(0)
+                .nonce(nonce)
+                .gas(SELF_TRANSFER_GAS)
+                .max_fee_per_gas(max_fee)
+                .max_priority_fee_per_gas(max_priority_fee)
+                .into(),
+        }
+    }
+}
+
+/// Raises the fee by `percent`, rounding up
+fn bump(fee: EthersU256, percent: u64) -> EthersU256 {
+    let (bumped, remainder) = fee
+        .saturating_mul((100 + percent).into())
+        .div_mod(100.into());
+    if remainder.is_zero() {
+        bumped
+    } else {
+        bumped + 1
+    }
+}
+
+/// The number of mined transactions of the sender, and the number including
+/// the transactions pending in the node's mempool
+async fn get_nonces<M: Middleware>(
+    provider: &M,
+    sender: H160,
+) -> Result<(EthersU256, EthersU256), M::Error> {
+    let mined = provider
+        .get_transaction_count(sender, Some(BlockNumber::Latest.into()))
+        .await?;
+    let pending = provider
+        .get_transaction_count(sender, Some(BlockNumber::Pending.into()))
+        .await?;
+    Ok((mined, pending))
+}
+
+impl NonceState {
+    fn record_sent(&mut self, nonce: EthersU256, fees: Option<TxFees>) {
+        self.highest_sent = self.highest_sent.max(Some(nonce));
+        if let Some(fees) = fees {
+            self.sent_fees.insert(nonce, fees);
+        }
+    }
+
+    /// Updates how long the lowest unmined nonce has been stuck, given the
+    /// number of mined transactions of the signer and the number including
+    /// the transactions pending in the node's mempool. Returns the
+    /// replacement to send, if any.
+    fn assess(
+        &mut self,
+        mined: EthersU256,
+        pending: EthersU256,
+        now: Instant,
+    ) -> Option<NonceReplacement> {
+        // Transactions pending in the mempool were sent by the signer, even
+        // before a restart
+        self.highest_sent = self.highest_sent.max(pending.checked_sub(1.into()));
+        self.sent_fees = self.sent_fees.split_off(&mined);
+
+        let reason = if pending > mined {
+            // A transaction with the lowest unmined nonce is in the mempool
+            REASON_STUCK
+        } else if self.highest_sent.is_some_and(|highest| highest >= mined) {
+            // A transaction was sent with this nonce or a higher one, but
+            // none is pending with this nonce
+            REASON_GAP
+        } else {
+            self.stuck = None;
+            return None;
+        };
+
+        if !self.stuck.is_some_and(|stuck| stuck.nonce == mined) {
+            self.stuck = Some(StuckNonce {
+                nonce: mined,
+                since: now,
+                replacements: 0,
+                last_replacement: None,
+            });
+        }
+        let stuck = self.stuck.as_mut()?;
+        let waited_since = stuck.last_replacement.unwrap_or(stuck.since);
+        if now.saturating_duration_since(waited_since) < STUCK_NONCE_THRESHOLD {
+            return None;
+        }
+        if stuck.replacements >= MAX_REPLACEMENTS {
+            warn!(
+                nonce = ?stuck.nonce,
+                replacements = stuck.replacements,
+                "Nonce is still stuck after the maximum number of replacements"
+            );
+            return None;
+        }
+        stuck.replacements += 1;
+        stuck.last_replacement = Some(now);
+        Some(NonceReplacement {
+            nonce: stuck.nonce,
+            attempt: stuck.replacements,
+            reason,
+            fees: self.sent_fees.get(&stuck.nonce).copied(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::providers::{MockProvider, Provider};
+    use ethers_core::types::H256;
+    use prometheus::opts;
+
+    use super::*;
+
+    #[test]
+    fn test_healthy_nonces_arent_replaced() {
+        let mut state = NonceState::default();
+        let now = Instant::now();
+        assert_eq!(state.assess(5.into(), 5.into(), now), None);
+
+        // Transactions that get mined before the threshold aren't replaced
+        state.highest_sent = Some(5.into());
+        assert_eq!(state.assess(5.into(), 6.into(), now), None);
+        let later = now + STUCK_NONCE_THRESHOLD;
+        assert_eq!(state.assess(6.into(), 6.into(), later), None);
+        assert_eq!(state.stuck, None);
+    }
+
+    #[test]
+    fn test_stuck_nonce_is_replaced_with_increasing_attempts() {
+        let mut state = NonceState::default();
+        let start = Instant::now();
+        assert_eq!(state.assess(5.into(), 7.into(), start), None);
+
+        let first = start + STUCK_NONCE_THRESHOLD;
+        assert_eq!(
+            state.assess(5.into(), 7.into(), first),
+            Some(NonceReplacement {
+                nonce: 5.into(),
+                attempt: 1,
+                reason: REASON_STUCK,
+                fees: None,
+            })
+        );
+        // The replacement gets time to be mined before the next one
+        assert_eq!(
+            state.assess(5.into(), 7.into(), first + NONCE_CHECK_INTERVAL),
+            None
+        );
+        assert_eq!(
+            state
+                .assess(5.into(), 7.into(), first + STUCK_NONCE_THRESHOLD)
+                .map(|replacement| replacement.attempt),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_nonce_gap_is_filled() {
+        let mut state = NonceState::default();
+        // The transaction with nonce 5 was dropped, so the one with nonce 6
+        // can't be mined
+        state.highest_sent = Some(6.into());
+        let start = Instant::now();
+        assert_eq!(state.assess(5.into(), 5.into(), start), None);
+        assert_eq!(
+            state.assess(5.into(), 5.into(), start + STUCK_NONCE_THRESHOLD),
+            Some(NonceReplacement {
+                nonce: 5.into(),
+                attempt: 1,
+                reason: REASON_GAP,
+                fees: None,
+            })
+        );
+
+        // Once the gap is filled, both get mined
+        assert_eq!(
+            state.assess(7.into(), 7.into(), start + STUCK_NONCE_THRESHOLD * 2),
+            None
+        );
+        assert_eq!(state.stuck, None);
+    }
+
+    #[test]
+    fn test_replacement_fees_are_bumped() {
+        let sent = TxFees::Eip1559 {
+            max_fee: 100.into(),
+            max_priority_fee: 10.into(),
+        };
+        assert_eq!(
+            sent.bumped(120.into()),
+            TxFees::Eip1559 {
+                max_fee: 150.into(),
+                max_priority_fee: 15.into(),
+            }
+        );
+        // The current gas price is paid at least
+        assert_eq!(
+            sent.bumped(200.into()),
+            TxFees::Eip1559 {
+                max_fee: 200.into(),
+                max_priority_fee: 15.into(),
+            }
+        );
+        assert_eq!(
+            TxFees::Legacy {
+                gas_price: 101.into()
+            }
+            .bumped(0.into()),
+            TxFees::Legacy {
+                gas_price: 152.into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replacement_is_sent_with_bumped_fees() {
+        let mock_provider = MockProvider::new();
+        let provider = Provider::new(mock_provider.clone());
+        let monitor = NonceMonitor::new(
+            "ethereum".to_owned(),
+            IntGaugeVec::new(
+                opts!("stuck_nonce_seconds", "help"),
+                STUCK_NONCE_SECONDS_LABELS,
+            )
+            .unwrap(),
+            IntCounterVec::new(
+                opts!("nonce_replacements", "help"),
+                NONCE_REPLACEMENTS_TOTAL_LABELS,
+            )
+            .unwrap(),
+        );
+        let sender = H160::from_low_u64_be(1);
+        let sent = TxFees::Eip1559 {
+            max_fee: 100.into(),
+            max_priority_fee: 10.into(),
+        };
+        monitor
+            .state
+            .lock()
+            .unwrap()
+            .record_sent(5.into(), Some(sent));
+
+        // The MockProvider responses we push are processed in LIFO order, and
+        // the gas price is fetched before sending the replacement
+        mock_provider.push(H256::from_low_u64_be(2)).unwrap();
+        mock_provider.push(EthersU256::from(120)).unwrap();
+        monitor
+            .send_replacement(
+                &provider,
+                sender,
+                NonceReplacement {
+                    nonce: 5.into(),
+                    attempt: 1,
+                    reason: REASON_STUCK,
+                    fees: Some(sent),
+                },
+            )
+            .await;
+
+        let replacement = TxFees::Eip1559 {
+            max_fee: 150.into(),
+            max_priority_fee: 15.into(),
+        };
+        mock_provider.assert_request("eth_gasPrice", ()).unwrap();
+        mock_provider
+            .assert_request(
+                "eth_sendTransaction",
+                [replacement.self_transfer(sender, 5.into())],
+            )
+            .unwrap();
+        // A further replacement bumps the fees of this one
+        assert_eq!(
+            monitor.state.lock().unwrap().sent_fees.get(&5.into()),
+            Some(&replacement)
+        );
+        assert_eq!(
+            monitor
+                .replacements
+                .with_label_values(&["ethereum", REASON_STUCK])
+                .get(),
+            1
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ethers::abi::{self, Detokenize, ParamType, Token};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Bytes, NameOrAddress, TransactionRequest, H160 as EthersH160};
+use ethers_contract::builders::ContractCall;
+use hyperlane_core::{utils::hex_or_base58_to_h256, ChainCommunicationError, ChainResult, H256};
+use tokio::sync::oneshot;
+use tracing::{debug, trace};
+
+use crate::{Middleware, MULTICALL3_ADDRESS};
+
+/// How long the first read of a batch waits for others to join it
+const BATCH_WINDOW: Duration = Duration::from_millis(10);
+/// Most reads made in a single `aggregate3` call
+const MAX_BATCH_SIZE: usize = 100;
+/// Selector of Multicall3's `aggregate3((address,bool,bytes)[])`
+const AGGREGATE3_SELECTOR: [u8; 4] = [0x82, 0xad, 0x56, 0xcb];
+
+/// Batches the contract reads of a chain made at about the same time into a
+/// single `eth_call` to Multicall3's `aggregate3`.
+///
+/// The metadata of a message takes many small reads, e.g. of the ISMs'
+/// validators and routes and of whether the message was delivered, and the
+/// relayer builds the metadata of many messages concurrently. The first read
+/// queued starts a batch, which is sent once the reads made in the next few
+/// milliseconds joined it. A read that reverts, or whose batch fails, is
+/// made again on its own, so that it fails the way it would unbatched.
+/// Clones share the same batches.
+#[derive(Debug, Clone)]
+pub struct ReadBatcher {
+    multicall_address: EthersH160,
+    queued: Arc<Mutex<Vec<QueuedRead>>>,
+}
+
+#[derive(Debug)]
+struct QueuedRead {
+    target: EthersH160,
+    data: Bytes,
+    /// The output of the read, or None if it must be made on its own
+    output: oneshot::Sender<Option<Bytes>>,
+}
+
+impl ReadBatcher {
+    /// Batch the reads with the Multicall3 contract at `multicall_address`,
+    /// or at its usual address if unset
+    pub fn new(multicall_address: Option<H256>) -> Self {
+        let multicall_address =
+            multicall_address.unwrap_or_else(|| hex_or_base58_to_h256(MULTICALL3_ADDRESS).unwrap());
+        Self {
+            multicall_address: multicall_address.into(),
+            queued: Default::default(),
+        }
+    }
+
+    /// Makes the read `call` in a batch. Reads at a specific block aren't
+    /// batched.
+    pub(crate) async fn call<M, D>(
+        &self,
+        provider: Arc<M>,
+        call: ContractCall<M, D>,
+    ) -> ChainResult<D>
+    where
+        M: Middleware + 'static,
+        D: Detokenize,
+    {
+        let (Some(NameOrAddress::Address(target)), Some(data), None) =
+            (call.tx.to(), call.tx.data(), call.block)
+        else {
+            return Ok(call.call().await?);
+        };
+        let (sender, receiver) = oneshot::channel();
+        let starts_batch = {
+            let mut queued = self.queued.lock().expect("read batcher lock poisoned");
+            queued.push(QueuedRead {
+                target: *target,
+                data: data.clone(),
+                output: sender,
+            });
+            queued.len() == 1
+        };
+        if starts_batch {
+            // Spawned so that the batch is sent even if this read is dropped
+            tokio::spawn(self.clone().send_batches(provider));
+        }
+        match receiver.await {
+            Ok(Some(output)) => decode_output(&call, &output),
+            _ => Ok(call.call().await?),
+        }
+    }
+
+    /// Sends the queued reads once the batch window elapsed, until none are
+    /// left
+    async fn send_batches<M: Middleware + 'static>(self, provider: Arc<M>) {
+        tokio::time::sleep(BATCH_WINDOW).await;
+        loop {
+            let batch: Vec<_> = {
+                let mut queued = self.queued.lock().expect("read batcher lock poisoned");
+                let len = queued.len().min(MAX_BATCH_SIZE);
+                queued.drain(..len).collect()
+            };
+            if batch.is_empty() {
+                return;
+            }
+            trace!(reads = batch.len(), "Sending batched reads");
+            let outputs = self
+                .aggregate(&provider, &batch)
+                .await
+                .unwrap_or_else(|err| {
+                    debug!(?err, "Batched reads failed, making them on their own");
+                    vec![None; batch.len()]
+                });
+            for (read, output) in batch.into_iter().zip(outputs) {
+                // The read may have been dropped
+                let _ = read.output.send(output);
+            }
+        }
+    }
+
+    async fn aggregate<M: Middleware + 'static>(
+        &self,
+        provider: &M,
+        batch: &[QueuedRead],
+    ) -> ChainResult<Vec<Option<Bytes>>> {
+        let calls = batch
+            .iter()
+            .map(|read| {
+                Token::Tuple(vec![
+                    Token::Address(read.target),
+                    // Allow failures, so that one read can't fail the others
+                    Token::Bool(true),
+                    Token::Bytes(read.data.to_vec()),
+                ])
+            })
+            .collect();
+        let mut data = AGGREGATE3_SELECTOR.to_vec();
+        data.extend(abi::encode(&[Token::Array(calls)]));
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(self.multicall_address)
+            .data(data)
+            .into();
+        let output = provider
+            .call(&tx, None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        decode_aggregate3_output(&output, batch.len())
+    }
+}
+
+/// Makes the read `call` on `provider`, in a batch if there's a batcher
+pub(crate) async fn batched_call<M, D>(
+    batcher: Option<&ReadBatcher>,
+    provider: Arc<M>,
+    call: ContractCall<M, D>,
+) -> ChainResult<D>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    match batcher {
+        Some(batcher) => batcher.call(provider, call).await,
+        None => Ok(call.call().await?),
+    }
+}
+
+/// The outputs of the reads that succeeded
+fn decode_aggregate3_output(output: &[u8], len: usize) -> ChainResult<Vec<Option<Bytes>>> {
+    let result_type = ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes]);
+    let results = match abi::decode(&[ParamType::Array(Box::new(result_type))], output)
+        .map_err(ChainCommunicationError::from_other)?
+        .pop()
+    {
+        Some(Token::Array(results)) if results.len() == len => results,
+        _ => {
+            return Err(ChainCommunicationError::from_other_str(
+                "Unexpected output of aggregate3",
+            ))
+        }
+    };
+    Ok(results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(result) => match result.as_slice() {
+                [Token::Bool(true), Token::Bytes(output)] => Some(output.clone().into()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect())
+}
+
+fn decode_output<M, D: Detokenize>(call: &ContractCall<M, D>, output: &[u8]) -> ChainResult<D> {
+    let tokens = call
+        .function
+        .decode_output(output)
+        .map_err(ChainCommunicationError::from_other)?;
+    D::from_tokens(tokens).map_err(ChainCommunicationError::from_other)
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::providers::{MockProvider, Provider};
+
+    use crate::interfaces::i_mailbox::IMailbox;
+
+    use super::*;
+
+    fn aggregate3_output(results: Vec<(bool, Vec<u8>)>) -> Bytes {
+        let results = results
+            .into_iter()
+            .map(|(success, output)| Token::Tuple(vec![Token::Bool(success), Token::Bytes(output)]))
+            .collect();
+        abi::encode(&[Token::Array(results)]).into()
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reads_are_batched() {
+        let mock_provider = MockProvider::new();
+        let provider = Arc::new(Provider::new(mock_provider.clone()));
+        let mailbox = IMailbox::new(EthersH160::from_low_u64_be(1), provider.clone());
+        let batcher = ReadBatcher::new(Some(H256::from_low_u64_be(2)));
+
+        // A single response for both reads, any other request fails
+        mock_provider
+            .push(aggregate3_output(vec![
+                (true, abi::encode(&[Token::Bool(true)])),
+                (true, abi::encode(&[Token::Bool(false)])),
+            ]))
+            .unwrap();
+        let (first, second) = tokio::join!(
+            batcher.call(provider.clone(), mailbox.delivered([1; 32])),
+            batcher.call(provider.clone(), mailbox.delivered([2; 32])),
+        );
+        assert!(first.unwrap());
+        assert!(!second.unwrap());
+    }
+
+    #[test]
+    fn test_failed_reads_are_made_on_their_own() {
+        let output = aggregate3_output(vec![
+            (false, vec![]),
+            (true, abi::encode(&[Token::Uint(7.into())])),
+        ]);
+        assert_eq!(
+            decode_aggregate3_output(&output, 2).unwrap(),
+            vec![None, Some(abi::encode(&[Token::Uint(7.into())]).into())]
+        );
+        assert!(decode_aggregate3_output(&output, 3).is_err());
+    }
+}
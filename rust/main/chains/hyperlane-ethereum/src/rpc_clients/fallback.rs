@@ -145,8 +145,12 @@ where
             // future which visits all providers as they fulfill their requests
             let mut unordered = self.populate_unordered_future(method, &params);
 
-            while let Some(resp) = unordered.next().await {
-                let value = match categorize_client_response(method, resp) {
+            while let Some((provider, resp)) = unordered.next().await {
+                let categorized = categorize_client_response(method, resp);
+                if let Some(error_type) = categorized.error_type() {
+                    provider.record_request_error(method, error_type);
+                }
+                let value = match categorized {
                     IsOk(v) => serde_json::from_value(v)?,
                     RetryableErr(e) | RateLimitErr(e) => {
                         retryable_errors.push(e.into());
@@ -203,7 +207,11 @@ where
                 let _span =
                     warn_span!("fallback_request", fallback_count=%idx, provider_index=%priority.index, ?provider).entered();
 
-                match categorize_client_response(method, resp) {
+                let categorized = categorize_client_response(method, resp);
+                if let Some(error_type) = categorized.error_type() {
+                    provider.record_request_error(method, error_type);
+                }
+                match categorized {
                     IsOk(v) => return Ok(serde_json::from_value(v)?),
                     RetryableErr(e) | RateLimitErr(e) => errors.push(e.into()),
                     NonRetryableErr(e) => return Err(e.into()),
@@ -229,12 +237,12 @@ where
         &'a self,
         method: &'a str,
         params: &'a Value,
-    ) -> FuturesUnordered<impl Future<Output = Result<Value, HttpClientError>> + Sized + '_> {
+    ) -> FuturesUnordered<impl Future<Output = (&'a C, Result<Value, HttpClientError>)> + Sized + '_>
+    {
         let unordered = FuturesUnordered::new();
-        self.inner
-            .providers
-            .iter()
-            .for_each(|p| unordered.push(Self::provider_request(p, method, params)));
+        self.inner.providers.iter().for_each(|p| {
+            unordered.push(async move { (p, Self::provider_request(p, method, params).await) })
+        });
         unordered
     }
 }
@@ -19,6 +19,18 @@ enum CategorizedResponse<R> {
     NonRetryableErr(HttpClientError),
 }
 
+impl<R> CategorizedResponse<R> {
+    /// The `error_type` label to record for this response, if it is an error
+    fn error_type(&self) -> Option<&'static str> {
+        match self {
+            CategorizedResponse::IsOk(_) => None,
+            CategorizedResponse::RetryableErr(_) => Some("retryable"),
+            CategorizedResponse::RateLimitErr(_) => Some("rate_limited"),
+            CategorizedResponse::NonRetryableErr(_) => Some("non_retryable"),
+        }
+    }
+}
+
 const METHODS_TO_NOT_RETRY: &[&str] = &["eth_estimateGas"];
 const METHOD_TO_NOT_RETRY_WHEN_NOT_SUPPORTED: &[&str] = &["eth_feeHistory"];
 const METHODS_TO_NOT_RETRY_ON_REVERT: &[&str] =
@@ -6,6 +6,7 @@ use std::time::Duration;
 use async_trait::async_trait;
 use derive_new::new;
 use ethers::prelude::Middleware;
+use ethers::utils::keccak256;
 use ethers_core::{abi::Address, types::BlockNumber};
 use hyperlane_core::{ethers_core_types, ChainInfo, HyperlaneCustomErrorWrapper, H512, U256};
 use tokio::time::sleep;
@@ -133,6 +134,17 @@ where
         Ok(!code.is_empty())
     }
 
+    #[instrument(err, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn get_code_hash(&self, address: &H256) -> ChainResult<Option<H256>> {
+        let code = self
+            .provider
+            .get_code(ethers_core_types::H160::from(*address), None)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(Some(keccak256(&code).into()))
+    }
+
     #[instrument(err, skip(self))]
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
     async fn get_balance(&self, address: String) -> ChainResult<U256> {
@@ -187,7 +187,11 @@ impl JsonRpcClient for RetryingProvider<PrometheusJsonRpcClient<Http>> {
             )
             .entered();
 
-            match categorize_client_response(method, res) {
+            let categorized = categorize_client_response(method, res);
+            if let Some(error_type) = categorized.error_type() {
+                self.inner.record_request_error(method, error_type);
+            }
+            match categorized {
                 IsOk(res) => Accept(res),
                 RetryableErr(e) => Retry(e),
                 RateLimitErr(e) => RateLimitedRetry(e),
@@ -87,12 +87,14 @@ pub trait BuildableWithProvider {
                     // RPCs being retried, while retrying at the inner provider
                     // level will result in only the second RPC being retried
                     // (the one with the error), which is the desired behavior.
-                    let metrics_provider = self.wrap_rpc_with_metrics(
-                        http_provider,
-                        url.clone(),
-                        &client_metrics,
-                        &middleware_metrics,
-                    );
+                    let metrics_provider = self
+                        .wrap_rpc_with_metrics(
+                            http_provider,
+                            url.clone(),
+                            &client_metrics,
+                            &middleware_metrics,
+                        )
+                        .with_rate_limiter(conn.rpc_rate_limiter.clone());
                     let retrying_provider =
                         RetryingProvider::new(metrics_provider, Some(5), Some(1000));
                     let weighted_provider = WeightedProvider::new(retrying_provider);
@@ -105,12 +107,14 @@ pub trait BuildableWithProvider {
                 let mut builder = FallbackProvider::builder();
                 for url in urls {
                     let http_provider = build_http_provider(url.clone())?;
-                    let metrics_provider = self.wrap_rpc_with_metrics(
-                        http_provider,
-                        url.clone(),
-                        &client_metrics,
-                        &middleware_metrics,
-                    );
+                    let metrics_provider = self
+                        .wrap_rpc_with_metrics(
+                            http_provider,
+                            url.clone(),
+                            &client_metrics,
+                            &middleware_metrics,
+                        )
+                        .with_rate_limiter(conn.rpc_rate_limiter.clone());
                     builder = builder.add_provider(metrics_provider);
                 }
                 let fallback_provider = builder.build();
@@ -123,12 +127,14 @@ pub trait BuildableWithProvider {
             }
             RpcConnectionConf::Http { url } => {
                 let http_provider = build_http_provider(url.clone())?;
-                let metrics_provider = self.wrap_rpc_with_metrics(
-                    http_provider,
-                    url.clone(),
-                    &client_metrics,
-                    &middleware_metrics,
-                );
+                let metrics_provider = self
+                    .wrap_rpc_with_metrics(
+                        http_provider,
+                        url.clone(),
+                        &client_metrics,
+                        &middleware_metrics,
+                    )
+                    .with_rate_limiter(conn.rpc_rate_limiter.clone());
                 let retrying_http_provider = RetryingProvider::new(metrics_provider, None, None);
                 self.build(retrying_http_provider, conn, locator, signer)
                     .await?
@@ -0,0 +1,466 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use ethers::prelude::{Address, Signature, H256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::keccak256;
+use ethers_signers::{to_eip155_v, Signer};
+use reqwest::{Client, Url};
+use serde_json::json;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use hyperlane_core::H160;
+
+/// Path of the health check endpoint exposed by the signing service
+const HEALTH_CHECK_PATH: &str = "upcheck";
+/// Header carrying the unix timestamp (in seconds) a request was signed at
+const TIMESTAMP_HEADER: &str = "X-Hyperlane-Timestamp";
+/// Header carrying the HMAC of a request
+const REQUEST_SIGNATURE_HEADER: &str = "X-Hyperlane-Signature";
+/// Block size of keccak256, used to build the HMAC
+const KECCAK_BLOCK_SIZE: usize = 136;
+/// Timeout for a single request to a signing service endpoint
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors returned by the remote signer
+#[derive(Debug, Error)]
+pub enum RemoteSignerError {
+    /// None of the configured endpoints could produce a signature
+    #[error("All remote signer endpoints failed, last error: {0}")]
+    AllEndpointsFailed(String),
+    /// The signing service returned a signature for a different address
+    #[error("Remote signer returned a signature for {recovered:?}, expected {expected:?}")]
+    UnexpectedSigner {
+        /// Address the signer was configured with
+        expected: Address,
+        /// Address recovered from the returned signature
+        recovered: Address,
+    },
+    /// The signing service response could not be parsed
+    #[error("Invalid signature returned by remote signer: {0}")]
+    InvalidSignature(String),
+    /// EIP-712 encoding of the payload failed
+    #[error("Failed to encode EIP-712 payload: {0}")]
+    Eip712(String),
+    /// Request to the signing service failed
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// The remote signer was configured without any endpoints
+    #[error("Remote signer requires at least one endpoint")]
+    NoEndpoints,
+    /// An endpoint url could not be extended with the API path
+    #[error("Invalid remote signer endpoint url: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+}
+
+/// A signer that delegates signing to a remote (e.g. threshold ECDSA) signing
+/// service, so that keys don't have to live on the agent host.
+///
+/// The service is expected to expose web3signer's API:
+/// - `POST {url}/api/v1/eth1/sign/{address}` with a `{"data": "0x.."}` body,
+///   returning the 65-byte signature of the keccak256 hash of the data as a
+///   hex string. The data is the pre-image of the digest to sign, e.g. the
+///   EIP-191 prefixed message or the RLP encoded transaction.
+/// - `GET {url}/upcheck`, returning a 2xx status when the service is healthy.
+///
+/// Endpoint urls may have a path prefix, e.g. of a proxy in front of the
+/// service, which the API paths are appended to.
+///
+/// Endpoints are tried in order, starting from the last one that succeeded, so
+/// an unhealthy endpoint fails over to the next. Every signature is checked to
+/// recover to the configured address before it's used.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    address: Address,
+    chain_id: u64,
+    endpoints: Arc<Vec<Url>>,
+    /// Index of the endpoint that last served a request successfully
+    preferred_endpoint: Arc<AtomicUsize>,
+    /// Optional shared secret used to sign each request with an HMAC
+    request_signing_key: Option<Arc<Vec<u8>>>,
+    client: Client,
+}
+
+impl fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // intentionally leaves out the request signing key
+        f.debug_struct("RemoteSigner")
+            .field("address", &self.address)
+            .field("chain_id", &self.chain_id)
+            .field("endpoints", &self.endpoints)
+            .finish()
+    }
+}
+
+impl RemoteSigner {
+    /// Create a new remote signer for `address`, backed by the given endpoints.
+    pub fn new(
+        address: H160,
+        endpoints: Vec<Url>,
+        request_signing_key: Option<Vec<u8>>,
+    ) -> Result<Self, RemoteSignerError> {
+        if endpoints.is_empty() {
+            return Err(RemoteSignerError::NoEndpoints);
+        }
+        let client = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        let endpoints = endpoints.into_iter().map(with_trailing_slash).collect();
+        Ok(Self {
+            address: address.into(),
+            chain_id: 1,
+            endpoints: Arc::new(endpoints),
+            preferred_endpoint: Arc::new(AtomicUsize::new(0)),
+            request_signing_key: request_signing_key.map(Arc::new),
+            client,
+        })
+    }
+
+    /// Check the health of every endpoint, returning the endpoints that are
+    /// unhealthy. If the currently preferred endpoint is unhealthy, the first
+    /// healthy one becomes preferred.
+    pub async fn check_health(&self) -> Vec<(Url, String)> {
+        let mut unhealthy = vec![];
+        let mut first_healthy = None;
+        for (i, endpoint) in self.endpoints.iter().enumerate() {
+            match self.check_endpoint_health(endpoint).await {
+                Ok(()) => {
+                    first_healthy.get_or_insert(i);
+                }
+                Err(err) => unhealthy.push((endpoint.clone(), err.to_string())),
+            }
+        }
+        let preferred = self.preferred_endpoint.load(Ordering::Relaxed);
+        if let Some(first_healthy) = first_healthy {
+            if unhealthy
+                .iter()
+                .any(|(url, _)| *url == self.endpoints[preferred])
+            {
+                self.preferred_endpoint
+                    .store(first_healthy, Ordering::Relaxed);
+            }
+        }
+        unhealthy
+    }
+
+    async fn check_endpoint_health(&self, endpoint: &Url) -> Result<(), RemoteSignerError> {
+        let url = endpoint.join(HEALTH_CHECK_PATH)?;
+        self.client.get(url).send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    /// Sign the keccak256 hash of `preimage`, returning a signature with `v`
+    /// in {27, 28}
+    pub async fn sign_preimage(&self, preimage: &[u8]) -> Result<Signature, RemoteSignerError> {
+        let digest = H256::from(keccak256(preimage));
+        let body = json!({ "data": format!("0x{}", hex::encode(preimage)) }).to_string();
+        let endpoint_count = self.endpoints.len();
+        let start = self.preferred_endpoint.load(Ordering::Relaxed);
+        let mut last_err = None;
+        for offset in 0..endpoint_count {
+            let i = (start + offset) % endpoint_count;
+            let endpoint = &self.endpoints[i];
+            match self.request_signature(endpoint, body.clone()).await {
+                Ok(signature) => {
+                    if i != start {
+                        debug!(?endpoint, "Remote signer failed over to endpoint");
+                        self.preferred_endpoint.store(i, Ordering::Relaxed);
+                    }
+                    return self.verify(signature, digest);
+                }
+                Err(err) => {
+                    warn!(?endpoint, error = ?err, "Remote signer endpoint failed to sign");
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(RemoteSignerError::AllEndpointsFailed(
+            last_err.map(|e| e.to_string()).unwrap_or_default(),
+        ))
+    }
+
+    async fn request_signature(
+        &self,
+        endpoint: &Url,
+        body: String,
+    ) -> Result<Signature, RemoteSignerError> {
+        let url = endpoint.join(&format!("api/v1/eth1/sign/{:?}", self.address))?;
+        let mut request = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(key) = &self.request_signing_key {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+                .to_string();
+            let mac = hmac_keccak256(key, format!("{timestamp}.{body}").as_bytes());
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp)
+                .header(REQUEST_SIGNATURE_HEADER, hex::encode(mac));
+        }
+        let response = request.body(body).send().await?.error_for_status()?;
+        let text = response.text().await?;
+        let signature = text.trim().trim_matches('"');
+        Signature::from_str(signature)
+            .map_err(|err| RemoteSignerError::InvalidSignature(err.to_string()))
+    }
+
+    /// Normalize `v` and make sure the signature was produced by the expected key
+    fn verify(
+        &self,
+        mut signature: Signature,
+        digest: H256,
+    ) -> Result<Signature, RemoteSignerError> {
+        if signature.v < 27 {
+            signature.v += 27;
+        }
+        let recovered = signature
+            .recover(digest)
+            .map_err(|err| RemoteSignerError::InvalidSignature(err.to_string()))?;
+        if recovered != self.address {
+            return Err(RemoteSignerError::UnexpectedSigner {
+                expected: self.address,
+                recovered,
+            });
+        }
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_preimage(&eip191_preimage(message.as_ref())).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        // rlp (for sighash) must have the same chain id as v in the signature
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or(self.chain_id);
+        let mut tx = tx.clone();
+        tx.set_chain_id(chain_id);
+
+        let mut signature = self.sign_preimage(&tx.rlp()).await?;
+        signature.v = to_eip155_v(signature.v as u8 - 27, chain_id);
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let preimage =
+            eip712_preimage(payload).map_err(|err| RemoteSignerError::Eip712(err.to_string()))?;
+        self.sign_preimage(&preimage).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+/// Makes `url` a base the API paths can be joined onto without dropping the
+/// last segment of its path
+fn with_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
+}
+
+/// The EIP-191 prefixed message, whose hash `Signer::sign_message` signs
+fn eip191_preimage(message: &[u8]) -> Vec<u8> {
+    let mut preimage = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    preimage.extend_from_slice(message);
+    preimage
+}
+
+/// The encoding of an EIP-712 payload, whose hash `Signer::sign_typed_data`
+/// signs
+fn eip712_preimage<T: Eip712>(payload: &T) -> Result<Vec<u8>, T::Error> {
+    let mut preimage = vec![0x19, 0x01];
+    preimage.extend_from_slice(&payload.domain_separator()?);
+    preimage.extend_from_slice(&payload.struct_hash()?);
+    Ok(preimage)
+}
+
+/// HMAC (RFC 2104) using keccak256 as the underlying hash function
+fn hmac_keccak256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block_key = [0u8; KECCAK_BLOCK_SIZE];
+    if key.len() > KECCAK_BLOCK_SIZE {
+        block_key[..32].copy_from_slice(&keccak256(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = block_key.map(|b| b ^ 0x36).to_vec();
+    inner.extend_from_slice(message);
+    let mut outer = block_key.map(|b| b ^ 0x5c).to_vec();
+    outer.extend_from_slice(&keccak256(inner));
+    keccak256(outer)
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+    use ethers::types::{transaction::eip712::TypedData, TransactionRequest};
+    use ethers::utils::hash_message;
+
+    use super::*;
+
+    /// Signs like web3signer's `eth1/sign` endpoint, which signs the keccak256
+    /// hash of the data it's given
+    fn web3signer_sign(wallet: &LocalWallet, data: &[u8]) -> Signature {
+        wallet.sign_hash(keccak256(data).into()).unwrap()
+    }
+
+    #[test]
+    fn test_preimages_match_local_signatures() {
+        let t = async {
+            let wallet: LocalWallet =
+                "1111111111111111111111111111111111111111111111111111111111111111"
+                    .parse()
+                    .unwrap();
+
+            let message = b"hello";
+            assert_eq!(
+                keccak256(eip191_preimage(message)),
+                hash_message(message).to_fixed_bytes()
+            );
+            assert_eq!(
+                web3signer_sign(&wallet, &eip191_preimage(message)),
+                wallet.sign_message(message).await.unwrap()
+            );
+
+            let tx: TypedTransaction = TransactionRequest::new()
+                .to(Address::repeat_byte(1))
+                .value(1000)
+                .nonce(3)
+                .gas(21000)
+                .gas_price(1)
+                .chain_id(1)
+                .into();
+            let mut signature = web3signer_sign(&wallet, &tx.rlp());
+            signature.v = to_eip155_v(signature.v as u8 - 27, 1);
+            assert_eq!(signature, wallet.sign_transaction(&tx).await.unwrap());
+
+            let typed_data: TypedData = serde_json::from_value(json!({
+                "types": {
+                    "EIP712Domain": [{ "name": "name", "type": "string" }],
+                    "Mail": [{ "name": "contents", "type": "string" }]
+                },
+                "primaryType": "Mail",
+                "domain": { "name": "Hyperlane" },
+                "message": { "contents": "hello" }
+            }))
+            .unwrap();
+            assert_eq!(
+                keccak256(eip712_preimage(&typed_data).unwrap()),
+                typed_data.encode_eip712().unwrap()
+            );
+        };
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(t)
+    }
+
+    #[test]
+    fn test_endpoint_path_prefixes_are_kept() {
+        let signer = RemoteSigner::new(
+            Address::repeat_byte(1).into(),
+            vec![
+                Url::parse("http://localhost:9000").unwrap(),
+                Url::parse("http://proxy/web3signer").unwrap(),
+            ],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            signer.endpoints[0].join("upcheck").unwrap().as_str(),
+            "http://localhost:9000/upcheck"
+        );
+        assert_eq!(
+            signer.endpoints[1].join("upcheck").unwrap().as_str(),
+            "http://proxy/web3signer/upcheck"
+        );
+    }
+
+    #[test]
+    fn test_hmac_depends_on_key_and_message() {
+        let mac = hmac_keccak256(b"key", b"message");
+        assert_eq!(mac, hmac_keccak256(b"key", b"message"));
+        assert_ne!(mac, hmac_keccak256(b"other key", b"message"));
+        assert_ne!(mac, hmac_keccak256(b"key", b"other message"));
+        // keys longer than the block size are hashed first
+        let long_key = [7u8; KECCAK_BLOCK_SIZE + 1];
+        assert_eq!(
+            hmac_keccak256(&long_key, b"message"),
+            hmac_keccak256(&keccak256(long_key), b"message")
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_signature_from_unexpected_key() {
+        let t = async {
+            let expected: LocalWallet =
+                "1111111111111111111111111111111111111111111111111111111111111111"
+                    .parse()
+                    .unwrap();
+            let other: LocalWallet =
+                "2222222222222222222222222222222222222222222222222222222222222222"
+                    .parse()
+                    .unwrap();
+            let signer = RemoteSigner::new(
+                expected.address().into(),
+                vec![Url::parse("http://localhost:9000").unwrap()],
+                None,
+            )
+            .unwrap();
+            let message = b"hello";
+            let digest = hash_message(message);
+
+            let mut signature = expected.sign_message(message).await.unwrap();
+            // signing services may return `v` as the raw recovery id
+            signature.v -= 27;
+            let verified = signer.verify(signature, digest).unwrap();
+            assert!(verified.v == 27 || verified.v == 28);
+
+            let signature = other.sign_message(message).await.unwrap();
+            assert!(matches!(
+                signer.verify(signature, digest),
+                Err(RemoteSignerError::UnexpectedSigner { .. })
+            ));
+        };
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(t)
+    }
+}
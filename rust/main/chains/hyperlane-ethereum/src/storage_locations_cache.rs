@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use tokio::sync::Mutex;
+use tracing::trace;
+
+/// Caches the storage locations announced by validators, so that building
+/// the metadata of every message doesn't query the ValidatorAnnounce contract
+/// again for the same validator set.
+///
+/// Locations are cached by validator for up to the TTL. When a validator set
+/// changes, only the validators that aren't cached yet are fetched, in a
+/// single call for all of them. Validators without an announced location
+/// aren't cached, so that their announcements are picked up right away.
+/// Clones share the same cache.
+#[derive(Debug, Clone)]
+pub struct StorageLocationsCache {
+    ttl: Duration,
+    // An async lock, so that concurrent misses wait for a single request
+    cached: Arc<Mutex<HashMap<H256, CachedStorageLocations>>>,
+}
+
+#[derive(Debug)]
+struct CachedStorageLocations {
+    fetched_at: Instant,
+    locations: Vec<String>,
+}
+
+impl CachedStorageLocations {
+    fn is_fresh(&self, ttl: Duration, now: Instant) -> bool {
+        now.saturating_duration_since(self.fetched_at) < ttl
+    }
+}
+
+impl StorageLocationsCache {
+    /// Create an empty cache whose storage locations are used for up to `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cached: Default::default(),
+        }
+    }
+
+    /// How long storage locations are used for at most
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The storage locations announced by each of the validators, in the same
+    /// order. Those that aren't cached are fetched with a single call to
+    /// `fetch`.
+    pub(crate) async fn storage_locations<F, Fut>(
+        &self,
+        validators: &[H256],
+        fetch: F,
+    ) -> ChainResult<Vec<Vec<String>>>
+    where
+        F: FnOnce(Vec<H256>) -> Fut,
+        Fut: Future<Output = ChainResult<Vec<Vec<String>>>>,
+    {
+        let mut cached = self.cached.lock().await;
+        let now = Instant::now();
+        cached.retain(|_, locations| locations.is_fresh(self.ttl, now));
+
+        let mut missing: Vec<H256> = validators
+            .iter()
+            .filter(|validator| !cached.contains_key(validator))
+            .copied()
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        let mut fetched = HashMap::new();
+        if !missing.is_empty() {
+            trace!(
+                cached = validators.len() - missing.len(),
+                missing = missing.len(),
+                "Fetching announced storage locations"
+            );
+            let locations = fetch(missing.clone()).await?;
+            if locations.len() != missing.len() {
+                return Err(ChainCommunicationError::CustomError(format!(
+                    "Expected the storage locations of {} validators, got {}",
+                    missing.len(),
+                    locations.len()
+                )));
+            }
+            fetched = missing.into_iter().zip(locations).collect();
+        }
+
+        let storage_locations = validators
+            .iter()
+            .map(|validator| {
+                fetched
+                    .get(validator)
+                    .or_else(|| cached.get(validator).map(|cached| &cached.locations))
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let fetched_at = Instant::now();
+        cached.extend(
+            fetched
+                .into_iter()
+                .filter(|(_, locations)| !locations.is_empty())
+                .map(|(validator, locations)| {
+                    (
+                        validator,
+                        CachedStorageLocations {
+                            fetched_at,
+                            locations,
+                        },
+                    )
+                }),
+        );
+        Ok(storage_locations)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex as StdMutex;
+
+    use super::*;
+
+    fn location(validator: &H256) -> Vec<String> {
+        vec![format!("s3://bucket/{validator:x}")]
+    }
+
+    #[test]
+    fn test_cached_storage_locations_expire() {
+        let now = Instant::now();
+        let cached = CachedStorageLocations {
+            fetched_at: now,
+            locations: vec![],
+        };
+        let ttl = Duration::from_secs(5);
+        assert!(cached.is_fresh(ttl, now + Duration::from_secs(4)));
+        assert!(!cached.is_fresh(ttl, now + ttl));
+    }
+
+    #[tokio::test]
+    async fn test_only_uncached_validators_are_fetched() {
+        let cache = StorageLocationsCache::new(Duration::from_secs(60));
+        let fetches = StdMutex::new(vec![]);
+        let unannounced = H256::repeat_byte(9);
+        let fetch = |validators: Vec<H256>| {
+            fetches.lock().unwrap().push(validators.clone());
+            async move {
+                Ok(validators
+                    .iter()
+                    .map(|validator| {
+                        if *validator == unannounced {
+                            vec![]
+                        } else {
+                            location(validator)
+                        }
+                    })
+                    .collect())
+            }
+        };
+
+        let set = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        let locations = cache.storage_locations(&set, fetch).await.unwrap();
+        assert_eq!(locations, vec![location(&set[0]), location(&set[1])]);
+        let locations = cache.storage_locations(&set, fetch).await.unwrap();
+        assert_eq!(locations, vec![location(&set[0]), location(&set[1])]);
+        assert_eq!(fetches.lock().unwrap().len(), 1);
+
+        // A changed validator set only fetches the new validators, at once
+        let changed_set = [H256::repeat_byte(3), set[1], unannounced];
+        let locations = cache.storage_locations(&changed_set, fetch).await.unwrap();
+        assert_eq!(
+            locations,
+            vec![location(&changed_set[0]), location(&set[1]), vec![]]
+        );
+        assert_eq!(
+            fetches.lock().unwrap().last().unwrap(),
+            &vec![H256::repeat_byte(3), unannounced]
+        );
+
+        // Validators without an announced location are fetched again
+        cache.storage_locations(&changed_set, fetch).await.unwrap();
+        assert_eq!(fetches.lock().unwrap().last().unwrap(), &vec![unannounced]);
+        assert_eq!(fetches.lock().unwrap().len(), 3);
+    }
+}
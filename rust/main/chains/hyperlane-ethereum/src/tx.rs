@@ -1,6 +1,6 @@
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::{
@@ -13,10 +13,7 @@ use ethers_contract::builders::ContractCall;
 use ethers_core::types::H160;
 use ethers_core::{
     types::{BlockNumber, U256 as EthersU256},
-    utils::{
-        eip1559_default_estimator, EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
-        EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE,
-    },
+    utils::eip1559_default_estimator,
 };
 use hyperlane_core::{
     utils::bytes_to_hex, ChainCommunicationError, ChainResult, HyperlaneDomain, ReorgPeriod, H256,
@@ -24,7 +21,10 @@ use hyperlane_core::{
 };
 use tracing::{debug, error, info, warn};
 
-use crate::{EthereumReorgPeriod, Middleware, TransactionOverrides};
+use crate::{
+    fee_history::fetch_fee_history_rewards, EthereumReorgPeriod, FeeHistoryCache, Middleware,
+    TransactionInclusionWatcher, TransactionOverrides,
+};
 
 /// An amount of gas to add to the estimated gas
 pub const GAS_ESTIMATE_BUFFER: u32 = 75_000;
@@ -54,8 +54,13 @@ pub fn apply_gas_estimate_buffer(gas: U256, domain: &HyperlaneDomain) -> ChainRe
 const PENDING_TRANSACTION_POLLING_INTERVAL: Duration = Duration::from_secs(2);
 const EVM_RELAYER_ADDRESS: &str = "0x74cae0ecc47b02ed9b9d32e000fd70b9417970c5";
 
-/// Dispatches a transaction, logs the tx id, and returns the result
-pub(crate) async fn report_tx<M, D>(tx: ContractCall<M, D>) -> ChainResult<TransactionReceipt>
+/// Dispatches a transaction, logs the tx id, and returns the result. The
+/// transaction's inclusion is tracked by the `watcher`.
+pub(crate) async fn report_tx<M, D>(
+    tx: ContractCall<M, D>,
+    provider: Arc<M>,
+    watcher: &TransactionInclusionWatcher,
+) -> ChainResult<TransactionReceipt>
 where
     M: Middleware + 'static,
     D: Detokenize,
@@ -72,12 +77,25 @@ where
         .cloned()
         .unwrap_or_else(|| NameOrAddress::Address(Default::default()));
 
+    watcher.check_nonces(&*provider).await;
+
     info!(?to, %data, tx=?tx.tx, "Dispatching transaction");
+    let broadcast_at = Instant::now();
     let dispatch_fut = tx.send();
     let dispatched = dispatch_fut
-        .await?
+        .await
+        .inspect_err(|err| {
+            if err.to_string().contains("nonce") {
+                watcher.expedite_nonce_check();
+            }
+        })?
         .interval(PENDING_TRANSACTION_POLLING_INTERVAL);
-    track_pending_tx(dispatched).await
+    let watched = watcher
+        .watch(provider, (*dispatched).into(), broadcast_at)
+        .await;
+    let outcome = track_pending_tx(dispatched).await;
+    watched.record_outcome(&outcome).await;
+    outcome
 }
 
 pub(crate) async fn track_pending_tx<P: JsonRpcClient>(
@@ -114,6 +132,7 @@ pub(crate) async fn fill_tx_gas_params<M, D>(
     tx: ContractCall<M, D>,
     provider: Arc<M>,
     transaction_overrides: &TransactionOverrides,
+    fee_history_cache: Option<&FeeHistoryCache>,
     domain: &HyperlaneDomain,
 ) -> ChainResult<ContractCall<M, D>>
 where
@@ -157,8 +176,15 @@ where
         return Ok(tx.gas_price(gas_price).gas(gas_limit));
     }
 
-    let Ok((base_fee, max_fee, max_priority_fee)) =
-        estimate_eip1559_fees(provider, None, &latest_block, domain, &tx.tx).await
+    let Ok((base_fee, max_fee, max_priority_fee)) = estimate_eip1559_fees(
+        provider,
+        None,
+        fee_history_cache,
+        &latest_block,
+        domain,
+        &tx.tx,
+    )
+    .await
     else {
         // Is not EIP 1559 chain
         return Ok(tx.gas(gas_limit));
@@ -210,6 +236,7 @@ type FeeEstimator = fn(EthersU256, Vec<Vec<EthersU256>>) -> (EthersU256, EthersU
 async fn estimate_eip1559_fees<M>(
     provider: Arc<M>,
     estimator: Option<FeeEstimator>,
+    fee_history_cache: Option<&FeeHistoryCache>,
     latest_block: &Block<TxHash>,
     domain: &HyperlaneDomain,
     tx: &TypedTransaction,
@@ -220,7 +247,7 @@ where
     if domain.is_zksync_stack() {
         estimate_eip1559_fees_zksync(provider, latest_block, tx).await
     } else {
-        estimate_eip1559_fees_default(provider, estimator, latest_block).await
+        estimate_eip1559_fees_default(provider, estimator, fee_history_cache, latest_block).await
     }
 }
 
@@ -287,6 +314,7 @@ struct ZksyncEstimateFeeResponse {
 async fn estimate_eip1559_fees_default<M>(
     provider: Arc<M>,
     estimator: Option<FeeEstimator>,
+    fee_history_cache: Option<&FeeHistoryCache>,
     latest_block: &Block<TxHash>,
 ) -> ChainResult<(EthersU256, EthersU256, EthersU256)>
 where
@@ -296,20 +324,16 @@ where
         .base_fee_per_gas
         .ok_or_else(|| ProviderError::CustomError("EIP-1559 not activated".into()))?;
 
-    let fee_history = provider
-        .fee_history(
-            EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
-            BlockNumber::Latest,
-            &[EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
-        )
-        .await
-        .map_err(ChainCommunicationError::from_other)?;
+    let reward = match fee_history_cache {
+        Some(cache) => cache.rewards(&*provider, latest_block.number).await?,
+        None => fetch_fee_history_rewards(&*provider).await?,
+    };
 
     // use the provided fee estimator function, or fallback to the default implementation.
     let (max_fee_per_gas, max_priority_fee_per_gas) = if let Some(es) = estimator {
-        es(base_fee_per_gas, fee_history.reward)
+        es(base_fee_per_gas, reward)
     } else {
-        eip1559_default_estimator(base_fee_per_gas, fee_history.reward)
+        eip1559_default_estimator(base_fee_per_gas, reward)
     };
 
     Ok((base_fee_per_gas, max_fee_per_gas, max_priority_fee_per_gas))
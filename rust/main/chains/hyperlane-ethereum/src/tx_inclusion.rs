@@ -0,0 +1,412 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ethers::prelude::TransactionReceipt;
+use ethers_core::types::{H160, U256 as EthersU256};
+use hyperlane_core::{ChainCommunicationError, ChainResult, HyperlaneDomain, H256};
+use prometheus::{HistogramVec, IntCounterVec, IntGaugeVec};
+use tracing::{debug, warn};
+
+use crate::{get_finalized_block_number, nonce::NonceMonitor, EthereumReorgPeriod, Middleware};
+
+/// Expected label names for the `transaction_inclusion_seconds` metric.
+pub const TRANSACTION_INCLUSION_SECONDS_LABELS: &[&str] = &["chain", "stage"];
+/// Help string for the metric.
+pub const TRANSACTION_INCLUSION_SECONDS_HELP: &str =
+    "Seconds from broadcasting a transaction until \
+    it was accepted into the node's `mempool`, `mined` or `finalized`";
+/// Buckets for the `transaction_inclusion_seconds` metric, spanning from
+/// mempool acceptance to finalization on slow chains.
+pub const TRANSACTION_INCLUSION_SECONDS_BUCKETS: &[f64] = &[
+    0.5, 1., 2., 5., 10., 20., 30., 60., 120., 300., 600., 1200., 1800., 3600.,
+];
+
+/// Expected label names for the `transaction_not_included_total` metric.
+pub const TRANSACTION_NOT_INCLUDED_TOTAL_LABELS: &[&str] = &["chain", "reason"];
+/// Help string for the metric.
+pub const TRANSACTION_NOT_INCLUDED_TOTAL_HELP: &str = "Number of broadcast transactions that were \
+    not included, because they were `dropped`, `replaced` or `timed_out`";
+
+const STAGE_MEMPOOL: &str = "mempool";
+const STAGE_MINED: &str = "mined";
+const STAGE_FINALIZED: &str = "finalized";
+
+const REASON_DROPPED: &str = "dropped";
+const REASON_REPLACED: &str = "replaced";
+const REASON_TIMED_OUT: &str = "timed_out";
+
+/// How often to check whether a mined transaction was finalized
+const FINALITY_POLLING_INTERVAL: Duration = Duration::from_secs(15);
+/// How long after broadcasting to stop waiting for a transaction's finality
+const FINALITY_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Metrics of the lifecycle of submitted transactions.
+#[derive(Clone, Debug)]
+pub struct TransactionInclusionMetrics {
+    /// Time from broadcast until each lifecycle stage, by chain and stage
+    pub inclusion_seconds: HistogramVec,
+    /// Transactions that never got included, by chain and reason
+    pub not_included: IntCounterVec,
+    /// How long the signer's lowest unmined nonce has been stuck, by chain
+    pub stuck_nonce_seconds: IntGaugeVec,
+    /// Transactions sent to unblock a stuck nonce, by chain and reason
+    pub nonce_replacements: IntCounterVec,
+}
+
+/// Follows every submitted transaction from broadcast through mempool
+/// acceptance, inclusion and finalization, so operators can tell whether
+/// delivery latency comes from the submitter or from the chain.
+///
+/// It also repairs the signer's nonces when transactions stop being mined
+/// because a nonce is stuck.
+#[derive(Clone, Debug)]
+pub struct TransactionInclusionWatcher {
+    metrics: TransactionInclusionMetrics,
+    chain: String,
+    /// Transactions deeper than this are considered finalized
+    reorg_period: EthereumReorgPeriod,
+    nonces: NonceMonitor,
+    /// Mined transactions waiting to be finalized
+    pending_finality: Arc<Mutex<PendingFinality>>,
+}
+
+/// Mined transactions of a chain waiting to be finalized. A single task
+/// polls the finalized block for all of them, and exits once none are left.
+#[derive(Debug, Default)]
+struct PendingFinality {
+    txs: Vec<MinedTransaction>,
+    /// Whether a task is polling the finalized block
+    polling: bool,
+}
+
+#[derive(Debug)]
+struct MinedTransaction {
+    tx_hash: H256,
+    block_number: u64,
+    broadcast_at: Instant,
+}
+
+impl TransactionInclusionWatcher {
+    /// Create a watcher for transactions submitted to the domain
+    pub fn new(
+        metrics: TransactionInclusionMetrics,
+        domain: &HyperlaneDomain,
+        reorg_period: EthereumReorgPeriod,
+    ) -> Self {
+        let nonces = NonceMonitor::new(
+            domain.name().to_owned(),
+            metrics.stuck_nonce_seconds.clone(),
+            metrics.nonce_replacements.clone(),
+        );
+        Self {
+            metrics,
+            chain: domain.name().to_owned(),
+            reorg_period,
+            nonces,
+            pending_finality: Default::default(),
+        }
+    }
+
+    /// Repairs the signer's lowest unmined nonce if it has been stuck for too
+    /// long. Called before submitting a transaction.
+    pub(crate) async fn check_nonces<M>(&self, provider: &M)
+    where
+        M: Middleware + 'static,
+    {
+        self.nonces.check_and_repair(provider).await
+    }
+
+    /// Makes the nonces be checked at the next submission, after submitting
+    /// a transaction failed because of its nonce
+    pub(crate) fn expedite_nonce_check(&self) {
+        self.nonces.expedite()
+    }
+
+    /// Starts watching a transaction that was broadcast at `broadcast_at`.
+    /// Records when the node accepted it into its mempool, if it did.
+    pub(crate) async fn watch<M>(
+        &self,
+        provider: Arc<M>,
+        tx_hash: H256,
+        broadcast_at: Instant,
+    ) -> WatchedTransaction<M>
+    where
+        M: Middleware + 'static,
+    {
+        let sender_nonce = match provider.get_transaction(tx_hash).await {
+            Ok(Some(tx)) => {
+                self.observe(STAGE_MEMPOOL, broadcast_at);
+                self.nonces.record_sent(&tx);
+                Some((tx.from, tx.nonce))
+            }
+            Ok(None) => None,
+            Err(err) => {
+                debug!(?tx_hash, ?err, "Failed to get broadcast transaction");
+                None
+            }
+        };
+        WatchedTransaction {
+            watcher: self.clone(),
+            provider,
+            tx_hash,
+            broadcast_at,
+            sender_nonce,
+        }
+    }
+
+    /// Waits in the background for the mined transaction to be finalized.
+    /// Starts polling the finalized block unless a task already does.
+    fn wait_for_finality<M>(&self, provider: Arc<M>, tx: MinedTransaction)
+    where
+        M: Middleware + 'static,
+    {
+        if self.add_pending_finality(tx) {
+            tokio::spawn(self.clone().poll_finality(provider));
+        }
+    }
+
+    /// Adds a transaction waiting to be finalized. Returns whether a task
+    /// needs to start polling the finalized block.
+    fn add_pending_finality(&self, tx: MinedTransaction) -> bool {
+        let mut pending = self
+            .pending_finality
+            .lock()
+            .expect("pending finality lock poisoned");
+        pending.txs.push(tx);
+        !std::mem::replace(&mut pending.polling, true)
+    }
+
+    async fn poll_finality<M>(self, provider: Arc<M>)
+    where
+        M: Middleware + 'static,
+    {
+        loop {
+            let finalized = match get_finalized_block_number(&*provider, &self.reorg_period).await {
+                Ok(finalized) => Some(finalized as u64),
+                Err(err) => {
+                    debug!(
+                        chain = %self.chain,
+                        ?err,
+                        "Failed to get finalized block number"
+                    );
+                    None
+                }
+            };
+            {
+                let mut pending = self
+                    .pending_finality
+                    .lock()
+                    .expect("pending finality lock poisoned");
+                pending
+                    .txs
+                    .retain(|tx| !self.is_finality_resolved(tx, finalized));
+                if pending.txs.is_empty() {
+                    pending.polling = false;
+                    return;
+                }
+            }
+            tokio::time::sleep(FINALITY_POLLING_INTERVAL).await;
+        }
+    }
+
+    /// Whether the transaction was finalized, or it was waited for too long
+    fn is_finality_resolved(&self, tx: &MinedTransaction, finalized: Option<u64>) -> bool {
+        if finalized.map_or(false, |finalized| finalized >= tx.block_number) {
+            self.observe(STAGE_FINALIZED, tx.broadcast_at);
+            return true;
+        }
+        if tx.broadcast_at.elapsed() > FINALITY_TIMEOUT {
+            warn!(
+                tx_hash=?tx.tx_hash,
+                block_number=tx.block_number,
+                "Stopped waiting for transaction finality"
+            );
+            return true;
+        }
+        false
+    }
+
+    fn observe(&self, stage: &str, broadcast_at: Instant) {
+        self.metrics
+            .inclusion_seconds
+            .with_label_values(&[&self.chain, stage])
+            .observe(broadcast_at.elapsed().as_secs_f64());
+    }
+
+    fn count_not_included(&self, reason: &str) {
+        self.metrics
+            .not_included
+            .with_label_values(&[&self.chain, reason])
+            .inc();
+    }
+}
+
+/// A broadcast transaction whose inclusion is being watched
+pub(crate) struct WatchedTransaction<M> {
+    watcher: TransactionInclusionWatcher,
+    provider: Arc<M>,
+    tx_hash: H256,
+    broadcast_at: Instant,
+    /// The sender and nonce of the transaction, if it was seen in the mempool
+    sender_nonce: Option<(H160, EthersU256)>,
+}
+
+impl<M> WatchedTransaction<M>
+where
+    M: Middleware + 'static,
+{
+    /// Records the outcome of waiting for the transaction's receipt. Mined
+    /// transactions keep being watched in the background until they are
+    /// finalized.
+    pub(crate) async fn record_outcome(self, outcome: &ChainResult<TransactionReceipt>) {
+        match outcome {
+            Ok(receipt) => {
+                self.watcher.observe(STAGE_MINED, self.broadcast_at);
+                if let Some(block_number) = receipt.block_number {
+                    self.watcher.wait_for_finality(
+                        self.provider.clone(),
+                        MinedTransaction {
+                            tx_hash: self.tx_hash,
+                            block_number: block_number.as_u64(),
+                            broadcast_at: self.broadcast_at,
+                        },
+                    );
+                }
+            }
+            Err(ChainCommunicationError::TransactionDropped(_)) => {
+                let reason = if self.was_replaced().await {
+                    REASON_REPLACED
+                } else {
+                    REASON_DROPPED
+                };
+                self.watcher.count_not_included(reason);
+            }
+            Err(ChainCommunicationError::TransactionTimeout()) => {
+                self.watcher.count_not_included(REASON_TIMED_OUT);
+            }
+            // Failing to poll for the receipt says nothing about the transaction
+            Err(_) => {}
+        }
+    }
+
+    /// A dropped transaction was replaced if another transaction of the same
+    /// sender used its nonce.
+    async fn was_replaced(&self) -> bool {
+        let Some((sender, nonce)) = self.sender_nonce else {
+            return false;
+        };
+        match self.provider.get_transaction_count(sender, None).await {
+            Ok(next_nonce) => next_nonce > nonce,
+            Err(err) => {
+                debug!(tx_hash=?self.tx_hash, ?err, "Failed to get sender nonce");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use ethers::providers::{MockProvider, Provider};
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::{histogram_opts, opts};
+
+    use crate::nonce::{NONCE_REPLACEMENTS_TOTAL_LABELS, STUCK_NONCE_SECONDS_LABELS};
+
+    use super::*;
+
+    pub(crate) fn dummy_watcher() -> TransactionInclusionWatcher {
+        let metrics = TransactionInclusionMetrics {
+            inclusion_seconds: HistogramVec::new(
+                histogram_opts!("inclusion_seconds", "help"),
+                TRANSACTION_INCLUSION_SECONDS_LABELS,
+            )
+            .unwrap(),
+            not_included: IntCounterVec::new(
+                opts!("not_included", "help"),
+                TRANSACTION_NOT_INCLUDED_TOTAL_LABELS,
+            )
+            .unwrap(),
+            stuck_nonce_seconds: IntGaugeVec::new(
+                opts!("stuck_nonce_seconds", "help"),
+                STUCK_NONCE_SECONDS_LABELS,
+            )
+            .unwrap(),
+            nonce_replacements: IntCounterVec::new(
+                opts!("nonce_replacements", "help"),
+                NONCE_REPLACEMENTS_TOTAL_LABELS,
+            )
+            .unwrap(),
+        };
+        TransactionInclusionWatcher::new(
+            metrics,
+            &HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum),
+            EthereumReorgPeriod::Blocks(1),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_counts_not_included_transactions() {
+        let watcher = dummy_watcher();
+        let provider = Arc::new(Provider::new(MockProvider::new()));
+        for outcome in [
+            Err(ChainCommunicationError::TransactionDropped(H256::zero())),
+            Err(ChainCommunicationError::TransactionTimeout()),
+            Err(ChainCommunicationError::TransactionTimeout()),
+        ] {
+            let watched = WatchedTransaction {
+                watcher: watcher.clone(),
+                provider: provider.clone(),
+                tx_hash: H256::zero(),
+                broadcast_at: Instant::now(),
+                // Not seen in the mempool, so it can't have been replaced
+                sender_nonce: None,
+            };
+            watched.record_outcome(&outcome).await;
+        }
+
+        let not_included = |reason| {
+            watcher
+                .metrics
+                .not_included
+                .with_label_values(&["ethereum", reason])
+                .get()
+        };
+        assert_eq!(not_included(REASON_DROPPED), 1);
+        assert_eq!(not_included(REASON_REPLACED), 0);
+        assert_eq!(not_included(REASON_TIMED_OUT), 2);
+    }
+
+    #[tokio::test]
+    async fn test_one_task_polls_finality_of_all_mined_transactions() {
+        let watcher = dummy_watcher();
+        let mined = |block_number| MinedTransaction {
+            tx_hash: H256::zero(),
+            block_number,
+            broadcast_at: Instant::now(),
+        };
+        assert!(watcher.add_pending_finality(mined(5)));
+        assert!(!watcher.add_pending_finality(mined(6)));
+
+        // The reorg period is 1 block, so both blocks are finalized at 7
+        let mock_provider = MockProvider::new();
+        mock_provider.push(EthersU256::from(7)).unwrap();
+        watcher
+            .clone()
+            .poll_finality(Arc::new(Provider::new(mock_provider)))
+            .await;
+
+        let pending = watcher.pending_finality.lock().unwrap();
+        assert!(pending.txs.is_empty());
+        assert!(!pending.polling);
+        drop(pending);
+        assert_eq!(
+            watcher
+                .metrics
+                .inclusion_seconds
+                .with_label_values(&["ethereum", STAGE_FINALIZED])
+                .get_sample_count(),
+            2
+        );
+    }
+}
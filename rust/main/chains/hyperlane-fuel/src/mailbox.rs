@@ -99,6 +99,18 @@ impl Mailbox for FuelMailbox {
             .map_err(ChainCommunicationError::from_other)
     }
 
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn local_domain(&self) -> ChainResult<u32> {
+        self.contract
+            .methods()
+            .local_domain()
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)
+    }
+
     #[instrument(err, ret, skip(self))]
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
     async fn default_ism(&self) -> ChainResult<H256> {
@@ -209,6 +221,8 @@ impl Mailbox for FuelMailbox {
             gas_limit: call_res.total_fee.into(),
             gas_price: call_res.gas_price.into(),
             l2_gas_limit: None,
+            l1_data_fee: None,
+            l1_blob_base_fee: None,
         })
     }
 
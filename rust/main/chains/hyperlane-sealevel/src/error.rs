@@ -48,6 +48,14 @@ pub enum HyperlaneSealevelError {
     /// No non-native programs
     #[error("transaction contains no non-native programs, hash: {0:?}")]
     NoNonNativePrograms(H512),
+    /// Slot was pruned by the RPC node and no archive RPC node has it
+    #[error("slot {slot} is below the minimum slot available on the RPC node ({minimum_available_slot}) and no archive RPC node has it")]
+    SlotNotAvailable {
+        /// The requested slot
+        slot: u64,
+        /// The minimum slot available on the RPC node
+        minimum_available_slot: u64,
+    },
 }
 
 impl From<HyperlaneSealevelError> for ChainCommunicationError {
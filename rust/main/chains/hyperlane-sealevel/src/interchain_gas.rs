@@ -3,21 +3,27 @@ use std::{ops::RangeInclusive, sync::Arc};
 use async_trait::async_trait;
 use derive_new::new;
 use hyperlane_sealevel_igp::{
-    accounts::{GasPaymentAccount, ProgramDataAccount},
+    accounts::{GasPaymentAccount, IgpAccount, ProgramDataAccount},
     igp_gas_payment_pda_seeds, igp_program_data_pda_seeds,
+    instruction::claim_instruction,
 };
-use solana_sdk::{account::Account, clock::Slot, pubkey::Pubkey};
-use tracing::{info, instrument};
+use solana_sdk::{
+    account::Account, clock::Slot, commitment_config::CommitmentConfig, pubkey::Pubkey,
+};
+use tracing::{info, instrument, warn};
 
 use hyperlane_core::{
     config::StrOrIntParseError, ChainCommunicationError, ChainResult, ContractLocator,
-    HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer,
-    InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer, H256, H512, U256,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider,
+    Indexed, Indexer, InterchainGasPaymaster, InterchainGasPayment, LogMeta, SequenceAwareIndexer,
+    TxOutcome, H256, H512, U256,
 };
 
 use crate::account::{search_accounts_by_discriminator, search_and_validate_account};
 use crate::log_meta_composer::{is_interchain_payment_instruction, LogMetaComposer};
-use crate::{ConnectionConf, SealevelProvider, SealevelRpcClient};
+use crate::priority_fee::PriorityFeeOracle;
+use crate::tx_submitter::TransactionSubmitter;
+use crate::{ConnectionConf, SealevelKeypair, SealevelProvider, SealevelRpcClient};
 
 /// The offset to get the `unique_gas_payment_pubkey` field from the serialized GasPaymentData.
 /// The account data includes prefixes that are accounted for here: a 1 byte initialized flag
@@ -25,13 +31,29 @@ use crate::{ConnectionConf, SealevelProvider, SealevelRpcClient};
 const UNIQUE_GAS_PAYMENT_PUBKEY_OFFSET: usize = 1 + 8 + 8 + 32 + 4 + 32 + 8 + 8;
 
 /// A reference to an IGP contract on some Sealevel chain
-#[derive(Debug)]
 pub struct SealevelInterchainGasPaymaster {
     program_id: Pubkey,
     data_pda_pubkey: Pubkey,
     domain: HyperlaneDomain,
     igp_account: H256,
     provider: SealevelProvider,
+    /// Pays for the transactions claiming the payments, which only the
+    /// relayer sends
+    payer: Option<SealevelKeypair>,
+    tx_submitter: Option<Box<dyn TransactionSubmitter>>,
+    priority_fee_oracle: Box<dyn PriorityFeeOracle>,
+}
+
+impl std::fmt::Debug for SealevelInterchainGasPaymaster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SealevelInterchainGasPaymaster")
+            .field("program_id", &self.program_id)
+            .field("data_pda_pubkey", &self.data_pda_pubkey)
+            .field("domain", &self.domain)
+            .field("igp_account", &self.igp_account)
+            .field("provider", &self.provider)
+            .finish()
+    }
 }
 
 impl SealevelInterchainGasPaymaster {
@@ -53,9 +75,27 @@ impl SealevelInterchainGasPaymaster {
             domain: igp_account_locator.domain.clone(),
             igp_account: igp_account_locator.address,
             provider,
+            payer: None,
+            tx_submitter: None,
+            priority_fee_oracle: conf.priority_fee_oracle.create_oracle(),
         })
     }
 
+    /// Lets the IGP send transactions, to claim its payments
+    pub fn with_signer(
+        mut self,
+        payer: Option<SealevelKeypair>,
+        tx_submitter: Box<dyn TransactionSubmitter>,
+    ) -> Self {
+        self.payer = payer;
+        self.tx_submitter = Some(tx_submitter);
+        self
+    }
+
+    fn igp_account_pubkey(&self) -> Pubkey {
+        Pubkey::new_from_array(self.igp_account.into())
+    }
+
     async fn determine_igp_program_id(
         rpc_client: &SealevelRpcClient,
         igp_account_pubkey: &H256,
@@ -85,7 +125,80 @@ impl HyperlaneChain for SealevelInterchainGasPaymaster {
     }
 }
 
-impl InterchainGasPaymaster for SealevelInterchainGasPaymaster {}
+#[async_trait]
+impl InterchainGasPaymaster for SealevelInterchainGasPaymaster {
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn claimable_balance(&self) -> ChainResult<U256> {
+        // The IGP account must stay rent exempt, the rest are payments
+        let account = self
+            .provider
+            .rpc()
+            .get_account_with_finalized_commitment(&self.igp_account_pubkey())
+            .await?;
+        let rent_exempt_balance = self
+            .provider
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(account.data.len())
+            .await?;
+        Ok(account.lamports.saturating_sub(rent_exempt_balance).into())
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn claim(&self) -> ChainResult<TxOutcome> {
+        let payer = self
+            .payer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let tx_submitter = self
+            .tx_submitter
+            .as_deref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+
+        // Anyone can claim the payments, but they always go to the beneficiary
+        let igp_account_pubkey = self.igp_account_pubkey();
+        let account = self
+            .provider
+            .rpc()
+            .get_account_with_finalized_commitment(&igp_account_pubkey)
+            .await?;
+        let igp = IgpAccount::fetch(&mut account.data.as_ref())
+            .map_err(ChainCommunicationError::from_other)?
+            .into_inner();
+        let instruction = claim_instruction(self.program_id, igp_account_pubkey, igp.beneficiary)
+            .map_err(ChainCommunicationError::from_other)?;
+
+        let (tx, _) = self
+            .provider
+            .rpc()
+            .build_estimated_tx_for_instruction(
+                instruction,
+                payer,
+                tx_submitter,
+                &*self.priority_fee_oracle,
+            )
+            .await?;
+        let rpc = tx_submitter
+            .rpc_client()
+            .unwrap_or_else(|| self.provider.rpc());
+        let signature = tx_submitter.send_transaction(&tx, true).await?;
+        info!(?signature, beneficiary = ?igp.beneficiary, "Sent IGP claim transaction");
+        rpc.wait_for_transaction_confirmation(&tx).await?;
+
+        let executed = rpc
+            .confirm_transaction_with_commitment(&signature, CommitmentConfig::processed())
+            .await
+            .map_err(|err| warn!("Failed to confirm transaction: {}", err))
+            .unwrap_or(false);
+        Ok(TxOutcome {
+            transaction_id: signature.into(),
+            executed,
+            gas_price: FixedPointNumber::zero(),
+            gas_used: U256::zero(),
+        })
+    }
+}
 
 /// Struct that retrieves event data for a Sealevel IGP contract
 #[derive(Debug)]
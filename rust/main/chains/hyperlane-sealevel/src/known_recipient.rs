@@ -0,0 +1,192 @@
+use std::io::Cursor;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, Decode, HyperlaneMessage};
+use hyperlane_sealevel_client_lib::mailbox::ProcessAccountMetas;
+use hyperlane_sealevel_multisig_ism_message_id::domain_data_pda_seeds;
+use hyperlane_warp_route::TokenMessage;
+use solana_program::pubkey;
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+
+/// The SPL associated token account program.
+const ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// The statically configured accounts a known recipient's messages are
+/// processed with, so they don't have to be discovered by simulation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownRecipientConfig {
+    /// The accounts required to get the recipient's ISM.
+    pub ism_getter: Vec<AccountMetaTemplate>,
+    /// The recipient's ISM.
+    pub ism: Pubkey,
+    /// The accounts required to verify a message with the ISM.
+    pub ism_verify: Vec<AccountMetaTemplate>,
+    /// The accounts required to handle a message.
+    pub handle: Vec<AccountMetaTemplate>,
+}
+
+/// An account meta whose key may depend on the message being processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountMetaTemplate {
+    /// How to get the account's key
+    pub key: AccountKeyTemplate,
+    /// Whether the account is writable
+    pub is_writable: bool,
+}
+
+/// How to get the key of an account meta for a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountKeyTemplate {
+    /// The same key for every message
+    Static(Pubkey),
+    /// The recipient of the warp route transfer in the message body
+    TokenRecipient,
+    /// The associated token account of the warp route transfer recipient
+    TokenRecipientAta {
+        /// The token mint
+        mint: Pubkey,
+        /// The token program the mint belongs to
+        token_program: Pubkey,
+    },
+    /// The domain data PDA of a message ID multisig ISM for the message origin
+    MultisigIsmDomainData(Pubkey),
+}
+
+impl KnownRecipientConfig {
+    /// Resolves the templates into the account metas of the message's
+    /// process instruction.
+    pub fn process_account_metas(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<ProcessAccountMetas> {
+        Ok(ProcessAccountMetas {
+            ism_getter: resolve(&self.ism_getter, message)?,
+            ism: self.ism,
+            ism_verify: resolve(&self.ism_verify, message)?,
+            handle: resolve(&self.handle, message)?,
+        })
+    }
+}
+
+impl AccountMetaTemplate {
+    /// Resolves the template into a non-signer account meta for the message.
+    pub fn resolve(&self, message: &HyperlaneMessage) -> ChainResult<AccountMeta> {
+        let pubkey = match &self.key {
+            AccountKeyTemplate::Static(pubkey) => *pubkey,
+            AccountKeyTemplate::TokenRecipient => token_recipient(message)?,
+            AccountKeyTemplate::TokenRecipientAta {
+                mint,
+                token_program,
+            } => {
+                let wallet = token_recipient(message)?;
+                Pubkey::find_program_address(
+                    &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+                    &ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+                )
+                .0
+            }
+            AccountKeyTemplate::MultisigIsmDomainData(ism) => {
+                Pubkey::find_program_address(domain_data_pda_seeds!(message.origin), ism).0
+            }
+        };
+        Ok(AccountMeta {
+            pubkey,
+            is_signer: false,
+            is_writable: self.is_writable,
+        })
+    }
+}
+
+fn resolve(
+    templates: &[AccountMetaTemplate],
+    message: &HyperlaneMessage,
+) -> ChainResult<Vec<AccountMeta>> {
+    templates
+        .iter()
+        .map(|template| template.resolve(message))
+        .collect()
+}
+
+fn token_recipient(message: &HyperlaneMessage) -> ChainResult<Pubkey> {
+    let token_message = TokenMessage::read_from(&mut Cursor::new(&message.body))
+        .map_err(ChainCommunicationError::from_other)?;
+    Ok(Pubkey::new_from_array(token_message.recipient().into()))
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Encode, H256, U256};
+
+    use super::*;
+
+    #[test]
+    fn test_process_account_metas() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = Pubkey::new_unique();
+        let ism = Pubkey::new_unique();
+        let escrow = Pubkey::new_unique();
+        let message = HyperlaneMessage {
+            origin: 1234,
+            body: TokenMessage::new(H256(wallet.to_bytes()), U256::from(100), vec![]).to_vec(),
+            ..Default::default()
+        };
+        let config = KnownRecipientConfig {
+            ism_getter: vec![],
+            ism,
+            ism_verify: vec![AccountMetaTemplate {
+                key: AccountKeyTemplate::MultisigIsmDomainData(ism),
+                is_writable: false,
+            }],
+            handle: vec![
+                AccountMetaTemplate {
+                    key: AccountKeyTemplate::TokenRecipient,
+                    is_writable: false,
+                },
+                AccountMetaTemplate {
+                    key: AccountKeyTemplate::TokenRecipientAta {
+                        mint,
+                        token_program,
+                    },
+                    is_writable: true,
+                },
+                AccountMetaTemplate {
+                    key: AccountKeyTemplate::Static(escrow),
+                    is_writable: true,
+                },
+            ],
+        };
+
+        let (domain_data, _) = Pubkey::find_program_address(domain_data_pda_seeds!(1234u32), &ism);
+        let (ata, _) = Pubkey::find_program_address(
+            &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_ACCOUNT_PROGRAM_ID,
+        );
+        assert_eq!(
+            config.process_account_metas(&message).unwrap(),
+            ProcessAccountMetas {
+                ism_getter: vec![],
+                ism,
+                ism_verify: vec![AccountMeta::new_readonly(domain_data, false)],
+                handle: vec![
+                    AccountMeta::new_readonly(wallet, false),
+                    AccountMeta::new(ata, false),
+                    AccountMeta::new(escrow, false),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_token_recipient_requires_token_message() {
+        let template = AccountMetaTemplate {
+            key: AccountKeyTemplate::TokenRecipient,
+            is_writable: false,
+        };
+        let message = HyperlaneMessage {
+            body: vec![1, 2, 3],
+            ..Default::default()
+        };
+        assert!(template.resolve(&message).is_err());
+    }
+}
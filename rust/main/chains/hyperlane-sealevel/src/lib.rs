@@ -8,8 +8,12 @@ pub use crate::multisig_ism::*;
 pub use interchain_gas::*;
 pub use interchain_security_module::*;
 pub use keypair::*;
+pub use known_recipient::*;
 pub use mailbox::*;
+pub use mailbox_config::*;
 pub use merkle_tree_hook::*;
+pub use payer_pool::*;
+pub use priority_fee_metrics::*;
 pub use provider::*;
 pub use rpc::*;
 pub use solana_sdk::signer::keypair::Keypair;
@@ -24,12 +28,16 @@ mod error;
 mod interchain_gas;
 mod interchain_security_module;
 mod keypair;
+mod known_recipient;
 mod log_meta_composer;
 mod mailbox;
+mod mailbox_config;
 mod merkle_tree_hook;
 mod metric;
 mod multisig_ism;
+mod payer_pool;
 mod priority_fee;
+mod priority_fee_metrics;
 mod provider;
 mod rpc;
 mod trait_builder;
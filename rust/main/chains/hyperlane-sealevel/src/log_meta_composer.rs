@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
-use solana_sdk::{clock::Slot, pubkey::Pubkey};
+use hyperlane_sealevel_mailbox::{events::MailboxEvent, spl_noop};
+use solana_sdk::{clock::Slot, instruction::CompiledInstruction, pubkey::Pubkey};
 use solana_transaction_status::{
     option_serializer::OptionSerializer, EncodedTransaction, EncodedTransactionWithStatusMeta,
-    UiCompiledInstruction, UiConfirmedBlock, UiInstruction, UiMessage, UiTransaction,
-    UiTransactionStatusMeta,
+    UiCompiledInstruction, UiConfirmedBlock, UiInstruction, UiMessage, UiParsedInstruction,
+    UiTransaction, UiTransactionStatusMeta,
 };
 use tracing::warn;
 
@@ -119,6 +120,38 @@ pub fn is_interchain_payment_instruction(instruction_data: &[u8]) -> bool {
     matches!(instruction, Instruction::PayForGas(_))
 }
 
+/// Decodes the events the Mailbox logged with CPIs to the SPL Noop program
+/// in a transaction, in the order they were logged. Transactions that don't
+/// invoke the Mailbox have none.
+///
+/// Anyone can log data to the SPL Noop program, so callers should check the
+/// events against the Mailbox instructions of the transaction.
+pub fn mailbox_events(
+    program_id: &Pubkey,
+    account_keys: &[String],
+    instructions: &[UiCompiledInstruction],
+) -> Vec<MailboxEvent> {
+    let program_of = |instruction: &UiCompiledInstruction| {
+        account_keys.get(instruction.program_id_index as usize)
+    };
+
+    let program_id = program_id.to_string();
+    if !instructions
+        .iter()
+        .any(|instruction| program_of(instruction) == Some(&program_id))
+    {
+        return vec![];
+    }
+
+    let spl_noop_id = spl_noop::id().to_string();
+    instructions
+        .iter()
+        .filter(|instruction| program_of(instruction) == Some(&spl_noop_id))
+        .filter_map(|instruction| from_base58(&instruction.data).ok())
+        .filter_map(|data| MailboxEvent::from_noop_data(&data))
+        .collect()
+}
+
 /// This function searches for relevant transactions in the vector of provided transactions and
 /// returns the relative index and hashes of such transactions.
 ///
@@ -189,34 +222,19 @@ fn filter_by_relevancy(
         None => return None, // If account keys do not contain the given PDA account, transaction is not relevant
     };
 
-    let program_maybe = instructions
+    // The transaction is relevant if one of its calls into the program is the specified
+    // instruction operating on the given PDA account. Versioned transactions commonly call
+    // other programs first, or the program more than once, e.g. through CPIs.
+    instructions
         .into_iter()
-        .find(|instruction| instruction.program_id_index == program_index);
-
-    let program = match program_maybe {
-        Some(p) => p,
-        None => return None, // If transaction does not contain call into program, transaction is not relevant
-    };
-
-    // If program does not operate on the given PDA account, transaction is not relevant
-    if !program.accounts.contains(&pda_account_index) {
-        return None;
-    }
-
-    let instruction_data = match from_base58(&program.data) {
-        Ok(d) => d,
-        Err(_) => return None, // If we cannot decode instruction data, transaction is not relevant
-    };
-
-    // If the call into program is not the specified instruction, transaction is not relevant
-    if !is_specified_instruction(&instruction_data) {
-        return None;
-    }
-
-    Some(hash)
+        .filter(|instruction| instruction.program_id_index == program_index)
+        .filter(|instruction| instruction.accounts.contains(&pda_account_index))
+        .filter_map(|instruction| from_base58(&instruction.data).ok())
+        .any(|instruction_data| is_specified_instruction(&instruction_data))
+        .then_some(hash)
 }
 
-fn filter_by_validity(
+pub fn filter_by_validity(
     tx: UiTransaction,
     meta: UiTransactionStatusMeta,
 ) -> Option<(H512, Vec<String>, Vec<UiCompiledInstruction>)> {
@@ -238,37 +256,65 @@ fn filter_by_validity(
         return None;
     };
 
-    let UiMessage::Raw(message) = tx.message else {
-        warn!(message = ?tx.message, "we expect messages in Raw format");
-        return None;
-    };
-
-    // Orders the account keys in line with the behavior of compiled instructions.
-    let account_keys = match &meta.loaded_addresses {
-        OptionSerializer::Some(addresses) => {
-            // If there are loaded addresses, we have a versioned transaction
-            // that may include dynamically loaded addresses (e.g. from a lookup table).
-            // The order of these is [static, dynamic writeable, dynamic readonly] and
-            // follows the iter ordering of https://docs.rs/solana-sdk/latest/solana_sdk/message/struct.AccountKeys.html.
-            [
-                message.account_keys,
-                addresses.writable.clone(),
-                addresses.readonly.clone(),
-            ]
-            .concat()
+    let (account_keys, instructions) = match tx.message {
+        UiMessage::Raw(message) => {
+            // Orders the account keys in line with the behavior of compiled instructions.
+            let account_keys = match &meta.loaded_addresses {
+                OptionSerializer::Some(addresses) => {
+                    // If there are loaded addresses, we have a versioned transaction
+                    // that may include dynamically loaded addresses (e.g. from a lookup table).
+                    // The order of these is [static, dynamic writeable, dynamic readonly] and
+                    // follows the iter ordering of https://docs.rs/solana-sdk/latest/solana_sdk/message/struct.AccountKeys.html.
+                    [
+                        message.account_keys,
+                        addresses.writable.clone(),
+                        addresses.readonly.clone(),
+                    ]
+                    .concat()
+                }
+                OptionSerializer::None | OptionSerializer::Skip => {
+                    // Without the loaded addresses, the indexes of the accounts loaded from
+                    // lookup tables can't be resolved, and the instructions would be misread.
+                    if message
+                        .address_table_lookups
+                        .as_ref()
+                        .is_some_and(|lookups| !lookups.is_empty())
+                    {
+                        warn!(
+                            ?transaction_hash,
+                            "versioned transaction loads accounts from lookup tables, but its metadata has no loaded addresses",
+                        );
+                        return None;
+                    }
+                    // There are only static addresses in the transaction.
+                    message.account_keys
+                }
+            };
+            (account_keys, message.instructions)
         }
-        OptionSerializer::None | OptionSerializer::Skip => {
-            // There are only static addresses in the transaction.
-            message.account_keys
+        UiMessage::Parsed(message) => {
+            // The account keys of parsed messages already include the ones loaded from
+            // lookup tables, in the order compiled instructions refer to them.
+            let account_keys: Vec<String> = message
+                .account_keys
+                .into_iter()
+                .map(|account| account.pubkey)
+                .collect();
+            let instructions = message
+                .instructions
+                .into_iter()
+                .filter_map(|instruction| compiled_instruction(instruction, &account_keys))
+                .collect();
+            (account_keys, instructions)
         }
     };
 
-    let instructions = instructions(message.instructions, meta);
+    let instructions = instructions(instructions, &account_keys, meta);
 
     Some((transaction_hash, account_keys, instructions))
 }
 
-fn filter_by_encoding(
+pub fn filter_by_encoding(
     tx: EncodedTransactionWithStatusMeta,
 ) -> Option<(UiTransaction, UiTransactionStatusMeta)> {
     match (tx.transaction, tx.meta) {
@@ -296,16 +342,14 @@ fn account_index_map(account_keys: Vec<String>) -> HashMap<String, usize> {
 /// Extract all instructions from transaction
 fn instructions(
     instruction: Vec<UiCompiledInstruction>,
+    account_keys: &[String],
     meta: UiTransactionStatusMeta,
 ) -> Vec<UiCompiledInstruction> {
     let inner_instructions = match meta.inner_instructions {
         OptionSerializer::Some(ii) => ii
             .into_iter()
             .flat_map(|ii| ii.instructions)
-            .flat_map(|ii| match ii {
-                UiInstruction::Compiled(ci) => Some(ci),
-                _ => None,
-            })
+            .filter_map(|ii| compiled_instruction(ii, account_keys))
             .collect::<Vec<UiCompiledInstruction>>(),
         OptionSerializer::None | OptionSerializer::Skip => vec![],
     };
@@ -313,5 +357,39 @@ fn instructions(
     [instruction, inner_instructions].concat()
 }
 
+/// Converts an instruction into a compiled one, which refers to its program and accounts
+/// by their index in the account keys of the transaction.
+///
+/// Instructions of parsed transactions are only partially decoded if the RPC doesn't know
+/// their program, which is the case of the Hyperlane programs. Fully parsed instructions
+/// are the ones of programs like the System program, and are skipped.
+fn compiled_instruction(
+    instruction: UiInstruction,
+    account_keys: &[String],
+) -> Option<UiCompiledInstruction> {
+    let instruction = match instruction {
+        UiInstruction::Compiled(instruction) => return Some(instruction),
+        UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => instruction,
+        UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => return None,
+    };
+
+    let index_of = |key: &String| {
+        account_keys
+            .iter()
+            .position(|account_key| account_key == key)
+            .and_then(|index| u8::try_from(index).ok())
+    };
+    let compiled = CompiledInstruction {
+        program_id_index: index_of(&instruction.program_id)?,
+        accounts: instruction
+            .accounts
+            .iter()
+            .map(index_of)
+            .collect::<Option<Vec<u8>>>()?,
+        data: from_base58(&instruction.data).ok()?,
+    };
+    Some(UiCompiledInstruction::from(&compiled))
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,12 +1,16 @@
 use std::fs;
 use std::path::PathBuf;
 
-use hyperlane_core::{LogMeta, U256};
-use solana_transaction_status::{EncodedTransactionWithStatusMeta, UiConfirmedBlock};
+use hyperlane_core::{LogMeta, H256, U256};
+use hyperlane_sealevel_mailbox::{events::MailboxEvent, spl_noop};
+use solana_sdk::{bs58, pubkey::Pubkey};
+use solana_transaction_status::{
+    EncodedTransactionWithStatusMeta, UiCompiledInstruction, UiConfirmedBlock,
+};
 
 use crate::log_meta_composer::{
     is_interchain_payment_instruction, is_message_delivery_instruction,
-    is_message_dispatch_instruction, search_transactions,
+    is_message_dispatch_instruction, mailbox_events, search_transactions,
 };
 use crate::utils::{decode_h256, decode_h512, decode_pubkey};
 
@@ -52,6 +56,57 @@ pub fn test_search_dispatched_message_versioned_transaction() {
     assert!(!transaction_hashes.is_empty());
 }
 
+#[test]
+pub fn test_search_dispatched_message_parsed_versioned_transaction() {
+    // given
+    let mailbox_program_id = decode_pubkey("EitxJuv2iBjsg2d7jVy2LDC1e2zBrx4GB5Y9h2Ko3A9Y").unwrap();
+    let dispatched_message_pda_account =
+        decode_pubkey("9g87Di4xiYVvBE5F8Atk8xorbbVD8yKqbdHRkFu5HEgw").unwrap();
+    // The same transaction, as returned with the `jsonParsed` encoding
+    let transactions = transactions(&json_parsed(&read_json(
+        "dispatch_message_versioned_txn.json",
+    )));
+
+    // when
+    let transaction_hashes = search_transactions(
+        transactions,
+        &mailbox_program_id,
+        &dispatched_message_pda_account,
+        is_message_dispatch_instruction,
+    );
+
+    // then
+    assert_eq!(transaction_hashes.len(), 1);
+}
+
+#[test]
+pub fn test_search_versioned_transaction_without_loaded_addresses() {
+    // given
+    let mailbox_program_id = decode_pubkey("EitxJuv2iBjsg2d7jVy2LDC1e2zBrx4GB5Y9h2Ko3A9Y").unwrap();
+    let dispatched_message_pda_account =
+        decode_pubkey("9g87Di4xiYVvBE5F8Atk8xorbbVD8yKqbdHRkFu5HEgw").unwrap();
+    let mut transaction: serde_json::Value =
+        serde_json::from_str(&read_json("dispatch_message_versioned_txn.json")).unwrap();
+    transaction["meta"]
+        .as_object_mut()
+        .unwrap()
+        .remove("loadedAddresses");
+    let transactions = transactions(&transaction.to_string());
+
+    // when
+    let transaction_hashes = search_transactions(
+        transactions,
+        &mailbox_program_id,
+        &dispatched_message_pda_account,
+        is_message_dispatch_instruction,
+    );
+
+    // then
+    // The accounts loaded from lookup tables can't be resolved, so the transaction is skipped
+    // rather than misread
+    assert!(transaction_hashes.is_empty());
+}
+
 #[test]
 pub fn test_search_delivered_message_transaction() {
     // given
@@ -156,6 +211,54 @@ fn test_log_meta_block_with_multiple_txs_only_one_successful() {
     });
 }
 
+#[test]
+fn test_mailbox_events_are_decoded_from_noop_instructions() {
+    // given
+    let mailbox_program_id = Pubkey::new_unique();
+    let account_keys = vec![
+        mailbox_program_id.to_string(),
+        spl_noop::id().to_string(),
+        Pubkey::new_unique().to_string(),
+    ];
+    let event = MailboxEvent::Process {
+        message_id: H256::random(),
+        origin: 1234,
+        sequence: 5,
+        recipient: Pubkey::new_unique(),
+    };
+    let process = compiled_instruction(0, &[1]);
+    let noop_event = compiled_instruction(1, &event.to_noop_data().unwrap());
+    // Other data logged to the SPL Noop program, e.g. by the recipient
+    let noop_other = compiled_instruction(1, b"Hyperlane inbox: 0x1234");
+
+    // when
+    let events = mailbox_events(
+        &mailbox_program_id,
+        &account_keys,
+        &[process, noop_other, noop_event.clone()],
+    );
+
+    // then
+    assert_eq!(events, vec![event]);
+    // Transactions that don't invoke the Mailbox have no Mailbox events
+    let other_program = compiled_instruction(2, &[1]);
+    assert!(mailbox_events(
+        &mailbox_program_id,
+        &account_keys,
+        &[other_program, noop_event]
+    )
+    .is_empty());
+}
+
+fn compiled_instruction(program_id_index: u8, data: &[u8]) -> UiCompiledInstruction {
+    serde_json::from_value(serde_json::json!({
+        "programIdIndex": program_id_index,
+        "accounts": [],
+        "data": bs58::encode(data).into_string(),
+    }))
+    .unwrap()
+}
+
 fn read_json(path: &str) -> String {
     let relative = PathBuf::new().join("src/log_meta_composer/").join(path);
     let absolute = fs::canonicalize(relative).expect("cannot find path");
@@ -167,3 +270,60 @@ fn transactions(json: &str) -> Vec<EncodedTransactionWithStatusMeta> {
     let transactions = vec![transaction];
     transactions
 }
+
+/// Converts a transaction encoded as `json` into the `jsonParsed` encoding, whose account
+/// keys include the ones loaded from lookup tables and whose instructions refer to accounts
+/// by their key
+fn json_parsed(json: &str) -> String {
+    let mut transaction: serde_json::Value = serde_json::from_str(json).unwrap();
+    let message = transaction["transaction"]["message"].clone();
+    let loaded_addresses = &transaction["meta"]["loadedAddresses"];
+    let account_keys: Vec<String> = [
+        &message["accountKeys"],
+        &loaded_addresses["writable"],
+        &loaded_addresses["readonly"],
+    ]
+    .into_iter()
+    .flat_map(|keys| keys.as_array().unwrap().clone())
+    .map(|key| key.as_str().unwrap().to_owned())
+    .collect();
+    let key = |index: &serde_json::Value| account_keys[index.as_u64().unwrap() as usize].clone();
+    let partially_decoded = |instruction: &serde_json::Value| {
+        serde_json::json!({
+            "programId": key(&instruction["programIdIndex"]),
+            "accounts": instruction["accounts"].as_array().unwrap().iter().map(key).collect::<Vec<_>>(),
+            "data": instruction["data"],
+        })
+    };
+    let partially_decoded_all = |instructions: &serde_json::Value| {
+        instructions
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(partially_decoded)
+            .collect::<Vec<_>>()
+    };
+
+    let inner_instructions: Vec<_> = transaction["meta"]["innerInstructions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|inner| {
+            serde_json::json!({
+                "index": inner["index"],
+                "instructions": partially_decoded_all(&inner["instructions"]),
+            })
+        })
+        .collect();
+    transaction["meta"]["innerInstructions"] = inner_instructions.into();
+    transaction["transaction"]["message"] = serde_json::json!({
+        "accountKeys": account_keys
+            .iter()
+            .map(|key| serde_json::json!({"pubkey": key, "writable": false, "signer": false}))
+            .collect::<Vec<_>>(),
+        "recentBlockhash": message["recentBlockhash"],
+        "instructions": partially_decoded_all(&message["instructions"]),
+        "addressTableLookups": message["addressTableLookups"],
+    });
+    transaction.to_string()
+}
@@ -1,25 +1,24 @@
 // Silence a clippy bug https://github.com/rust-lang/rust-clippy/issues/12281
 #![allow(clippy::blocks_in_conditions)]
 
-use std::{collections::HashMap, ops::RangeInclusive, str::FromStr as _};
+use std::{collections::HashMap, ops::RangeInclusive};
 
 use async_trait::async_trait;
 use borsh::{BorshDeserialize, BorshSerialize};
-use hyperlane_sealevel_interchain_security_module_interface::{
-    InterchainSecurityModuleInstruction, VerifyInstruction,
+use hyperlane_sealevel_client_lib::{
+    account_metas,
+    mailbox::{self as client_mailbox, ProcessAccountMetas},
 };
 use hyperlane_sealevel_mailbox::{
     accounts::{
         DispatchedMessageAccount, Inbox, InboxAccount, ProcessedMessageAccount,
         DISPATCHED_MESSAGE_DISCRIMINATOR, PROCESSED_MESSAGE_DISCRIMINATOR,
     },
-    instruction::InboxProcess,
+    instruction::{get_delivered_instruction, is_delivered_in_bitmap},
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds,
-    mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds,
-};
-use hyperlane_sealevel_message_recipient_interface::{
-    HandleInstruction, MessageRecipientInstruction,
+    mailbox_processed_message_pda_seeds,
 };
+use hyperlane_warp_route::TokenMessage;
 use lazy_static::lazy_static;
 use serializable_account_meta::SimulationReturnData;
 use solana_program::pubkey;
@@ -31,30 +30,32 @@ use solana_sdk::{
     pubkey::Pubkey,
     signer::Signer as _,
 };
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
 use tracing::{debug, info, instrument, warn};
 
 use hyperlane_core::{
     config::StrOrIntParseError, ChainCommunicationError, ChainResult, ContractLocator, Decode as _,
     Encode as _, FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
     HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, MerkleTreeHook,
-    ReorgPeriod, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, H512, U256,
+    PreTransaction, ReorgPeriod, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, H512, U256,
 };
 
 use crate::{
     account::{search_accounts_by_discriminator, search_and_validate_account},
     priority_fee::PriorityFeeOracle,
+    priority_fee_metrics::{PriorityFeeMetrics, PriorityFeeRecorder},
 };
 use crate::{
     log_meta_composer::{
         is_message_delivery_instruction, is_message_dispatch_instruction, LogMetaComposer,
     },
-    SealevelKeypair,
+    SealevelKeypair, SealevelPayers,
 };
 use crate::{tx_submitter::TransactionSubmitter, utils::force_non_signers};
-use crate::{ConnectionConf, SealevelProvider, SealevelRpcClient};
-
-const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
-const SPL_NOOP: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
+use crate::{ConnectionConf, KnownRecipientConfig, SealevelProvider, SealevelRpcClient};
 
 // Earlier versions of collateral warp routes were deployed off a version where the mint
 // was requested as a writeable account for handle instruction. This is not necessary,
@@ -76,25 +77,34 @@ lazy_static! {
     ]);
 }
 
+/// The most messages whose delivery status can be fetched with a single
+/// InboxGetDelivered simulation without exceeding the transaction size limit.
+const DELIVERED_BATCH_SIZE: usize = 16;
+
 /// A reference to a Mailbox contract on some Sealevel chain
 pub struct SealevelMailbox {
     pub(crate) program_id: Pubkey,
     inbox: (Pubkey, u8),
     pub(crate) outbox: (Pubkey, u8),
     pub(crate) provider: SealevelProvider,
-    payer: Option<SealevelKeypair>,
+    payers: Option<SealevelPayers>,
     priority_fee_oracle: Box<dyn PriorityFeeOracle>,
     tx_submitter: Box<dyn TransactionSubmitter>,
+    known_recipients: HashMap<Pubkey, KnownRecipientConfig>,
+    priority_fee_recorder: Option<PriorityFeeRecorder>,
 }
 
 impl SealevelMailbox {
-    /// Create a new sealevel mailbox
+    /// Create a new sealevel mailbox, whose transactions are spread across
+    /// `payers`. The priority fees of the transactions it submits are
+    /// recorded in `priority_fee_metrics`, if set.
     pub fn new(
         provider: SealevelProvider,
         tx_submitter: Box<dyn TransactionSubmitter>,
         conf: &ConnectionConf,
         locator: &ContractLocator,
-        payer: Option<SealevelKeypair>,
+        payers: Option<SealevelPayers>,
+        priority_fee_metrics: Option<PriorityFeeMetrics>,
     ) -> ChainResult<Self> {
         let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
         let domain = locator.domain.id();
@@ -110,10 +120,13 @@ impl SealevelMailbox {
             program_id,
             inbox,
             outbox,
-            payer,
+            payers,
             priority_fee_oracle: conf.priority_fee_oracle.create_oracle(),
             tx_submitter,
             provider,
+            known_recipients: conf.known_recipients.clone(),
+            priority_fee_recorder: priority_fee_metrics
+                .map(|metrics| PriorityFeeRecorder::new(metrics, locator.domain.name())),
         })
     }
 
@@ -141,12 +154,7 @@ impl SealevelMailbox {
         instruction: Instruction,
     ) -> ChainResult<Option<T>> {
         self.rpc()
-            .simulate_instruction(
-                self.payer
-                    .as_ref()
-                    .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
-                instruction,
-            )
+            .simulate_instruction(self.get_payers()?.primary(), instruction)
             .await
     }
 
@@ -156,12 +164,7 @@ impl SealevelMailbox {
         instruction: Instruction,
     ) -> ChainResult<Vec<AccountMeta>> {
         self.rpc()
-            .get_account_metas(
-                self.payer
-                    .as_ref()
-                    .ok_or_else(|| ChainCommunicationError::SignerUnavailable)?,
-                instruction,
-            )
+            .get_account_metas(self.get_payers()?.primary(), instruction)
             .await
     }
 
@@ -171,21 +174,12 @@ impl SealevelMailbox {
         recipient_program_id: Pubkey,
         ism_getter_account_metas: Vec<AccountMeta>,
     ) -> ChainResult<Pubkey> {
-        let mut accounts = vec![
-            // Inbox PDA
-            AccountMeta::new_readonly(self.inbox.0, false),
-            // The recipient program.
-            AccountMeta::new_readonly(recipient_program_id, false),
-        ];
-        accounts.extend(ism_getter_account_metas);
-
-        let instruction = Instruction::new_with_borsh(
+        let instruction = client_mailbox::get_recipient_ism_instruction(
             self.program_id,
-            &hyperlane_sealevel_mailbox::instruction::Instruction::InboxGetRecipientIsm(
-                recipient_program_id,
-            ),
-            accounts,
-        );
+            recipient_program_id,
+            ism_getter_account_metas,
+        )
+        .map_err(ChainCommunicationError::from_other)?;
         let ism = self
             .simulate_instruction::<SimulationReturnData<Pubkey>>(instruction)
             .await?
@@ -196,21 +190,49 @@ impl SealevelMailbox {
         Ok(ism)
     }
 
+    /// Gets the ISM of the recipient's messages from `origin`. Only inboxes
+    /// with a default ISM override for `origin` are asked with the
+    /// origin-aware instruction, so mailbox programs that predate the
+    /// overrides keep being supported.
+    pub async fn get_recipient_ism_for_origin(
+        &self,
+        recipient_program_id: Pubkey,
+        origin: u32,
+        ism_getter_account_metas: Vec<AccountMeta>,
+    ) -> ChainResult<Pubkey> {
+        let inbox = self.get_inbox().await?;
+        if !inbox.default_ism_overrides.contains_key(&origin) {
+            return self
+                .get_recipient_ism(recipient_program_id, ism_getter_account_metas)
+                .await;
+        }
+
+        let instruction = client_mailbox::get_recipient_ism_for_origin_instruction(
+            self.program_id,
+            recipient_program_id,
+            origin,
+            ism_getter_account_metas,
+        )
+        .map_err(ChainCommunicationError::from_other)?;
+        let ism = self
+            .simulate_instruction::<SimulationReturnData<Pubkey>>(instruction)
+            .await?
+            .ok_or(ChainCommunicationError::from_other_str(
+                "No return data from InboxGetRecipientIsmForOrigin instruction",
+            ))?
+            .return_data;
+        Ok(ism)
+    }
+
     /// Gets the account metas required for the recipient's
     /// `MessageRecipientInstruction::InterchainSecurityModule` instruction.
     pub async fn get_ism_getter_account_metas(
         &self,
         recipient_program_id: Pubkey,
     ) -> ChainResult<Vec<AccountMeta>> {
-        let instruction =
-            hyperlane_sealevel_message_recipient_interface::MessageRecipientInstruction::InterchainSecurityModuleAccountMetas;
-        self.get_non_signer_account_metas_with_instruction_bytes(
-            recipient_program_id,
-            &instruction
-                .encode()
-                .map_err(ChainCommunicationError::from_other)?,
-                hyperlane_sealevel_message_recipient_interface::INTERCHAIN_SECURITY_MODULE_ACCOUNT_METAS_PDA_SEEDS,
-        ).await
+        let instruction = account_metas::ism_getter_account_metas_instruction(recipient_program_id)
+            .map_err(ChainCommunicationError::from_other)?;
+        self.get_non_signer_account_metas(instruction).await
     }
 
     /// Gets the account metas required for the ISM's `Verify` instruction.
@@ -221,18 +243,9 @@ impl SealevelMailbox {
         message: Vec<u8>,
     ) -> ChainResult<Vec<AccountMeta>> {
         let instruction =
-            InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction {
-                metadata,
-                message,
-            });
-        self.get_non_signer_account_metas_with_instruction_bytes(
-            ism,
-            &instruction
-                .encode()
-                .map_err(ChainCommunicationError::from_other)?,
-            hyperlane_sealevel_interchain_security_module_interface::VERIFY_ACCOUNT_METAS_PDA_SEEDS,
-        )
-        .await
+            account_metas::ism_verify_account_metas_instruction(ism, metadata, message)
+                .map_err(ChainCommunicationError::from_other)?;
+        self.get_non_signer_account_metas(instruction).await
     }
 
     /// Gets the account metas required for the recipient's `MessageRecipientInstruction::Handle` instruction.
@@ -241,21 +254,10 @@ impl SealevelMailbox {
         message: &HyperlaneMessage,
     ) -> ChainResult<Vec<AccountMeta>> {
         let recipient_program_id = Pubkey::new_from_array(message.recipient.into());
-        let instruction = MessageRecipientInstruction::HandleAccountMetas(HandleInstruction {
-            sender: message.sender,
-            origin: message.origin,
-            message: message.body.clone(),
-        });
-
-        let mut account_metas = self
-            .get_non_signer_account_metas_with_instruction_bytes(
-                recipient_program_id,
-                &instruction
-                    .encode()
-                    .map_err(ChainCommunicationError::from_other)?,
-                hyperlane_sealevel_message_recipient_interface::HANDLE_ACCOUNT_METAS_PDA_SEEDS,
-            )
-            .await?;
+        let instruction = account_metas::handle_account_metas_instruction(message)
+            .map_err(ChainCommunicationError::from_other)?;
+
+        let mut account_metas = self.get_non_signer_account_metas(instruction).await?;
 
         if let Some(forced_readonly_account) =
             RECIPIENT_FORCED_READONLY_ACCOUNTS.get(&recipient_program_id)
@@ -269,20 +271,10 @@ impl SealevelMailbox {
         Ok(account_metas)
     }
 
-    async fn get_non_signer_account_metas_with_instruction_bytes(
+    async fn get_non_signer_account_metas(
         &self,
-        program_id: Pubkey,
-        instruction_data: &[u8],
-        account_metas_pda_seeds: &[&[u8]],
+        instruction: Instruction,
     ) -> ChainResult<Vec<AccountMeta>> {
-        let (account_metas_pda_key, _) =
-            Pubkey::find_program_address(account_metas_pda_seeds, &program_id);
-        let instruction = Instruction::new_with_bytes(
-            program_id,
-            instruction_data,
-            vec![AccountMeta::new(account_metas_pda_key, false)],
-        );
-
         let account_metas = self.get_account_metas(instruction).await?;
 
         // Force all dynamically provided account metas to be non-signers to protect against
@@ -294,84 +286,57 @@ impl SealevelMailbox {
         &self,
         message: &HyperlaneMessage,
         metadata: &[u8],
+        payer: &Pubkey,
     ) -> ChainResult<Instruction> {
         let recipient: Pubkey = message.recipient.0.into();
-        let mut encoded_message = vec![];
-        message.write_to(&mut encoded_message).unwrap();
-
-        let payer = self.get_payer()?;
 
-        let (process_authority_key, _process_authority_bump) = Pubkey::try_find_program_address(
-            mailbox_process_authority_pda_seeds!(&recipient),
-            &self.program_id,
-        )
-        .ok_or_else(|| {
-            ChainCommunicationError::from_other_str(
-                "Could not find program address for process authority",
-            )
-        })?;
-        let (processed_message_account_key, _processed_message_account_bump) =
-            Pubkey::try_find_program_address(
-                mailbox_processed_message_pda_seeds!(message.id()),
-                &self.program_id,
+        // Known recipients are configured with all the accounts they need, so
+        // there's nothing to simulate.
+        if let Some(known_recipient) = self.known_recipients.get(&recipient) {
+            debug!(%recipient, "Using configured account metas for known recipient");
+            return client_mailbox::process_instruction(
+                self.program_id,
+                *payer,
+                message,
+                metadata.to_vec(),
+                known_recipient.process_account_metas(message)?,
             )
-            .ok_or_else(|| {
-                ChainCommunicationError::from_other_str(
-                    "Could not find program address for processed message account",
-                )
-            })?;
+            .map_err(ChainCommunicationError::from_other);
+        }
 
         // Get the account metas required for the recipient.InterchainSecurityModule instruction.
         let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
 
-        // Get the recipient ISM.
+        // Get the recipient ISM, which may be the default ISM override of the origin.
         let ism = self
-            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
+            .get_recipient_ism_for_origin(
+                recipient,
+                message.origin,
+                ism_getter_account_metas.clone(),
+            )
             .await?;
 
-        let ixn =
-            hyperlane_sealevel_mailbox::instruction::Instruction::InboxProcess(InboxProcess {
-                metadata: metadata.to_vec(),
-                message: encoded_message.clone(),
-            });
-        let ixn_data = ixn
-            .into_instruction_data()
-            .map_err(ChainCommunicationError::from_other)?;
-
-        // Craft the accounts for the transaction.
-        let mut accounts: Vec<AccountMeta> = vec![
-            AccountMeta::new_readonly(payer.pubkey(), true),
-            AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM).unwrap(), false),
-            AccountMeta::new(self.inbox.0, false),
-            AccountMeta::new_readonly(process_authority_key, false),
-            AccountMeta::new(processed_message_account_key, false),
-        ];
-        accounts.extend(ism_getter_account_metas);
-        accounts.extend([
-            AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
-            AccountMeta::new_readonly(ism, false),
-        ]);
-
         // Get the account metas required for the ISM.Verify instruction.
         let ism_verify_account_metas = self
-            .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
+            .get_ism_verify_account_metas(ism, metadata.into(), message.to_vec())
             .await?;
-        accounts.extend(ism_verify_account_metas);
-
-        // The recipient.
-        accounts.extend([AccountMeta::new_readonly(recipient, false)]);
 
         // Get account metas required for the Handle instruction
         let handle_account_metas = self.get_handle_account_metas(message).await?;
-        accounts.extend(handle_account_metas);
 
-        let process_instruction = Instruction {
-            program_id: self.program_id,
-            data: ixn_data,
-            accounts,
-        };
-
-        Ok(process_instruction)
+        client_mailbox::process_instruction(
+            self.program_id,
+            *payer,
+            message,
+            metadata.to_vec(),
+            ProcessAccountMetas {
+                ism_getter: ism_getter_account_metas,
+                ism,
+                ism_verify: ism_verify_account_metas,
+                handle: handle_account_metas,
+            },
+        )
+        .map_err(ChainCommunicationError::from_other)
     }
 
     async fn get_inbox(&self) -> ChainResult<Box<Inbox>> {
@@ -385,11 +350,129 @@ impl SealevelMailbox {
         Ok(inbox)
     }
 
-    fn get_payer(&self) -> ChainResult<&SealevelKeypair> {
-        self.payer
+    fn get_payers(&self) -> ChainResult<&SealevelPayers> {
+        self.payers
             .as_ref()
             .ok_or_else(|| ChainCommunicationError::SignerUnavailable)
     }
+
+    /// Submits a transaction with `instruction`, paid for by `payer`, and
+    /// waits for it to be confirmed at `commitment`
+    async fn submit_instruction(
+        &self,
+        instruction: Instruction,
+        payer: &SealevelKeypair,
+        commitment: CommitmentConfig,
+    ) -> ChainResult<TxOutcome> {
+        let (tx, estimate) = self
+            .provider
+            .rpc()
+            .build_estimated_tx_for_instruction(
+                instruction,
+                payer,
+                &*self.tx_submitter,
+                &*self.priority_fee_oracle,
+            )
+            .await?;
+
+        tracing::info!(?tx, "Created sealevel transaction");
+
+        let rpc = self.tx_submitter.rpc_client().unwrap_or_else(|| self.rpc());
+
+        // The slot the transaction is sent at, to measure how long it takes to land
+        let sent_slot = match &self.priority_fee_recorder {
+            Some(_) => rpc
+                .get_slot_with_commitment(CommitmentConfig::processed())
+                .await
+                .map_err(|err| warn!(?err, "Failed to get slot before sending transaction"))
+                .ok(),
+            None => None,
+        };
+
+        let signature = self.tx_submitter.send_transaction(&tx, true).await?;
+
+        tracing::info!(?tx, ?signature, "Sealevel transaction sent");
+
+        let send_instant = std::time::Instant::now();
+
+        // Wait for the transaction to be confirmed.
+        let landed_slot = rpc.wait_for_transaction_confirmation(&tx).await;
+
+        if let Some(recorder) = &self.priority_fee_recorder {
+            recorder
+                .record(
+                    rpc,
+                    &tx,
+                    estimate.compute_unit_price_micro_lamports,
+                    sent_slot,
+                    landed_slot.as_ref().ok().copied(),
+                )
+                .await;
+        }
+        landed_slot?;
+
+        // We expect time_to_confirm to fluctuate depending on the commitment level when submitting the
+        // tx, but still use it as a proxy for tx latency to help debug.
+        tracing::info!(?tx, ?signature, time_to_confirm=?send_instant.elapsed(), "Sealevel transaction confirmed");
+
+        // TODO: not sure if this actually checks if the transaction was executed / reverted?
+        // Confirm the transaction.
+        let executed = rpc
+            .confirm_transaction_with_commitment(&signature, commitment)
+            .await
+            .map_err(|err| warn!("Failed to confirm transaction: {}", err))
+            .unwrap_or(false);
+        let txid = signature.into();
+
+        Ok(TxOutcome {
+            transaction_id: txid,
+            executed,
+            // TODO use correct data upon integrating IGP support
+            gas_price: U256::zero().try_into()?,
+            gas_used: U256::zero(),
+        })
+    }
+
+    /// The instruction that creates the associated token account for `mint`
+    /// of the recipient of a warp route transfer, funded by `payer`, or None
+    /// if it already exists. The payer covers the rent exemption of the
+    /// account, which the relayer limits per config since the gas payment of
+    /// the message doesn't include it.
+    async fn create_associated_token_account_instruction(
+        &self,
+        message: &HyperlaneMessage,
+        mint: H256,
+        payer: &Pubkey,
+    ) -> ChainResult<Option<Instruction>> {
+        let token_message = TokenMessage::read_from(&mut message.body.as_slice())?;
+        let wallet = Pubkey::new_from_array(token_message.recipient().0);
+        let mint = Pubkey::new_from_array(mint.0);
+        // The mint is owned by the token program it belongs to, which is
+        // either the original token program or Token-2022
+        let token_program = self
+            .rpc()
+            .get_account_with_finalized_commitment(&mint)
+            .await?
+            .owner;
+        let associated_token_account =
+            get_associated_token_address_with_program_id(&wallet, &mint, &token_program);
+        if self
+            .rpc()
+            .get_account_option_with_finalized_commitment(&associated_token_account)
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+        // Creating the account idempotently makes it harmless to submit while
+        // a previous creation isn't finalized yet
+        Ok(Some(create_associated_token_account_idempotent(
+            payer,
+            &wallet,
+            &mint,
+            &token_program,
+        )))
+    }
 }
 
 impl HyperlaneContract for SealevelMailbox {
@@ -414,8 +497,6 @@ impl std::fmt::Debug for SealevelMailbox {
     }
 }
 
-// TODO refactor the sealevel client into a lib and bin, pull in and use the lib here rather than
-// duplicating.
 #[async_trait]
 impl Mailbox for SealevelMailbox {
     #[instrument(err, ret, skip(self))]
@@ -439,6 +520,38 @@ impl Mailbox for SealevelMailbox {
         Ok(account.is_some())
     }
 
+    #[instrument(err, ret, skip(self))]
+    async fn delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {
+        let payer = self.get_payers()?.primary();
+
+        let mut delivered = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(DELIVERED_BATCH_SIZE) {
+            let instruction = get_delivered_instruction(self.program_id, chunk.to_vec())
+                .map_err(ChainCommunicationError::from_other)?;
+            // Like `delivered`, only consider finalized deliveries
+            let bitmap = self
+                .rpc()
+                .simulate_instruction_with_commitment::<SimulationReturnData<Vec<u8>>>(
+                    payer,
+                    instruction,
+                    Some(CommitmentConfig::finalized()),
+                )
+                .await?
+                .ok_or(ChainCommunicationError::from_other_str(
+                    "No return data from InboxGetDelivered instruction",
+                ))?
+                .return_data;
+            delivered.extend((0..chunk.len()).map(|index| is_delivered_in_bitmap(&bitmap, index)));
+        }
+        Ok(delivered)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn local_domain(&self) -> ChainResult<u32> {
+        let inbox = self.get_inbox().await?;
+        Ok(inbox.local_domain)
+    }
+
     #[instrument(err, ret, skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
         let inbox = self.get_inbox().await?;
@@ -462,6 +575,18 @@ impl Mailbox for SealevelMailbox {
         Ok(ism_pubkey.to_bytes().into())
     }
 
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism_for_origin(&self, recipient: H256, origin: u32) -> ChainResult<H256> {
+        let recipient_program_id = Pubkey::new_from_array(recipient.0);
+        let ism_getter_account_metas = self
+            .get_ism_getter_account_metas(recipient_program_id)
+            .await?;
+        let ism_pubkey = self
+            .get_recipient_ism_for_origin(recipient_program_id, origin, ism_getter_account_metas)
+            .await?;
+        Ok(ism_pubkey.to_bytes().into())
+    }
+
     #[instrument(err, ret, skip(self))]
     async fn process(
         &self,
@@ -475,52 +600,14 @@ impl Mailbox for SealevelMailbox {
         // is retry logic in the agents.
         let commitment = CommitmentConfig::processed();
 
-        let process_instruction = self.get_process_instruction(message, metadata).await?;
-
-        let tx = self
-            .provider
-            .rpc()
-            .build_estimated_tx_for_instruction(
-                process_instruction,
-                self.get_payer()?,
-                &*self.tx_submitter,
-                &*self.priority_fee_oracle,
-            )
+        // The payer is checked out for the whole submission, since it also
+        // funds the account that marks the message as processed
+        let payer = self.get_payers()?.checkout();
+        let process_instruction = self
+            .get_process_instruction(message, metadata, &payer.pubkey())
             .await?;
-
-        tracing::info!(?tx, "Created sealevel transaction to process message");
-
-        let signature = self.tx_submitter.send_transaction(&tx, true).await?;
-
-        tracing::info!(?tx, ?signature, "Sealevel transaction sent");
-
-        let send_instant = std::time::Instant::now();
-
-        let rpc = self.tx_submitter.rpc_client().unwrap_or_else(|| self.rpc());
-
-        // Wait for the transaction to be confirmed.
-        rpc.wait_for_transaction_confirmation(&tx).await?;
-
-        // We expect time_to_confirm to fluctuate depending on the commitment level when submitting the
-        // tx, but still use it as a proxy for tx latency to help debug.
-        tracing::info!(?tx, ?signature, time_to_confirm=?send_instant.elapsed(), "Sealevel transaction confirmed");
-
-        // TODO: not sure if this actually checks if the transaction was executed / reverted?
-        // Confirm the transaction.
-        let executed = rpc
-            .confirm_transaction_with_commitment(&signature, commitment)
+        self.submit_instruction(process_instruction, &payer, commitment)
             .await
-            .map_err(|err| warn!("Failed to confirm inbox process transaction: {}", err))
-            .unwrap_or(false);
-        let txid = signature.into();
-
-        Ok(TxOutcome {
-            transaction_id: txid,
-            executed,
-            // TODO use correct data upon integrating IGP support
-            gas_price: U256::zero().try_into()?,
-            gas_used: U256::zero(),
-        })
     }
 
     #[instrument(err, ret, skip(self))]
@@ -532,7 +619,10 @@ impl Mailbox for SealevelMailbox {
         // Getting a process instruction in Sealevel is a pretty expensive operation
         // that involves some view calls. Consider reusing the instruction with subsequent
         // calls to `process` to avoid this cost.
-        let process_instruction = self.get_process_instruction(message, metadata).await?;
+        let payer = self.get_payers()?.primary();
+        let process_instruction = self
+            .get_process_instruction(message, metadata, &payer.pubkey())
+            .await?;
 
         // The returned costs are unused at the moment - we simply want to perform a simulation to
         // determine if the message will revert or not.
@@ -540,7 +630,7 @@ impl Mailbox for SealevelMailbox {
             .rpc()
             .get_estimated_costs_for_instruction(
                 process_instruction,
-                self.get_payer()?,
+                payer,
                 &*self.tx_submitter,
                 &*self.priority_fee_oracle,
             )
@@ -554,12 +644,56 @@ impl Mailbox for SealevelMailbox {
             gas_limit: U256::zero(),
             gas_price: FixedPointNumber::zero(),
             l2_gas_limit: None,
+            l1_data_fee: None,
+            l1_blob_base_fee: None,
         })
     }
 
     fn process_calldata(&self, _message: &HyperlaneMessage, _metadata: &[u8]) -> Vec<u8> {
         todo!()
     }
+
+    #[instrument(err, ret, skip(self))]
+    async fn pre_transaction_required(
+        &self,
+        message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<bool> {
+        match pre_transaction {
+            PreTransaction::CreateAssociatedTokenAccount { mint } => {
+                let payer = self.get_payers()?.primary().pubkey();
+                Ok(self
+                    .create_associated_token_account_instruction(message, *mint, &payer)
+                    .await?
+                    .is_some())
+            }
+            _ => Err(ChainCommunicationError::PreTransactionUnsupported(
+                pre_transaction.kind(),
+            )),
+        }
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn submit_pre_transaction(
+        &self,
+        message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<TxOutcome> {
+        let PreTransaction::CreateAssociatedTokenAccount { mint } = pre_transaction else {
+            return Err(ChainCommunicationError::PreTransactionUnsupported(
+                pre_transaction.kind(),
+            ));
+        };
+        let payer = self.get_payers()?.checkout();
+        let instruction = self
+            .create_associated_token_account_instruction(message, *mint, &payer.pubkey())
+            .await?
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Associated token account already exists")
+            })?;
+        self.submit_instruction(instruction, &payer, CommitmentConfig::processed())
+            .await
+    }
 }
 
 /// Struct that retrieves event data for a Sealevel Mailbox contract
@@ -581,7 +715,7 @@ impl SealevelMailboxIndexer {
         conf: &ConnectionConf,
         advanced_log_meta: bool,
     ) -> ChainResult<Self> {
-        let mailbox = SealevelMailbox::new(provider, tx_submitter, conf, locator, None)?;
+        let mailbox = SealevelMailbox::new(provider, tx_submitter, conf, locator, None, None)?;
 
         let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
 
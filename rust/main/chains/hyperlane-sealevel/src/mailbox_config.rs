@@ -0,0 +1,314 @@
+use std::{collections::HashSet, ops::RangeInclusive, str::FromStr};
+
+use async_trait::async_trait;
+use hyperlane_sealevel_mailbox::{
+    events::MailboxEvent, instruction::Instruction as MailboxInstruction,
+    mailbox_config_events_pda_seeds,
+};
+use solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiCompiledInstruction;
+use tracing::{info, warn};
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, Indexed, Indexer, LogMeta,
+    MailboxConfigChange, SequenceAwareIndexer, H256, U256,
+};
+
+use crate::log_meta_composer::{filter_by_encoding, filter_by_validity, mailbox_events};
+use crate::utils::{decode_h256, from_base58};
+use crate::{SealevelProvider, SealevelRpcClient};
+
+/// Indexes the configuration changes of a Sealevel Mailbox.
+///
+/// The Mailbox logs its configuration changes with a CPI to the SPL Noop
+/// program. The instructions that change its configuration also reference a
+/// config events PDA, so the transactions can be found by its signatures.
+#[derive(Debug)]
+pub struct SealevelMailboxConfigIndexer {
+    provider: SealevelProvider,
+    program_id: Pubkey,
+    config_events_account: Pubkey,
+}
+
+impl SealevelMailboxConfigIndexer {
+    /// Create a new SealevelMailboxConfigIndexer
+    pub fn new(provider: SealevelProvider, locator: &ContractLocator) -> Self {
+        let program_id = Pubkey::from(<[u8; 32]>::from(locator.address));
+        let (config_events_account, _bump) =
+            Pubkey::find_program_address(mailbox_config_events_pda_seeds!(), &program_id);
+        Self {
+            provider,
+            program_id,
+            config_events_account,
+        }
+    }
+
+    fn rpc(&self) -> &SealevelRpcClient {
+        self.provider.rpc()
+    }
+
+    /// Signatures of the successful transactions in the slot range that
+    /// reference the config events account, oldest first
+    async fn signatures_in_range(
+        &self,
+        range: &RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Slot, String)>> {
+        let (start, end) = (Slot::from(*range.start()), Slot::from(*range.end()));
+        let mut signatures = vec![];
+        let mut before = None;
+        // Signatures are returned newest first, one page at a time
+        loop {
+            let page = self
+                .rpc()
+                .get_signatures_for_address(&self.config_events_account, before)
+                .await?;
+            let Some(oldest) = page.last() else {
+                break;
+            };
+            let reached_start = oldest.slot < start;
+            before = Some(
+                Signature::from_str(&oldest.signature)
+                    .map_err(ChainCommunicationError::from_other)?,
+            );
+            signatures.extend(
+                page.into_iter()
+                    .filter(|status| status.err.is_none())
+                    .filter(|status| start <= status.slot && status.slot <= end)
+                    .map(|status| (status.slot, status.signature)),
+            );
+            if reached_start {
+                break;
+            }
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    /// Config changes made in the given transactions of the slot
+    async fn config_changes_in_slot(
+        &self,
+        slot: Slot,
+        signatures: &HashSet<&String>,
+    ) -> ChainResult<Vec<(Indexed<MailboxConfigChange>, LogMeta)>> {
+        let block = self.rpc().get_block(slot).await?;
+        let block_hash = decode_h256(&block.blockhash)?;
+
+        let mut changes = vec![];
+        for (transaction_index, tx) in block
+            .transactions
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let Some((tx, meta)) = filter_by_encoding(tx) else {
+                continue;
+            };
+            if !tx
+                .signatures
+                .first()
+                .is_some_and(|signature| signatures.contains(signature))
+            {
+                continue;
+            }
+            let Some((transaction_id, account_keys, instructions)) = filter_by_validity(tx, meta)
+            else {
+                continue;
+            };
+            let tx_changes = config_changes(&self.program_id, &account_keys, &instructions);
+            changes.extend(
+                tx_changes
+                    .into_iter()
+                    .enumerate()
+                    .map(|(log_index, change)| {
+                        let log_meta = LogMeta {
+                            address: self.program_id.to_bytes().into(),
+                            block_number: slot,
+                            block_hash,
+                            transaction_id,
+                            transaction_index: transaction_index as u64,
+                            log_index: U256::from(log_index),
+                        };
+                        (change.into(), log_meta)
+                    }),
+            );
+        }
+        Ok(changes)
+    }
+}
+
+#[async_trait]
+impl Indexer<MailboxConfigChange> for SealevelMailboxConfigIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MailboxConfigChange>, LogMeta)>> {
+        info!(
+            ?range,
+            "Fetching SealevelMailboxConfigIndexer config changes"
+        );
+
+        let signatures = self.signatures_in_range(&range).await?;
+        let mut slots = signatures.iter().map(|(slot, _)| *slot).collect::<Vec<_>>();
+        slots.dedup();
+        let signatures = signatures
+            .iter()
+            .map(|(_, signature)| signature)
+            .collect::<HashSet<_>>();
+
+        let mut changes = vec![];
+        for slot in slots {
+            changes.extend(self.config_changes_in_slot(slot, &signatures).await?);
+        }
+        Ok(changes)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.rpc().get_slot().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MailboxConfigChange> for SealevelMailboxConfigIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // Config changes have no sequence, they are indexed by slot
+        let tip = self.rpc().get_slot().await?;
+        Ok((None, tip))
+    }
+}
+
+/// Decodes the config changes logged by the Mailbox in a transaction.
+///
+/// Anyone can log data to the SPL Noop program, so an event is only accepted
+/// if the transaction also instructs the Mailbox to make the same change. As
+/// the transaction succeeded, the Mailbox owner must have signed it.
+fn config_changes(
+    program_id: &Pubkey,
+    account_keys: &[String],
+    instructions: &[UiCompiledInstruction],
+) -> Vec<MailboxConfigChange> {
+    let program_id_str = program_id.to_string();
+    let mut mailbox_instructions = instructions
+        .iter()
+        .filter(|instruction| {
+            account_keys.get(instruction.program_id_index as usize) == Some(&program_id_str)
+        })
+        .filter_map(|instruction| from_base58(&instruction.data).ok())
+        .filter_map(|data| MailboxInstruction::from_instruction_data(&data).ok())
+        .collect::<Vec<_>>();
+
+    mailbox_events(program_id, account_keys, instructions)
+        .into_iter()
+        .filter_map(|event| to_config_change(&event).map(|change| (event, change)))
+        .filter_map(|(event, change)| {
+            let matching_instruction =
+                mailbox_instructions
+                    .iter()
+                    .position(|instruction| match (instruction, &event) {
+                        (
+                            MailboxInstruction::InboxSetDefaultIsm(ism),
+                            MailboxEvent::DefaultIsmSet { new_ism, .. },
+                        ) => ism == new_ism,
+                        (
+                            MailboxInstruction::TransferOwnership(owner),
+                            MailboxEvent::OwnershipTransferred { new_owner, .. },
+                        ) => owner == new_owner,
+                        _ => false,
+                    });
+            match matching_instruction {
+                Some(index) => {
+                    mailbox_instructions.remove(index);
+                    Some(change)
+                }
+                None => {
+                    warn!(
+                        ?event,
+                        "Ignoring mailbox config event without a matching mailbox instruction"
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// The config change of an event, or None if the event isn't a config change
+fn to_config_change(event: &MailboxEvent) -> Option<MailboxConfigChange> {
+    let to_h256 = |pubkey: &Pubkey| H256::from(pubkey.to_bytes());
+    match event {
+        MailboxEvent::DefaultIsmSet {
+            previous_ism,
+            new_ism,
+        } => Some(MailboxConfigChange::DefaultIsmSet {
+            previous_ism: to_h256(previous_ism),
+            new_ism: to_h256(new_ism),
+        }),
+        MailboxEvent::OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        } => Some(MailboxConfigChange::OwnershipTransferred {
+            previous_owner: previous_owner.as_ref().map(to_h256),
+            new_owner: new_owner.as_ref().map(to_h256),
+        }),
+        MailboxEvent::Dispatch { .. } | MailboxEvent::Process { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_sealevel_mailbox::spl_noop;
+    use solana_sdk::bs58;
+
+    use super::*;
+
+    fn compiled_instruction(program_id_index: u8, data: &[u8]) -> UiCompiledInstruction {
+        serde_json::from_value(serde_json::json!({
+            "programIdIndex": program_id_index,
+            "accounts": [],
+            "data": bs58::encode(data).into_string(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_config_changes_require_matching_mailbox_instruction() {
+        let program_id = Pubkey::new_unique();
+        let account_keys = vec![program_id.to_string(), spl_noop::id().to_string()];
+        let (previous_ism, new_ism) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let event = MailboxEvent::DefaultIsmSet {
+            previous_ism,
+            new_ism,
+        };
+        let set_default_ism = |ism| {
+            compiled_instruction(
+                0,
+                &MailboxInstruction::InboxSetDefaultIsm(ism)
+                    .into_instruction_data()
+                    .unwrap(),
+            )
+        };
+        let noop_event = compiled_instruction(1, &event.to_noop_data().unwrap());
+
+        let changes = config_changes(
+            &program_id,
+            &account_keys,
+            &[set_default_ism(new_ism), noop_event.clone()],
+        );
+        assert_eq!(
+            changes,
+            vec![MailboxConfigChange::DefaultIsmSet {
+                previous_ism: H256::from(previous_ism.to_bytes()),
+                new_ism: H256::from(new_ism.to_bytes()),
+            }]
+        );
+
+        // Events logged without the Mailbox making the change are ignored
+        let changes = config_changes(&program_id, &account_keys, &[noop_event.clone()]);
+        assert!(changes.is_empty());
+        let changes = config_changes(
+            &program_id,
+            &account_keys,
+            &[set_default_ism(Pubkey::new_unique()), noop_event],
+        );
+        assert!(changes.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use std::time::Instant;
 
+use hyperlane_core::rpc_clients::RpcRateLimiter;
 use hyperlane_metric::prometheus_metric::{PrometheusClientMetrics, PrometheusConfig};
 use solana_client::{
     client_error::ClientError,
@@ -16,14 +17,21 @@ pub struct PrometheusSealevelRpcSender {
     pub inner: HttpSender,
     pub metrics: PrometheusClientMetrics,
     pub config: PrometheusConfig,
+    pub rate_limiter: Option<RpcRateLimiter>,
 }
 
 impl PrometheusSealevelRpcSender {
-    pub fn new(url: Url, metrics: PrometheusClientMetrics, config: PrometheusConfig) -> Self {
+    pub fn new(
+        url: Url,
+        metrics: PrometheusClientMetrics,
+        config: PrometheusConfig,
+        rate_limiter: Option<RpcRateLimiter>,
+    ) -> Self {
         Self {
             inner: HttpSender::new(url),
             metrics,
             config,
+            rate_limiter,
         }
     }
 }
@@ -40,6 +48,9 @@ impl RpcSender for PrometheusSealevelRpcSender {
         request: RpcRequest,
         params: serde_json::Value,
     ) -> Result<serde_json::Value, ClientError> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
         let start = Instant::now();
         let method = format!("{}", request);
 
@@ -0,0 +1,134 @@
+use std::{
+    ops::Deref,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::SealevelKeypair;
+
+/// The keypairs that pay for and sign the transactions submitted to a
+/// program. Each submission checks out the payer with the fewest
+/// transactions in flight, so that submissions are spread across the payers
+/// and signed in parallel instead of all being funded by the same account.
+/// Clones share the same payers.
+#[derive(Debug, Clone)]
+pub struct SealevelPayers {
+    payers: Arc<Vec<PooledPayer>>,
+}
+
+#[derive(Debug)]
+struct PooledPayer {
+    keypair: SealevelKeypair,
+    in_flight: AtomicUsize,
+}
+
+impl SealevelPayers {
+    /// Pool the payers, the first of which is the primary one. Returns `None`
+    /// if there are none.
+    pub fn new(payers: Vec<SealevelKeypair>) -> Option<Self> {
+        if payers.is_empty() {
+            return None;
+        }
+        let payers = payers
+            .into_iter()
+            .map(|keypair| PooledPayer {
+                keypair,
+                in_flight: AtomicUsize::new(0),
+            })
+            .collect();
+        Some(Self {
+            payers: Arc::new(payers),
+        })
+    }
+
+    /// The payer of simulations, which never land so don't need to be spread
+    pub fn primary(&self) -> &SealevelKeypair {
+        &self.payers[0].keypair
+    }
+
+    /// How many payers there are
+    pub fn len(&self) -> usize {
+        self.payers.len()
+    }
+
+    /// Always false, since there's at least one payer
+    pub fn is_empty(&self) -> bool {
+        self.payers.is_empty()
+    }
+
+    /// Checks out the payer with the fewest transactions in flight, which it
+    /// counts one more of until the returned lease is dropped. Ties go to the
+    /// payer that comes first.
+    pub fn checkout(&self) -> PayerLease {
+        let index = self
+            .payers
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, payer)| payer.in_flight.load(Ordering::Relaxed))
+            .map(|(index, _)| index)
+            .unwrap_or_default();
+        self.payers[index].in_flight.fetch_add(1, Ordering::Relaxed);
+        PayerLease {
+            payers: self.payers.clone(),
+            index,
+        }
+    }
+}
+
+/// A payer checked out for a transaction in flight
+#[derive(Debug)]
+pub struct PayerLease {
+    payers: Arc<Vec<PooledPayer>>,
+    index: usize,
+}
+
+impl Deref for PayerLease {
+    type Target = SealevelKeypair;
+
+    fn deref(&self) -> &Self::Target {
+        &self.payers[self.index].keypair
+    }
+}
+
+impl Drop for PayerLease {
+    fn drop(&mut self) {
+        self.payers[self.index]
+            .in_flight
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use solana_sdk::{signature::Keypair, signer::Signer};
+
+    use super::*;
+
+    #[test]
+    fn test_checkouts_are_spread_across_payers() {
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::new()).collect();
+        let pubkeys: Vec<_> = keypairs.iter().map(Signer::pubkey).collect();
+        let payers =
+            SealevelPayers::new(keypairs.into_iter().map(SealevelKeypair::new).collect()).unwrap();
+        assert_eq!(payers.primary().pubkey(), pubkeys[0]);
+
+        let first = payers.checkout();
+        let second = payers.checkout();
+        let third = payers.checkout();
+        assert_eq!(
+            vec![first.pubkey(), second.pubkey(), third.pubkey()],
+            pubkeys
+        );
+
+        // The payer of a landed transaction is the least busy again
+        drop(second);
+        assert_eq!(payers.checkout().pubkey(), pubkeys[1]);
+    }
+
+    #[test]
+    fn test_no_payers() {
+        assert!(SealevelPayers::new(vec![]).is_none());
+    }
+}
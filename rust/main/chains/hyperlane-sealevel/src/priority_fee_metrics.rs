@@ -0,0 +1,198 @@
+use prometheus::{HistogramVec, IntCounterVec};
+use solana_program::clock::Slot;
+use solana_sdk::transaction::Transaction;
+use tracing::{debug, warn};
+
+use crate::SealevelRpcClient;
+
+/// Expected label names for the `sealevel_priority_fee_micro_lamports` metric.
+pub const PRIORITY_FEE_MICRO_LAMPORTS_LABELS: &[&str] = &["chain"];
+/// Help string for the metric.
+pub const PRIORITY_FEE_MICRO_LAMPORTS_HELP: &str =
+    "Compute unit price in microlamports chosen for each submitted transaction";
+/// Buckets for the `sealevel_priority_fee_micro_lamports` metric, one per
+/// order of magnitude.
+pub const PRIORITY_FEE_MICRO_LAMPORTS_BUCKETS: &[f64] = &[
+    0.,
+    1.,
+    10.,
+    100.,
+    1_000.,
+    10_000.,
+    100_000.,
+    1_000_000.,
+    10_000_000.,
+    100_000_000.,
+];
+
+/// Expected label names for the `sealevel_priority_fee_percentile` metric.
+pub const PRIORITY_FEE_PERCENTILE_LABELS: &[&str] = &["chain"];
+/// Help string for the metric.
+pub const PRIORITY_FEE_PERCENTILE_HELP: &str = "Percentile of the priority fee of each landed \
+    transaction among the minimum prioritization fees paid to write the same accounts in the \
+    slots from its submission until it landed";
+/// Buckets for the `sealevel_priority_fee_percentile` metric.
+pub const PRIORITY_FEE_PERCENTILE_BUCKETS: &[f64] = &[10., 25., 50., 75., 90., 95., 99., 100.];
+
+/// Expected label names for the `sealevel_transaction_landing_slots` metric.
+pub const TRANSACTION_LANDING_SLOTS_LABELS: &[&str] = &["chain"];
+/// Help string for the metric.
+pub const TRANSACTION_LANDING_SLOTS_HELP: &str =
+    "Slots from submitting a transaction until it landed";
+/// Buckets for the `sealevel_transaction_landing_slots` metric, up to the
+/// ~150 slots after which the blockhash of a transaction expires.
+pub const TRANSACTION_LANDING_SLOTS_BUCKETS: &[f64] =
+    &[0., 1., 2., 3., 5., 10., 20., 50., 100., 150.];
+
+/// Expected label names for the `sealevel_transactions_landed_total` metric.
+pub const TRANSACTIONS_LANDED_TOTAL_LABELS: &[&str] = &["chain", "outcome"];
+/// Help string for the metric.
+pub const TRANSACTIONS_LANDED_TOTAL_HELP: &str = "Number of submitted transactions by whether \
+    they landed `within_target` slots, landed `late`, or were `not_landed` before their \
+    blockhash expired";
+
+/// Transactions that land within this many slots of being submitted landed
+/// on time
+pub const LANDING_TARGET_SLOTS: u64 = 10;
+
+const OUTCOME_WITHIN_TARGET: &str = "within_target";
+const OUTCOME_LATE: &str = "late";
+const OUTCOME_NOT_LANDED: &str = "not_landed";
+
+/// Metrics to backtest the priority fees chosen by the priority fee oracle
+/// against the fees transactions needed to land.
+#[derive(Clone, Debug)]
+pub struct PriorityFeeMetrics {
+    /// Priority fee of each submitted transaction, by chain
+    pub priority_fee_micro_lamports: HistogramVec,
+    /// Percentile of the priority fee of each landed transaction among the
+    /// recent prioritization fees, by chain
+    pub priority_fee_percentile: HistogramVec,
+    /// Slots from submission until landing, by chain
+    pub landing_slots: HistogramVec,
+    /// Submitted transactions by chain and outcome
+    pub landed_total: IntCounterVec,
+}
+
+/// Records the priority fee of the transactions submitted to a chain and
+/// how quickly they landed.
+#[derive(Clone, Debug)]
+pub(crate) struct PriorityFeeRecorder {
+    metrics: PriorityFeeMetrics,
+    chain: String,
+}
+
+impl PriorityFeeRecorder {
+    pub(crate) fn new(metrics: PriorityFeeMetrics, chain: &str) -> Self {
+        Self {
+            metrics,
+            chain: chain.to_owned(),
+        }
+    }
+
+    /// Records a transaction submitted with `priority_fee` at `sent_slot`,
+    /// which landed at `landed_slot` or never did if it's None.
+    pub(crate) async fn record(
+        &self,
+        rpc: &SealevelRpcClient,
+        tx: &Transaction,
+        priority_fee: u64,
+        sent_slot: Option<Slot>,
+        landed_slot: Option<Slot>,
+    ) {
+        self.metrics
+            .priority_fee_micro_lamports
+            .with_label_values(&[&self.chain])
+            .observe(priority_fee as f64);
+
+        let Some(landed_slot) = landed_slot else {
+            self.record_outcome(OUTCOME_NOT_LANDED);
+            return;
+        };
+        // Without the slot it was sent at, neither how long the transaction
+        // took to land nor the fees it competed with are known
+        let Some(sent_slot) = sent_slot else {
+            return;
+        };
+        let landing_slots = landed_slot.saturating_sub(sent_slot);
+        self.metrics
+            .landing_slots
+            .with_label_values(&[&self.chain])
+            .observe(landing_slots as f64);
+        self.record_outcome(if landing_slots <= LANDING_TARGET_SLOTS {
+            OUTCOME_WITHIN_TARGET
+        } else {
+            OUTCOME_LATE
+        });
+
+        let writable_accounts: Vec<_> = tx
+            .message
+            .account_keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| tx.message.is_writable(*i))
+            .map(|(_, account)| *account)
+            .collect();
+        let fees = match rpc.get_recent_prioritization_fees(&writable_accounts).await {
+            Ok(fees) => fees,
+            Err(err) => {
+                warn!(?err, "Failed to fetch recent prioritization fees");
+                return;
+            }
+        };
+        let competing_fees: Vec<u64> = fees
+            .into_iter()
+            .filter(|fee| (sent_slot..=landed_slot).contains(&fee.slot))
+            .map(|fee| fee.prioritization_fee)
+            .collect();
+        match fee_percentile(priority_fee, &competing_fees) {
+            Some(percentile) => {
+                debug!(
+                    priority_fee,
+                    percentile, landing_slots, "Recorded priority fee of landed transaction"
+                );
+                self.metrics
+                    .priority_fee_percentile
+                    .with_label_values(&[&self.chain])
+                    .observe(percentile);
+            }
+            None => debug!(
+                sent_slot,
+                landed_slot, "No prioritization fees known for the slots of the transaction"
+            ),
+        }
+    }
+
+    fn record_outcome(&self, outcome: &str) {
+        self.metrics
+            .landed_total
+            .with_label_values(&[&self.chain, outcome])
+            .inc();
+    }
+}
+
+/// The percentage of `fees` that are at most `fee`, or None if there are no
+/// fees to compare with.
+///
+/// Each fee is the lowest one paid to write the accounts in a slot, so a fee
+/// in the 100th percentile would have been enough to land in every slot.
+fn fee_percentile(fee: u64, fees: &[u64]) -> Option<f64> {
+    if fees.is_empty() {
+        return None;
+    }
+    let at_most = fees.iter().filter(|&&other| other <= fee).count();
+    Some(at_most as f64 * 100. / fees.len() as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fee_percentile() {
+        assert_eq!(fee_percentile(100, &[]), None);
+        assert_eq!(fee_percentile(100, &[0, 50, 100, 200]), Some(75.));
+        assert_eq!(fee_percentile(0, &[10, 20]), Some(0.));
+        assert_eq!(fee_percentile(1_000, &[10, 20]), Some(100.));
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use tokio::sync::oneshot;
+use tracing::trace;
+
+/// The most accounts `getMultipleAccounts` returns per request
+const MAX_ACCOUNTS_PER_BATCH: usize = 100;
+
+/// How long the first account requested in a batch waits for others to be
+/// requested with it
+const FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+type AccountResult = Result<Option<Account>, String>;
+
+type AccountsFuture = Pin<Box<dyn Future<Output = ChainResult<Vec<Option<Account>>>> + Send>>;
+
+type FetchAccounts = Arc<dyn Fn(Vec<Pubkey>) -> AccountsFuture + Send + Sync>;
+
+/// Coalesces the accounts requested concurrently into `getMultipleAccounts`
+/// requests, e.g. the PDAs of the messages and gas payments fetched by the
+/// indexers while they backfill.
+///
+/// A batch is fetched once it has as many accounts as a request can return,
+/// or once the flush interval elapsed since its first account was requested.
+/// The batches are fetched by spawned tasks, so that a caller giving up on
+/// its account doesn't leave the others of its batch waiting.
+#[derive(Clone)]
+pub(crate) struct AccountBatcher {
+    flush_interval: Duration,
+    fetch: FetchAccounts,
+    pending: Arc<Mutex<PendingBatch>>,
+}
+
+#[derive(Default)]
+struct PendingBatch {
+    /// Incremented every time a batch is taken, so that the flush of a batch
+    /// that already filled up doesn't take the next one early
+    generation: u64,
+    requests: Vec<(Pubkey, oneshot::Sender<AccountResult>)>,
+}
+
+impl PendingBatch {
+    fn take(&mut self) -> Vec<(Pubkey, oneshot::Sender<AccountResult>)> {
+        self.generation += 1;
+        std::mem::take(&mut self.requests)
+    }
+}
+
+impl std::fmt::Debug for AccountBatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccountBatcher")
+            .field("flush_interval", &self.flush_interval)
+            .finish()
+    }
+}
+
+impl AccountBatcher {
+    /// Batches the accounts fetched from the client with finalized commitment
+    pub(crate) fn new(client: Arc<RpcClient>) -> Self {
+        Self::with_fetch(
+            FLUSH_INTERVAL,
+            Arc::new(move |pubkeys| -> AccountsFuture {
+                let client = client.clone();
+                Box::pin(async move {
+                    let accounts = client
+                        .get_multiple_accounts_with_commitment(
+                            &pubkeys,
+                            CommitmentConfig::finalized(),
+                        )
+                        .await
+                        .map_err(ChainCommunicationError::from_other)?
+                        .value;
+                    Ok(accounts)
+                })
+            }),
+        )
+    }
+
+    fn with_fetch(flush_interval: Duration, fetch: FetchAccounts) -> Self {
+        Self {
+            flush_interval,
+            fetch,
+            pending: Default::default(),
+        }
+    }
+
+    /// The account, fetched along with the others requested around the same
+    /// time
+    pub(crate) async fn get_account(&self, pubkey: Pubkey) -> ChainResult<Option<Account>> {
+        let (sender, receiver) = oneshot::channel();
+        let full_batch = {
+            let mut pending = self.pending.lock().expect("account batch lock poisoned");
+            pending.requests.push((pubkey, sender));
+            if pending.requests.len() == 1 {
+                self.flush_after_interval(pending.generation);
+            }
+            (pending.requests.len() >= MAX_ACCOUNTS_PER_BATCH).then(|| pending.take())
+        };
+        if let Some(batch) = full_batch {
+            tokio::spawn(fetch_batch(self.fetch.clone(), batch));
+        }
+
+        receiver
+            .await
+            .map_err(|_| ChainCommunicationError::from_other_str("Account batch was dropped"))?
+            .map_err(|err| ChainCommunicationError::from_other_str(&err))
+    }
+
+    fn flush_after_interval(&self, generation: u64) {
+        let flush_interval = self.flush_interval;
+        let fetch = self.fetch.clone();
+        let pending = self.pending.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(flush_interval).await;
+            let batch = {
+                let mut pending = pending.lock().expect("account batch lock poisoned");
+                // The batch was already taken once it filled up
+                (pending.generation == generation).then(|| pending.take())
+            };
+            if let Some(batch) = batch {
+                fetch_batch(fetch, batch).await;
+            }
+        });
+    }
+}
+
+/// Fetches the accounts of the batch with a single request, and hands each
+/// one to those who requested it
+async fn fetch_batch(fetch: FetchAccounts, batch: Vec<(Pubkey, oneshot::Sender<AccountResult>)>) {
+    let mut pubkeys: Vec<Pubkey> = batch.iter().map(|(pubkey, _)| *pubkey).collect();
+    pubkeys.sort();
+    pubkeys.dedup();
+    trace!(
+        requested = batch.len(),
+        accounts = pubkeys.len(),
+        "Fetching batch of accounts"
+    );
+
+    let accounts: Result<HashMap<Pubkey, Option<Account>>, String> =
+        match fetch(pubkeys.clone()).await {
+            Ok(accounts) if accounts.len() == pubkeys.len() => {
+                Ok(pubkeys.into_iter().zip(accounts).collect())
+            }
+            Ok(accounts) => Err(format!(
+                "Expected {} accounts, got {}",
+                pubkeys.len(),
+                accounts.len()
+            )),
+            Err(err) => Err(err.to_string()),
+        };
+    for (pubkey, sender) in batch {
+        let account = match &accounts {
+            Ok(accounts) => Ok(accounts.get(&pubkey).cloned().flatten()),
+            Err(err) => Err(err.clone()),
+        };
+        // The requester may have given up on its account
+        let _ = sender.send(account);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn account(lamports: u64) -> Account {
+        Account {
+            lamports,
+            ..Default::default()
+        }
+    }
+
+    fn batcher(
+        fetched: Arc<Mutex<Vec<Vec<Pubkey>>>>,
+        failures: Arc<AtomicUsize>,
+    ) -> AccountBatcher {
+        AccountBatcher::with_fetch(
+            Duration::from_millis(50),
+            Arc::new(move |pubkeys: Vec<Pubkey>| -> AccountsFuture {
+                fetched.lock().unwrap().push(pubkeys.clone());
+                let fail = failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                Box::pin(async move {
+                    if fail {
+                        return Err(ChainCommunicationError::from_other_str("rpc error"));
+                    }
+                    // Every other account doesn't exist
+                    Ok(pubkeys
+                        .iter()
+                        .map(|pubkey| (pubkey.to_bytes()[0] % 2 == 0).then(|| account(1)))
+                        .collect())
+                })
+            }),
+        )
+    }
+
+    fn pubkey(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_accounts_are_fetched_together() {
+        let fetched = Arc::new(Mutex::new(vec![]));
+        let batcher = batcher(fetched.clone(), Default::default());
+
+        let accounts =
+            get_accounts_concurrently(&batcher, &[pubkey(2), pubkey(3), pubkey(2)]).await;
+        assert_eq!(accounts.len(), 3);
+        assert_eq!(accounts[0].as_ref().unwrap(), &Some(account(1)));
+        assert_eq!(accounts[1].as_ref().unwrap(), &None);
+        assert_eq!(accounts[2].as_ref().unwrap(), &Some(account(1)));
+        // Duplicates are only fetched once
+        assert_eq!(*fetched.lock().unwrap(), vec![vec![pubkey(2), pubkey(3)]]);
+    }
+
+    #[tokio::test]
+    async fn test_full_batches_are_fetched_right_away() {
+        let fetched = Arc::new(Mutex::new(vec![]));
+        let batcher = batcher(fetched.clone(), Default::default());
+
+        let pubkeys: Vec<Pubkey> = (0..=MAX_ACCOUNTS_PER_BATCH as u8).map(pubkey).collect();
+        let accounts = get_accounts_concurrently(&batcher, &pubkeys).await;
+        assert!(accounts.iter().all(Result::is_ok));
+        let fetched = fetched.lock().unwrap();
+        assert_eq!(fetched.len(), 2);
+        assert_eq!(fetched[0].len(), MAX_ACCOUNTS_PER_BATCH);
+        assert_eq!(fetched[1].len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_errors_are_returned_to_every_requester() {
+        let fetched = Arc::new(Mutex::new(vec![]));
+        let batcher = batcher(fetched.clone(), Arc::new(AtomicUsize::new(1)));
+
+        let accounts = get_accounts_concurrently(&batcher, &[pubkey(2), pubkey(4)]).await;
+        assert!(accounts.iter().all(Result::is_err));
+        // The next batch is fetched again
+        assert!(batcher.get_account(pubkey(2)).await.is_ok());
+        assert_eq!(fetched.lock().unwrap().len(), 2);
+    }
+
+    /// Requests the accounts concurrently
+    async fn get_accounts_concurrently(
+        batcher: &AccountBatcher,
+        pubkeys: &[Pubkey],
+    ) -> Vec<ChainResult<Option<Account>>> {
+        let handles: Vec<_> = pubkeys
+            .iter()
+            .map(|pubkey| {
+                let batcher = batcher.clone();
+                let pubkey = *pubkey;
+                tokio::spawn(async move { batcher.get_account(pubkey).await })
+            })
+            .collect();
+        let mut accounts = vec![];
+        for handle in handles {
+            accounts.push(handle.await.unwrap());
+        }
+        accounts
+    }
+}
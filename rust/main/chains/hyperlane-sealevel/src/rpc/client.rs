@@ -1,14 +1,22 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
 use base64::Engine;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
 use solana_client::{
     nonblocking::rpc_client::RpcClient,
-    rpc_client::SerializableTransaction,
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, SerializableTransaction},
     rpc_config::{
         RpcBlockConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
         RpcSimulateTransactionConfig, RpcTransactionConfig,
     },
-    rpc_response::{Response, RpcSimulateTransactionResult},
+    rpc_request::RpcRequest,
+    rpc_response::{
+        Response, RpcConfirmedTransactionStatusWithSignature, RpcSimulateTransactionResult,
+    },
 };
 use solana_program::clock::Slot;
 use solana_sdk::{
@@ -28,12 +36,17 @@ use solana_transaction_status::{
 };
 
 use hyperlane_core::{ChainCommunicationError, ChainResult, U256};
+use hyperlane_metric::prometheus_metric::{PrometheusClientMetrics, PrometheusConfig};
+use serde::Deserialize;
+use tracing::warn;
 
 use crate::{
     error::HyperlaneSealevelError, priority_fee::PriorityFeeOracle,
     tx_submitter::TransactionSubmitter, SealevelKeypair,
 };
 
+use super::account_batcher::AccountBatcher;
+
 const COMPUTE_UNIT_MULTIPLIER_NUMERATOR: u32 = 11;
 const COMPUTE_UNIT_MULTIPLIER_DENOMINATOR: u32 = 10;
 
@@ -42,11 +55,36 @@ const PRIORITY_FEE_MULTIPLIER_DENOMINATOR: u64 = 100;
 
 pub struct SealevelTxCostEstimate {
     compute_units: u32,
-    compute_unit_price_micro_lamports: u64,
+    pub(crate) compute_unit_price_micro_lamports: u64,
+}
+
+/// The lowest prioritization fee paid to write some accounts in a slot, as
+/// returned by `getRecentPrioritizationFees`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentPrioritizationFee {
+    /// The slot
+    pub slot: Slot,
+    /// The fee in microlamports per compute unit
+    pub prioritization_fee: u64,
 }
 
 /// Wrapper struct around Solana's RpcClient
-pub struct SealevelRpcClient(RpcClient);
+pub struct SealevelRpcClient {
+    client: Arc<RpcClient>,
+    /// The lowest slot the node was last known to have history for, or 0 if
+    /// unknown. Nodes only ever prune more history, so this may be too low but
+    /// never too high.
+    minimum_available_slot: AtomicU64,
+    /// Clients to fetch blocks from when this node has pruned them, in order
+    /// of preference
+    archive_clients: Vec<SealevelRpcClient>,
+    metrics: PrometheusClientMetrics,
+    metrics_config: PrometheusConfig,
+    /// If set, accounts fetched with finalized commitment are batched into
+    /// `getMultipleAccounts` requests
+    account_batcher: Option<AccountBatcher>,
+}
 
 impl SealevelRpcClient {
     /// The max amount of compute units for a transaction.
@@ -54,7 +92,7 @@ impl SealevelRpcClient {
 
     /// constructor
     pub fn new(rpc_endpoint: String) -> Self {
-        Self(RpcClient::new_with_commitment(
+        Self::from_rpc_client(RpcClient::new_with_commitment(
             rpc_endpoint,
             CommitmentConfig::processed(),
         ))
@@ -62,7 +100,29 @@ impl SealevelRpcClient {
 
     /// constructor with an rpc client
     pub fn from_rpc_client(rpc_client: RpcClient) -> Self {
-        Self(rpc_client)
+        Self::from_rpc_client_with_archives(rpc_client, vec![], Default::default())
+    }
+
+    /// constructor with an rpc client, and clients to fall back to for blocks
+    /// the rpc client's node has pruned
+    pub fn from_rpc_client_with_archives(
+        rpc_client: RpcClient,
+        archive_clients: Vec<SealevelRpcClient>,
+        (metrics, metrics_config): (PrometheusClientMetrics, PrometheusConfig),
+    ) -> Self {
+        Self {
+            client: Arc::new(rpc_client),
+            minimum_available_slot: AtomicU64::new(0),
+            archive_clients,
+            metrics,
+            metrics_config,
+            account_batcher: None,
+        }
+    }
+
+    /// batch the accounts fetched concurrently with finalized commitment
+    pub(crate) fn enable_account_batching(&mut self) {
+        self.account_batcher = Some(AccountBatcher::new(self.client.clone()));
     }
 
     /// confirm transaction with given commitment
@@ -71,7 +131,7 @@ impl SealevelRpcClient {
         signature: &Signature,
         commitment: CommitmentConfig,
     ) -> ChainResult<bool> {
-        self.0
+        self.client
             .confirm_transaction_with_commitment(signature, commitment)
             .await
             .map(|ctx| ctx.value)
@@ -119,8 +179,11 @@ impl SealevelRpcClient {
         &self,
         pubkey: &Pubkey,
     ) -> ChainResult<Option<Account>> {
+        if let Some(account_batcher) = &self.account_batcher {
+            return account_batcher.get_account(*pubkey).await;
+        }
         let account = self
-            .0
+            .client
             .get_account_with_commitment(pubkey, CommitmentConfig::finalized())
             .await
             .map_err(ChainCommunicationError::from_other)?
@@ -131,7 +194,7 @@ impl SealevelRpcClient {
     /// get balance
     pub async fn get_balance(&self, pubkey: &Pubkey) -> ChainResult<U256> {
         let balance = self
-            .0
+            .client
             .get_balance(pubkey)
             .await
             .map_err(Into::<HyperlaneSealevelError>::into)
@@ -141,22 +204,120 @@ impl SealevelRpcClient {
     }
 
     /// get block
+    ///
+    /// Slots which the node has pruned are not requested from it, but from the
+    /// first healthy archive client which still has them.
     pub async fn get_block(&self, slot: u64) -> ChainResult<UiConfirmedBlock> {
+        if slot >= self.minimum_available_slot.load(Ordering::Relaxed) {
+            match self.get_block_from_node(slot).await {
+                Ok(block) => return Ok(block),
+                // The node may have pruned the slot since we last checked
+                Err(err) if !self.is_slot_pruned(slot).await => return Err(err),
+                Err(_) => {}
+            }
+        }
+        self.get_pruned_block(slot).await
+    }
+
+    async fn get_block_from_node(&self, slot: u64) -> ChainResult<UiConfirmedBlock> {
         let config = RpcBlockConfig {
             commitment: Some(CommitmentConfig::finalized()),
             max_supported_transaction_version: Some(0),
             ..Default::default()
         };
-        self.0
+        self.client
             .get_block_with_config(slot, config)
             .await
             .map_err(HyperlaneSealevelError::ClientError)
             .map_err(Into::into)
     }
 
+    async fn get_pruned_block(&self, slot: u64) -> ChainResult<UiConfirmedBlock> {
+        let minimum_available_slot = self.minimum_available_slot.load(Ordering::Relaxed);
+        warn!(
+            slot,
+            minimum_available_slot,
+            url = self.url(),
+            "Refusing to request block below the minimum slot available on RPC node"
+        );
+        self.metrics
+            .increment_error_count(&self.metrics_config, "getBlock", "slot_pruned");
+
+        for archive in &self.archive_clients {
+            if let Err(err) = archive.get_health().await {
+                warn!(
+                    url = archive.url(),
+                    ?err,
+                    "Skipping unhealthy archive RPC node"
+                );
+                continue;
+            }
+            if archive.is_slot_pruned(slot).await {
+                continue;
+            }
+            match archive.get_block_from_node(slot).await {
+                Ok(block) => return Ok(block),
+                Err(err) => warn!(
+                    slot,
+                    url = archive.url(),
+                    ?err,
+                    "Failed to get block from archive RPC node"
+                ),
+            }
+        }
+        Err(HyperlaneSealevelError::SlotNotAvailable {
+            slot,
+            minimum_available_slot,
+        }
+        .into())
+    }
+
+    /// Whether the node has pruned `slot`, refreshing the minimum slot it has
+    /// history for. If the minimum slot can't be fetched, the slot is assumed
+    /// to be available.
+    async fn is_slot_pruned(&self, slot: u64) -> bool {
+        match self.get_minimum_available_slot().await {
+            Ok(minimum_available_slot) => {
+                self.minimum_available_slot
+                    .fetch_max(minimum_available_slot, Ordering::Relaxed);
+                slot < minimum_available_slot
+            }
+            Err(err) => {
+                warn!(
+                    url = self.url(),
+                    ?err,
+                    "Failed to get minimum available slot"
+                );
+                false
+            }
+        }
+    }
+
+    /// get the lowest slot the node has history for, using
+    /// `minimumLedgerSlot`, or `getFirstAvailableBlock` if the node doesn't
+    /// support it
+    pub async fn get_minimum_available_slot(&self) -> ChainResult<Slot> {
+        match self.client.minimum_ledger_slot().await {
+            Ok(slot) => Ok(slot),
+            Err(_) => self
+                .client
+                .get_first_available_block()
+                .await
+                .map_err(ChainCommunicationError::from_other),
+        }
+    }
+
+    /// get health of the node
+    pub async fn get_health(&self) -> ChainResult<()> {
+        self.client
+            .get_health()
+            .await
+            .map_err(ChainCommunicationError::from_other)
+    }
+
     /// get minimum balance for rent exemption
     pub async fn get_minimum_balance_for_rent_exemption(&self, len: usize) -> ChainResult<u64> {
-        self.0
+        self.client
             .get_minimum_balance_for_rent_exemption(len)
             .await
             .map_err(ChainCommunicationError::from_other)
@@ -168,7 +329,7 @@ impl SealevelRpcClient {
         pubkeys: &[Pubkey],
     ) -> ChainResult<Vec<Option<Account>>> {
         let accounts = self
-            .0
+            .client
             .get_multiple_accounts_with_commitment(pubkeys, CommitmentConfig::finalized())
             .await
             .map_err(ChainCommunicationError::from_other)?
@@ -182,7 +343,7 @@ impl SealevelRpcClient {
         &self,
         commitment: CommitmentConfig,
     ) -> ChainResult<Hash> {
-        self.0
+        self.client
             .get_latest_blockhash_with_commitment(commitment)
             .await
             .map_err(ChainCommunicationError::from_other)
@@ -195,7 +356,7 @@ impl SealevelRpcClient {
         pubkey: &Pubkey,
         config: RpcProgramAccountsConfig,
     ) -> ChainResult<Vec<(Pubkey, Account)>> {
-        self.0
+        self.client
             .get_program_accounts_with_config(pubkey, config)
             .await
             .map_err(ChainCommunicationError::from_other)
@@ -206,12 +367,31 @@ impl SealevelRpcClient {
         &self,
         signatures: &[Signature],
     ) -> ChainResult<Response<Vec<Option<TransactionStatus>>>> {
-        self.0
+        self.client
             .get_signature_statuses(signatures)
             .await
             .map_err(ChainCommunicationError::from_other)
     }
 
+    /// get signatures of finalized transactions referencing the address,
+    /// newest first, starting before the given signature
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+    ) -> ChainResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: None,
+            commitment: Some(CommitmentConfig::finalized()),
+        };
+        self.client
+            .get_signatures_for_address_with_config(address, config)
+            .await
+            .map_err(ChainCommunicationError::from_other)
+    }
+
     /// get slot
     pub async fn get_slot(&self) -> ChainResult<u32> {
         let slot = self
@@ -225,8 +405,35 @@ impl SealevelRpcClient {
 
     /// get slot
     pub async fn get_slot_raw(&self) -> ChainResult<Slot> {
-        self.0
-            .get_slot_with_commitment(CommitmentConfig::finalized())
+        self.get_slot_with_commitment(CommitmentConfig::finalized())
+            .await
+    }
+
+    /// get slot with the given commitment
+    pub async fn get_slot_with_commitment(
+        &self,
+        commitment: CommitmentConfig,
+    ) -> ChainResult<Slot> {
+        self.client
+            .get_slot_with_commitment(commitment)
+            .await
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    /// get the lowest prioritization fees paid to write all the accounts in
+    /// each of the recent slots the node knows of
+    pub async fn get_recent_prioritization_fees(
+        &self,
+        accounts: &[Pubkey],
+    ) -> ChainResult<Vec<RecentPrioritizationFee>> {
+        let accounts: Vec<String> = accounts.iter().map(ToString::to_string).collect();
+        self.client
+            .send(
+                RpcRequest::Custom {
+                    method: "getRecentPrioritizationFees",
+                },
+                serde_json::json!([accounts]),
+            )
             .await
             .map_err(ChainCommunicationError::from_other)
     }
@@ -239,9 +446,10 @@ impl SealevelRpcClient {
         let config = RpcTransactionConfig {
             encoding: Some(UiTransactionEncoding::JsonParsed),
             commitment: Some(CommitmentConfig::finalized()),
-            ..Default::default()
+            // Without it, versioned transactions aren't returned
+            max_supported_transaction_version: Some(0),
         };
-        self.0
+        self.client
             .get_transaction_with_config(signature, config)
             .await
             .map_err(HyperlaneSealevelError::ClientError)
@@ -250,7 +458,7 @@ impl SealevelRpcClient {
 
     /// check if block hash is valid
     pub async fn is_blockhash_valid(&self, hash: &Hash) -> ChainResult<bool> {
-        self.0
+        self.client
             .is_blockhash_valid(hash, CommitmentConfig::processed())
             .await
             .map_err(ChainCommunicationError::from_other)
@@ -262,7 +470,7 @@ impl SealevelRpcClient {
         transaction: &Transaction,
         skip_preflight: bool,
     ) -> ChainResult<Signature> {
-        self.0
+        self.client
             .send_transaction_with_config(
                 transaction,
                 RpcSendTransactionConfig {
@@ -275,13 +483,13 @@ impl SealevelRpcClient {
     }
 
     /// Polls the RPC until the transaction is confirmed or the blockhash
-    /// expires.
+    /// expires. Returns the slot the transaction landed in.
     /// Standalone logic stolen from Solana's non-blocking client,
     /// decoupled from the sending of a transaction.
     pub async fn wait_for_transaction_confirmation(
         &self,
         transaction: &impl SerializableTransaction,
-    ) -> ChainResult<()> {
+    ) -> ChainResult<Slot> {
         let signature = transaction.get_signature();
 
         const GET_STATUS_RETRIES: usize = usize::MAX;
@@ -298,7 +506,7 @@ impl SealevelRpcClient {
                 self.get_signature_statuses(&[*signature]).await?;
             let signature_status = signature_statuses.value.first().cloned().flatten();
             match signature_status {
-                Some(_) => return Ok(()),
+                Some(status) => return Ok(status.slot),
                 None => {
                     if !self.is_blockhash_valid(&recent_blockhash).await? {
                         // Block hash is not found by some reason
@@ -333,6 +541,18 @@ impl SealevelRpcClient {
         &self,
         payer: &SealevelKeypair,
         instruction: Instruction,
+    ) -> ChainResult<Option<T>> {
+        self.simulate_instruction_with_commitment(payer, instruction, None)
+            .await
+    }
+
+    /// Like `simulate_instruction`, but simulates against the bank at
+    /// `commitment` rather than the client's default commitment.
+    pub async fn simulate_instruction_with_commitment<T: BorshDeserialize + BorshSerialize>(
+        &self,
+        payer: &SealevelKeypair,
+        instruction: Instruction,
+        simulation_commitment: Option<CommitmentConfig>,
     ) -> ChainResult<Option<T>> {
         let commitment = CommitmentConfig::finalized();
         let recent_blockhash = self
@@ -343,7 +563,9 @@ impl SealevelRpcClient {
             Some(&payer.pubkey()),
             &recent_blockhash,
         ));
-        let simulation = self.simulate_transaction(&transaction).await?;
+        let simulation = self
+            .simulate_transaction_with_commitment(&transaction, simulation_commitment)
+            .await?;
 
         if let Some(return_data) = simulation.return_data {
             let bytes = match return_data.data.1 {
@@ -365,14 +587,24 @@ impl SealevelRpcClient {
     pub async fn simulate_transaction(
         &self,
         transaction: &Transaction,
+    ) -> ChainResult<RpcSimulateTransactionResult> {
+        self.simulate_transaction_with_commitment(transaction, None)
+            .await
+    }
+
+    async fn simulate_transaction_with_commitment(
+        &self,
+        transaction: &Transaction,
+        commitment: Option<CommitmentConfig>,
     ) -> ChainResult<RpcSimulateTransactionResult> {
         let result = self
-            .0
+            .client
             .simulate_transaction_with_config(
                 transaction,
                 RpcSimulateTransactionConfig {
                     sig_verify: false,
                     replace_recent_blockhash: true,
+                    commitment,
                     ..Default::default()
                 },
             )
@@ -467,18 +699,16 @@ impl SealevelRpcClient {
     }
 
     /// Builds a transaction with estimated costs for a given instruction.
+    /// Returns the transaction with the costs it was built with.
     pub async fn build_estimated_tx_for_instruction(
         &self,
         instruction: Instruction,
         payer: &SealevelKeypair,
         tx_submitter: &dyn TransactionSubmitter,
         priority_fee_oracle: &dyn PriorityFeeOracle,
-    ) -> ChainResult<Transaction> {
+    ) -> ChainResult<(Transaction, SealevelTxCostEstimate)> {
         // Get the estimated costs for the instruction.
-        let SealevelTxCostEstimate {
-            compute_units,
-            compute_unit_price_micro_lamports,
-        } = self
+        let estimate = self
             .get_estimated_costs_for_instruction(
                 instruction.clone(),
                 payer,
@@ -486,6 +716,10 @@ impl SealevelRpcClient {
                 priority_fee_oracle,
             )
             .await?;
+        let SealevelTxCostEstimate {
+            compute_units,
+            compute_unit_price_micro_lamports,
+        } = estimate;
 
         tracing::info!(
             ?compute_units,
@@ -505,7 +739,7 @@ impl SealevelRpcClient {
             )
             .await?;
 
-        Ok(tx)
+        Ok((tx, estimate))
     }
 
     /// Creates a transaction for a given instruction, compute unit limit, and compute unit price.
@@ -555,7 +789,7 @@ impl SealevelRpcClient {
 
     /// Get Url
     pub fn url(&self) -> String {
-        self.0.url()
+        self.client.url()
     }
 }
 
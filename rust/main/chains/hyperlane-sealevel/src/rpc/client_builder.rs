@@ -1,3 +1,4 @@
+use hyperlane_core::rpc_clients::RpcRateLimiter;
 use hyperlane_metric::prometheus_metric::{ChainInfo, PrometheusClientMetrics, PrometheusConfig};
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_client::RpcClientConfig};
 use solana_sdk::commitment_config::CommitmentConfig;
@@ -11,7 +12,10 @@ use super::SealevelRpcClient;
 /// SealevelRpcClient builder
 pub struct SealevelRpcClientBuilder {
     rpc_url: Url,
+    archive_urls: Vec<Url>,
     prometheus_config: Option<(PrometheusClientMetrics, PrometheusConfig)>,
+    rate_limiter: Option<RpcRateLimiter>,
+    batch_account_queries: bool,
 }
 
 impl SealevelRpcClientBuilder {
@@ -19,10 +23,20 @@ impl SealevelRpcClientBuilder {
     pub fn new(rpc_url: Url) -> Self {
         Self {
             rpc_url,
+            archive_urls: vec![],
             prometheus_config: None,
+            rate_limiter: None,
+            batch_account_queries: false,
         }
     }
 
+    /// add archive nodes to fetch blocks from when the node at `rpc_url`
+    /// has pruned them
+    pub fn with_archive_urls(mut self, archive_urls: Vec<Url>) -> Self {
+        self.archive_urls = archive_urls;
+        self
+    }
+
     /// add prometheus metrics to builder
     pub fn with_prometheus_metrics(
         mut self,
@@ -34,15 +48,68 @@ impl SealevelRpcClientBuilder {
         self
     }
 
+    /// limit the rate of requests to all the nodes of the client,
+    /// including archive nodes
+    pub fn with_rate_limiter(mut self, rate_limiter: Option<RpcRateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// batch the accounts fetched concurrently into `getMultipleAccounts`
+    /// requests
+    pub fn with_account_batching(mut self, batch_account_queries: bool) -> Self {
+        self.batch_account_queries = batch_account_queries;
+        self
+    }
+
     /// build SealevelRpcClient
     pub fn build(self) -> SealevelRpcClient {
         let (metrics, metrics_config) = self.prometheus_config.unwrap_or_default();
 
-        let sender = PrometheusSealevelRpcSender::new(self.rpc_url, metrics, metrics_config);
-        let rpc_client = RpcClient::new_sender(
+        let archive_clients = self
+            .archive_urls
+            .into_iter()
+            .map(|url| {
+                let archive_config = PrometheusConfig::from_url(&url, metrics_config.chain.clone());
+                SealevelRpcClient::from_rpc_client_with_archives(
+                    Self::rpc_client(
+                        url,
+                        metrics.clone(),
+                        archive_config.clone(),
+                        self.rate_limiter.clone(),
+                    ),
+                    vec![],
+                    (metrics.clone(), archive_config),
+                )
+            })
+            .collect();
+        let rpc_client = Self::rpc_client(
+            self.rpc_url,
+            metrics.clone(),
+            metrics_config.clone(),
+            self.rate_limiter,
+        );
+        let mut client = SealevelRpcClient::from_rpc_client_with_archives(
+            rpc_client,
+            archive_clients,
+            (metrics, metrics_config),
+        );
+        if self.batch_account_queries {
+            client.enable_account_batching();
+        }
+        client
+    }
+
+    fn rpc_client(
+        url: Url,
+        metrics: PrometheusClientMetrics,
+        config: PrometheusConfig,
+        rate_limiter: Option<RpcRateLimiter>,
+    ) -> RpcClient {
+        let sender = PrometheusSealevelRpcSender::new(url, metrics, config, rate_limiter);
+        RpcClient::new_sender(
             sender,
             RpcClientConfig::with_commitment(CommitmentConfig::processed()),
-        );
-        SealevelRpcClient::from_rpc_client(rpc_client)
+        )
     }
 }
@@ -1,5 +1,6 @@
-pub use client::SealevelRpcClient;
+pub use client::{RecentPrioritizationFee, SealevelRpcClient};
 
+mod account_batcher;
 mod client;
 /// SealevelRpcClientBuilder
 pub mod client_builder;
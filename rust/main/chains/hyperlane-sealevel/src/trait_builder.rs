@@ -1,12 +1,18 @@
-use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError, NativeToken};
+use std::collections::HashMap;
+
+use hyperlane_core::{
+    config::OperationBatchConfig, rpc_clients::RpcRateLimiter, ChainCommunicationError, NativeToken,
+};
 use hyperlane_metric::prometheus_metric::{ChainInfo, PrometheusClientMetrics};
 use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
 use url::Url;
 
 use crate::{
     client_builder::SealevelRpcClientBuilder,
     priority_fee::{ConstantPriorityFeeOracle, HeliusPriorityFeeOracle, PriorityFeeOracle},
     tx_submitter::{JitoTransactionSubmitter, RpcTransactionSubmitter, TransactionSubmitter},
+    KnownRecipientConfig,
 };
 
 /// Sealevel connection configuration
@@ -14,6 +20,9 @@ use crate::{
 pub struct ConnectionConf {
     /// Fully qualified string to connect to
     pub url: Url,
+    /// RPC urls of archive nodes, used to fetch blocks the node at `url` has
+    /// pruned from its history
+    pub archive_urls: Vec<Url>,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
     /// Native token and its denomination
@@ -22,6 +31,15 @@ pub struct ConnectionConf {
     pub priority_fee_oracle: PriorityFeeOracleConfig,
     /// Transaction submitter configuration
     pub transaction_submitter: TransactionSubmitterConfig,
+    /// Recipient programs whose messages are processed with statically
+    /// configured accounts instead of ones discovered by simulation
+    pub known_recipients: HashMap<Pubkey, KnownRecipientConfig>,
+    /// Limits the rate of requests to the RPCs at `url` and `archive_urls`,
+    /// and to `url` when transactions are submitted to it
+    pub rpc_rate_limiter: Option<RpcRateLimiter>,
+    /// If true, the accounts fetched concurrently, e.g. by the indexers, are
+    /// batched into `getMultipleAccounts` requests
+    pub batch_account_queries: bool,
 }
 
 /// An error type when parsing a connection configuration.
@@ -122,9 +140,16 @@ impl TransactionSubmitterConfig {
         default_rpc_url: String,
         metrics: PrometheusClientMetrics,
         chain: Option<ChainInfo>,
+        default_rpc_rate_limiter: Option<RpcRateLimiter>,
     ) -> Box<dyn TransactionSubmitter> {
         match self {
             TransactionSubmitterConfig::Rpc { url } => {
+                // the rate limit only applies to the default RPC
+                let rate_limiter = if url.is_none() {
+                    default_rpc_rate_limiter
+                } else {
+                    None
+                };
                 let rpc_url = url.clone().unwrap_or(default_rpc_url);
                 let rpc_url = Url::parse(&rpc_url).unwrap();
                 // now that we know what the RPC URL is, we
@@ -132,6 +157,7 @@ impl TransactionSubmitterConfig {
                 // node info
                 let rpc_client = SealevelRpcClientBuilder::new(rpc_url)
                     .with_prometheus_metrics(metrics, chain)
+                    .with_rate_limiter(rate_limiter)
                     .build();
                 Box::new(RpcTransactionSubmitter::new(rpc_client))
             }
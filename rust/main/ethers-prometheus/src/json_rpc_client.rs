@@ -8,7 +8,7 @@ use async_trait::async_trait;
 use derive_new::new;
 use ethers::prelude::JsonRpcClient;
 use ethers_core::types::U64;
-use hyperlane_core::rpc_clients::BlockNumberGetter;
+use hyperlane_core::rpc_clients::{BlockNumberGetter, RpcRateLimiter};
 use hyperlane_core::ChainCommunicationError;
 use hyperlane_metric::prometheus_metric::{
     PrometheusClientMetrics, PrometheusConfig, PrometheusConfigExt,
@@ -19,11 +19,16 @@ use serde::{de::DeserializeOwned, Serialize};
 /// metrics. To make this as flexible as possible, the metric vecs need to be
 /// created and named externally, they should follow the naming convention here
 /// and must include the described labels.
+///
+/// Requests can optionally be rate limited, which is done here as well since
+/// every request to a node goes through this wrapper.
 #[derive(new)]
 pub struct PrometheusJsonRpcClient<C> {
     inner: C,
     metrics: PrometheusClientMetrics,
     config: PrometheusConfig,
+    #[new(default)]
+    rate_limiter: Option<RpcRateLimiter>,
 }
 
 impl<C: Clone> Clone for PrometheusJsonRpcClient<C> {
@@ -32,6 +37,7 @@ impl<C: Clone> Clone for PrometheusJsonRpcClient<C> {
             inner: self.inner.clone(),
             metrics: self.metrics.clone(),
             config: self.config.clone(),
+            rate_limiter: self.rate_limiter.clone(),
         }
     }
 }
@@ -50,6 +56,12 @@ impl<C> PrometheusJsonRpcClient<C> {
     pub fn inner(&self) -> &C {
         &self.inner
     }
+
+    /// Limit the rate of requests sent by this client
+    pub fn with_rate_limiter(mut self, rate_limiter: Option<RpcRateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
 }
 
 impl<C> PrometheusConfigExt for PrometheusJsonRpcClient<C> {
@@ -63,6 +75,11 @@ impl<C> PrometheusConfigExt for PrometheusJsonRpcClient<C> {
     fn chain_name(&self) -> &str {
         self.config.chain_name()
     }
+
+    fn record_request_error(&self, method: &str, error_type: &str) {
+        self.metrics
+            .increment_error_count(&self.config, method, error_type);
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -78,6 +95,9 @@ where
         T: Debug + Serialize + Send + Sync,
         R: DeserializeOwned,
     {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
         let start = Instant::now();
         let res = self.inner.request(method, params).await;
         self.metrics
@@ -10,7 +10,7 @@ use tracing::info;
 use crate::{
     metrics::{AgentMetrics, CoreMetrics, RuntimeMetrics},
     settings::Settings,
-    ChainMetrics,
+    ChainMetrics, SelfTestReport,
 };
 
 /// Properties shared across all hyperlane agents
@@ -55,6 +55,16 @@ pub trait BaseAgent: Send + Sync + Debug {
     /// Start running this agent.
     #[allow(clippy::async_yields_async)]
     async fn run(self);
+
+    /// Exercise the components of this agent against synthetic data instead
+    /// of running, reporting whether each of them works. Agents without
+    /// self-tests report no tested components, which fails the self-test.
+    async fn self_test(self) -> SelfTestReport
+    where
+        Self: Sized,
+    {
+        SelfTestReport::default()
+    }
 }
 
 /// Call this from `main` to fully initialize and run the agent for its entire
@@ -88,6 +98,7 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
 
     let settings = A::Settings::load()?;
     let core_settings: &Settings = settings.as_ref();
+    let self_test = core_settings.self_test;
 
     let metrics = settings.as_ref().metrics(A::AGENT_NAME)?;
     let task_monitor = tokio_metrics::TaskMonitor::new();
@@ -106,6 +117,14 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
     )
     .await?;
 
+    if self_test {
+        info!(
+            agent = A::AGENT_NAME,
+            "Running self-test instead of the agent"
+        );
+        return agent.self_test().await.into_result();
+    }
+
     // This await will only end if a panic happens. We won't crash, but instead gracefully shut down
     agent.run().await;
     info!(agent = A::AGENT_NAME, "Shutting down agent...");
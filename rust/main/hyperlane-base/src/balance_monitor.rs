@@ -0,0 +1,231 @@
+//! Monitoring of the balance of an agent's signer on each of its chains.
+//!
+//! A [`BalanceMonitor`] periodically fetches the balance of the signer on a
+//! chain and compares it to the floor configured for the chain. Whether the
+//! balance of a chain is below its floor is shared through
+//! [`SignerBalanceFloors`], so that agents can stop submitting transactions
+//! to chains they can't pay for until the signer is topped up.
+//!
+//! The balance itself is exported by the `wallet_balance` metric.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use eyre::Result;
+use hyperlane_core::{
+    metrics::agent::u256_as_scaled_f64, HyperlaneDomain, HyperlaneProvider, U256,
+};
+use prometheus::{GaugeVec, IntGaugeVec};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{debug, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::{settings::ChainConf, CoreMetrics};
+
+/// How often the signer balances are fetched
+pub const BALANCE_MONITOR_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Metrics of the signer balance floors, by chain
+#[derive(Debug, Clone)]
+pub struct BalanceMonitorMetrics {
+    /// The floor of the signer balance, in the native token of the chain
+    ///
+    /// Labels:
+    /// - `chain`: The chain the floor applies to.
+    pub floor: GaugeVec,
+
+    /// Whether the signer balance was below its floor when last fetched
+    ///
+    /// Labels:
+    /// - `chain`: The chain of the signer.
+    pub below_floor: IntGaugeVec,
+}
+
+pub(crate) fn create_balance_monitor_metrics(
+    metrics: &CoreMetrics,
+) -> Result<BalanceMonitorMetrics> {
+    Ok(BalanceMonitorMetrics {
+        floor: metrics.new_gauge(
+            "signer_balance_floor",
+            "Floor of the signer balance on a chain, in its native token",
+            &["chain"],
+        )?,
+        below_floor: metrics.new_int_gauge(
+            "signer_balance_below_floor",
+            "Whether the signer balance on a chain was below its floor when last fetched",
+            &["chain"],
+        )?,
+    })
+}
+
+/// The chains whose signer balance is below its floor, by domain id
+#[derive(Debug, Default)]
+pub struct SignerBalanceFloors {
+    below_floor: RwLock<HashSet<u32>>,
+}
+
+impl SignerBalanceFloors {
+    /// Whether the signer balance on `domain` was below its floor when last
+    /// fetched. Chains whose balance wasn't fetched yet aren't.
+    pub fn is_below_floor(&self, domain: u32) -> bool {
+        self.below_floor
+            .read()
+            .expect("RwLock poisoned")
+            .contains(&domain)
+    }
+
+    fn set_below_floor(&self, domain: u32, below_floor: bool) {
+        let mut below = self.below_floor.write().expect("RwLock poisoned");
+        if below_floor {
+            below.insert(domain);
+        } else {
+            below.remove(&domain);
+        }
+    }
+}
+
+/// The floor of the signer balance on a chain
+#[derive(Debug)]
+struct BalanceFloor {
+    domain: HyperlaneDomain,
+    floor: U256,
+    floors: Arc<SignerBalanceFloors>,
+    metrics: BalanceMonitorMetrics,
+}
+
+impl BalanceFloor {
+    /// Records a fetched balance, returning whether it's below the floor
+    fn observe(&self, balance: U256) -> bool {
+        let below_floor = balance < self.floor;
+        let was_below_floor = self.floors.is_below_floor(self.domain.id());
+        if below_floor && !was_below_floor {
+            warn!(
+                chain = self.domain.name(),
+                %balance,
+                floor = %self.floor,
+                "Signer balance fell below its floor"
+            );
+        } else if !below_floor && was_below_floor {
+            warn!(
+                chain = self.domain.name(),
+                %balance,
+                floor = %self.floor,
+                "Signer balance is back above its floor"
+            );
+        }
+        self.floors.set_below_floor(self.domain.id(), below_floor);
+        self.metrics
+            .below_floor
+            .with_label_values(&[self.domain.name()])
+            .set(below_floor as i64);
+        below_floor
+    }
+}
+
+/// Periodically fetches the balance of the signer on a chain and compares it
+/// to the floor of the chain
+pub struct BalanceMonitor {
+    provider: Box<dyn HyperlaneProvider>,
+    signer_address: String,
+    floor: BalanceFloor,
+}
+
+impl BalanceMonitor {
+    /// Creates a monitor of the signer balance on the chain of `chain_conf`,
+    /// or None if the agent has no signer on the chain
+    pub async fn new(
+        chain_conf: &ChainConf,
+        agent_name: String,
+        floor: U256,
+        floors: Arc<SignerBalanceFloors>,
+        core_metrics: &CoreMetrics,
+    ) -> Result<Option<Self>> {
+        let Some(signer_address) = chain_conf.agent_metrics_conf(agent_name).await?.address else {
+            return Ok(None);
+        };
+        let provider = chain_conf.build_provider(core_metrics).await?;
+        let domain = chain_conf.domain.clone();
+        let metrics = core_metrics.balance_monitor_metrics();
+        metrics
+            .floor
+            .with_label_values(&[domain.name()])
+            .set(u256_as_scaled_f64(floor, domain.domain_protocol()));
+        Ok(Some(Self {
+            provider,
+            signer_address,
+            floor: BalanceFloor {
+                domain,
+                floor,
+                floors,
+                metrics,
+            },
+        }))
+    }
+
+    async fn check(&self) {
+        match self.provider.get_balance(self.signer_address.clone()).await {
+            Ok(balance) => {
+                self.floor.observe(balance);
+            }
+            // The previous state is kept until the balance can be fetched again
+            Err(err) => debug!(
+                ?err,
+                chain = self.floor.domain.name(),
+                "Failed to fetch signer balance"
+            ),
+        }
+    }
+
+    /// Spawns a tokio task that fetches the balance every `interval`
+    pub fn spawn(self, interval: Duration) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("BalanceMonitor", chain = %self.floor.domain);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                self.check().await;
+            }
+        })
+        .instrument(span)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+    use prometheus::Registry;
+
+    use super::*;
+
+    #[test]
+    fn test_balance_below_floor_is_shared() {
+        let metrics = CoreMetrics::new("test", 9090, Registry::new()).unwrap();
+        let floors = Arc::new(SignerBalanceFloors::default());
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+        let floor = BalanceFloor {
+            domain: domain.clone(),
+            floor: U256::from(100),
+            floors: floors.clone(),
+            metrics: metrics.balance_monitor_metrics(),
+        };
+        let below_floor = || {
+            metrics
+                .balance_monitor_metrics()
+                .below_floor
+                .with_label_values(&[domain.name()])
+                .get()
+        };
+
+        assert!(!floors.is_below_floor(domain.id()));
+        assert!(floor.observe(U256::from(99)));
+        assert!(floors.is_below_floor(domain.id()));
+        assert_eq!(below_floor(), 1);
+
+        assert!(!floor.observe(U256::from(100)));
+        assert!(!floors.is_below_floor(domain.id()));
+        assert_eq!(below_floor(), 0);
+    }
+}
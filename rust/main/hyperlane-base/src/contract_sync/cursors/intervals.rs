@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+/// Sleep once caught up when the block time of the chain is unknown
+const DEFAULT_SLEEP: Duration = Duration::from_secs(5);
+/// Time between tip updates when the block time of the chain is unknown
+const DEFAULT_TIP_UPDATE: Duration = Duration::from_secs(30);
+
+/// Bounds of the sleep derived from the block time, so that very fast chains
+/// aren't hammered and very slow ones are still checked regularly
+const MIN_SLEEP: Duration = Duration::from_millis(200);
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// The tip is updated about every this many blocks
+const TIP_UPDATE_BLOCKS: u32 = 3;
+const MIN_TIP_UPDATE: Duration = Duration::from_secs(1);
+const MAX_TIP_UPDATE: Duration = Duration::from_secs(5 * 60);
+
+/// How often cursors poll the chain once they have caught up with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CursorIntervals {
+    /// How long to sleep when there is nothing new to index
+    pub sleep: Duration,
+    /// Minimum time between two updates of the tip by the rate limited cursor
+    pub tip_update: Duration,
+}
+
+impl Default for CursorIntervals {
+    fn default() -> Self {
+        Self {
+            sleep: DEFAULT_SLEEP,
+            tip_update: DEFAULT_TIP_UPDATE,
+        }
+    }
+}
+
+impl CursorIntervals {
+    /// Derives the intervals from the estimated block time of the chain,
+    /// falling back to the defaults if it isn't known.
+    pub fn from_block_time(block_time: Option<Duration>) -> Self {
+        let Some(block_time) = block_time else {
+            return Self::default();
+        };
+        Self {
+            // There is no point in polling more than once per block
+            sleep: block_time.clamp(MIN_SLEEP, MAX_SLEEP),
+            tip_update: block_time
+                .saturating_mul(TIP_UPDATE_BLOCKS)
+                .clamp(MIN_TIP_UPDATE, MAX_TIP_UPDATE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intervals_follow_block_time_within_bounds() {
+        assert_eq!(
+            CursorIntervals::from_block_time(None),
+            CursorIntervals::default()
+        );
+        assert_eq!(
+            CursorIntervals::from_block_time(Some(Duration::from_secs(12))),
+            CursorIntervals {
+                sleep: Duration::from_secs(12),
+                tip_update: Duration::from_secs(36),
+            }
+        );
+        // Fast chains poll often, but not more than the lower bounds allow
+        assert_eq!(
+            CursorIntervals::from_block_time(Some(Duration::from_millis(100))),
+            CursorIntervals {
+                sleep: MIN_SLEEP,
+                tip_update: MIN_TIP_UPDATE,
+            }
+        );
+        // Slow chains poll less often, but are still checked regularly
+        assert_eq!(
+            CursorIntervals::from_block_time(Some(Duration::from_secs(10 * 60))),
+            CursorIntervals {
+                sleep: MAX_SLEEP,
+                tip_update: MAX_TIP_UPDATE,
+            }
+        );
+    }
+}
@@ -1,6 +1,8 @@
+use std::sync::Arc;
+
 use prometheus::IntGaugeVec;
 
-use crate::CoreMetrics;
+use crate::{AgentHealth, CoreMetrics};
 
 /// Struct encapsulating prometheus metrics used by SequenceAware and RateLimited cursors.
 #[derive(Debug, Clone)]
@@ -27,6 +29,10 @@ pub struct CursorMetrics {
     /// - `event_type`: the event type the cursor is indexing. Could be anything implementing `Indexable`.
     /// - `chain`: Chain the cursor is collecting data from.
     pub cursor_max_sequence: IntGaugeVec,
+
+    /// Health of the agent, to which forward cursors report the latest block
+    /// they indexed.
+    pub health: Arc<AgentHealth>,
 }
 
 impl CursorMetrics {
@@ -60,6 +66,7 @@ impl CursorMetrics {
             cursor_current_block,
             cursor_current_sequence,
             cursor_max_sequence,
+            health: metrics.health(),
         }
     }
 }
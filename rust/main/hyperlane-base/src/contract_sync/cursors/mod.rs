@@ -1,5 +1,6 @@
 use hyperlane_core::{
-    Delivery, HyperlaneDomainProtocol, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion,
+    Delivery, HyperlaneDomainProtocol, HyperlaneMessage, InterchainGasPayment, MailboxConfigChange,
+    MerkleTreeInsertion,
 };
 
 pub(crate) mod sequence_aware;
@@ -11,6 +12,9 @@ pub(crate) use rate_limited::RateLimitedContractSyncCursor;
 pub(crate) mod metrics;
 pub(crate) use metrics::CursorMetrics;
 
+pub(crate) mod intervals;
+pub(crate) use intervals::CursorIntervals;
+
 pub enum CursorType {
     SequenceAware,
     RateLimited,
@@ -95,3 +99,19 @@ impl Indexable for Delivery {
         "delivery"
     }
 }
+
+impl Indexable for MailboxConfigChange {
+    fn indexing_cursor(domain: HyperlaneDomainProtocol) -> CursorType {
+        match domain {
+            HyperlaneDomainProtocol::Ethereum => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Fuel => todo!(),
+            // Config changes have no sequence, so they are indexed by slot
+            HyperlaneDomainProtocol::Sealevel => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
+        }
+    }
+
+    fn name() -> &'static str {
+        "mailbox_config_change"
+    }
+}
@@ -16,7 +16,7 @@ use hyperlane_core::{
 
 use crate::contract_sync::eta_calculator::SyncerEtaCalculator;
 
-use super::{CursorMetrics, Indexable};
+use super::{CursorIntervals, CursorMetrics, Indexable};
 
 /// Time window for the moving average used in the eta calculator in seconds.
 const ETA_TIME_WINDOW: f64 = 2. * 60.;
@@ -89,6 +89,7 @@ pub(crate) struct RateLimitedContractSyncCursor<T> {
     sync_state: SyncState,
     metrics: Arc<CursorMetrics>,
     domain: HyperlaneDomain,
+    intervals: CursorIntervals,
 }
 
 impl<T: Indexable + Sync + Send + Debug + 'static> RateLimitedContractSyncCursor<T> {
@@ -100,6 +101,7 @@ impl<T: Indexable + Sync + Send + Debug + 'static> RateLimitedContractSyncCursor
         store: Arc<dyn HyperlaneWatermarkedLogStore<T>>,
         chunk_size: u32,
         initial_height: u32,
+        intervals: CursorIntervals,
     ) -> Result<Self> {
         let tip = indexer.get_finalized_block_number().await?;
         Ok(Self {
@@ -117,6 +119,7 @@ impl<T: Indexable + Sync + Send + Debug + 'static> RateLimitedContractSyncCursor
             ),
             metrics,
             domain: domain.to_owned(),
+            intervals,
         })
     }
 
@@ -129,9 +132,11 @@ impl<T: Indexable + Sync + Send + Debug + 'static> RateLimitedContractSyncCursor
         }
 
         // We are within one chunk size of the known tip.
-        // If it's been fewer than 30s since the last tip update, sleep for a bit until we're ready to fetch the next tip.
-        if let Some(sleep_time) =
-            Duration::from_secs(30).checked_sub(self.last_tip_update.elapsed())
+        // If the tip was updated recently, sleep for a bit until we're ready to fetch the next tip.
+        if let Some(sleep_time) = self
+            .intervals
+            .tip_update
+            .checked_sub(self.last_tip_update.elapsed())
         {
             return Ok(Some(sleep_time));
         }
@@ -176,6 +181,12 @@ impl<T: Indexable + Sync + Send + Debug + 'static> RateLimitedContractSyncCursor
             .cursor_current_block
             .with_label_values(label_values)
             .set(latest_block as i64);
+        self.metrics.health.report_indexed_block(
+            chain_name,
+            T::name(),
+            latest_block.into(),
+            self.tip.into(),
+        );
     }
 }
 
@@ -195,8 +206,7 @@ where
         if let Some(range) = self.get_next_range().await? {
             return Ok((CursorAction::Query(range), eta));
         } else {
-            // TODO: Define the sleep time from interval flag
-            return Ok((CursorAction::Sleep(Duration::from_secs(5)), eta));
+            return Ok((CursorAction::Sleep(self.intervals.sleep), eta));
         }
     }
 
@@ -332,6 +342,7 @@ pub(crate) mod test {
                 &["event_type", "chain"],
             )
             .unwrap(),
+            health: Default::default(),
         }
     }
     async fn mock_rate_limited_cursor<T: Indexable + Debug + Send + Sync + 'static>(
@@ -371,6 +382,7 @@ pub(crate) mod test {
             Arc::new(db),
             chunk_size,
             initial_height,
+            CursorIntervals::default(),
         )
         .await
         .unwrap()
@@ -15,7 +15,7 @@ mod forward;
 pub(crate) use backward::BackwardSequenceAwareSyncCursor;
 pub(crate) use forward::ForwardSequenceAwareSyncCursor;
 
-use super::{CursorMetrics, Indexable};
+use super::{CursorIntervals, CursorMetrics, Indexable};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct LastIndexedSnapshot {
@@ -77,6 +77,7 @@ pub(crate) struct ForwardBackwardSequenceAwareSyncCursor<T> {
     forward: ForwardSequenceAwareSyncCursor<T>,
     backward: BackwardSequenceAwareSyncCursor<T>,
     last_direction: SyncDirection,
+    intervals: CursorIntervals,
 }
 
 impl<T: Debug + Indexable + Clone + Sync + Send + 'static>
@@ -90,6 +91,7 @@ impl<T: Debug + Indexable + Clone + Sync + Send + 'static>
         store: Arc<dyn HyperlaneSequenceAwareIndexerStoreReader<T>>,
         chunk_size: u32,
         mode: IndexMode,
+        intervals: CursorIntervals,
     ) -> Result<Self> {
         let (sequence_count, tip) = latest_sequence_querier
             .latest_sequence_count_and_tip()
@@ -122,6 +124,7 @@ impl<T: Debug + Indexable + Clone + Sync + Send + 'static>
             forward: forward_cursor,
             backward: backward_cursor,
             last_direction: SyncDirection::Forward,
+            intervals,
         })
     }
 }
@@ -143,8 +146,7 @@ impl<T: Send + Sync + Clone + Debug + 'static + Indexable> ContractSyncCursor<T>
             self.last_direction = SyncDirection::Backward;
             return Ok((CursorAction::Query(backward_range), eta));
         }
-        // TODO: Define the sleep time from interval flag
-        return Ok((CursorAction::Sleep(Duration::from_secs(5)), eta));
+        return Ok((CursorAction::Sleep(self.intervals.sleep), eta));
     }
 
     fn latest_queried_block(&self) -> u32 {
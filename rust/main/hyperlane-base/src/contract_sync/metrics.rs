@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use prometheus::{IntCounterVec, IntGaugeVec};
 
-use crate::CoreMetrics;
+use crate::{CoreMetrics, RetryMetrics};
 
 use super::cursors::CursorMetrics;
 
@@ -31,6 +31,9 @@ pub struct ContractSyncMetrics {
 
     /// Metrics for SequenceAware and RateLimited cursors.
     pub cursor_metrics: Arc<CursorMetrics>,
+
+    /// Metrics of the retries of failed cursor steps
+    pub retries: RetryMetrics,
 }
 
 impl ContractSyncMetrics {
@@ -62,6 +65,7 @@ impl ContractSyncMetrics {
 
         let message_nonce = metrics.last_known_message_nonce();
         let cursor_metrics = Arc::new(CursorMetrics::new(metrics));
+        let retries = metrics.retry_metrics();
 
         ContractSyncMetrics {
             indexed_height,
@@ -69,6 +73,7 @@ impl ContractSyncMetrics {
             message_nonce,
             liveness_metrics,
             cursor_metrics,
+            retries,
         }
     }
 }
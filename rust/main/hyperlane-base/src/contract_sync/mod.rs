@@ -9,9 +9,9 @@ use cursors::*;
 use derive_new::new;
 use eyre::Result;
 use hyperlane_core::{
-    utils::fmt_sync_time, ContractSyncCursor, CursorAction, HyperlaneDomain, HyperlaneLogStore,
-    HyperlaneSequenceAwareIndexerStore, HyperlaneWatermarkedLogStore, Indexer,
-    SequenceAwareIndexer,
+    rpc_clients::RetryPolicy, utils::fmt_sync_time, ContractSyncCursor, CursorAction,
+    HyperlaneDomain, HyperlaneLogStore, HyperlaneSequenceAwareIndexerStore,
+    HyperlaneWatermarkedLogStore, Indexer, SequenceAwareIndexer,
 };
 use hyperlane_core::{Indexed, LogMeta, H512};
 pub use metrics::ContractSyncMetrics;
@@ -20,7 +20,7 @@ use tokio::sync::mpsc::{error::TryRecvError, Receiver as MpscReceiver};
 use tokio::time::sleep;
 use tracing::{debug, info, instrument, trace, warn};
 
-use crate::settings::IndexSettings;
+use crate::{settings::IndexSettings, Retrier, RetryBackoff};
 
 /// Broadcast channel utility, with async interface for `send`
 pub mod broadcast;
@@ -30,7 +30,10 @@ mod metrics;
 
 use cursors::ForwardBackwardSequenceAwareSyncCursor;
 
-const SLEEP_DURATION: Duration = Duration::from_secs(5);
+/// Delays before retrying a cursor step that failed, e.g. because the RPC
+/// errored. They grow while the step keeps failing.
+const CURSOR_RETRY_POLICY: RetryPolicy =
+    RetryPolicy::unbounded_with_cap(Duration::from_secs(5), Duration::from_secs(60));
 
 #[derive(Debug, derive_new::new)]
 #[allow(dead_code)]
@@ -103,6 +106,12 @@ where
             .metrics
             .liveness_metrics
             .with_label_values(&[label, chain_name]);
+        let mut backoff = Retrier::with_metrics(
+            CURSOR_RETRY_POLICY,
+            &format!("contract_sync_{label}"),
+            self.metrics.retries.clone(),
+        )
+        .backoff();
 
         loop {
             Self::update_liveness_metric(&liveness_metric);
@@ -110,8 +119,13 @@ where
                 self.fetch_logs_from_receiver(rx, &stored_logs_metric).await;
             }
             if let Some(cursor) = opts.cursor.as_mut() {
-                self.fetch_logs_with_cursor(cursor, &stored_logs_metric, &indexed_height_metric)
-                    .await;
+                self.fetch_logs_with_cursor(
+                    cursor,
+                    &mut backoff,
+                    &stored_logs_metric,
+                    &indexed_height_metric,
+                )
+                .await;
             }
 
             // Added so that we confuse compiler that it is an infinite loop
@@ -173,10 +187,11 @@ where
         }
     }
 
-    #[instrument(fields(domain=self.domain().name()), skip(self, stored_logs_metric, indexed_height_metric))]
+    #[instrument(fields(domain=self.domain().name()), skip(self, backoff, stored_logs_metric, indexed_height_metric))]
     async fn fetch_logs_with_cursor(
         &self,
         cursor: &mut Box<dyn ContractSyncCursor<T>>,
+        backoff: &mut RetryBackoff,
         stored_logs_metric: &GenericCounter<AtomicU64>,
         indexed_height_metric: &GenericGauge<AtomicI64>,
     ) {
@@ -185,7 +200,9 @@ where
             Ok((action, eta)) => (action, eta),
             Err(err) => {
                 warn!(?err, "Error getting next action");
-                sleep(SLEEP_DURATION).await;
+                if let Some(sleep_duration) = backoff.failed() {
+                    sleep(sleep_duration).await;
+                }
                 return;
             }
         };
@@ -200,7 +217,7 @@ where
                     Ok(logs) => logs,
                     Err(err) => {
                         warn!(?err, ?range, "Error fetching logs in range");
-                        break Some(SLEEP_DURATION);
+                        break backoff.failed();
                     }
                 };
 
@@ -226,8 +243,9 @@ where
                 // Update cursor
                 if let Err(err) = cursor.update(logs, range).await {
                     warn!(?err, "Error updating cursor");
-                    break Some(SLEEP_DURATION);
+                    break backoff.failed();
                 };
+                backoff.succeeded();
                 break None;
             },
             CursorAction::Sleep(duration) => Some(duration),
@@ -331,8 +349,7 @@ where
         let watermark = self.store.retrieve_high_watermark().await.unwrap();
         let index_settings = IndexSettings {
             from: watermark.unwrap_or(index_settings.from),
-            chunk_size: index_settings.chunk_size,
-            mode: index_settings.mode,
+            ..index_settings
         };
         Ok(Box::new(
             RateLimitedContractSyncCursor::new(
@@ -342,6 +359,7 @@ where
                 self.store.clone(),
                 index_settings.chunk_size,
                 index_settings.from,
+                CursorIntervals::from_block_time(index_settings.block_time),
             )
             .await?,
         ))
@@ -385,6 +403,7 @@ where
                 Arc::new(self.store.clone()),
                 index_settings.chunk_size,
                 index_settings.mode,
+                CursorIntervals::from_block_time(index_settings.block_time),
             )
             .await?,
         ))
@@ -25,6 +25,20 @@ pub enum DbError {
     /// Hyperlane Error
     #[error("{0}")]
     HyperlaneError(#[from] HyperlaneProtocolError),
+    /// A backup can't be restored over an existing database
+    #[error("Can't restore a backup to {0}, which already exists")]
+    RestoreTargetExists(PathBuf),
+    /// The backup is of a newer schema than the agent supports
+    #[error("Backup has schema version {found}, newer than the supported {supported}")]
+    UnsupportedSchemaVersion {
+        /// Schema version of the backup
+        found: u32,
+        /// Latest schema version the agent supports
+        supported: u32,
+    },
+    /// IO error while restoring a backup
+    #[error("{0}")]
+    IoError(#[from] io::Error),
 }
 
 impl From<DbError> for ChainCommunicationError {
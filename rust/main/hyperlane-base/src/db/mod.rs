@@ -1,11 +1,14 @@
 pub use error::*;
 use hyperlane_core::{
-    GasPaymentKey, HyperlaneDomain, HyperlaneMessage, InterchainGasPayment,
-    InterchainGasPaymentMeta, MerkleTreeInsertion, PendingOperationStatus, H256,
+    accumulator::incremental::IncrementalMerkle, GasPaymentKey, HyperlaneDomain, HyperlaneMessage,
+    InterchainGasPayment, InterchainGasPaymentMeta, MerkleTreeInsertion, PendingOperationStatus,
+    H256, U256,
 };
 pub use rocks::*;
 
-pub use self::storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData};
+pub use self::storage_types::{
+    AnnouncedStorageLocations, InterchainGasExpenditureData, InterchainGasPaymentData,
+};
 
 mod error;
 mod rocks;
@@ -123,6 +126,32 @@ pub trait HyperlaneDb: Send + Sync {
         message_id: &H256,
     ) -> DbResult<Option<u32>>;
 
+    /// Store when a message was first queued for delivery, as a unix
+    /// timestamp in seconds
+    fn store_message_first_seen_at_by_message_id(
+        &self,
+        message_id: &H256,
+        timestamp: &u64,
+    ) -> DbResult<()>;
+
+    /// Retrieve when a message was first queued for delivery
+    fn retrieve_message_first_seen_at_by_message_id(
+        &self,
+        message_id: &H256,
+    ) -> DbResult<Option<u64>>;
+
+    /// Store when a message expired without being delivered, as a unix
+    /// timestamp in seconds
+    fn store_message_expired_at_by_message_id(
+        &self,
+        message_id: &H256,
+        timestamp: &u64,
+    ) -> DbResult<()>;
+
+    /// Retrieve when a message expired without being delivered, if it did
+    fn retrieve_message_expired_at_by_message_id(&self, message_id: &H256)
+        -> DbResult<Option<u64>>;
+
     fn store_merkle_tree_insertion_by_leaf_index(
         &self,
         leaf_index: &u32,
@@ -159,4 +188,18 @@ pub trait HyperlaneDb: Send + Sync {
 
     /// Retrieve the nonce of the highest processed message we're aware of
     fn retrieve_highest_seen_message_nonce_number(&self) -> DbResult<Option<u32>>;
+
+    /// Store a snapshot of the merkle tree, replacing the previous one. All
+    /// checkpoints up to the snapshot must have been submitted, since a
+    /// restarted validator resumes building the tree from it.
+    fn store_merkle_tree_snapshot(&self, tree: &IncrementalMerkle) -> DbResult<()>;
+
+    /// Retrieve the latest snapshot of the merkle tree
+    fn retrieve_merkle_tree_snapshot(&self) -> DbResult<Option<IncrementalMerkle>>;
+
+    /// Store the tokens a spender, e.g. an app context, spent in the given period
+    fn store_spend_in_period(&self, spender: &str, period: u64, spend: &U256) -> DbResult<()>;
+
+    /// Retrieve the tokens a spender, e.g. an app context, spent in the given period
+    fn retrieve_spend_in_period(&self, spender: &str, period: u64) -> DbResult<Option<U256>>;
 }
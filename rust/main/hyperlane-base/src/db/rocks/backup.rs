@@ -0,0 +1,138 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rocksdb::{checkpoint::Checkpoint, Options, DB as Rocks};
+use tracing::info;
+
+use super::{Result, DB};
+use crate::db::DbError;
+
+/// Version of the layout of the data in the database. Backups of a newer
+/// version can't be restored. Databases that predate it are at version 1.
+pub const DB_SCHEMA_VERSION: u32 = 1;
+
+const SCHEMA_VERSION_KEY: &[u8] = b"db_schema_version";
+
+impl DB {
+    /// Writes a consistent snapshot of the database to `backup_path`, which
+    /// must not exist, while the agent keeps using it. The files of the
+    /// snapshot are hard linked to the database's if they're on the same
+    /// filesystem, so the backup should be copied elsewhere to move hosts.
+    pub fn backup(&self, backup_path: &Path) -> Result<()> {
+        self.store(SCHEMA_VERSION_KEY, &DB_SCHEMA_VERSION.to_be_bytes())?;
+        Checkpoint::new(&*self.0)?.create_checkpoint(backup_path)?;
+        info!(path=%backup_path.to_string_lossy(), "Backed up db");
+        Ok(())
+    }
+
+    /// Restores the backup at `backup_path` as the database at `db_path`,
+    /// which must not exist
+    pub fn restore_backup(backup_path: &Path, db_path: &Path) -> Result<()> {
+        if db_path.exists() {
+            return Err(DbError::RestoreTargetExists(db_path.into()));
+        }
+        let schema_version = {
+            let backup = Rocks::open_for_read_only(&Options::default(), backup_path, false)?;
+            backup
+                .get(SCHEMA_VERSION_KEY)?
+                .and_then(|version| version.try_into().ok())
+                .map(u32::from_be_bytes)
+                .unwrap_or(1)
+        };
+        if schema_version > DB_SCHEMA_VERSION {
+            return Err(DbError::UnsupportedSchemaVersion {
+                found: schema_version,
+                supported: DB_SCHEMA_VERSION,
+            });
+        }
+
+        // Copied next to the database first, so that a failed restore doesn't
+        // leave a partial database behind
+        let mut restoring_path = db_path.as_os_str().to_owned();
+        restoring_path.push(".restoring");
+        let restoring_path = PathBuf::from(restoring_path);
+        if restoring_path.exists() {
+            fs::remove_dir_all(&restoring_path)?;
+        }
+        fs::create_dir_all(&restoring_path)?;
+        for entry in fs::read_dir(backup_path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                fs::copy(entry.path(), restoring_path.join(entry.file_name()))?;
+            }
+        }
+        fs::rename(&restoring_path, db_path)?;
+        info!(
+            backup=%backup_path.to_string_lossy(),
+            path=%db_path.to_string_lossy(),
+            schema_version,
+            "Restored db from backup"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backup_is_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DB::from_path(&dir.path().join("db")).unwrap();
+        db.store(b"key", b"value").unwrap();
+        db.backup(&dir.path().join("backup")).unwrap();
+        // Not in the backup
+        db.store(b"later", b"value").unwrap();
+
+        let restored_path = dir.path().join("restored");
+        DB::restore_backup(&dir.path().join("backup"), &restored_path).unwrap();
+        let restored = DB::from_path(&restored_path).unwrap();
+        assert_eq!(restored.retrieve(b"key").unwrap(), Some(b"value".to_vec()));
+        assert_eq!(restored.retrieve(b"later").unwrap(), None);
+
+        // Existing databases aren't overwritten
+        assert!(matches!(
+            DB::restore_backup(&dir.path().join("backup"), &restored_path),
+            Err(DbError::RestoreTargetExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_restore_keeps_paths_sharing_the_db_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DB::from_path(&dir.path().join("db")).unwrap();
+        db.backup(&dir.path().join("backup")).unwrap();
+        let sibling = dir.path().join("relayer.restoring");
+        fs::create_dir_all(&sibling).unwrap();
+        fs::write(sibling.join("file"), b"data").unwrap();
+
+        let restored_path = dir.path().join("relayer.db");
+        DB::restore_backup(&dir.path().join("backup"), &restored_path).unwrap();
+        assert!(restored_path.exists());
+        assert!(sibling.join("file").exists());
+        assert!(!dir.path().join("relayer.db.restoring").exists());
+    }
+
+    #[test]
+    fn test_backup_of_newer_schema_is_not_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DB::from_path(&dir.path().join("db")).unwrap();
+        db.backup(&dir.path().join("backup")).unwrap();
+        drop(db);
+        let backup = DB::from_path(&dir.path().join("backup")).unwrap();
+        backup
+            .store(SCHEMA_VERSION_KEY, &(DB_SCHEMA_VERSION + 1).to_be_bytes())
+            .unwrap();
+        drop(backup);
+
+        let restored_path = dir.path().join("restored");
+        assert!(matches!(
+            DB::restore_backup(&dir.path().join("backup"), &restored_path),
+            Err(DbError::UnsupportedSchemaVersion { .. })
+        ));
+        assert!(!restored_path.exists());
+    }
+}
@@ -3,15 +3,18 @@ use eyre::{bail, Result};
 use tracing::{debug, instrument, trace};
 
 use hyperlane_core::{
-    Decode, Encode, GasPaymentKey, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage,
-    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexed,
-    InterchainGasExpenditure, InterchainGasPayment, InterchainGasPaymentMeta, LogMeta,
-    MerkleTreeInsertion, PendingOperationStatus, H256,
+    accumulator::incremental::IncrementalMerkle, Decode, Encode, GasPaymentKey, HyperlaneDomain,
+    HyperlaneLogStore, HyperlaneMessage, HyperlaneSequenceAwareIndexerStoreReader,
+    HyperlaneWatermarkedLogStore, Indexed, InterchainGasExpenditure, InterchainGasPayment,
+    InterchainGasPaymentMeta, LogMeta, MerkleTreeInsertion, PendingOperationStatus, H256, U256,
 };
 
 use super::{DbError, TypedDB, DB};
 use crate::db::{
-    storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData},
+    storage_types::{
+        AnnouncedStorageLocations, InterchainGasExpenditureData, InterchainGasPaymentData,
+        LegacyInterchainGasPaymentData,
+    },
     HyperlaneDb,
 };
 
@@ -25,17 +28,27 @@ const NONCE_PROCESSED: &str = "nonce_processed_";
 const GAS_PAYMENT_BY_SEQUENCE: &str = "gas_payment_by_sequence_";
 const GAS_PAYMENT_BLOCK_BY_SEQUENCE: &str = "gas_payment_block_by_sequence_";
 const HIGHEST_SEEN_MESSAGE_NONCE: &str = "highest_seen_message_nonce_";
-const GAS_PAYMENT_FOR_MESSAGE_ID: &str = "gas_payment_sequence_for_message_id_v2_";
+const GAS_PAYMENT_FOR_MESSAGE_ID: &str = "gas_payment_sequence_for_message_id_v3_";
+/// Totals stored before the number of payments was tracked. They are migrated
+/// to `GAS_PAYMENT_FOR_MESSAGE_ID` when first read.
+const LEGACY_GAS_PAYMENT_FOR_MESSAGE_ID: &str = "gas_payment_sequence_for_message_id_v2_";
 const GAS_PAYMENT_META_PROCESSED: &str = "gas_payment_meta_processed_v3_";
 const GAS_EXPENDITURE_FOR_MESSAGE_ID: &str = "gas_expenditure_for_message_id_v2_";
 const STATUS_BY_MESSAGE_ID: &str = "status_by_message_id_";
 const PENDING_MESSAGE_RETRY_COUNT_FOR_MESSAGE_ID: &str =
     "pending_message_retry_count_for_message_id_";
+const MESSAGE_FIRST_SEEN_AT_BY_MESSAGE_ID: &str = "message_first_seen_at_by_message_id_";
+const MESSAGE_EXPIRED_AT_BY_MESSAGE_ID: &str = "message_expired_at_by_message_id_";
 const MERKLE_TREE_INSERTION: &str = "merkle_tree_insertion_";
 const MERKLE_LEAF_INDEX_BY_MESSAGE_ID: &str = "merkle_leaf_index_by_message_id_";
 const MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX: &str =
     "merkle_tree_insertion_block_number_by_leaf_index_";
+const MERKLE_TREE_SNAPSHOT: &str = "merkle_tree_snapshot_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+// Spend used to only be tracked per day for app contexts, the prefix is kept
+// so that spend stored before stays readable
+const SPEND_BY_PERIOD: &str = "app_context_spend_by_day_";
+const ANNOUNCED_STORAGE_LOCATIONS_BY_VALIDATOR: &str = "announced_storage_locations_by_validator_";
 
 /// Rocks DB result type
 pub type DbResult<T> = std::result::Result<T, DbError>;
@@ -222,18 +235,18 @@ impl HyperlaneRocksDB {
         self.update_gas_expenditure_by_message_id(expenditure)
     }
 
-    /// Update the total gas payment for a message to include gas_payment
+    /// Update the total gas payment for a message to include gas_payment.
+    /// Payments for the same message and destination are summed regardless of
+    /// the transaction they were made in or who paid.
     fn update_gas_payment_by_gas_payment_key(&self, event: InterchainGasPayment) -> DbResult<()> {
         let gas_payment_key = event.into();
-        let existing_payment =
-            match self.retrieve_gas_payment_by_gas_payment_key(gas_payment_key)? {
-                Some(payment) => payment,
-                None => InterchainGasPayment::from_gas_payment_key(gas_payment_key),
-            };
-        let total = existing_payment + event;
+        let total = self
+            .retrieve_gas_payment_data_by_gas_payment_key(gas_payment_key)?
+            .unwrap_or_default()
+            .add_payment(event);
 
         debug!(?event, new_total_gas_payment=?total, "Storing gas payment");
-        self.store_interchain_gas_payment_data_by_gas_payment_key(&gas_payment_key, &total.into())?;
+        self.store_interchain_gas_payment_data_by_gas_payment_key(&gas_payment_key, &total)?;
 
         Ok(())
     }
@@ -263,12 +276,48 @@ impl HyperlaneRocksDB {
         gas_payment_key: GasPaymentKey,
     ) -> DbResult<Option<InterchainGasPayment>> {
         Ok(self
-            .retrieve_interchain_gas_payment_data_by_gas_payment_key(&gas_payment_key)?
+            .retrieve_gas_payment_data_by_gas_payment_key(gas_payment_key)?
             .map(|payment| {
                 payment.complete(gas_payment_key.message_id, gas_payment_key.destination)
             }))
     }
 
+    /// Retrieve the number of gas payments made for a message
+    pub fn retrieve_gas_payment_count_by_gas_payment_key(
+        &self,
+        gas_payment_key: GasPaymentKey,
+    ) -> DbResult<u32> {
+        Ok(self
+            .retrieve_gas_payment_data_by_gas_payment_key(gas_payment_key)?
+            .map(|payment| payment.num_payments)
+            .unwrap_or_default())
+    }
+
+    /// Retrieve the gas payment totals for a message, migrating totals stored
+    /// under the legacy key
+    fn retrieve_gas_payment_data_by_gas_payment_key(
+        &self,
+        gas_payment_key: GasPaymentKey,
+    ) -> DbResult<Option<InterchainGasPaymentData>> {
+        if let Some(payment) =
+            self.retrieve_interchain_gas_payment_data_by_gas_payment_key(&gas_payment_key)?
+        {
+            return Ok(Some(payment));
+        }
+        let Some(legacy_payment) = self
+            .retrieve_value_by_key::<_, LegacyInterchainGasPaymentData>(
+                LEGACY_GAS_PAYMENT_FOR_MESSAGE_ID,
+                &gas_payment_key,
+            )?
+        else {
+            return Ok(None);
+        };
+        let payment: InterchainGasPaymentData = legacy_payment.into();
+        debug!(?gas_payment_key, ?payment, "Migrating legacy gas payment");
+        self.store_interchain_gas_payment_data_by_gas_payment_key(&gas_payment_key, &payment)?;
+        Ok(Some(payment))
+    }
+
     /// Retrieve the total gas payment for a message
     pub fn retrieve_gas_expenditure_by_message_id(
         &self,
@@ -279,6 +328,28 @@ impl HyperlaneRocksDB {
             .unwrap_or_default()
             .complete(message_id))
     }
+
+    /// Store the storage locations a validator announced
+    pub fn store_announced_storage_locations(
+        &self,
+        validator: &H256,
+        locations: &AnnouncedStorageLocations,
+    ) -> DbResult<()> {
+        self.store_value_by_key(
+            ANNOUNCED_STORAGE_LOCATIONS_BY_VALIDATOR,
+            validator,
+            locations,
+        )
+    }
+
+    /// Retrieve the storage locations a validator announced, if they were
+    /// stored
+    pub fn retrieve_announced_storage_locations(
+        &self,
+        validator: &H256,
+    ) -> DbResult<Option<AnnouncedStorageLocations>> {
+        self.retrieve_value_by_key(ANNOUNCED_STORAGE_LOCATIONS_BY_VALIDATOR, validator)
+    }
 }
 
 #[async_trait]
@@ -598,6 +669,36 @@ impl HyperlaneDb for HyperlaneRocksDB {
         self.retrieve_value_by_key(PENDING_MESSAGE_RETRY_COUNT_FOR_MESSAGE_ID, message_id)
     }
 
+    fn store_message_first_seen_at_by_message_id(
+        &self,
+        message_id: &H256,
+        timestamp: &u64,
+    ) -> DbResult<()> {
+        self.store_value_by_key(MESSAGE_FIRST_SEEN_AT_BY_MESSAGE_ID, message_id, timestamp)
+    }
+
+    fn retrieve_message_first_seen_at_by_message_id(
+        &self,
+        message_id: &H256,
+    ) -> DbResult<Option<u64>> {
+        self.retrieve_value_by_key(MESSAGE_FIRST_SEEN_AT_BY_MESSAGE_ID, message_id)
+    }
+
+    fn store_message_expired_at_by_message_id(
+        &self,
+        message_id: &H256,
+        timestamp: &u64,
+    ) -> DbResult<()> {
+        self.store_value_by_key(MESSAGE_EXPIRED_AT_BY_MESSAGE_ID, message_id, timestamp)
+    }
+
+    fn retrieve_message_expired_at_by_message_id(
+        &self,
+        message_id: &H256,
+    ) -> DbResult<Option<u64>> {
+        self.retrieve_value_by_key(MESSAGE_EXPIRED_AT_BY_MESSAGE_ID, message_id)
+    }
+
     fn store_merkle_tree_insertion_by_leaf_index(
         &self,
         leaf_index: &u32,
@@ -656,6 +757,31 @@ impl HyperlaneDb for HyperlaneRocksDB {
         // There's no unit struct Encode/Decode impl, so just use `bool` and always use the `Default::default()` key
         self.retrieve_value_by_key(HIGHEST_SEEN_MESSAGE_NONCE, &bool::default())
     }
+
+    /// Store a snapshot of the merkle tree, replacing the previous one
+    fn store_merkle_tree_snapshot(&self, tree: &IncrementalMerkle) -> DbResult<()> {
+        // Only the latest snapshot is kept, so always use the `Default::default()` key
+        self.store_value_by_key(MERKLE_TREE_SNAPSHOT, &bool::default(), tree)
+    }
+
+    /// Retrieve the latest snapshot of the merkle tree
+    fn retrieve_merkle_tree_snapshot(&self) -> DbResult<Option<IncrementalMerkle>> {
+        self.retrieve_value_by_key(MERKLE_TREE_SNAPSHOT, &bool::default())
+    }
+
+    fn store_spend_in_period(&self, spender: &str, period: u64, spend: &U256) -> DbResult<()> {
+        self.store_encodable(SPEND_BY_PERIOD, spend_key(spender, period), spend)
+    }
+
+    fn retrieve_spend_in_period(&self, spender: &str, period: u64) -> DbResult<Option<U256>> {
+        self.retrieve_decodable(SPEND_BY_PERIOD, spend_key(spender, period))
+    }
+}
+
+/// Spenders are arbitrary strings, so the period comes first to keep keys
+/// unambiguous
+fn spend_key(spender: &str, period: u64) -> Vec<u8> {
+    [&period.to_be_bytes()[..], spender.as_bytes()].concat()
 }
 
 impl HyperlaneRocksDB {
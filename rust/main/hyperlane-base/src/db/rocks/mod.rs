@@ -4,12 +4,15 @@ use super::error::DbError;
 use rocksdb::{Options, DB as Rocks};
 use tracing::info;
 
+pub use backup::*;
 pub use hyperlane_db::*;
 pub use typed_db::*;
 
 /// Shared functionality surrounding use of rocksdb
 pub mod iterator;
 
+/// Backups of the whole db
+mod backup;
 /// DB operations tied to specific Mailbox
 mod hyperlane_db;
 /// Type-specific db operations
@@ -31,11 +31,11 @@ where
 #[cfg(test)]
 mod test {
     use hyperlane_core::{
-        HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, Indexed, LogMeta,
-        RawHyperlaneMessage, H256, H512, U256,
+        Encode, GasPaymentKey, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, Indexed,
+        InterchainGasPayment, LogMeta, RawHyperlaneMessage, H256, H512, U256,
     };
 
-    use crate::db::HyperlaneRocksDB;
+    use crate::db::{storage_types::LegacyInterchainGasPaymentData, HyperlaneRocksDB};
 
     use super::*;
 
@@ -77,4 +77,50 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn db_migrates_legacy_gas_payments() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_migrates_legacy_gas_payments"),
+                db,
+            );
+
+            let key = GasPaymentKey {
+                message_id: H256::from_low_u64_be(1),
+                destination: 12,
+            };
+            // Totals as stored by agents from before payments were counted
+            db.store_encodable(
+                "gas_payment_sequence_for_message_id_v2_",
+                key.to_vec(),
+                &LegacyInterchainGasPaymentData {
+                    payment: U256::from(100),
+                    gas_amount: U256::from(10),
+                },
+            )
+            .unwrap();
+
+            let top_up = InterchainGasPayment {
+                message_id: key.message_id,
+                destination: key.destination,
+                payment: U256::from(50),
+                gas_amount: U256::from(5),
+            };
+            assert!(db.process_gas_payment(top_up, &LogMeta::random()).unwrap());
+
+            let total = db
+                .retrieve_gas_payment_by_gas_payment_key(key)
+                .unwrap()
+                .unwrap();
+            assert_eq!(total.payment, U256::from(150));
+            assert_eq!(total.gas_amount, U256::from(15));
+            assert_eq!(
+                db.retrieve_gas_payment_count_by_gas_payment_key(key)
+                    .unwrap(),
+                2
+            );
+        })
+        .await;
+    }
 }
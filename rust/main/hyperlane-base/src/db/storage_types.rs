@@ -13,6 +13,16 @@ pub struct InterchainGasPaymentData {
     pub payment: U256,
     /// The amount of gas paid for.
     pub gas_amount: U256,
+    /// The number of payments summed into `payment` and `gas_amount`.
+    pub num_payments: u32,
+}
+
+/// `InterchainGasPaymentData` as stored before the number of payments was
+/// tracked.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct LegacyInterchainGasPaymentData {
+    pub payment: U256,
+    pub gas_amount: U256,
 }
 
 /// Subset of `InterchainGasExpenditure` excluding the message id which is
@@ -29,6 +39,7 @@ impl Default for InterchainGasPaymentData {
         Self {
             payment: U256::zero(),
             gas_amount: U256::zero(),
+            num_payments: 0,
         }
     }
 }
@@ -43,13 +54,25 @@ impl InterchainGasPaymentData {
             gas_amount: self.gas_amount,
         }
     }
+
+    /// Add a payment to the totals
+    pub fn add_payment(self, p: InterchainGasPayment) -> Self {
+        Self {
+            payment: self.payment + p.payment,
+            gas_amount: self.gas_amount + p.gas_amount,
+            num_payments: self.num_payments + 1,
+        }
+    }
 }
 
-impl From<InterchainGasPayment> for InterchainGasPaymentData {
-    fn from(p: InterchainGasPayment) -> Self {
+impl From<LegacyInterchainGasPaymentData> for InterchainGasPaymentData {
+    fn from(p: LegacyInterchainGasPaymentData) -> Self {
         Self {
             payment: p.payment,
             gas_amount: p.gas_amount,
+            // How many payments the legacy totals were summed from wasn't
+            // recorded, so they count as one
+            num_payments: 1,
         }
     }
 }
@@ -59,11 +82,36 @@ impl Encode for InterchainGasPaymentData {
     where
         W: Write,
     {
-        Ok(self.payment.write_to(writer)? + self.gas_amount.write_to(writer)?)
+        Ok(self.payment.write_to(writer)?
+            + self.gas_amount.write_to(writer)?
+            + self.num_payments.write_to(writer)?)
     }
 }
 
 impl Decode for InterchainGasPaymentData {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            payment: U256::read_from(reader)?,
+            gas_amount: U256::read_from(reader)?,
+            num_payments: u32::read_from(reader)?,
+        })
+    }
+}
+
+impl Encode for LegacyInterchainGasPaymentData {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        Ok(self.payment.write_to(writer)? + self.gas_amount.write_to(writer)?)
+    }
+}
+
+impl Decode for LegacyInterchainGasPaymentData {
     fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
     where
         R: Read,
@@ -126,3 +174,52 @@ impl Decode for InterchainGasExpenditureData {
         })
     }
 }
+
+/// The storage locations a validator announced, as fetched at a point in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnouncedStorageLocations {
+    /// When the locations were fetched, as a unix timestamp in seconds
+    pub fetched_at: u64,
+    /// The locations, in the order they were announced
+    pub locations: Vec<String>,
+}
+
+impl Encode for AnnouncedStorageLocations {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let mut written = self.fetched_at.write_to(writer)?;
+        written += (self.locations.len() as u32).write_to(writer)?;
+        for location in &self.locations {
+            written += (location.len() as u32).write_to(writer)?;
+            writer.write_all(location.as_bytes())?;
+            written += location.len();
+        }
+        Ok(written)
+    }
+}
+
+impl Decode for AnnouncedStorageLocations {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let fetched_at = u64::read_from(reader)?;
+        let num_locations = u32::read_from(reader)?;
+        let mut locations = Vec::new();
+        for _ in 0..num_locations {
+            let mut location = vec![0; u32::read_from(reader)? as usize];
+            reader.read_exact(&mut location)?;
+            let location = String::from_utf8(location).map_err(|err| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, err.utf8_error())
+            })?;
+            locations.push(location);
+        }
+        Ok(Self {
+            fetched_at,
+            locations,
+        })
+    }
+}
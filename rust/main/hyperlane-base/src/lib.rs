@@ -12,6 +12,12 @@ pub mod settings;
 mod agent;
 pub use agent::*;
 
+mod balance_monitor;
+pub use balance_monitor::*;
+
+mod periodic_spend;
+pub use periodic_spend::*;
+
 /// The local database used by agents
 pub mod db;
 
@@ -27,11 +33,20 @@ pub use server::*;
 mod contract_sync;
 pub use contract_sync::*;
 
+mod retry;
+pub use retry::*;
+
+mod self_test;
+pub use self_test::*;
+
 mod traits;
 pub use traits::*;
 
 mod types;
 pub use types::*;
 
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 #[cfg(feature = "oneline-eyre")]
 pub mod oneline_eyre;
@@ -2,7 +2,7 @@ use derive_new::new;
 use serde::{Deserialize, Serialize};
 
 /// Metadata about agent
-#[derive(Debug, Deserialize, Serialize, new)]
+#[derive(Debug, Clone, Deserialize, Serialize, new)]
 pub struct AgentMetadata {
     /// Contains git commit hash of the agent binary
     pub git_sha: String,
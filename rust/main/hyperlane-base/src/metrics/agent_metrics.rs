@@ -18,7 +18,7 @@ use tracing::info_span;
 use tracing::{debug, instrument::Instrumented, trace, warn, Instrument};
 
 use crate::settings::ChainConf;
-use crate::CoreMetrics;
+use crate::{AgentHealth, CoreMetrics};
 
 /// Expected label names for the `wallet_balance` metric.
 pub const WALLET_BALANCE_LABELS: &[&str] = &[
@@ -154,6 +154,7 @@ pub struct ChainSpecificMetricsUpdater {
     chain_metrics: ChainMetrics,
     conf: AgentMetricsConf,
     provider: Box<dyn HyperlaneProvider>,
+    health: Arc<AgentHealth>,
 }
 
 impl ChainSpecificMetricsUpdater {
@@ -167,12 +168,19 @@ impl ChainSpecificMetricsUpdater {
     ) -> Result<Self> {
         let agent_metrics_conf = chain_conf.agent_metrics_conf(agent_name).await?;
         let provider = chain_conf.build_provider(&core_metrics).await?;
+        let health = core_metrics.health();
+        health.register_chain(
+            chain_conf.domain.name(),
+            chain_conf.index_settings().block_time,
+            agent_metrics_conf.address.clone(),
+        );
 
         Ok(Self {
             agent_metrics,
             chain_metrics,
             conf: agent_metrics_conf,
             provider,
+            health,
         })
     }
 
@@ -215,13 +223,17 @@ impl ChainSpecificMetricsUpdater {
             Ok(Some(chain_metrics)) => chain_metrics,
             Err(err) => {
                 warn!(chain, ?err, "Failed to get chain metrics");
+                self.health.report_chain_error(chain, &err);
                 return;
             }
             _ => {
                 trace!(chain, "No chain metrics available");
+                self.health.report_chain_reached(chain, None);
                 return;
             }
         };
+        self.health
+            .report_chain_reached(chain, Some(chain_metrics.latest_block.number));
 
         let height = chain_metrics.latest_block.number as i64;
         trace!(chain, height, "Fetched block height for metrics");
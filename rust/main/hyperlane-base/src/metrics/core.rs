@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 use std::time;
 
+use convert_case::{Case, Casing};
 use eyre::Result;
 use hyperlane_core::{HyperlaneDomain, H160};
 use prometheus::{
@@ -14,10 +15,21 @@ use prometheus::{
 use tokio::sync::RwLock;
 
 use ethers_prometheus::middleware::MiddlewareMetrics;
+use hyperlane_ethereum::TransactionInclusionMetrics;
 use hyperlane_metric::prometheus_metric::PrometheusClientMetrics;
-
-use crate::metrics::{
-    json_rpc_client::create_json_rpc_client_metrics, provider::create_provider_metrics,
+use hyperlane_sealevel::PriorityFeeMetrics;
+
+use crate::{
+    balance_monitor::{create_balance_monitor_metrics, BalanceMonitorMetrics},
+    metrics::{
+        json_rpc_client::create_json_rpc_client_metrics,
+        provider::{
+            create_provider_metrics, create_sealevel_priority_fee_metrics,
+            create_transaction_inclusion_metrics,
+        },
+    },
+    retry::{create_retry_metrics, RetryMetrics},
+    server::AgentHealth,
 };
 
 /// Macro to prefix a string with the namespace.
@@ -34,16 +46,22 @@ pub struct CoreMetrics {
     const_labels: HashMap<String, String>,
     listen_port: u16,
     agent_name: String,
+    /// Bucket bounds overriding the ones histograms are created with, by
+    /// metric name in flat case
+    histogram_buckets: HashMap<String, Vec<f64>>,
 
     span_durations: CounterVec,
     span_counts: IntCounterVec,
     span_events: IntCounterVec,
+    task_panics: IntCounterVec,
     last_known_message_nonce: IntGaugeVec,
     latest_tree_insertion_index: IntGaugeVec,
     submitter_queue_length: IntGaugeVec,
 
     operations_processed_count: IntCounterVec,
     messages_processed_count: IntCounterVec,
+    messages_processed_gas_used: IntCounterVec,
+    message_delivery_phases: IntCounterVec,
 
     latest_checkpoint: IntGaugeVec,
 
@@ -54,8 +72,28 @@ pub struct CoreMetrics {
     /// Set of provider-specific metrics. These only need to get created once.
     provider_metrics: OnceLock<MiddlewareMetrics>,
 
+    /// Set of metrics of the lifecycle of submitted transactions. These only
+    /// need to get created once.
+    transaction_inclusion_metrics: OnceLock<TransactionInclusionMetrics>,
+
+    /// Set of metrics of the priority fees of Sealevel transactions. These
+    /// only need to get created once.
+    sealevel_priority_fee_metrics: OnceLock<PriorityFeeMetrics>,
+
+    /// Set of metrics of retried calls, shared by all their call sites. These
+    /// only need to get created once.
+    retry_metrics: OnceLock<RetryMetrics>,
+
+    /// Set of metrics of the signer balance floors of all chains. These only
+    /// need to get created once.
+    balance_monitor_metrics: OnceLock<BalanceMonitorMetrics>,
+
     /// Metrics that are used to observe validator sets.
     pub validator_metrics: ValidatorObservabilityMetricManager,
+
+    /// Health of the components of the agent, served on `/health` and
+    /// `/ready`
+    health: Arc<AgentHealth>,
 }
 
 impl CoreMetrics {
@@ -104,6 +142,16 @@ impl CoreMetrics {
             registry
         )?;
 
+        let task_panics = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("task_panics"),
+                "Number of times a task panicked and was restarted",
+                const_labels_ref
+            ),
+            &["task", "chain"],
+            registry
+        )?;
+
         let last_known_message_nonce = register_int_gauge_vec_with_registry!(
             opts!(
                 namespaced!("last_known_message_nonce"),
@@ -179,6 +227,26 @@ impl CoreMetrics {
             registry
         )?;
 
+        let message_delivery_phases = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("message_delivery_phases"),
+                "Number of delivery phases completed by messages to destinations that deliver them in two phases",
+                const_labels_ref
+            ),
+            &["origin", "remote", "phase"],
+            registry
+        )?;
+
+        let messages_processed_gas_used = register_int_counter_vec_with_registry!(
+            opts!(
+                namespaced!("messages_processed_gas_used"),
+                "Destination gas used to process messages",
+                const_labels_ref
+            ),
+            &["origin", "remote"],
+            registry
+        )?;
+
         Ok(Self {
             agent_name: for_agent.into(),
             registry,
@@ -188,6 +256,7 @@ impl CoreMetrics {
             span_durations,
             span_counts,
             span_events,
+            task_panics,
             last_known_message_nonce,
             latest_tree_insertion_index,
 
@@ -195,18 +264,44 @@ impl CoreMetrics {
 
             operations_processed_count,
             messages_processed_count,
+            messages_processed_gas_used,
+            message_delivery_phases,
 
             latest_checkpoint,
 
             client_metrics: OnceLock::new(),
             provider_metrics: OnceLock::new(),
+            transaction_inclusion_metrics: OnceLock::new(),
+            sealevel_priority_fee_metrics: OnceLock::new(),
+            retry_metrics: OnceLock::new(),
+            balance_monitor_metrics: OnceLock::new(),
 
             validator_metrics: ValidatorObservabilityMetricManager::new(
                 observed_validator_latest_index.clone(),
             ),
+
+            health: Default::default(),
         })
     }
 
+    /// Override the bucket bounds of histograms, by metric name without the
+    /// namespace prefix. Latencies differ by orders of magnitude between
+    /// chains, e.g. sub-second on Solana and minutes on Ethereum, so the
+    /// default bounds don't fit every deployment.
+    pub fn with_histogram_buckets(mut self, histogram_buckets: HashMap<String, Vec<f64>>) -> Self {
+        self.histogram_buckets = histogram_buckets
+            .into_iter()
+            .map(|(metric_name, buckets)| (metric_name.to_case(Case::Flat), buckets))
+            .collect();
+        self
+    }
+
+    /// The health of the components of the agent, which they report as they
+    /// run
+    pub fn health(&self) -> Arc<AgentHealth> {
+        self.health.clone()
+    }
+
     /// Create the provider metrics attached to this core metrics instance.
     pub fn provider_metrics(&self) -> MiddlewareMetrics {
         self.provider_metrics
@@ -216,6 +311,47 @@ impl CoreMetrics {
             .clone()
     }
 
+    /// Create the transaction inclusion metrics attached to this core metrics
+    /// instance.
+    pub fn transaction_inclusion_metrics(&self) -> TransactionInclusionMetrics {
+        self.transaction_inclusion_metrics
+            .get_or_init(|| {
+                create_transaction_inclusion_metrics(self)
+                    .expect("Failed to create transaction inclusion metrics!")
+            })
+            .clone()
+    }
+
+    /// Create the Sealevel priority fee metrics attached to this core metrics
+    /// instance.
+    pub fn sealevel_priority_fee_metrics(&self) -> PriorityFeeMetrics {
+        self.sealevel_priority_fee_metrics
+            .get_or_init(|| {
+                create_sealevel_priority_fee_metrics(self)
+                    .expect("Failed to create sealevel priority fee metrics!")
+            })
+            .clone()
+    }
+
+    /// Create the metrics of retried calls attached to this core metrics
+    /// instance.
+    pub fn retry_metrics(&self) -> RetryMetrics {
+        self.retry_metrics
+            .get_or_init(|| create_retry_metrics(self).expect("Failed to create retry metrics!"))
+            .clone()
+    }
+
+    /// Create the metrics of the signer balance floors attached to this core
+    /// metrics instance.
+    pub fn balance_monitor_metrics(&self) -> BalanceMonitorMetrics {
+        self.balance_monitor_metrics
+            .get_or_init(|| {
+                create_balance_monitor_metrics(self)
+                    .expect("Failed to create balance monitor metrics!")
+            })
+            .clone()
+    }
+
     /// Create the json rpc provider metrics attached to this core metrics
     /// instance.
     pub fn client_metrics(&self) -> PrometheusClientMetrics {
@@ -277,7 +413,8 @@ impl CoreMetrics {
         )?)
     }
 
-    /// Create and register a new histogram.
+    /// Create and register a new histogram. `buckets` are the default bucket
+    /// bounds, used unless overridden in the settings.
     pub fn new_histogram(
         &self,
         metric_name: &str,
@@ -285,6 +422,11 @@ impl CoreMetrics {
         labels: &[&str],
         buckets: Vec<f64>,
     ) -> Result<HistogramVec> {
+        let buckets = self
+            .histogram_buckets
+            .get(&metric_name.to_case(Case::Flat))
+            .cloned()
+            .unwrap_or(buckets);
         Ok(register_histogram_vec_with_registry!(
             histogram_opts!(
                 namespaced!(metric_name),
@@ -377,7 +519,7 @@ impl CoreMetrics {
     }
 
     /// The number of messages successfully submitted by this process during its
-    /// lifetime.
+    /// lifetime, or across restarts if persistent metrics are enabled.
     ///
     /// The value of
     /// `hyperlane_last_known_message_nonce{phase=message_processed}`
@@ -393,6 +535,28 @@ impl CoreMetrics {
         self.messages_processed_count.clone()
     }
 
+    /// The destination gas used to process messages successfully submitted by
+    /// this process during its lifetime, or across restarts if persistent
+    /// metrics are enabled.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain we delivered the message to.
+    pub fn messages_processed_gas_used(&self) -> IntCounterVec {
+        self.messages_processed_gas_used.clone()
+    }
+
+    /// Messages that completed a phase of their delivery, on destinations
+    /// whose mailbox enqueues messages before executing them.
+    ///
+    /// Labels:
+    /// - `origin`: Chain the message came from.
+    /// - `remote`: Chain we delivered the message to.
+    /// - `phase`: `enqueued` or `executed`.
+    pub fn message_delivery_phases(&self) -> IntCounterVec {
+        self.message_delivery_phases.clone()
+    }
+
     /// Measure of span durations provided by tracing.
     ///
     /// Labels:
@@ -425,6 +589,16 @@ impl CoreMetrics {
         self.span_events.clone()
     }
 
+    /// Counts of the panics of supervised tasks, which are restarted instead
+    /// of stopping the agent.
+    ///
+    /// Labels:
+    /// - `task`: the task that panicked, e.g. `message_processor`.
+    /// - `chain`: the chain the task runs for.
+    pub fn task_panics(&self) -> IntCounterVec {
+        self.task_panics.clone()
+    }
+
     /// Gather available metrics into an encoded (plaintext, OpenMetrics format)
     /// report.
     pub fn gather(&self) -> prometheus::Result<Vec<u8>> {
@@ -585,3 +759,38 @@ impl ValidatorObservabilityMetricManager {
         self.observed_validator_latest_index.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use prometheus::core::Collector;
+
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_overridden() {
+        let metrics = CoreMetrics::new("test", 9090, Registry::new())
+            .unwrap()
+            .with_histogram_buckets(HashMap::from([(
+                "transaction_inclusion_seconds".to_owned(),
+                vec![0.4, 0.8],
+            )]));
+        let bucket_bounds = |histogram: HistogramVec| {
+            histogram.with_label_values(&["test"]).observe(1.0);
+            histogram.collect()[0].get_metric()[0]
+                .get_histogram()
+                .get_bucket()
+                .iter()
+                .map(|bucket| bucket.get_upper_bound())
+                .collect::<Vec<_>>()
+        };
+
+        let overridden = metrics
+            .new_histogram("transactionInclusionSeconds", "help", &["chain"], vec![1.0])
+            .unwrap();
+        assert_eq!(bucket_bounds(overridden), [0.4, 0.8]);
+        let default = metrics
+            .new_histogram("request_latency_seconds", "help", &["chain"], vec![1.0])
+            .unwrap();
+        assert_eq!(bucket_bounds(default), [1.0]);
+    }
+}
@@ -2,6 +2,8 @@ use eyre::Result;
 use hyperlane_metric::prometheus_metric::{
     PrometheusClientMetrics, PrometheusClientMetricsBuilder, REQUEST_COUNT_HELP,
     REQUEST_COUNT_LABELS, REQUEST_DURATION_SECONDS_HELP, REQUEST_DURATION_SECONDS_LABELS,
+    REQUEST_ERROR_COUNT_HELP, REQUEST_ERROR_COUNT_LABELS, REQUEST_LATENCY_SECONDS_BUCKETS,
+    REQUEST_LATENCY_SECONDS_HELP, REQUEST_LATENCY_SECONDS_LABELS,
 };
 
 use crate::CoreMetrics;
@@ -20,5 +22,16 @@ pub(crate) fn create_json_rpc_client_metrics(
             REQUEST_DURATION_SECONDS_HELP,
             REQUEST_DURATION_SECONDS_LABELS,
         )?)
+        .request_latency_seconds(metrics.new_histogram(
+            "request_latency_seconds",
+            REQUEST_LATENCY_SECONDS_HELP,
+            REQUEST_LATENCY_SECONDS_LABELS,
+            REQUEST_LATENCY_SECONDS_BUCKETS.to_vec(),
+        )?)
+        .request_error_count(metrics.new_int_counter(
+            "request_error_count",
+            REQUEST_ERROR_COUNT_HELP,
+            REQUEST_ERROR_COUNT_LABELS,
+        )?)
         .build()?)
 }
@@ -9,8 +9,10 @@ mod core;
 
 mod agent_metrics;
 mod json_rpc_client;
+mod persistent;
 mod provider;
 mod runtime_metrics;
 
 pub use self::agent_metrics::*;
+pub use self::persistent::*;
 pub use self::runtime_metrics::*;
@@ -0,0 +1,214 @@
+use std::sync::{Arc, Mutex};
+
+use hyperlane_core::{Decode, Encode};
+use prometheus::IntCounter;
+use tracing::warn;
+
+use crate::db::DB;
+
+const PERSISTENT_METRIC: &str = "persistent_metric_";
+
+/// A Prometheus counter whose value is persisted in the agent database, so
+/// that long-window dashboards aren't broken by agent restarts.
+///
+/// If no database is provided, this behaves like a regular counter.
+#[derive(Debug, Clone)]
+pub struct PersistentIntCounter {
+    counter: IntCounter,
+    store: Option<PersistentStore>,
+}
+
+#[derive(Debug, Clone)]
+struct PersistentStore {
+    db: DB,
+    key: Vec<u8>,
+    /// The last value written to the database. Writes happen under this
+    /// lock and only for greater values, so that concurrent increments can't
+    /// overwrite a value with an older one.
+    persisted: Arc<Mutex<u64>>,
+}
+
+impl PersistentIntCounter {
+    /// Wraps `counter`, persisting its value in `db` under a key unique to the
+    /// agent, metric name and label values.
+    ///
+    /// If a value was persisted by a previous run of the agent, the counter is
+    /// initialized to it, unless it was already incremented in this run.
+    pub fn new(
+        counter: IntCounter,
+        db: Option<DB>,
+        agent_name: &str,
+        metric_name: &str,
+        label_values: &[&str],
+    ) -> Self {
+        let store = db.map(|db| PersistentStore {
+            db,
+            key: persistent_metric_key(agent_name, metric_name, label_values),
+            persisted: Default::default(),
+        });
+        let persistent = Self { counter, store };
+        persistent.restore();
+        persistent
+    }
+
+    /// Increments the counter by 1.
+    pub fn inc(&self) {
+        self.inc_by(1)
+    }
+
+    /// Increments the counter by `v`.
+    pub fn inc_by(&self, v: u64) {
+        self.counter.inc_by(v);
+        self.persist();
+    }
+
+    /// The current value of the counter.
+    pub fn get(&self) -> u64 {
+        self.counter.get()
+    }
+
+    fn restore(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        // Another handle to the same counter may have already restored it
+        if self.counter.get() != 0 {
+            return;
+        }
+        match store.db.retrieve(&store.key) {
+            Ok(Some(value)) => match u64::read_from(&mut value.as_slice()) {
+                Ok(value) => {
+                    self.counter.inc_by(value);
+                    *store.persisted.lock().expect("persisted lock poisoned") = value;
+                }
+                Err(err) => warn!(?err, "Failed to decode persisted metric value"),
+            },
+            Ok(None) => {}
+            Err(err) => warn!(?err, "Failed to retrieve persisted metric value"),
+        }
+    }
+
+    fn persist(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let mut persisted = store.persisted.lock().expect("persisted lock poisoned");
+        // Read under the lock, so that a value read before a concurrent
+        // increment is never written after it
+        let value = self.counter.get();
+        if value <= *persisted {
+            return;
+        }
+        match store.db.store(&store.key, &value.to_vec()) {
+            Ok(()) => *persisted = value,
+            Err(err) => warn!(?err, "Failed to persist metric value"),
+        }
+    }
+}
+
+fn persistent_metric_key(agent_name: &str, metric_name: &str, label_values: &[&str]) -> Vec<u8> {
+    let mut key = format!("{PERSISTENT_METRIC}{agent_name}_{metric_name}");
+    for label_value in label_values {
+        key.push('_');
+        key.push_str(label_value);
+    }
+    key.into_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db::test_utils;
+
+    use super::*;
+
+    fn counter() -> IntCounter {
+        IntCounter::new("messages_processed_count", "help string").unwrap()
+    }
+
+    fn persistent_counter(db: &DB, label_values: &[&str]) -> PersistentIntCounter {
+        PersistentIntCounter::new(
+            counter(),
+            Some(db.clone()),
+            "relayer",
+            "messages_processed_count",
+            label_values,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_value_survives_restart() {
+        test_utils::run_test_db(|db| async move {
+            let first_run = persistent_counter(&db, &["ethereum", "arbitrum"]);
+            assert_eq!(first_run.get(), 0);
+            first_run.inc();
+            first_run.inc_by(41);
+
+            let second_run = persistent_counter(&db, &["ethereum", "arbitrum"]);
+            assert_eq!(second_run.get(), 42);
+            second_run.inc();
+
+            let third_run = persistent_counter(&db, &["ethereum", "arbitrum"]);
+            assert_eq!(third_run.get(), 43);
+
+            // Different label values are persisted separately
+            let other_labels = persistent_counter(&db, &["ethereum", "optimism"]);
+            assert_eq!(other_labels.get(), 0);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_already_incremented_counter_is_not_restored() {
+        test_utils::run_test_db(|db| async move {
+            persistent_counter(&db, &[]).inc_by(10);
+
+            let counter = counter();
+            let first = PersistentIntCounter::new(
+                counter.clone(),
+                Some(db.clone()),
+                "relayer",
+                "messages_processed_count",
+                &[],
+            );
+            first.inc();
+            // A second handle to the same counter must not add the persisted value again
+            let second = PersistentIntCounter::new(
+                counter,
+                Some(db.clone()),
+                "relayer",
+                "messages_processed_count",
+                &[],
+            );
+            assert_eq!(second.get(), 11);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_increments_are_all_persisted() {
+        test_utils::run_test_db(|db| async move {
+            let counter = persistent_counter(&db, &[]);
+            std::thread::scope(|scope| {
+                for _ in 0..8 {
+                    let counter = counter.clone();
+                    scope.spawn(move || {
+                        for _ in 0..100 {
+                            counter.inc();
+                        }
+                    });
+                }
+            });
+
+            let restarted = persistent_counter(&db, &[]);
+            assert_eq!(restarted.get(), 800);
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_without_db() {
+        let counter = PersistentIntCounter::new(counter(), None, "relayer", "count", &[]);
+        counter.inc();
+        assert_eq!(counter.get(), 1);
+    }
+}
@@ -1,6 +1,20 @@
 use eyre::Result;
 
 use ethers_prometheus::middleware::*;
+use hyperlane_ethereum::{
+    TransactionInclusionMetrics, NONCE_REPLACEMENTS_TOTAL_HELP, NONCE_REPLACEMENTS_TOTAL_LABELS,
+    STUCK_NONCE_SECONDS_HELP, STUCK_NONCE_SECONDS_LABELS, TRANSACTION_INCLUSION_SECONDS_BUCKETS,
+    TRANSACTION_INCLUSION_SECONDS_HELP, TRANSACTION_INCLUSION_SECONDS_LABELS,
+    TRANSACTION_NOT_INCLUDED_TOTAL_HELP, TRANSACTION_NOT_INCLUDED_TOTAL_LABELS,
+};
+
+use hyperlane_sealevel::{
+    PriorityFeeMetrics, PRIORITY_FEE_MICRO_LAMPORTS_BUCKETS, PRIORITY_FEE_MICRO_LAMPORTS_HELP,
+    PRIORITY_FEE_MICRO_LAMPORTS_LABELS, PRIORITY_FEE_PERCENTILE_BUCKETS,
+    PRIORITY_FEE_PERCENTILE_HELP, PRIORITY_FEE_PERCENTILE_LABELS, TRANSACTIONS_LANDED_TOTAL_HELP,
+    TRANSACTIONS_LANDED_TOTAL_LABELS, TRANSACTION_LANDING_SLOTS_BUCKETS,
+    TRANSACTION_LANDING_SLOTS_HELP, TRANSACTION_LANDING_SLOTS_LABELS,
+};
 
 use crate::CoreMetrics;
 
@@ -38,3 +52,61 @@ pub(crate) fn create_provider_metrics(metrics: &CoreMetrics) -> Result<Middlewar
         )?)
         .build()?)
 }
+
+pub(crate) fn create_transaction_inclusion_metrics(
+    metrics: &CoreMetrics,
+) -> Result<TransactionInclusionMetrics> {
+    Ok(TransactionInclusionMetrics {
+        inclusion_seconds: metrics.new_histogram(
+            "transaction_inclusion_seconds",
+            TRANSACTION_INCLUSION_SECONDS_HELP,
+            TRANSACTION_INCLUSION_SECONDS_LABELS,
+            TRANSACTION_INCLUSION_SECONDS_BUCKETS.to_vec(),
+        )?,
+        not_included: metrics.new_int_counter(
+            "transaction_not_included_total",
+            TRANSACTION_NOT_INCLUDED_TOTAL_HELP,
+            TRANSACTION_NOT_INCLUDED_TOTAL_LABELS,
+        )?,
+        stuck_nonce_seconds: metrics.new_int_gauge(
+            "stuck_nonce_seconds",
+            STUCK_NONCE_SECONDS_HELP,
+            STUCK_NONCE_SECONDS_LABELS,
+        )?,
+        nonce_replacements: metrics.new_int_counter(
+            "nonce_replacements_total",
+            NONCE_REPLACEMENTS_TOTAL_HELP,
+            NONCE_REPLACEMENTS_TOTAL_LABELS,
+        )?,
+    })
+}
+
+pub(crate) fn create_sealevel_priority_fee_metrics(
+    metrics: &CoreMetrics,
+) -> Result<PriorityFeeMetrics> {
+    Ok(PriorityFeeMetrics {
+        priority_fee_micro_lamports: metrics.new_histogram(
+            "sealevel_priority_fee_micro_lamports",
+            PRIORITY_FEE_MICRO_LAMPORTS_HELP,
+            PRIORITY_FEE_MICRO_LAMPORTS_LABELS,
+            PRIORITY_FEE_MICRO_LAMPORTS_BUCKETS.to_vec(),
+        )?,
+        priority_fee_percentile: metrics.new_histogram(
+            "sealevel_priority_fee_percentile",
+            PRIORITY_FEE_PERCENTILE_HELP,
+            PRIORITY_FEE_PERCENTILE_LABELS,
+            PRIORITY_FEE_PERCENTILE_BUCKETS.to_vec(),
+        )?,
+        landing_slots: metrics.new_histogram(
+            "sealevel_transaction_landing_slots",
+            TRANSACTION_LANDING_SLOTS_HELP,
+            TRANSACTION_LANDING_SLOTS_LABELS,
+            TRANSACTION_LANDING_SLOTS_BUCKETS.to_vec(),
+        )?,
+        landed_total: metrics.new_int_counter(
+            "sealevel_transactions_landed_total",
+            TRANSACTIONS_LANDED_TOTAL_HELP,
+            TRANSACTIONS_LANDED_TOTAL_LABELS,
+        )?,
+    })
+}
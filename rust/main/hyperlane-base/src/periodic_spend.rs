@@ -0,0 +1,132 @@
+//! Tracking of the tokens an agent spends per period, e.g. to enforce daily
+//! budgets.
+//!
+//! Periods are fixed-length windows numbered from the unix epoch, e.g. the UTC
+//! days returned by [`today`]. Spend is persisted so that restarting an agent
+//! doesn't reset its budgets.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_core::U256;
+
+use crate::db::{DbResult, HyperlaneDb};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+/// The tokens spent per period by each spender, e.g. an app context, persisted
+/// in a chain's database.
+///
+/// A spender must always be tracked over periods of the same length, since
+/// only the number of the period is stored.
+pub struct PeriodicSpend {
+    db: Arc<dyn HyperlaneDb>,
+    /// Serializes the read-modify-write of recorded spend, so that spend
+    /// recorded concurrently isn't lost
+    lock: Mutex<()>,
+}
+
+impl std::fmt::Debug for PeriodicSpend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PeriodicSpend")
+            .field("domain", self.db.domain())
+            .finish()
+    }
+}
+
+impl PeriodicSpend {
+    /// Tracks spend in `db`
+    pub fn new(db: Arc<dyn HyperlaneDb>) -> Self {
+        Self {
+            db,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// The tokens `spender` spent in `period`
+    pub fn spent_in_period(&self, spender: &str, period: u64) -> DbResult<U256> {
+        Ok(self
+            .db
+            .retrieve_spend_in_period(spender, period)?
+            .unwrap_or_default())
+    }
+
+    /// Adds `tokens` to what `spender` spent in `period`. Returns the new
+    /// total.
+    pub fn record_in_period(&self, spender: &str, tokens: U256, period: u64) -> DbResult<U256> {
+        let _guard = self.lock.lock().expect("periodic spend lock poisoned");
+        let spent = self
+            .spent_in_period(spender, period)?
+            .saturating_add(tokens);
+        self.db.store_spend_in_period(spender, period, &spent)?;
+        Ok(spent)
+    }
+}
+
+/// The current UTC day, in days since the unix epoch
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+        / SECONDS_PER_DAY
+}
+
+/// Converts an amount of tokens to a float to export it as a metric
+pub fn u256_as_f64(value: U256) -> f64 {
+    // Precision loss is fine for metrics
+    value.to_string().parse().unwrap_or(f64::MAX)
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain};
+
+    use crate::db::{test_utils, HyperlaneRocksDB};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spend_is_tracked_per_spender_and_period() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+            let spend = PeriodicSpend::new(Arc::new(HyperlaneRocksDB::new(&domain, db)));
+
+            assert_eq!(
+                spend.record_in_period("a", U256::from(60), 1).unwrap(),
+                U256::from(60)
+            );
+            assert_eq!(
+                spend.record_in_period("a", U256::from(60), 1).unwrap(),
+                U256::from(120)
+            );
+            assert_eq!(spend.spent_in_period("a", 1).unwrap(), U256::from(120));
+            assert_eq!(spend.spent_in_period("a", 2).unwrap(), U256::zero());
+            assert_eq!(spend.spent_in_period("b", 1).unwrap(), U256::zero());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_spend_is_not_lost() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+            let spend = PeriodicSpend::new(Arc::new(HyperlaneRocksDB::new(&domain, db)));
+
+            std::thread::scope(|scope| {
+                for _ in 0..10 {
+                    scope.spawn(|| {
+                        for _ in 0..10 {
+                            spend.record_in_period("a", U256::from(1), 1).unwrap();
+                        }
+                    });
+                }
+            });
+
+            assert_eq!(spend.spent_in_period("a", 1).unwrap(), U256::from(100));
+        })
+        .await;
+    }
+}
@@ -0,0 +1,250 @@
+//! Retries of fallible calls shared by all agents.
+//!
+//! The [`RetryPolicy`] of a call site decides how long to wait between
+//! attempts and when to give up, and a [`Retrier`] applies it while counting
+//! the attempts and abandoned calls of the call site, so that every retry
+//! loop of an agent can be observed the same way.
+
+use std::{future::Future, pin::Pin, time::Duration};
+
+use eyre::Result;
+use hyperlane_core::{
+    rpc_clients::{call_with_retry_policy, RetryPolicy},
+    ChainResult,
+};
+use prometheus::IntCounterVec;
+use tracing::warn;
+
+use crate::CoreMetrics;
+
+const OUTCOME_SUCCESS: &str = "success";
+const OUTCOME_FAILURE: &str = "failure";
+
+/// Metrics of the retried calls of an agent, by call site
+#[derive(Debug, Clone)]
+pub struct RetryMetrics {
+    /// Attempts of retried calls
+    ///
+    /// Labels:
+    /// - `call_site`: What was called, e.g. `validator_submit_checkpoint`.
+    /// - `outcome`: `success` or `failure`.
+    pub attempts: IntCounterVec,
+
+    /// Calls whose retry policy gave up on them
+    ///
+    /// Labels:
+    /// - `call_site`: What was called.
+    pub exhausted: IntCounterVec,
+}
+
+pub(crate) fn create_retry_metrics(metrics: &CoreMetrics) -> Result<RetryMetrics> {
+    Ok(RetryMetrics {
+        attempts: metrics.new_int_counter(
+            "retried_call_attempts_total",
+            "Attempts of calls that are retried on failure, by call site and outcome",
+            &["call_site", "outcome"],
+        )?,
+        exhausted: metrics.new_int_counter(
+            "retried_calls_exhausted_total",
+            "Calls abandoned after their retry policy gave up on them, by call site",
+            &["call_site"],
+        )?,
+    })
+}
+
+/// Retries the calls of a call site according to its policy, counting their
+/// attempts
+#[derive(Debug, Clone)]
+pub struct Retrier {
+    policy: RetryPolicy,
+    call_site: String,
+    metrics: Option<RetryMetrics>,
+}
+
+impl Retrier {
+    /// A retrier of the calls of `call_site`, whose attempts are counted in
+    /// the metrics of the agent
+    pub fn new(policy: RetryPolicy, call_site: &str, metrics: &CoreMetrics) -> Self {
+        Self::with_metrics(policy, call_site, metrics.retry_metrics())
+    }
+
+    /// A retrier of the calls of `call_site`, whose attempts are counted in
+    /// `metrics`
+    pub fn with_metrics(policy: RetryPolicy, call_site: &str, metrics: RetryMetrics) -> Self {
+        Self {
+            policy,
+            call_site: call_site.to_owned(),
+            metrics: Some(metrics),
+        }
+    }
+
+    /// A retrier whose attempts aren't counted
+    pub fn without_metrics(policy: RetryPolicy, call_site: &str) -> Self {
+        Self {
+            policy,
+            call_site: call_site.to_owned(),
+            metrics: None,
+        }
+    }
+
+    /// The policy calls are retried with
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Calls a fallible async function until it succeeds or the policy gives
+    /// up, in which case the last error is returned
+    pub async fn call<T>(
+        &self,
+        f: impl FnMut() -> Pin<Box<dyn Future<Output = ChainResult<T>> + Send>>,
+    ) -> ChainResult<T> {
+        let res = call_with_retry_policy(&self.policy, f, |failures, err| {
+            self.record_failure();
+            warn!(call_site = self.call_site.as_str(), failures, error = ?err, "Retrying call");
+        })
+        .await;
+        match &res {
+            Ok(_) => self.record_success(),
+            Err(_) => self.record_exhausted(),
+        }
+        res
+    }
+
+    /// Calls a fallible async function until it succeeds. If the policy gives
+    /// up on the call, it's started over.
+    pub async fn call_until_success<T>(
+        &self,
+        mut f: impl FnMut() -> Pin<Box<dyn Future<Output = ChainResult<T>> + Send>>,
+    ) -> T {
+        loop {
+            if let Ok(res) = self.call(&mut f).await {
+                return res;
+            }
+        }
+    }
+
+    /// A backoff for a loop that retries an operation it can't wrap in a
+    /// closure, e.g. the steps of a cursor
+    pub fn backoff(&self) -> RetryBackoff {
+        RetryBackoff {
+            retrier: self.clone(),
+            failures: 0,
+        }
+    }
+
+    fn record_success(&self) {
+        self.record_attempt(OUTCOME_SUCCESS);
+    }
+
+    fn record_failure(&self) {
+        self.record_attempt(OUTCOME_FAILURE);
+    }
+
+    fn record_attempt(&self, outcome: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .attempts
+                .with_label_values(&[&self.call_site, outcome])
+                .inc();
+        }
+    }
+
+    fn record_exhausted(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .exhausted
+                .with_label_values(&[&self.call_site])
+                .inc();
+        }
+    }
+}
+
+/// The consecutive failures of an operation retried by a loop, from which
+/// the delays before its next attempts are derived
+#[derive(Debug, Clone)]
+pub struct RetryBackoff {
+    retrier: Retrier,
+    failures: usize,
+}
+
+impl RetryBackoff {
+    /// Records a successful attempt, which resets the delays
+    pub fn succeeded(&mut self) {
+        self.retrier.record_success();
+        self.failures = 0;
+    }
+
+    /// Records a failed attempt, returning how long to wait before the next
+    /// one, or None if the policy gives up on the operation
+    pub fn failed(&mut self) -> Option<Duration> {
+        self.retrier.record_failure();
+        self.failures = self.failures.saturating_add(1);
+        if !self.retrier.policy.should_retry(self.failures) {
+            self.retrier.record_exhausted();
+            return None;
+        }
+        Some(self.retrier.policy.delay(self.failures))
+    }
+
+    /// Number of consecutive failed attempts
+    pub fn failures(&self) -> usize {
+        self.failures
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::ChainCommunicationError;
+    use prometheus::Registry;
+
+    use super::*;
+
+    fn metrics() -> CoreMetrics {
+        CoreMetrics::new("test", 9090, Registry::new()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retrier_counts_attempts_by_call_site() {
+        let metrics = metrics();
+        let retrier = Retrier::new(RetryPolicy::fixed(Duration::ZERO, 2), "test_call", &metrics);
+
+        let res: ChainResult<()> = retrier
+            .call(|| Box::pin(async { Err(ChainCommunicationError::CustomError("err".into())) }))
+            .await;
+        assert!(res.is_err());
+        assert_eq!(retrier.call(|| Box::pin(async { Ok(1) })).await.unwrap(), 1);
+
+        let retry_metrics = metrics.retry_metrics();
+        let attempts = |outcome: &str| {
+            retry_metrics
+                .attempts
+                .with_label_values(&["test_call", outcome])
+                .get()
+        };
+        assert_eq!(attempts(OUTCOME_FAILURE), 2);
+        assert_eq!(attempts(OUTCOME_SUCCESS), 1);
+        assert_eq!(
+            retry_metrics
+                .exhausted
+                .with_label_values(&["test_call"])
+                .get(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_backoff_resets_after_success() {
+        let retrier = Retrier::without_metrics(
+            RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(10), 3),
+            "test_loop",
+        );
+        let mut backoff = retrier.backoff();
+        assert!(backoff.failed().unwrap() <= Duration::from_secs(1));
+        assert!(backoff.failed().unwrap() <= Duration::from_secs(2));
+        assert_eq!(backoff.failed(), None);
+
+        backoff.succeeded();
+        assert_eq!(backoff.failures(), 0);
+        assert!(backoff.failed().is_some());
+    }
+}
@@ -0,0 +1,207 @@
+//! Self-tests of the components of an agent.
+//!
+//! In self-test mode, an agent exercises the components it needs to process
+//! traffic, e.g. its signer and checkpoint syncer, against a synthetic
+//! checkpoint and reports whether each of them works, then exits instead of
+//! running. This catches misconfigured keys and storage permissions before
+//! the agent processes real traffic.
+
+use std::path::PathBuf;
+
+use ethers::{core::rand::thread_rng, signers::LocalWallet};
+use eyre::{bail, eyre, Result};
+use hyperlane_core::{
+    Checkpoint, CheckpointWithMessageId, HyperlaneDomain, HyperlaneSigner, HyperlaneSignerExt,
+    SignedCheckpointWithMessageId, H160, H256,
+};
+use hyperlane_ethereum::Signers;
+use tracing::{error, info};
+
+use crate::{CheckpointSyncer, LocalStorage};
+
+/// The index of the synthetic checkpoint. A merkle tree can't hold enough
+/// messages for a real checkpoint to have it, so writing the synthetic
+/// checkpoint never overwrites a real one.
+pub const SELF_TEST_CHECKPOINT_INDEX: u32 = u32::MAX;
+
+/// The synthetic checkpoint of a chain that components are tested against
+pub fn self_test_checkpoint(domain: &HyperlaneDomain) -> CheckpointWithMessageId {
+    CheckpointWithMessageId {
+        checkpoint: Checkpoint {
+            merkle_tree_hook_address: H256::zero(),
+            mailbox_domain: domain.id(),
+            root: H256::zero(),
+            index: SELF_TEST_CHECKPOINT_INDEX,
+        },
+        message_id: H256::zero(),
+    }
+}
+
+/// Signs the synthetic checkpoint of `domain` and checks that the signature
+/// recovers to the address of the signer
+pub async fn self_test_sign_checkpoint<S: HyperlaneSigner>(
+    signer: &S,
+    domain: &HyperlaneDomain,
+) -> Result<SignedCheckpointWithMessageId> {
+    let signed = signer.sign(self_test_checkpoint(domain)).await?;
+    check_recovered_signer(&signed, signer.eth_address())?;
+    Ok(signed)
+}
+
+/// Writes a signed synthetic checkpoint to a checkpoint syncer
+pub async fn self_test_write_checkpoint(
+    checkpoint_syncer: &dyn CheckpointSyncer,
+    signed: &SignedCheckpointWithMessageId,
+) -> Result<()> {
+    checkpoint_syncer.write_checkpoint(signed).await
+}
+
+/// Fetches a signed synthetic checkpoint from a checkpoint syncer, checking
+/// that it's the one that was written and that it recovers to `signer`
+pub async fn self_test_read_checkpoint(
+    checkpoint_syncer: &dyn CheckpointSyncer,
+    signed: &SignedCheckpointWithMessageId,
+    signer: H160,
+) -> Result<()> {
+    let fetched = checkpoint_syncer
+        .fetch_checkpoint(signed.value.index)
+        .await?
+        .ok_or_else(|| eyre!("The written checkpoint wasn't found"))?;
+    if fetched != *signed {
+        bail!("The fetched checkpoint {fetched:?} isn't the written one {signed:?}");
+    }
+    check_recovered_signer(&fetched, signer)
+}
+
+/// Signs the synthetic checkpoint of `domain` with a throwaway key, writes it
+/// to a checkpoint syncer in a temporary directory, then fetches it and
+/// recovers its signer like relayers do with the checkpoints of validators
+pub async fn self_test_fetch_checkpoint(domain: &HyperlaneDomain) -> Result<()> {
+    let signer = Signers::Local(LocalWallet::new(&mut thread_rng()));
+    let signed = self_test_sign_checkpoint(&signer, domain).await?;
+
+    let path = std::env::temp_dir().join(format!("hyperlane-self-test-{}", std::process::id()));
+    let result = fetch_checkpoint_from(path.clone(), &signed, signer.eth_address()).await;
+    // Best effort, the directory is in the temporary directory anyway
+    let _ = std::fs::remove_dir_all(&path);
+    result
+}
+
+async fn fetch_checkpoint_from(
+    path: PathBuf,
+    signed: &SignedCheckpointWithMessageId,
+    signer: H160,
+) -> Result<()> {
+    let checkpoint_syncer = LocalStorage::new(path, None)?;
+    self_test_write_checkpoint(&checkpoint_syncer, signed).await?;
+    self_test_read_checkpoint(&checkpoint_syncer, signed, signer).await
+}
+
+fn check_recovered_signer(signed: &SignedCheckpointWithMessageId, signer: H160) -> Result<()> {
+    let recovered = signed.recover()?;
+    if recovered != signer {
+        bail!(
+            "The checkpoint signature recovers to {recovered:?} instead of the signer {signer:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Whether each tested component of an agent works
+#[derive(Debug, Default)]
+pub struct SelfTestReport {
+    results: Vec<(String, Result<()>)>,
+}
+
+impl SelfTestReport {
+    /// Records and logs the outcome of testing `component`, returning the
+    /// output of the test if it passed
+    pub fn record<T>(&mut self, component: &str, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(output) => {
+                info!(component, "Self-test passed");
+                self.results.push((component.to_owned(), Ok(())));
+                Some(output)
+            }
+            Err(err) => {
+                error!(component, ?err, "Self-test failed");
+                self.results.push((component.to_owned(), Err(err)));
+                None
+            }
+        }
+    }
+
+    /// Records a component that couldn't be tested because the component it
+    /// depends on failed
+    pub fn skip(&mut self, component: &str, failed_dependency: &str) {
+        self.record::<()>(
+            component,
+            Err(eyre!("Not tested because `{failed_dependency}` failed")),
+        );
+    }
+
+    /// Whether every tested component works
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|(_, result)| result.is_ok())
+    }
+
+    /// An error listing the components that failed, if any. A report without
+    /// any tested component fails too.
+    pub fn into_result(self) -> Result<()> {
+        if self.results.is_empty() {
+            bail!("No components were tested");
+        }
+        let failed: Vec<_> = self
+            .results
+            .into_iter()
+            .filter(|(_, result)| result.is_err())
+            .map(|(component, _)| component)
+            .collect();
+        if !failed.is_empty() {
+            bail!("Self-test failed for {}", failed.join(", "));
+        }
+        info!("Self-test passed for every component");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_checkpoint_round_trip() {
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+        self_test_fetch_checkpoint(&domain).await.unwrap();
+
+        // A checkpoint fetched from a syncer written by another signer fails
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoint_syncer = LocalStorage::new(dir.path().to_path_buf(), None).unwrap();
+        let signer = Signers::Local(LocalWallet::new(&mut thread_rng()));
+        let signed = self_test_sign_checkpoint(&signer, &domain).await.unwrap();
+        self_test_write_checkpoint(&checkpoint_syncer, &signed)
+            .await
+            .unwrap();
+        assert!(
+            self_test_read_checkpoint(&checkpoint_syncer, &signed, H160::zero())
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_report_fails_if_any_component_fails() {
+        let mut report = SelfTestReport::default();
+        assert!(report.passed());
+        assert_eq!(report.record("signer", Ok(1)), Some(1));
+        assert!(report.passed());
+        report.skip("checkpoint_syncer", "signer");
+        assert!(!report.passed());
+        let err = report.into_result().unwrap_err();
+        assert_eq!(err.to_string(), "Self-test failed for checkpoint_syncer");
+
+        assert!(SelfTestReport::default().into_result().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! What an agent is, served on `GET /about` for fleet audits and
+//! compatibility checks: the version and git commit it was built from, the
+//! features it was compiled with, and the domain ids and core contract
+//! addresses of the chains it's configured with.
+//!
+//! The same is exported as the `agent_info` and `chain_info` metrics, which
+//! are always 1 and only carry labels.
+
+use std::collections::HashMap;
+
+use axum::{extract::State, routing::get, Json, Router};
+use eyre::Result;
+use hyperlane_core::H256;
+use serde::Serialize;
+
+use crate::{settings::ChainConf, CoreMetrics};
+
+/// Path of the about route
+pub const ABOUT_API_PATH: &str = "/about";
+
+/// The build and configuration of the agent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentAbout {
+    /// The name of the agent, e.g. `relayer`
+    pub agent: String,
+    /// The version of the agent
+    pub version: String,
+    /// The git commit the agent was built from
+    pub git_sha: String,
+    /// The optional features the agent was compiled with
+    pub features: Vec<String>,
+    /// The configured chains, by name
+    pub chains: Vec<ChainAbout>,
+}
+
+/// A chain the agent is configured with
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainAbout {
+    /// The name of the chain
+    pub name: String,
+    /// The domain id of the chain
+    pub domain_id: u32,
+    /// The protocol of the chain, e.g. `ethereum`
+    pub protocol: String,
+    /// Address of the mailbox
+    pub mailbox: H256,
+    /// Address of the merkle tree hook
+    pub merkle_tree_hook: H256,
+    /// Address of the interchain gas paymaster
+    pub interchain_gas_paymaster: H256,
+    /// Address of the validator announce
+    pub validator_announce: H256,
+}
+
+impl AgentAbout {
+    /// Describes the agent running this build with the `chains`
+    pub fn new(agent: &str, chains: &HashMap<String, ChainConf>) -> Self {
+        let mut chains: Vec<_> = chains
+            .values()
+            .map(|chain| ChainAbout {
+                name: chain.domain.name().to_owned(),
+                domain_id: chain.domain.id(),
+                protocol: chain.domain.domain_protocol().to_string(),
+                mailbox: chain.addresses.mailbox,
+                merkle_tree_hook: chain.addresses.merkle_tree_hook,
+                interchain_gas_paymaster: chain.addresses.interchain_gas_paymaster,
+                validator_announce: chain.addresses.validator_announce,
+            })
+            .collect();
+        chains.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            agent: agent.to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            git_sha: env!("VERGEN_GIT_SHA").to_owned(),
+            features: compiled_features(),
+            chains,
+        }
+    }
+
+    /// Exports the build as the `agent_info` metric and each chain as the
+    /// `chain_info` metric
+    pub fn export_metrics(&self, metrics: &CoreMetrics) -> Result<()> {
+        let features = self.features.join(",");
+        metrics
+            .new_int_gauge(
+                "agent_info",
+                "The build of the agent, always 1",
+                &["version", "git_sha", "features"],
+            )?
+            .with_label_values(&[&self.version, &self.git_sha, &features])
+            .set(1);
+        let chain_info = metrics.new_int_gauge(
+            "chain_info",
+            "The domain id and core contract addresses of a configured chain, always 1",
+            &[
+                "chain",
+                "domain_id",
+                "protocol",
+                "mailbox",
+                "merkle_tree_hook",
+            ],
+        )?;
+        for chain in &self.chains {
+            chain_info
+                .with_label_values(&[
+                    &chain.name,
+                    &chain.domain_id.to_string(),
+                    &chain.protocol,
+                    &format!("{:?}", chain.mailbox),
+                    &format!("{:?}", chain.merkle_tree_hook),
+                ])
+                .set(1);
+        }
+        Ok(())
+    }
+}
+
+/// The optional features of this crate that are enabled
+fn compiled_features() -> Vec<String> {
+    [
+        ("oneline-errors", cfg!(feature = "oneline-errors")),
+        ("color-eyre", cfg!(feature = "color-eyre")),
+        ("profiling", cfg!(feature = "profiling")),
+        ("jemalloc", cfg!(feature = "jemalloc")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_owned())
+    .collect()
+}
+
+/// The about route
+pub fn routes(about: AgentAbout) -> Router {
+    Router::new()
+        .route(ABOUT_API_PATH, get(about_agent))
+        .with_state(about)
+}
+
+async fn about_agent(State(about): State<AgentAbout>) -> Json<AgentAbout> {
+    Json(about)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_about_route_serves_the_chains() {
+        let about = AgentAbout {
+            agent: "relayer".into(),
+            version: "1.0.0".into(),
+            git_sha: "abc".into(),
+            features: vec!["profiling".into()],
+            chains: vec![ChainAbout {
+                name: "ethereum".into(),
+                domain_id: 1,
+                protocol: "ethereum".into(),
+                mailbox: H256::from_low_u64_be(1),
+                merkle_tree_hook: H256::from_low_u64_be(2),
+                interchain_gas_paymaster: H256::from_low_u64_be(3),
+                validator_announce: H256::from_low_u64_be(4),
+            }],
+        };
+        let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap())
+            .serve(routes(about).into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let body = reqwest::get(format!("http://{addr}{ABOUT_API_PATH}"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["gitSha"], "abc");
+        assert_eq!(body["chains"][0]["domainId"], 1);
+        assert_eq!(
+            body["chains"][0]["mailbox"],
+            format!("{:?}", H256::from_low_u64_be(1))
+        );
+    }
+}
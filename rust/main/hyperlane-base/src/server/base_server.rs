@@ -1,7 +1,13 @@
+use super::{
+    about::{self, AgentAbout},
+    db_backup::DbBackupApi,
+    health,
+    profiling::{self, PROFILING_API_BASE},
+};
 use crate::CoreMetrics;
 use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
 use derive_new::new;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use tokio::task::JoinHandle;
 
 /// A server that serves agent-specific routes
@@ -9,9 +15,37 @@ use tokio::task::JoinHandle;
 pub struct Server {
     listen_port: u16,
     core_metrics: Arc<CoreMetrics>,
+    /// Whether the profiling routes are served
+    #[new(default)]
+    profiling: bool,
+    /// What's served on `/about`, if anything
+    #[new(default)]
+    about: Option<AgentAbout>,
+    /// Where the database is backed up to on `/db/backup`, if anywhere
+    #[new(default)]
+    db_backup_dir: Option<PathBuf>,
 }
 
 impl Server {
+    /// Serve the profiling routes under `/debug/pprof`
+    pub fn with_profiling(mut self, profiling: bool) -> Self {
+        self.profiling = profiling;
+        self
+    }
+
+    /// Serve the build and configured chains of the agent under `/about`
+    pub fn with_about(mut self, about: AgentAbout) -> Self {
+        self.about = Some(about);
+        self
+    }
+
+    /// Serve backups of the agent's database to `db_backup_dir` under
+    /// `/db/backup`, if set and the agent has a database
+    pub fn with_db_backups(mut self, db_backup_dir: Option<PathBuf>) -> Self {
+        self.db_backup_dir = db_backup_dir;
+        self
+    }
+
     /// Run an HTTP server
     pub fn run(self: Arc<Self>) -> JoinHandle<()> {
         self.run_with_custom_routes(vec![])
@@ -22,6 +56,10 @@ impl Server {
     /// routes:
     ///  - metrics - serving OpenMetrics format reports on `/metrics`
     ///     (this is compatible with Prometheus, which ought to be configured to scrape this endpoint)
+    ///  - health - serving the aggregated health of the agent on `/health` and `/ready`
+    ///  - about - serving the build and configured chains of the agent on `/about`, if set
+    ///  - db backup - backing up the agent's database on `/db/backup`, if enabled
+    ///  - profiling - serving CPU profiles and heap stats on `/debug/pprof`, if enabled
     ///  - custom_routes - additional routes to be served by the server as per the specific agent
     pub fn run_with_custom_routes(
         self: Arc<Self>,
@@ -32,10 +70,28 @@ impl Server {
 
         let core_metrics_clone = self.core_metrics.clone();
 
-        let mut app = Router::new().route(
-            "/metrics",
-            get(move || Self::gather_metrics(core_metrics_clone)),
-        );
+        let mut app = Router::new()
+            .route(
+                "/metrics",
+                get(move || Self::gather_metrics(core_metrics_clone)),
+            )
+            .merge(health::routes(self.core_metrics.health()));
+
+        if let Some(about) = self.about.clone() {
+            app = app.merge(about::routes(about));
+        }
+
+        if let (Some(backup_dir), Some(db)) =
+            (self.db_backup_dir.clone(), self.core_metrics.health().db())
+        {
+            let (route, router) = DbBackupApi::new(db, backup_dir).get_route();
+            app = app.nest(route, router);
+        }
+
+        if self.profiling {
+            tracing::info!("serving profiling routes on {PROFILING_API_BASE}");
+            app = app.nest(PROFILING_API_BASE, profiling::routes());
+        }
 
         for (route, router) in custom_routes {
             app = app.nest(route, router);
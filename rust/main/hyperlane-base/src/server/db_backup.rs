@@ -0,0 +1,117 @@
+//! Backups of the agent's database, taken while it runs, so that operators
+//! can move an agent to another host without indexing its history again.
+//!
+//! `POST /db/backup` writes a snapshot of the database to a new directory
+//! named after the current unix time in milliseconds and a counter in the
+//! configured backup directory, and responds with its path. The agent on the new host restores it on
+//! startup with `restoreDbFrom`.
+
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{extract::State, http::StatusCode, routing, Json, Router};
+use derive_new::new;
+use serde::Serialize;
+
+use crate::db::DB;
+
+/// Path the backup routes are nested under
+pub const DB_BACKUP_API_BASE: &str = "/db";
+
+/// Backups taken by this process, so that backups taken in the same
+/// millisecond get different directories
+static BACKUP_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// Backs up the database to a directory
+#[derive(new, Clone)]
+pub struct DbBackupApi {
+    db: DB,
+    backup_dir: PathBuf,
+}
+
+/// A backup that was written
+#[derive(Debug, Clone, Serialize)]
+pub struct DbBackup {
+    /// Where the backup was written
+    pub path: PathBuf,
+}
+
+async fn backup(State(api): State<DbBackupApi>) -> Result<Json<DbBackup>, (StatusCode, String)> {
+    let path = api.new_backup_path();
+    let backup_path = path.clone();
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&api.backup_dir)?;
+        api.db.backup(&backup_path)
+    })
+    .await
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+    Ok(Json(DbBackup { path }))
+}
+
+impl DbBackupApi {
+    fn new_backup_path(&self) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let count = BACKUP_COUNT.fetch_add(1, Ordering::Relaxed);
+        self.backup_dir.join(format!("{timestamp}-{count}"))
+    }
+
+    /// The backup route
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/backup", routing::post(backup))
+            .with_state(self.clone())
+    }
+
+    /// The backup route and the path it's nested under
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DB_BACKUP_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_backup_route_writes_a_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DB::from_path(&dir.path().join("db")).unwrap();
+        db.store(b"key", b"value").unwrap();
+
+        let (path, router) = DbBackupApi::new(db, dir.path().join("backups")).get_route();
+        let app = Router::new().nest(path, router);
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let mut backup_paths = vec![];
+        // Backups taken right after each other don't collide
+        for _ in 0..2 {
+            let response = reqwest::Client::new()
+                .post(format!("http://{addr}{path}/backup"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value =
+                serde_json::from_str(&response.text().await.unwrap()).unwrap();
+            backup_paths.push(PathBuf::from(body["path"].as_str().unwrap()));
+        }
+        assert_ne!(backup_paths[0], backup_paths[1]);
+        let backup_path = &backup_paths[0];
+        assert!(backup_path.starts_with(dir.path().join("backups")));
+
+        let restored_path = dir.path().join("restored");
+        DB::restore_backup(backup_path, &restored_path).unwrap();
+        let restored = DB::from_path(&restored_path).unwrap();
+        assert_eq!(restored.retrieve(b"key").unwrap(), Some(b"value".to_vec()));
+    }
+}
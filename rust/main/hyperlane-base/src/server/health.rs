@@ -0,0 +1,469 @@
+//! Health and readiness of the agent, served next to the metrics for
+//! Kubernetes probes.
+//!
+//! Components report their state to the [`AgentHealth`] of the agent as they
+//! run, and the routes aggregate it into a JSON report of:
+//! - the latest block height fetched from each chain, and the last error if
+//!   fetching it failed
+//! - the address of the signer of each chain, if one is configured
+//! - how far each index lags behind the tip it's syncing to
+//! - the components of each chain that are still starting, e.g. contracts
+//!   built in the background while the chain is unreachable
+//! - whether a value can be written to the agent's database, if it has one,
+//!   checked at most once per minute
+//!
+//! `GET /health` fails with a 503 only if the database can't be written,
+//! which restarting the agent may fix. `GET /ready` also fails while a chain
+//! is unreachable or still starting, or an index lags too far behind the tip.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use serde::Serialize;
+
+use crate::db::DB;
+
+/// Path of the liveness route
+pub const HEALTH_API_PATH: &str = "/health";
+/// Path of the readiness route
+pub const READY_API_PATH: &str = "/ready";
+
+/// Key the database check writes to
+const DB_CHECK_KEY: &[u8] = b"agent_health_check";
+/// The database is written to at most once per interval, however often the
+/// agent is probed
+const DB_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// A chain whose block height wasn't fetched for this long is unreachable
+const CHAIN_STALE_AFTER: Duration = Duration::from_secs(5 * 60);
+/// An index that lags further behind the tip than this isn't ready
+const MAX_SYNC_LAG: Duration = Duration::from_secs(10 * 60);
+/// The maximum lag in blocks of an index on a chain with an unknown block
+/// time
+const MAX_SYNC_LAG_BLOCKS: u64 = 1000;
+
+/// The health of the components of an agent, as reported by them
+#[derive(Debug, Default)]
+pub struct AgentHealth {
+    state: Mutex<HealthState>,
+    db: OnceLock<DB>,
+    /// The last database check, and when it ran
+    last_db_check: Mutex<Option<(Instant, CheckResult)>>,
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    chains: BTreeMap<String, ChainState>,
+}
+
+#[derive(Debug, Default)]
+struct ChainState {
+    block_time: Option<Duration>,
+    signer: Option<String>,
+    block_height: Option<u64>,
+    last_success: Option<Instant>,
+    last_error: Option<String>,
+    /// Latest block indexed by each forward cursor, by event type
+    indexed_blocks: BTreeMap<String, IndexedBlock>,
+    /// Components that didn't finish starting
+    starting: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IndexedBlock {
+    block: u64,
+    /// The tip the cursor is syncing to, which may trail the latest block of
+    /// the chain, e.g. by its reorg period
+    tip: u64,
+}
+
+/// The aggregated health of an agent, served as JSON
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    /// Whether the agent is alive, i.e. its database can be written
+    pub healthy: bool,
+    /// Whether the agent is fully operational
+    pub ready: bool,
+    /// The database check, if the agent has a database
+    pub db: Option<CheckResult>,
+    /// The health of each chain, by name
+    pub chains: BTreeMap<String, ChainHealth>,
+}
+
+/// The outcome of a check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    /// Whether the check passed
+    pub ok: bool,
+    /// Why the check failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The health of a chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainHealth {
+    /// Whether the chain was reached recently, if it was checked, and all
+    /// its indexes are synced
+    pub ok: bool,
+    /// The latest block height fetched from the chain
+    pub block_height: Option<u64>,
+    /// Seconds since the chain was last reached
+    pub seconds_since_update: Option<u64>,
+    /// The last error reaching the chain, if the last attempt failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The address of the signer of the chain, if one is configured
+    pub signer: Option<String>,
+    /// The sync status of each index of the chain, by event type
+    pub sync: BTreeMap<String, SyncHealth>,
+    /// The components of the chain that are still starting
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub starting: BTreeSet<String>,
+}
+
+/// The sync status of an index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncHealth {
+    /// Whether the index is close enough to the tip
+    pub ok: bool,
+    /// The latest block indexed
+    pub indexed_block: u64,
+    /// The tip the index is syncing to
+    pub tip: u64,
+    /// Number of blocks between the tip and the latest block indexed
+    pub lag_blocks: u64,
+}
+
+impl AgentHealth {
+    /// Checks that the database can be written as part of the health of the
+    /// agent
+    pub fn set_db(&self, db: DB) {
+        let _ = self.db.set(db);
+    }
+
+    /// The database of the agent, if it has one
+    pub fn db(&self) -> Option<DB> {
+        self.db.get().cloned()
+    }
+
+    /// Registers a chain the agent uses, with its estimated block time and
+    /// the address of its signer, if any
+    pub fn register_chain(
+        &self,
+        chain: &str,
+        block_time: Option<Duration>,
+        signer: Option<String>,
+    ) {
+        let mut state = self.state.lock().expect("health state lock poisoned");
+        let chain = state.chains.entry(chain.to_owned()).or_default();
+        chain.block_time = block_time;
+        chain.signer = signer;
+    }
+
+    /// Records that the chain was reached, with its latest block height if
+    /// known
+    pub fn report_chain_reached(&self, chain: &str, block_height: Option<u64>) {
+        self.report_chain_reached_at(chain, block_height, Instant::now())
+    }
+
+    /// Records that the chain couldn't be reached
+    pub fn report_chain_error(&self, chain: &str, err: &impl Display) {
+        let mut state = self.state.lock().expect("health state lock poisoned");
+        state.chains.entry(chain.to_owned()).or_default().last_error = Some(err.to_string());
+    }
+
+    /// Records the latest block indexed by a forward cursor, and the tip it
+    /// is syncing to
+    pub(crate) fn report_indexed_block(&self, chain: &str, event_type: &str, block: u64, tip: u64) {
+        let mut state = self.state.lock().expect("health state lock poisoned");
+        state
+            .chains
+            .entry(chain.to_owned())
+            .or_default()
+            .indexed_blocks
+            .insert(event_type.to_owned(), IndexedBlock { block, tip });
+    }
+
+    /// Records that a component of the chain is starting, e.g. a contract
+    /// built in the background. The chain isn't ready until it started.
+    /// Returns the number of components of the chain still starting.
+    pub fn report_component_starting(&self, chain: &str, component: &str) -> usize {
+        let mut state = self.state.lock().expect("health state lock poisoned");
+        let starting = &mut state.chains.entry(chain.to_owned()).or_default().starting;
+        starting.insert(component.to_owned());
+        starting.len()
+    }
+
+    /// Records that a component of the chain started. Returns the number of
+    /// components of the chain still starting.
+    pub fn report_component_started(&self, chain: &str, component: &str) -> usize {
+        let mut state = self.state.lock().expect("health state lock poisoned");
+        let starting = &mut state.chains.entry(chain.to_owned()).or_default().starting;
+        starting.remove(component);
+        starting.len()
+    }
+
+    /// Aggregates the health of the components
+    pub fn report(&self) -> HealthReport {
+        self.report_at(Instant::now())
+    }
+
+    fn report_chain_reached_at(&self, chain: &str, block_height: Option<u64>, now: Instant) {
+        let mut state = self.state.lock().expect("health state lock poisoned");
+        let chain = state.chains.entry(chain.to_owned()).or_default();
+        chain.block_height = block_height.or(chain.block_height);
+        chain.last_success = Some(now);
+        chain.last_error = None;
+    }
+
+    fn report_at(&self, now: Instant) -> HealthReport {
+        let db = self.db.get().map(|db| self.check_db_at(db, now));
+        let chains: BTreeMap<_, _> = self
+            .state
+            .lock()
+            .expect("health state lock poisoned")
+            .chains
+            .iter()
+            .map(|(name, chain)| (name.clone(), chain.health(now)))
+            .collect();
+        let healthy = db.as_ref().map_or(true, |db| db.ok);
+        HealthReport {
+            healthy,
+            ready: healthy && chains.values().all(|chain| chain.ok),
+            db,
+            chains,
+        }
+    }
+
+    /// Checks the database, or returns the last check if it ran less than
+    /// `DB_CHECK_INTERVAL` ago
+    fn check_db_at(&self, db: &DB, now: Instant) -> CheckResult {
+        let mut last_db_check = self.last_db_check.lock().expect("db check lock poisoned");
+        if let Some((checked_at, result)) = last_db_check.as_ref() {
+            if now.saturating_duration_since(*checked_at) < DB_CHECK_INTERVAL {
+                return result.clone();
+            }
+        }
+        let result = check_db(db);
+        *last_db_check = Some((now, result.clone()));
+        result
+    }
+}
+
+impl ChainState {
+    fn health(&self, now: Instant) -> ChainHealth {
+        let since_update = self
+            .last_success
+            .map(|last_success| now.saturating_duration_since(last_success));
+        let max_lag_blocks = self
+            .block_time
+            .filter(|block_time| !block_time.is_zero())
+            .map_or(MAX_SYNC_LAG_BLOCKS, |block_time| {
+                (MAX_SYNC_LAG.as_secs_f64() / block_time.as_secs_f64()) as u64
+            });
+        let sync: BTreeMap<_, _> = self
+            .indexed_blocks
+            .iter()
+            .map(|(event_type, indexed)| {
+                let lag_blocks = indexed.tip.saturating_sub(indexed.block);
+                let sync = SyncHealth {
+                    ok: lag_blocks <= max_lag_blocks,
+                    indexed_block: indexed.block,
+                    tip: indexed.tip,
+                    lag_blocks,
+                };
+                (event_type.clone(), sync)
+            })
+            .collect();
+        ChainHealth {
+            ok: self.reachable(since_update)
+                && sync.values().all(|sync| sync.ok)
+                && self.starting.is_empty(),
+            block_height: self.block_height,
+            seconds_since_update: since_update.map(|since_update| since_update.as_secs()),
+            error: self.last_error.clone(),
+            signer: self.signer.clone(),
+            sync,
+            starting: self.starting.clone(),
+        }
+    }
+
+    /// Chains whose block height isn't checked, e.g. because their domain is
+    /// unknown, are assumed to be reachable
+    fn reachable(&self, since_update: Option<Duration>) -> bool {
+        match since_update {
+            Some(since_update) => since_update < CHAIN_STALE_AFTER,
+            None => self.last_error.is_none(),
+        }
+    }
+}
+
+fn check_db(db: &DB) -> CheckResult {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    match db.store(DB_CHECK_KEY, &now.to_be_bytes()) {
+        Ok(()) => CheckResult {
+            ok: true,
+            error: None,
+        },
+        Err(err) => CheckResult {
+            ok: false,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+/// The health and readiness routes
+pub fn routes(health: Arc<AgentHealth>) -> Router {
+    Router::new()
+        .route(HEALTH_API_PATH, get(health_check))
+        .route(READY_API_PATH, get(ready_check))
+        .with_state(health)
+}
+
+async fn health_check(State(health): State<Arc<AgentHealth>>) -> (StatusCode, Json<HealthReport>) {
+    let report = health.report();
+    (status_code(report.healthy), Json(report))
+}
+
+async fn ready_check(State(health): State<Arc<AgentHealth>>) -> (StatusCode, Json<HealthReport>) {
+    let report = health.report();
+    (status_code(report.ready), Json(report))
+}
+
+fn status_code(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db::test_utils;
+
+    use super::*;
+
+    #[test]
+    fn test_readiness_follows_chains_and_sync_lag() {
+        let health = AgentHealth::default();
+        let start = Instant::now();
+        health.register_chain(
+            "ethereum",
+            Some(Duration::from_secs(12)),
+            Some("0x1".into()),
+        );
+        health.register_chain("unknown", None, None);
+
+        health.report_chain_reached_at("ethereum", Some(1_000), start);
+        health.report_chain_reached_at("unknown", None, start);
+        // The cursor syncs to a tip behind the latest block, e.g. because of
+        // the reorg period, which doesn't count as lag
+        health.report_indexed_block("ethereum", "dispatched_messages", 890, 900);
+        let report = health.report_at(start);
+        assert!(report.ready);
+        assert_eq!(
+            report.chains["ethereum"].sync["dispatched_messages"],
+            SyncHealth {
+                ok: true,
+                indexed_block: 890,
+                tip: 900,
+                lag_blocks: 10,
+            }
+        );
+
+        // 10 minutes of 12 second blocks is 50 blocks
+        health.report_indexed_block("ethereum", "dispatched_messages", 849, 900);
+        assert!(!health.report_at(start).ready);
+        health.report_indexed_block("ethereum", "dispatched_messages", 850, 900);
+        assert!(health.report_at(start).ready);
+
+        // A chain that stops responding is no longer ready, but the agent is
+        // still healthy
+        health.report_chain_error("unknown", &"connection refused");
+        let report = health.report_at(start + CHAIN_STALE_AFTER);
+        assert!(report.healthy);
+        assert!(!report.ready);
+        assert_eq!(
+            report.chains["unknown"].error.as_deref(),
+            Some("connection refused")
+        );
+    }
+
+    #[test]
+    fn test_chains_are_not_ready_until_started() {
+        let health = AgentHealth::default();
+        let start = Instant::now();
+        health.report_chain_reached_at("ethereum", Some(1_000), start);
+        assert_eq!(health.report_component_starting("ethereum", "mailbox"), 1);
+        assert_eq!(
+            health.report_component_starting("ethereum", "validator_announce"),
+            2
+        );
+
+        let report = health.report_at(start);
+        assert!(report.healthy);
+        assert!(!report.ready);
+        assert_eq!(report.chains["ethereum"].starting.len(), 2);
+
+        assert_eq!(health.report_component_started("ethereum", "mailbox"), 1);
+        assert!(!health.report_at(start).ready);
+        assert_eq!(
+            health.report_component_started("ethereum", "validator_announce"),
+            0
+        );
+        assert!(health.report_at(start).ready);
+    }
+
+    #[tokio::test]
+    async fn test_health_checks_db() {
+        test_utils::run_test_db(|db| async move {
+            let health = AgentHealth::default();
+            assert_eq!(health.report().db, None);
+            health.set_db(db.clone());
+            assert_eq!(
+                health.report().db,
+                Some(CheckResult {
+                    ok: true,
+                    error: None,
+                })
+            );
+            assert!(db.retrieve(DB_CHECK_KEY).unwrap().is_some());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_db_is_not_written_on_every_probe() {
+        test_utils::run_test_db(|db| async move {
+            let health = AgentHealth::default();
+            health.set_db(db.clone());
+            let start = Instant::now();
+            assert!(health.report_at(start).healthy);
+
+            // Probes within the interval reuse the last check
+            db.store(DB_CHECK_KEY, b"marker").unwrap();
+            assert!(health.report_at(start + DB_CHECK_INTERVAL / 2).healthy);
+            assert_eq!(
+                db.retrieve(DB_CHECK_KEY).unwrap().as_deref(),
+                Some(&b"marker"[..])
+            );
+
+            // The database is checked again once the interval passed
+            assert!(health.report_at(start + DB_CHECK_INTERVAL).healthy);
+            assert_ne!(
+                db.retrieve(DB_CHECK_KEY).unwrap().as_deref(),
+                Some(&b"marker"[..])
+            );
+        })
+        .await;
+    }
+}
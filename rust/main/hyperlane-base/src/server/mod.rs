@@ -1,2 +1,16 @@
 mod base_server;
 pub use base_server::Server;
+
+/// Route describing the build and configured chains of the agent
+pub mod about;
+pub use about::AgentAbout;
+
+/// Route backing up the database of the agent
+pub mod db_backup;
+
+/// Health and readiness routes served by the agent server
+pub mod health;
+pub use health::AgentHealth;
+
+/// Profiling routes served by the agent server
+pub mod profiling;
@@ -0,0 +1,158 @@
+//! Profiling routes, served next to the metrics when enabled in the config.
+//!
+//! - `GET /debug/pprof/profile?seconds=30&frequency=99` samples the CPU for
+//!   the given duration and responds with a pprof protobuf profile, which can
+//!   be opened with `go tool pprof`. Requires the `profiling` feature.
+//! - `GET /debug/pprof/heap` responds with jemalloc heap stats in JSON.
+//!   Requires the `jemalloc` feature, and the agent to use jemalloc as its
+//!   global allocator.
+
+use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
+#[cfg(feature = "profiling")]
+use serde::Deserialize;
+
+/// Path the profiling routes are nested under
+pub const PROFILING_API_BASE: &str = "/debug/pprof";
+
+/// Duration of a CPU profile if none is requested
+#[cfg(feature = "profiling")]
+const DEFAULT_PROFILE_SECONDS: u64 = 30;
+/// Longest CPU profile that can be requested
+#[cfg(feature = "profiling")]
+const MAX_PROFILE_SECONDS: u64 = 300;
+/// Sampling frequency of a CPU profile if none is requested, in Hz
+#[cfg(feature = "profiling")]
+const DEFAULT_PROFILE_FREQUENCY: i32 = 99;
+
+#[cfg(feature = "profiling")]
+#[derive(Debug, Deserialize)]
+struct ProfileRequest {
+    seconds: Option<u64>,
+    frequency: Option<i32>,
+}
+
+/// The profiling routes
+pub fn routes() -> Router {
+    Router::new()
+        .route("/profile", get(cpu_profile))
+        .route("/heap", get(heap_stats))
+}
+
+#[cfg(feature = "profiling")]
+async fn cpu_profile(
+    axum::extract::Query(request): axum::extract::Query<ProfileRequest>,
+) -> impl IntoResponse {
+    use pprof::protos::Message;
+
+    let seconds = request
+        .seconds
+        .unwrap_or(DEFAULT_PROFILE_SECONDS)
+        .min(MAX_PROFILE_SECONDS);
+    let frequency = request.frequency.unwrap_or(DEFAULT_PROFILE_FREQUENCY);
+    tracing::info!(seconds, frequency, "Starting CPU profile");
+
+    let guard = match pprof::ProfilerGuardBuilder::default()
+        .frequency(frequency)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+    {
+        Ok(guard) => guard,
+        // Only one profile can be taken at a time
+        Err(pprof::Error::Running) => {
+            return (
+                StatusCode::CONFLICT,
+                "A CPU profile is already being taken"
+                    .to_owned()
+                    .into_bytes(),
+            )
+        }
+        Err(err) => return internal_error(err),
+    };
+    tokio::time::sleep(std::time::Duration::from_secs(seconds)).await;
+
+    match guard.report().build().and_then(|report| report.pprof()) {
+        Ok(profile) => (StatusCode::OK, profile.encode_to_vec()),
+        Err(err) => internal_error(err),
+    }
+}
+
+#[cfg(not(feature = "profiling"))]
+async fn cpu_profile() -> impl IntoResponse {
+    (
+        StatusCode::NOT_IMPLEMENTED,
+        "CPU profiles require the agent to be built with the `profiling` feature",
+    )
+}
+
+#[cfg(feature = "jemalloc")]
+async fn heap_stats() -> impl IntoResponse {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    // Stats are cached by jemalloc until the epoch is advanced
+    let stats = epoch::advance().and_then(|_| {
+        Ok(serde_json::json!({
+            "allocated": stats::allocated::read()?,
+            "active": stats::active::read()?,
+            "resident": stats::resident::read()?,
+            "mapped": stats::mapped::read()?,
+            "retained": stats::retained::read()?,
+            "metadata": stats::metadata::read()?,
+        }))
+    });
+    match stats {
+        Ok(stats) => (StatusCode::OK, stats.to_string().into_bytes()),
+        Err(err) => internal_error(err),
+    }
+}
+
+#[cfg(not(feature = "jemalloc"))]
+async fn heap_stats() -> impl IntoResponse {
+    (
+        StatusCode::NOT_IMPLEMENTED,
+        "Heap stats require the agent to be built with the `jemalloc` feature",
+    )
+}
+
+#[cfg(any(feature = "profiling", feature = "jemalloc"))]
+fn internal_error(err: impl std::fmt::Display) -> (StatusCode, Vec<u8>) {
+    tracing::warn!(%err, "Failed to profile");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        err.to_string().into_bytes(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_profiling_routes() {
+        let app = Router::new().nest(PROFILING_API_BASE, routes());
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        let client = reqwest::Client::new();
+        let heap = client
+            .get(format!("http://{}{}/heap", addr, PROFILING_API_BASE))
+            .send()
+            .await
+            .unwrap();
+        #[cfg(not(feature = "jemalloc"))]
+        assert_eq!(heap.status(), StatusCode::NOT_IMPLEMENTED);
+        #[cfg(feature = "jemalloc")]
+        assert!(heap.status().is_success());
+
+        #[cfg(not(feature = "profiling"))]
+        {
+            let profile = client
+                .get(format!("http://{}{}/profile", addr, PROFILING_API_BASE))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(profile.status(), StatusCode::NOT_IMPLEMENTED);
+        }
+    }
+}
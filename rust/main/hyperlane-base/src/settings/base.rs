@@ -1,7 +1,13 @@
-use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use eyre::{eyre, Context, Result};
-use futures_util::future::join_all;
+use futures_util::future::{join_all, try_join_all};
 
 use hyperlane_core::{
     HyperlaneDomain, HyperlaneLogStore, HyperlaneProvider,
@@ -9,9 +15,12 @@ use hyperlane_core::{
     Mailbox, MerkleTreeHook, MultisigIsm, SequenceAwareIndexer, ValidatorAnnounce, H256,
 };
 use hyperlane_operation_verifier::ApplicationOperationVerifier;
+use tracing::warn;
 
 use crate::{
     cursors::{CursorType, Indexable},
+    db::DB,
+    server::AgentAbout,
     settings::{chains::ChainConf, trace::TracingConfig},
     ContractSync, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
     SequenceAwareLogStore, SequencedDataContractSync, Server, WatermarkContractSync,
@@ -50,8 +59,28 @@ pub struct Settings {
     pub chains: HashMap<String, ChainConf>,
     /// Port to listen for prometheus scrape requests
     pub metrics_port: u16,
+    /// Whether selected counters are persisted in the agent DB so that they
+    /// survive restarts. Only applies to agents with a local DB.
+    pub persistent_metrics: bool,
+    /// Whether CPU profiles and heap stats are served on the metrics port
+    pub enable_profiling: bool,
+    /// If set, the database can be backed up to this directory through the
+    /// metrics port. Only applies to agents with a local DB.
+    pub db_backup_dir: Option<PathBuf>,
+    /// A backup the database is restored from on startup if it doesn't
+    /// exist yet. Only applies to agents with a local DB.
+    pub restore_db_from: Option<PathBuf>,
     /// The tracing configuration
     pub tracing: TracingConfig,
+    /// If true, the agent tests its components against synthetic data and
+    /// exits instead of running
+    pub self_test: bool,
+    /// Whether the agent verifies at startup that the core contracts of its
+    /// chains are deployed at the configured addresses
+    pub verify_deployments: bool,
+    /// Bucket bounds overriding the default ones of histograms, by metric
+    /// name without the namespace prefix, in flat case
+    pub histogram_buckets: HashMap<String, Vec<f64>>,
 }
 
 impl Settings {
@@ -91,18 +120,57 @@ impl Settings {
             .map(|c| c.domain.clone())
     }
 
+    /// Verify that the core contracts configured for each of the domains are
+    /// deployed at their addresses, unless disabled
+    pub async fn verify_contract_deployments(
+        &self,
+        domains: impl Iterator<Item = &HyperlaneDomain>,
+        metrics: &CoreMetrics,
+    ) -> Result<()> {
+        if !self.verify_deployments {
+            return Ok(());
+        }
+        try_join_all(domains.map(|domain| async move {
+            self.chain_setup(domain)?.verify_deployment(metrics).await
+        }))
+        .await?;
+        Ok(())
+    }
+
     /// Create the core metrics from the settings given the name of the agent.
     pub fn metrics(&self, name: &str) -> Result<Arc<CoreMetrics>> {
-        Ok(Arc::new(CoreMetrics::new(
-            name,
-            self.metrics_port,
-            prometheus::Registry::new(),
-        )?))
+        Ok(Arc::new(
+            CoreMetrics::new(name, self.metrics_port, prometheus::Registry::new())?
+                .with_histogram_buckets(self.histogram_buckets.clone()),
+        ))
     }
 
     /// Create the server from the settings given the name of the agent.
     pub fn server(&self, core_metrics: Arc<CoreMetrics>) -> Result<Arc<Server>> {
-        Ok(Arc::new(Server::new(self.metrics_port, core_metrics)))
+        let about = AgentAbout::new(core_metrics.agent_name(), &self.chains);
+        about.export_metrics(&core_metrics)?;
+        Ok(Arc::new(
+            Server::new(self.metrics_port, core_metrics)
+                .with_profiling(self.enable_profiling)
+                .with_about(about)
+                .with_db_backups(self.db_backup_dir.clone()),
+        ))
+    }
+
+    /// Open the agent's database at `db_path`, restoring it from the
+    /// configured backup first if it doesn't exist yet
+    pub fn open_db(&self, db_path: &Path) -> Result<DB> {
+        if let Some(backup_path) = &self.restore_db_from {
+            if db_path.exists() {
+                warn!(
+                    path=%db_path.to_string_lossy(),
+                    "Not restoring the db from the backup, since it already exists"
+                );
+            } else {
+                DB::restore_backup(backup_path, db_path)?;
+            }
+        }
+        Ok(DB::from_path(db_path)?)
     }
 
     /// Private to preserve linearity of AgentCore::from_settings -- creating an
@@ -111,7 +179,14 @@ impl Settings {
         Self {
             chains: self.chains.clone(),
             metrics_port: self.metrics_port,
+            persistent_metrics: self.persistent_metrics,
+            enable_profiling: self.enable_profiling,
+            db_backup_dir: self.db_backup_dir.clone(),
+            restore_db_from: self.restore_db_from.clone(),
             tracing: self.tracing.clone(),
+            self_test: self.self_test,
+            verify_deployments: self.verify_deployments,
+            histogram_buckets: self.histogram_buckets.clone(),
         }
     }
 }
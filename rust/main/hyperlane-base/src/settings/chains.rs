@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use axum::async_trait;
 use ethers::prelude::Selector;
@@ -6,13 +6,16 @@ use eyre::{eyre, Context, Report, Result};
 
 use ethers_prometheus::middleware::{ContractInfo, PrometheusMiddlewareConf};
 use hyperlane_core::{
-    config::OperationBatchConfig, AggregationIsm, CcipReadIsm, ContractLocator, HyperlaneAbi,
-    HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider, IndexMode,
-    InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
-    MerkleTreeHook, MerkleTreeInsertion, MultisigIsm, ReorgPeriod, RoutingIsm,
-    SequenceAwareIndexer, ValidatorAnnounce, H256,
+    config::OperationBatchConfig, AggregationIsm, CcipReadIsm, CheckpointAttestation,
+    ContractLocator, HashAlgorithm, HyperlaneAbi, HyperlaneDomain, HyperlaneDomainProtocol,
+    HyperlaneMessage, HyperlaneProvider, IndexMode, InterchainGasPaymaster, InterchainGasPayment,
+    InterchainSecurityModule, Mailbox, MailboxConfigChange, MerkleTreeHook, MerkleTreeInsertion,
+    ModuleType, MultisigIsm, NativeBridgeIsm, ReorgPeriod, RoutingIsm, SequenceAwareIndexer,
+    ValidatorAnnounce, H256,
 };
 use hyperlane_operation_verifier::ApplicationOperationVerifier;
+use tracing::warn;
+use url::Url;
 
 use hyperlane_cosmos as h_cosmos;
 use hyperlane_ethereum::{
@@ -53,6 +56,10 @@ pub struct ChainConf {
     pub domain: HyperlaneDomain,
     /// Signer configuration for this chain
     pub signer: Option<SignerConf>,
+    /// Signers that transactions are spread across along with `signer`, so
+    /// they can be signed and submitted in parallel. Only used by Sealevel
+    /// mailboxes, where the signer of a transaction also pays for it.
+    pub additional_signers: Vec<SignerConf>,
     /// The reorg period of the chain, i.e. the number of blocks until finality
     pub reorg_period: ReorgPeriod,
     /// Addresses of contracts on the chain
@@ -65,6 +72,10 @@ pub struct ChainConf {
     pub metrics_conf: PrometheusMiddlewareConf,
     /// Settings for event indexing
     pub index: IndexSettings,
+    /// The hash function the chain's contracts compute checkpoint and
+    /// announcement digests with. Message ids and merkle trees always use
+    /// keccak256.
+    pub hash_algorithm: HashAlgorithm,
 }
 
 /// A sequence-aware indexer for messages
@@ -79,6 +90,9 @@ pub type IgpIndexer = Arc<dyn SequenceAwareIndexer<InterchainGasPayment>>;
 /// A sequence-aware indexer for merkle tree hooks
 pub type MerkleTreeHookIndexer = Arc<dyn SequenceAwareIndexer<MerkleTreeInsertion>>;
 
+/// An indexer for mailbox configuration changes
+pub type MailboxConfigChangeIndexer = Arc<dyn SequenceAwareIndexer<MailboxConfigChange>>;
+
 #[async_trait]
 impl TryFromWithMetrics<ChainConf> for MessageIndexer {
     async fn try_from_with_metrics(
@@ -131,6 +145,19 @@ impl TryFromWithMetrics<ChainConf> for MerkleTreeHookIndexer {
     }
 }
 
+#[async_trait]
+impl TryFromWithMetrics<ChainConf> for MailboxConfigChangeIndexer {
+    async fn try_from_with_metrics(
+        conf: &ChainConf,
+        metrics: &CoreMetrics,
+        _advanced_log_meta: bool,
+    ) -> Result<Self> {
+        conf.build_mailbox_config_change_indexer(metrics)
+            .await
+            .map(Into::into)
+    }
+}
+
 /// A connection to _some_ blockchain.
 #[derive(Clone, Debug)]
 pub enum ChainConnectionConf {
@@ -164,6 +191,26 @@ impl ChainConnectionConf {
             _ => None,
         }
     }
+
+    /// The same connection, but through the RPC at `url` only, e.g. to
+    /// cross-check what the configured RPCs return. None for protocols whose
+    /// contracts aren't read through a single RPC url.
+    pub fn with_rpc_url(&self, url: Url) -> Option<Self> {
+        match self {
+            Self::Ethereum(conf) => Some(Self::Ethereum(h_eth::ConnectionConf {
+                rpc_connection: h_eth::RpcConnectionConf::Http { url },
+                // Batches are sent through the RPC of any of the reads in them
+                read_batcher: None,
+                ..conf.clone()
+            })),
+            Self::Sealevel(conf) => Some(Self::Sealevel(h_sealevel::ConnectionConf {
+                url,
+                archive_urls: vec![],
+                ..conf.clone()
+            })),
+            Self::Fuel(_) | Self::Cosmos(_) => None,
+        }
+    }
 }
 
 /// Addresses for mailbox chain contracts
@@ -188,6 +235,9 @@ pub struct IndexSettings {
     pub chunk_size: u32,
     /// The indexing mode.
     pub mode: IndexMode,
+    /// The estimated time between blocks, which tunes how often cursors poll
+    /// the chain once they have caught up with it.
+    pub block_time: Option<Duration>,
 }
 
 impl ChainConf {
@@ -258,6 +308,63 @@ impl ChainConf {
         .context(ctx)
     }
 
+    /// Verify that the core contracts configured for the chain are deployed
+    /// at their addresses, failing with a descriptive error if one isn't.
+    /// Only Ethereum chains are verified. Failures to find out, e.g. of the
+    /// RPC, are only logged so that an unavailable chain doesn't prevent the
+    /// agent from starting.
+    pub async fn verify_deployment(&self, metrics: &CoreMetrics) -> Result<()> {
+        let ChainConnectionConf::Ethereum(conf) = &self.connection else {
+            return Ok(());
+        };
+        let locator = self.locator(H256::zero());
+        let verifier = self
+            .build_ethereum(conf, &locator, metrics, h_eth::DeploymentVerifierBuilder {})
+            .await
+            .context("Building deployment verifier")?;
+
+        let contracts = [
+            (h_eth::ConfiguredContract::Mailbox, self.addresses.mailbox),
+            (
+                h_eth::ConfiguredContract::InterchainGasPaymaster,
+                self.addresses.interchain_gas_paymaster,
+            ),
+            (
+                h_eth::ConfiguredContract::MerkleTreeHook,
+                self.addresses.merkle_tree_hook,
+            ),
+            (
+                h_eth::ConfiguredContract::ValidatorAnnounce,
+                self.addresses.validator_announce,
+            ),
+        ];
+        for (contract, address) in contracts {
+            // Contracts a chain doesn't have are configured with the zero address
+            if address.is_zero() {
+                continue;
+            }
+            match verifier.verify(contract, address).await {
+                Ok(None) => {}
+                Ok(Some(mismatch)) => {
+                    return Err(Report::new(mismatch)).with_context(|| {
+                        format!(
+                            "The {contract} address configured for {} is invalid",
+                            self.domain
+                        )
+                    });
+                }
+                Err(err) => warn!(
+                    ?err,
+                    domain = %self.domain,
+                    %contract,
+                    ?address,
+                    "Failed to verify contract deployment"
+                ),
+            }
+        }
+        Ok(())
+    }
+
     /// Try to convert the chain setting into a Mailbox contract
     pub async fn build_mailbox(&self, metrics: &CoreMetrics) -> Result<Box<dyn Mailbox>> {
         let ctx = "Building mailbox";
@@ -265,8 +372,16 @@ impl ChainConf {
 
         match &self.connection {
             ChainConnectionConf::Ethereum(conf) => {
-                self.build_ethereum(conf, &locator, metrics, h_eth::MailboxBuilder {})
-                    .await
+                let inclusion_watcher = self
+                    .build_ethereum_inclusion_watcher(metrics)
+                    .context(ctx)?;
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::MailboxBuilder { inclusion_watcher },
+                )
+                .await
             }
             ChainConnectionConf::Fuel(conf) => {
                 let wallet = self.fuel_signer().await.context(ctx)?;
@@ -276,7 +391,7 @@ impl ChainConf {
                     .map_err(Into::into)
             }
             ChainConnectionConf::Sealevel(conf) => {
-                let keypair = self.sealevel_signer().await.context(ctx)?;
+                let payers = self.sealevel_payers().await.context(ctx)?;
 
                 let rpc_client = Arc::new(build_sealevel_rpc_client(self, conf, metrics));
                 let provider = build_sealevel_provider(rpc_client, &locator, conf);
@@ -287,7 +402,8 @@ impl ChainConf {
                     tx_submitter,
                     conf,
                     &locator,
-                    keypair.map(h_sealevel::SealevelKeypair::new),
+                    payers,
+                    Some(metrics.sealevel_priority_fee_metrics()),
                 )
                 .map(|m| Box::new(m) as Box<dyn Mailbox>)
                 .map_err(Into::into)
@@ -323,7 +439,7 @@ impl ChainConf {
                 let provider = build_sealevel_provider(rpc_client, &locator, conf);
                 let tx_submitter = build_tx_submitter(self, conf, metrics);
 
-                h_sealevel::SealevelMailbox::new(provider, tx_submitter, conf, &locator, None)
+                h_sealevel::SealevelMailbox::new(provider, tx_submitter, conf, &locator, None, None)
                     .map(|m| Box::new(m) as Box<dyn MerkleTreeHook>)
                     .map_err(Into::into)
             }
@@ -374,12 +490,12 @@ impl ChainConf {
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
             }
             ChainConnectionConf::Cosmos(conf) => {
-                let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
                 let indexer = Box::new(h_cosmos::CosmosMailboxDispatchIndexer::new(
                     conf.clone(),
                     locator,
-                    signer,
+                    // Indexers only read, so origins don't need a signer
+                    None,
                     reorg_period,
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
@@ -424,12 +540,11 @@ impl ChainConf {
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
             }
             ChainConnectionConf::Cosmos(conf) => {
-                let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
                 let indexer = Box::new(h_cosmos::CosmosMailboxDeliveryIndexer::new(
                     conf.clone(),
                     locator,
-                    signer,
+                    None,
                     reorg_period,
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
@@ -449,20 +564,26 @@ impl ChainConf {
 
         match &self.connection {
             ChainConnectionConf::Ethereum(conf) => {
+                let inclusion_watcher = self
+                    .build_ethereum_inclusion_watcher(metrics)
+                    .context(ctx)?;
                 self.build_ethereum(
                     conf,
                     &locator,
                     metrics,
-                    h_eth::InterchainGasPaymasterBuilder {},
+                    h_eth::InterchainGasPaymasterBuilder { inclusion_watcher },
                 )
                 .await
             }
             ChainConnectionConf::Fuel(_) => todo!(),
             ChainConnectionConf::Sealevel(conf) => {
+                let keypair = self.sealevel_signer().await.context(ctx)?;
                 let rpc_client = Arc::new(build_sealevel_rpc_client(self, conf, metrics));
+                let tx_submitter = build_tx_submitter(self, conf, metrics);
                 let paymaster = Box::new(
                     h_sealevel::SealevelInterchainGasPaymaster::new(rpc_client, conf, &locator)
-                        .await?,
+                        .await?
+                        .with_signer(keypair.map(h_sealevel::SealevelKeypair::new), tx_submitter),
                 );
                 Ok(paymaster as Box<dyn InterchainGasPaymaster>)
             }
@@ -571,13 +692,11 @@ impl ChainConf {
                 Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
             }
             ChainConnectionConf::Cosmos(conf) => {
-                let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
                 let indexer = Box::new(h_cosmos::CosmosMerkleTreeHookIndexer::new(
                     conf.clone(),
                     locator,
-                    // TODO: remove signer requirement entirely
-                    signer,
+                    None,
                     reorg_period,
                 )?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
@@ -586,6 +705,33 @@ impl ChainConf {
         .context(ctx)
     }
 
+    /// Try to convert the chain settings into an indexer for the
+    /// configuration changes of the mailbox. Only Sealevel mailboxes log
+    /// their configuration changes in a way that can be indexed.
+    pub async fn build_mailbox_config_change_indexer(
+        &self,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn SequenceAwareIndexer<MailboxConfigChange>>> {
+        let ctx = "Building mailbox config change indexer";
+        let locator = self.locator(self.addresses.mailbox);
+
+        match &self.connection {
+            ChainConnectionConf::Sealevel(conf) => {
+                let rpc_client = Arc::new(build_sealevel_rpc_client(self, conf, metrics));
+                let provider = build_sealevel_provider(rpc_client, &locator, conf);
+                let indexer = Box::new(h_sealevel::SealevelMailboxConfigIndexer::new(
+                    provider, &locator,
+                ));
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MailboxConfigChange>>)
+            }
+            _ => Err(eyre!(
+                "Mailbox config changes can't be indexed for {}",
+                self.domain.domain_protocol()
+            )),
+        }
+        .context(ctx)
+    }
+
     /// Try to convert the chain settings into a ValidatorAnnounce
     pub async fn build_validator_announce(
         &self,
@@ -595,8 +741,16 @@ impl ChainConf {
         let locator = self.locator(self.addresses.validator_announce);
         match &self.connection {
             ChainConnectionConf::Ethereum(conf) => {
-                self.build_ethereum(conf, &locator, metrics, h_eth::ValidatorAnnounceBuilder {})
-                    .await
+                let inclusion_watcher = self
+                    .build_ethereum_inclusion_watcher(metrics)
+                    .context(ctx)?;
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::ValidatorAnnounceBuilder { inclusion_watcher },
+                )
+                .await
             }
             ChainConnectionConf::Fuel(_) => todo!(),
             ChainConnectionConf::Sealevel(conf) => {
@@ -621,6 +775,37 @@ impl ChainConf {
         .context("Building ValidatorAnnounce")
     }
 
+    /// Try to convert the chain setting into a checkpoint attestation
+    /// contract, which is only supported on EVM chains
+    pub async fn build_checkpoint_attestation(
+        &self,
+        address: H256,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn CheckpointAttestation>> {
+        let ctx = "Building checkpoint attestation";
+        let locator = self.locator(address);
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                let inclusion_watcher = self
+                    .build_ethereum_inclusion_watcher(metrics)
+                    .context(ctx)?;
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::CheckpointAttestationBuilder { inclusion_watcher },
+                )
+                .await
+            }
+            ChainConnectionConf::Fuel(_)
+            | ChainConnectionConf::Sealevel(_)
+            | ChainConnectionConf::Cosmos(_) => Err(eyre!(
+                "Checkpoint attestation is only supported on EVM chains"
+            )),
+        }
+        .context(ctx)
+    }
+
     /// Try to convert the chain setting into an InterchainSecurityModule
     /// contract
     pub async fn build_ism(
@@ -801,6 +986,46 @@ impl ChainConf {
         .context(ctx)
     }
 
+    /// Try to convert the chain setting into a native bridge ISM contract
+    /// that verifies messages from the `origin` chain
+    pub async fn build_native_bridge_ism(
+        &self,
+        address: H256,
+        module_type: ModuleType,
+        origin: &ChainConf,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn NativeBridgeIsm>> {
+        let ctx = "Building native bridge ISM";
+        let locator = ContractLocator {
+            domain: &self.domain,
+            address,
+        };
+
+        match (&self.connection, &origin.connection) {
+            (ChainConnectionConf::Ethereum(conf), ChainConnectionConf::Ethereum(origin_conf)) => {
+                match module_type {
+                    ModuleType::ArbL2ToL1 => {
+                        let builder = h_eth::ArbL2ToL1IsmBuilder {
+                            origin: origin_conf.clone(),
+                        };
+                        self.build_ethereum(conf, &locator, metrics, builder).await
+                    }
+                    ModuleType::OpL2ToL1 => {
+                        let builder = h_eth::OpL2ToL1IsmBuilder {
+                            origin: origin_conf.clone(),
+                        };
+                        self.build_ethereum(conf, &locator, metrics, builder).await
+                    }
+                    _ => Err(eyre!("{module_type} is not a native bridge ISM")),
+                }
+            }
+            _ => Err(eyre!(
+                "Native bridge ISMs are only supported between Ethereum chains"
+            )),
+        }
+        .context(ctx)
+    }
+
     async fn signer<S: BuildableWithSignerConf>(&self) -> Result<Option<S>> {
         if let Some(conf) = &self.signer {
             Ok(Some(conf.build::<S>().await?))
@@ -842,6 +1067,19 @@ impl ChainConf {
         self.signer().await
     }
 
+    /// The signer followed by the additional signers, if a signer is
+    /// configured
+    async fn sealevel_payers(&self) -> Result<Option<h_sealevel::SealevelPayers>> {
+        let Some(signer) = self.sealevel_signer().await? else {
+            return Ok(None);
+        };
+        let mut payers = vec![h_sealevel::SealevelKeypair::new(signer)];
+        for conf in &self.additional_signers {
+            payers.push(h_sealevel::SealevelKeypair::new(conf.build().await?));
+        }
+        Ok(h_sealevel::SealevelPayers::new(payers))
+    }
+
     async fn cosmos_signer(&self) -> Result<Option<h_cosmos::Signer>> {
         self.signer().await
     }
@@ -932,6 +1170,20 @@ impl ChainConf {
             .await;
         Ok(res?)
     }
+
+    /// Watches the inclusion of transactions submitted to an Ethereum chain,
+    /// considering them finalized once they are beyond the reorg period.
+    fn build_ethereum_inclusion_watcher(
+        &self,
+        metrics: &CoreMetrics,
+    ) -> Result<h_eth::TransactionInclusionWatcher> {
+        let reorg_period = EthereumReorgPeriod::try_from(&self.reorg_period)?;
+        Ok(h_eth::TransactionInclusionWatcher::new(
+            metrics.transaction_inclusion_metrics(),
+            &self.domain,
+            reorg_period,
+        ))
+    }
 }
 
 /// Helper to build a sealevel rpc client with metrics
@@ -944,7 +1196,10 @@ fn build_sealevel_rpc_client(
     let rpc_client_url = connection_conf.url.clone();
     let client_metrics = metrics.client_metrics();
     SealevelRpcClientBuilder::new(rpc_client_url)
+        .with_archive_urls(connection_conf.archive_urls.clone())
         .with_prometheus_metrics(client_metrics.clone(), middleware_metrics.chain.clone())
+        .with_rate_limiter(connection_conf.rpc_rate_limiter.clone())
+        .with_account_batching(connection_conf.batch_account_queries)
         .build()
 }
 
@@ -969,5 +1224,6 @@ fn build_tx_submitter(
         rpc_client_url.to_string(),
         client_metrics,
         middleware_metrics.chain.clone(),
+        connection_conf.rpc_rate_limiter.clone(),
     )
 }
@@ -65,6 +65,7 @@
 pub use base::*;
 pub use chains::*;
 pub use checkpoint_syncer::*;
+pub use reload::*;
 pub use signers::*;
 pub use trace::*;
 
@@ -81,6 +82,8 @@ mod base;
 /// Chain configuration
 mod chains;
 pub mod loader;
+/// Reloading settings at runtime
+mod reload;
 /// Signer configuration
 mod signers;
 /// Tracing subscriber management
@@ -1,18 +1,25 @@
+use std::{collections::HashMap, time::Duration};
+
 use eyre::eyre;
 use hyperlane_sealevel::{
-    HeliusPriorityFeeLevel, HeliusPriorityFeeOracleConfig, PriorityFeeOracleConfig,
+    AccountKeyTemplate, AccountMetaTemplate, HeliusPriorityFeeLevel, HeliusPriorityFeeOracleConfig,
+    KnownRecipientConfig, PriorityFeeOracleConfig,
 };
+use solana_sdk::pubkey::Pubkey;
 use url::Url;
 
-use h_eth::TransactionOverrides;
+use h_eth::{
+    FeeHistoryCache, LogQueryBatcher, ReadBatcher, StorageLocationsCache, TransactionOverrides,
+};
 
 use hyperlane_core::config::{ConfigErrResultExt, OperationBatchConfig};
+use hyperlane_core::rpc_clients::{RpcRateLimitConf, RpcRateLimiter};
 use hyperlane_core::{config::ConfigParsingError, HyperlaneDomainProtocol, NativeToken};
 
 use crate::settings::envs::*;
 use crate::settings::ChainConnectionConf;
 
-use super::{parse_base_and_override_urls, parse_cosmos_gas_price, ValueParser};
+use super::{parse_base_and_override_urls, parse_cosmos_gas_price, parse_opt_urls, ValueParser};
 
 #[allow(clippy::question_mark)] // TODO: `rustc` 1.80.1 clippy issue
 pub fn build_ethereum_connection_conf(
@@ -21,6 +28,8 @@ pub fn build_ethereum_connection_conf(
     err: &mut ConfigParsingError,
     default_rpc_consensus_type: &str,
     operation_batch: OperationBatchConfig,
+    rpc_rate_limiter: Option<RpcRateLimiter>,
+    default_native_token_decimals: Option<u32>,
 ) -> Option<ChainConnectionConf> {
     let Some(first_url) = rpcs.to_owned().clone().into_iter().next() else {
         return None;
@@ -71,10 +80,28 @@ pub fn build_ethereum_connection_conf(
         })
         .unwrap_or_default();
 
+    let read_batcher = chain
+        .chain(err)
+        .get_opt_key("batchReads")
+        .parse_bool()
+        .unwrap_or(false)
+        .then(|| ReadBatcher::new(operation_batch.batch_contract_address));
+
     Some(ChainConnectionConf::Ethereum(h_eth::ConnectionConf {
         rpc_connection: rpc_connection_conf?,
         transaction_overrides,
         operation_batch,
+        native_token: parse_native_token(chain, err, default_native_token_decimals.unwrap_or(18)),
+        rpc_rate_limiter,
+        fee_history_cache: parse_fee_history_cache(chain, err),
+        log_query_batcher: chain
+            .chain(err)
+            .get_opt_key("batchLogQueries")
+            .parse_bool()
+            .unwrap_or(false)
+            .then(LogQueryBatcher::default),
+        storage_locations_cache: parse_storage_locations_cache(chain, err),
+        read_batcher,
     }))
 }
 
@@ -83,6 +110,8 @@ pub fn build_cosmos_connection_conf(
     chain: &ValueParser,
     err: &mut ConfigParsingError,
     operation_batch: OperationBatchConfig,
+    rpc_rate_limiter: Option<RpcRateLimiter>,
+    default_native_token_decimals: Option<u32>,
 ) -> Option<ChainConnectionConf> {
     let mut local_err = ConfigParsingError::default();
     let grpcs =
@@ -140,7 +169,7 @@ pub fn build_cosmos_connection_conf(
         .parse_u64()
         .end();
 
-    let native_token = parse_native_token(chain, err, 18);
+    let native_token = parse_native_token(chain, err, default_native_token_decimals.unwrap_or(18));
 
     if !local_err.is_ok() {
         err.merge(local_err);
@@ -156,6 +185,7 @@ pub fn build_cosmos_connection_conf(
             contract_address_bytes.unwrap().try_into().unwrap(),
             operation_batch,
             native_token,
+            rpc_rate_limiter,
         )))
     }
 }
@@ -165,12 +195,16 @@ fn build_sealevel_connection_conf(
     chain: &ValueParser,
     err: &mut ConfigParsingError,
     operation_batch: OperationBatchConfig,
+    rpc_rate_limiter: Option<RpcRateLimiter>,
+    default_native_token_decimals: Option<u32>,
 ) -> Option<ChainConnectionConf> {
     let mut local_err = ConfigParsingError::default();
 
-    let native_token = parse_native_token(chain, err, 9);
+    let native_token = parse_native_token(chain, err, default_native_token_decimals.unwrap_or(9));
     let priority_fee_oracle = parse_sealevel_priority_fee_oracle_config(chain, &mut local_err);
     let transaction_submitter = parse_transaction_submitter_config(chain, &mut local_err);
+    let known_recipients = parse_sealevel_known_recipients(chain, &mut local_err);
+    let archive_urls = parse_opt_urls(chain, "archiveRpcUrls", "http", &mut local_err);
 
     if !local_err.is_ok() {
         err.merge(local_err);
@@ -178,10 +212,18 @@ fn build_sealevel_connection_conf(
     } else {
         Some(ChainConnectionConf::Sealevel(h_sealevel::ConnectionConf {
             url: url.clone(),
+            archive_urls,
             operation_batch,
             native_token,
             priority_fee_oracle: priority_fee_oracle.unwrap(),
             transaction_submitter: transaction_submitter.unwrap(),
+            known_recipients,
+            rpc_rate_limiter,
+            batch_account_queries: chain
+                .chain(err)
+                .get_opt_key("batchAccountQueries")
+                .parse_bool()
+                .unwrap_or(false),
         }))
     }
 }
@@ -211,6 +253,68 @@ fn parse_native_token(
     }
 }
 
+fn parse_fee_history_cache(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<FeeHistoryCache> {
+    let ttl = chain
+        .chain(err)
+        .get_opt_key("feeHistoryCacheTtl")
+        .parse_u64()
+        .end()?;
+    // A TTL of zero disables the cache
+    (ttl > 0).then(|| FeeHistoryCache::new(Duration::from_secs(ttl)))
+}
+
+fn parse_storage_locations_cache(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<StorageLocationsCache> {
+    let ttl = chain
+        .chain(err)
+        .get_opt_key("storageLocationsCacheTtl")
+        .parse_u64()
+        .end()?;
+    // A TTL of zero disables the cache
+    (ttl > 0).then(|| StorageLocationsCache::new(Duration::from_secs(ttl)))
+}
+
+fn parse_rpc_rate_limiter(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<RpcRateLimiter> {
+    let rate_limit = chain
+        .get_opt_key("rpcRateLimit")
+        .take_err(err, || &chain.cwp + "rpc_rate_limit")
+        .flatten()?;
+
+    let requests_per_second = rate_limit
+        .chain(err)
+        .get_key("requestsPerSecond")
+        .parse_f64()
+        .end()?;
+    if requests_per_second <= 0.0 {
+        err.push(
+            &rate_limit.cwp + "requests_per_second",
+            eyre!("Expected a positive number of requests per second, got {requests_per_second}"),
+        );
+        return None;
+    }
+
+    // By default, up to a second's worth of requests can be sent at once
+    let burst = rate_limit
+        .chain(err)
+        .get_opt_key("burst")
+        .parse_u32()
+        .unwrap_or_else(|| requests_per_second.ceil() as u32)
+        .max(1);
+
+    Some(RpcRateLimiter::new(RpcRateLimitConf {
+        requests_per_second,
+        burst,
+    }))
+}
+
 fn parse_sealevel_priority_fee_oracle_config(
     chain: &ValueParser,
     err: &mut ConfigParsingError,
@@ -352,6 +456,112 @@ fn parse_transaction_submitter_config(
     }
 }
 
+fn parse_sealevel_known_recipients(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> HashMap<Pubkey, KnownRecipientConfig> {
+    let Some(recipients) = chain
+        .chain(err)
+        .get_opt_key("knownRecipients")
+        .into_array_iter()
+    else {
+        return HashMap::new();
+    };
+
+    recipients
+        .filter_map(|value_parser| {
+            let recipient = parse_pubkey(&value_parser, "recipient", err);
+            let ism = parse_pubkey(&value_parser, "ism", err);
+            let config = KnownRecipientConfig {
+                ism_getter: parse_account_meta_templates(&value_parser, "ismGetter", err),
+                ism: ism?,
+                ism_verify: parse_account_meta_templates(&value_parser, "ismVerify", err),
+                handle: parse_account_meta_templates(&value_parser, "handle", err),
+            };
+            Some((recipient?, config))
+        })
+        .collect()
+}
+
+fn parse_account_meta_templates(
+    value_parser: &ValueParser,
+    key: &str,
+    err: &mut ConfigParsingError,
+) -> Vec<AccountMetaTemplate> {
+    let Some(templates) = value_parser.chain(err).get_opt_key(key).into_array_iter() else {
+        return vec![];
+    };
+
+    templates
+        .filter_map(|template| {
+            let is_writable = template
+                .chain(err)
+                .get_opt_key("writable")
+                .parse_bool()
+                .unwrap_or(false);
+            let key = parse_account_key_template(&template, err)?;
+            Some(AccountMetaTemplate { key, is_writable })
+        })
+        .collect()
+}
+
+fn parse_account_key_template(
+    template: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<AccountKeyTemplate> {
+    if let Some(pubkey) = parse_opt_pubkey(template, "pubkey", err) {
+        Some(AccountKeyTemplate::Static(pubkey))
+    } else if let Some(ism) = parse_opt_pubkey(template, "multisigIsmDomainData", err) {
+        Some(AccountKeyTemplate::MultisigIsmDomainData(ism))
+    } else if let Some(ata) = template.chain(err).get_opt_key("tokenRecipientAta").end() {
+        let mint = parse_pubkey(&ata, "mint", err);
+        let token_program = parse_pubkey(&ata, "tokenProgram", err);
+        Some(AccountKeyTemplate::TokenRecipientAta {
+            mint: mint?,
+            token_program: token_program?,
+        })
+    } else if template
+        .chain(err)
+        .get_opt_key("tokenRecipient")
+        .parse_bool()
+        .unwrap_or(false)
+    {
+        Some(AccountKeyTemplate::TokenRecipient)
+    } else {
+        err.push(
+            template.cwp.clone(),
+            eyre!("Expected one of `pubkey`, `tokenRecipient`, `tokenRecipientAta` or `multisigIsmDomainData`"),
+        );
+        None
+    }
+}
+
+fn parse_pubkey(
+    value_parser: &ValueParser,
+    key: &str,
+    err: &mut ConfigParsingError,
+) -> Option<Pubkey> {
+    value_parser
+        .chain(err)
+        .get_key(key)
+        .parse_address_hash()
+        .end()
+        .map(|hash| Pubkey::new_from_array(hash.0))
+}
+
+fn parse_opt_pubkey(
+    value_parser: &ValueParser,
+    key: &str,
+    err: &mut ConfigParsingError,
+) -> Option<Pubkey> {
+    value_parser
+        .chain(err)
+        .get_opt_key(key)
+        .parse_address_hash()
+        .end()
+        .map(|hash| Pubkey::new_from_array(hash.0))
+}
+
 pub fn build_connection_conf(
     domain_protocol: HyperlaneDomainProtocol,
     rpcs: &[Url],
@@ -359,7 +569,10 @@ pub fn build_connection_conf(
     err: &mut ConfigParsingError,
     default_rpc_consensus_type: &str,
     operation_batch: OperationBatchConfig,
+    default_native_token_decimals: Option<u32>,
 ) -> Option<ChainConnectionConf> {
+    // A single limiter is shared by all the clients built for the chain
+    let rpc_rate_limiter = parse_rpc_rate_limiter(chain, err);
     match domain_protocol {
         HyperlaneDomainProtocol::Ethereum => build_ethereum_connection_conf(
             rpcs,
@@ -367,17 +580,86 @@ pub fn build_connection_conf(
             err,
             default_rpc_consensus_type,
             operation_batch,
+            rpc_rate_limiter,
+            default_native_token_decimals,
         ),
         HyperlaneDomainProtocol::Fuel => rpcs
             .iter()
             .next()
             .map(|url| ChainConnectionConf::Fuel(h_fuel::ConnectionConf { url: url.clone() })),
-        HyperlaneDomainProtocol::Sealevel => rpcs
-            .iter()
-            .next()
-            .and_then(|url| build_sealevel_connection_conf(url, chain, err, operation_batch)),
-        HyperlaneDomainProtocol::Cosmos => {
-            build_cosmos_connection_conf(rpcs, chain, err, operation_batch)
+        HyperlaneDomainProtocol::Sealevel => rpcs.iter().next().and_then(|url| {
+            build_sealevel_connection_conf(
+                url,
+                chain,
+                err,
+                operation_batch,
+                rpc_rate_limiter,
+                default_native_token_decimals,
+            )
+        }),
+        HyperlaneDomainProtocol::Cosmos => build_cosmos_connection_conf(
+            rpcs,
+            chain,
+            err,
+            operation_batch,
+            rpc_rate_limiter,
+            default_native_token_decimals,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn parse_sealevel_connection_conf(chain: serde_json::Value) -> h_sealevel::ConnectionConf {
+        let rpcs: Vec<Url> = vec![
+            "https://rpc1.com".parse().unwrap(),
+            "https://rpc2.com".parse().unwrap(),
+        ];
+        let mut err = ConfigParsingError::default();
+        let conf = build_connection_conf(
+            HyperlaneDomainProtocol::Sealevel,
+            &rpcs,
+            &ValueParser::new(Default::default(), &chain),
+            &mut err,
+            "fallback",
+            OperationBatchConfig::default(),
+            None,
+        );
+        assert!(err.is_ok(), "{err:?}");
+        match conf {
+            Some(ChainConnectionConf::Sealevel(conf)) => conf,
+            conf => panic!("Expected a sealevel connection conf, got {conf:?}"),
         }
     }
+
+    #[test]
+    fn test_sealevel_archive_rpc_urls_are_parsed() {
+        let conf = parse_sealevel_connection_conf(json!({
+            "archiverpcurls": [
+                { "http": "https://archive1.com" },
+                { "http": "https://archive2.com" },
+            ],
+        }));
+
+        assert_eq!(conf.url.as_str(), "https://rpc1.com/");
+        assert_eq!(
+            conf.archive_urls
+                .iter()
+                .map(Url::as_str)
+                .collect::<Vec<_>>(),
+            vec!["https://archive1.com/", "https://archive2.com/"]
+        );
+    }
+
+    #[test]
+    fn test_sealevel_rpc_urls_are_not_archives() {
+        let conf = parse_sealevel_connection_conf(json!({}));
+
+        assert_eq!(conf.url.as_str(), "https://rpc1.com/");
+        assert!(conf.archive_urls.is_empty());
+    }
 }
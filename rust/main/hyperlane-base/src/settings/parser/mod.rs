@@ -7,6 +7,7 @@
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
+    time::Duration,
 };
 
 use convert_case::{Case, Casing};
@@ -18,8 +19,8 @@ use url::Url;
 
 use h_cosmos::RawCosmosAmount;
 use hyperlane_core::{
-    cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol,
-    HyperlaneDomainTechnicalStack, IndexMode, ReorgPeriod,
+    cfg_unwrap_all, config::*, DomainMetadata, DomainRegistry, HashAlgorithm, HyperlaneDomain,
+    HyperlaneDomainProtocol, HyperlaneDomainTechnicalStack, IndexMode, ReorgPeriod, H160,
 };
 
 use crate::settings::{
@@ -57,6 +58,42 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_u16()
             .unwrap_or(9090);
 
+        let persistent_metrics = p
+            .chain(&mut err)
+            .get_opt_key("persistentMetrics")
+            .parse_bool()
+            .unwrap_or(true);
+
+        let enable_profiling = p
+            .chain(&mut err)
+            .get_opt_key("enableProfiling")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let db_backup_dir = p
+            .chain(&mut err)
+            .get_opt_key("dbBackupDir")
+            .parse_from_str("Expected db backup directory")
+            .end();
+
+        let restore_db_from = p
+            .chain(&mut err)
+            .get_opt_key("restoreDbFrom")
+            .parse_from_str("Expected db backup path")
+            .end();
+
+        let self_test = p
+            .chain(&mut err)
+            .get_opt_key("selfTest")
+            .parse_bool()
+            .unwrap_or(false);
+
+        let verify_deployments = p
+            .chain(&mut err)
+            .get_opt_key("verifyDeployments")
+            .parse_bool()
+            .unwrap_or(true);
+
         let fmt = p
             .chain(&mut err)
             .get_opt_key("log")
@@ -71,6 +108,20 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_value("Invalid log level")
             .unwrap_or_default();
 
+        // Chains that aren't a `KnownHyperlaneDomain` are typed with the
+        // embedded domain registry, extended with the one at `domainRegistry`
+        let mut domain_registry = DomainRegistry::embedded().clone();
+        if let Some(path) = p
+            .chain(&mut err)
+            .get_opt_key("domainRegistry")
+            .parse_string()
+            .end()
+        {
+            DomainRegistry::from_file(path)
+                .map(|external| domain_registry.extend(external))
+                .take_err(&mut err, || cwp + "domain_registry");
+        }
+
         let raw_chains: Vec<(String, ValueParser)> = if let Some(filter) = filter {
             p.chain(&mut err)
                 .get_opt_key("chains")
@@ -96,10 +147,26 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_string()
             .unwrap_or("fallback");
 
+        let raw_histogram_buckets: Vec<(String, ValueParser)> = p
+            .chain(&mut err)
+            .get_opt_key("metricsHistogramBuckets")
+            .into_obj_iter()
+            .map(|v| v.collect())
+            .unwrap_or_default();
+
+        let histogram_buckets: HashMap<String, Vec<f64>> = raw_histogram_buckets
+            .into_iter()
+            .filter_map(|(metric, buckets)| {
+                parse_histogram_buckets(buckets)
+                    .take_config_err(&mut err)
+                    .map(|buckets| (metric.to_case(Case::Flat), buckets))
+            })
+            .collect();
+
         let chains: HashMap<String, ChainConf> = raw_chains
             .into_iter()
             .filter_map(|(name, chain)| {
-                parse_chain(chain, &name, default_rpc_consensus_type)
+                parse_chain(chain, &name, default_rpc_consensus_type, &domain_registry)
                     .take_config_err(&mut err)
                     .map(|v| (name, v))
             })
@@ -114,33 +181,91 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
         err.into_result(Self {
             chains,
             metrics_port,
+            persistent_metrics,
+            enable_profiling,
+            db_backup_dir,
+            restore_db_from,
             tracing: TracingConfig { fmt, level },
+            self_test,
+            verify_deployments,
+            histogram_buckets,
         })
     }
 }
 
-/// The chain name and ChainMetadata
+/// The upper bounds of the buckets of a histogram, in increasing order
+fn parse_histogram_buckets(buckets: ValueParser) -> ConfigResult<Vec<f64>> {
+    let cwp = buckets.cwp.clone();
+    let bounds = buckets
+        .into_array_iter()?
+        .map(|bound| bound.parse_f64())
+        .collect::<ConfigResult<Vec<_>>>()?;
+    if bounds.is_empty() || bounds.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(eyre!(
+            "Expected a non-empty list of increasing histogram bucket bounds, got {bounds:?}"
+        ))
+        .into_config_result(|| cwp);
+    }
+    Ok(bounds)
+}
+
+/// The chain name and ChainMetadata. Values missing from the metadata default
+/// to the ones in the domain registry.
 fn parse_chain(
     chain: ValueParser,
     name: &str,
     default_rpc_consensus_type: &str,
+    domain_registry: &DomainRegistry,
 ) -> ConfigResult<ChainConf> {
     let mut err = ConfigParsingError::default();
 
-    let domain = parse_domain(chain.clone(), name).take_config_err(&mut err);
+    let registered = domain_registry.by_name(name);
+    let domain =
+        parse_domain(chain.clone(), name, domain_registry, registered).take_config_err(&mut err);
     let signer = chain
         .chain(&mut err)
         .get_opt_key("signer")
         .and_then(parse_signer)
         .end();
 
+    let additional_signers = chain
+        .chain(&mut err)
+        .get_opt_key("additionalSigners")
+        .into_array_iter()
+        .map(|signers| {
+            signers
+                .filter_map(|signer| parse_signer(signer).take_config_err(&mut err))
+                .collect()
+        })
+        .unwrap_or_default();
+
     let reorg_period = chain
         .chain(&mut err)
         .get_opt_key("blocks")
         .get_key("reorgPeriod")
         .parse_value("Invalid reorgPeriod")
+        .end()
+        .or_else(|| registered.map(|metadata| metadata.finality.clone()))
         .unwrap_or(ReorgPeriod::from_blocks(1));
 
+    let block_time = chain
+        .chain(&mut err)
+        .get_opt_key("blocks")
+        .get_opt_key("estimateBlockTime")
+        .parse_f64()
+        .end()
+        .and_then(|secs| {
+            Duration::try_from_secs_f64(secs)
+                .ok()
+                .filter(|block_time| !block_time.is_zero())
+                .or_else(|| {
+                    Err(eyre!(
+                        "Expected a positive estimateBlockTime in seconds, got {secs}"
+                    ))
+                    .take_err(&mut err, || &chain.cwp + "blocks" + "estimate_block_time")
+                })
+        });
+
     let rpcs = parse_base_and_override_urls(&chain, "rpcUrls", "customRpcUrls", "http", &mut err);
 
     let from = chain
@@ -171,6 +296,12 @@ fn parse_chain(
                 .unwrap_or_default()
         });
 
+    let hash_algorithm = chain
+        .chain(&mut err)
+        .get_opt_key("hashAlgorithm")
+        .parse_value("Invalid hashAlgorithm")
+        .unwrap_or_default();
+
     let mailbox = chain
         .chain(&mut err)
         .get_key("mailbox")
@@ -215,12 +346,14 @@ fn parse_chain(
             batch_contract_address,
             max_batch_size,
         },
+        registered.map(|metadata| metadata.native_token_decimals),
     );
 
     cfg_unwrap_all!(&chain.cwp, err: [connection, mailbox, interchain_gas_paymaster, validator_announce, merkle_tree_hook]);
     err.into_result(ChainConf {
         domain,
         signer,
+        additional_signers,
         reorg_period,
         addresses: CoreContractAddresses {
             mailbox,
@@ -234,12 +367,19 @@ fn parse_chain(
             from,
             chunk_size,
             mode,
+            block_time,
         },
+        hash_algorithm,
     })
 }
 
 /// Expects ChainMetadata
-fn parse_domain(chain: ValueParser, name: &str) -> ConfigResult<HyperlaneDomain> {
+fn parse_domain(
+    chain: ValueParser,
+    name: &str,
+    domain_registry: &DomainRegistry,
+    registered: Option<&DomainMetadata>,
+) -> ConfigResult<HyperlaneDomain> {
     let mut err = ConfigParsingError::default();
     let internal_name = chain.chain(&mut err).get_key("name").parse_string().end();
 
@@ -261,26 +401,36 @@ fn parse_domain(chain: ValueParser, name: &str) -> ConfigResult<HyperlaneDomain>
         .get_opt_key("domainId")
         .parse_u32()
         .end()
+        .or_else(|| registered.map(|metadata| metadata.id))
         .or_else(|| chain.chain(&mut err).get_key("chainId").parse_u32().end());
 
     let protocol = chain
         .chain(&mut err)
-        .get_key("protocol")
+        .get_opt_key("protocol")
         .parse_from_str::<HyperlaneDomainProtocol>("Invalid Hyperlane domain protocol")
-        .end();
+        .end()
+        .or_else(|| registered.map(|metadata| metadata.protocol))
+        .or_else(|| {
+            Err(eyre!(
+                "Missing protocol, and the chain isn't in the domain registry"
+            ))
+            .take_err(&mut err, || &chain.cwp + "protocol")
+        });
 
     let technical_stack = chain
         .chain(&mut err)
         .get_opt_key("technicalStack")
         .parse_from_str::<HyperlaneDomainTechnicalStack>("Invalid chain technical stack")
         .end()
+        .or_else(|| registered.map(|metadata| metadata.technical_stack))
         .or_else(|| Some(HyperlaneDomainTechnicalStack::default()));
 
     cfg_unwrap_all!(&chain.cwp, err: [domain_id, protocol, technical_stack]);
 
-    let domain = HyperlaneDomain::from_config(domain_id, name, protocol, technical_stack)
-        .context("Invalid domain data")
-        .take_err(&mut err, || chain.cwp.clone());
+    let domain =
+        HyperlaneDomain::from_config(domain_id, name, protocol, technical_stack, domain_registry)
+            .context("Invalid domain data")
+            .take_err(&mut err, || chain.cwp.clone());
 
     cfg_unwrap_all!(&chain.cwp, err: [domain]);
     err.into_result(domain)
@@ -323,6 +473,42 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 .unwrap_or_default();
             err.into_result(SignerConf::Aws { id, region })
         }};
+        (remote) => {{
+            let address = signer
+                .chain(&mut err)
+                .get_key("address")
+                .parse_address_hash()
+                .map(H160::from)
+                .unwrap_or_default();
+            let urls = signer
+                .chain(&mut err)
+                .get_key("urls")
+                .parse_string()
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|url| {
+                    url.trim()
+                        .parse()
+                        .context("Invalid remote signer url")
+                        .take_err(&mut err, || &signer.cwp + "urls")
+                })
+                .collect_vec();
+            if urls.is_empty() {
+                Err::<(), _>(eyre!("Remote signer requires at least one url"))
+                    .take_err(&mut err, || &signer.cwp + "urls");
+            }
+            let request_signing_key = signer
+                .chain(&mut err)
+                .get_opt_key("requestSigningKey")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            err.into_result(SignerConf::Remote {
+                address,
+                urls,
+                request_signing_key,
+            })
+        }};
         (cosmosKey) => {{
             let key = signer
                 .chain(&mut err)
@@ -351,6 +537,7 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
     match signer_type {
         Some("hexKey") => parse_signer!(hexKey),
         Some("aws") => parse_signer!(aws),
+        Some("remote") => parse_signer!(remote),
         Some("cosmosKey") => parse_signer!(cosmosKey),
         Some(t) => {
             Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| &signer.cwp + "type")
@@ -438,6 +625,29 @@ fn parse_urls(
         .unwrap_or_default()
 }
 
+/// Like `parse_urls`, but for a key that may be omitted
+fn parse_opt_urls(
+    chain: &ValueParser,
+    key: &str,
+    protocol: &str,
+    err: &mut ConfigParsingError,
+) -> Vec<Url> {
+    chain
+        .chain(err)
+        .get_opt_key(key)
+        .into_array_iter()
+        .map(|urls| {
+            urls.filter_map(|v| {
+                v.chain(err)
+                    .get_key(protocol)
+                    .parse_from_str("Invalid url")
+                    .end()
+            })
+            .collect_vec()
+        })
+        .unwrap_or_default()
+}
+
 fn parse_custom_urls(
     chain: &ValueParser,
     key: &str,
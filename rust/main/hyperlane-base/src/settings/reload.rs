@@ -0,0 +1,41 @@
+use eyre::Result;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::mpsc::{self, UnboundedReceiver},
+    task::JoinHandle,
+};
+use tracing::{info, info_span, warn, Instrument};
+
+use crate::LoadableFromSettings;
+
+/// Spawns a task that re-reads the agent's settings from the config files and
+/// env vars every time the process receives a SIGHUP.
+///
+/// Settings that load successfully are sent on the returned channel. Settings
+/// that fail to load are logged and ignored, so a bad edit to a config file
+/// leaves the agent running with its current settings.
+pub fn reload_settings_on_sighup<S>() -> Result<(UnboundedReceiver<S>, JoinHandle<()>)>
+where
+    S: LoadableFromSettings + Send + 'static,
+{
+    let mut hangups = signal(SignalKind::hangup())?;
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let handle = tokio::spawn(
+        async move {
+            while hangups.recv().await.is_some() {
+                info!("Received SIGHUP, reloading settings");
+                match S::load() {
+                    Ok(settings) => {
+                        if sender.send(settings).is_err() {
+                            // Nobody is listening for reloaded settings anymore
+                            break;
+                        }
+                    }
+                    Err(err) => warn!(?err, "Failed to reload settings, keeping current ones"),
+                }
+            }
+        }
+        .instrument(info_span!("SettingsReloader")),
+    );
+    Ok((receiver, handle))
+}
@@ -3,11 +3,12 @@ use ed25519_dalek::SecretKey;
 use ethers::prelude::{AwsSigner, LocalWallet};
 use ethers::utils::hex::ToHex;
 use eyre::{bail, Context, Report};
-use hyperlane_core::{AccountAddressType, H256};
+use hyperlane_core::{AccountAddressType, H160, H256};
 use hyperlane_sealevel::Keypair;
 use rusoto_core::Region;
 use rusoto_kms::KmsClient;
-use tracing::instrument;
+use tracing::{instrument, warn};
+use url::Url;
 
 use super::aws_credentials::AwsChainCredentialsProvider;
 use crate::types::utils;
@@ -28,6 +29,16 @@ pub enum SignerConf {
         /// The AWS region
         region: Region,
     },
+    /// A remote signing service, e.g. a threshold ECDSA signer exposing a
+    /// web3signer-style API. Endpoints are tried in order for failover.
+    Remote {
+        /// The address of the key held by the signing service
+        address: H160,
+        /// Endpoints of the signing service, in order of preference
+        urls: Vec<Url>,
+        /// Optional shared secret used to sign requests to the service
+        request_signing_key: Option<String>,
+    },
     /// Cosmos Specific key
     CosmosKey {
         /// Private key value
@@ -85,6 +96,21 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
                 let signer = AwsSigner::new(client, id, 0).await?;
                 hyperlane_ethereum::Signers::Aws(signer)
             }
+            SignerConf::Remote {
+                address,
+                urls,
+                request_signing_key,
+            } => {
+                let signer = hyperlane_ethereum::RemoteSigner::new(
+                    *address,
+                    urls.clone(),
+                    request_signing_key.as_ref().map(|k| k.as_bytes().to_vec()),
+                )?;
+                for (url, err) in signer.check_health().await {
+                    warn!(%url, %err, "Remote signer endpoint failed health check");
+                }
+                hyperlane_ethereum::Signers::Remote(signer)
+            }
             SignerConf::CosmosKey { .. } => {
                 bail!("cosmosKey signer is not supported by Ethereum")
             }
@@ -0,0 +1,190 @@
+use std::fmt::{self, Write};
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::{
+        format::{self, FormatEvent, FormatFields},
+        FmtContext,
+    },
+    layer::Context,
+    registry::LookupSpan,
+    Layer,
+};
+
+/// The span field that correlates the logs of a unit of work, e.g. the id of
+/// the message being processed. Spans without it inherit their parent's.
+pub const CORRELATION_ID_FIELD: &str = "correlation_id";
+
+/// The correlation id of a span, stored in its extensions
+#[derive(Debug, Clone)]
+struct CorrelationId(String);
+
+#[derive(Default)]
+struct CorrelationIdVisitor(Option<String>);
+
+impl Visit for CorrelationIdVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == CORRELATION_ID_FIELD {
+            self.0 = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == CORRELATION_ID_FIELD {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+/// Tracks the correlation id of every span, either recorded on it or
+/// inherited from its parent, so that the logs of all the spans of a unit of
+/// work can be correlated.
+pub struct CorrelationLayer;
+
+impl<S> Layer<S> for CorrelationLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = CorrelationIdVisitor::default();
+        attrs.record(&mut visitor);
+        let correlation_id = visitor.0.map(CorrelationId).or_else(|| {
+            span.parent()
+                .and_then(|parent| parent.extensions().get::<CorrelationId>().cloned())
+        });
+        if let Some(correlation_id) = correlation_id {
+            span.extensions_mut().insert(correlation_id);
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut visitor = CorrelationIdVisitor::default();
+        values.record(&mut visitor);
+        if let Some(correlation_id) = visitor.0 {
+            span.extensions_mut().replace(CorrelationId(correlation_id));
+        }
+    }
+}
+
+/// Formats events as JSON with the correlation id of their span as a top
+/// level `correlation_id` key, so that log pipelines can group the records
+/// of a unit of work without parsing the span list.
+pub struct CorrelatedJson {
+    inner: format::Format<format::Json>,
+}
+
+impl Default for CorrelatedJson {
+    fn default() -> Self {
+        Self {
+            inner: format::Format::default().json(),
+        }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for CorrelatedJson
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: format::Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let correlation_id = ctx.event_scope().and_then(|scope| {
+            scope
+                .into_iter()
+                .find_map(|span| span.extensions().get::<CorrelationId>().cloned())
+        });
+        let Some(CorrelationId(correlation_id)) = correlation_id else {
+            return self.inner.format_event(ctx, writer, event);
+        };
+
+        let mut record = String::new();
+        self.inner
+            .format_event(ctx, format::Writer::new(&mut record), event)?;
+        match record.strip_prefix('{') {
+            Some(fields) => {
+                let correlation_id =
+                    serde_json::to_string(&correlation_id).map_err(|_| fmt::Error)?;
+                write!(
+                    writer,
+                    "{{\"{CORRELATION_ID_FIELD}\":{correlation_id},{fields}"
+                )
+            }
+            None => writer.write_str(&record),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{info, info_span};
+    use tracing_subscriber::{fmt::MakeWriter, prelude::*};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Output {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_records_have_the_correlation_id_of_their_span() {
+        let output = Output::default();
+        let subscriber = tracing_subscriber::registry().with(CorrelationLayer).with(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .event_format(CorrelatedJson::default())
+                .with_writer(output.clone()),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            info!("Outside of any message");
+            let _message = info_span!("prepare", correlation_id = "0xabc").entered();
+            let _metadata = info_span!("build_metadata").entered();
+            info!("Building metadata");
+        });
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].get(CORRELATION_ID_FIELD).is_none());
+        assert_eq!(records[1][CORRELATION_ID_FIELD], "0xabc");
+        assert_eq!(records[1]["fields"]["message"], "Building metadata");
+    }
+}
@@ -4,12 +4,14 @@ use tracing::{span, Subscriber};
 use tracing_subscriber::{
     fmt::{
         self,
-        format::{Compact, DefaultFields, Format, Full, Json, JsonFields, Pretty},
+        format::{Compact, DefaultFields, Format, Full, JsonFields, Pretty},
     },
     registry::LookupSpan,
     Layer,
 };
 
+use super::correlation::CorrelatedJson;
+
 /// Basic tracing configuration
 #[derive(Default, Debug, Clone, Copy, serde::Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -47,8 +49,9 @@ pub enum LogOutputLayer<S, N = DefaultFields, W = fn() -> Stdout> {
     Pretty(fmt::Layer<S, Pretty, Format<Pretty>, W>),
     /// Compact log output
     Compact(fmt::Layer<S, N, Format<Compact>, W>),
-    /// Json log output
-    Json(fmt::Layer<S, JsonFields, Format<Json>, W>),
+    /// Newline-delimited JSON log output, with the correlation id of the
+    /// span of each record
+    Json(fmt::Layer<S, JsonFields, CorrelatedJson, W>),
 }
 
 impl<S> Default for LogOutputLayer<S> {
@@ -63,7 +66,7 @@ impl<S> From<Style> for LogOutputLayer<S> {
             Style::Full => Self::Full(fmt::layer()),
             Style::Pretty => Self::Pretty(fmt::layer().pretty()),
             Style::Compact => Self::Compact(fmt::layer().compact()),
-            Style::Json => Self::Json(fmt::layer().json()),
+            Style::Json => Self::Json(fmt::layer().json().event_format(CorrelatedJson::default())),
         }
     }
 }
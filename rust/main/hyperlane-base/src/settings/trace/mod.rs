@@ -5,12 +5,13 @@ use tracing_subscriber::{
     prelude::*,
 };
 
-use self::fmt::LogOutputLayer;
+use self::{correlation::CorrelationLayer, fmt::LogOutputLayer};
 use crate::{settings::trace::fmt::Style, CoreMetrics};
 
 /// Configure a `tracing_subscriber::fmt` Layer outputting to stdout
 pub mod fmt;
 
+mod correlation;
 mod span_metrics;
 
 /// Logging level. A "higher level" means more will be logged.
@@ -93,6 +94,7 @@ impl TracingConfig {
             .with(tokio_layer)
             .with(target_layer)
             .with(TimeSpanLifetime::new(metrics))
+            .with(CorrelationLayer)
             .with(fmt_layer)
             .with(err_layer);
 
@@ -0,0 +1,137 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use eyre::Result;
+use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
+
+use crate::{AgentMetadata, CheckpointSyncer};
+
+/// A checkpoint syncer that keeps everything written to it in memory
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpointSyncer {
+    latest_index: Mutex<Option<u32>>,
+    checkpoints: Mutex<HashMap<u32, SignedCheckpointWithMessageId>>,
+    checkpoint_batches: Mutex<HashMap<u32, Vec<SignedCheckpointWithMessageId>>>,
+    metadata: Mutex<Option<AgentMetadata>>,
+    announcement: Mutex<Option<SignedAnnouncement>>,
+    reorg_status: Mutex<Option<ReorgEvent>>,
+}
+
+impl InMemoryCheckpointSyncer {
+    /// The last agent metadata written to this syncer
+    pub fn metadata(&self) -> Option<AgentMetadata> {
+        lock(&self.metadata).clone()
+    }
+
+    /// The last announcement written to this syncer
+    pub fn announcement(&self) -> Option<SignedAnnouncement> {
+        lock(&self.announcement).clone()
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for InMemoryCheckpointSyncer {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        Ok(*lock(&self.latest_index))
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        *lock(&self.latest_index) = Some(index);
+        Ok(())
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        Ok(lock(&self.checkpoints).get(&index).cloned())
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        lock(&self.checkpoints).insert(signed_checkpoint.value.index, signed_checkpoint.clone());
+        Ok(())
+    }
+
+    async fn fetch_checkpoint_batch(
+        &self,
+        start_index: u32,
+    ) -> Result<Option<Vec<SignedCheckpointWithMessageId>>> {
+        Ok(lock(&self.checkpoint_batches).get(&start_index).cloned())
+    }
+
+    async fn write_checkpoint_batch(
+        &self,
+        checkpoints: &[SignedCheckpointWithMessageId],
+    ) -> Result<()> {
+        if let Some(first) = checkpoints.first() {
+            lock(&self.checkpoint_batches).insert(first.value.index, checkpoints.to_vec());
+        }
+        Ok(())
+    }
+
+    async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
+        *lock(&self.metadata) = Some(metadata.clone());
+        Ok(())
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        *lock(&self.announcement) = Some(signed_announcement.clone());
+        Ok(())
+    }
+
+    fn announcement_location(&self) -> String {
+        "memory://".to_owned()
+    }
+
+    async fn write_reorg_status(&self, reorg_event: &ReorgEvent) -> Result<()> {
+        *lock(&self.reorg_status) = Some(reorg_event.clone());
+        Ok(())
+    }
+
+    async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
+        Ok(lock(&self.reorg_status).clone())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .expect("in-memory checkpoint syncer lock poisoned")
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, SignedType, H256, U256};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stores_checkpoints_and_latest_index() {
+        let syncer = InMemoryCheckpointSyncer::default();
+        let checkpoint = SignedType {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::random(),
+                    mailbox_domain: 1,
+                    root: H256::random(),
+                    index: 0,
+                },
+                message_id: H256::random(),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        };
+
+        syncer.write_checkpoint(&checkpoint).await.unwrap();
+        syncer.update_latest_index(0).await.unwrap();
+        syncer.update_latest_index(5).await.unwrap();
+        syncer.update_latest_index(3).await.unwrap();
+
+        assert_eq!(syncer.fetch_checkpoint(0).await.unwrap(), Some(checkpoint));
+        assert_eq!(syncer.fetch_checkpoint(1).await.unwrap(), None);
+        assert_eq!(syncer.latest_index().await.unwrap(), Some(5));
+    }
+}
@@ -0,0 +1,57 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// A clock that only moves forward when told to.
+///
+/// Clones share the same time, so a test can hand a clone to the code under
+/// test and advance it from the outside.
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl TestClock {
+    /// Creates a clock that starts at `start`
+    pub fn new(start: Instant) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// The current time of the clock
+    pub fn now(&self) -> Instant {
+        *self.now.lock().expect("test clock lock poisoned")
+    }
+
+    /// Moves the clock forward by `duration` and returns the new time
+    pub fn advance(&self, duration: Duration) -> Instant {
+        let mut now = self.now.lock().expect("test clock lock poisoned");
+        *now += duration;
+        *now
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_time() {
+        let clock = TestClock::default();
+        let start = clock.now();
+        let other = clock.clone();
+
+        other.advance(Duration::from_secs(5));
+
+        assert_eq!(clock.now(), start + Duration::from_secs(5));
+        assert_eq!(other.now(), clock.now());
+    }
+}
@@ -0,0 +1,11 @@
+use prometheus::Registry;
+
+use crate::CoreMetrics;
+
+/// Core metrics for `agent_name` that are registered with a registry of their
+/// own, so that tests can create as many as they like. No HTTP server is
+/// started for them.
+pub fn dummy_core_metrics(agent_name: &str) -> CoreMetrics {
+    CoreMetrics::new(agent_name, 0, Registry::new())
+        .expect("registering metrics with a fresh registry can't fail")
+}
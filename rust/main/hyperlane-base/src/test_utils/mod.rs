@@ -0,0 +1,15 @@
+//! Deterministic stand-ins for the clocks, randomness, checkpoint storage and
+//! metrics that agents use, so that their tests don't need network access or
+//! a real Prometheus registry.
+
+pub use crate::db::test_utils::*;
+
+mod checkpoint_syncer;
+mod clock;
+mod metrics;
+mod rng;
+
+pub use checkpoint_syncer::*;
+pub use clock::*;
+pub use metrics::*;
+pub use rng::*;
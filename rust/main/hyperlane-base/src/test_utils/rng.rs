@@ -0,0 +1,49 @@
+use hyperlane_core::{HyperlaneMessage, H256};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A random number generator that returns the same values for the same seed
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// A random H256
+pub fn random_h256(rng: &mut impl Rng) -> H256 {
+    H256(rng.gen())
+}
+
+/// A message with the given nonce, origin and destination and otherwise
+/// random contents
+pub fn random_message(
+    rng: &mut impl Rng,
+    nonce: u32,
+    origin: u32,
+    destination: u32,
+) -> HyperlaneMessage {
+    let body_len = rng.gen_range(0..256);
+    HyperlaneMessage {
+        version: 3,
+        nonce,
+        origin,
+        sender: random_h256(rng),
+        destination,
+        recipient: random_h256(rng),
+        body: (0..body_len).map(|_| rng.gen()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_values() {
+        let mut a = seeded_rng(42);
+        let mut b = seeded_rng(42);
+
+        assert_eq!(random_h256(&mut a), random_h256(&mut b));
+        assert_eq!(
+            random_message(&mut a, 1, 2, 3),
+            random_message(&mut b, 1, 2, 3)
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
-use eyre::Result;
+use eyre::{bail, Result};
 
 use crate::AgentMetadata;
 use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
@@ -28,6 +28,25 @@ pub trait CheckpointSyncer: Debug + Send + Sync {
         &self,
         signed_checkpoint: &SignedCheckpointWithMessageId,
     ) -> Result<()>;
+    /// Attempt to fetch the batch of signed (checkpoint, messageId) tuples
+    /// starting at this index, which must be a multiple of
+    /// `CHECKPOINT_BATCH_SIZE`. Returns None if the syncer doesn't hold the
+    /// batch or doesn't support batches.
+    async fn fetch_checkpoint_batch(
+        &self,
+        _start_index: u32,
+    ) -> Result<Option<Vec<SignedCheckpointWithMessageId>>> {
+        Ok(None)
+    }
+    /// Write a batch of `CHECKPOINT_BATCH_SIZE` consecutive signed
+    /// (checkpoint, messageId) tuples to this syncer, in addition to the
+    /// individual checkpoints
+    async fn write_checkpoint_batch(
+        &self,
+        _checkpoints: &[SignedCheckpointWithMessageId],
+    ) -> Result<()> {
+        bail!("Checkpoint batches aren't supported by this checkpoint syncer")
+    }
     /// Write the agent metadata to this syncer
     async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()>;
     /// Write the signed announcement to this syncer
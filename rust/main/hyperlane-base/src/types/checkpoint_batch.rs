@@ -0,0 +1,244 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{Read, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use eyre::{ensure, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use hyperlane_core::SignedCheckpointWithMessageId;
+
+/// Number of consecutive checkpoints in a batch. Batches start at multiples
+/// of this size, so readers can tell which batch holds a checkpoint.
+pub const CHECKPOINT_BATCH_SIZE: u32 = 1000;
+
+/// How many batches a cache keeps, about 300 KB each
+const MAX_CACHED_BATCHES: usize = 64;
+/// How long a cache remembers that a batch is missing before fetching it
+/// again, e.g. because the validator hadn't completed it yet
+const MISSING_BATCH_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// The index of the first checkpoint of the batch holding the checkpoint at
+/// `index`
+pub fn checkpoint_batch_start(index: u32) -> u32 {
+    index - index % CHECKPOINT_BATCH_SIZE
+}
+
+/// Serializes a batch of checkpoints as gzipped JSON
+pub(crate) fn encode_checkpoint_batch(
+    checkpoints: &[SignedCheckpointWithMessageId],
+) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&serde_json::to_vec(checkpoints)?)?;
+    Ok(encoder.finish()?)
+}
+
+/// Deserializes a batch of checkpoints written by `encode_checkpoint_batch`,
+/// checking that it holds the complete batch starting at `start_index`
+pub(crate) fn decode_checkpoint_batch(
+    start_index: u32,
+    data: &[u8],
+) -> Result<Vec<SignedCheckpointWithMessageId>> {
+    let mut json = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut json)?;
+    let checkpoints: Vec<SignedCheckpointWithMessageId> = serde_json::from_slice(&json)?;
+    ensure!(
+        checkpoints.len() == CHECKPOINT_BATCH_SIZE as usize
+            && checkpoints
+                .iter()
+                .zip(start_index..)
+                .all(|(checkpoint, index)| checkpoint.value.index == index),
+        "Checkpoint batch starting at {start_index} doesn't hold the checkpoints of the batch"
+    );
+    Ok(checkpoints)
+}
+
+/// Keeps the checkpoint batches recently fetched from checkpoint syncers, so
+/// that relayers catching up on long histories fetch each batch once instead
+/// of each checkpoint.
+#[derive(Debug, Default)]
+pub struct CheckpointBatchCache {
+    inner: Mutex<CachedBatches>,
+}
+
+#[derive(Debug, Default)]
+struct CachedBatches {
+    batches: HashMap<BatchKey, CachedBatch>,
+    /// Keys in the order they were inserted, to evict the oldest first
+    order: VecDeque<BatchKey>,
+}
+
+/// The storage location of the syncer and the start index of the batch
+type BatchKey = (String, u32);
+
+#[derive(Debug, Clone)]
+enum CachedBatch {
+    Found(Arc<Vec<SignedCheckpointWithMessageId>>),
+    Missing { since: Instant },
+}
+
+/// What the cache knows about a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CachedCheckpoint {
+    /// The batch is cached, with the checkpoint at this index
+    Found(SignedCheckpointWithMessageId),
+    /// The batch was recently found to be missing
+    Missing,
+    /// The batch should be fetched
+    Unknown,
+}
+
+impl CheckpointBatchCache {
+    /// Looks up the checkpoint at `index` in the cached batches of the syncer
+    /// at `location`
+    pub(crate) fn get(&self, location: &str, index: u32) -> CachedCheckpoint {
+        self.get_at(location, index, Instant::now())
+    }
+
+    fn get_at(&self, location: &str, index: u32, now: Instant) -> CachedCheckpoint {
+        let start = checkpoint_batch_start(index);
+        let inner = self
+            .inner
+            .lock()
+            .expect("checkpoint batch cache lock poisoned");
+        match inner.batches.get(&(location.to_owned(), start)) {
+            Some(CachedBatch::Found(batch)) => batch
+                .get((index - start) as usize)
+                .cloned()
+                .map(CachedCheckpoint::Found)
+                .unwrap_or(CachedCheckpoint::Missing),
+            Some(CachedBatch::Missing { since })
+                if now.saturating_duration_since(*since) < MISSING_BATCH_TTL =>
+            {
+                CachedCheckpoint::Missing
+            }
+            _ => CachedCheckpoint::Unknown,
+        }
+    }
+
+    /// Caches the batch fetched from the syncer at `location`, or that it's
+    /// missing if `batch` is None
+    pub(crate) fn insert(
+        &self,
+        location: &str,
+        start_index: u32,
+        batch: Option<Vec<SignedCheckpointWithMessageId>>,
+    ) {
+        self.insert_at(location, start_index, batch, Instant::now())
+    }
+
+    fn insert_at(
+        &self,
+        location: &str,
+        start_index: u32,
+        batch: Option<Vec<SignedCheckpointWithMessageId>>,
+        now: Instant,
+    ) {
+        let batch = match batch {
+            Some(batch) => CachedBatch::Found(Arc::new(batch)),
+            None => CachedBatch::Missing { since: now },
+        };
+        let key = (location.to_owned(), start_index);
+        let mut inner = self
+            .inner
+            .lock()
+            .expect("checkpoint batch cache lock poisoned");
+        if inner.batches.insert(key.clone(), batch).is_none() {
+            inner.order.push_back(key);
+        }
+        while inner.order.len() > MAX_CACHED_BATCHES {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.batches.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Signature, SignedType, H256, U256};
+
+    use super::*;
+
+    fn checkpoint(index: u32) -> SignedCheckpointWithMessageId {
+        SignedType {
+            value: CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::zero(),
+                    mailbox_domain: 1,
+                    root: H256::from_low_u64_be(index as u64),
+                    index,
+                },
+                message_id: H256::from_low_u64_be(index as u64),
+            },
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            },
+        }
+    }
+
+    fn batch(start_index: u32) -> Vec<SignedCheckpointWithMessageId> {
+        (start_index..start_index + CHECKPOINT_BATCH_SIZE)
+            .map(checkpoint)
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_roundtrip() {
+        let checkpoints = batch(2000);
+        let data = encode_checkpoint_batch(&checkpoints).unwrap();
+        assert!(data.len() < serde_json::to_vec(&checkpoints).unwrap().len());
+        assert_eq!(decode_checkpoint_batch(2000, &data).unwrap(), checkpoints);
+
+        // A batch read at the wrong location or cut short is rejected
+        assert!(decode_checkpoint_batch(3000, &data).is_err());
+        let partial = encode_checkpoint_batch(&checkpoints[..10]).unwrap();
+        assert!(decode_checkpoint_batch(2000, &partial).is_err());
+    }
+
+    #[test]
+    fn test_cache_finds_checkpoints_and_forgets_missing_batches() {
+        let cache = CheckpointBatchCache::default();
+        let start = Instant::now();
+        assert_eq!(
+            cache.get_at("s3://a", 1234, start),
+            CachedCheckpoint::Unknown
+        );
+
+        cache.insert_at("s3://a", 1000, Some(batch(1000)), start);
+        cache.insert_at("s3://b", 1000, None, start);
+        assert_eq!(
+            cache.get_at("s3://a", 1234, start),
+            CachedCheckpoint::Found(checkpoint(1234))
+        );
+        assert_eq!(
+            cache.get_at("s3://a", 2000, start),
+            CachedCheckpoint::Unknown
+        );
+        assert_eq!(
+            cache.get_at("s3://b", 1234, start),
+            CachedCheckpoint::Missing
+        );
+        assert_eq!(
+            cache.get_at("s3://b", 1234, start + MISSING_BATCH_TTL),
+            CachedCheckpoint::Unknown
+        );
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest_batches() {
+        let cache = CheckpointBatchCache::default();
+        let now = Instant::now();
+        for i in 0..=MAX_CACHED_BATCHES as u32 {
+            cache.insert_at("s3://a", i * CHECKPOINT_BATCH_SIZE, None, now);
+        }
+        assert_eq!(cache.get_at("s3://a", 0, now), CachedCheckpoint::Unknown);
+        assert_eq!(
+            cache.get_at("s3://a", CHECKPOINT_BATCH_SIZE, now),
+            CachedCheckpoint::Missing
+        );
+    }
+}
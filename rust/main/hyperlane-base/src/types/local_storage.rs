@@ -1,9 +1,10 @@
 use std::path::PathBuf;
 
 use crate::traits::CheckpointSyncer;
+use crate::types::checkpoint_batch::{decode_checkpoint_batch, encode_checkpoint_batch};
 use crate::AgentMetadata;
 use async_trait::async_trait;
-use eyre::{Context, Result};
+use eyre::{eyre, Context, Result};
 use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
 use prometheus::IntGauge;
 
@@ -33,6 +34,11 @@ impl LocalStorage {
         self.path.join(format!("{}_with_id.json", index))
     }
 
+    fn checkpoint_batch_file_path(&self, start_index: u32) -> PathBuf {
+        self.path
+            .join(format!("checkpoint_batch_{}_with_id.json.gz", start_index))
+    }
+
     fn latest_index_file_path(&self) -> PathBuf {
         self.path.join("index.json")
     }
@@ -99,6 +105,33 @@ impl CheckpointSyncer for LocalStorage {
         Ok(())
     }
 
+    async fn fetch_checkpoint_batch(
+        &self,
+        start_index: u32,
+    ) -> Result<Option<Vec<SignedCheckpointWithMessageId>>> {
+        let Ok(data) = tokio::fs::read(self.checkpoint_batch_file_path(start_index)).await else {
+            return Ok(None);
+        };
+        Ok(Some(decode_checkpoint_batch(start_index, &data)?))
+    }
+
+    async fn write_checkpoint_batch(
+        &self,
+        checkpoints: &[SignedCheckpointWithMessageId],
+    ) -> Result<()> {
+        let start_index = checkpoints
+            .first()
+            .ok_or_else(|| eyre!("Empty checkpoint batch"))?
+            .value
+            .index;
+        let data = encode_checkpoint_batch(checkpoints)?;
+        let path = self.checkpoint_batch_file_path(start_index);
+        tokio::fs::write(&path, &data)
+            .await
+            .with_context(|| format!("Writing checkpoint batch to {path:?}"))?;
+        Ok(())
+    }
+
     async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
         let serialized_metadata = serde_json::to_string_pretty(metadata)?;
         let path = self.metadata_file_path();
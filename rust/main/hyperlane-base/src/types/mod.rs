@@ -1,12 +1,16 @@
+mod checkpoint_batch;
 mod gcs_storage;
 mod local_storage;
 mod multisig;
 mod s3_storage;
+mod validator_announce_index;
 
 /// Reusable logic for working with storage backends.
 pub mod utils;
 
+pub use checkpoint_batch::*;
 pub use gcs_storage::*;
 pub use local_storage::*;
 pub use multisig::*;
 pub use s3_storage::*;
+pub use validator_announce_index::*;
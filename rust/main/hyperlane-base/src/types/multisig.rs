@@ -6,9 +6,13 @@ use eyre::Result;
 use tracing::{debug, instrument, warn};
 
 use hyperlane_core::{
-    HyperlaneDomain, MultisigSignedCheckpoint, SignedCheckpointWithMessageId, H160, H256,
+    HashAlgorithm, HyperlaneDomain, MultisigSignedCheckpoint, SignedCheckpointWithMessageId, H160,
+    H256,
 };
 
+use crate::types::checkpoint_batch::{
+    checkpoint_batch_start, CachedCheckpoint, CheckpointBatchCache,
+};
 use crate::{CheckpointSyncer, CoreMetrics};
 
 /// For a particular validator set, fetches signed checkpoints from multiple
@@ -19,6 +23,12 @@ pub struct MultisigCheckpointSyncer {
     checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>>,
     metrics: Arc<CoreMetrics>,
     app_context: Option<String>,
+    /// The hash function the origin chain's contracts compute checkpoint
+    /// digests with
+    hash_algorithm: HashAlgorithm,
+    /// Checkpoint batches recently fetched from the validators, shared
+    /// across syncers
+    batch_cache: Arc<CheckpointBatchCache>,
 }
 
 impl MultisigCheckpointSyncer {
@@ -163,7 +173,9 @@ impl MultisigCheckpointSyncer {
                 // Gracefully ignore an error fetching the checkpoint from a validator's
                 // checkpoint syncer, which can happen if the validator has not
                 // signed the checkpoint at `index`.
-                if let Ok(Some(signed_checkpoint)) = checkpoint_syncer.fetch_checkpoint(index).await
+                if let Ok(Some(signed_checkpoint)) = self
+                    .fetch_validator_checkpoint(checkpoint_syncer.as_ref(), index)
+                    .await
                 {
                     // If the signed checkpoint is for a different index, ignore it
                     if signed_checkpoint.value.index != index {
@@ -177,7 +189,7 @@ impl MultisigCheckpointSyncer {
                     }
 
                     // Ensure that the signature is actually by the validator
-                    let signer = signed_checkpoint.recover()?;
+                    let signer = signed_checkpoint.recover_with_hasher(&self.hash_algorithm)?;
 
                     if H256::from(signer) != *validator {
                         debug!(
@@ -224,4 +236,59 @@ impl MultisigCheckpointSyncer {
         debug!("No quorum checkpoint found for message");
         Ok(None)
     }
+
+    /// Fetches the signed checkpoint at `index` from a validator's syncer,
+    /// from the batch holding it if the validator writes batches, or else on
+    /// its own.
+    async fn fetch_validator_checkpoint(
+        &self,
+        checkpoint_syncer: &dyn CheckpointSyncer,
+        index: u32,
+    ) -> Result<Option<SignedCheckpointWithMessageId>> {
+        let location = checkpoint_syncer.announcement_location();
+        let batch_fetched = match self.batch_cache.get(&location, index) {
+            CachedCheckpoint::Found(checkpoint) => return Ok(Some(checkpoint)),
+            CachedCheckpoint::Missing => false,
+            CachedCheckpoint::Unknown => {
+                if let Some(checkpoint) = self
+                    .fetch_from_batch(checkpoint_syncer, &location, index)
+                    .await
+                {
+                    return Ok(Some(checkpoint));
+                }
+                true
+            }
+        };
+        let checkpoint = checkpoint_syncer.fetch_checkpoint(index).await?;
+        if checkpoint.is_none() && !batch_fetched {
+            // Validators that only write older checkpoints in their batch may
+            // have written it since it was found missing
+            return Ok(self
+                .fetch_from_batch(checkpoint_syncer, &location, index)
+                .await);
+        }
+        Ok(checkpoint)
+    }
+
+    /// Fetches and caches the batch holding the checkpoint at `index`
+    async fn fetch_from_batch(
+        &self,
+        checkpoint_syncer: &dyn CheckpointSyncer,
+        location: &str,
+        index: u32,
+    ) -> Option<SignedCheckpointWithMessageId> {
+        let start_index = checkpoint_batch_start(index);
+        let batch = checkpoint_syncer
+            .fetch_checkpoint_batch(start_index)
+            .await
+            .unwrap_or_else(|err| {
+                debug!(?err, %location, start_index, "Failed to fetch checkpoint batch");
+                None
+            });
+        self.batch_cache.insert(location, start_index, batch);
+        match self.batch_cache.get(location, index) {
+            CachedCheckpoint::Found(checkpoint) => Some(checkpoint),
+            _ => None,
+        }
+    }
 }
@@ -2,7 +2,7 @@ use std::{fmt, sync::OnceLock, time::Duration};
 
 use async_trait::async_trait;
 use derive_new::new;
-use eyre::{bail, Result};
+use eyre::{bail, eyre, Result};
 use futures_util::TryStreamExt;
 use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
 use prometheus::IntGauge;
@@ -13,6 +13,7 @@ use rusoto_core::{
 use rusoto_s3::{GetObjectError, GetObjectRequest, PutObjectRequest, S3Client, S3};
 use tokio::time::timeout;
 
+use crate::types::checkpoint_batch::{decode_checkpoint_batch, encode_checkpoint_batch};
 use crate::types::utils;
 use crate::{
     settings::aws_credentials::AwsChainCredentialsProvider, AgentMetadata, CheckpointSyncer,
@@ -54,11 +55,21 @@ impl fmt::Debug for S3Storage {
 
 impl S3Storage {
     async fn write_to_bucket(&self, key: String, body: &str) -> Result<()> {
+        self.write_bytes_to_bucket(key, Vec::from(body), "application/json")
+            .await
+    }
+
+    async fn write_bytes_to_bucket(
+        &self,
+        key: String,
+        body: Vec<u8>,
+        content_type: &str,
+    ) -> Result<()> {
         let req = PutObjectRequest {
             key: self.get_composite_key(key),
             bucket: self.bucket.clone(),
-            body: Some(Vec::from(body).into()),
-            content_type: Some("application/json".to_owned()),
+            body: Some(body.into()),
+            content_type: Some(content_type.to_owned()),
             ..Default::default()
         };
         timeout(
@@ -134,6 +145,10 @@ impl S3Storage {
         format!("checkpoint_{index}_with_id.json")
     }
 
+    fn checkpoint_batch_key(start_index: u32) -> String {
+        format!("checkpoint_batch_{start_index}_with_id.json.gz")
+    }
+
     fn latest_index_key() -> String {
         "checkpoint_latest_index.json".to_owned()
     }
@@ -198,6 +213,34 @@ impl CheckpointSyncer for S3Storage {
         Ok(())
     }
 
+    async fn fetch_checkpoint_batch(
+        &self,
+        start_index: u32,
+    ) -> Result<Option<Vec<SignedCheckpointWithMessageId>>> {
+        self.anonymously_read_from_bucket(S3Storage::checkpoint_batch_key(start_index))
+            .await?
+            .map(|data| decode_checkpoint_batch(start_index, &data))
+            .transpose()
+    }
+
+    async fn write_checkpoint_batch(
+        &self,
+        checkpoints: &[SignedCheckpointWithMessageId],
+    ) -> Result<()> {
+        let start_index = checkpoints
+            .first()
+            .ok_or_else(|| eyre!("Empty checkpoint batch"))?
+            .value
+            .index;
+        self.write_bytes_to_bucket(
+            S3Storage::checkpoint_batch_key(start_index),
+            encode_checkpoint_batch(checkpoints)?,
+            "application/gzip",
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
         let serialized_metadata = serde_json::to_string_pretty(metadata)?;
         self.write_to_bucket(S3Storage::metadata_key(), &serialized_metadata)
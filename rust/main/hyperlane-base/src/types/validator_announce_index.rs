@@ -0,0 +1,402 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, HyperlaneChain, ValidatorAnnounce, H160, H256,
+};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+use crate::{
+    db::{AnnouncedStorageLocations, HyperlaneRocksDB},
+    settings::{CheckpointSyncerBuildError, CheckpointSyncerConf},
+    CheckpointSyncer,
+};
+
+/// How many validators' storage locations are fetched in a single call while
+/// indexing
+const INDEX_BATCH_SIZE: usize = 100;
+
+/// Resolves the storage locations announced by the validators of an origin
+/// and builds their checkpoint syncers from them, so that the storage of a
+/// validator never has to be configured.
+///
+/// Locations are stored in the origin's database and used for up to the
+/// TTL, so that they survive restarts. Validators without an announced
+/// location aren't stored, so that their announcements are picked up right
+/// away. When run, the index keeps the locations of all the validators
+/// announced on the origin fresh, so that they're rarely fetched while
+/// building metadata.
+#[derive(Debug)]
+pub struct ValidatorAnnounceIndex {
+    validator_announce: Arc<dyn ValidatorAnnounce>,
+    db: HyperlaneRocksDB,
+    ttl: Duration,
+    allow_local_checkpoint_syncers: bool,
+}
+
+impl ValidatorAnnounceIndex {
+    /// Create an index of the locations announced on `validator_announce`,
+    /// stored in the database of its origin
+    pub fn new(
+        validator_announce: Arc<dyn ValidatorAnnounce>,
+        db: HyperlaneRocksDB,
+        ttl: Duration,
+        allow_local_checkpoint_syncers: bool,
+    ) -> Self {
+        Self {
+            validator_announce,
+            db,
+            ttl,
+            allow_local_checkpoint_syncers,
+        }
+    }
+
+    /// The ValidatorAnnounce contract the locations are announced on
+    pub fn validator_announce(&self) -> &Arc<dyn ValidatorAnnounce> {
+        &self.validator_announce
+    }
+
+    /// The storage locations announced by each of the validators, in the same
+    /// order. Those that aren't stored or are stale are fetched with a single
+    /// call.
+    pub async fn storage_locations(&self, validators: &[H256]) -> ChainResult<Vec<Vec<String>>> {
+        let now = unix_timestamp();
+        let stored: Vec<_> = validators
+            .iter()
+            .map(|validator| self.stored_locations(validator, now))
+            .collect();
+        let mut missing: Vec<H256> = validators
+            .iter()
+            .zip(&stored)
+            .filter(|(_, locations)| locations.is_none())
+            .map(|(validator, _)| *validator)
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        let mut fetched = HashMap::new();
+        if !missing.is_empty() {
+            debug!(
+                stored = validators.len() - missing.len(),
+                missing = missing.len(),
+                "Fetching announced storage locations"
+            );
+            fetched = self.fetch(&missing, now).await?;
+        }
+        Ok(validators
+            .iter()
+            .zip(stored)
+            .map(|(validator, locations)| {
+                locations
+                    .or_else(|| fetched.get(validator).cloned())
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    fn stored_locations(&self, validator: &H256, now: u64) -> Option<Vec<String>> {
+        let stored = match self.db.retrieve_announced_storage_locations(validator) {
+            Ok(stored) => stored?,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    ?validator,
+                    "Failed to retrieve stored storage locations"
+                );
+                return None;
+            }
+        };
+        (now.saturating_sub(stored.fetched_at) < self.ttl.as_secs()).then_some(stored.locations)
+    }
+
+    /// Fetches the locations of the validators and stores those of the
+    /// validators that announced any
+    async fn fetch(
+        &self,
+        validators: &[H256],
+        now: u64,
+    ) -> ChainResult<HashMap<H256, Vec<String>>> {
+        let fetched = self
+            .validator_announce
+            .get_announced_storage_locations(validators)
+            .await?;
+        let mut by_validator = HashMap::with_capacity(validators.len());
+        for (validator, locations) in validators.iter().zip(fetched) {
+            if !locations.is_empty() {
+                let stored = AnnouncedStorageLocations {
+                    fetched_at: now,
+                    locations: locations.clone(),
+                };
+                if let Err(err) = self
+                    .db
+                    .store_announced_storage_locations(validator, &stored)
+                {
+                    warn!(
+                        ?err,
+                        ?validator,
+                        "Failed to store announced storage locations"
+                    );
+                }
+            }
+            by_validator.insert(*validator, locations);
+        }
+        Ok(by_validator)
+    }
+
+    /// Fetches and stores the locations of all the validators announced on
+    /// the origin. Returns how many validators were announced.
+    pub async fn index(&self) -> ChainResult<usize> {
+        let validators = self.validator_announce.announced_validators().await?;
+        let now = unix_timestamp();
+        for batch in validators.chunks(INDEX_BATCH_SIZE) {
+            self.fetch(batch, now).await?;
+        }
+        Ok(validators.len())
+    }
+
+    /// Indexes the announced validators every `interval`, until it turns out
+    /// they can't be listed on the origin
+    pub async fn run(self: Arc<Self>, interval: Duration) {
+        let origin = self.validator_announce.domain().clone();
+        let origin = origin.name();
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            match self.index().await {
+                Ok(num_validators) => {
+                    debug!(origin, num_validators, "Indexed announced validators")
+                }
+                Err(ChainCommunicationError::AnnouncedValidatorsUnsupported) => {
+                    info!(
+                        origin,
+                        "The announced validators can't be listed on this origin, not indexing them"
+                    );
+                    return;
+                }
+                // Retried at the next interval
+                Err(err) => warn!(?err, origin, "Failed to index announced validators"),
+            }
+        }
+    }
+
+    /// Builds a checkpoint syncer for each of the validators from the most
+    /// recent of their announced locations that can be built. Validators
+    /// without one are left out.
+    pub async fn checkpoint_syncers(
+        &self,
+        validators: &[H256],
+    ) -> Result<HashMap<H160, Arc<dyn CheckpointSyncer>>, CheckpointSyncerBuildError> {
+        let storage_locations = self.storage_locations(validators).await?;
+
+        let mut checkpoint_syncers: HashMap<H160, Arc<dyn CheckpointSyncer>> = HashMap::new();
+        for (&validator, validator_storage_locations) in validators.iter().zip(storage_locations) {
+            debug!(
+                ?validator,
+                ?validator_storage_locations,
+                "Validator and its storage locations"
+            );
+            for storage_location in validator_storage_locations.iter().rev() {
+                let Ok(config) = CheckpointSyncerConf::from_str(storage_location) else {
+                    debug!(
+                        ?validator,
+                        ?storage_location,
+                        "Could not parse checkpoint syncer config for validator"
+                    );
+                    continue;
+                };
+
+                // If this is a LocalStorage based checkpoint syncer and it's not
+                // allowed, ignore it
+                if !self.allow_local_checkpoint_syncers
+                    && matches!(config, CheckpointSyncerConf::LocalStorage { .. })
+                {
+                    debug!(
+                        ?config,
+                        "Ignoring disallowed LocalStorage based checkpoint syncer"
+                    );
+                    continue;
+                }
+
+                match config.build_and_validate(None).await {
+                    Ok(checkpoint_syncer) => {
+                        // found the syncer for this validator
+                        checkpoint_syncers.insert(validator.into(), checkpoint_syncer.into());
+                        break;
+                    }
+                    Err(CheckpointSyncerBuildError::ReorgEvent(reorg_event)) => {
+                        // If a reorg event has been posted to a checkpoint syncer,
+                        // we refuse to build
+                        return Err(CheckpointSyncerBuildError::ReorgEvent(reorg_event));
+                    }
+                    Err(err) => {
+                        debug!(
+                            error=%err,
+                            ?config,
+                            ?validator,
+                            "Error when loading checkpoint syncer; will attempt to use the next config"
+                        );
+                    }
+                }
+            }
+            if !checkpoint_syncers.contains_key(&validator.into()) {
+                if validator_storage_locations.is_empty() {
+                    warn!(?validator, "Validator has not announced any storage locations; see https://docs.hyperlane.xyz/docs/operators/validators/announcing-your-validator");
+                } else {
+                    warn!(
+                        ?validator,
+                        ?validator_storage_locations,
+                        "No valid checkpoint syncer configs for validator"
+                    );
+                }
+            }
+        }
+        Ok(checkpoint_syncers)
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use hyperlane_core::{
+        Announcement, HyperlaneContract, HyperlaneDomain, HyperlaneProvider, KnownHyperlaneDomain,
+        SignedType, TxOutcome, U256,
+    };
+
+    use super::*;
+    use crate::db::test_utils;
+
+    #[derive(Debug)]
+    struct MockValidatorAnnounce {
+        domain: HyperlaneDomain,
+        locations: HashMap<H256, Vec<String>>,
+        requests: Mutex<Vec<Vec<H256>>>,
+    }
+
+    impl HyperlaneChain for MockValidatorAnnounce {
+        fn domain(&self) -> &HyperlaneDomain {
+            &self.domain
+        }
+
+        fn provider(&self) -> Box<dyn HyperlaneProvider> {
+            unimplemented!()
+        }
+    }
+
+    impl HyperlaneContract for MockValidatorAnnounce {
+        fn address(&self) -> H256 {
+            H256::zero()
+        }
+    }
+
+    #[async_trait]
+    impl ValidatorAnnounce for MockValidatorAnnounce {
+        async fn get_announced_storage_locations(
+            &self,
+            validators: &[H256],
+        ) -> ChainResult<Vec<Vec<String>>> {
+            self.requests.lock().unwrap().push(validators.to_vec());
+            Ok(validators
+                .iter()
+                .map(|validator| self.locations.get(validator).cloned().unwrap_or_default())
+                .collect())
+        }
+
+        async fn announced_validators(&self) -> ChainResult<Vec<H256>> {
+            Ok(self.locations.keys().copied().collect())
+        }
+
+        async fn announce(&self, _: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+            unimplemented!()
+        }
+
+        async fn announce_tokens_needed(&self, _: SignedType<Announcement>) -> Option<U256> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_announced_locations_are_stored_until_stale() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+            let (announced, unannounced) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+            let validator_announce = Arc::new(MockValidatorAnnounce {
+                domain: domain.clone(),
+                locations: HashMap::from([(announced, vec!["s3://bucket/region".to_owned()])]),
+                requests: Default::default(),
+            });
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let index = ValidatorAnnounceIndex::new(
+                validator_announce.clone(),
+                db.clone(),
+                Duration::from_secs(60),
+                false,
+            );
+
+            let locations = index
+                .storage_locations(&[announced, unannounced])
+                .await
+                .unwrap();
+            assert_eq!(
+                locations,
+                vec![vec!["s3://bucket/region".to_owned()], vec![]]
+            );
+            // Only the validator without a location is fetched again
+            index
+                .storage_locations(&[announced, unannounced])
+                .await
+                .unwrap();
+            assert_eq!(
+                *validator_announce.requests.lock().unwrap(),
+                vec![vec![announced, unannounced], vec![unannounced]]
+            );
+
+            // Stale locations are fetched again, even by a new index
+            let stale =
+                ValidatorAnnounceIndex::new(validator_announce.clone(), db, Duration::ZERO, false);
+            stale.storage_locations(&[announced]).await.unwrap();
+            assert_eq!(validator_announce.requests.lock().unwrap().len(), 3);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_index_stores_all_announced_validators() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+            let validator = H256::from_low_u64_be(1);
+            let validator_announce = Arc::new(MockValidatorAnnounce {
+                domain: domain.clone(),
+                locations: HashMap::from([(validator, vec!["file:///tmp/validator".to_owned()])]),
+                requests: Default::default(),
+            });
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let index =
+                ValidatorAnnounceIndex::new(validator_announce, db.clone(), Duration::MAX, false);
+
+            assert_eq!(index.index().await.unwrap(), 1);
+            assert_eq!(
+                db.retrieve_announced_storage_locations(&validator)
+                    .unwrap()
+                    .unwrap()
+                    .locations,
+                vec!["file:///tmp/validator".to_owned()]
+            );
+        })
+        .await;
+    }
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use hyperlane_core::{Decode, Encode, HyperlaneMessage};
+use libfuzzer_sys::fuzz_target;
+
+// Messages are decoded from the logs and accounts of every indexed chain
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = HyperlaneMessage::read_from(&mut &data[..]) {
+        assert_eq!(message.to_vec(), data);
+        let _ = message.id();
+        let _ = message.to_string();
+    }
+});
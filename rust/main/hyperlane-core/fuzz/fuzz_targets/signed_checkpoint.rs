@@ -0,0 +1,16 @@
+#![no_main]
+
+use hyperlane_core::{Signable, SignedCheckpointWithMessageId};
+use libfuzzer_sys::fuzz_target;
+
+// Signed checkpoints are read from the storage locations validators announce,
+// which anyone can write to
+fuzz_target!(|data: &[u8]| {
+    if let Ok(signed) = serde_json::from_slice::<SignedCheckpointWithMessageId>(data) {
+        let _ = signed.value.signing_hash();
+        let _ = signed.value.eth_signed_message_hash();
+        let json = serde_json::to_vec(&signed).unwrap();
+        let decoded: SignedCheckpointWithMessageId = serde_json::from_slice(&json).unwrap();
+        assert_eq!(decoded, signed);
+    }
+});
@@ -14,6 +14,8 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(feature = "strum")]
 use strum::{EnumIter, EnumString, IntoStaticStr};
 
+#[cfg(feature = "strum")]
+use crate::DomainRegistry;
 use crate::{
     utils::many_to_one, ChainCommunicationError, HyperlaneProtocolError, IndexMode, H160, H256,
 };
@@ -489,15 +491,20 @@ pub enum HyperlaneDomainConfigError {
     UnknownDomainName(String),
     #[error("The domain name (`{0}`) implies a different domain than the domain id provided; the domain id ({1}) is probably wrong.")]
     DomainNameMismatch(String, u32),
+    #[error("The chain `{0}` with domain id {1} doesn't match the domain registry, which lists `{2}` with domain id {3}.")]
+    RegistryMismatch(String, u32, String, u32),
 }
 
 impl HyperlaneDomain {
+    /// The domain of a chain in the agent config. Domains that aren't known
+    /// take their type from the registry, if it lists them.
     #[cfg(feature = "strum")]
     pub fn from_config(
         domain_id: u32,
         name: &str,
         protocol: HyperlaneDomainProtocol,
         domain_technical_stack: HyperlaneDomainTechnicalStack,
+        registry: &DomainRegistry,
     ) -> Result<Self, HyperlaneDomainConfigError> {
         let name = name.to_ascii_lowercase();
         if let Ok(domain) = KnownHyperlaneDomain::try_from(domain_id) {
@@ -511,12 +518,26 @@ impl HyperlaneDomain {
                 name, domain_id,
             ))
         } else {
+            let registered = registry
+                .by_id(domain_id)
+                .or_else(|| registry.by_name(&name));
+            let domain_type = match registered {
+                Some(metadata) if metadata.id != domain_id || metadata.name != name => {
+                    return Err(HyperlaneDomainConfigError::RegistryMismatch(
+                        name,
+                        domain_id,
+                        metadata.name.clone(),
+                        metadata.id,
+                    ))
+                }
+                Some(metadata) => metadata.domain_type,
+                None => HyperlaneDomainType::Unknown,
+            };
             Ok(HyperlaneDomain::Unknown {
                 domain_id,
                 domain_name: name,
                 domain_protocol: protocol,
-                // we might want to support accepting this from the config later
-                domain_type: HyperlaneDomainType::Unknown,
+                domain_type,
                 domain_technical_stack,
             })
         }
@@ -577,6 +598,13 @@ impl HyperlaneDomain {
         )
     }
 
+    pub const fn is_op_stack(&self) -> bool {
+        matches!(
+            self.domain_technical_stack(),
+            HyperlaneDomainTechnicalStack::OpStack
+        )
+    }
+
     pub const fn is_injective(&self) -> bool {
         matches!(self, Self::Known(KnownHyperlaneDomain::Injective))
     }
@@ -603,7 +631,10 @@ impl HyperlaneDomain {
 mod tests {
     use std::{num::NonZeroU32, str::FromStr};
 
-    use crate::{KnownHyperlaneDomain, ReorgPeriod};
+    use crate::{
+        DomainRegistry, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneDomainTechnicalStack,
+        HyperlaneDomainType, KnownHyperlaneDomain, ReorgPeriod,
+    };
 
     #[test]
     fn domain_strings() {
@@ -657,6 +688,29 @@ mod tests {
         assert!("foo".parse::<KnownHyperlaneDomain>().is_err());
     }
 
+    #[test]
+    fn test_domain_from_config_uses_registry() {
+        let registry = DomainRegistry::embedded();
+        let from_config = |domain_id, name| {
+            HyperlaneDomain::from_config(
+                domain_id,
+                name,
+                HyperlaneDomainProtocol::Ethereum,
+                HyperlaneDomainTechnicalStack::OpStack,
+                registry,
+            )
+        };
+
+        let base = from_config(8453, "base").unwrap();
+        assert_eq!(base.domain_type(), HyperlaneDomainType::Mainnet);
+        let unregistered = from_config(4242424242, "newchain").unwrap();
+        assert_eq!(unregistered.domain_type(), HyperlaneDomainType::Unknown);
+
+        // A registered name with another domain id, and the other way around
+        assert!(from_config(4242424242, "base").is_err());
+        assert!(from_config(8453, "newchain").is_err());
+    }
+
     #[test]
     fn parse_reorg_period() {
         assert_eq!(
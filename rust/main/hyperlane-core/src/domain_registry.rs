@@ -0,0 +1,296 @@
+use std::{collections::HashMap, path::Path, sync::OnceLock};
+
+use serde::Deserialize;
+
+use crate::{
+    HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneDomainTechnicalStack, HyperlaneDomainType,
+    KnownHyperlaneDomain, ReorgPeriod,
+};
+
+/// The registry compiled into the agents, built from the chains in the agent
+/// configs shipped with them
+const EMBEDDED_DOMAIN_REGISTRY: &str = include_str!("domain_registry.json");
+
+/// What is known about a domain without connecting to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainMetadata {
+    /// The domain id
+    pub id: u32,
+    /// The chain name, in lowercase
+    pub name: String,
+    /// The protocol the chain implements
+    pub protocol: HyperlaneDomainProtocol,
+    /// Whether it's a mainnet or a testnet
+    pub domain_type: HyperlaneDomainType,
+    /// The stack the chain is built with
+    pub technical_stack: HyperlaneDomainTechnicalStack,
+    /// How many blocks to wait for before considering a block final
+    pub finality: ReorgPeriod,
+    /// The decimals of the chain's native token
+    pub native_token_decimals: u32,
+}
+
+/// An entry of a domain registry artifact. The keys are the ones of chain
+/// metadata.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawDomainMetadata {
+    domain_id: u32,
+    name: String,
+    protocol: String,
+    #[serde(default)]
+    is_testnet: bool,
+    technical_stack: Option<String>,
+    reorg_period: ReorgPeriod,
+    native_token_decimals: u32,
+}
+
+/// Error loading a domain registry
+#[derive(thiserror::Error, Debug)]
+pub enum DomainRegistryError {
+    /// The artifact couldn't be read
+    #[error("Failed to read domain registry `{0}`: {1}")]
+    Io(String, #[source] std::io::Error),
+    /// The artifact isn't a list of domains
+    #[error("Invalid domain registry: {0}")]
+    Json(#[from] serde_json::Error),
+    /// An entry of the artifact is invalid
+    #[error("Invalid domain registry entry `{0}`: {1}")]
+    InvalidEntry(String, String),
+}
+
+/// Domains by id, loaded from a JSON artifact so that agents can support new
+/// chains without `KnownHyperlaneDomain` growing.
+///
+/// The artifact is a list of chain metadata subsets, i.e. objects with a
+/// `domainId`, `name`, `protocol`, `isTestnet`, `technicalStack`,
+/// `reorgPeriod` and `nativeTokenDecimals`. An artifact is embedded in the
+/// agents, and one loaded at startup can add domains to it or override them.
+/// Entries of known domains must match their id and name.
+#[derive(Debug, Clone, Default)]
+pub struct DomainRegistry {
+    domains: HashMap<u32, DomainMetadata>,
+}
+
+impl DomainRegistry {
+    /// The registry embedded in the agents
+    pub fn embedded() -> &'static Self {
+        static EMBEDDED: OnceLock<DomainRegistry> = OnceLock::new();
+        EMBEDDED.get_or_init(|| {
+            Self::from_json(EMBEDDED_DOMAIN_REGISTRY).expect("Invalid embedded domain registry")
+        })
+    }
+
+    /// Parses a registry artifact
+    pub fn from_json(json: &str) -> Result<Self, DomainRegistryError> {
+        let raw: Vec<RawDomainMetadata> = serde_json::from_str(json)?;
+        let mut registry = Self::default();
+        for raw in raw {
+            let metadata = parse_metadata(raw)?;
+            if let Some(existing) = registry.by_name(&metadata.name) {
+                return Err(DomainRegistryError::InvalidEntry(
+                    metadata.name.clone(),
+                    format!("the name is also used by domain {}", existing.id),
+                ));
+            }
+            if let Some(existing) = registry.domains.get(&metadata.id) {
+                return Err(DomainRegistryError::InvalidEntry(
+                    metadata.name.clone(),
+                    format!("the domain id is also used by `{}`", existing.name),
+                ));
+            }
+            registry.domains.insert(metadata.id, metadata);
+        }
+        Ok(registry)
+    }
+
+    /// Reads and parses a registry artifact
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, DomainRegistryError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| DomainRegistryError::Io(path.display().to_string(), err))?;
+        Self::from_json(&json)
+    }
+
+    /// Adds the domains of another registry, overriding the ones with the
+    /// same id or name
+    pub fn extend(&mut self, other: Self) {
+        for (id, metadata) in other.domains {
+            self.domains.retain(|existing_id, existing| {
+                *existing_id == id || existing.name != metadata.name
+            });
+            self.domains.insert(id, metadata);
+        }
+    }
+
+    /// The metadata of the domain with this id
+    pub fn by_id(&self, domain_id: u32) -> Option<&DomainMetadata> {
+        self.domains.get(&domain_id)
+    }
+
+    /// The metadata of the domain with this name, compared in lowercase
+    pub fn by_name(&self, name: &str) -> Option<&DomainMetadata> {
+        let name = name.to_ascii_lowercase();
+        self.domains.values().find(|metadata| metadata.name == name)
+    }
+
+    /// The domain with this id, if it's known or in the registry
+    pub fn domain(&self, domain_id: u32) -> Option<HyperlaneDomain> {
+        if let Ok(known) = KnownHyperlaneDomain::try_from(domain_id) {
+            return Some(HyperlaneDomain::Known(known));
+        }
+        self.by_id(domain_id)
+            .map(|metadata| HyperlaneDomain::Unknown {
+                domain_id,
+                domain_name: metadata.name.clone(),
+                domain_type: metadata.domain_type,
+                domain_protocol: metadata.protocol,
+                domain_technical_stack: metadata.technical_stack,
+            })
+    }
+
+    /// The number of domains in the registry
+    pub fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    /// Whether the registry has no domains
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+}
+
+fn parse_metadata(raw: RawDomainMetadata) -> Result<DomainMetadata, DomainRegistryError> {
+    let name = raw.name.to_ascii_lowercase();
+    let invalid = |reason: String| DomainRegistryError::InvalidEntry(name.clone(), reason);
+
+    if let Ok(known) = KnownHyperlaneDomain::try_from(raw.domain_id) {
+        if known.as_str() != name {
+            return Err(invalid(format!(
+                "domain id {} is the one of `{}`",
+                raw.domain_id,
+                known.as_str()
+            )));
+        }
+    } else if name.parse::<KnownHyperlaneDomain>().is_ok() {
+        return Err(invalid(format!(
+            "the name is the one of a known domain, not of domain id {}",
+            raw.domain_id
+        )));
+    }
+
+    let protocol = raw
+        .protocol
+        .parse()
+        .map_err(|_| invalid(format!("invalid protocol `{}`", raw.protocol)))?;
+    let technical_stack = raw
+        .technical_stack
+        .map(|stack| {
+            stack
+                .parse()
+                .map_err(|_| invalid(format!("invalid technical stack `{stack}`")))
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let domain_type = if raw.is_testnet {
+        HyperlaneDomainType::Testnet
+    } else {
+        HyperlaneDomainType::Mainnet
+    };
+
+    Ok(DomainMetadata {
+        id: raw.domain_id,
+        name,
+        protocol,
+        domain_type,
+        technical_stack,
+        finality: raw.reorg_period,
+        native_token_decimals: raw.native_token_decimals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_registry_is_valid() {
+        let registry = DomainRegistry::embedded();
+        assert!(!registry.is_empty());
+
+        let base = registry.by_name("Base").unwrap();
+        assert_eq!(base.id, 8453);
+        assert_eq!(base.protocol, HyperlaneDomainProtocol::Ethereum);
+        assert_eq!(base.domain_type, HyperlaneDomainType::Mainnet);
+        assert_eq!(base.technical_stack, HyperlaneDomainTechnicalStack::OpStack);
+        assert_eq!(base.native_token_decimals, 18);
+
+        // Domains not in `KnownHyperlaneDomain` are unknown, but typed
+        let domain = registry.domain(8453).unwrap();
+        assert!(matches!(domain, HyperlaneDomain::Unknown { .. }));
+        assert_eq!(domain.name(), "base");
+        assert!(domain.is_op_stack());
+        assert_eq!(
+            registry.domain(1),
+            Some(HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum))
+        );
+    }
+
+    #[test]
+    fn external_registry_overrides_embedded() {
+        let external = DomainRegistry::from_json(
+            r#"[
+                {
+                    "domainId": 8453,
+                    "name": "base",
+                    "protocol": "ethereum",
+                    "technicalStack": "opstack",
+                    "reorgPeriod": "finalized",
+                    "nativeTokenDecimals": 18
+                },
+                {
+                    "domainId": 4242424242,
+                    "name": "newchain",
+                    "protocol": "sealevel",
+                    "isTestnet": true,
+                    "reorgPeriod": 0,
+                    "nativeTokenDecimals": 9
+                }
+            ]"#,
+        )
+        .unwrap();
+        let mut registry = DomainRegistry::embedded().clone();
+        let len = registry.len();
+        registry.extend(external);
+
+        assert_eq!(registry.len(), len + 1);
+        assert_eq!(
+            registry.by_id(8453).unwrap().finality,
+            ReorgPeriod::Tag("finalized".into())
+        );
+        let new_chain = registry.by_name("newchain").unwrap();
+        assert_eq!(new_chain.protocol, HyperlaneDomainProtocol::Sealevel);
+        assert_eq!(new_chain.domain_type, HyperlaneDomainType::Testnet);
+        assert_eq!(new_chain.native_token_decimals, 9);
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected() {
+        let entry = |domain_id: u32, name: &str| {
+            format!(
+                r#"[{{"domainId": {domain_id}, "name": "{name}", "protocol": "ethereum", "reorgPeriod": 1, "nativeTokenDecimals": 18}}]"#
+            )
+        };
+        // The id of a known domain with another name
+        assert!(DomainRegistry::from_json(&entry(1, "notethereum")).is_err());
+        // The name of a known domain with another id
+        assert!(DomainRegistry::from_json(&entry(4242424242, "ethereum")).is_err());
+        assert!(DomainRegistry::from_json(&entry(1, "ethereum")).is_ok());
+
+        let duplicate = r#"[
+            {"domainId": 4242424241, "name": "newchain", "protocol": "ethereum", "reorgPeriod": 1, "nativeTokenDecimals": 18},
+            {"domainId": 4242424242, "name": "newchain", "protocol": "ethereum", "reorgPeriod": 1, "nativeTokenDecimals": 18}
+        ]"#;
+        assert!(DomainRegistry::from_json(duplicate).is_err());
+    }
+}
@@ -159,6 +159,24 @@ pub enum ChainCommunicationError {
     /// Invalid reorg period
     #[error("Invalid reorg period: {0:?}")]
     InvalidReorgPeriod(ReorgPeriod),
+    /// The chain can't submit a kind of pre-transaction
+    #[error("Pre-transactions of kind {0} aren't supported on this chain")]
+    PreTransactionUnsupported(&'static str),
+    /// The indexer can't read the state of the chain at a past block
+    #[error("Querying the state at a past block isn't supported by this indexer")]
+    HistoricalQueryUnsupported,
+    /// The chain's IGP payments can't be claimed by the agents
+    #[error("Claiming IGP payments isn't supported on this chain")]
+    IgpClaimUnsupported,
+    /// The gas oracle values of the chain's IGP can't be read by the agents
+    #[error("Reading the gas oracle of the IGP isn't supported on this chain")]
+    GasOracleUnsupported,
+    /// The chain's mailbox delivers messages in a single phase
+    #[error("Executing enqueued messages isn't supported on this chain")]
+    TwoPhaseDeliveryUnsupported,
+    /// The validators announced on the chain can't be listed by the agents
+    #[error("Listing the announced validators isn't supported on this chain")]
+    AnnouncedValidatorsUnsupported,
 }
 
 impl ChainCommunicationError {
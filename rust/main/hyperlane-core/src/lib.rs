@@ -8,6 +8,8 @@
 extern crate core;
 
 pub use chain::*;
+#[cfg(feature = "strum")]
+pub use domain_registry::*;
 pub use error::*;
 pub use error::{ChainCommunicationError, ChainResult, HyperlaneProtocolError};
 pub use identifiers::HyperlaneIdentifier;
@@ -34,6 +36,8 @@ pub mod metrics;
 mod types;
 
 mod chain;
+#[cfg(feature = "strum")]
+mod domain_registry;
 mod error;
 
 /// Implementations of custom rpc client logic (e.g. fallback)
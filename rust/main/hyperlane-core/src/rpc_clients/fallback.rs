@@ -15,7 +15,7 @@ use tracing::{info, trace, warn_span};
 
 use crate::ChainCommunicationError;
 
-use super::RpcClientError;
+use super::{RpcClientError, RpcRateLimiter};
 
 /// Read the current block number from a chain.
 #[async_trait]
@@ -64,6 +64,7 @@ pub struct FallbackProvider<T, B> {
     /// The sub-providers called by this provider
     pub inner: Arc<PrioritizedProviders<T>>,
     max_block_time: Duration,
+    rate_limiter: Option<RpcRateLimiter>,
     _phantom: PhantomData<B>,
 }
 
@@ -72,6 +73,7 @@ impl<T, B> Clone for FallbackProvider<T, B> {
         Self {
             inner: self.inner.clone(),
             max_block_time: self.max_block_time,
+            rate_limiter: self.rate_limiter.clone(),
             _phantom: PhantomData,
         }
     }
@@ -180,6 +182,9 @@ where
             let priorities_snapshot = self.take_priorities_snapshot().await;
             for (idx, priority) in priorities_snapshot.iter().enumerate() {
                 let provider = &self.inner.providers[priority.index];
+                if let Some(rate_limiter) = &self.rate_limiter {
+                    rate_limiter.acquire().await;
+                }
                 let resp = f(provider.clone()).await;
                 self.handle_stalled_provider(priority, provider).await;
                 let _span =
@@ -206,6 +211,7 @@ where
 pub struct FallbackProviderBuilder<T, B> {
     providers: Vec<T>,
     max_block_time: Duration,
+    rate_limiter: Option<RpcRateLimiter>,
     _phantom: PhantomData<B>,
 }
 
@@ -214,6 +220,7 @@ impl<T, B> Default for FallbackProviderBuilder<T, B> {
         Self {
             providers: Vec::new(),
             max_block_time: MAX_BLOCK_TIME,
+            rate_limiter: None,
             _phantom: PhantomData,
         }
     }
@@ -240,6 +247,13 @@ impl<T, B> FallbackProviderBuilder<T, B> {
         self
     }
 
+    /// Limit the rate of requests sent to the providers. The limit is shared
+    /// by all providers.
+    pub fn with_rate_limiter(mut self, rate_limiter: Option<RpcRateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     /// Create a fallback provider.
     pub fn build(self) -> FallbackProvider<T, B> {
         let provider_count = self.providers.len();
@@ -255,6 +269,7 @@ impl<T, B> FallbackProviderBuilder<T, B> {
         FallbackProvider {
             inner: Arc::new(prioritized_providers),
             max_block_time: self.max_block_time,
+            rate_limiter: self.rate_limiter,
             _phantom: PhantomData,
         }
     }
@@ -0,0 +1,120 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tracing::trace;
+
+/// Configuration of the rate at which requests are sent to a chain's RPCs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RpcRateLimitConf {
+    /// Sustained number of requests per second
+    pub requests_per_second: f64,
+    /// Number of requests that can be sent at once after a quiet period
+    pub burst: u32,
+}
+
+/// A token bucket limiting the rate of RPC requests to a chain.
+///
+/// Clones share the same bucket, so a single limiter can be handed to every
+/// client built for the chain.
+#[derive(Debug, Clone)]
+pub struct RpcRateLimiter {
+    conf: RpcRateLimitConf,
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens available. Negative when requests are waiting for tokens that
+    /// haven't been refilled yet.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RpcRateLimiter {
+    /// Create a limiter that starts with a full bucket
+    pub fn new(conf: RpcRateLimitConf) -> Self {
+        Self {
+            conf,
+            bucket: Arc::new(Mutex::new(TokenBucket {
+                tokens: conf.burst as f64,
+                last_refill: Instant::now(),
+            })),
+        }
+    }
+
+    /// The configuration of the limiter
+    pub fn conf(&self) -> RpcRateLimitConf {
+        self.conf
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            trace!(?wait, "Waiting for RPC rate limit");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token, and returns how long to wait until it is refilled.
+    /// Tokens are reserved in order, so concurrent requests are spaced out
+    /// instead of all retrying once a token is available.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|err| err.into_inner());
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.conf.requests_per_second)
+            .min(self.conf.burst as f64);
+        bucket.last_refill = bucket.last_refill.max(now);
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.conf.requests_per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_second: f64, burst: u32) -> RpcRateLimiter {
+        RpcRateLimiter::new(RpcRateLimitConf {
+            requests_per_second,
+            burst,
+        })
+    }
+
+    #[test]
+    fn test_burst_is_not_delayed() {
+        let limiter = limiter(10.0, 3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve(now), Duration::ZERO);
+        }
+        // Requests beyond the burst are spaced out at the sustained rate
+        assert_eq!(limiter.reserve(now), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(now), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_tokens_are_refilled_up_to_burst() {
+        let limiter = limiter(2.0, 2);
+        let start = Instant::now();
+        limiter.reserve(start);
+        limiter.reserve(start);
+
+        // Half a second refills one token
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::from_millis(500));
+
+        // A long quiet period doesn't refill more than the burst
+        let much_later = later + Duration::from_secs(60);
+        assert_eq!(limiter.reserve(much_later), Duration::ZERO);
+        assert_eq!(limiter.reserve(much_later), Duration::ZERO);
+        assert_eq!(limiter.reserve(much_later), Duration::from_millis(500));
+    }
+}
@@ -11,33 +11,238 @@ pub const DEFAULT_MAX_RPC_RETRIES: usize = 10;
 /// Duration to sleep between retries
 pub const RPC_RETRY_SLEEP_DURATION: Duration = Duration::from_secs(2);
 
-// TODO: Refactor this function into a retrying provider
-/// Retry calling a fallible async function a certain number of times, with a delay between each retry
-#[instrument(err, skip(f))]
-pub async fn call_and_retry_n_times<T>(
+/// Fraction of an exponential delay that is randomized by default, so that
+/// callers failing together don't retry together
+pub const DEFAULT_RETRY_JITTER: f64 = 0.25;
+
+/// How long to wait between the attempts of a fallible call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// The same delay before every retry
+    Fixed(Duration),
+    /// A delay that doubles after every failed attempt, from `initial` up to
+    /// `max`. Each delay is shortened by a random fraction of itself of at
+    /// most `jitter`.
+    Exponential {
+        /// Delay before the first retry
+        initial: Duration,
+        /// The longest delay
+        max: Duration,
+        /// Fraction of each delay that is randomized, between 0 and 1
+        jitter: f64,
+    },
+}
+
+/// How often to retry a fallible call, and how long to wait between attempts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The delay between attempts
+    pub backoff: Backoff,
+    /// Attempts after which to give up, or None to retry until the call
+    /// succeeds
+    pub max_attempts: Option<usize>,
+}
+
+impl RetryPolicy {
+    /// Makes up to `max_attempts` attempts, `delay` apart
+    pub const fn fixed(delay: Duration, max_attempts: usize) -> Self {
+        Self {
+            backoff: Backoff::Fixed(delay),
+            max_attempts: Some(max_attempts),
+        }
+    }
+
+    /// Makes up to `max_attempts` attempts, with exponentially growing and
+    /// jittered delays between them
+    pub const fn exponential(initial: Duration, max: Duration, max_attempts: usize) -> Self {
+        Self {
+            backoff: Backoff::Exponential {
+                initial,
+                max,
+                jitter: DEFAULT_RETRY_JITTER,
+            },
+            max_attempts: Some(max_attempts),
+        }
+    }
+
+    /// Retries until the call succeeds, with exponentially growing and
+    /// jittered delays that are capped at `max`
+    pub const fn unbounded_with_cap(initial: Duration, max: Duration) -> Self {
+        Self {
+            backoff: Backoff::Exponential {
+                initial,
+                max,
+                jitter: DEFAULT_RETRY_JITTER,
+            },
+            max_attempts: None,
+        }
+    }
+
+    /// Whether to make another attempt after `failures` failed ones
+    pub fn should_retry(&self, failures: usize) -> bool {
+        self.max_attempts.map_or(true, |max| failures < max)
+    }
+
+    /// The delay before the attempt that follows `failures` failed ones
+    pub fn delay(&self, failures: usize) -> Duration {
+        self.delay_with_jitter(failures, rand::random())
+    }
+
+    /// The delay before the attempt that follows `failures` failed ones,
+    /// shortened by `random`, between 0 and 1, times the jitter of the policy
+    fn delay_with_jitter(&self, failures: usize, random: f64) -> Duration {
+        match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential {
+                initial,
+                max,
+                jitter,
+            } => {
+                let exponent = failures.saturating_sub(1).min(u32::MAX as usize) as u32;
+                let delay = 2u32
+                    .checked_pow(exponent)
+                    .and_then(|factor| initial.checked_mul(factor))
+                    .map_or(max, |delay| delay.min(max));
+                delay.mul_f64(1. - jitter.clamp(0., 1.) * random.clamp(0., 1.))
+            }
+        }
+    }
+}
+
+/// Calls a fallible async function until it succeeds or the policy gives up,
+/// in which case the last error is returned. `on_failure` is called with the
+/// number of failed attempts so far and the error of each failed attempt.
+pub async fn call_with_retry_policy<T>(
+    policy: &RetryPolicy,
     mut f: impl FnMut() -> Pin<Box<dyn Future<Output = ChainResult<T>> + Send>>,
-    n: usize,
+    mut on_failure: impl FnMut(usize, &ChainCommunicationError),
 ) -> ChainResult<T> {
-    for retry_number in 1..n {
+    let mut failures = 0;
+    loop {
         match f().await {
             Ok(res) => return Ok(res),
             Err(err) => {
-                warn!(retries=retry_number, error=?err, "Retrying call");
-                sleep(RPC_RETRY_SLEEP_DURATION).await;
+                failures += 1;
+                on_failure(failures, &err);
+                if !policy.should_retry(failures) {
+                    return Err(err);
+                }
+                sleep(policy.delay(failures)).await;
             }
         }
     }
+}
 
-    // TODO: Return the last error, or a vec of all the error instead of this string error
-    Err(ChainCommunicationError::CustomError(
-        "Retrying call failed".to_string(),
-    ))
+// TODO: Refactor this function into a retrying provider
+/// Retry calling a fallible async function a certain number of times, with a delay between each retry
+#[instrument(err, skip(f))]
+pub async fn call_and_retry_n_times<T>(
+    f: impl FnMut() -> Pin<Box<dyn Future<Output = ChainResult<T>> + Send>>,
+    n: usize,
+) -> ChainResult<T> {
+    let policy = RetryPolicy::fixed(RPC_RETRY_SLEEP_DURATION, n);
+    call_with_retry_policy(&policy, f, |retries, err| {
+        warn!(retries, error=?err, "Retrying call");
+    })
+    .await
 }
 
 /// Retry calling a fallible async function indefinitely, until it succeeds
 pub async fn call_and_retry_indefinitely<T>(
     f: impl FnMut() -> Pin<Box<dyn Future<Output = ChainResult<T>> + Send>>,
 ) -> T {
-    // It's ok to unwrap, because `usize::MAX * RPC_RETRY_SLEEP_DURATION` means billions of years worth of retrying
-    call_and_retry_n_times(f, usize::MAX).await.unwrap()
+    let policy = RetryPolicy {
+        backoff: Backoff::Fixed(RPC_RETRY_SLEEP_DURATION),
+        max_attempts: None,
+    };
+    call_with_retry_policy(&policy, f, |retries, err| {
+        warn!(retries, error=?err, "Retrying call");
+    })
+    .await
+    .expect("Retrying indefinitely never gives up")
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_fixed_backoff() {
+        let policy = RetryPolicy::fixed(Duration::from_secs(2), 3);
+        assert_eq!(policy.delay_with_jitter(1, 0.5), Duration::from_secs(2));
+        assert_eq!(policy.delay_with_jitter(10, 0.5), Duration::from_secs(2));
+        assert!(policy.should_retry(2));
+        assert!(!policy.should_retry(3));
+    }
+
+    #[test]
+    fn test_exponential_backoff_is_capped_and_jittered() {
+        let policy =
+            RetryPolicy::unbounded_with_cap(Duration::from_secs(1), Duration::from_secs(60));
+        let delays: Vec<_> = (1..=8)
+            .map(|failures| policy.delay_with_jitter(failures, 0.).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(
+            policy.delay_with_jitter(usize::MAX, 0.),
+            Duration::from_secs(60)
+        );
+        // At most a quarter of each delay is randomized
+        assert_eq!(policy.delay_with_jitter(3, 1.), Duration::from_secs(3));
+        assert!(policy.should_retry(usize::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_returns_last_error() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let policy = RetryPolicy::fixed(Duration::ZERO, 3);
+        let mut failures = vec![];
+        let res: ChainResult<()> = call_with_retry_policy(
+            &policy,
+            || {
+                let calls = calls.clone();
+                Box::pin(async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                    Err(ChainCommunicationError::CustomError(format!("call {call}")))
+                })
+            },
+            |failed, _| failures.push(failed),
+        )
+        .await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(failures, vec![1, 2, 3]);
+        assert!(matches!(
+            res,
+            Err(ChainCommunicationError::CustomError(err)) if err == "call 3"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retry_policy_stops_on_success() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let policy = RetryPolicy {
+            backoff: Backoff::Fixed(Duration::ZERO),
+            max_attempts: None,
+        };
+        let res = call_with_retry_policy(
+            &policy,
+            || {
+                let calls = calls.clone();
+                Box::pin(async move {
+                    match calls.fetch_add(1, Ordering::SeqCst) {
+                        0 | 1 => Err(ChainCommunicationError::CustomError("failed".into())),
+                        call => Ok(call),
+                    }
+                })
+            },
+            |_, _| {},
+        )
+        .await;
+        assert_eq!(res.unwrap(), 2);
+    }
 }
@@ -0,0 +1,25 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::{ChainResult, HyperlaneContract, SignedCheckpointWithMessageId, TxOutcome, U256};
+
+/// Interface for a contract on the origin chain that records checkpoints
+/// signed by validators, as an on-chain proof of their liveness
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait CheckpointAttestation: HyperlaneContract + Send + Sync + Debug {
+    /// Submit a signed checkpoint to the contract
+    async fn attest_checkpoint(
+        &self,
+        checkpoint: &SignedCheckpointWithMessageId,
+    ) -> ChainResult<TxOutcome>;
+
+    /// Returns the maximum cost in native tokens of submitting the signed
+    /// checkpoint
+    async fn attest_checkpoint_max_cost(
+        &self,
+        checkpoint: &SignedCheckpointWithMessageId,
+    ) -> ChainResult<U256>;
+}
@@ -0,0 +1,112 @@
+use std::fmt::Debug;
+
+use blake2::{digest::consts::U32, Blake2b};
+use serde::Deserialize;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+use crate::H256;
+
+/// The hash function a chain's Hyperlane contracts use to compute the digests
+/// validators sign, i.e. checkpoint and announcement digests. This is
+/// keccak256 on most chains, but chains whose VM only offers other hash
+/// functions natively deploy contracts that use those instead.
+///
+/// Message ids and the merkle tree of dispatched messages are always
+/// computed with keccak256.
+pub trait HyperlaneHasher: Send + Sync + Debug {
+    /// Hash the concatenation of `parts`
+    fn hash(&self, parts: &[&[u8]]) -> H256;
+}
+
+fn hash_parts<D: Digest>(parts: &[&[u8]]) -> H256 {
+    let mut hasher = D::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    H256::from_slice(hasher.finalize().as_slice())
+}
+
+/// keccak256, as used by the EVM
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Keccak256Hasher;
+
+impl HyperlaneHasher for Keccak256Hasher {
+    fn hash(&self, parts: &[&[u8]]) -> H256 {
+        hash_parts::<Keccak256>(parts)
+    }
+}
+
+/// sha256
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Sha256Hasher;
+
+impl HyperlaneHasher for Sha256Hasher {
+    fn hash(&self, parts: &[&[u8]]) -> H256 {
+        hash_parts::<Sha256>(parts)
+    }
+}
+
+/// blake2b with a 256 bit output
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Blake2b256Hasher;
+
+impl HyperlaneHasher for Blake2b256Hasher {
+    fn hash(&self, parts: &[&[u8]]) -> H256 {
+        hash_parts::<Blake2b<U32>>(parts)
+    }
+}
+
+/// The hash functions agents know how to use, for selecting one in the
+/// configuration of a chain.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgorithm {
+    /// See [`Keccak256Hasher`]
+    #[default]
+    Keccak256,
+    /// See [`Sha256Hasher`]
+    Sha256,
+    /// See [`Blake2b256Hasher`]
+    Blake2b256,
+}
+
+impl HyperlaneHasher for HashAlgorithm {
+    fn hash(&self, parts: &[&[u8]]) -> H256 {
+        match self {
+            HashAlgorithm::Keccak256 => Keccak256Hasher.hash(parts),
+            HashAlgorithm::Sha256 => Sha256Hasher.hash(parts),
+            HashAlgorithm::Blake2b256 => Blake2b256Hasher.hash(parts),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_hashes_concatenated_parts() {
+        let cases = [
+            (
+                HashAlgorithm::Keccak256,
+                "0x1c8aff950685c2ed4bc3174f3472287b56d9517b9c948127319a09a7a36deac8",
+            ),
+            (
+                HashAlgorithm::Sha256,
+                "0x2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+            ),
+            (
+                HashAlgorithm::Blake2b256,
+                "0x324dcf027dd4a30a932c441f365a25e86b173defa4b8e58948253471b81b72cf",
+            ),
+        ];
+        for (algorithm, expected) in cases {
+            let expected = H256::from_str(expected).unwrap();
+            assert_eq!(algorithm.hash(&[b"hello"]), expected);
+            assert_eq!(algorithm.hash(&[b"he", b"llo"]), expected);
+        }
+    }
+}
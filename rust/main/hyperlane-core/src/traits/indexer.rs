@@ -11,7 +11,7 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use serde::Deserialize;
 
-use crate::{ChainResult, Indexed, LogMeta, H512};
+use crate::{ChainCommunicationError, ChainResult, Indexed, LogMeta, H512};
 
 /// Indexing mode.
 #[derive(Copy, Debug, Default, Deserialize, Clone)]
@@ -55,4 +55,16 @@ pub trait Indexer<T: Sized>: Send + Sync + Debug {
 pub trait SequenceAwareIndexer<T>: Indexer<T> {
     /// Return the latest finalized sequence (if any) and block number
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)>;
+
+    /// Return the sequence count (if any) as of block `block`, i.e. read at
+    /// that block rather than at the tip.
+    ///
+    /// Together with `fetch_logs_in_range` up to the same block, this gives
+    /// a consistent snapshot at a historical watermark, which tooling that
+    /// backfills or reconciles indexed data can compare without racing the
+    /// tip. Indexers that can't read state at a past block return
+    /// `ChainCommunicationError::HistoricalQueryUnsupported`.
+    async fn sequence_count_at_block(&self, _block: u32) -> ChainResult<Option<u32>> {
+        Err(ChainCommunicationError::HistoricalQueryUnsupported)
+    }
 }
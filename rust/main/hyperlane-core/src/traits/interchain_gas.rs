@@ -3,10 +3,31 @@ use std::fmt::Debug;
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 
-use crate::HyperlaneContract;
+use crate::{
+    traits::TxOutcome, ChainCommunicationError, ChainResult, HyperlaneContract, RemoteGasData, U256,
+};
 
 /// Interface for the InterchainGasPaymaster chain contract.
 /// Allows abstraction over different chains.
 #[async_trait]
 #[auto_impl(&, Box, Arc)]
-pub trait InterchainGasPaymaster: HyperlaneContract + Send + Sync + Debug {}
+pub trait InterchainGasPaymaster: HyperlaneContract + Send + Sync + Debug {
+    /// The payments held by the IGP that its beneficiary can claim, in the
+    /// native token of the chain
+    async fn claimable_balance(&self) -> ChainResult<U256> {
+        Err(ChainCommunicationError::IgpClaimUnsupported)
+    }
+
+    /// Sends the payments held by the IGP to its beneficiary and waits for
+    /// the transaction to be confirmed
+    async fn claim(&self) -> ChainResult<TxOutcome> {
+        Err(ChainCommunicationError::IgpClaimUnsupported)
+    }
+
+    /// The values the IGP quotes the gas payments for `destination` with, or
+    /// `None` if it has no gas oracle for `destination`, in which case paying
+    /// for the gas of its messages reverts
+    async fn remote_gas_data(&self, _destination: u32) -> ChainResult<Option<RemoteGasData>> {
+        Err(ChainCommunicationError::GasOracleUnsupported)
+    }
+}
@@ -41,6 +41,14 @@ pub enum ModuleType {
     Null,
     /// Ccip Read ISM (accepts offchain signature information)
     CcipRead,
+    /// Arbitrum L2 -> L1 ISM (verified by the Arbitrum native bridge)
+    ArbL2ToL1,
+    /// Weighted Merkle Proof ISM (UNSUPPORTED)
+    WeightedMerkleRootMultisig,
+    /// Weighted Message ID ISM (UNSUPPORTED)
+    WeightedMessageIdMultisig,
+    /// OP Stack L2 -> L1 ISM (verified by the OP Stack native bridge)
+    OpL2ToL1,
 }
 
 /// Interface for the InterchainSecurityModule chain contract. Allows abstraction over
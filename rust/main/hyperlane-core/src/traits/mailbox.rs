@@ -5,7 +5,8 @@ use derive_new::new;
 
 use crate::{
     traits::TxOutcome, utils::domain_hash, BatchItem, ChainCommunicationError, ChainResult,
-    HyperlaneContract, HyperlaneMessage, QueueOperation, ReorgPeriod, TxCostEstimate, H256, U256,
+    HyperlaneContract, HyperlaneMessage, PreTransaction, QueueOperation, ReorgPeriod,
+    TxCostEstimate, H256, U256,
 };
 
 /// Interface for the Mailbox chain contract. Allows abstraction over different
@@ -23,15 +24,43 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     ///   it will query at the latest block.
     async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32>;
 
-    /// Fetch the status of a message
+    /// Fetch the status of a message. On mailboxes that deliver messages in
+    /// two phases, a message is only delivered once it was executed.
     async fn delivered(&self, id: H256) -> ChainResult<bool>;
 
+    /// Fetch the status of multiple messages, in the same order as `ids`
+    #[cfg(feature = "async")]
+    async fn delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {
+        // Without a batched query, fetch each status individually, concurrently
+        futures::future::try_join_all(ids.iter().map(|id| self.delivered(*id))).await
+    }
+
+    /// Fetch the status of multiple messages, in the same order as `ids`
+    #[cfg(not(feature = "async"))]
+    async fn delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {
+        let mut delivered = Vec::with_capacity(ids.len());
+        for id in ids {
+            delivered.push(self.delivered(*id).await?);
+        }
+        Ok(delivered)
+    }
+
+    /// Fetch the domain id the mailbox contract was deployed with
+    async fn local_domain(&self) -> ChainResult<u32>;
+
     /// Fetch the current default interchain security module value
     async fn default_ism(&self) -> ChainResult<H256>;
 
     /// Get the latest checkpoint.
     async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256>;
 
+    /// Fetch the ISM that verifies the recipient's messages from `origin`.
+    /// Only differs from `recipient_ism` on mailboxes whose default ISM can
+    /// be overridden per origin.
+    async fn recipient_ism_for_origin(&self, recipient: H256, _origin: u32) -> ChainResult<H256> {
+        self.recipient_ism(recipient).await
+    }
+
     /// Process a message with a proof against the provided signed checkpoint
     async fn process(
         &self,
@@ -69,6 +98,46 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     /// Get the calldata for a transaction to process a message with a proof
     /// against the provided signed checkpoint
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8>;
+
+    /// Whether `pre_transaction` still has to be submitted before `message`
+    /// can be processed, e.g. false if the account it creates already exists
+    async fn pre_transaction_required(
+        &self,
+        _message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<bool> {
+        Err(ChainCommunicationError::PreTransactionUnsupported(
+            pre_transaction.kind(),
+        ))
+    }
+
+    /// Submits `pre_transaction` for `message` and waits for it to be
+    /// confirmed
+    async fn submit_pre_transaction(
+        &self,
+        _message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<TxOutcome> {
+        Err(ChainCommunicationError::PreTransactionUnsupported(
+            pre_transaction.kind(),
+        ))
+    }
+
+    /// Whether a message was enqueued by `process` and waits to be executed.
+    /// Always false on mailboxes that deliver messages in a single phase.
+    async fn awaiting_execution(&self, _id: H256) -> ChainResult<bool> {
+        Ok(false)
+    }
+
+    /// Executes a message enqueued by `process` and waits for the transaction
+    /// to be confirmed
+    async fn execute(
+        &self,
+        _message: &HyperlaneMessage,
+        _tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        Err(ChainCommunicationError::TwoPhaseDeliveryUnsupported)
+    }
 }
 
 /// The result of processing a batch of messages
@@ -1,15 +1,18 @@
 pub use aggregation_ism::*;
 pub use ccip_read_ism::*;
+pub use checkpoint_attestation::*;
 pub use cursor::*;
 pub use db::*;
 pub use deployed::*;
 pub use encode::*;
+pub use hasher::*;
 pub use indexer::*;
 pub use interchain_gas::*;
 pub use interchain_security_module::*;
 pub use mailbox::*;
 pub use merkle_tree_hook::*;
 pub use multisig_ism::*;
+pub use native_bridge_ism::*;
 pub use pending_operation::*;
 pub use provider::*;
 pub use routing_ism::*;
@@ -20,16 +23,19 @@ use crate::{FixedPointNumber, H512, U256};
 
 mod aggregation_ism;
 mod ccip_read_ism;
+mod checkpoint_attestation;
 mod cursor;
 mod db;
 mod deployed;
 mod encode;
+mod hasher;
 mod indexer;
 mod interchain_gas;
 mod interchain_security_module;
 mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
+mod native_bridge_ism;
 mod pending_operation;
 mod provider;
 mod routing_ism;
@@ -0,0 +1,26 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::{ChainResult, HyperlaneContract, HyperlaneMessage};
+
+/// Interface for ISMs that verify messages from an L2 origin through the
+/// native L2 -> L1 bridge of the origin rollup, like the ArbL2ToL1Ism and the
+/// OPL2ToL1Ism
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait NativeBridgeIsm: HyperlaneContract + Send + Sync + Debug {
+    /// Whether the bridge already delivered the message id to the ISM, in
+    /// which case the message can be delivered with empty metadata
+    async fn is_verified(&self, message: &HyperlaneMessage) -> ChainResult<bool>;
+
+    /// Returns the metadata that executes the bridge withdrawal carrying the
+    /// message id, which was sent in `dispatched_block` on the origin chain.
+    /// Returns `None` if the withdrawal can't be executed yet.
+    async fn withdrawal_metadata(
+        &self,
+        message: &HyperlaneMessage,
+        dispatched_block: u64,
+    ) -> ChainResult<Option<Vec<u8>>>;
+}
@@ -17,7 +17,7 @@ use hyperlane_application::ApplicationReport;
 
 use crate::{
     ChainResult, Decode, Encode, FixedPointNumber, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, Mailbox, TryBatchAs, TxOutcome, H256, U256,
+    HyperlaneProtocolError, Mailbox, TryBatchAs, TxOutcome, H256, H512, U256,
 };
 
 /// Boxed operation that can be stored in an operation queue
@@ -130,6 +130,12 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// Get the estimated the cost of the `submit` call
     fn get_tx_cost_estimate(&self) -> Option<U256>;
 
+    /// The hash of the transaction the operation was last submitted in, if
+    /// it was submitted
+    fn submission_tx_hash(&self) -> Option<H512> {
+        None
+    }
+
     /// This will be called after the operation has been submitted and is
     /// responsible for checking if the operation has reached a point at
     /// which we consider it safe from reorgs.
@@ -155,6 +161,11 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     /// retried immediately.
     fn reset_attempts(&mut self);
 
+    /// Get the number of times this operation has been retried.
+    fn retry_count(&self) -> u32 {
+        0
+    }
+
     /// Set the number of times this operation has been retried.
     #[cfg(any(test, feature = "test-utils"))]
     fn set_retries(&mut self, retries: u32);
@@ -259,6 +270,44 @@ pub enum ReprepareReason {
     #[strum(to_string = "ApplicationReport({0})")]
     /// Application report
     ApplicationReport(ApplicationReport),
+    #[strum(to_string = "Error fetching message recipient code hash")]
+    /// Error fetching the code hash of the message recipient
+    ErrorFetchingRecipientCodeHash,
+    #[strum(to_string = "Message metadata exceeds max metadata size of destination")]
+    /// The metadata is larger than the destination chain accepts
+    MetadataExceedsMaxSize,
+    #[strum(to_string = "Error fetching the domain of the destination mailbox")]
+    /// Error fetching the domain id reported by the destination mailbox
+    ErrorFetchingDestinationDomain,
+    #[strum(
+        to_string = "Destination mailbox is on a different domain than the message destination"
+    )]
+    /// The destination mailbox reports a different domain id than the message
+    /// destination, i.e. the destination chain is misconfigured
+    DestinationDomainMismatch,
+    #[strum(to_string = "Destination is paused")]
+    /// Delivery reverts because a contract it goes through, e.g. the
+    /// destination mailbox, the recipient's ISM or the recipient, is paused
+    DestinationPaused,
+    #[strum(to_string = "ISM verification failed")]
+    /// Simulating the delivery failed because the recipient's ISM rejected
+    /// the metadata
+    IsmVerificationFailed,
+    #[strum(to_string = "Message recipient reverted")]
+    /// Simulating the delivery failed because the recipient reverted while
+    /// handling the message
+    RecipientReverted,
+    #[strum(to_string = "Simulated delivery ran out of gas")]
+    /// Simulating the delivery failed because it ran out of gas
+    SimulationOutOfGas,
+    #[strum(to_string = "Failed to submit a pre-transaction")]
+    /// A transaction the message requires to land on the destination before
+    /// it's delivered failed or reverted
+    PreTransactionFailed,
+    #[strum(to_string = "Message recipient code hash is not allowed")]
+    /// The code hash of the message recipient is denied, or not allowed, by
+    /// the relayer's recipient code hash lists
+    RecipientCodeHashNotAllowed,
 }
 
 #[derive(Display, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -272,6 +321,10 @@ pub enum ConfirmReason {
     #[strum(to_string = "Already submitted, awaiting confirmation")]
     /// Operation was already submitted (either by another relayer, or by a previous run of this relayer), awaiting confirmation
     AlreadySubmitted,
+    #[strum(to_string = "Enqueued, execution submitted")]
+    /// Operation was enqueued by a two-phase mailbox and this relayer
+    /// submitted its execution
+    ExecutionSubmitted,
     /// Error checking message delivery status
     ErrorConfirmingDelivery,
     /// Error storing delivery outcome
@@ -25,6 +25,12 @@ pub trait HyperlaneProvider: HyperlaneChain + Send + Sync + Debug {
     /// Returns whether a contract exists at the provided address
     async fn is_contract(&self, address: &H256) -> ChainResult<bool>;
 
+    /// Returns the hash of the code deployed at the provided address, or None
+    /// if the chain doesn't support fetching code hashes.
+    async fn get_code_hash(&self, _address: &H256) -> ChainResult<Option<H256>> {
+        Ok(None)
+    }
+
     /// Fetch the balance of the wallet address associated with the chain provider.
     async fn get_balance(&self, address: String) -> ChainResult<U256>;
 
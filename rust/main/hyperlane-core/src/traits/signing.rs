@@ -8,7 +8,7 @@ use serde::{
 };
 
 use crate::utils::bytes_to_hex;
-use crate::{Signature, H160, H256};
+use crate::{HyperlaneHasher, Keccak256Hasher, Signature, H160, H256};
 
 /// An error incurred by a signer
 #[derive(thiserror::Error, Debug)]
@@ -37,6 +37,13 @@ pub trait HyperlaneSignerExt {
         value: T,
     ) -> Result<SignedType<T>, HyperlaneSignerError>;
 
+    /// Sign a `Signable` value, whose signing hash is computed with `hasher`
+    async fn sign_with_hasher<T: Signable + Send>(
+        &self,
+        value: T,
+        hasher: &dyn HyperlaneHasher,
+    ) -> Result<SignedType<T>, HyperlaneSignerError>;
+
     /// Check whether a message was signed by a specific address.
     #[cfg(feature = "ethers")]
     fn verify<T: Signable>(
@@ -51,7 +58,15 @@ impl<S: HyperlaneSigner> HyperlaneSignerExt for S {
         &self,
         value: T,
     ) -> Result<SignedType<T>, HyperlaneSignerError> {
-        let signing_hash = value.signing_hash();
+        self.sign_with_hasher(value, &Keccak256Hasher).await
+    }
+
+    async fn sign_with_hasher<T: Signable + Send>(
+        &self,
+        value: T,
+        hasher: &dyn HyperlaneHasher,
+    ) -> Result<SignedType<T>, HyperlaneSignerError> {
+        let signing_hash = value.signing_hash_with(hasher);
         let signature = self.sign_hash(&signing_hash).await?;
 
         Ok(SignedType { value, signature })
@@ -72,12 +87,23 @@ impl<S: HyperlaneSigner> HyperlaneSignerExt for S {
 pub trait Signable: Sized {
     /// A hash of the contents.
     /// The EIP-191 compliant version of this hash is signed by validators.
-    fn signing_hash(&self) -> H256;
+    fn signing_hash(&self) -> H256 {
+        self.signing_hash_with(&Keccak256Hasher)
+    }
+
+    /// A hash of the contents computed with `hasher`, for chains whose
+    /// contracts don't use keccak256.
+    fn signing_hash_with(&self, hasher: &dyn HyperlaneHasher) -> H256;
 
     /// EIP-191 compliant hash of the signing hash.
     fn eth_signed_message_hash(&self) -> H256 {
         hashes::hash_message(self.signing_hash())
     }
+
+    /// EIP-191 compliant hash of the signing hash computed with `hasher`.
+    fn eth_signed_message_hash_with(&self, hasher: &dyn HyperlaneHasher) -> H256 {
+        hashes::hash_message(self.signing_hash_with(hasher))
+    }
 }
 
 /// A signed type. Contains the original value and the signature.
@@ -109,7 +135,17 @@ impl<T: Signable> SignedType<T> {
     /// Recover the Ethereum address of the signer
     #[cfg(feature = "ethers")]
     pub fn recover(&self) -> Result<H160, crate::HyperlaneProtocolError> {
-        let hash = ethers_core::types::H256::from(self.value.eth_signed_message_hash());
+        self.recover_with_hasher(&Keccak256Hasher)
+    }
+
+    /// Recover the Ethereum address of the signer of a value whose signing
+    /// hash was computed with `hasher`
+    #[cfg(feature = "ethers")]
+    pub fn recover_with_hasher(
+        &self,
+        hasher: &dyn HyperlaneHasher,
+    ) -> Result<H160, crate::HyperlaneProtocolError> {
+        let hash = ethers_core::types::H256::from(self.value.eth_signed_message_hash_with(hasher));
         let sig = ethers_core::types::Signature::from(self.signature);
 
         Ok(sig.recover(hash)?.into())
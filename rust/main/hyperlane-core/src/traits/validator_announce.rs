@@ -3,7 +3,10 @@ use std::fmt::Debug;
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 
-use crate::{Announcement, ChainResult, HyperlaneContract, SignedType, TxOutcome, H256, U256};
+use crate::{
+    Announcement, ChainCommunicationError, ChainResult, HyperlaneContract, SignedType, TxOutcome,
+    H256, U256,
+};
 
 /// Interface for the ValidatorAnnounce chain contract. Allows abstraction over
 /// different chains
@@ -16,6 +19,11 @@ pub trait ValidatorAnnounce: HyperlaneContract + Send + Sync + Debug {
         validators: &[H256],
     ) -> ChainResult<Vec<Vec<String>>>;
 
+    /// Returns all the validators that announced a storage location
+    async fn announced_validators(&self) -> ChainResult<Vec<H256>> {
+        Err(ChainCommunicationError::AnnouncedValidatorsUnsupported)
+    }
+
     /// Announce a storage location for a validator
     async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome>;
 
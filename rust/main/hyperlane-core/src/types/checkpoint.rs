@@ -2,9 +2,8 @@ use std::fmt::Debug;
 
 use derive_more::Deref;
 use serde::{Deserialize, Serialize};
-use sha3::{digest::Update, Digest, Keccak256};
 
-use crate::{utils::domain_hash, Signable, Signature, SignedType, H256};
+use crate::{utils::domain_hash_with, HyperlaneHasher, Signable, Signature, SignedType, H256};
 
 /// An Hyperlane checkpoint
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
@@ -32,21 +31,15 @@ pub struct CheckpointWithMessageId {
 impl Signable for CheckpointWithMessageId {
     /// A hash of the checkpoint contents.
     /// The EIP-191 compliant version of this hash is signed by validators.
-    fn signing_hash(&self) -> H256 {
+    fn signing_hash_with(&self, hasher: &dyn HyperlaneHasher) -> H256 {
         // sign:
         // domain_hash(mailbox_address, mailbox_domain) || root || index (as u32) || message_id
-        H256::from_slice(
-            Keccak256::new()
-                .chain(domain_hash(
-                    self.merkle_tree_hook_address,
-                    self.mailbox_domain,
-                ))
-                .chain(self.root)
-                .chain(self.index.to_be_bytes())
-                .chain(self.message_id)
-                .finalize()
-                .as_slice(),
-        )
+        hasher.hash(&[
+            domain_hash_with(hasher, self.merkle_tree_hook_address, self.mailbox_domain).as_bytes(),
+            self.root.as_bytes(),
+            &self.index.to_be_bytes(),
+            self.message_id.as_bytes(),
+        ])
     }
 }
 
@@ -98,3 +91,67 @@ impl TryFrom<&mut Vec<SignedCheckpointWithMessageId>> for MultisigSignedCheckpoi
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{Keccak256Hasher, U256};
+
+    prop_compose! {
+        fn arb_checkpoint()(
+            merkle_tree_hook_address in any::<[u8; 32]>(),
+            mailbox_domain in any::<u32>(),
+            root in any::<[u8; 32]>(),
+            index in any::<u32>(),
+            message_id in any::<[u8; 32]>(),
+        ) -> CheckpointWithMessageId {
+            CheckpointWithMessageId {
+                checkpoint: Checkpoint {
+                    merkle_tree_hook_address: H256::from(merkle_tree_hook_address),
+                    mailbox_domain,
+                    root: H256::from(root),
+                    index,
+                },
+                message_id: H256::from(message_id),
+            }
+        }
+    }
+
+    prop_compose! {
+        fn arb_signature()(
+            r in any::<[u8; 32]>(),
+            s in any::<[u8; 32]>(),
+            v in any::<u64>(),
+        ) -> Signature {
+            Signature {
+                r: U256::from_big_endian(&r),
+                s: U256::from_big_endian(&s),
+                v,
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn signed_checkpoint_json_round_trips(
+            value in arb_checkpoint(),
+            signature in arb_signature(),
+        ) {
+            let signed = SignedCheckpointWithMessageId { value, signature };
+            let json = serde_json::to_string(&signed).unwrap();
+            let decoded: SignedCheckpointWithMessageId = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(decoded, signed);
+        }
+
+        #[test]
+        fn signing_hash_commits_to_every_field(a in arb_checkpoint(), b in arb_checkpoint()) {
+            prop_assume!(a != b);
+            prop_assert_ne!(
+                a.signing_hash_with(&Keccak256Hasher),
+                b.signing_hash_with(&Keccak256Hasher)
+            );
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::H256;
+
+/// A change of the configuration of a Mailbox, e.g. to alert on unexpected
+/// changes of the security settings of a chain.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MailboxConfigChange {
+    /// The default ISM was set
+    DefaultIsmSet {
+        /// The default ISM before the change
+        previous_ism: H256,
+        /// The new default ISM
+        new_ism: H256,
+    },
+    /// Ownership of the Mailbox was transferred
+    OwnershipTransferred {
+        /// The owner before the transfer
+        previous_owner: Option<H256>,
+        /// The new owner. None if ownership was renounced.
+        new_owner: Option<H256>,
+    },
+}
+
+impl MailboxConfigChange {
+    /// Name of the kind of change, e.g. to store it in a database
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::DefaultIsmSet { .. } => "default_ism_set",
+            Self::OwnershipTransferred { .. } => "ownership_transferred",
+        }
+    }
+
+    /// The configured value before the change
+    pub fn previous_value(&self) -> Option<H256> {
+        match self {
+            Self::DefaultIsmSet { previous_ism, .. } => Some(*previous_ism),
+            Self::OwnershipTransferred { previous_owner, .. } => *previous_owner,
+        }
+    }
+
+    /// The configured value after the change
+    pub fn new_value(&self) -> Option<H256> {
+        match self {
+            Self::DefaultIsmSet { new_ism, .. } => Some(*new_ism),
+            Self::OwnershipTransferred { new_owner, .. } => *new_owner,
+        }
+    }
+}
@@ -165,3 +165,53 @@ impl HyperlaneMessage {
         H256::from_slice(Keccak256::new().chain(self.to_vec()).finalize().as_slice())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    prop_compose! {
+        fn arb_message()(
+            version in any::<u8>(),
+            nonce in any::<u32>(),
+            origin in any::<u32>(),
+            sender in any::<[u8; 32]>(),
+            destination in any::<u32>(),
+            recipient in any::<[u8; 32]>(),
+            body in vec(any::<u8>(), 0..1024),
+        ) -> HyperlaneMessage {
+            HyperlaneMessage {
+                version,
+                nonce,
+                origin,
+                sender: H256::from(sender),
+                destination,
+                recipient: H256::from(recipient),
+                body,
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn message_encoding_round_trips(message in arb_message()) {
+            let bytes = message.to_vec();
+            prop_assert_eq!(bytes.len(), HYPERLANE_MESSAGE_PREFIX_LEN + message.body.len());
+
+            let decoded = HyperlaneMessage::read_from(&mut bytes.as_slice()).unwrap();
+            prop_assert_eq!(&decoded, &message);
+            prop_assert_eq!(HyperlaneMessage::from(bytes), message);
+        }
+
+        #[test]
+        fn decoding_arbitrary_bytes_does_not_panic(bytes in vec(any::<u8>(), 0..256)) {
+            match HyperlaneMessage::read_from(&mut bytes.as_slice()) {
+                // Every byte after the prefix is part of the body
+                Ok(message) => prop_assert_eq!(message.to_vec(), bytes),
+                Err(_) => prop_assert!(bytes.len() < HYPERLANE_MESSAGE_PREFIX_LEN),
+            }
+        }
+    }
+}
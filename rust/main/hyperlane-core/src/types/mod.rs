@@ -2,6 +2,7 @@ use std::fmt;
 use std::io::{Read, Write};
 use std::ops::Add;
 
+use num::CheckedDiv;
 use serde::{Deserialize, Serialize};
 
 pub use self::primitive_types::*;
@@ -15,13 +16,15 @@ pub use checkpoint::*;
 pub use conversions::*;
 pub use indexing::*;
 pub use log_metadata::*;
+pub use mailbox_config::*;
 pub use merkle_tree::*;
 pub use message::*;
 pub use native_token::NativeToken;
+pub use pre_transaction::PreTransaction;
 pub use reorg::*;
 pub use transaction::*;
 
-use crate::{Decode, Encode, HyperlaneProtocolError};
+use crate::{ChainResult, Decode, Encode, HyperlaneProtocolError};
 
 /// This module contains enum for account address type
 mod account_address_type;
@@ -32,9 +35,11 @@ mod checkpoint;
 mod conversions;
 mod indexing;
 mod log_metadata;
+mod mailbox_config;
 mod merkle_tree;
 mod message;
 mod native_token;
+mod pre_transaction;
 mod reorg;
 mod serialize;
 mod transaction;
@@ -164,6 +169,25 @@ impl InterchainGasPayment {
     }
 }
 
+/// The values an IGP quotes the gas payments for a destination with, as set
+/// on its gas oracle
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct RemoteGasData {
+    /// The gas oracle the IGP reads the values from
+    pub gas_oracle: H256,
+    /// How many units of the origin's native token a unit of the
+    /// destination's native token is worth, scaled by
+    /// [`RemoteGasData::TOKEN_EXCHANGE_RATE_SCALE`]
+    pub token_exchange_rate: U256,
+    /// The gas price of the destination, in its smallest denomination
+    pub gas_price: U256,
+}
+
+impl RemoteGasData {
+    /// The scale of the token exchange rates of the IGPs
+    pub const TOKEN_EXCHANGE_RATE_SCALE: u64 = 10_000_000_000;
+}
+
 /// Amount of gas spent attempting to send the message.
 #[derive(Debug, Copy, Clone)]
 pub struct InterchainGasExpenditure {
@@ -269,11 +293,43 @@ pub struct TxCostEstimate {
     /// is used to cover L1 and L2 costs. For details:
     /// `<https://medium.com/offchainlabs/understanding-arbitrum-2-dimensional-fees-fd1d582596c9>`
     pub l2_gas_limit: Option<U256>,
+    /// The fee for posting the transaction's data to L1, denominated like
+    /// `gas_price`. Only present for OP Stack chains, where it is charged on
+    /// top of `gas_limit * gas_price`. For details:
+    /// `<https://docs.optimism.io/stack/transactions/fees#l1-data-fee>`
+    pub l1_data_fee: Option<U256>,
+    /// The L1 blob base fee `l1_data_fee` was priced at, in wei of the L1.
+    /// Only present for OP Stack chains since Ecotone, which post their data
+    /// to L1 in EIP-4844 blobs, so that their L1 data fee mostly follows it.
+    pub l1_blob_base_fee: Option<U256>,
 }
 
 impl TxCostEstimate {
     /// The gas limit to be used by gas enforcement policies.
-    pub fn enforceable_gas_limit(&self) -> U256 {
-        self.l2_gas_limit.unwrap_or(self.gas_limit)
+    /// The L1 data fee isn't covered by the gas limit, so it's added as
+    /// the amount of gas it's worth at `gas_price`.
+    pub fn enforceable_gas_limit(&self) -> ChainResult<U256> {
+        let gas_limit = self.l2_gas_limit.unwrap_or(self.gas_limit);
+        Ok(gas_limit.saturating_add(self.l1_data_fee_in_gas()?))
+    }
+
+    /// The L1 data fee, as the amount of gas it's worth at `gas_price`,
+    /// rounded up. Zero if there's no L1 data fee or the gas is free.
+    pub fn l1_data_fee_in_gas(&self) -> ChainResult<U256> {
+        let Some(l1_data_fee) = self.l1_data_fee else {
+            return Ok(U256::zero());
+        };
+        match FixedPointNumber::try_from(l1_data_fee)?.checked_div(&self.gas_price) {
+            Some(gas) => gas.ceil_to_integer().try_into(),
+            None => Ok(U256::zero()),
+        }
+    }
+
+    /// The estimated cost of the transaction, including the L1 data fee if
+    /// there is one.
+    pub fn total_cost(&self) -> ChainResult<U256> {
+        let execution_cost: U256 =
+            (FixedPointNumber::try_from(self.gas_limit)? * self.gas_price.clone()).try_into()?;
+        Ok(execution_cost.saturating_add(self.l1_data_fee.unwrap_or_default()))
     }
 }
@@ -0,0 +1,48 @@
+use std::fmt;
+
+use crate::H256;
+
+/// A transaction that has to land on the destination chain before a message
+/// can be delivered there, e.g. because the recipient requires an account to
+/// exist when it handles the message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreTransaction {
+    /// Creates the associated token account of the recipient of a warp route
+    /// transfer for `mint`, which Sealevel token programs require to exist
+    /// before tokens can be transferred to it. The relayer pays the rent of
+    /// the account, which the gas payment of the message doesn't cover.
+    CreateAssociatedTokenAccount {
+        /// The mint of the transferred token
+        mint: H256,
+    },
+    /// Calls `to` with `data`, e.g. to register the proof root the recipient
+    /// verifies the message against. The call is made at most once per
+    /// message, so it should be harmless to repeat for other messages.
+    Call {
+        /// The called contract
+        to: H256,
+        /// The calldata
+        data: Vec<u8>,
+    },
+}
+
+impl PreTransaction {
+    /// A short name of the kind of pre-transaction, e.g. for metric labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::CreateAssociatedTokenAccount { .. } => "create_associated_token_account",
+            Self::Call { .. } => "call",
+        }
+    }
+}
+
+impl fmt::Display for PreTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateAssociatedTokenAccount { mint } => {
+                write!(f, "CreateAssociatedTokenAccount(mint: {mint:?})")
+            }
+            Self::Call { to, .. } => write!(f, "Call(to: {to:?})"),
+        }
+    }
+}
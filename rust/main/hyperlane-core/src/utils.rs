@@ -1,11 +1,10 @@
 use eyre::Result;
-use sha3::{digest::Update, Digest, Keccak256};
 use std::str::FromStr;
 
 #[cfg(feature = "float")]
 use std::time::Duration;
 
-use crate::{KnownHyperlaneDomain, H160, H256, U256};
+use crate::{HyperlaneHasher, Keccak256Hasher, KnownHyperlaneDomain, H160, H256, U256};
 
 /// Converts a hex or base58 string to an H256.
 pub fn hex_or_base58_to_h256(string: &str) -> Result<H256> {
@@ -28,26 +27,39 @@ pub fn hex_or_base58_to_h256(string: &str) -> Result<H256> {
 
 /// Computes hash of domain concatenated with "HYPERLANE"
 pub fn domain_hash(address: H256, domain: impl Into<u32>) -> H256 {
-    H256::from_slice(
-        Keccak256::new()
-            .chain(domain.into().to_be_bytes())
-            .chain(address)
-            .chain("HYPERLANE")
-            .finalize()
-            .as_slice(),
-    )
+    domain_hash_with(&Keccak256Hasher, address, domain)
+}
+
+/// Computes hash of domain concatenated with "HYPERLANE", using `hasher`
+pub fn domain_hash_with(
+    hasher: &dyn HyperlaneHasher,
+    address: H256,
+    domain: impl Into<u32>,
+) -> H256 {
+    hasher.hash(&[
+        &domain.into().to_be_bytes(),
+        address.as_bytes(),
+        b"HYPERLANE",
+    ])
 }
 
 /// Computes hash of domain concatenated with "HYPERLANE_ANNOUNCEMENT"
 pub fn announcement_domain_hash(address: H256, domain: impl Into<u32>) -> H256 {
-    H256::from_slice(
-        Keccak256::new()
-            .chain(domain.into().to_be_bytes())
-            .chain(address)
-            .chain("HYPERLANE_ANNOUNCEMENT")
-            .finalize()
-            .as_slice(),
-    )
+    announcement_domain_hash_with(&Keccak256Hasher, address, domain)
+}
+
+/// Computes hash of domain concatenated with "HYPERLANE_ANNOUNCEMENT", using
+/// `hasher`
+pub fn announcement_domain_hash_with(
+    hasher: &dyn HyperlaneHasher,
+    address: H256,
+    domain: impl Into<u32>,
+) -> H256 {
+    hasher.hash(&[
+        &domain.into().to_be_bytes(),
+        address.as_bytes(),
+        b"HYPERLANE_ANNOUNCEMENT",
+    ])
 }
 
 /// Pretty print an address based on the domain it is for.
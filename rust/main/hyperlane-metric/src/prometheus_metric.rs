@@ -4,7 +4,7 @@ use std::{fmt::Debug, time::Instant};
 
 use derive_builder::Builder;
 use maplit::hashmap;
-use prometheus::{CounterVec, IntCounterVec};
+use prometheus::{CounterVec, HistogramVec, IntCounterVec};
 use serde::Deserialize;
 use url::Url;
 
@@ -21,6 +21,20 @@ pub const REQUEST_DURATION_SECONDS_LABELS: &[&str] =
 /// Help string for the metric.
 pub const REQUEST_DURATION_SECONDS_HELP: &str = "Total number of seconds spent making requests";
 
+/// Expected label names for the metric.
+pub const REQUEST_LATENCY_SECONDS_LABELS: &[&str] = &["provider_node", "chain", "method", "status"];
+/// Help string for the metric.
+pub const REQUEST_LATENCY_SECONDS_HELP: &str = "Latency of requests made to this client";
+/// Buckets for the metric, in seconds.
+pub const REQUEST_LATENCY_SECONDS_BUCKETS: &[f64] =
+    &[0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Expected label names for the metric.
+pub const REQUEST_ERROR_COUNT_LABELS: &[&str] = &["provider_node", "chain", "method", "error_type"];
+/// Help string for the metric.
+pub const REQUEST_ERROR_COUNT_HELP: &str =
+    "Total number of errors returned by this client, by how they were handled";
+
 /// Container for all the relevant rpc client metrics.
 #[derive(Clone, Builder, Default)]
 pub struct PrometheusClientMetrics {
@@ -45,6 +59,28 @@ pub struct PrometheusClientMetrics {
     ///   might still be an "error" but not one with the transport layer.
     #[builder(setter(into, strip_option), default)]
     pub request_duration_seconds: Option<CounterVec>,
+
+    /// Latency of requests made to this client.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the request was made on.
+    /// - `method`: request method string.
+    /// - `status`: `success` or `failure` depending on the response.
+    #[builder(setter(into, strip_option), default)]
+    pub request_latency_seconds: Option<HistogramVec>,
+
+    /// Total number of errors returned by this client, as categorized by the
+    /// provider wrapping it (e.g. a retrying or fallback provider).
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the request was made on.
+    /// - `method`: request method string.
+    /// - `error_type`: how the error was handled, e.g. `retryable`,
+    ///   `rate_limited` or `non_retryable`.
+    #[builder(setter(into, strip_option), default)]
+    pub request_error_count: Option<IntCounterVec>,
 }
 
 impl PrometheusClientMetrics {
@@ -65,12 +101,28 @@ impl PrometheusClientMetrics {
         if let Some(counter) = &self.request_count {
             counter.with(&labels).inc()
         }
+        let elapsed = (Instant::now() - start).as_secs_f64();
         if let Some(counter) = &self.request_duration_seconds {
-            counter
-                .with(&labels)
-                .inc_by((Instant::now() - start).as_secs_f64())
+            counter.with(&labels).inc_by(elapsed)
+        };
+        if let Some(histogram) = &self.request_latency_seconds {
+            histogram.with(&labels).observe(elapsed)
         };
     }
+
+    /// Update the error counter for a request that failed with `error_type`
+    pub fn increment_error_count(&self, config: &PrometheusConfig, method: &str, error_type: &str) {
+        if let Some(counter) = &self.request_error_count {
+            counter
+                .with(&hashmap! {
+                    "provider_node" => config.node_host(),
+                    "chain" => config.chain_name(),
+                    "method" => method,
+                    "error_type" => error_type,
+                })
+                .inc()
+        }
+    }
 }
 
 /// Some basic information about a chain.
@@ -121,6 +173,9 @@ pub trait PrometheusConfigExt {
     fn node_host(&self) -> &str;
     /// Chain name this RPC client is connected to.
     fn chain_name(&self) -> &str;
+    /// Records an error returned by this node for `method`, categorized as
+    /// `error_type`. Does nothing unless the implementor tracks metrics.
+    fn record_request_error(&self, _method: &str, _error_type: &str) {}
 }
 
 impl PrometheusConfigExt for PrometheusConfig {
@@ -32,11 +32,15 @@ mock! {
 
         pub fn _latest_checkpoint(&self, reorg_period: &ReorgPeriod) -> ChainResult<Checkpoint> {}
 
+        pub fn _local_domain(&self) -> ChainResult<u32> {}
+
         pub fn _default_ism(&self) -> ChainResult<H256> {}
         pub fn _recipient_ism(&self, recipient: H256) -> ChainResult<H256> {}
 
         pub fn _delivered(&self, id: H256) -> ChainResult<bool> {}
 
+        pub fn _delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {}
+
         pub fn process(
             &self,
             message: &HyperlaneMessage,
@@ -55,6 +59,18 @@ mock! {
             message: &HyperlaneMessage,
             metadata: &[u8],
         ) -> Vec<u8> {}
+
+        pub fn _pre_transaction_required(
+            &self,
+            message: &HyperlaneMessage,
+            pre_transaction: &PreTransaction,
+        ) -> ChainResult<bool> {}
+
+        pub fn _submit_pre_transaction(
+            &self,
+            message: &HyperlaneMessage,
+            pre_transaction: &PreTransaction,
+        ) -> ChainResult<TxOutcome> {}
     }
 }
 
@@ -70,6 +86,10 @@ impl Mailbox for MockMailboxContract {
         self._count(reorg_period)
     }
 
+    async fn local_domain(&self) -> ChainResult<u32> {
+        self._local_domain()
+    }
+
     async fn default_ism(&self) -> ChainResult<H256> {
         self._default_ism()
     }
@@ -82,6 +102,10 @@ impl Mailbox for MockMailboxContract {
         self._delivered(id)
     }
 
+    async fn delivered_batch(&self, ids: &[H256]) -> ChainResult<Vec<bool>> {
+        self._delivered_batch(ids)
+    }
+
     async fn process(
         &self,
         message: &HyperlaneMessage,
@@ -109,6 +133,22 @@ impl Mailbox for MockMailboxContract {
     fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
         self.process_calldata(message, metadata)
     }
+
+    async fn pre_transaction_required(
+        &self,
+        message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<bool> {
+        self._pre_transaction_required(message, pre_transaction)
+    }
+
+    async fn submit_pre_transaction(
+        &self,
+        message: &HyperlaneMessage,
+        pre_transaction: &PreTransaction,
+    ) -> ChainResult<TxOutcome> {
+        self._submit_pre_transaction(message, pre_transaction)
+    }
 }
 
 impl HyperlaneChain for MockMailboxContract {
@@ -1,4 +1,5 @@
 use std::env;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 #[derive(Debug)]
@@ -7,6 +8,7 @@ pub struct Config {
     pub ci_mode: bool,
     pub ci_mode_timeout: u64,
     pub kathy_messages: u64,
+    pub scenario: Option<PathBuf>,
     // TODO: Include count of sealevel messages in a field separate from `kathy_messages`?
 }
 
@@ -27,6 +29,7 @@ impl Config {
                     .map(|r| r.parse::<u64>().unwrap());
                 r.unwrap_or(16)
             },
+            scenario: env::var("E2E_SCENARIO").ok().map(PathBuf::from),
         })
     }
 }
@@ -13,6 +13,8 @@
 //! the end conditions are met, the test is a failure. Defaults to 10 min.
 //! - `E2E_KATHY_MESSAGES`: Number of kathy messages to dispatch. Defaults to 16 if CI mode is enabled.
 //! else false.
+//! - `E2E_SCENARIO`: Path to a `.toml` or `.json` scenario to run once the agents
+//!   are up, instead of the default checks. See the `scenario` module.
 
 use std::{
     collections::HashMap,
@@ -44,6 +46,7 @@ use crate::{
     ethereum::start_anvil,
     invariants::{post_startup_invariants, termination_invariants_met},
     metrics::agent_balance_sum,
+    scenario::{Scenario, ScenarioAgent, ScenarioRunner},
     utils::{concat_path, make_static, stop_child, AgentHandles, ArbitraryData, TaskHandle},
 };
 
@@ -53,6 +56,7 @@ mod invariants;
 mod logging;
 mod metrics;
 mod program;
+mod scenario;
 mod server;
 mod utils;
 
@@ -162,6 +166,11 @@ fn main() -> ExitCode {
 
     let config = Config::load();
     log!("Running with config: {:?}", config);
+    // loaded before the setup to fail fast on invalid scenarios
+    let scenario = config
+        .scenario
+        .as_deref()
+        .map(|path| Scenario::load(path).expect("Invalid scenario"));
 
     let ts_infra_path = get_ts_infra_path();
 
@@ -322,6 +331,27 @@ fn main() -> ExitCode {
         .arg("required-hook", "merkleTreeHook");
     kathy_env_double_insertion.clone().run().join();
 
+    let mut scenario_agents: HashMap<String, ScenarioAgent> = validator_envs
+        .iter()
+        .enumerate()
+        .map(|(i, validator_env)| {
+            let agent = ScenarioAgent {
+                handle: make_static(format!("VL{}", 1 + i)),
+                program: validator_env.clone(),
+                metrics_port: (9094 + i).to_string(),
+            };
+            (format!("validator{}", 1 + i), agent)
+        })
+        .collect();
+    scenario_agents.insert(
+        "relayer".to_owned(),
+        ScenarioAgent {
+            handle: "RLY",
+            program: relayer_env.clone(),
+            metrics_port: RELAYER_METRICS_PORT.to_owned(),
+        },
+    );
+
     // spawn the rest of the validators
     for (i, validator_env) in validator_envs.into_iter().enumerate().skip(1) {
         let validator = validator_env.spawn(
@@ -336,6 +366,16 @@ fn main() -> ExitCode {
     log!("Setup complete! Agents running in background...");
     log!("Ctrl+C to end execution...");
 
+    if let Some(scenario) = scenario {
+        let passed = ScenarioRunner::new(&mut state, &config, scenario_agents, &ts_infra_path)
+            .map(|mut runner| runner.run(&scenario))
+            .unwrap_or_else(|err| {
+                log!("Failed to set up the scenario: {:?}", err);
+                false
+            });
+        return report_test_result(passed);
+    }
+
     // Send half the kathy messages after the relayer comes up
     kathy_env_double_insertion.clone().run().join();
     kathy_env_zero_insertion.clone().run().join();
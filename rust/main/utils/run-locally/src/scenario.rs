@@ -0,0 +1,627 @@
+//! Scripted scenarios, to encode regression cases such as "dispatch 10
+//! messages, stop the relayer, pay for the gas of message 3 late, restart the
+//! relayer" as repeatable tests.
+//!
+//! A scenario is a TOML or JSON file with a sequence of `steps`, run once the
+//! agents are up, and `assertions` on the final state, polled until they all
+//! hold or the CI timeout is reached. Messages are referred to by their
+//! 1-based index among the messages the scenario dispatched. See the
+//! `scenarios` directory for examples.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    sync::{atomic::Ordering, Arc},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use ethers::{
+    abi::{self, ParamType, Token},
+    middleware::SignerMiddleware,
+    providers::{Http, Middleware, Provider},
+    signers::{LocalWallet, Signer},
+    types::{Bytes, Filter, TransactionRequest, H160, H256, U256, U64},
+    utils::{id, keccak256},
+};
+use eyre::{bail, eyre, Context, Result};
+use serde::Deserialize;
+use tokio::runtime::Runtime;
+
+use crate::{
+    config::Config, fetch_metric, logging::log, long_running_processes_exited_check,
+    program::Program, State, AGENT_LOGGING_DIR, SHUTDOWN,
+};
+
+/// The anvil account kathy dispatches from, which also pays for gas late
+const ANVIL_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const ANVIL_CHAIN_ID: u64 = 31337;
+const ANVIL_RPC_URL: &str = "http://127.0.0.1:8545";
+
+/// The test chains of the SDK, which all run on the same anvil node
+const TEST_CHAINS: &[(&str, u32)] = &[("test1", 9913371), ("test2", 9913372), ("test3", 9913373)];
+
+const DEFAULT_GAS_AMOUNT: u64 = 100_000;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub steps: Vec<Step>,
+    /// Checked once all the steps ran
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Step {
+    /// Dispatches messages with kathy, from `origin` or round robin from the
+    /// test chains, to random destinations
+    Dispatch {
+        messages: usize,
+        #[serde(default)]
+        origin: Option<String>,
+        /// Whether the dispatch pays for the gas. Messages that don't only
+        /// go through the merkle tree hook.
+        #[serde(default = "default_true")]
+        pay_gas: bool,
+    },
+    /// Pays the IGP of the origin of a message for its gas
+    PayGas {
+        message: usize,
+        #[serde(default = "default_gas_amount")]
+        gas_amount: u64,
+    },
+    /// Stops an agent, e.g. `relayer` or `validator2`
+    StopAgent {
+        agent: String,
+    },
+    /// Starts an agent stopped by a previous step
+    StartAgent {
+        agent: String,
+    },
+    /// Stops and starts an agent, keeping its database
+    RestartAgent {
+        agent: String,
+    },
+    Sleep {
+        secs: u64,
+    },
+    /// Waits until an assertion holds, failing the scenario on timeout
+    Await(Assertion),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum Assertion {
+    /// The messages were processed on their destination
+    Delivered { messages: Vec<usize> },
+    /// The messages weren't processed on their destination
+    NotDelivered { messages: Vec<usize> },
+    /// The sum of the values of a metric of an agent, filtered by labels, is
+    /// within bounds
+    Metric {
+        #[serde(default = "default_agent")]
+        agent: String,
+        name: String,
+        #[serde(default)]
+        labels: BTreeMap<String, String>,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_gas_amount() -> u64 {
+    DEFAULT_GAS_AMOUNT
+}
+
+fn default_agent() -> String {
+    "relayer".to_owned()
+}
+
+impl Scenario {
+    /// Loads a scenario from a `.toml` or `.json` file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read scenario {}", path.display()))?;
+        let scenario: Self = match path.extension().and_then(OsStr::to_str) {
+            Some("toml") => toml_edit::de::from_str(&contents)?,
+            Some("json") => serde_json::from_str(&contents)?,
+            _ => bail!("Scenario {} isn't a .toml or .json file", path.display()),
+        };
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Checks that messages are only referred to once dispatched
+    fn validate(&self) -> Result<()> {
+        let mut dispatched = 0;
+        for (index, step) in self.steps.iter().enumerate() {
+            let step_number = index + 1;
+            let referred = match step {
+                Step::Dispatch { messages, .. } => {
+                    dispatched += messages;
+                    vec![]
+                }
+                Step::PayGas { message, .. } => vec![*message],
+                Step::Await(assertion) => assertion.messages().to_vec(),
+                _ => vec![],
+            };
+            if let Some(message) = referred
+                .into_iter()
+                .find(|message| *message == 0 || *message > dispatched)
+            {
+                bail!(
+                    "Step {step_number} refers to message {message}, but only {dispatched} were dispatched before it"
+                );
+            }
+        }
+        if let Some(message) = self
+            .assertions
+            .iter()
+            .flat_map(Assertion::messages)
+            .find(|message| **message == 0 || **message > dispatched)
+        {
+            bail!(
+                "An assertion refers to message {message}, but only {dispatched} were dispatched"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Assertion {
+    fn messages(&self) -> &[usize] {
+        match self {
+            Self::Delivered { messages } | Self::NotDelivered { messages } => messages,
+            Self::Metric { .. } => &[],
+        }
+    }
+}
+
+/// An agent the scenario can stop and start
+pub struct ScenarioAgent {
+    /// The name of the agent's handles in the [`State`], e.g. `RLY`
+    pub handle: &'static str,
+    pub program: Program,
+    pub metrics_port: String,
+}
+
+#[derive(Debug, Clone)]
+struct TestChain {
+    name: &'static str,
+    domain: u32,
+    mailbox: H160,
+    igp: H160,
+}
+
+#[derive(Debug, Clone)]
+struct DispatchedMessage {
+    id: H256,
+    origin: TestChain,
+    destination: TestChain,
+}
+
+type AnvilClient = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Runs a scenario against the agents and the test chains
+pub struct ScenarioRunner<'a> {
+    state: &'a mut State,
+    config: &'a Config,
+    agents: HashMap<String, ScenarioAgent>,
+    ts_infra_path: PathBuf,
+    runtime: Runtime,
+    client: Arc<AnvilClient>,
+    chains: Vec<TestChain>,
+    dispatched: Vec<DispatchedMessage>,
+}
+
+impl<'a> ScenarioRunner<'a> {
+    pub fn new(
+        state: &'a mut State,
+        config: &'a Config,
+        agents: HashMap<String, ScenarioAgent>,
+        ts_infra_path: &Path,
+    ) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(ANVIL_RPC_URL)?;
+        let wallet = ANVIL_KEY
+            .parse::<LocalWallet>()?
+            .with_chain_id(ANVIL_CHAIN_ID);
+        let addresses: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+            ts_infra_path.join("config/environments/test/core/addresses.json"),
+        )?)?;
+        let address = |chain: &str, contract: &str| -> Result<H160> {
+            addresses[chain][contract]
+                .as_str()
+                .ok_or_else(|| eyre!("No {contract} address for {chain}"))?
+                .parse::<H160>()
+                .map_err(Into::into)
+        };
+        let chains = TEST_CHAINS
+            .iter()
+            .map(|&(name, domain)| {
+                Ok(TestChain {
+                    name,
+                    domain,
+                    mailbox: address(name, "mailbox")?,
+                    igp: address(name, "interchainGasPaymaster")?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            state,
+            config,
+            agents,
+            ts_infra_path: ts_infra_path.to_owned(),
+            runtime: tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+            client: Arc::new(SignerMiddleware::new(provider, wallet)),
+            chains,
+            dispatched: vec![],
+        })
+    }
+
+    /// Runs the steps, then waits for the assertions. Returns whether the
+    /// scenario passed.
+    pub fn run(&mut self, scenario: &Scenario) -> bool {
+        log!("Running scenario {}...", scenario.name);
+        if let Some(description) = &scenario.description {
+            log!("{}", description);
+        }
+        for (index, step) in scenario.steps.iter().enumerate() {
+            log!("Scenario step {}: {:?}", index + 1, step);
+            match self.run_step(step) {
+                Ok(true) => {}
+                Ok(false) => return false,
+                Err(err) => {
+                    log!("Scenario step {} failed: {:?}", index + 1, err);
+                    return false;
+                }
+            }
+        }
+        self.await_assertions(&scenario.assertions)
+    }
+
+    /// Returns whether the scenario should go on
+    fn run_step(&mut self, step: &Step) -> Result<bool> {
+        match step {
+            Step::Dispatch {
+                messages,
+                origin,
+                pay_gas,
+            } => self.dispatch(*messages, origin.as_deref(), *pay_gas)?,
+            Step::PayGas {
+                message,
+                gas_amount,
+            } => self.pay_gas(*message, *gas_amount)?,
+            Step::StopAgent { agent } => self.stop_agent(agent)?,
+            Step::StartAgent { agent } => self.start_agent(agent)?,
+            Step::RestartAgent { agent } => {
+                self.stop_agent(agent)?;
+                self.start_agent(agent)?;
+            }
+            Step::Sleep { secs } => sleep(Duration::from_secs(*secs)),
+            Step::Await(assertion) => {
+                return Ok(self.await_assertions(std::slice::from_ref(assertion)))
+            }
+        }
+        Ok(true)
+    }
+
+    fn dispatch(&mut self, messages: usize, origin: Option<&str>, pay_gas: bool) -> Result<()> {
+        let client = self.client.clone();
+        let from_block = self.runtime.block_on(client.get_block_number())? + 1;
+
+        // kathy sets the default hook of the mailboxes before each dispatch,
+        // so it's always passed to not depend on the previous steps
+        let mut kathy = Program::new("yarn")
+            .working_dir(&self.ts_infra_path)
+            .cmd("kathy")
+            .arg("messages", messages.to_string())
+            .arg("timeout", "1000")
+            .arg(
+                "default-hook",
+                if pay_gas {
+                    "aggregationHook"
+                } else {
+                    "merkleTreeHook"
+                },
+            );
+        if let Some(origin) = origin {
+            kathy = kathy.arg("single-origin", origin);
+        }
+        kathy.run().join();
+
+        let dispatched = self.runtime.block_on(self.dispatched_since(from_block))?;
+        if dispatched.len() != messages {
+            bail!(
+                "Expected {messages} messages to be dispatched, found {}",
+                dispatched.len()
+            );
+        }
+        for message in &dispatched {
+            log!(
+                "Scenario message {}: {:?} from {} to {}",
+                self.dispatched.len() + 1,
+                message.id,
+                message.origin.name,
+                message.destination.name
+            );
+            self.dispatched.push(message.clone());
+        }
+        Ok(())
+    }
+
+    /// The messages dispatched on the test chains since a block, in order
+    async fn dispatched_since(&self, from_block: U64) -> Result<Vec<DispatchedMessage>> {
+        let filter = Filter::new()
+            .address(
+                self.chains
+                    .iter()
+                    .map(|chain| chain.mailbox)
+                    .collect::<Vec<_>>(),
+            )
+            .event("Dispatch(address,uint32,bytes32,bytes)")
+            .from_block(from_block);
+        let mut logs = self.client.get_logs(&filter).await?;
+        logs.sort_by_key(|log| (log.block_number, log.log_index));
+        logs.into_iter()
+            .map(|log| {
+                let origin = self.chain_by(|chain| chain.mailbox == log.address)?;
+                let destination = log
+                    .topics
+                    .get(2)
+                    .map(|topic| U256::from_big_endian(topic.as_bytes()).low_u32())
+                    .ok_or_else(|| eyre!("Dispatch log without a destination"))?;
+                let destination = self.chain_by(|chain| chain.domain == destination)?;
+                let message = match abi::decode(&[ParamType::Bytes], &log.data)?.pop() {
+                    Some(Token::Bytes(message)) => message,
+                    _ => bail!("Dispatch log without a message"),
+                };
+                Ok(DispatchedMessage {
+                    id: keccak256(message).into(),
+                    origin,
+                    destination,
+                })
+            })
+            .collect()
+    }
+
+    fn pay_gas(&mut self, message: usize, gas_amount: u64) -> Result<()> {
+        let message = self.message(message)?.clone();
+        let client = self.client.clone();
+        self.runtime.block_on(async move {
+            let quote = client
+                .call(
+                    &TransactionRequest::new()
+                        .to(message.origin.igp)
+                        .data(calldata(
+                            "quoteGasPayment(uint32,uint256)",
+                            &[
+                                Token::Uint(message.destination.domain.into()),
+                                Token::Uint(gas_amount.into()),
+                            ],
+                        ))
+                        .into(),
+                    None,
+                )
+                .await
+                .wrap_err(
+                    "Failed to quote the gas payment, is the destination's gas oracle set?",
+                )?;
+            let quote = U256::from_big_endian(&quote);
+            let pay_for_gas = TransactionRequest::new()
+                .to(message.origin.igp)
+                .value(quote)
+                .data(calldata(
+                    "payForGas(bytes32,uint32,uint256,address)",
+                    &[
+                        Token::FixedBytes(message.id.as_bytes().to_vec()),
+                        Token::Uint(message.destination.domain.into()),
+                        Token::Uint(gas_amount.into()),
+                        Token::Address(client.address()),
+                    ],
+                ));
+            let receipt = client
+                .send_transaction(pay_for_gas, None)
+                .await?
+                .await?
+                .ok_or_else(|| eyre!("The gas payment was dropped"))?;
+            if receipt.status != Some(1.into()) {
+                bail!("The gas payment reverted");
+            }
+            log!("Paid {} for the gas of message {:?}", quote, message.id);
+            Ok(())
+        })
+    }
+
+    fn stop_agent(&mut self, agent: &str) -> Result<()> {
+        let handle = self.agent(agent)?.handle;
+        let (mut child, _) = self
+            .state
+            .agents
+            .remove(handle)
+            .ok_or_else(|| eyre!("Agent {agent} isn't running"))?;
+        log!("Stopping {}...", agent);
+        child.kill()?;
+        child.wait()?;
+        Ok(())
+    }
+
+    fn start_agent(&mut self, agent: &str) -> Result<()> {
+        let ScenarioAgent {
+            handle, program, ..
+        } = self.agent(agent)?;
+        let (handle, program) = (*handle, program.clone());
+        if self.state.agents.contains_key(handle) {
+            bail!("Agent {agent} is already running");
+        }
+        log!("Starting {}...", agent);
+        let handles = program.spawn(handle, Some(&AGENT_LOGGING_DIR));
+        self.state.push_agent(handles);
+        Ok(())
+    }
+
+    /// Polls the assertions until they all hold. Returns false on timeout,
+    /// shutdown, or if an agent exited.
+    fn await_assertions(&mut self, assertions: &[Assertion]) -> bool {
+        let start = Instant::now();
+        loop {
+            let failure = assertions
+                .iter()
+                .find_map(|assertion| match self.check(assertion) {
+                    Ok(true) => None,
+                    Ok(false) => Some(format!("{assertion:?} doesn't hold")),
+                    Err(err) => Some(format!("{assertion:?} couldn't be checked: {err}")),
+                });
+            let Some(failure) = failure else {
+                return true;
+            };
+            if SHUTDOWN.load(Ordering::Relaxed) {
+                return false;
+            }
+            if (Instant::now() - start).as_secs() > self.config.ci_mode_timeout {
+                log!("Error: Scenario timed out: {}", failure);
+                return false;
+            }
+            if long_running_processes_exited_check(self.state) {
+                SHUTDOWN.store(true, Ordering::Relaxed);
+                return false;
+            }
+            log!("Waiting: {}", failure);
+            sleep(Duration::from_secs(5));
+        }
+    }
+
+    fn check(&self, assertion: &Assertion) -> Result<bool> {
+        match assertion {
+            Assertion::Delivered { messages } => self.all_delivered(messages, true),
+            Assertion::NotDelivered { messages } => self.all_delivered(messages, false),
+            Assertion::Metric {
+                agent,
+                name,
+                labels,
+                min,
+                max,
+            } => {
+                let labels = labels
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect();
+                let value: f64 =
+                    fetch_metric::<f64, _>(&self.agent(agent)?.metrics_port, name, &labels)?
+                        .iter()
+                        .sum();
+                Ok(min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max))
+            }
+        }
+    }
+
+    /// Whether all the messages have the given delivery status
+    fn all_delivered(&self, messages: &[usize], delivered: bool) -> Result<bool> {
+        for message in messages {
+            let message = self.message(*message)?;
+            let result = self.runtime.block_on(
+                self.client.call(
+                    &TransactionRequest::new()
+                        .to(message.destination.mailbox)
+                        .data(calldata(
+                            "delivered(bytes32)",
+                            &[Token::FixedBytes(message.id.as_bytes().to_vec())],
+                        ))
+                        .into(),
+                    None,
+                ),
+            )?;
+            if U256::from_big_endian(&result).is_zero() == delivered {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn message(&self, message: usize) -> Result<&DispatchedMessage> {
+        message
+            .checked_sub(1)
+            .and_then(|index| self.dispatched.get(index))
+            .ok_or_else(|| eyre!("Message {message} wasn't dispatched by the scenario"))
+    }
+
+    fn agent(&self, agent: &str) -> Result<&ScenarioAgent> {
+        self.agents
+            .get(agent)
+            .ok_or_else(|| eyre!("Unknown agent {agent}"))
+    }
+
+    fn chain_by(&self, predicate: impl Fn(&TestChain) -> bool) -> Result<TestChain> {
+        self.chains
+            .iter()
+            .find(|chain| predicate(chain))
+            .cloned()
+            .ok_or_else(|| eyre!("Dispatch from or to an unknown chain"))
+    }
+}
+
+fn calldata(signature: &str, args: &[Token]) -> Bytes {
+    [id(signature).as_slice(), &abi::encode(args)]
+        .concat()
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_example_scenarios_are_valid() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if let Err(err) = Scenario::load(&path) {
+                panic!("Invalid scenario {}: {err:?}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn test_messages_are_referred_to_once_dispatched() {
+        let scenario: Scenario = serde_json::from_str(
+            r#"{
+                "name": "early payment",
+                "steps": [
+                    { "action": "dispatch", "messages": 2, "pay_gas": false },
+                    { "action": "pay_gas", "message": 3 },
+                    { "action": "dispatch", "messages": 1 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(scenario.validate().is_err());
+
+        let scenario: Scenario = serde_json::from_str(
+            r#"{
+                "name": "late payment",
+                "steps": [
+                    { "action": "dispatch", "messages": 3, "pay_gas": false },
+                    { "action": "pay_gas", "message": 3 },
+                    { "action": "await", "check": "delivered", "messages": [3] }
+                ],
+                "assertions": [{ "check": "not_delivered", "messages": [1, 2] }]
+            }"#,
+        )
+        .unwrap();
+        scenario.validate().unwrap();
+    }
+}
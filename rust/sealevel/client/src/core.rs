@@ -5,7 +5,10 @@ use serde::{Deserialize, Serialize};
 use solana_program::pubkey::Pubkey;
 use solana_sdk::{compute_budget, compute_budget::ComputeBudgetInstruction};
 
-use std::{fs::File, path::Path};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
 
 use crate::cmd_utils::get_compute_unit_price_micro_lamports_for_chain_name;
 use crate::ONE_SOL_IN_LAMPORTS;
@@ -298,12 +301,19 @@ pub(crate) fn read_core_program_ids(
     environment: &str,
     chain: &str,
 ) -> CoreProgramIds {
-    let path = environments_dir
+    read_json(&core_program_ids_path(environments_dir, environment, chain))
+}
+
+pub(crate) fn core_program_ids_path(
+    environments_dir: &Path,
+    environment: &str,
+    chain: &str,
+) -> PathBuf {
+    environments_dir
         .join(environment)
         .join(chain)
         .join("core")
-        .join("program-ids.json");
-    read_json(&path)
+        .join("program-ids.json")
 }
 
 #[cfg(test)]
@@ -9,7 +9,6 @@ use clap::{Args, Parser, Subcommand, ValueEnum};
 use solana_clap_utils::input_validators::{is_keypair, is_url, normalize_to_url_if_moniker};
 use solana_cli_config::{Config, CONFIG_FILE};
 use solana_client::rpc_client::RpcClient;
-use solana_program::pubkey;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
@@ -21,6 +20,7 @@ use solana_sdk::{
 
 use account_utils::DiscriminatorEncode;
 use hyperlane_core::{H160, H256};
+use hyperlane_sealevel_client_lib::mailbox as client_mailbox;
 use hyperlane_sealevel_connection_client::router::RemoteRouterConfig;
 use hyperlane_sealevel_igp::{
     accounts::{InterchainGasPaymasterType, OverheadIgpAccount},
@@ -28,10 +28,9 @@ use hyperlane_sealevel_igp::{
 };
 use hyperlane_sealevel_mailbox::{
     accounts::{InboxAccount, OutboxAccount},
-    instruction::{Instruction as MailboxInstruction, OutboxDispatch},
+    instruction::OutboxDispatch,
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds,
     mailbox_message_dispatch_authority_pda_seeds, mailbox_outbox_pda_seeds,
-    mailbox_processed_message_pda_seeds,
     protocol_fee::ProtocolFee,
     spl_noop,
 };
@@ -41,6 +40,7 @@ use hyperlane_sealevel_token::{
     spl_associated_token_account::get_associated_token_address_with_program_id, spl_token_2022,
 };
 use hyperlane_sealevel_token_collateral::{
+    accounts::DepositorAllowlistAccount, hyperlane_token_depositor_allowlist_pda_seeds,
     hyperlane_token_escrow_pda_seeds, plugin::CollateralPlugin,
 };
 use hyperlane_sealevel_token_lib::{
@@ -67,6 +67,7 @@ mod r#core;
 mod helloworld;
 mod igp;
 mod multisig_ism;
+mod program_ids;
 mod router;
 mod serde;
 mod warp_route;
@@ -74,6 +75,10 @@ mod warp_route;
 use crate::helloworld::process_helloworld_cmd;
 use crate::igp::process_igp_cmd;
 use crate::multisig_ism::process_multisig_ism_message_id_cmd;
+use crate::program_ids::{
+    hyperlane_token_program_id, mailbox_program_id, multisig_ism_message_id_program_id,
+    program_id_or_default, validator_announce_program_id,
+};
 use crate::warp_route::process_warp_route_cmd;
 pub(crate) use crate::{context::*, core::*};
 
@@ -133,6 +138,7 @@ pub(crate) struct WarpRouteCmd {
 pub(crate) enum WarpRouteSubCmd {
     Deploy(WarpRouteDeploy),
     DestinationGas(DestinationGasArgs),
+    Audit(WarpRouteAudit),
 }
 
 #[derive(Args)]
@@ -151,6 +157,20 @@ pub(crate) struct WarpRouteDeploy {
     ata_payer_funding_amount: Option<u64>,
 }
 
+#[derive(Args)]
+pub(crate) struct WarpRouteAudit {
+    #[command(flatten)]
+    env_args: EnvironmentArgs,
+    #[arg(long)]
+    warp_route_name: String,
+    #[arg(long)]
+    token_config_file: PathBuf,
+    #[arg(long)]
+    chain_config_file: PathBuf,
+    #[arg(long)]
+    output_file: Option<PathBuf>,
+}
+
 #[derive(Args)]
 struct DestinationGasArgs {
     #[arg(long)]
@@ -204,24 +224,22 @@ enum MailboxSubCmd {
     Delivered(Delivered),
     TransferOwnership(TransferOwnership),
     SetDefaultIsm(SetDefaultIsm),
+    SetDefaultIsmOverride(SetDefaultIsmOverride),
 }
 
-const MAILBOX_PROG_ID: Pubkey = pubkey!("692KZJaoe2KRcD6uhCQDLLXnLNA5ZLnfvdqjE4aX9iu1");
-const HYPERLANE_TOKEN_PROG_ID: Pubkey = pubkey!("3MzUPjP5LEkiHH82nEAe28Xtz9ztuMqWc8UmuKxrpVQH");
-const MULTISIG_ISM_MESSAGE_ID_PROG_ID: Pubkey =
-    pubkey!("2YjtZDiUoptoSsA5eVrDCcX6wxNK6YoEVW7y82x5Z2fw");
-const VALIDATOR_ANNOUNCE_PROG_ID: Pubkey = pubkey!("DH43ae1LwemXAboWwSh8zc9pG8j72gKUEXNi57w8fEnn");
 pub const DEFAULT_PROTOCOL_FEE: u64 = 0;
 pub const ONE_SOL_IN_LAMPORTS: u64 = 1_000_000_000;
 
 #[derive(Args)]
 struct Init {
-    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the mailbox program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     #[arg(long, short, default_value_t = ECLIPSE_DOMAIN)]
     local_domain: u32,
-    #[arg(long, short, default_value_t = MULTISIG_ISM_MESSAGE_ID_PROG_ID)]
-    default_ism: Pubkey,
+    /// Defaults to the multisig ISM program of the configured environment
+    #[arg(long, short)]
+    default_ism: Option<Pubkey>,
     #[arg(long, short, default_value_t = ONE_SOL_IN_LAMPORTS)]
     max_protocol_fee: u64,
     #[arg(long, short, default_value_t = DEFAULT_PROTOCOL_FEE)]
@@ -232,8 +250,9 @@ struct Init {
 
 #[derive(Args)]
 struct Query {
-    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the mailbox program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
 }
 
 #[derive(Args)]
@@ -244,6 +263,17 @@ struct SetDefaultIsm {
     default_ism: Pubkey,
 }
 
+#[derive(Args)]
+struct SetDefaultIsmOverride {
+    #[arg(long, short)]
+    program_id: Pubkey,
+    #[arg(long, short)]
+    origin: u32,
+    /// The ISM of the origin's messages. Removes the override if omitted.
+    #[arg(long, short)]
+    ism: Option<Pubkey>,
+}
+
 #[derive(Args)]
 struct Outbox {
     #[arg(long, short, default_value_t = ECLIPSE_DOMAIN)]
@@ -252,8 +282,9 @@ struct Outbox {
     recipient: Pubkey,
     #[arg(long, short, default_value = "Hello, World!")]
     message: String,
-    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the mailbox program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
 }
 
 #[derive(Args)]
@@ -268,16 +299,19 @@ struct Inbox {
     message: String,
     #[arg(long, short, default_value_t = 1)]
     nonce: u32,
-    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
-    program_id: Pubkey,
-    #[arg(long, default_value_t = MULTISIG_ISM_MESSAGE_ID_PROG_ID)]
-    ism: Pubkey,
+    /// Defaults to the mailbox program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
+    /// Defaults to the multisig ISM program of the configured environment
+    #[arg(long)]
+    ism: Option<Pubkey>,
 }
 
 #[derive(Args)]
 struct Delivered {
-    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the mailbox program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     #[arg(long, short)]
     message_id: H256,
 }
@@ -296,6 +330,7 @@ enum TokenSubCmd {
     TransferOwnership(TransferOwnership),
     SetInterchainSecurityModule(SetInterchainSecurityModule),
     Igp(Igp),
+    DepositorAllowlist(DepositorAllowlist),
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -307,16 +342,18 @@ pub enum TokenType {
 
 #[derive(Args)]
 struct TokenQuery {
-    #[arg(long, short, default_value_t = HYPERLANE_TOKEN_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the token program of the configured warp route
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     #[arg(value_enum)]
     token_type: TokenType,
 }
 
 #[derive(Args)]
 struct TokenTransferRemote {
-    #[arg(long, short, default_value_t = HYPERLANE_TOKEN_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the token program of the configured warp route
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     // Note this is the keypair for normal account not the derived associated token account or delegate.
     sender: String,
     amount: u64,
@@ -329,8 +366,9 @@ struct TokenTransferRemote {
 
 #[derive(Args)]
 struct TokenEnrollRemoteRouter {
-    #[arg(long, short, default_value_t = HYPERLANE_TOKEN_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the token program of the configured warp route
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     domain: u32,
     router: H256,
 }
@@ -354,12 +392,36 @@ struct TransferOwnership {
 
 #[derive(Args)]
 struct Igp {
-    #[arg(long, short, default_value_t = HYPERLANE_TOKEN_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the token program of the configured warp route
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     #[command(subcommand)]
     cmd: GetSetCmd<GetIgpArgs, SetIgpArgs>,
 }
 
+/// Manages the depositor allowlist of a collateral token
+#[derive(Args)]
+struct DepositorAllowlist {
+    #[arg(long, short)]
+    program_id: Pubkey,
+    #[command(subcommand)]
+    cmd: DepositorAllowlistSubCmd,
+}
+
+#[derive(Subcommand)]
+enum DepositorAllowlistSubCmd {
+    /// Prints the depositor allowlist
+    Query,
+    /// Restricts deposits to the allowed depositors
+    Enable,
+    /// Lifts the restriction of deposits to the allowed depositors
+    Disable,
+    /// Adds depositors to the allowlist
+    Add { depositors: Vec<Pubkey> },
+    /// Removes depositors from the allowlist
+    Remove { depositors: Vec<Pubkey> },
+}
+
 #[derive(Subcommand)]
 enum GetSetCmd<G: Args, S: Args> {
     Get(G),
@@ -577,18 +639,21 @@ enum ValidatorAnnounceSubCmd {
 
 #[derive(Args)]
 struct ValidatorAnnounceInit {
-    #[arg(long, short, default_value_t = VALIDATOR_ANNOUNCE_PROG_ID)]
-    program_id: Pubkey,
-    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
-    mailbox_id: Pubkey,
+    /// Defaults to the validator announce program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
+    /// Defaults to the mailbox program of the configured environment
+    #[arg(long, short)]
+    mailbox_id: Option<Pubkey>,
     #[arg(long, short, default_value_t = ECLIPSE_DOMAIN)]
     local_domain: u32,
 }
 
 #[derive(Args)]
 struct ValidatorAnnounceAnnounce {
-    #[arg(long, short, default_value_t = VALIDATOR_ANNOUNCE_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the validator announce program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     #[arg(long)]
     validator: H160,
     #[arg(long)]
@@ -599,8 +664,9 @@ struct ValidatorAnnounceAnnounce {
 
 #[derive(Args)]
 struct ValidatorAnnounceQuery {
-    #[arg(long, short, default_value_t = VALIDATOR_ANNOUNCE_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the validator announce program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     validator: H160,
 }
 
@@ -644,8 +710,9 @@ struct MultisigIsmMessageIdConfigure {
 
 #[derive(Args)]
 struct MultisigIsmMessageIdInit {
-    #[arg(long, short, default_value_t = MULTISIG_ISM_MESSAGE_ID_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the multisig ISM program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
 }
 
 #[derive(Args)]
@@ -658,8 +725,9 @@ struct MultisigIsmMessageIdQuery {
 
 #[derive(Args)]
 struct MultisigIsmMessageIdSetValidatorsAndThreshold {
-    #[arg(long, short, default_value_t = MULTISIG_ISM_MESSAGE_ID_PROG_ID)]
-    program_id: Pubkey,
+    /// Defaults to the multisig ISM program of the configured environment
+    #[arg(long, short)]
+    program_id: Option<Pubkey>,
     #[arg(long)]
     domain: u32,
     #[arg(long, value_delimiter = ',')]
@@ -782,12 +850,15 @@ fn main() {
 fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
     match cmd.cmd {
         MailboxSubCmd::Init(init) => {
+            let program_id = program_id_or_default(init.program_id, mailbox_program_id);
+            let default_ism =
+                program_id_or_default(init.default_ism, multisig_ism_message_id_program_id);
             let protocol_fee_beneficiary =
                 init.protocol_fee_beneficiary.unwrap_or(ctx.payer_pubkey);
             let instruction = hyperlane_sealevel_mailbox::instruction::init_instruction(
-                init.program_id,
+                program_id,
                 init.local_domain,
-                init.default_ism,
+                default_ism,
                 init.max_protocol_fee,
                 ProtocolFee {
                     fee: init.protocol_fee,
@@ -800,10 +871,11 @@ fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
             ctx.new_txn().add(instruction).send_with_payer();
         }
         MailboxSubCmd::Query(query) => {
+            let program_id = program_id_or_default(query.program_id, mailbox_program_id);
             let (inbox_account, inbox_bump) =
-                Pubkey::find_program_address(mailbox_inbox_pda_seeds!(), &query.program_id);
+                Pubkey::find_program_address(mailbox_inbox_pda_seeds!(), &program_id);
             let (outbox_account, outbox_bump) =
-                Pubkey::find_program_address(mailbox_outbox_pda_seeds!(), &query.program_id);
+                Pubkey::find_program_address(mailbox_outbox_pda_seeds!(), &program_id);
 
             let accounts = ctx
                 .client
@@ -813,7 +885,7 @@ fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
                 )
                 .unwrap()
                 .value;
-            println!("mailbox={}", query.program_id);
+            println!("mailbox={}", program_id);
             println!("--------------------------------");
             println!("Inbox: {}, bump={}", inbox_account, inbox_bump);
             if let Some(info) = &accounts[0] {
@@ -838,31 +910,29 @@ fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
             }
         }
         MailboxSubCmd::Send(outbox) => {
-            let (outbox_account, _outbox_bump) =
-                Pubkey::find_program_address(mailbox_outbox_pda_seeds!(), &outbox.program_id);
-            let ixn = MailboxInstruction::OutboxDispatch(OutboxDispatch {
-                sender: ctx.payer_pubkey,
-                destination_domain: outbox.destination,
-                recipient: H256(outbox.recipient.to_bytes()),
-                message_body: outbox.message.into(),
-            });
-            let outbox_instruction = Instruction {
-                program_id: outbox.program_id,
-                data: ixn.into_instruction_data().unwrap(),
-                accounts: vec![
-                    AccountMeta::new(outbox_account, false),
-                    AccountMeta::new_readonly(ctx.payer_pubkey, true),
-                    AccountMeta::new_readonly(spl_noop::id(), false),
-                ],
-            };
-            ctx.new_txn().add(outbox_instruction).send_with_payer();
+            let program_id = program_id_or_default(outbox.program_id, mailbox_program_id);
+            let unique_message_account_keypair = Keypair::new();
+            let outbox_instruction = client_mailbox::dispatch_instruction(
+                program_id,
+                ctx.payer_pubkey,
+                ctx.payer_pubkey,
+                unique_message_account_keypair.pubkey(),
+                OutboxDispatch {
+                    sender: ctx.payer_pubkey,
+                    destination_domain: outbox.destination,
+                    recipient: H256(outbox.recipient.to_bytes()),
+                    message_body: outbox.message.into(),
+                },
+            )
+            .unwrap();
+            ctx.new_txn()
+                .add(outbox_instruction)
+                .send(&[&*ctx.payer_signer(), &unique_message_account_keypair]);
         }
         MailboxSubCmd::Delivered(delivered) => {
+            let program_id = program_id_or_default(delivered.program_id, mailbox_program_id);
             let (processed_message_account_key, _processed_message_account_bump) =
-                Pubkey::find_program_address(
-                    mailbox_processed_message_pda_seeds!(delivered.message_id),
-                    &delivered.program_id,
-                );
+                client_mailbox::processed_message_pda(&program_id, delivered.message_id).unwrap();
             let account = ctx
                 .client
                 .get_account_with_commitment(&processed_message_account_key, ctx.commitment)
@@ -903,14 +973,38 @@ fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
                 )
                 .send_with_payer();
         }
+        MailboxSubCmd::SetDefaultIsmOverride(set_override) => {
+            let instruction =
+                hyperlane_sealevel_mailbox::instruction::set_default_ism_override_instruction(
+                    set_override.program_id,
+                    ctx.payer_pubkey,
+                    set_override.origin,
+                    set_override.ism,
+                )
+                .unwrap();
+            let description = match set_override.ism {
+                Some(ism) => format!(
+                    "Setting default ISM override of origin {} to {}",
+                    set_override.origin, ism
+                ),
+                None => format!(
+                    "Removing default ISM override of origin {}",
+                    set_override.origin
+                ),
+            };
+            ctx.new_txn()
+                .add_with_description(instruction, description)
+                .send_with_payer();
+        }
     };
 }
 
 fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
     match cmd.cmd {
         TokenSubCmd::Query(query) => {
+            let program_id = program_id_or_default(query.program_id, hyperlane_token_program_id);
             let (token_account, token_bump) =
-                Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &query.program_id);
+                Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &program_id);
 
             let mut accounts_to_query = vec![token_account];
 
@@ -919,18 +1013,18 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                     let (native_collateral_account, _native_collateral_bump) =
                         Pubkey::find_program_address(
                             hyperlane_token_native_collateral_pda_seeds!(),
-                            &query.program_id,
+                            &program_id,
                         );
                     accounts_to_query.push(native_collateral_account);
                 }
                 TokenType::Synthetic => {
                     let (mint_account, _mint_bump) = Pubkey::find_program_address(
                         hyperlane_token_mint_pda_seeds!(),
-                        &query.program_id,
+                        &program_id,
                     );
                     let (ata_payer_account, _ata_payer_bump) = Pubkey::find_program_address(
                         hyperlane_token_ata_payer_pda_seeds!(),
-                        &query.program_id,
+                        &program_id,
                     );
                     accounts_to_query.push(mint_account);
                     accounts_to_query.push(ata_payer_account);
@@ -938,7 +1032,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                 TokenType::Collateral => {
                     let (escrow_account, _escrow_bump) = Pubkey::find_program_address(
                         hyperlane_token_escrow_pda_seeds!(),
-                        &query.program_id,
+                        &program_id,
                     );
                     accounts_to_query.push(escrow_account);
                 }
@@ -949,7 +1043,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                 .get_multiple_accounts_with_commitment(&accounts_to_query, ctx.commitment)
                 .unwrap()
                 .value;
-            println!("hyperlane-sealevel-token={}", query.program_id);
+            println!("hyperlane-sealevel-token={}", program_id);
             println!("--------------------------------");
             println!(
                 "Hyperlane Token Storage: {}, bump={}",
@@ -968,7 +1062,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                     let (native_collateral_account, native_collateral_bump) =
                         Pubkey::find_program_address(
                             hyperlane_token_native_collateral_pda_seeds!(),
-                            &query.program_id,
+                            &program_id,
                         );
                     println!(
                         "Native Token Collateral: {}, bump={}",
@@ -984,7 +1078,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                 TokenType::Synthetic => {
                     let (mint_account, mint_bump) = Pubkey::find_program_address(
                         hyperlane_token_mint_pda_seeds!(),
-                        &query.program_id,
+                        &program_id,
                     );
                     println!(
                         "Mint / Mint Authority: {}, bump={}",
@@ -1003,7 +1097,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
 
                     let (ata_payer_account, ata_payer_bump) = Pubkey::find_program_address(
                         hyperlane_token_ata_payer_pda_seeds!(),
-                        &query.program_id,
+                        &program_id,
                     );
                     println!(
                         "ATA payer account: {}, bump={}",
@@ -1013,7 +1107,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                 TokenType::Collateral => {
                     let (escrow_account, escrow_bump) = Pubkey::find_program_address(
                         hyperlane_token_escrow_pda_seeds!(),
-                        &query.program_id,
+                        &program_id,
                     );
 
                     println!(
@@ -1023,7 +1117,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
 
                     let (ata_payer_account, ata_payer_bump) = Pubkey::find_program_address(
                         hyperlane_token_ata_payer_pda_seeds!(),
-                        &query.program_id,
+                        &program_id,
                     );
 
                     println!(
@@ -1034,6 +1128,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
             }
         }
         TokenSubCmd::TransferRemote(xfer) => {
+            let program_id = program_id_or_default(xfer.program_id, hyperlane_token_program_id);
             is_keypair(&xfer.sender).unwrap();
             ctx.commitment = CommitmentConfig::finalized();
             let sender = read_keypair_file(xfer.sender).unwrap();
@@ -1046,11 +1141,11 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
             };
 
             let (token_account, _token_bump) =
-                Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &xfer.program_id);
+                Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &program_id);
             let (dispatch_authority_account, _dispatch_authority_bump) =
                 Pubkey::find_program_address(
                     mailbox_message_dispatch_authority_pda_seeds!(),
-                    &xfer.program_id,
+                    &program_id,
                 );
 
             let fetched_token_account = ctx
@@ -1156,7 +1251,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                     let (native_collateral_account, _native_collateral_bump) =
                         Pubkey::find_program_address(
                             hyperlane_token_native_collateral_pda_seeds!(),
-                            &xfer.program_id,
+                            &program_id,
                         );
                     accounts.extend([
                         AccountMeta::new_readonly(system_program::id(), false),
@@ -1169,7 +1264,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                     // 7. [writeable] The token sender's associated token account, from which tokens will be burned.
                     let (mint_account, _mint_bump) = Pubkey::find_program_address(
                         hyperlane_token_mint_pda_seeds!(),
-                        &xfer.program_id,
+                        &program_id,
                     );
                     let sender_associated_token_account =
                         get_associated_token_address_with_program_id(
@@ -1188,6 +1283,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                     // 6. [writeable] The mint.
                     // 7. [writeable] The token sender's associated token account, from which tokens will be sent.
                     // 8. [writeable] The escrow PDA account.
+                    // 9. [] The depositor allowlist PDA account, if the program has one.
                     let token = HyperlaneTokenAccount::<CollateralPlugin>::fetch(
                         &mut &fetched_token_account.data[..],
                     )
@@ -1199,18 +1295,37 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                             &token.plugin_data.mint,
                             &token.plugin_data.spl_token_program,
                         );
+                    let (depositor_allowlist_account, _depositor_allowlist_bump) =
+                        Pubkey::find_program_address(
+                            hyperlane_token_depositor_allowlist_pda_seeds!(),
+                            &program_id,
+                        );
                     accounts.extend([
                         AccountMeta::new_readonly(token.plugin_data.spl_token_program, false),
                         AccountMeta::new(token.plugin_data.mint, false),
                         AccountMeta::new(sender_associated_token_account, false),
                         AccountMeta::new(token.plugin_data.escrow, false),
                     ]);
+                    // Only programs built with the `depositor-allowlist`
+                    // feature can create the allowlist, and they take it
+                    let has_depositor_allowlist = ctx
+                        .client
+                        .get_account_with_commitment(&depositor_allowlist_account, ctx.commitment)
+                        .unwrap()
+                        .value
+                        .is_some();
+                    if has_depositor_allowlist {
+                        accounts.push(AccountMeta::new_readonly(
+                            depositor_allowlist_account,
+                            false,
+                        ));
+                    }
                 }
             }
 
             eprintln!("accounts={:#?}", accounts); // FIXME remove
             let xfer_instruction = Instruction {
-                program_id: xfer.program_id,
+                program_id,
                 data: ixn.encode().unwrap(),
                 accounts,
             };
@@ -1223,15 +1338,16 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
             println!("{:?}", tx_result);
         }
         TokenSubCmd::EnrollRemoteRouter(enroll) => {
+            let program_id = program_id_or_default(enroll.program_id, hyperlane_token_program_id);
             let enroll_instruction = HtInstruction::EnrollRemoteRouter(RemoteRouterConfig {
                 domain: enroll.domain,
                 router: enroll.router.into(),
             });
             let (token_account, _token_bump) =
-                Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &enroll.program_id);
+                Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &program_id);
 
             let instruction = Instruction {
-                program_id: enroll.program_id,
+                program_id,
                 data: enroll_instruction.encode().unwrap(),
                 accounts: vec![
                     AccountMeta::new(token_account, false),
@@ -1269,63 +1385,139 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                 .add_with_description(instruction, format!("Set ISM to {:?}", set_ism.ism))
                 .send_with_payer();
         }
-        TokenSubCmd::Igp(args) => match args.cmd {
-            GetSetCmd::Set(set_args) => {
-                let igp_type: InterchainGasPaymasterType = match set_args.igp_type {
-                    IgpType::Igp => InterchainGasPaymasterType::Igp(set_args.igp_account),
-                    IgpType::OverheadIgp => {
-                        InterchainGasPaymasterType::OverheadIgp(set_args.igp_account)
+        TokenSubCmd::DepositorAllowlist(args) => {
+            use hyperlane_sealevel_token_collateral::instruction::{
+                add_depositors_instruction, remove_depositors_instruction,
+                set_depositor_allowlist_enabled_instruction,
+            };
+
+            let (instruction, description) = match args.cmd {
+                DepositorAllowlistSubCmd::Query => {
+                    let (depositor_allowlist_account, _depositor_allowlist_bump) =
+                        Pubkey::find_program_address(
+                            hyperlane_token_depositor_allowlist_pda_seeds!(),
+                            &args.program_id,
+                        );
+                    let account = ctx
+                        .client
+                        .get_account_with_commitment(&depositor_allowlist_account, ctx.commitment)
+                        .unwrap()
+                        .value;
+                    match account {
+                        Some(account) => {
+                            let depositor_allowlist =
+                                DepositorAllowlistAccount::fetch(&mut &account.data[..])
+                                    .unwrap()
+                                    .into_inner();
+                            println!("Depositor allowlist: {:#?}", depositor_allowlist);
+                        }
+                        None => println!("No depositor allowlist, deposits are unrestricted"),
                     }
-                };
-                let instruction = hyperlane_sealevel_token_lib::instruction::set_igp_instruction(
-                    args.program_id,
-                    ctx.payer_pubkey,
-                    Some((set_args.igp_program, igp_type.clone())),
-                )
-                .unwrap();
+                    return;
+                }
+                DepositorAllowlistSubCmd::Enable => (
+                    set_depositor_allowlist_enabled_instruction(
+                        args.program_id,
+                        ctx.payer_pubkey,
+                        true,
+                    ),
+                    "Restrict deposits to the depositor allowlist".to_owned(),
+                ),
+                DepositorAllowlistSubCmd::Disable => (
+                    set_depositor_allowlist_enabled_instruction(
+                        args.program_id,
+                        ctx.payer_pubkey,
+                        false,
+                    ),
+                    "Lift the restriction of deposits to the depositor allowlist".to_owned(),
+                ),
+                DepositorAllowlistSubCmd::Add { depositors } => (
+                    add_depositors_instruction(
+                        args.program_id,
+                        ctx.payer_pubkey,
+                        depositors.clone(),
+                    ),
+                    format!("Add depositors {:?} to the allowlist", depositors),
+                ),
+                DepositorAllowlistSubCmd::Remove { depositors } => (
+                    remove_depositors_instruction(
+                        args.program_id,
+                        ctx.payer_pubkey,
+                        depositors.clone(),
+                    ),
+                    format!("Remove depositors {:?} from the allowlist", depositors),
+                ),
+            };
 
-                ctx.new_txn()
-                    .add_with_description(
-                        instruction,
-                        format!(
-                            "Set IGP of {} to program {}, type {:?}",
-                            args.program_id, set_args.igp_program, igp_type
-                        ),
-                    )
-                    .send_with_payer();
-            }
-            GetSetCmd::Get(get_args) => {
-                let (token_account, _token_bump) =
-                    Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &args.program_id);
-                let token_account = ctx
-                    .client
-                    .get_account_with_commitment(&token_account, ctx.commitment)
-                    .unwrap()
-                    .value
-                    .expect(
-                        "Token account not found. Make sure you are connected to the right RPC.",
-                    );
+            ctx.new_txn()
+                .add_with_description(instruction.unwrap(), description)
+                .send_with_payer();
+        }
+        TokenSubCmd::Igp(args) => {
+            let program_id = program_id_or_default(args.program_id, hyperlane_token_program_id);
+            match args.cmd {
+                GetSetCmd::Set(set_args) => {
+                    let igp_type: InterchainGasPaymasterType = match set_args.igp_type {
+                        IgpType::Igp => InterchainGasPaymasterType::Igp(set_args.igp_account),
+                        IgpType::OverheadIgp => {
+                            InterchainGasPaymasterType::OverheadIgp(set_args.igp_account)
+                        }
+                    };
+                    let instruction =
+                        hyperlane_sealevel_token_lib::instruction::set_igp_instruction(
+                            program_id,
+                            ctx.payer_pubkey,
+                            Some((set_args.igp_program, igp_type.clone())),
+                        )
+                        .unwrap();
+
+                    ctx.new_txn()
+                        .add_with_description(
+                            instruction,
+                            format!(
+                                "Set IGP of {} to program {}, type {:?}",
+                                program_id, set_args.igp_program, igp_type
+                            ),
+                        )
+                        .send_with_payer();
+                }
+                GetSetCmd::Get(get_args) => {
+                    let (token_account, _token_bump) =
+                        Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &program_id);
+                    let token_account = ctx
+                        .client
+                        .get_account_with_commitment(&token_account, ctx.commitment)
+                        .unwrap()
+                        .value
+                        .expect(
+                            "Token account not found. Make sure you are connected to the right RPC.",
+                        );
 
-                parse_token_account_data(get_args.token_type, &mut &token_account.data[..]);
+                    parse_token_account_data(get_args.token_type, &mut &token_account.data[..]);
+                }
             }
-        },
+        }
     }
 }
 
 fn process_validator_announce_cmd(ctx: Context, cmd: ValidatorAnnounceCmd) {
     match cmd.cmd {
         ValidatorAnnounceSubCmd::Init(init) => {
+            let program_id = program_id_or_default(init.program_id, validator_announce_program_id);
+            let mailbox_id = program_id_or_default(init.mailbox_id, mailbox_program_id);
             let init_instruction =
                 hyperlane_sealevel_validator_announce::instruction::init_instruction(
-                    init.program_id,
+                    program_id,
                     ctx.payer_pubkey,
-                    init.mailbox_id,
+                    mailbox_id,
                     init.local_domain,
                 )
                 .unwrap();
             ctx.new_txn().add(init_instruction).send_with_payer();
         }
         ValidatorAnnounceSubCmd::Announce(announce) => {
+            let program_id =
+                program_id_or_default(announce.program_id, validator_announce_program_id);
             let signature = hex::decode(if announce.signature.starts_with("0x") {
                 &announce.signature[2..]
             } else {
@@ -1340,20 +1532,17 @@ fn process_validator_announce_cmd(ctx: Context, cmd: ValidatorAnnounceCmd) {
             };
 
             let (validator_announce_account, _validator_announce_bump) =
-                Pubkey::find_program_address(validator_announce_pda_seeds!(), &announce.program_id);
+                Pubkey::find_program_address(validator_announce_pda_seeds!(), &program_id);
 
             let (validator_storage_locations_key, _validator_storage_locations_bump_seed) =
                 Pubkey::find_program_address(
                     validator_storage_locations_pda_seeds!(announce.validator),
-                    &announce.program_id,
+                    &program_id,
                 );
 
             let replay_id = announce_instruction.replay_id();
             let (replay_protection_pda_key, _replay_protection_bump_seed) =
-                Pubkey::find_program_address(
-                    replay_protection_pda_seeds!(replay_id),
-                    &announce.program_id,
-                );
+                Pubkey::find_program_address(replay_protection_pda_seeds!(replay_id), &program_id);
 
             let ixn = ValidatorAnnounceInstruction::Announce(announce_instruction);
 
@@ -1372,17 +1561,18 @@ fn process_validator_announce_cmd(ctx: Context, cmd: ValidatorAnnounceCmd) {
             ];
 
             let announce_instruction = Instruction {
-                program_id: announce.program_id,
+                program_id,
                 data: ixn.into_instruction_data().unwrap(),
                 accounts,
             };
             ctx.new_txn().add(announce_instruction).send_with_payer();
         }
         ValidatorAnnounceSubCmd::Query(query) => {
+            let program_id = program_id_or_default(query.program_id, validator_announce_program_id);
             let (validator_storage_locations_key, _validator_storage_locations_bump_seed) =
                 Pubkey::find_program_address(
                     validator_storage_locations_pda_seeds!(query.validator),
-                    &query.program_id,
+                    &program_id,
                 );
 
             let account = ctx
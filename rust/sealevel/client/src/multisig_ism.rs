@@ -2,11 +2,14 @@ use std::collections::{HashMap, HashSet};
 use std::{fs::File, path::Path};
 
 use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
 use solana_program::pubkey::Pubkey;
+use solana_sdk::commitment_config::CommitmentConfig;
 
 use crate::{
     artifacts::{write_json, SingularProgramIdArtifact},
     cmd_utils::{create_new_directory, deploy_program},
+    program_ids::{multisig_ism_message_id_program_id, program_id_or_default},
     router::ChainMetadata,
     Context, MultisigIsmMessageIdCmd, MultisigIsmMessageIdSubCmd,
 };
@@ -73,18 +76,22 @@ pub(crate) fn process_multisig_ism_message_id_cmd(mut ctx: Context, cmd: Multisi
             );
         }
         MultisigIsmMessageIdSubCmd::Init(init) => {
+            let program_id =
+                program_id_or_default(init.program_id, multisig_ism_message_id_program_id);
             let init_instruction =
                 hyperlane_sealevel_multisig_ism_message_id::instruction::init_instruction(
-                    init.program_id,
+                    program_id,
                     ctx.payer_pubkey,
                 )
                 .unwrap();
             ctx.new_txn().add(init_instruction).send_with_payer();
         }
         MultisigIsmMessageIdSubCmd::SetValidatorsAndThreshold(set_config) => {
+            let program_id =
+                program_id_or_default(set_config.program_id, multisig_ism_message_id_program_id);
             set_validators_and_threshold(
                 &mut ctx,
-                set_config.program_id,
+                program_id,
                 set_config.domain,
                 ValidatorsAndThreshold {
                     validators: set_config.validators,
@@ -237,7 +244,8 @@ fn configure_multisig_ism_message_id(
         let chain_config = chain_configs.get(&chain_name).unwrap();
 
         let matches = multisig_ism_config_matches_chain(
-            ctx,
+            &ctx.client,
+            ctx.commitment,
             program_id,
             chain_config.domain_id(),
             &multisig_ism_config,
@@ -263,8 +271,9 @@ fn configure_multisig_ism_message_id(
     }
 }
 
-fn multisig_ism_config_matches_chain(
-    ctx: &mut Context,
+pub(crate) fn multisig_ism_config_matches_chain(
+    client: &RpcClient,
+    commitment: CommitmentConfig,
     program_id: Pubkey,
     remote_domain: u32,
     expected: &MultisigIsmConfig,
@@ -272,9 +281,8 @@ fn multisig_ism_config_matches_chain(
     let (domain_data_key, _domain_data_bump) =
         Pubkey::find_program_address(domain_data_pda_seeds!(remote_domain), &program_id);
 
-    let domain_data_account = ctx
-        .client
-        .get_account_with_commitment(&domain_data_key, ctx.commitment)
+    let domain_data_account = client
+        .get_account_with_commitment(&domain_data_key, commitment)
         .expect("Failed to get domain data account")
         .value;
 
@@ -0,0 +1,123 @@
+//! Default program ids of the commands that operate on deployed programs.
+//!
+//! Without configuration these are the program ids of the local test
+//! deployment. Setting `HYPERLANE_SEALEVEL_ENVIRONMENTS_DIR`,
+//! `HYPERLANE_SEALEVEL_ENVIRONMENT` and `HYPERLANE_SEALEVEL_CHAIN` instead
+//! defaults them to the core programs deployed to that chain, as recorded in
+//! the environment's artifacts. Additionally setting
+//! `HYPERLANE_SEALEVEL_WARP_ROUTE` defaults the token program id to that warp
+//! route's program on the chain.
+//!
+//! The defaults are only resolved by the commands that use them, so a
+//! misconfigured environment doesn't break the other commands.
+
+use std::{env, path::PathBuf, process, sync::OnceLock};
+
+use solana_program::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    artifacts::try_read_json, core_program_ids_path, router::read_router_program_ids,
+    CoreProgramIds,
+};
+
+const LOCAL_MAILBOX_PROG_ID: Pubkey = pubkey!("692KZJaoe2KRcD6uhCQDLLXnLNA5ZLnfvdqjE4aX9iu1");
+const LOCAL_HYPERLANE_TOKEN_PROG_ID: Pubkey =
+    pubkey!("3MzUPjP5LEkiHH82nEAe28Xtz9ztuMqWc8UmuKxrpVQH");
+const LOCAL_MULTISIG_ISM_MESSAGE_ID_PROG_ID: Pubkey =
+    pubkey!("2YjtZDiUoptoSsA5eVrDCcX6wxNK6YoEVW7y82x5Z2fw");
+const LOCAL_VALIDATOR_ANNOUNCE_PROG_ID: Pubkey =
+    pubkey!("DH43ae1LwemXAboWwSh8zc9pG8j72gKUEXNi57w8fEnn");
+
+const ENVIRONMENTS_DIR_VAR: &str = "HYPERLANE_SEALEVEL_ENVIRONMENTS_DIR";
+const ENVIRONMENT_VAR: &str = "HYPERLANE_SEALEVEL_ENVIRONMENT";
+const CHAIN_VAR: &str = "HYPERLANE_SEALEVEL_CHAIN";
+const WARP_ROUTE_VAR: &str = "HYPERLANE_SEALEVEL_WARP_ROUTE";
+
+/// The program ids deployed to the chain selected through the environment
+struct DeployedProgramIds {
+    core: CoreProgramIds,
+    token: Option<Pubkey>,
+}
+
+fn deployed_program_ids() -> Result<Option<&'static DeployedProgramIds>, String> {
+    static DEPLOYED: OnceLock<Result<Option<DeployedProgramIds>, String>> = OnceLock::new();
+    match DEPLOYED.get_or_init(read_deployed_program_ids) {
+        Ok(ids) => Ok(ids.as_ref()),
+        Err(err) => Err(err.clone()),
+    }
+}
+
+fn read_deployed_program_ids() -> Result<Option<DeployedProgramIds>, String> {
+    let vars = [ENVIRONMENTS_DIR_VAR, ENVIRONMENT_VAR, CHAIN_VAR].map(|var| env::var(var).ok());
+    let [Some(environments_dir), Some(environment), Some(chain)] = vars else {
+        if vars.iter().any(Option::is_some) {
+            return Err(format!(
+                "{ENVIRONMENTS_DIR_VAR}, {ENVIRONMENT_VAR} and {CHAIN_VAR} must be set together"
+            ));
+        }
+        return Ok(None);
+    };
+    let environments_dir = PathBuf::from(environments_dir);
+
+    let core_path = core_program_ids_path(&environments_dir, &environment, &chain);
+    let core = try_read_json(&core_path).map_err(|err| {
+        format!(
+            "Failed to read core program ids from {}: {err}",
+            core_path.display()
+        )
+    })?;
+    let token = match env::var(WARP_ROUTE_VAR) {
+        Ok(warp_route) => {
+            let deploy_dir = environments_dir
+                .join(&environment)
+                .join("warp-routes")
+                .join(&warp_route);
+            let token = read_router_program_ids(&deploy_dir)
+                .and_then(|program_ids| program_ids.get(&chain).copied())
+                .ok_or_else(|| format!("Warp route {warp_route} is not deployed to {chain}"))?;
+            Some(token)
+        }
+        Err(_) => None,
+    };
+
+    Ok(Some(DeployedProgramIds { core, token }))
+}
+
+/// `program_id` if it was passed, or else the default returned by `default`.
+/// Exits if the default can't be resolved.
+pub(crate) fn program_id_or_default(
+    program_id: Option<Pubkey>,
+    default: fn() -> Result<Pubkey, String>,
+) -> Pubkey {
+    program_id.map_or_else(default, Ok).unwrap_or_else(|err| {
+        eprintln!("Failed to resolve the default program id: {err}");
+        process::exit(1)
+    })
+}
+
+pub(crate) fn mailbox_program_id() -> Result<Pubkey, String> {
+    Ok(deployed_program_ids()?.map_or(LOCAL_MAILBOX_PROG_ID, |ids| ids.core.mailbox))
+}
+
+pub(crate) fn validator_announce_program_id() -> Result<Pubkey, String> {
+    Ok(
+        deployed_program_ids()?.map_or(LOCAL_VALIDATOR_ANNOUNCE_PROG_ID, |ids| {
+            ids.core.validator_announce
+        }),
+    )
+}
+
+pub(crate) fn multisig_ism_message_id_program_id() -> Result<Pubkey, String> {
+    Ok(
+        deployed_program_ids()?.map_or(LOCAL_MULTISIG_ISM_MESSAGE_ID_PROG_ID, |ids| {
+            ids.core.multisig_ism_message_id
+        }),
+    )
+}
+
+pub(crate) fn hyperlane_token_program_id() -> Result<Pubkey, String> {
+    Ok(deployed_program_ids()?
+        .and_then(|ids| ids.token)
+        .unwrap_or(LOCAL_HYPERLANE_TOKEN_PROG_ID))
+}
@@ -651,7 +651,7 @@ fn write_router_program_ids(deploy_dir: &Path, routers: &HashMap<String, H256>)
     write_json(&program_ids_file, serialized_program_ids);
 }
 
-fn read_router_program_ids(deploy_dir: &Path) -> Option<HashMap<String, Pubkey>> {
+pub(crate) fn read_router_program_ids(deploy_dir: &Path) -> Option<HashMap<String, Pubkey>> {
     let program_ids_file = deploy_dir.join("program-ids.json");
 
     if !program_ids_file.exists() {
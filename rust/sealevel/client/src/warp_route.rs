@@ -1,11 +1,15 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use hyperlane_core::H256;
+use hyperlane_core::{utils::hex_or_base58_to_h256, H256, U256};
+use hyperlane_sealevel_mailbox::{accounts::InboxAccount, mailbox_inbox_pda_seeds};
 use hyperlane_sealevel_token_collateral::plugin::CollateralPlugin;
-use hyperlane_sealevel_token_native::plugin::NativePlugin;
+use hyperlane_sealevel_token_native::{
+    hyperlane_token_native_collateral_pda_seeds, plugin::NativePlugin,
+};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Debug,
+    path::Path,
     process::{Command, Stdio},
 };
 
@@ -24,7 +28,7 @@ use hyperlane_sealevel_token::{
     hyperlane_token_mint_pda_seeds, plugin::SyntheticPlugin, spl_token_2022,
 };
 use hyperlane_sealevel_token_lib::{
-    accounts::{HyperlaneToken, HyperlaneTokenAccount},
+    accounts::{convert_decimals, HyperlaneToken, HyperlaneTokenAccount},
     hyperlane_token_pda_seeds,
     instruction::{
         enroll_remote_routers_instruction, set_destination_gas_configs, set_igp_instruction,
@@ -33,13 +37,15 @@ use hyperlane_sealevel_token_lib::{
 };
 
 use crate::{
+    artifacts::{read_json, try_read_json, write_json, SingularProgramIdArtifact},
     cmd_utils::account_exists,
     core::CoreProgramIds,
+    multisig_ism::{multisig_ism_config_matches_chain, MultisigIsmConfig},
     router::{
-        deploy_routers, ChainMetadata, ConnectionClient, Ownable, RouterConfig, RouterConfigGetter,
-        RouterDeployer,
+        deploy_routers, read_router_program_ids, ChainMetadata, ConnectionClient, Ownable,
+        RouterConfig, RouterConfigGetter, RouterDeployer,
     },
-    Context, TokenType as FlatTokenType, WarpRouteCmd, WarpRouteSubCmd,
+    Context, TokenType as FlatTokenType, WarpRouteAudit, WarpRouteCmd, WarpRouteSubCmd,
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -167,6 +173,17 @@ pub(crate) fn process_warp_route_cmd(mut ctx: Context, cmd: WarpRouteCmd) {
                 destination_gas[&args.destination_domain]
             );
         }
+        WarpRouteSubCmd::Audit(audit) => {
+            let output_file = audit.output_file.clone();
+            let report = audit_warp_route(audit);
+            match output_file {
+                Some(output_file) => write_json(&output_file, &report),
+                None => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+            }
+            if !report.healthy {
+                std::process::exit(1);
+            }
+        }
     }
 }
 
@@ -666,6 +683,463 @@ fn get_destination_gas(
     Ok(token_data.destination_gas)
 }
 
+/// A problem found by the warp route audit.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "check", rename_all = "camelCase")]
+enum AuditFinding {
+    /// The router of a Sealevel chain isn't deployed or initialized.
+    #[serde(rename_all = "camelCase")]
+    RouterNotDeployed { chain: String },
+    /// The router enrolled for a remote chain isn't the remote chain's router.
+    #[serde(rename_all = "camelCase")]
+    RemoteRouterMismatch {
+        chain: String,
+        remote_chain: String,
+        expected: H256,
+        enrolled: Option<H256>,
+    },
+    /// A router is enrolled for a domain that isn't part of the warp route.
+    #[serde(rename_all = "camelCase")]
+    UnexpectedRemoteRouter {
+        chain: String,
+        domain: u32,
+        enrolled: H256,
+    },
+    /// The chains don't agree on the decimals of the amounts sent between them.
+    #[serde(rename_all = "camelCase")]
+    RemoteDecimalsInconsistent {
+        remote_decimals: BTreeMap<String, u8>,
+    },
+    /// The decimals of a router differ from its config.
+    #[serde(rename_all = "camelCase")]
+    DecimalsMismatch {
+        chain: String,
+        configured_decimals: u8,
+        decimals: u8,
+        configured_remote_decimals: u8,
+        remote_decimals: u8,
+    },
+    /// The decimals of the mint of a router differ from the router's decimals.
+    #[serde(rename_all = "camelCase")]
+    MintDecimalsMismatch {
+        chain: String,
+        mint: String,
+        mint_decimals: u8,
+        decimals: u8,
+    },
+    /// Less collateral is locked than synthetic tokens were minted.
+    #[serde(rename_all = "camelCase")]
+    CollateralShortfall {
+        locked_collateral: String,
+        synthetic_supply: String,
+    },
+    /// The ISM of a router differs from its config.
+    #[serde(rename_all = "camelCase")]
+    IsmMismatch {
+        chain: String,
+        configured: Option<String>,
+        ism: Option<String>,
+    },
+    /// The ISM used by a router isn't deployed.
+    #[serde(rename_all = "camelCase")]
+    IsmNotDeployed { chain: String, ism: String },
+    /// The validators and threshold of the ISM for messages from a remote
+    /// chain differ from the multisig config of the environment.
+    #[serde(rename_all = "camelCase")]
+    IsmValidatorsMismatch {
+        chain: String,
+        ism_context: String,
+        remote_chain: String,
+    },
+}
+
+/// The machine-readable result of a warp route audit.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WarpRouteAuditReport {
+    warp_route_name: String,
+    healthy: bool,
+    chains: BTreeMap<String, ChainAudit>,
+    /// Collateral locked on the Sealevel chains, in the route's remote decimals
+    locked_collateral: String,
+    /// Synthetic tokens minted on the Sealevel chains, in the route's remote decimals
+    synthetic_supply: String,
+    findings: Vec<AuditFinding>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainAudit {
+    router: Option<H256>,
+    /// Foreign deployments can't be queried, so only the routers enrolled
+    /// for them are checked
+    foreign_deployment: bool,
+    /// The ISM used by the router, which is the mailbox's default ISM if the
+    /// router doesn't set one
+    ism: Option<String>,
+    /// The multisig ISM context of the environment the ISM belongs to, if any
+    ism_context: Option<String>,
+    /// Collateral locked or synthetic tokens minted by the router, in the
+    /// route's remote decimals
+    balance: Option<String>,
+}
+
+enum RouterBalance {
+    Collateral(U256),
+    Synthetic(U256),
+}
+
+/// Checks that the routers of a warp route are enrolled with each other, agree
+/// on decimals, are backed by enough collateral and use the expected ISMs.
+fn audit_warp_route(audit: WarpRouteAudit) -> WarpRouteAuditReport {
+    let app_configs: HashMap<String, TokenConfig> = read_json(&audit.token_config_file);
+    let chain_configs: HashMap<String, ChainMetadata> = read_json(&audit.chain_config_file);
+    let environment_dir = audit
+        .env_args
+        .environments_dir
+        .join(&audit.env_args.environment);
+    let deployed_program_ids = read_router_program_ids(
+        &environment_dir
+            .join("warp-routes")
+            .join(&audit.warp_route_name),
+    )
+    .unwrap_or_default();
+
+    // Foreign deployments are taken from the config, like when deploying
+    let routers = app_configs
+        .iter()
+        .filter_map(|(chain_name, app_config)| {
+            let router = match &app_config.router_config().foreign_deployment {
+                Some(foreign_deployment) => hex_or_base58_to_h256(foreign_deployment).unwrap(),
+                None => H256::from(deployed_program_ids.get(chain_name)?.to_bytes()),
+            };
+            Some((chain_name.clone(), router))
+        })
+        .collect::<HashMap<String, H256>>();
+
+    let mut chains = BTreeMap::new();
+    let mut findings: Vec<AuditFinding> = check_remote_decimals(&app_configs).into_iter().collect();
+    let mut locked_collateral = U256::zero();
+    let mut synthetic_supply = U256::zero();
+    let mut unaudited_collateral = false;
+
+    let sorted_app_configs = app_configs.iter().collect::<BTreeMap<_, _>>();
+    for (chain_name, app_config) in sorted_app_configs {
+        let mut chain_audit = ChainAudit {
+            router: routers.get(chain_name).copied(),
+            ..Default::default()
+        };
+        if app_config.router_config().foreign_deployment.is_some() {
+            chain_audit.foreign_deployment = true;
+            unaudited_collateral |= !matches!(app_config.token_type, TokenType::Synthetic(_));
+        } else {
+            match audit_sealevel_router(
+                chain_name,
+                &app_configs,
+                &chain_configs,
+                &routers,
+                &environment_dir,
+                &mut chain_audit,
+                &mut findings,
+            ) {
+                Some(RouterBalance::Collateral(amount)) => locked_collateral += amount,
+                Some(RouterBalance::Synthetic(amount)) => synthetic_supply += amount,
+                None => {}
+            }
+        }
+        chains.insert(chain_name.clone(), chain_audit);
+    }
+
+    findings.extend(check_collateral(
+        locked_collateral,
+        synthetic_supply,
+        unaudited_collateral,
+    ));
+
+    WarpRouteAuditReport {
+        warp_route_name: audit.warp_route_name,
+        healthy: findings.is_empty(),
+        chains,
+        locked_collateral: locked_collateral.to_string(),
+        synthetic_supply: synthetic_supply.to_string(),
+        findings,
+    }
+}
+
+/// Audits the router of a Sealevel chain, returning the collateral it locked
+/// or the synthetic tokens it minted in the route's remote decimals.
+fn audit_sealevel_router(
+    chain_name: &str,
+    app_configs: &HashMap<String, TokenConfig>,
+    chain_configs: &HashMap<String, ChainMetadata>,
+    routers: &HashMap<String, H256>,
+    environment_dir: &Path,
+    chain_audit: &mut ChainAudit,
+    findings: &mut Vec<AuditFinding>,
+) -> Option<RouterBalance> {
+    let domain_id = |chain_name: &str| {
+        chain_configs
+            .get(chain_name)
+            .unwrap_or_else(|| panic!("Chain config not found for chain: {}", chain_name))
+            .domain_id()
+    };
+    let chain_config = chain_configs.get(chain_name).unwrap();
+    let app_config = &app_configs[chain_name];
+    let client = chain_config.client();
+
+    let token_account = routers.get(chain_name).and_then(|router| {
+        let program_id = Pubkey::new_from_array(router.to_fixed_bytes());
+        let (token_pda, _token_bump) =
+            Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &program_id);
+        client
+            .get_account_with_commitment(&token_pda, client.commitment())
+            .unwrap()
+            .value
+            .map(|account| (program_id, account))
+    });
+    let Some((program_id, token_account)) = token_account else {
+        findings.push(AuditFinding::RouterNotDeployed {
+            chain: chain_name.to_owned(),
+        });
+        return None;
+    };
+    let token = *HyperlaneTokenAccount::<()>::fetch(&mut &token_account.data[..])
+        .unwrap()
+        .into_inner();
+
+    // Remote routers. Routers that aren't deployed are reported on their own chain.
+    let remote_chains = app_configs
+        .keys()
+        .filter(|remote_chain| *remote_chain != chain_name)
+        .collect::<BTreeSet<_>>();
+    for remote_chain in &remote_chains {
+        let Some(expected) = routers.get(*remote_chain) else {
+            continue;
+        };
+        let enrolled = token
+            .remote_routers
+            .get(&domain_id(remote_chain.as_str()))
+            .copied();
+        if enrolled != Some(*expected) {
+            findings.push(AuditFinding::RemoteRouterMismatch {
+                chain: chain_name.to_owned(),
+                remote_chain: (*remote_chain).clone(),
+                expected: *expected,
+                enrolled,
+            });
+        }
+    }
+    let remote_domains = remote_chains
+        .iter()
+        .map(|remote_chain| domain_id(remote_chain.as_str()))
+        .collect::<HashSet<u32>>();
+    let unexpected_routers = token
+        .remote_routers
+        .iter()
+        .filter(|(domain, _)| !remote_domains.contains(domain))
+        .collect::<BTreeMap<_, _>>();
+    for (domain, enrolled) in unexpected_routers {
+        findings.push(AuditFinding::UnexpectedRemoteRouter {
+            chain: chain_name.to_owned(),
+            domain: *domain,
+            enrolled: *enrolled,
+        });
+    }
+
+    // Decimals
+    let decimal_metadata = &app_config.decimal_metadata;
+    if token.decimals != decimal_metadata.decimals
+        || token.remote_decimals != decimal_metadata.remote_decimals()
+    {
+        findings.push(AuditFinding::DecimalsMismatch {
+            chain: chain_name.to_owned(),
+            configured_decimals: decimal_metadata.decimals,
+            decimals: token.decimals,
+            configured_remote_decimals: decimal_metadata.remote_decimals(),
+            remote_decimals: token.remote_decimals,
+        });
+    }
+
+    // ISM
+    let configured_ism = app_config
+        .router_config()
+        .connection_client
+        .interchain_security_module();
+    if token.interchain_security_module != configured_ism {
+        findings.push(AuditFinding::IsmMismatch {
+            chain: chain_name.to_owned(),
+            configured: configured_ism.map(|ism| ism.to_string()),
+            ism: token.interchain_security_module.map(|ism| ism.to_string()),
+        });
+    }
+    let ism = token
+        .interchain_security_module
+        .unwrap_or_else(|| get_default_ism(&client, &token.mailbox));
+    chain_audit.ism = Some(ism.to_string());
+    if !account_exists(&client, &ism).unwrap() {
+        findings.push(AuditFinding::IsmNotDeployed {
+            chain: chain_name.to_owned(),
+            ism: ism.to_string(),
+        });
+    } else if let Some((ism_context, multisig_configs)) =
+        find_multisig_ism_context(environment_dir, chain_name, &ism)
+    {
+        for remote_chain in &remote_chains {
+            let matches = multisig_configs
+                .get(*remote_chain)
+                .map_or(false, |expected| {
+                    multisig_ism_config_matches_chain(
+                        &client,
+                        client.commitment(),
+                        ism,
+                        domain_id(remote_chain.as_str()),
+                        expected,
+                    )
+                });
+            if !matches {
+                findings.push(AuditFinding::IsmValidatorsMismatch {
+                    chain: chain_name.to_owned(),
+                    ism_context: ism_context.clone(),
+                    remote_chain: (*remote_chain).clone(),
+                });
+            }
+        }
+        chain_audit.ism_context = Some(ism_context);
+    }
+
+    // Collateral and supply, in local decimals
+    let mut check_mint_decimals = |mint: &Pubkey, mint_decimals: u8| {
+        if mint_decimals != token.decimals {
+            findings.push(AuditFinding::MintDecimalsMismatch {
+                chain: chain_name.to_owned(),
+                mint: mint.to_string(),
+                mint_decimals,
+                decimals: token.decimals,
+            });
+        }
+    };
+    let balance = match &app_config.token_type {
+        TokenType::Native => {
+            let (native_collateral, _native_collateral_bump) = Pubkey::find_program_address(
+                hyperlane_token_native_collateral_pda_seeds!(),
+                &program_id,
+            );
+            // The native collateral account is kept rent exempt
+            let rent_exempt_minimum = client.get_minimum_balance_for_rent_exemption(0).unwrap();
+            let lamports = client
+                .get_balance(&native_collateral)
+                .unwrap()
+                .saturating_sub(rent_exempt_minimum);
+            RouterBalance::Collateral(lamports.into())
+        }
+        TokenType::Collateral(_) => {
+            let plugin =
+                HyperlaneTokenAccount::<CollateralPlugin>::fetch(&mut &token_account.data[..])
+                    .unwrap()
+                    .into_inner()
+                    .plugin_data;
+            check_mint_decimals(
+                &plugin.mint,
+                client.get_token_supply(&plugin.mint).unwrap().decimals,
+            );
+            let escrow = client.get_token_account_balance(&plugin.escrow).unwrap();
+            RouterBalance::Collateral(U256::from_dec_str(&escrow.amount).unwrap())
+        }
+        TokenType::Synthetic(_) => {
+            let plugin =
+                HyperlaneTokenAccount::<SyntheticPlugin>::fetch(&mut &token_account.data[..])
+                    .unwrap()
+                    .into_inner()
+                    .plugin_data;
+            let supply = client.get_token_supply(&plugin.mint).unwrap();
+            check_mint_decimals(&plugin.mint, supply.decimals);
+            RouterBalance::Synthetic(U256::from_dec_str(&supply.amount).unwrap())
+        }
+    };
+
+    // Converted to the remote decimals, so the balances of all chains add up
+    let to_remote_decimals = |amount: U256| {
+        convert_decimals(amount, token.decimals, token.remote_decimals)
+            .expect("Overflow converting balance to remote decimals")
+    };
+    let balance = match balance {
+        RouterBalance::Collateral(amount) => RouterBalance::Collateral(to_remote_decimals(amount)),
+        RouterBalance::Synthetic(amount) => RouterBalance::Synthetic(to_remote_decimals(amount)),
+    };
+    let (RouterBalance::Collateral(amount) | RouterBalance::Synthetic(amount)) = &balance;
+    chain_audit.balance = Some(amount.to_string());
+    Some(balance)
+}
+
+/// All chains must use the same decimals for the amounts sent between them.
+fn check_remote_decimals(app_configs: &HashMap<String, TokenConfig>) -> Option<AuditFinding> {
+    let remote_decimals = app_configs
+        .iter()
+        .map(|(chain_name, app_config)| {
+            (
+                chain_name.clone(),
+                app_config.decimal_metadata.remote_decimals(),
+            )
+        })
+        .collect::<BTreeMap<String, u8>>();
+    let consistent = remote_decimals.values().collect::<HashSet<_>>().len() <= 1;
+    (!consistent).then_some(AuditFinding::RemoteDecimalsInconsistent { remote_decimals })
+}
+
+/// The shortfall can only be determined if all collateral of the route is on
+/// audited chains, since synthetic tokens minted on unaudited chains only
+/// increase the amount of collateral needed.
+fn check_collateral(
+    locked_collateral: U256,
+    synthetic_supply: U256,
+    unaudited_collateral: bool,
+) -> Option<AuditFinding> {
+    (!unaudited_collateral && locked_collateral < synthetic_supply).then(|| {
+        AuditFinding::CollateralShortfall {
+            locked_collateral: locked_collateral.to_string(),
+            synthetic_supply: synthetic_supply.to_string(),
+        }
+    })
+}
+
+fn get_default_ism(client: &RpcClient, mailbox: &Pubkey) -> Pubkey {
+    let (inbox_pda, _inbox_bump) =
+        Pubkey::find_program_address(mailbox_inbox_pda_seeds!(), mailbox);
+    let account = client.get_account(&inbox_pda).unwrap();
+    InboxAccount::fetch(&mut &account.data[..])
+        .unwrap()
+        .into_inner()
+        .default_ism
+}
+
+/// Finds the multisig ISM context of the environment deployed at `ism`,
+/// returning its name and multisig config.
+fn find_multisig_ism_context(
+    environment_dir: &Path,
+    chain_name: &str,
+    ism: &Pubkey,
+) -> Option<(String, HashMap<String, MultisigIsmConfig>)> {
+    let chain_dir = environment_dir
+        .join("multisig-ism-message-id")
+        .join(chain_name);
+    std::fs::read_dir(chain_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .find_map(|entry| {
+            let context_dir = entry.path();
+            let program_id: SingularProgramIdArtifact =
+                try_read_json(&context_dir.join("program-ids.json")).ok()?;
+            if program_id.program_id != *ism {
+                return None;
+            }
+            let multisig_configs = try_read_json(&context_dir.join("multisig-config.json")).ok()?;
+            Some((
+                entry.file_name().to_string_lossy().into_owned(),
+                multisig_configs,
+            ))
+        })
+}
+
 // Funds the ATA payer up to the specified amount.
 fn fund_ata_payer_up_to(
     ctx: &mut Context,
@@ -753,3 +1227,73 @@ pub fn install_spl_token_cli() {
         .status()
         .expect("Failed to run command");
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn app_configs(configs: serde_json::Value) -> HashMap<String, TokenConfig> {
+        serde_json::from_value(configs).unwrap()
+    }
+
+    #[test]
+    fn test_check_remote_decimals() {
+        let consistent = app_configs(serde_json::json!({
+            "solanamainnet": { "type": "native", "decimals": 9, "remoteDecimals": 18 },
+            "ethereum": {
+                "type": "synthetic",
+                "decimals": 18,
+                "name": "Solana",
+                "symbol": "SOL",
+                "foreignDeployment": "0x0000000000000000000000000000000000000000000000000000000000000001"
+            }
+        }));
+        assert_eq!(check_remote_decimals(&consistent), None);
+
+        let inconsistent = app_configs(serde_json::json!({
+            "solanamainnet": { "type": "native", "decimals": 9 },
+            "eclipsemainnet": { "type": "synthetic", "decimals": 6, "name": "Solana", "symbol": "SOL" }
+        }));
+        assert_eq!(
+            check_remote_decimals(&inconsistent),
+            Some(AuditFinding::RemoteDecimalsInconsistent {
+                remote_decimals: BTreeMap::from([
+                    ("eclipsemainnet".to_owned(), 6),
+                    ("solanamainnet".to_owned(), 9),
+                ]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_collateral() {
+        assert_eq!(check_collateral(100.into(), 100.into(), false), None);
+        assert_eq!(
+            check_collateral(99.into(), 100.into(), false),
+            Some(AuditFinding::CollateralShortfall {
+                locked_collateral: "99".to_owned(),
+                synthetic_supply: "100".to_owned(),
+            })
+        );
+        // Collateral on unaudited chains may cover the supply
+        assert_eq!(check_collateral(99.into(), 100.into(), true), None);
+    }
+
+    #[test]
+    fn test_finding_serialization() {
+        let finding = AuditFinding::IsmValidatorsMismatch {
+            chain: "solanamainnet".to_owned(),
+            ism_context: "hyperlane".to_owned(),
+            remote_chain: "ethereum".to_owned(),
+        };
+        assert_eq!(
+            serde_json::to_value(finding).unwrap(),
+            serde_json::json!({
+                "check": "ismValidatorsMismatch",
+                "chain": "solanamainnet",
+                "ismContext": "hyperlane",
+                "remoteChain": "ethereum",
+            })
+        );
+    }
+}
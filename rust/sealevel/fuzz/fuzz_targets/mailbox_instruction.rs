@@ -0,0 +1,15 @@
+#![no_main]
+
+use hyperlane_core::{Decode, HyperlaneMessage};
+use hyperlane_sealevel_mailbox::instruction::Instruction;
+use libfuzzer_sys::fuzz_target;
+
+// Instruction data is supplied by whoever sends the transaction
+fuzz_target!(|data: &[u8]| {
+    if let Ok(instruction) = Instruction::from_instruction_data(data) {
+        if let Instruction::InboxProcess(process) = &instruction {
+            let _ = HyperlaneMessage::read_from(&mut &process.message[..]);
+        }
+        assert_eq!(instruction.into_instruction_data().unwrap(), data);
+    }
+});
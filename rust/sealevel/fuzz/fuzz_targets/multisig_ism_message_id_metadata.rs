@@ -0,0 +1,19 @@
+#![no_main]
+
+use hyperlane_core::Encode;
+use hyperlane_sealevel_multisig_ism_message_id::metadata::MultisigIsmMessageIdMetadata;
+use libfuzzer_sys::fuzz_target;
+
+// Metadata is supplied by whoever relays the message
+fuzz_target!(|data: &[u8]| {
+    if let Ok(metadata) = MultisigIsmMessageIdMetadata::try_from(data.to_vec()) {
+        // Recovery ids of 27 and 28 are normalized, so only the length is
+        // preserved, but re-encoding is stable
+        let encoded = metadata.to_vec();
+        assert_eq!(encoded.len(), data.len());
+        let reencoded = MultisigIsmMessageIdMetadata::try_from(encoded.clone())
+            .unwrap()
+            .to_vec();
+        assert_eq!(reencoded, encoded);
+    }
+});
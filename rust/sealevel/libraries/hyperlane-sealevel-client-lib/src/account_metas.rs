@@ -0,0 +1,82 @@
+//! Instructions that return the account metas other instructions require.
+//!
+//! Programs return these account metas as `SimulationReturnData<Vec<SerializableAccountMeta>>`
+//! when the instruction is simulated.
+
+use hyperlane_core::HyperlaneMessage;
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction, VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+};
+use hyperlane_sealevel_message_recipient_interface::{
+    HandleInstruction, MessageRecipientInstruction, HANDLE_ACCOUNT_METAS_PDA_SEEDS,
+    INTERCHAIN_SECURITY_MODULE_ACCOUNT_METAS_PDA_SEEDS,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// Creates an instruction for `program_id` with the given data, whose only
+/// account is the program's account metas PDA for `account_metas_pda_seeds`.
+pub fn account_metas_instruction(
+    program_id: Pubkey,
+    instruction_data: Vec<u8>,
+    account_metas_pda_seeds: &[&[u8]],
+) -> Result<Instruction, ProgramError> {
+    let (account_metas_pda_key, _account_metas_pda_bump) =
+        Pubkey::try_find_program_address(account_metas_pda_seeds, &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    Ok(Instruction::new_with_bytes(
+        program_id,
+        &instruction_data,
+        vec![AccountMeta::new(account_metas_pda_key, false)],
+    ))
+}
+
+/// Creates a recipient's `MessageRecipientInstruction::InterchainSecurityModuleAccountMetas`
+/// instruction, which returns the account metas required to get its ISM.
+pub fn ism_getter_account_metas_instruction(
+    recipient_program_id: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    account_metas_instruction(
+        recipient_program_id,
+        MessageRecipientInstruction::InterchainSecurityModuleAccountMetas.encode()?,
+        INTERCHAIN_SECURITY_MODULE_ACCOUNT_METAS_PDA_SEEDS,
+    )
+}
+
+/// Creates an ISM's `InterchainSecurityModuleInstruction::VerifyAccountMetas`
+/// instruction, which returns the account metas required to verify the message.
+pub fn ism_verify_account_metas_instruction(
+    ism: Pubkey,
+    metadata: Vec<u8>,
+    message: Vec<u8>,
+) -> Result<Instruction, ProgramError> {
+    account_metas_instruction(
+        ism,
+        InterchainSecurityModuleInstruction::VerifyAccountMetas(VerifyInstruction {
+            metadata,
+            message,
+        })
+        .encode()?,
+        VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+    )
+}
+
+/// Creates a recipient's `MessageRecipientInstruction::HandleAccountMetas`
+/// instruction, which returns the account metas required to handle the message.
+pub fn handle_account_metas_instruction(
+    message: &HyperlaneMessage,
+) -> Result<Instruction, ProgramError> {
+    account_metas_instruction(
+        Pubkey::new_from_array(message.recipient.into()),
+        MessageRecipientInstruction::HandleAccountMetas(HandleInstruction {
+            sender: message.sender,
+            origin: message.origin,
+            message: message.body.clone(),
+        })
+        .encode()?,
+        HANDLE_ACCOUNT_METAS_PDA_SEEDS,
+    )
+}
@@ -0,0 +1,10 @@
+//! Instruction building for off-chain clients of the Hyperlane Sealevel
+//! programs, shared by the agents and the CLI tool.
+//!
+//! Nothing in this crate talks to an RPC node. Instructions that return data,
+//! like the account metas getters, are meant to be simulated by the caller.
+
+#![deny(missing_docs)]
+
+pub mod account_metas;
+pub mod mailbox;
@@ -0,0 +1,449 @@
+//! Instructions and PDAs of the Mailbox program.
+
+use hyperlane_core::{Encode, HyperlaneMessage, H256};
+use hyperlane_sealevel_mailbox::{
+    instruction::{
+        InboxGetRecipientIsmForOrigin, InboxProcess, InboxProcessBatch, InboxProcessBatchItem,
+        Instruction as MailboxInstruction, OutboxDispatch,
+    },
+    mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds,
+    mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds, spl_noop,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// Gets the Inbox PDA.
+pub fn inbox_pda(mailbox_program_id: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
+    Pubkey::try_find_program_address(mailbox_inbox_pda_seeds!(), mailbox_program_id)
+        .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Gets the Outbox PDA.
+pub fn outbox_pda(mailbox_program_id: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
+    Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), mailbox_program_id)
+        .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Gets the PDA that the contents of the message dispatched with the given
+/// unique message account are stored in.
+pub fn dispatched_message_pda(
+    mailbox_program_id: &Pubkey,
+    unique_message_account: &Pubkey,
+) -> Result<(Pubkey, u8), ProgramError> {
+    Pubkey::try_find_program_address(
+        mailbox_dispatched_message_pda_seeds!(unique_message_account),
+        mailbox_program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Gets the PDA the Mailbox signs the recipient's `Handle` instruction with.
+pub fn process_authority_pda(
+    mailbox_program_id: &Pubkey,
+    recipient_program_id: &Pubkey,
+) -> Result<(Pubkey, u8), ProgramError> {
+    Pubkey::try_find_program_address(
+        mailbox_process_authority_pda_seeds!(recipient_program_id),
+        mailbox_program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Gets the PDA that exists once the message with the given ID was processed.
+pub fn processed_message_pda(
+    mailbox_program_id: &Pubkey,
+    message_id: H256,
+) -> Result<(Pubkey, u8), ProgramError> {
+    Pubkey::try_find_program_address(
+        mailbox_processed_message_pda_seeds!(message_id),
+        mailbox_program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Creates an OutboxDispatch instruction.
+///
+/// `sender_signer` must be `dispatch.sender`, or the dispatch authority PDA of
+/// the `dispatch.sender` program. Both `payer` and `unique_message_account`
+/// must sign the transaction.
+pub fn dispatch_instruction(
+    mailbox_program_id: Pubkey,
+    sender_signer: Pubkey,
+    payer: Pubkey,
+    unique_message_account: Pubkey,
+    dispatch: OutboxDispatch,
+) -> Result<Instruction, ProgramError> {
+    let (outbox_account, _outbox_bump) = outbox_pda(&mailbox_program_id)?;
+    let (dispatched_message_account, _dispatched_message_bump) =
+        dispatched_message_pda(&mailbox_program_id, &unique_message_account)?;
+
+    // 0. `[writeable]` Outbox PDA.
+    // 1. `[signer]` Message sender signer.
+    // 2. `[executable]` System program.
+    // 3. `[executable]` SPL Noop program.
+    // 4. `[signer]` Payer.
+    // 5. `[signer]` Unique message account.
+    // 6. `[writeable]` Dispatched message PDA.
+    let instruction = Instruction {
+        program_id: mailbox_program_id,
+        data: MailboxInstruction::OutboxDispatch(dispatch).into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new(outbox_account, false),
+            AccountMeta::new_readonly(sender_signer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_noop::id(), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(unique_message_account, true),
+            AccountMeta::new(dispatched_message_account, false),
+        ],
+    };
+    Ok(instruction)
+}
+
+/// Creates an InboxGetRecipientIsm instruction, which returns the recipient's
+/// ISM as `SimulationReturnData<Pubkey>` when simulated.
+///
+/// `ism_getter_account_metas` are the account metas returned by the recipient's
+/// `InterchainSecurityModuleAccountMetas` instruction.
+pub fn get_recipient_ism_instruction(
+    mailbox_program_id: Pubkey,
+    recipient_program_id: Pubkey,
+    ism_getter_account_metas: Vec<AccountMeta>,
+) -> Result<Instruction, ProgramError> {
+    let (inbox_account, _inbox_bump) = inbox_pda(&mailbox_program_id)?;
+
+    // 0.    `[]` - The Inbox PDA.
+    // 1.    `[]` - The recipient program.
+    // 2..N. [??] - The accounts required to make the CPI into the recipient program.
+    let mut accounts = vec![
+        AccountMeta::new_readonly(inbox_account, false),
+        AccountMeta::new_readonly(recipient_program_id, false),
+    ];
+    accounts.extend(ism_getter_account_metas);
+
+    let instruction = Instruction {
+        program_id: mailbox_program_id,
+        data: MailboxInstruction::InboxGetRecipientIsm(recipient_program_id)
+            .into_instruction_data()?,
+        accounts,
+    };
+    Ok(instruction)
+}
+
+/// Creates an InboxGetRecipientIsmForOrigin instruction, which returns the
+/// ISM of the recipient's messages from `origin`, taking the default ISM
+/// overrides into account, as `SimulationReturnData<Pubkey>` when simulated.
+///
+/// `ism_getter_account_metas` are the account metas returned by the recipient's
+/// `InterchainSecurityModuleAccountMetas` instruction.
+pub fn get_recipient_ism_for_origin_instruction(
+    mailbox_program_id: Pubkey,
+    recipient_program_id: Pubkey,
+    origin: u32,
+    ism_getter_account_metas: Vec<AccountMeta>,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = get_recipient_ism_instruction(
+        mailbox_program_id,
+        recipient_program_id,
+        ism_getter_account_metas,
+    )?;
+    // Same accounts as InboxGetRecipientIsm
+    instruction.data =
+        MailboxInstruction::InboxGetRecipientIsmForOrigin(InboxGetRecipientIsmForOrigin {
+            recipient: recipient_program_id,
+            origin,
+        })
+        .into_instruction_data()?;
+    Ok(instruction)
+}
+
+/// The accounts of an InboxProcess instruction that depend on the recipient
+/// and its ISM.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessAccountMetas {
+    /// The account metas returned by the recipient's
+    /// `InterchainSecurityModuleAccountMetas` instruction.
+    pub ism_getter: Vec<AccountMeta>,
+    /// The recipient's ISM.
+    pub ism: Pubkey,
+    /// The account metas returned by the ISM's `VerifyAccountMetas` instruction.
+    pub ism_verify: Vec<AccountMeta>,
+    /// The account metas returned by the recipient's `HandleAccountMetas` instruction.
+    pub handle: Vec<AccountMeta>,
+}
+
+/// Creates an InboxProcess instruction. `payer` must sign the transaction.
+pub fn process_instruction(
+    mailbox_program_id: Pubkey,
+    payer: Pubkey,
+    message: &HyperlaneMessage,
+    metadata: Vec<u8>,
+    account_metas: ProcessAccountMetas,
+) -> Result<Instruction, ProgramError> {
+    let recipient = Pubkey::new_from_array(message.recipient.into());
+    let (inbox_account, _inbox_bump) = inbox_pda(&mailbox_program_id)?;
+    let (process_authority_account, _process_authority_bump) =
+        process_authority_pda(&mailbox_program_id, &recipient)?;
+    let (processed_message_account, _processed_message_bump) =
+        processed_message_pda(&mailbox_program_id, message.id())?;
+
+    let data = MailboxInstruction::InboxProcess(InboxProcess {
+        metadata,
+        message: message.to_vec(),
+    })
+    .into_instruction_data()?;
+
+    // 0.      `[signer]` Payer account.
+    // 1.      `[executable]` The system program.
+    // 2.      `[writable]` Inbox PDA account.
+    // 3.      `[]` Mailbox process authority specific to the message recipient.
+    // 4.      `[writable]` Processed message PDA.
+    // 5..N    [??] Accounts required to invoke the recipient's InterchainSecurityModule instruction.
+    // N+1.    `[executable]` SPL noop
+    // N+2.    `[executable]` ISM
+    // N+2..M. [??] Accounts required to invoke the ISM's Verify instruction.
+    // M+1.    `[executable]` Recipient program.
+    // M+2..K. [??] Accounts required to invoke the recipient's Handle instruction.
+    let mut accounts = vec![
+        AccountMeta::new_readonly(payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(inbox_account, false),
+        AccountMeta::new_readonly(process_authority_account, false),
+        AccountMeta::new(processed_message_account, false),
+    ];
+    accounts.extend(account_metas.ism_getter);
+    accounts.extend([
+        AccountMeta::new_readonly(spl_noop::id(), false),
+        AccountMeta::new_readonly(account_metas.ism, false),
+    ]);
+    accounts.extend(account_metas.ism_verify);
+    accounts.push(AccountMeta::new_readonly(recipient, false));
+    accounts.extend(account_metas.handle);
+
+    let instruction = Instruction {
+        program_id: mailbox_program_id,
+        data,
+        accounts,
+    };
+    Ok(instruction)
+}
+
+/// A message of an InboxProcessBatch instruction, with the accounts that
+/// depend on its recipient.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchProcessMessage {
+    /// The message.
+    pub message: HyperlaneMessage,
+    /// The metadata the ISM verifies the message with.
+    pub metadata: Vec<u8>,
+    /// The account metas returned by the recipient's
+    /// `InterchainSecurityModuleAccountMetas` instruction.
+    pub ism_getter: Vec<AccountMeta>,
+    /// The account metas returned by the recipient's `HandleAccountMetas` instruction.
+    pub handle: Vec<AccountMeta>,
+}
+
+/// Creates an InboxProcessBatch instruction, which processes `messages` in
+/// order, all of whose recipients must use `ism`. `ism_verify` are the
+/// accounts of the ISM's `VerifyBatch` instruction. `payer` must sign the
+/// transaction.
+pub fn process_batch_instruction(
+    mailbox_program_id: Pubkey,
+    payer: Pubkey,
+    ism: Pubkey,
+    ism_verify: Vec<AccountMeta>,
+    messages: Vec<BatchProcessMessage>,
+) -> Result<Instruction, ProgramError> {
+    let (inbox_account, _inbox_bump) = inbox_pda(&mailbox_program_id)?;
+
+    // 0.      `[signer]` Payer account.
+    // 1.      `[executable]` The system program.
+    // 2.      `[writable]` Inbox PDA account.
+    // 3.      `[executable]` SPL noop
+    // 4.      `[executable]` ISM
+    // 5..N.   [??] Accounts required to invoke the ISM's VerifyBatch instruction.
+    // Then, for each message:
+    //         `[]` Mailbox process authority specific to the message recipient.
+    //         `[writable]` Processed message PDA.
+    //         [??] Accounts required to invoke the recipient's InterchainSecurityModule instruction.
+    //         `[executable]` Recipient program.
+    //         [??] Accounts required to invoke the recipient's Handle instruction.
+    let ism_verify_account_count =
+        u8::try_from(ism_verify.len()).map_err(|_| ProgramError::InvalidArgument)?;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(inbox_account, false),
+        AccountMeta::new_readonly(spl_noop::id(), false),
+        AccountMeta::new_readonly(ism, false),
+    ];
+    accounts.extend(ism_verify);
+
+    let mut items = Vec::with_capacity(messages.len());
+    for message in messages {
+        let recipient = Pubkey::new_from_array(message.message.recipient.into());
+        let (process_authority_account, _process_authority_bump) =
+            process_authority_pda(&mailbox_program_id, &recipient)?;
+        let (processed_message_account, _processed_message_bump) =
+            processed_message_pda(&mailbox_program_id, message.message.id())?;
+        items.push(InboxProcessBatchItem {
+            metadata: message.metadata,
+            message: message.message.to_vec(),
+            ism_getter_account_count: u8::try_from(message.ism_getter.len())
+                .map_err(|_| ProgramError::InvalidArgument)?,
+            handle_account_count: u8::try_from(message.handle.len())
+                .map_err(|_| ProgramError::InvalidArgument)?,
+        });
+
+        accounts.extend([
+            AccountMeta::new_readonly(process_authority_account, false),
+            AccountMeta::new(processed_message_account, false),
+        ]);
+        accounts.extend(message.ism_getter);
+        accounts.push(AccountMeta::new_readonly(recipient, false));
+        accounts.extend(message.handle);
+    }
+
+    let instruction = Instruction {
+        program_id: mailbox_program_id,
+        data: MailboxInstruction::InboxProcessBatch(InboxProcessBatch {
+            items,
+            ism_verify_account_count,
+        })
+        .into_instruction_data()?,
+        accounts,
+    };
+    Ok(instruction)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_process_instruction_accounts() {
+        let mailbox_program_id = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let message = HyperlaneMessage {
+            recipient: H256(recipient.to_bytes()),
+            ..Default::default()
+        };
+        let account_metas = ProcessAccountMetas {
+            ism_getter: vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)],
+            ism: Pubkey::new_unique(),
+            ism_verify: vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)],
+            handle: vec![AccountMeta::new(Pubkey::new_unique(), false)],
+        };
+
+        let instruction = process_instruction(
+            mailbox_program_id,
+            payer,
+            &message,
+            vec![1, 2, 3],
+            account_metas.clone(),
+        )
+        .unwrap();
+
+        let (inbox_account, _) = inbox_pda(&mailbox_program_id).unwrap();
+        let (process_authority_account, _) =
+            process_authority_pda(&mailbox_program_id, &recipient).unwrap();
+        let (processed_message_account, _) =
+            processed_message_pda(&mailbox_program_id, message.id()).unwrap();
+        assert_eq!(instruction.program_id, mailbox_program_id);
+        assert_eq!(
+            instruction.accounts,
+            vec![
+                AccountMeta::new_readonly(payer, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(inbox_account, false),
+                AccountMeta::new_readonly(process_authority_account, false),
+                AccountMeta::new(processed_message_account, false),
+                account_metas.ism_getter[0].clone(),
+                AccountMeta::new_readonly(spl_noop::id(), false),
+                AccountMeta::new_readonly(account_metas.ism, false),
+                account_metas.ism_verify[0].clone(),
+                AccountMeta::new_readonly(recipient, false),
+                account_metas.handle[0].clone(),
+            ]
+        );
+        assert_eq!(
+            MailboxInstruction::from_instruction_data(&instruction.data).unwrap(),
+            MailboxInstruction::InboxProcess(InboxProcess {
+                metadata: vec![1, 2, 3],
+                message: message.to_vec(),
+            })
+        );
+    }
+    #[test]
+    fn test_process_batch_instruction_accounts() {
+        let mailbox_program_id = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let ism = Pubkey::new_unique();
+        let ism_verify = vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)];
+        let messages: Vec<_> = (0..2)
+            .map(|nonce| BatchProcessMessage {
+                message: HyperlaneMessage {
+                    nonce,
+                    recipient: H256(Pubkey::new_unique().to_bytes()),
+                    ..Default::default()
+                },
+                metadata: vec![nonce as u8],
+                ism_getter: vec![],
+                handle: vec![AccountMeta::new(Pubkey::new_unique(), false)],
+            })
+            .collect();
+
+        let instruction = process_batch_instruction(
+            mailbox_program_id,
+            payer,
+            ism,
+            ism_verify.clone(),
+            messages.clone(),
+        )
+        .unwrap();
+
+        let (inbox_account, _) = inbox_pda(&mailbox_program_id).unwrap();
+        let mut expected_accounts = vec![
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(inbox_account, false),
+            AccountMeta::new_readonly(spl_noop::id(), false),
+            AccountMeta::new_readonly(ism, false),
+            ism_verify[0].clone(),
+        ];
+        for message in &messages {
+            let recipient = Pubkey::new_from_array(message.message.recipient.into());
+            let (process_authority_account, _) =
+                process_authority_pda(&mailbox_program_id, &recipient).unwrap();
+            let (processed_message_account, _) =
+                processed_message_pda(&mailbox_program_id, message.message.id()).unwrap();
+            expected_accounts.extend([
+                AccountMeta::new_readonly(process_authority_account, false),
+                AccountMeta::new(processed_message_account, false),
+                AccountMeta::new_readonly(recipient, false),
+                message.handle[0].clone(),
+            ]);
+        }
+        assert_eq!(instruction.accounts, expected_accounts);
+        assert_eq!(
+            MailboxInstruction::from_instruction_data(&instruction.data).unwrap(),
+            MailboxInstruction::InboxProcessBatch(InboxProcessBatch {
+                items: messages
+                    .iter()
+                    .map(|message| InboxProcessBatchItem {
+                        metadata: message.metadata.clone(),
+                        message: message.message.to_vec(),
+                        ism_getter_account_count: 0,
+                        handle_account_count: 1,
+                    })
+                    .collect(),
+                ism_verify_account_count: 1,
+            })
+        );
+    }
+}
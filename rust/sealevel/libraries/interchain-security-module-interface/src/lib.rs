@@ -15,6 +15,9 @@ pub enum InterchainSecurityModuleInstruction {
     /// The only account expected to be passed into this instruction is the
     /// read-only PDA relating to the program ID and the seeds `VERIFY_ACCOUNT_METAS_PDA_SEEDS`
     VerifyAccountMetas(VerifyInstruction),
+    /// Verifies several messages in a single instruction. Fails if any of
+    /// them fails verification.
+    VerifyBatch(VerifyBatchInstruction),
 }
 
 /// First 8 bytes of `hash::hashv(&[b"hyperlane-interchain-security-module:type"])`
@@ -37,11 +40,26 @@ impl VerifyInstruction {
 const VERIFY_DISCRIMINATOR: [u8; Discriminator::LENGTH] = [243, 53, 214, 0, 208, 18, 231, 67];
 const VERIFY_DISCRIMINATOR_SLICE: &[u8] = &VERIFY_DISCRIMINATOR;
 
+#[derive(Eq, PartialEq, BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VerifyBatchInstruction {
+    pub items: Vec<VerifyInstruction>,
+}
+
+impl VerifyBatchInstruction {
+    pub fn new(items: Vec<VerifyInstruction>) -> Self {
+        Self { items }
+    }
+}
+
 /// First 8 bytes of `hash::hashv(&[b"hyperlane-interchain-security-module:verify-account-metas"])`
 const VERIFY_ACCOUNT_METAS_DISCRIMINATOR: [u8; Discriminator::LENGTH] =
     [200, 65, 157, 12, 89, 255, 131, 216];
 const VERIFY_ACCOUNT_METAS_DISCRIMINATOR_SLICE: &[u8] = &VERIFY_ACCOUNT_METAS_DISCRIMINATOR;
 
+/// First 8 bytes of `hash::hashv(&[b"hyperlane-interchain-security-module:verify-batch"])`
+const VERIFY_BATCH_DISCRIMINATOR: [u8; Discriminator::LENGTH] = [237, 169, 74, 15, 54, 95, 154, 47];
+const VERIFY_BATCH_DISCRIMINATOR_SLICE: &[u8] = &VERIFY_BATCH_DISCRIMINATOR;
+
 /// Seeds for the PDA that's expected to be passed into the `VerifyAccountMetas`
 /// instruction.
 pub const VERIFY_ACCOUNT_METAS_PDA_SEEDS: &[&[u8]] =
@@ -70,6 +88,14 @@ impl InterchainSecurityModuleInstruction {
                         .map_err(|err| ProgramError::BorshIoError(err.to_string()))?[..],
                 );
             }
+            InterchainSecurityModuleInstruction::VerifyBatch(instruction) => {
+                buf.extend_from_slice(VERIFY_BATCH_DISCRIMINATOR_SLICE);
+                buf.extend_from_slice(
+                    &instruction
+                        .try_to_vec()
+                        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?[..],
+                );
+            }
         }
 
         Ok(buf)
@@ -92,6 +118,11 @@ impl InterchainSecurityModuleInstruction {
                     .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
                 Ok(Self::VerifyAccountMetas(instruction))
             }
+            VERIFY_BATCH_DISCRIMINATOR_SLICE => {
+                let instruction = VerifyBatchInstruction::try_from_slice(rest)
+                    .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+                Ok(Self::VerifyBatch(instruction))
+            }
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
@@ -121,6 +152,12 @@ mod test {
                 [..Discriminator::LENGTH],
             VERIFY_ACCOUNT_METAS_DISCRIMINATOR_SLICE,
         );
+
+        assert_eq!(
+            &hashv(&[b"hyperlane-interchain-security-module:verify-batch"]).to_bytes()
+                [..Discriminator::LENGTH],
+            VERIFY_BATCH_DISCRIMINATOR_SLICE,
+        );
     }
 
     #[test]
@@ -166,4 +203,22 @@ mod test {
         let decoded = InterchainSecurityModuleInstruction::decode(&encoded).unwrap();
         assert_eq!(instruction, decoded);
     }
+
+    #[test]
+    fn test_encode_decode_verify_batch_instruction() {
+        let instruction =
+            InterchainSecurityModuleInstruction::VerifyBatch(VerifyBatchInstruction::new(vec![
+                VerifyInstruction::new(vec![5, 4, 3, 2, 1], vec![1, 2, 3, 4, 5]),
+                VerifyInstruction::new(vec![9, 8, 7], vec![7, 8, 9]),
+            ]));
+
+        let encoded = instruction.encode().unwrap();
+        assert_eq!(
+            &encoded[..Discriminator::LENGTH],
+            VERIFY_BATCH_DISCRIMINATOR_SLICE,
+        );
+
+        let decoded = InterchainSecurityModuleInstruction::decode(&encoded).unwrap();
+        assert_eq!(instruction, decoded);
+    }
 }
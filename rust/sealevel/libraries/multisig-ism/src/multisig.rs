@@ -1,6 +1,42 @@
+use std::collections::BTreeMap;
+
 use crate::error::MultisigIsmError;
 use ecdsa_signature::EcdsaSignature;
-use hyperlane_core::{Signable, H160};
+use hyperlane_core::{Signable, H160, H256};
+
+/// The signers recovered from signatures, by signed digest and signature.
+/// Shared between the verifications of a batch, so that a signature over a
+/// digest that's verified several times, e.g. a checkpoint shared by several
+/// messages, is only recovered once.
+#[derive(Debug, Default)]
+pub struct RecoveredSigners(BTreeMap<(H256, [u8; 65]), H160>);
+
+impl RecoveredSigners {
+    fn recover(
+        &mut self,
+        signed_digest: H256,
+        signature: &EcdsaSignature,
+    ) -> Result<H160, MultisigIsmError> {
+        let key = (signed_digest, signature.as_fixed_bytes());
+        if let Some(signer) = self.0.get(&key) {
+            return Ok(*signer);
+        }
+        let signer = signature
+            .secp256k1_recover_ethereum_address(signed_digest.as_bytes())
+            .map_err(|_| MultisigIsmError::InvalidSignature)?;
+        self.0.insert(key, signer);
+        Ok(signer)
+    }
+
+    /// The number of distinct signatures recovered
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
 
 /// A type for verifying a quorum of ECDSA signatures from a validator set
 /// over a signable data type.
@@ -32,17 +68,23 @@ impl<T: Signable> MultisigIsm<T> {
     /// ordering.
     /// Returns an error if the threshold is not met or if any of the signatures are invalid.
     pub fn verify(&self) -> Result<(), MultisigIsmError> {
+        self.verify_with_recovered_signers(&mut RecoveredSigners::default())
+    }
+
+    /// Like `verify`, but reuses the signers already recovered in
+    /// `recovered_signers` and adds the ones it recovers.
+    pub fn verify_with_recovered_signers(
+        &self,
+        recovered_signers: &mut RecoveredSigners,
+    ) -> Result<(), MultisigIsmError> {
         let signed_digest = self.signed_data.eth_signed_message_hash();
-        let signed_digest_bytes = signed_digest.as_bytes();
 
         let validator_count = self.validators.len();
         let mut validator_index = 0;
 
         // Assumes that signatures are ordered by validator
         for i in 0..self.threshold {
-            let signer = self.signatures[i as usize]
-                .secp256k1_recover_ethereum_address(signed_digest_bytes)
-                .map_err(|_| MultisigIsmError::InvalidSignature)?;
+            let signer = recovered_signers.recover(signed_digest, &self.signatures[i as usize])?;
 
             while validator_index < validator_count && signer != self.validators[validator_index] {
                 validator_index += 1;
@@ -67,12 +109,12 @@ mod test {
 
     use std::str::FromStr;
 
-    use hyperlane_core::H256;
+    use hyperlane_core::{HyperlaneHasher, H256};
 
     struct TestSignedPayload();
 
     impl Signable for TestSignedPayload {
-        fn signing_hash(&self) -> H256 {
+        fn signing_hash_with(&self, _hasher: &dyn HyperlaneHasher) -> H256 {
             H256::from_str("0xf00000000000000000000000000000000000000000000000000000000000000f")
                 .unwrap()
         }
@@ -135,6 +177,35 @@ mod test {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_multisig_ism_verify_with_recovered_signers() {
+        let validator_0 = H160::from_str("0xfdB65576568b99A8a00a292577b8fc51abB115bD").unwrap();
+        let signature_0 = "4e561dcd350b7a271c7247843f7731a8a9810037c13784f5b3a9616788ca536976c5ff70b1865c4568e273a375851a5304dc7a1ac54f0783f3dde38d345313a91c";
+        let validator_1 = H160::from_str("0x5090cEd8BC5A7D3c2FbE2b2702eE4a8e7b227181").unwrap();
+        let signature_1 = "9d510e0d988e44cf05a4e29d7b1ecec6e3277a8be137164f89d6cf52325190f058101ef9aa57d118f9452a38c156efbdb1b69d4022ac2c35370c433ca5b61aeb1c";
+        let multisig_ism = || {
+            MultisigIsm::new(
+                TestSignedPayload(),
+                vec![
+                    EcdsaSignature::from_bytes(&hex::decode(signature_0).unwrap()[..]).unwrap(),
+                    EcdsaSignature::from_bytes(&hex::decode(signature_1).unwrap()[..]).unwrap(),
+                ],
+                vec![validator_0, validator_1],
+                2,
+            )
+        };
+
+        let mut recovered_signers = RecoveredSigners::default();
+        assert!(multisig_ism()
+            .verify_with_recovered_signers(&mut recovered_signers)
+            .is_ok());
+        assert!(multisig_ism()
+            .verify_with_recovered_signers(&mut recovered_signers)
+            .is_ok());
+        // The signatures of the second verification were already recovered
+        assert_eq!(recovered_signers.len(), 2);
+    }
+
     #[test]
     fn test_multisig_ism_verify_threshold_not_met() {
         let validator_0 = H160::from_str("0xfdB65576568b99A8a00a292577b8fc51abB115bD").unwrap();
@@ -0,0 +1,111 @@
+//! Accounts for the collateral token program.
+
+use account_utils::{AccountData, SizedData};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::hyperlane_token_depositor_allowlist_pda_seeds;
+
+/// Depositor allowlist account data.
+pub type DepositorAllowlistAccount = AccountData<DepositorAllowlist>;
+
+/// A PDA account containing the depositors that may transfer collateral to
+/// remote chains, for warp routes that must restrict who can lock collateral.
+///
+/// Deposits are unrestricted until the owner creates the account, and while
+/// it isn't enabled.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Default)]
+pub struct DepositorAllowlist {
+    /// The bump seed for this PDA.
+    pub bump: u8,
+    /// Whether deposits are restricted to the allowed depositors.
+    pub enabled: bool,
+    /// The wallets allowed to deposit collateral.
+    pub depositors: Vec<Pubkey>,
+}
+
+impl SizedData for DepositorAllowlist {
+    fn size(&self) -> usize {
+        // bump
+        std::mem::size_of::<u8>()
+            // enabled
+            + std::mem::size_of::<bool>()
+            // depositors
+            + 4 + (32 * self.depositors.len())
+    }
+}
+
+impl DepositorAllowlist {
+    /// Deserializes the data from the provided `depositor_allowlist_account_info`.
+    /// Returns None if the account wasn't created yet.
+    /// Returns an Err if the provided account is not the canonical depositor
+    /// allowlist PDA for this program.
+    pub fn verify_account_and_fetch(
+        program_id: &Pubkey,
+        depositor_allowlist_account_info: &AccountInfo<'_>,
+    ) -> Result<Option<Box<Self>>, ProgramError> {
+        if depositor_allowlist_account_info.data_is_empty() {
+            // There's no stored bump to derive the key with until the
+            // account is created.
+            let (expected_key, _bump) = Pubkey::find_program_address(
+                hyperlane_token_depositor_allowlist_pda_seeds!(),
+                program_id,
+            );
+            if depositor_allowlist_account_info.key != &expected_key {
+                return Err(ProgramError::InvalidArgument);
+            }
+            return Ok(None);
+        }
+        if depositor_allowlist_account_info.owner != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let depositor_allowlist = DepositorAllowlistAccount::fetch_data(
+            &mut &depositor_allowlist_account_info.data.borrow()[..],
+        )?
+        .ok_or(ProgramError::UninitializedAccount)?;
+        let expected_key = Pubkey::create_program_address(
+            hyperlane_token_depositor_allowlist_pda_seeds!(depositor_allowlist.bump),
+            program_id,
+        )?;
+        if depositor_allowlist_account_info.key != &expected_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(Some(depositor_allowlist))
+    }
+
+    /// Whether `depositor` may deposit collateral.
+    pub fn is_allowed(&self, depositor: &Pubkey) -> bool {
+        !self.enabled || self.depositors.contains(depositor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_allowed_only_restricts_when_enabled() {
+        let depositor = Pubkey::new_unique();
+        let mut allowlist = DepositorAllowlist {
+            bump: 255,
+            enabled: false,
+            depositors: vec![depositor],
+        };
+        assert!(allowlist.is_allowed(&Pubkey::new_unique()));
+
+        allowlist.enabled = true;
+        assert!(allowlist.is_allowed(&depositor));
+        assert!(!allowlist.is_allowed(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_size() {
+        let allowlist = DepositorAllowlist {
+            bump: 255,
+            enabled: true,
+            depositors: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let serialized = allowlist.try_to_vec().unwrap();
+        assert_eq!(serialized.len(), allowlist.size());
+    }
+}
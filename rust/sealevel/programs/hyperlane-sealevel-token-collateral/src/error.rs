@@ -0,0 +1,21 @@
+//! Errors for the Hyperlane Sealevel token collateral program.
+
+use solana_program::program_error::ProgramError;
+
+/// Custom errors that may be returned by the Hyperlane Sealevel token collateral program.
+#[derive(Copy, Clone, Debug, Eq, thiserror::Error, num_derive::FromPrimitive, PartialEq)]
+#[repr(u32)]
+pub enum Error {
+    /// The sender isn't allowed to deposit collateral.
+    #[error("Depositor not in the depositor allowlist")]
+    DepositorNotAllowed = 1,
+    /// The program wasn't built with the `depositor-allowlist` feature.
+    #[error("Depositor allowlist not supported by this program")]
+    DepositorAllowlistNotSupported = 2,
+}
+
+impl From<Error> for ProgramError {
+    fn from(err: Error) -> Self {
+        ProgramError::Custom(err as u32)
+    }
+}
@@ -0,0 +1,80 @@
+//! Events logged by the collateral token program when its depositor
+//! allowlist changes.
+//!
+//! Events are logged with a CPI to the SPL Noop program, like the events of
+//! the Mailbox. The data of each is the `COLLATERAL_EVENT_DISCRIMINATOR`
+//! followed by the Borsh encoded `CollateralEvent`.
+//!
+//! Anyone can log data to the SPL Noop program, so consumers should only trust
+//! events in transactions that also instruct this program to make the change.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Prefix of the data of the SPL Noop instructions that log collateral token
+/// events, to tell them apart from other data logged to the SPL Noop program.
+pub const COLLATERAL_EVENT_DISCRIMINATOR: &[u8; 8] = b"HYPLCLEV";
+
+/// An event of the collateral token program.
+///
+/// The variant index is part of the encoding, so new events must only ever be
+/// appended.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone)]
+pub enum CollateralEvent {
+    /// Deposits were restricted to the allowed depositors, or the restriction
+    /// was lifted.
+    DepositorAllowlistEnabledSet {
+        /// Whether deposits are now restricted.
+        enabled: bool,
+    },
+    /// A depositor was added to the allowlist.
+    DepositorAdded {
+        /// The added depositor.
+        depositor: Pubkey,
+    },
+    /// A depositor was removed from the allowlist.
+    DepositorRemoved {
+        /// The removed depositor.
+        depositor: Pubkey,
+    },
+}
+
+impl CollateralEvent {
+    /// Serializes the event into the data of an SPL Noop instruction.
+    pub fn to_noop_data(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut data = COLLATERAL_EVENT_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)
+            .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+        Ok(data)
+    }
+
+    /// Deserializes an event from the data of an SPL Noop instruction.
+    /// Returns None if the data isn't a collateral token event.
+    pub fn from_noop_data(data: &[u8]) -> Option<Self> {
+        let event_data = data.strip_prefix(&COLLATERAL_EVENT_DISCRIMINATOR[..])?;
+        Self::try_from_slice(event_data).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_noop_data_roundtrip() {
+        let events = [
+            CollateralEvent::DepositorAllowlistEnabledSet { enabled: true },
+            CollateralEvent::DepositorAdded {
+                depositor: Pubkey::new_unique(),
+            },
+            CollateralEvent::DepositorRemoved {
+                depositor: Pubkey::new_unique(),
+            },
+        ];
+        for event in events {
+            let data = event.to_noop_data().unwrap();
+            assert_eq!(CollateralEvent::from_noop_data(&data), Some(event));
+        }
+        assert_eq!(CollateralEvent::from_noop_data(b"HYPLMBEV"), None);
+    }
+}
@@ -1,8 +1,16 @@
 //! Instructions for the program.
 
-use hyperlane_sealevel_token_lib::instruction::{init_instruction as lib_init_instruction, Init};
+use account_utils::{DiscriminatorData, DiscriminatorEncode};
+use borsh::{BorshDeserialize, BorshSerialize};
+use hyperlane_sealevel_token_lib::{
+    hyperlane_token_pda_seeds,
+    instruction::{init_instruction as lib_init_instruction, Init},
+};
 
-use crate::{hyperlane_token_ata_payer_pda_seeds, hyperlane_token_escrow_pda_seeds};
+use crate::{
+    hyperlane_token_ata_payer_pda_seeds, hyperlane_token_depositor_allowlist_pda_seeds,
+    hyperlane_token_escrow_pda_seeds,
+};
 
 use solana_program::{
     instruction::{AccountMeta, Instruction as SolanaInstruction},
@@ -12,6 +20,26 @@ use solana_program::{
     sysvar::SysvarId,
 };
 
+/// First 8 bytes of `hash::hashv(&[b"hyperlane-token-collateral:instruction"])`
+pub const COLLATERAL_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [178, 38, 3, 141, 159, 14, 31, 205];
+
+/// Instructions specific to the collateral token program, in addition to the
+/// instructions of the Hyperlane token library. Only programs built with the
+/// `depositor-allowlist` feature support them.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub enum CollateralInstruction {
+    /// Restrict deposits to the depositor allowlist, or lift the restriction. Only owner.
+    SetDepositorAllowlistEnabled(bool),
+    /// Add depositors to the depositor allowlist. Only owner.
+    AddDepositors(Vec<Pubkey>),
+    /// Remove depositors from the depositor allowlist. Only owner.
+    RemoveDepositors(Vec<Pubkey>),
+}
+
+impl DiscriminatorData for CollateralInstruction {
+    const DISCRIMINATOR: [u8; Self::DISCRIMINATOR_LENGTH] = COLLATERAL_INSTRUCTION_DISCRIMINATOR;
+}
+
 /// Gets an instruction to initialize the program.
 pub fn init_instruction(
     program_id: Pubkey,
@@ -45,3 +73,79 @@ pub fn init_instruction(
 
     Ok(instruction)
 }
+
+/// Gets an instruction to restrict deposits to the depositor allowlist, or
+/// to lift the restriction.
+pub fn set_depositor_allowlist_enabled_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    enabled: bool,
+) -> Result<SolanaInstruction, ProgramError> {
+    depositor_allowlist_instruction(
+        program_id,
+        owner_payer,
+        CollateralInstruction::SetDepositorAllowlistEnabled(enabled),
+    )
+}
+
+/// Gets an instruction to add depositors to the depositor allowlist.
+pub fn add_depositors_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    depositors: Vec<Pubkey>,
+) -> Result<SolanaInstruction, ProgramError> {
+    depositor_allowlist_instruction(
+        program_id,
+        owner_payer,
+        CollateralInstruction::AddDepositors(depositors),
+    )
+}
+
+/// Gets an instruction to remove depositors from the depositor allowlist.
+pub fn remove_depositors_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    depositors: Vec<Pubkey>,
+) -> Result<SolanaInstruction, ProgramError> {
+    depositor_allowlist_instruction(
+        program_id,
+        owner_payer,
+        CollateralInstruction::RemoveDepositors(depositors),
+    )
+}
+
+fn depositor_allowlist_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    ixn: CollateralInstruction,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (token_key, _token_bump) =
+        Pubkey::try_find_program_address(hyperlane_token_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    let (depositor_allowlist_key, _depositor_allowlist_bump) = Pubkey::try_find_program_address(
+        hyperlane_token_depositor_allowlist_pda_seeds!(),
+        &program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)?;
+
+    // Accounts:
+    // 0. `[executable]` The system program.
+    // 1. `[]` The token PDA account.
+    // 2. `[writeable]` The depositor allowlist PDA account.
+    // 3. `[signer]` The access control owner, who pays for the depositor allowlist account.
+    // 4. `[executable]` The SPL Noop program.
+    let accounts = vec![
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(token_key, false),
+        AccountMeta::new(depositor_allowlist_key, false),
+        AccountMeta::new(owner_payer, true),
+        AccountMeta::new_readonly(spl_noop::id(), false),
+    ];
+
+    Ok(SolanaInstruction {
+        program_id,
+        data: ixn.encode()?,
+        accounts,
+    })
+}
@@ -4,6 +4,9 @@
 #![deny(missing_docs)]
 #![deny(unsafe_code)]
 
+pub mod accounts;
+pub mod error;
+pub mod events;
 pub mod instruction;
 pub mod plugin;
 pub mod processor;
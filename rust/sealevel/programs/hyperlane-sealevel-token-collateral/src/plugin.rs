@@ -22,6 +22,8 @@ use spl_associated_token_account::{
 };
 use spl_token_2022::instruction::{get_account_data_size, initialize_account, transfer_checked};
 
+use crate::{accounts::DepositorAllowlist, error::Error};
+
 /// Seeds relating to the PDA account that acts both as the mint
 /// *and* the mint authority.
 #[macro_export]
@@ -48,6 +50,23 @@ macro_rules! hyperlane_token_ata_payer_pda_seeds {
     }};
 }
 
+/// Seeds relating to the PDA account that holds the depositor allowlist.
+#[macro_export]
+macro_rules! hyperlane_token_depositor_allowlist_pda_seeds {
+    () => {{
+        &[b"hyperlane_token", b"-", b"depositor_allowlist"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[
+            b"hyperlane_token",
+            b"-",
+            b"depositor_allowlist",
+            &[$bump_seed],
+        ]
+    }};
+}
+
 /// A plugin for the Hyperlane token program that escrows SPL
 /// tokens when transferring out to a remote chain, and pays them
 /// out when transferring in from a remote chain.
@@ -237,8 +256,10 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
     /// 1. `[writeable]` The mint.
     /// 2. `[writeable]` The token sender's associated token account, from which tokens will be sent.
     /// 3. `[writeable]` The escrow PDA account.
+    /// 4. `[]` The depositor allowlist PDA account, only if the program is
+    ///    built with the `depositor-allowlist` feature.
     fn transfer_in<'a, 'b>(
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
         sender_wallet_account_info: &'a AccountInfo<'b>,
         accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
@@ -279,6 +300,22 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             return Err(ProgramError::IncorrectProgramId);
         }
 
+        // Account 4: The depositor allowlist PDA account.
+        // Only programs built for restricted warp routes take it, so that the
+        // accounts of existing routes don't change. Deposits are unrestricted
+        // if the owner never created the allowlist.
+        if cfg!(feature = "depositor-allowlist") {
+            let depositor_allowlist_account_info = next_account_info(accounts_iter)?;
+            if let Some(depositor_allowlist) = DepositorAllowlist::verify_account_and_fetch(
+                program_id,
+                depositor_allowlist_account_info,
+            )? {
+                if !depositor_allowlist.is_allowed(sender_wallet_account_info.key) {
+                    return Err(Error::DepositorNotAllowed.into());
+                }
+            }
+        }
+
         let transfer_instruction = transfer_checked(
             spl_token_account_info.key,
             sender_ata_account_info.key,
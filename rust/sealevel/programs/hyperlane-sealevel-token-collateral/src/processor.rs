@@ -1,6 +1,7 @@
 //! Program processor.
 
-use account_utils::DiscriminatorDecode;
+use access_control::AccessControl;
+use account_utils::{create_pda_account, DiscriminatorDecode, SizedData};
 use hyperlane_sealevel_connection_client::{
     gas_router::GasRouterConfig, router::RemoteRouterConfig,
 };
@@ -9,12 +10,31 @@ use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
 use hyperlane_sealevel_token_lib::{
+    accounts::HyperlaneToken,
+    error::Error as TokenError,
     instruction::{Init, Instruction as TokenIxn, TransferRemote},
     processor::HyperlaneSealevelToken,
 };
-use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
 
-use crate::plugin::CollateralPlugin;
+use crate::{
+    accounts::{DepositorAllowlist, DepositorAllowlistAccount},
+    error::Error,
+    events::CollateralEvent,
+    hyperlane_token_depositor_allowlist_pda_seeds,
+    instruction::CollateralInstruction,
+    plugin::CollateralPlugin,
+};
 
 #[cfg(not(feature = "no-entrypoint"))]
 solana_program::entrypoint!(process_instruction);
@@ -59,6 +79,25 @@ pub fn process_instruction(
         };
     }
 
+    // Then, check if the instruction is specific to this program.
+    if let Ok(collateral_instruction) = CollateralInstruction::decode(instruction_data) {
+        return match collateral_instruction {
+            CollateralInstruction::SetDepositorAllowlistEnabled(enabled) => {
+                set_depositor_allowlist_enabled(program_id, accounts, enabled)
+            }
+            CollateralInstruction::AddDepositors(depositors) => {
+                add_depositors(program_id, accounts, depositors)
+            }
+            CollateralInstruction::RemoveDepositors(depositors) => {
+                remove_depositors(program_id, accounts, depositors)
+            }
+        }
+        .map_err(|err| {
+            msg!("{}", err);
+            err
+        });
+    }
+
     // Otherwise, try decoding a "normal" token instruction
     match TokenIxn::decode(instruction_data)? {
         TokenIxn::Init(init) => initialize(program_id, accounts, init),
@@ -127,6 +166,8 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
 /// 15.  `[writeable]` The mint.
 /// 16.  `[writeable]` The token sender's associated token account, from which tokens will be sent.
 /// 17.  `[writeable]` The escrow PDA account.
+/// 18.  `[]` The depositor allowlist PDA account, only if the program is
+///      built with the `depositor-allowlist` feature.
 fn transfer_remote(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -270,3 +311,190 @@ fn set_interchain_gas_paymaster(
         program_id, accounts, new_igp,
     )
 }
+
+/// Lets the owner restrict deposits to the depositor allowlist, or lift the
+/// restriction. Creates the depositor allowlist account if necessary.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[]` The token PDA account.
+/// 2. `[writeable]` The depositor allowlist PDA account.
+/// 3. `[signer]` The access control owner, who pays for the depositor allowlist account.
+/// 4. `[executable]` The SPL Noop program.
+fn set_depositor_allowlist_enabled(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    update_depositor_allowlist(program_id, accounts, |depositor_allowlist| {
+        if depositor_allowlist.enabled == enabled {
+            return vec![];
+        }
+        depositor_allowlist.enabled = enabled;
+        vec![CollateralEvent::DepositorAllowlistEnabledSet { enabled }]
+    })
+}
+
+/// Lets the owner add depositors to the depositor allowlist. Creates the
+/// depositor allowlist account if necessary.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[]` The token PDA account.
+/// 2. `[writeable]` The depositor allowlist PDA account.
+/// 3. `[signer]` The access control owner, who pays for the depositor allowlist account.
+/// 4. `[executable]` The SPL Noop program.
+fn add_depositors(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    depositors: Vec<Pubkey>,
+) -> ProgramResult {
+    update_depositor_allowlist(program_id, accounts, |depositor_allowlist| {
+        let mut events = vec![];
+        for depositor in depositors {
+            if !depositor_allowlist.depositors.contains(&depositor) {
+                depositor_allowlist.depositors.push(depositor);
+                events.push(CollateralEvent::DepositorAdded { depositor });
+            }
+        }
+        events
+    })
+}
+
+/// Lets the owner remove depositors from the depositor allowlist.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[]` The token PDA account.
+/// 2. `[writeable]` The depositor allowlist PDA account.
+/// 3. `[signer]` The access control owner, who pays for the depositor allowlist account.
+/// 4. `[executable]` The SPL Noop program.
+fn remove_depositors(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    depositors: Vec<Pubkey>,
+) -> ProgramResult {
+    update_depositor_allowlist(program_id, accounts, |depositor_allowlist| {
+        let mut events = vec![];
+        for depositor in depositors {
+            if let Some(index) = depositor_allowlist
+                .depositors
+                .iter()
+                .position(|allowed| allowed == &depositor)
+            {
+                depositor_allowlist.depositors.remove(index);
+                events.push(CollateralEvent::DepositorRemoved { depositor });
+            }
+        }
+        events
+    })
+}
+
+/// Applies `update` to the depositor allowlist, creating the depositor
+/// allowlist account if necessary, and logs the events it returns.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[]` The token PDA account.
+/// 2. `[writeable]` The depositor allowlist PDA account.
+/// 3. `[signer]` The access control owner, who pays for the depositor allowlist account.
+/// 4. `[executable]` The SPL Noop program.
+fn update_depositor_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    update: impl FnOnce(&mut DepositorAllowlist) -> Vec<CollateralEvent>,
+) -> ProgramResult {
+    // An allowlist that transfers don't check would only mislead the owner
+    if !cfg!(feature = "depositor-allowlist") {
+        return Err(Error::DepositorAllowlistNotSupported.into());
+    }
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: System program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Token account.
+    let token_info = next_account_info(accounts_iter)?;
+    let token =
+        HyperlaneToken::<CollateralPlugin>::verify_account_and_fetch_inner(program_id, token_info)?;
+
+    // Account 2: Depositor allowlist account.
+    let depositor_allowlist_info = next_account_info(accounts_iter)?;
+    let depositor_allowlist =
+        DepositorAllowlist::verify_account_and_fetch(program_id, depositor_allowlist_info)?;
+
+    // Account 3: Owner.
+    let owner_info = next_account_info(accounts_iter)?;
+    token.ensure_owner_signer(owner_info)?;
+
+    // Account 4: SPL Noop program.
+    let spl_noop_info = next_account_info(accounts_iter)?;
+    if spl_noop_info.key != &spl_noop::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(TokenError::ExtraneousAccount));
+    }
+
+    let rent = Rent::get()?;
+
+    let mut depositor_allowlist = match depositor_allowlist {
+        Some(depositor_allowlist) => depositor_allowlist,
+        None => {
+            let (_depositor_allowlist_key, depositor_allowlist_bump) = Pubkey::find_program_address(
+                hyperlane_token_depositor_allowlist_pda_seeds!(),
+                program_id,
+            );
+            let depositor_allowlist = DepositorAllowlistAccount::from(DepositorAllowlist {
+                bump: depositor_allowlist_bump,
+                ..Default::default()
+            });
+            create_pda_account(
+                owner_info,
+                &rent,
+                depositor_allowlist.size(),
+                program_id,
+                system_program_info,
+                depositor_allowlist_info,
+                hyperlane_token_depositor_allowlist_pda_seeds!(depositor_allowlist_bump),
+            )?;
+            depositor_allowlist.into_inner()
+        }
+    };
+
+    let events = update(&mut depositor_allowlist);
+
+    // Store the updated allowlist and realloc if necessary.
+    DepositorAllowlistAccount::from(depositor_allowlist).store_with_rent_exempt_realloc(
+        depositor_allowlist_info,
+        &rent,
+        owner_info,
+        system_program_info,
+    )?;
+
+    for event in events {
+        log_event(event)?;
+    }
+
+    Ok(())
+}
+
+/// Logs an event, and also logs it with a CPI to the SPL Noop program so
+/// indexers can decode it from the inner instructions.
+fn log_event(event: CollateralEvent) -> ProgramResult {
+    msg!("Hyperlane token collateral event: {:?}", event);
+
+    let noop_cpi_log = Instruction {
+        program_id: spl_noop::id(),
+        accounts: vec![],
+        data: event.to_noop_data()?,
+    };
+    invoke(&noop_cpi_log, &[])?;
+
+    Ok(())
+}
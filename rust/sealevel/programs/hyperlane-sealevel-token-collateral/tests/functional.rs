@@ -30,9 +30,16 @@ use hyperlane_sealevel_mailbox::{
 use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
+#[cfg(feature = "depositor-allowlist")]
 use hyperlane_sealevel_token_collateral::{
-    hyperlane_token_ata_payer_pda_seeds, hyperlane_token_escrow_pda_seeds,
-    plugin::CollateralPlugin, processor::process_instruction,
+    accounts::{DepositorAllowlist, DepositorAllowlistAccount},
+    instruction::{add_depositors_instruction, remove_depositors_instruction},
+};
+use hyperlane_sealevel_token_collateral::{
+    error::Error as CollateralError, hyperlane_token_ata_payer_pda_seeds,
+    hyperlane_token_depositor_allowlist_pda_seeds, hyperlane_token_escrow_pda_seeds,
+    instruction::set_depositor_allowlist_enabled_instruction, plugin::CollateralPlugin,
+    processor::process_instruction,
 };
 use hyperlane_sealevel_token_lib::{
     accounts::{convert_decimals, HyperlaneToken, HyperlaneTokenAccount},
@@ -253,6 +260,7 @@ struct HyperlaneTokenAccounts {
     escrow_bump: u8,
     ata_payer: Pubkey,
     ata_payer_bump: u8,
+    depositor_allowlist: Pubkey,
 }
 
 async fn initialize_hyperlane_token(
@@ -281,6 +289,9 @@ async fn initialize_hyperlane_token(
     let (ata_payer_account_key, ata_payer_account_bump_seed) =
         Pubkey::find_program_address(hyperlane_token_ata_payer_pda_seeds!(), program_id);
 
+    let (depositor_allowlist_key, _depositor_allowlist_bump) =
+        Pubkey::find_program_address(hyperlane_token_depositor_allowlist_pda_seeds!(), program_id);
+
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
     let transaction = Transaction::new_signed_with_payer(
         &[Instruction::new_with_bytes(
@@ -347,6 +358,7 @@ async fn initialize_hyperlane_token(
         escrow_bump: escrow_account_bump_seed,
         ata_payer: ata_payer_account_key,
         ata_payer_bump: ata_payer_account_bump_seed,
+        depositor_allowlist: depositor_allowlist_key,
     })
 }
 
@@ -644,6 +656,55 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
     let remote_transfer_amount =
         convert_decimals(transfer_amount.into(), LOCAL_DECIMALS, REMOTE_DECIMALS).unwrap();
 
+    // 0.  `[executable]` The system program.
+    // 1.  `[executable]` The spl_noop program.
+    // 2.  `[]` The token PDA account.
+    // 3.  `[executable]` The mailbox program.
+    // 4.  `[writeable]` The mailbox outbox account.
+    // 5.  `[]` Message dispatch authority.
+    // 6.  `[signer]` The token sender and mailbox payer.
+    // 7.  `[signer]` Unique message account.
+    // 8.  `[writeable]` Message storage PDA.
+    //     ---- If using an IGP ----
+    // 9.  `[executable]` The IGP program.
+    // 10. `[writeable]` The IGP program data.
+    // 11. `[writeable]` Gas payment PDA.
+    // 12. `[]` OPTIONAL - The Overhead IGP program, if the configured IGP is an Overhead IGP.
+    // 13. `[writeable]` The IGP account.
+    //      ---- End if ----
+    // 14. `[executable]` The spl_token_2022 program.
+    // 15. `[writeable]` The mint.
+    // 16. `[writeable]` The token sender's associated token account, from which tokens will be sent.
+    // 17. `[writeable]` The escrow PDA account.
+    // 18. `[]` The depositor allowlist PDA account, if the program is built with
+    //     the `depositor-allowlist` feature.
+    let mut accounts = vec![
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(spl_noop::id(), false),
+        AccountMeta::new_readonly(hyperlane_token_accounts.token, false),
+        AccountMeta::new_readonly(mailbox_accounts.program, false),
+        AccountMeta::new(mailbox_accounts.outbox, false),
+        AccountMeta::new_readonly(hyperlane_token_accounts.dispatch_authority, false),
+        AccountMeta::new_readonly(token_sender_pubkey, true),
+        AccountMeta::new_readonly(unique_message_account_keypair.pubkey(), true),
+        AccountMeta::new(dispatched_message_key, false),
+        AccountMeta::new_readonly(igp_accounts.program, false),
+        AccountMeta::new(igp_accounts.program_data, false),
+        AccountMeta::new(gas_payment_pda_key, false),
+        AccountMeta::new_readonly(igp_accounts.overhead_igp, false),
+        AccountMeta::new(igp_accounts.igp, false),
+        AccountMeta::new_readonly(spl_token_2022::id(), false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new(token_sender_ata, false),
+        AccountMeta::new(hyperlane_token_accounts.escrow, false),
+    ];
+    if cfg!(feature = "depositor-allowlist") {
+        accounts.push(AccountMeta::new_readonly(
+            hyperlane_token_accounts.depositor_allowlist,
+            false,
+        ));
+    }
+
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
     let transaction = Transaction::new_signed_with_payer(
         &[Instruction::new_with_bytes(
@@ -655,46 +716,7 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
             })
             .encode()
             .unwrap(),
-            // 0.  `[executable]` The system program.
-            // 1.  `[executable]` The spl_noop program.
-            // 2.  `[]` The token PDA account.
-            // 3.  `[executable]` The mailbox program.
-            // 4.  `[writeable]` The mailbox outbox account.
-            // 5.  `[]` Message dispatch authority.
-            // 6.  `[signer]` The token sender and mailbox payer.
-            // 7.  `[signer]` Unique message account.
-            // 8.  `[writeable]` Message storage PDA.
-            //     ---- If using an IGP ----
-            // 9.  `[executable]` The IGP program.
-            // 10. `[writeable]` The IGP program data.
-            // 11. `[writeable]` Gas payment PDA.
-            // 12. `[]` OPTIONAL - The Overhead IGP program, if the configured IGP is an Overhead IGP.
-            // 13. `[writeable]` The IGP account.
-            //      ---- End if ----
-            // 14. `[executable]` The spl_token_2022 program.
-            // 15. `[writeable]` The mint.
-            // 16. `[writeable]` The token sender's associated token account, from which tokens will be sent.
-            // 17. `[writeable]` The escrow PDA account.
-            vec![
-                AccountMeta::new_readonly(solana_program::system_program::id(), false),
-                AccountMeta::new_readonly(spl_noop::id(), false),
-                AccountMeta::new_readonly(hyperlane_token_accounts.token, false),
-                AccountMeta::new_readonly(mailbox_accounts.program, false),
-                AccountMeta::new(mailbox_accounts.outbox, false),
-                AccountMeta::new_readonly(hyperlane_token_accounts.dispatch_authority, false),
-                AccountMeta::new_readonly(token_sender_pubkey, true),
-                AccountMeta::new_readonly(unique_message_account_keypair.pubkey(), true),
-                AccountMeta::new(dispatched_message_key, false),
-                AccountMeta::new_readonly(igp_accounts.program, false),
-                AccountMeta::new(igp_accounts.program_data, false),
-                AccountMeta::new(gas_payment_pda_key, false),
-                AccountMeta::new_readonly(igp_accounts.overhead_igp, false),
-                AccountMeta::new(igp_accounts.igp, false),
-                AccountMeta::new_readonly(spl_token_2022::id(), false),
-                AccountMeta::new(mint, false),
-                AccountMeta::new(token_sender_ata, false),
-                AccountMeta::new(hyperlane_token_accounts.escrow, false),
-            ],
+            accounts,
         )],
         Some(&token_sender_pubkey),
         &[&token_sender, &unique_message_account_keypair],
@@ -1799,3 +1821,376 @@ async fn test_set_interchain_gas_paymaster_errors_if_owner_not_signer() {
         TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
     );
 }
+
+/// Transfers tokens to the remote router with a token that has no IGP.
+#[cfg(feature = "depositor-allowlist")]
+async fn transfer_remote_without_igp(
+    banks_client: &mut BanksClient,
+    program_id: &Pubkey,
+    mailbox_outbox: &Pubkey,
+    hyperlane_token_accounts: &HyperlaneTokenAccounts,
+    mint: &Pubkey,
+    token_sender: &Keypair,
+    amount: u64,
+) -> Result<(), BanksClientError> {
+    let token_sender_pubkey = token_sender.pubkey();
+    let token_sender_ata =
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            &token_sender_pubkey,
+            mint,
+            &spl_token_2022::id(),
+        );
+    let unique_message_account_keypair = Keypair::new();
+    let (dispatched_message_key, _dispatched_message_bump) = Pubkey::find_program_address(
+        mailbox_dispatched_message_pda_seeds!(&unique_message_account_keypair.pubkey()),
+        &mailbox_id(),
+    );
+
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(
+            *program_id,
+            &HyperlaneTokenInstruction::TransferRemote(TransferRemote {
+                destination_domain: REMOTE_DOMAIN,
+                recipient: H256::random(),
+                amount_or_id: amount.into(),
+            })
+            .encode()
+            .unwrap(),
+            vec![
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(spl_noop::id(), false),
+                AccountMeta::new_readonly(hyperlane_token_accounts.token, false),
+                AccountMeta::new_readonly(mailbox_id(), false),
+                AccountMeta::new(*mailbox_outbox, false),
+                AccountMeta::new_readonly(hyperlane_token_accounts.dispatch_authority, false),
+                AccountMeta::new_readonly(token_sender_pubkey, true),
+                AccountMeta::new_readonly(unique_message_account_keypair.pubkey(), true),
+                AccountMeta::new(dispatched_message_key, false),
+                AccountMeta::new_readonly(spl_token_2022::id(), false),
+                AccountMeta::new(*mint, false),
+                AccountMeta::new(token_sender_ata, false),
+                AccountMeta::new(hyperlane_token_accounts.escrow, false),
+                AccountMeta::new_readonly(hyperlane_token_accounts.depositor_allowlist, false),
+            ],
+        )],
+        Some(&token_sender_pubkey),
+        &[token_sender, &unique_message_account_keypair],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await
+}
+
+async fn process_as_owner(
+    banks_client: &mut BanksClient,
+    owner: &Keypair,
+    instruction: Instruction,
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&owner.pubkey()),
+        &[owner],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await
+}
+
+#[cfg(feature = "depositor-allowlist")]
+async fn fetch_depositor_allowlist(
+    banks_client: &mut BanksClient,
+    depositor_allowlist: &Pubkey,
+) -> DepositorAllowlist {
+    let depositor_allowlist_data = banks_client
+        .get_account(*depositor_allowlist)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    *DepositorAllowlistAccount::fetch(&mut &depositor_allowlist_data[..])
+        .unwrap()
+        .into_inner()
+}
+
+#[cfg(feature = "depositor-allowlist")]
+#[tokio::test]
+async fn test_depositor_allowlist() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let spl_token_program_id = spl_token_2022::id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &mailbox_id(),
+        &payer,
+        LOCAL_DOMAIN,
+        ONE_SOL_IN_LAMPORTS,
+        ProtocolFee::default(),
+    )
+    .await
+    .unwrap();
+
+    let (mint, mint_authority) = initialize_mint(
+        &mut banks_client,
+        &payer,
+        LOCAL_DECIMALS,
+        &spl_token_program_id,
+    )
+    .await;
+
+    let hyperlane_token_accounts = initialize_hyperlane_token(
+        &program_id,
+        &mut banks_client,
+        &payer,
+        None,
+        &mint,
+        &spl_token_program_id,
+    )
+    .await
+    .unwrap();
+
+    enroll_remote_router(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        &hyperlane_token_accounts.token,
+        REMOTE_DOMAIN,
+        H256::random(),
+    )
+    .await
+    .unwrap();
+
+    let mut depositors = vec![];
+    for _ in 0..2 {
+        let depositor = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+        create_and_mint_to_ata(
+            &mut banks_client,
+            &spl_token_program_id,
+            &mint,
+            &mint_authority,
+            &payer,
+            &depositor.pubkey(),
+            100 * 10u64.pow(LOCAL_DECIMALS_U32),
+        )
+        .await;
+        depositors.push(depositor);
+    }
+    let (allowed, other) = (&depositors[0], &depositors[1]);
+    let amount = 10u64.pow(LOCAL_DECIMALS_U32);
+
+    // Deposits are unrestricted before the allowlist is created
+    transfer_remote_without_igp(
+        &mut banks_client,
+        &program_id,
+        &mailbox_accounts.outbox,
+        &hyperlane_token_accounts,
+        &mint,
+        other,
+        amount,
+    )
+    .await
+    .unwrap();
+
+    process_as_owner(
+        &mut banks_client,
+        &payer,
+        add_depositors_instruction(program_id, payer.pubkey(), vec![allowed.pubkey()]).unwrap(),
+    )
+    .await
+    .unwrap();
+    process_as_owner(
+        &mut banks_client,
+        &payer,
+        set_depositor_allowlist_enabled_instruction(program_id, payer.pubkey(), true).unwrap(),
+    )
+    .await
+    .unwrap();
+
+    let depositor_allowlist = fetch_depositor_allowlist(
+        &mut banks_client,
+        &hyperlane_token_accounts.depositor_allowlist,
+    )
+    .await;
+    assert!(depositor_allowlist.enabled);
+    assert_eq!(depositor_allowlist.depositors, vec![allowed.pubkey()]);
+
+    // Only allowed depositors can deposit once the allowlist is enabled
+    let result = transfer_remote_without_igp(
+        &mut banks_client,
+        &program_id,
+        &mailbox_accounts.outbox,
+        &hyperlane_token_accounts,
+        &mint,
+        other,
+        amount,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CollateralError::DepositorNotAllowed as u32),
+        ),
+    );
+    transfer_remote_without_igp(
+        &mut banks_client,
+        &program_id,
+        &mailbox_accounts.outbox,
+        &hyperlane_token_accounts,
+        &mint,
+        allowed,
+        amount,
+    )
+    .await
+    .unwrap();
+
+    // Removed depositors can't deposit anymore
+    process_as_owner(
+        &mut banks_client,
+        &payer,
+        remove_depositors_instruction(program_id, payer.pubkey(), vec![allowed.pubkey()]).unwrap(),
+    )
+    .await
+    .unwrap();
+    let result = transfer_remote_without_igp(
+        &mut banks_client,
+        &program_id,
+        &mailbox_accounts.outbox,
+        &hyperlane_token_accounts,
+        &mint,
+        allowed,
+        amount,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CollateralError::DepositorNotAllowed as u32),
+        ),
+    );
+
+    // And disabling the allowlist lifts the restriction
+    process_as_owner(
+        &mut banks_client,
+        &payer,
+        set_depositor_allowlist_enabled_instruction(program_id, payer.pubkey(), false).unwrap(),
+    )
+    .await
+    .unwrap();
+    transfer_remote_without_igp(
+        &mut banks_client,
+        &program_id,
+        &mailbox_accounts.outbox,
+        &hyperlane_token_accounts,
+        &mint,
+        other,
+        amount,
+    )
+    .await
+    .unwrap();
+
+    // The escrow holds the deposits that went through
+    assert_token_balance(
+        &mut banks_client,
+        &hyperlane_token_accounts.escrow,
+        3 * amount,
+    )
+    .await;
+}
+
+#[cfg(feature = "depositor-allowlist")]
+#[tokio::test]
+async fn test_depositor_allowlist_errors_if_owner_not_signer() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let spl_token_program_id = spl_token_2022::id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (mint, _mint_authority) = initialize_mint(
+        &mut banks_client,
+        &payer,
+        LOCAL_DECIMALS,
+        &spl_token_program_id,
+    )
+    .await;
+
+    initialize_hyperlane_token(
+        &program_id,
+        &mut banks_client,
+        &payer,
+        None,
+        &mint,
+        &spl_token_program_id,
+    )
+    .await
+    .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+
+    let result = process_as_owner(
+        &mut banks_client,
+        &non_owner,
+        add_depositors_instruction(program_id, non_owner.pubkey(), vec![non_owner.pubkey()])
+            .unwrap(),
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+
+    let result = process_as_owner(
+        &mut banks_client,
+        &non_owner,
+        set_depositor_allowlist_enabled_instruction(program_id, non_owner.pubkey(), true).unwrap(),
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[cfg(not(feature = "depositor-allowlist"))]
+#[tokio::test]
+async fn test_depositor_allowlist_errors_if_not_supported() {
+    let program_id = hyperlane_sealevel_token_collateral_id();
+    let spl_token_program_id = spl_token_2022::id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    let (mint, _mint_authority) = initialize_mint(
+        &mut banks_client,
+        &payer,
+        LOCAL_DECIMALS,
+        &spl_token_program_id,
+    )
+    .await;
+
+    initialize_hyperlane_token(
+        &program_id,
+        &mut banks_client,
+        &payer,
+        None,
+        &mint,
+        &spl_token_program_id,
+    )
+    .await
+    .unwrap();
+
+    let result = process_as_owner(
+        &mut banks_client,
+        &payer,
+        set_depositor_allowlist_enabled_instruction(program_id, payer.pubkey(), true).unwrap(),
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(CollateralError::DepositorAllowlistNotSupported as u32),
+        ),
+    );
+}
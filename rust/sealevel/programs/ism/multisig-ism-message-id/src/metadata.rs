@@ -87,6 +87,7 @@ impl Encode for MultisigIsmMessageIdMetadata {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::{collection::vec, prelude::*};
     use rand::Rng;
 
     // Provide a default test implementation
@@ -161,4 +162,20 @@ mod test {
         let bytes = hex::decode("000000000000000000000000149db7afd694722747035d5aec7007ccb6f8f112fb91807ccda2db543bfbd013242643553bc1238f891ae9d0abb3b8b46c5a89990000017addc429c97ca8bcd6ad86ef4461379374b0d545308a1f47db246a6c028f74d7af521dd9355afd2f2a02565a24f22ac7b7e388cbd1f2a931acc97ce689be5456851b4d22f1aece05d293e574e38edcda9f2db64f1dc5b69a89a6a5989e7aaa4f443c137e593bb794eb211de719ed0f466a0778c4d204cc275f54c0936eee918ae1651c").unwrap();
         MultisigIsmMessageIdMetadata::try_from(bytes).expect("Decoding should succeed");
     }
+
+    proptest! {
+        #[test]
+        fn test_decode_arbitrary_bytes_does_not_panic(bytes in vec(any::<u8>(), 0..512)) {
+            if let Ok(metadata) = MultisigIsmMessageIdMetadata::try_from(bytes.clone()) {
+                // Recovery ids of 27 and 28 are normalized, so only the length
+                // is preserved, but re-encoding is stable
+                let encoded = metadata.to_vec();
+                prop_assert_eq!(encoded.len(), bytes.len());
+                let reencoded = MultisigIsmMessageIdMetadata::try_from(encoded.clone())
+                    .unwrap()
+                    .to_vec();
+                prop_assert_eq!(reencoded, encoded);
+            }
+        }
+    }
 }
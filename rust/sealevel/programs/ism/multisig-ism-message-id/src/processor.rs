@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use hyperlane_core::{Checkpoint, CheckpointWithMessageId, Decode, HyperlaneMessage, ModuleType};
 
 use access_control::AccessControl;
@@ -21,8 +23,13 @@ use crate::{
     metadata::MultisigIsmMessageIdMetadata,
 };
 
-use hyperlane_sealevel_interchain_security_module_interface::InterchainSecurityModuleInstruction;
-use multisig_ism::{interface::MultisigIsmInstruction, multisig::MultisigIsm};
+use hyperlane_sealevel_interchain_security_module_interface::{
+    InterchainSecurityModuleInstruction, VerifyInstruction,
+};
+use multisig_ism::{
+    interface::MultisigIsmInstruction,
+    multisig::{MultisigIsm, RecoveredSigners},
+};
 
 use borsh::BorshSerialize;
 
@@ -114,6 +121,9 @@ pub fn process_instruction(
                 set_return_data(&bytes[..]);
                 Ok(())
             }
+            InterchainSecurityModuleInstruction::VerifyBatch(verify_batch_data) => {
+                verify_batch(program_id, accounts, verify_batch_data.items)
+            }
         };
     }
 
@@ -243,7 +253,65 @@ fn verify(
 
     let validators_and_threshold = validators_and_threshold(program_id, accounts, message.origin)?;
 
-    let multisig_ism = MultisigIsm::new(
+    multisig_ism(metadata, &message, validators_and_threshold)
+        .verify()
+        .map_err(|err| Into::<Error>::into(err).into())
+}
+
+/// Verifies several messages, failing if any of them fails verification.
+/// The validators and threshold of each origin domain are only fetched once,
+/// and a signature over a digest that's verified several times is only
+/// recovered once.
+///
+/// Accounts:
+/// 0..N. `[]` The PDA relating to each distinct origin domain of the messages,
+///            in the order the origin domains first appear in the batch.
+fn verify_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    items: Vec<VerifyInstruction>,
+) -> ProgramResult {
+    if items.is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let accounts_iter = &mut accounts.iter();
+    let mut validators_and_thresholds: BTreeMap<u32, ValidatorsAndThreshold> = BTreeMap::new();
+    let mut recovered_signers = RecoveredSigners::default();
+
+    for item in items {
+        let metadata = MultisigIsmMessageIdMetadata::try_from(item.metadata)?;
+        let message = HyperlaneMessage::read_from(&mut &item.message[..])
+            .map_err(|_| ProgramError::InvalidArgument)?;
+
+        if !validators_and_thresholds.contains_key(&message.origin) {
+            // Account N: The PDA relating to the message's origin domain.
+            let domain_pda_account = next_account_info(accounts_iter)?;
+            let domain_validators_and_threshold = validators_and_threshold(
+                program_id,
+                std::slice::from_ref(domain_pda_account),
+                message.origin,
+            )?;
+            validators_and_thresholds.insert(message.origin, domain_validators_and_threshold);
+        }
+        let validators_and_threshold = validators_and_thresholds[&message.origin].clone();
+
+        multisig_ism(metadata, &message, validators_and_threshold)
+            .verify_with_recovered_signers(&mut recovered_signers)
+            .map_err(Into::<Error>::into)?;
+    }
+
+    Ok(())
+}
+
+/// The multisig ISM verifying the message with the metadata against the
+/// validators and threshold of its origin domain.
+fn multisig_ism(
+    metadata: MultisigIsmMessageIdMetadata,
+    message: &HyperlaneMessage,
+    validators_and_threshold: ValidatorsAndThreshold,
+) -> MultisigIsm<CheckpointWithMessageId> {
+    MultisigIsm::new(
         CheckpointWithMessageId {
             checkpoint: Checkpoint {
                 merkle_tree_hook_address: metadata.origin_merkle_tree_hook,
@@ -256,11 +324,7 @@ fn verify(
         metadata.validator_signatures,
         validators_and_threshold.validators,
         validators_and_threshold.threshold,
-    );
-
-    multisig_ism
-        .verify()
-        .map_err(|err| Into::<Error>::into(err).into())
+    )
 }
 
 /// Gets the list of AccountMetas required by the `Verify` instruction.
@@ -539,7 +603,7 @@ pub mod test {
     use ecdsa_signature::EcdsaSignature;
     use hyperlane_core::{Encode, HyperlaneMessage, H160};
     use hyperlane_sealevel_interchain_security_module_interface::{
-        InterchainSecurityModuleInstruction, VerifyInstruction,
+        InterchainSecurityModuleInstruction, VerifyBatchInstruction, VerifyInstruction,
     };
     use multisig_ism::test_data::{get_multisig_ism_test_data, MultisigIsmTestData};
     use solana_program::stake_history::Epoch;
@@ -702,6 +766,129 @@ pub mod test {
         assert_eq!(result.unwrap_err(), Error::ThresholdNotMet.into());
     }
 
+    #[test]
+    fn test_verify_batch() {
+        let program_id = id();
+
+        let (domain_pda_key, domain_pda_bump_seed) =
+            Pubkey::find_program_address(domain_data_pda_seeds!(ORIGIN_DOMAIN), &program_id);
+
+        let MultisigIsmTestData {
+            message,
+            checkpoint,
+            validators,
+            signatures,
+        } = get_multisig_ism_test_data();
+
+        let mut domain_account_lamports = 0;
+        let mut domain_account_data = vec![0_u8; 2048];
+        let domain_pda_account = AccountInfo::new(
+            &domain_pda_key,
+            false,
+            true,
+            &mut domain_account_lamports,
+            &mut domain_account_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+        let init_domain_data = DomainData {
+            bump_seed: domain_pda_bump_seed,
+            validators_and_threshold: ValidatorsAndThreshold {
+                validators,
+                threshold: 2,
+            },
+        };
+        DomainDataAccount::from(init_domain_data)
+            .store(&domain_pda_account, false)
+            .unwrap();
+
+        let verify_instruction =
+            |signature_indices: [usize; 2], message: &HyperlaneMessage| VerifyInstruction {
+                metadata: MultisigIsmMessageIdMetadata {
+                    origin_merkle_tree_hook: checkpoint.merkle_tree_hook_address,
+                    merkle_root: checkpoint.root,
+                    merkle_index: checkpoint.index,
+                    validator_signatures: signature_indices
+                        .iter()
+                        .map(|i| EcdsaSignature::from_bytes(&signatures[*i]).unwrap())
+                        .collect(),
+                }
+                .to_vec(),
+                message: message.to_vec(),
+            };
+
+        // Messages from the same origin share its PDA, which is only passed once.
+        // Expect no error.
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account.clone()],
+            InterchainSecurityModuleInstruction::VerifyBatch(VerifyBatchInstruction::new(vec![
+                verify_instruction([0, 1], &message),
+                verify_instruction([0, 2], &message),
+            ]))
+            .encode()
+            .unwrap()
+            .as_slice(),
+        );
+        assert!(result.is_ok());
+
+        // The second message has a different nonce & therefore ID.
+        // Expect the whole batch to fail.
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account.clone()],
+            InterchainSecurityModuleInstruction::VerifyBatch(VerifyBatchInstruction::new(vec![
+                verify_instruction([0, 1], &message),
+                verify_instruction(
+                    [0, 1],
+                    &HyperlaneMessage {
+                        nonce: 420,
+                        ..message.clone()
+                    },
+                ),
+            ]))
+            .encode()
+            .unwrap()
+            .as_slice(),
+        );
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), Error::ThresholdNotMet.into());
+
+        // A message from another origin, whose PDA isn't passed.
+        // Expect an error.
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account.clone()],
+            InterchainSecurityModuleInstruction::VerifyBatch(VerifyBatchInstruction::new(vec![
+                verify_instruction([0, 1], &message),
+                verify_instruction(
+                    [0, 1],
+                    &HyperlaneMessage {
+                        origin: ORIGIN_DOMAIN + 1,
+                        ..message.clone()
+                    },
+                ),
+            ]))
+            .encode()
+            .unwrap()
+            .as_slice(),
+        );
+        assert_eq!(result.unwrap_err(), ProgramError::NotEnoughAccountKeys);
+
+        // An empty batch.
+        // Expect an error.
+        let result = process_instruction(
+            &program_id,
+            &[domain_pda_account],
+            InterchainSecurityModuleInstruction::VerifyBatch(VerifyBatchInstruction::new(vec![]))
+                .encode()
+                .unwrap()
+                .as_slice(),
+        );
+        assert_eq!(result.unwrap_err(), ProgramError::InvalidArgument);
+    }
+
     #[test]
     fn test_transfer_ownership() {
         let program_id = id();
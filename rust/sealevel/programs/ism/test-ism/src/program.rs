@@ -75,7 +75,8 @@ pub fn process_instruction(
 ) -> ProgramResult {
     if let Ok(ism_instruction) = InterchainSecurityModuleInstruction::decode(instruction_data) {
         return match ism_instruction {
-            InterchainSecurityModuleInstruction::Verify(_) => verify(program_id, accounts),
+            InterchainSecurityModuleInstruction::Verify(_)
+            | InterchainSecurityModuleInstruction::VerifyBatch(_) => verify(program_id, accounts),
             InterchainSecurityModuleInstruction::VerifyAccountMetas(_) => {
                 verify_account_metas(program_id, accounts)
             }
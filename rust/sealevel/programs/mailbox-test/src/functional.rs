@@ -1,14 +1,22 @@
-use std::thread::sleep;
+use std::{collections::BTreeMap, thread::sleep};
 
 use borsh::BorshDeserialize;
 use hyperlane_core::{
-    accumulator::incremental::IncrementalMerkle as MerkleTree, HyperlaneMessage, H256,
+    accumulator::incremental::IncrementalMerkle as MerkleTree, Encode, HyperlaneMessage, H256,
 };
 use hyperlane_sealevel_mailbox::{
-    accounts::{Inbox, InboxAccount, Outbox},
+    accounts::{Inbox, InboxAccount, Outbox, CURRENT_ACCOUNT_VERSION},
     error::Error as MailboxError,
-    instruction::{Instruction as MailboxInstruction, OutboxDispatch},
-    mailbox_dispatched_message_pda_seeds,
+    instruction::{
+        get_delivered_instruction, get_processed_message_instruction, is_delivered_in_bitmap,
+        migrate_account_instruction, quote_dispatch_instruction, set_default_ism_instruction,
+        set_default_ism_override_instruction, transfer_ownership_instruction,
+        InboxGetRecipientIsmForOrigin, InboxProcessBatch, InboxProcessBatchItem,
+        Instruction as MailboxInstruction, MigratableAccount, OutboxDispatch, OutboxQuoteDispatch,
+        ProcessedMessageInfo, QuoteDispatchIgpAccounts,
+    },
+    mailbox_dispatched_message_pda_seeds, mailbox_process_authority_pda_seeds,
+    mailbox_processed_message_pda_seeds,
     protocol_fee::ProtocolFee,
 };
 use hyperlane_sealevel_test_ism::{program::TestIsmError, test_client::TestIsmTestClient};
@@ -17,10 +25,13 @@ use hyperlane_sealevel_test_send_receiver::{
     test_client::TestSendReceiverTestClient,
 };
 use hyperlane_test_utils::{
-    assert_transaction_error, clone_keypair, get_process_account_metas, get_recipient_ism,
-    initialize_mailbox, mailbox_id, new_funded_keypair, process, process_instruction,
-    process_with_accounts,
+    assert_transaction_error, clone_keypair, get_handle_account_metas,
+    get_ism_getter_account_metas, get_ism_verify_account_metas, get_process_account_metas,
+    get_recipient_ism, igp_program_id, initialize_igp_accounts, initialize_mailbox, mailbox_id,
+    new_funded_keypair, process, process_instruction, process_with_accounts, simulate_instruction,
+    MailboxAccounts,
 };
+use serializable_account_meta::SimulationReturnData;
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
@@ -68,6 +79,12 @@ async fn setup_client() -> (
         processor!(hyperlane_sealevel_mailbox::processor::process_instruction),
     );
 
+    program_test.add_program(
+        "hyperlane_sealevel_igp",
+        igp_program_id(),
+        processor!(hyperlane_sealevel_igp::processor::process_instruction),
+    );
+
     program_test.add_program(
         "hyperlane_sealevel_test_ism",
         hyperlane_sealevel_test_ism::id(),
@@ -132,6 +149,7 @@ async fn test_initialize() {
             tree: MerkleTree::default(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config,
+            version: CURRENT_ACCOUNT_VERSION,
         },
     )
     .await;
@@ -154,6 +172,8 @@ async fn test_initialize() {
             inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
             default_ism: hyperlane_sealevel_test_ism::id(),
             processed_count: 0,
+            version: CURRENT_ACCOUNT_VERSION,
+            default_ism_overrides: BTreeMap::new(),
         }
     );
 }
@@ -260,6 +280,7 @@ async fn test_dispatch_from_eoa() {
             tree: expected_tree.clone(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config.clone(),
+            version: CURRENT_ACCOUNT_VERSION,
         },
     )
     .await;
@@ -316,6 +337,7 @@ async fn test_dispatch_from_eoa() {
             tree: expected_tree.clone(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config,
+            version: CURRENT_ACCOUNT_VERSION,
         },
     )
     .await;
@@ -419,6 +441,165 @@ async fn test_protocol_fee() {
     assert_eq!(outbox_account.lamports, rent_exempt_balance);
 }
 
+async fn quote_dispatch(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    quote: OutboxQuoteDispatch,
+    igp_accounts: Option<QuoteDispatchIgpAccounts>,
+) -> Result<u64, BanksClientError> {
+    let instruction = quote_dispatch_instruction(mailbox_id(), quote, igp_accounts).unwrap();
+
+    simulate_instruction::<SimulationReturnData<u64>>(banks_client, payer, instruction)
+        .await
+        .map(|r| r.unwrap().return_data)
+}
+
+#[tokio::test]
+async fn test_quote_dispatch_without_igp() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let message_body = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    let quote = quote_dispatch(
+        &mut banks_client,
+        &payer,
+        OutboxQuoteDispatch {
+            destination_domain: REMOTE_DOMAIN,
+            message_body: message_body.clone(),
+            gas_amount: None,
+        },
+        None,
+    )
+    .await
+    .unwrap();
+
+    let (_, _, dispatched_message_account_key) = dispatch_from_payer(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        OutboxDispatch {
+            sender: payer.pubkey(),
+            destination_domain: REMOTE_DOMAIN,
+            recipient: H256::random(),
+            message_body,
+        },
+    )
+    .await
+    .unwrap();
+
+    // The quote covers the protocol fee and the rent of the dispatched message PDA.
+    let dispatched_message_account = banks_client
+        .get_account(dispatched_message_account_key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(quote, PROTOCOL_FEE + dispatched_message_account.lamports);
+}
+
+#[tokio::test]
+async fn test_quote_dispatch_with_igp() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let igp_accounts =
+        initialize_igp_accounts(&mut banks_client, &igp_program_id(), &payer, REMOTE_DOMAIN)
+            .await
+            .unwrap();
+
+    let message_body = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let gas_amount = 100_000;
+
+    let quote_without_igp = quote_dispatch(
+        &mut banks_client,
+        &payer,
+        OutboxQuoteDispatch {
+            destination_domain: REMOTE_DOMAIN,
+            message_body: message_body.clone(),
+            gas_amount: None,
+        },
+        None,
+    )
+    .await
+    .unwrap();
+
+    let quote_with_igp = quote_dispatch(
+        &mut banks_client,
+        &payer,
+        OutboxQuoteDispatch {
+            destination_domain: REMOTE_DOMAIN,
+            message_body,
+            gas_amount: Some(gas_amount),
+        },
+        Some(QuoteDispatchIgpAccounts {
+            program_id: igp_accounts.program,
+            igp: igp_accounts.igp,
+            overhead_igp: Some(igp_accounts.overhead_igp),
+        }),
+    )
+    .await
+    .unwrap();
+
+    // The test IGP has a 1:1 exchange rate, a gas price of 1 and no gas overhead,
+    // so the IGP payment is exactly the gas amount.
+    assert_eq!(quote_with_igp, quote_without_igp + gas_amount);
+}
+
+#[tokio::test]
+async fn test_quote_dispatch_errors_if_gas_amount_without_igp_accounts() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let result = quote_dispatch(
+        &mut banks_client,
+        &payer,
+        OutboxQuoteDispatch {
+            destination_domain: REMOTE_DOMAIN,
+            message_body: vec![],
+            gas_amount: Some(100_000),
+        },
+        None,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+    );
+}
+
 #[tokio::test]
 async fn test_setting_valid_protocol_fee_config_works() {
     let program_id = mailbox_id();
@@ -468,6 +649,7 @@ async fn test_setting_valid_protocol_fee_config_works() {
             tree: MerkleTree::default(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: new_protocol_fee,
+            version: CURRENT_ACCOUNT_VERSION,
         },
     )
     .await;
@@ -629,6 +811,7 @@ async fn test_dispatch_from_program() {
             tree: expected_tree.clone(),
             max_protocol_fee: MAX_PROTOCOL_FEE,
             protocol_fee: protocol_fee_config,
+            version: CURRENT_ACCOUNT_VERSION,
         },
     )
     .await;
@@ -905,6 +1088,7 @@ async fn test_process_successful_verify_and_handle() {
         processed_message_account_key,
         &message,
         0,
+        payer.pubkey(),
     )
     .await;
 
@@ -936,6 +1120,7 @@ async fn test_process_successful_verify_and_handle() {
         processed_message_account_key,
         &message,
         1,
+        payer.pubkey(),
     )
     .await;
 }
@@ -999,6 +1184,270 @@ async fn test_process_errors_if_message_already_processed() {
     )
 }
 
+#[tokio::test]
+async fn test_process_batch_successful_verify_and_handle() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let recipient_id = hyperlane_sealevel_test_send_receiver::id();
+
+    let messages: Vec<_> = (0..2)
+        .map(|nonce| HyperlaneMessage {
+            version: 3,
+            nonce,
+            origin: REMOTE_DOMAIN,
+            sender: payer.pubkey().to_bytes().into(),
+            destination: LOCAL_DOMAIN,
+            recipient: recipient_id.to_bytes().into(),
+            body: vec![nonce as u8, 1, 2, 3],
+        })
+        .collect();
+
+    let ism = get_recipient_ism(&mut banks_client, &payer, &mailbox_accounts, recipient_id)
+        .await
+        .unwrap();
+    let ism_verify_account_metas =
+        get_ism_verify_account_metas(&mut banks_client, &payer, ism, vec![], messages[0].to_vec())
+            .await
+            .unwrap();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(mailbox_accounts.inbox, false),
+        AccountMeta::new_readonly(spl_noop::id(), false),
+        AccountMeta::new_readonly(ism, false),
+    ];
+    accounts.extend(ism_verify_account_metas.clone());
+    let mut items = vec![];
+    for message in &messages {
+        let ism_getter_account_metas =
+            get_ism_getter_account_metas(&mut banks_client, &payer, recipient_id)
+                .await
+                .unwrap();
+        let handle_account_metas = get_handle_account_metas(&mut banks_client, &payer, message)
+            .await
+            .unwrap();
+        items.push(InboxProcessBatchItem {
+            metadata: vec![],
+            message: message.to_vec(),
+            ism_getter_account_count: ism_getter_account_metas.len() as u8,
+            handle_account_count: handle_account_metas.len() as u8,
+        });
+
+        let (process_authority_key, _) = Pubkey::find_program_address(
+            mailbox_process_authority_pda_seeds!(&recipient_id),
+            &program_id,
+        );
+        let (processed_message_account_key, _) = Pubkey::find_program_address(
+            mailbox_processed_message_pda_seeds!(message.id()),
+            &program_id,
+        );
+        accounts.extend([
+            AccountMeta::new_readonly(process_authority_key, false),
+            AccountMeta::new(processed_message_account_key, false),
+        ]);
+        accounts.extend(ism_getter_account_metas);
+        accounts.push(AccountMeta::new_readonly(recipient_id, false));
+        accounts.extend(handle_account_metas);
+    }
+
+    let instruction = Instruction {
+        program_id,
+        data: MailboxInstruction::InboxProcessBatch(InboxProcessBatch {
+            items,
+            ism_verify_account_count: ism_verify_account_metas.len() as u8,
+        })
+        .into_instruction_data()
+        .unwrap(),
+        accounts,
+    };
+    let process_tx_signature =
+        process_instruction(&mut banks_client, instruction, &payer, &[&payer])
+            .await
+            .unwrap();
+
+    // Expect both messages to be processed, in order
+    for (sequence, message) in messages.iter().enumerate() {
+        let (processed_message_account_key, _) = Pubkey::find_program_address(
+            mailbox_processed_message_pda_seeds!(message.id()),
+            &program_id,
+        );
+        assert_processed_message(
+            &mut banks_client,
+            process_tx_signature,
+            processed_message_account_key,
+            message,
+            sequence as u64,
+            payer.pubkey(),
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn test_get_delivered() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: REMOTE_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: LOCAL_DOMAIN,
+        recipient: hyperlane_sealevel_test_send_receiver::id()
+            .to_bytes()
+            .into(),
+        body: vec![0, 1, 2, 3, 4, 5, 6, 7, 8],
+    };
+
+    process(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message,
+    )
+    .await
+    .unwrap();
+
+    // Put the delivered message after a full byte of undelivered ones, so the
+    // bitmap spans two bytes.
+    let mut message_ids: Vec<H256> = (0..8).map(|_| H256::random()).collect();
+    message_ids.push(message.id());
+
+    let instruction = get_delivered_instruction(program_id, message_ids).unwrap();
+    let bitmap = simulate_instruction::<SimulationReturnData<Vec<u8>>>(
+        &mut banks_client,
+        &payer,
+        instruction,
+    )
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+
+    assert_eq!(bitmap, vec![0b0000_0000, 0b0000_0001]);
+    assert!((0..8).all(|index| !is_delivered_in_bitmap(&bitmap, index)));
+    assert!(is_delivered_in_bitmap(&bitmap, 8));
+}
+
+#[tokio::test]
+async fn test_get_processed_message() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: REMOTE_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: LOCAL_DOMAIN,
+        recipient: hyperlane_sealevel_test_send_receiver::id()
+            .to_bytes()
+            .into(),
+        body: vec![0, 1, 2, 3, 4, 5, 6, 7, 8],
+    };
+
+    let (process_tx_signature, _) = process(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message,
+    )
+    .await
+    .unwrap();
+    let process_slot = banks_client
+        .get_transaction_status(process_tx_signature)
+        .await
+        .unwrap()
+        .unwrap()
+        .slot;
+
+    let instruction = get_processed_message_instruction(program_id, message.id()).unwrap();
+    let processed_message = simulate_instruction::<
+        SimulationReturnData<Option<ProcessedMessageInfo>>,
+    >(&mut banks_client, &payer, instruction)
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    assert_eq!(
+        processed_message,
+        Some(ProcessedMessageInfo {
+            sequence: 0,
+            slot: process_slot,
+            payer: Some(payer.pubkey()),
+        })
+    );
+
+    // Messages that weren't processed have no processing info
+    let instruction = get_processed_message_instruction(program_id, H256::random()).unwrap();
+    let processed_message = simulate_instruction::<
+        SimulationReturnData<Option<ProcessedMessageInfo>>,
+    >(&mut banks_client, &payer, instruction)
+    .await
+    .unwrap()
+    .unwrap()
+    .return_data;
+    assert_eq!(processed_message, None);
+}
+
+#[tokio::test]
+async fn test_get_delivered_errors_if_wrong_processed_message_account() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mut instruction = get_delivered_instruction(program_id, vec![H256::random()]).unwrap();
+    instruction.accounts[0].pubkey = Pubkey::new_unique();
+
+    let result = simulate_instruction::<SimulationReturnData<Vec<u8>>>(
+        &mut banks_client,
+        &payer,
+        instruction,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
 #[tokio::test]
 async fn test_process_errors_if_ism_verify_fails() {
     let program_id = mailbox_id();
@@ -1351,6 +1800,8 @@ async fn test_inbox_set_default_ism() {
             inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
             default_ism: new_default_ism,
             processed_count: 0,
+            version: CURRENT_ACCOUNT_VERSION,
+            default_ism_overrides: BTreeMap::new(),
         },
     )
     .await;
@@ -1420,3 +1871,397 @@ async fn test_inbox_set_default_ism_errors_if_owner_not_signer() {
         TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
     );
 }
+
+#[tokio::test]
+async fn test_inbox_set_default_ism_with_config_event_accounts() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let new_default_ism = Pubkey::new_unique();
+
+    // The instruction builder includes the accounts used to log the config change
+    let instruction =
+        set_default_ism_instruction(program_id, payer.pubkey(), new_default_ism).unwrap();
+    assert_eq!(instruction.accounts.len(), 5);
+
+    process_instruction(&mut banks_client, instruction, &payer, &[&payer])
+        .await
+        .unwrap();
+
+    assert_inbox(
+        &mut banks_client,
+        mailbox_accounts.inbox,
+        Inbox {
+            local_domain: LOCAL_DOMAIN,
+            inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
+            default_ism: new_default_ism,
+            processed_count: 0,
+            version: CURRENT_ACCOUNT_VERSION,
+            default_ism_overrides: BTreeMap::new(),
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_inbox_set_default_ism_errors_if_wrong_config_events_account() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let mut instruction =
+        set_default_ism_instruction(program_id, payer.pubkey(), Pubkey::new_unique()).unwrap();
+    // 4. `[]` - The config events PDA account.
+    instruction.accounts[4] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+
+    let result = process_instruction(&mut banks_client, instruction, &payer, &[&payer]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+async fn get_recipient_ism_for_origin(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    mailbox_accounts: &MailboxAccounts,
+    recipient_program_id: Pubkey,
+    origin: u32,
+) -> Pubkey {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(mailbox_accounts.inbox, false),
+        AccountMeta::new_readonly(recipient_program_id, false),
+    ];
+    accounts.extend(
+        get_ism_getter_account_metas(banks_client, payer, recipient_program_id)
+            .await
+            .unwrap(),
+    );
+    let instruction = Instruction::new_with_borsh(
+        mailbox_accounts.program,
+        &MailboxInstruction::InboxGetRecipientIsmForOrigin(InboxGetRecipientIsmForOrigin {
+            recipient: recipient_program_id,
+            origin,
+        }),
+        accounts,
+    );
+    simulate_instruction::<SimulationReturnData<Pubkey>>(banks_client, payer, instruction)
+        .await
+        .unwrap()
+        .unwrap()
+        .return_data
+}
+
+#[tokio::test]
+async fn test_inbox_set_default_ism_override() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, mut test_send_receiver, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let override_ism = Pubkey::new_unique();
+    let instruction = set_default_ism_override_instruction(
+        program_id,
+        payer.pubkey(),
+        REMOTE_DOMAIN,
+        Some(override_ism),
+    )
+    .unwrap();
+    process_instruction(&mut banks_client, instruction, &payer, &[&payer])
+        .await
+        .unwrap();
+
+    assert_inbox(
+        &mut banks_client,
+        mailbox_accounts.inbox,
+        Inbox {
+            local_domain: LOCAL_DOMAIN,
+            inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
+            default_ism: mailbox_accounts.default_ism,
+            processed_count: 0,
+            version: CURRENT_ACCOUNT_VERSION,
+            default_ism_overrides: BTreeMap::from([(REMOTE_DOMAIN, override_ism)]),
+        },
+    )
+    .await;
+
+    // Recipients that don't specify an ISM get the override of the origin,
+    // or else the default ISM
+    let recipient_id = test_send_receiver.id();
+    test_send_receiver
+        .set_ism(None, IsmReturnDataMode::EncodeOption)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_recipient_ism_for_origin(
+            &mut banks_client,
+            &payer,
+            &mailbox_accounts,
+            recipient_id,
+            REMOTE_DOMAIN
+        )
+        .await,
+        override_ism
+    );
+    assert_eq!(
+        get_recipient_ism_for_origin(
+            &mut banks_client,
+            &payer,
+            &mailbox_accounts,
+            recipient_id,
+            REMOTE_DOMAIN + 1
+        )
+        .await,
+        mailbox_accounts.default_ism
+    );
+
+    // Recipients that specify an ISM keep it
+    let recipient_ism = Pubkey::new_unique();
+    test_send_receiver
+        .set_ism(Some(recipient_ism), IsmReturnDataMode::EncodeOption)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_recipient_ism_for_origin(
+            &mut banks_client,
+            &payer,
+            &mailbox_accounts,
+            recipient_id,
+            REMOTE_DOMAIN
+        )
+        .await,
+        recipient_ism
+    );
+
+    // Removing the override falls back to the default ISM again
+    let instruction =
+        set_default_ism_override_instruction(program_id, payer.pubkey(), REMOTE_DOMAIN, None)
+            .unwrap();
+    process_instruction(&mut banks_client, instruction, &payer, &[&payer])
+        .await
+        .unwrap();
+    test_send_receiver
+        .set_ism(None, IsmReturnDataMode::EncodeOption)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_recipient_ism_for_origin(
+            &mut banks_client,
+            &payer,
+            &mailbox_accounts,
+            recipient_id,
+            REMOTE_DOMAIN
+        )
+        .await,
+        mailbox_accounts.default_ism
+    );
+}
+
+#[tokio::test]
+async fn test_inbox_set_default_ism_override_errors_if_owner_not_signer() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+
+    let instruction = set_default_ism_override_instruction(
+        program_id,
+        non_owner.pubkey(),
+        REMOTE_DOMAIN,
+        Some(Pubkey::new_unique()),
+    )
+    .unwrap();
+    let result =
+        process_instruction(&mut banks_client, instruction, &non_owner, &[&non_owner]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_ownership() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let protocol_fee_config = test_protocol_fee_config();
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        protocol_fee_config.clone(),
+    )
+    .await
+    .unwrap();
+
+    let new_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+
+    let instruction =
+        transfer_ownership_instruction(program_id, payer.pubkey(), Some(new_owner.pubkey()))
+            .unwrap();
+    process_instruction(&mut banks_client, instruction, &payer, &[&payer])
+        .await
+        .unwrap();
+
+    assert_outbox(
+        &mut banks_client,
+        mailbox_accounts.outbox,
+        Outbox {
+            local_domain: LOCAL_DOMAIN,
+            outbox_bump_seed: mailbox_accounts.outbox_bump_seed,
+            owner: Some(new_owner.pubkey()),
+            tree: MerkleTree::default(),
+            max_protocol_fee: MAX_PROTOCOL_FEE,
+            protocol_fee: protocol_fee_config,
+            version: CURRENT_ACCOUNT_VERSION,
+        },
+    )
+    .await;
+
+    // The previous owner can no longer transfer ownership
+    let instruction =
+        transfer_ownership_instruction(program_id, payer.pubkey(), Some(payer.pubkey())).unwrap();
+    let result = process_instruction(&mut banks_client, instruction, &payer, &[&payer]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_account_current_version_is_noop() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+    let protocol_fee_config = test_protocol_fee_config();
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        protocol_fee_config.clone(),
+    )
+    .await
+    .unwrap();
+
+    for account in [MigratableAccount::Outbox, MigratableAccount::Inbox] {
+        let instruction = migrate_account_instruction(program_id, payer.pubkey(), account).unwrap();
+        process_instruction(&mut banks_client, instruction, &payer, &[&payer])
+            .await
+            .unwrap();
+    }
+
+    assert_outbox(
+        &mut banks_client,
+        mailbox_accounts.outbox,
+        Outbox {
+            local_domain: LOCAL_DOMAIN,
+            outbox_bump_seed: mailbox_accounts.outbox_bump_seed,
+            owner: Some(payer.pubkey()),
+            tree: MerkleTree::default(),
+            max_protocol_fee: MAX_PROTOCOL_FEE,
+            protocol_fee: protocol_fee_config,
+            version: CURRENT_ACCOUNT_VERSION,
+        },
+    )
+    .await;
+    assert_inbox(
+        &mut banks_client,
+        mailbox_accounts.inbox,
+        Inbox {
+            local_domain: LOCAL_DOMAIN,
+            inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
+            default_ism: hyperlane_sealevel_test_ism::id(),
+            processed_count: 0,
+            version: CURRENT_ACCOUNT_VERSION,
+            default_ism_overrides: BTreeMap::new(),
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_migrate_account_errors_if_owner_not_signer() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+
+    // Where the signer is not the owner
+    let instruction =
+        migrate_account_instruction(program_id, non_owner.pubkey(), MigratableAccount::Inbox)
+            .unwrap();
+    let result =
+        process_instruction(&mut banks_client, instruction, &non_owner, &[&non_owner]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+
+    // Where the owner is correct but not a signer
+    let mut instruction =
+        migrate_account_instruction(program_id, payer.pubkey(), MigratableAccount::Outbox).unwrap();
+    instruction.accounts[2].is_signer = false;
+    let result =
+        process_instruction(&mut banks_client, instruction, &non_owner, &[&non_owner]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
+    );
+}
@@ -157,6 +157,7 @@ pub async fn assert_processed_message(
     processed_message_account_key: Pubkey,
     expected_message: &HyperlaneMessage,
     expected_sequence: u64,
+    expected_payer: Pubkey,
 ) {
     // Get the slot of the tx
     let process_tx_status = banks_client
@@ -178,7 +179,12 @@ pub async fn assert_processed_message(
             .into_inner();
     assert_eq!(
         *processed_message,
-        ProcessedMessage::new(expected_sequence, expected_message.id(), process_slot,),
+        ProcessedMessage::new(
+            expected_sequence,
+            expected_message.id(),
+            process_slot,
+            expected_payer,
+        ),
     );
 }
 
@@ -1,23 +1,83 @@
 //! Hyperlane Sealevel Mailbox data account layouts.
 
 use core::cell::RefMut;
-use std::io::Read;
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    ops::{Deref, DerefMut},
+};
 
 use access_control::AccessControl;
 use account_utils::{AccountData, SizedData};
+use arrayref::{array_mut_ref, array_ref};
 use borsh::{BorshDeserialize, BorshSerialize};
-use hyperlane_core::{accumulator::incremental::IncrementalMerkle as MerkleTree, H256};
+use hyperlane_core::{
+    accumulator::{incremental::IncrementalMerkle as MerkleTree, TREE_DEPTH, ZERO_HASHES},
+    H256,
+};
 use solana_program::{
-    account_info::AccountInfo, clock::Slot, program_error::ProgramError, pubkey::Pubkey,
+    account_info::AccountInfo, clock::Slot, keccak, program_error::ProgramError, pubkey::Pubkey,
 };
 
 use crate::{mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds, protocol_fee::ProtocolFee};
 
+/// The current layout version of the Inbox and Outbox accounts.
+/// Accounts created before their layouts were versioned are version 0.
+pub const CURRENT_ACCOUNT_VERSION: u8 = 2;
+
+/// The first layout version of the Inbox with default ISM overrides.
+pub const INBOX_DEFAULT_ISM_OVERRIDES_VERSION: u8 = 2;
+
+/// Account data with a versioned layout, which allows fields to be added
+/// without re-initializing the account.
+///
+/// Versioned layouts end with a version byte, after which any fields introduced
+/// in later versions are stored. Version 0 layouts have no version byte.
+pub trait VersionedData {
+    /// The layout version of the data.
+    fn version(&self) -> u8;
+
+    /// Migrates the data to `CURRENT_ACCOUNT_VERSION`, populating any fields
+    /// introduced since its version.
+    fn migrate(&mut self);
+}
+
+fn serialize_version<W: Write>(version: u8, writer: &mut W) -> std::io::Result<()> {
+    if version == 0 {
+        return Ok(());
+    }
+    version.serialize(writer)
+}
+
+fn deserialize_version(reader: &mut &[u8]) -> std::io::Result<u8> {
+    // Version 0 accounts end without a version byte.
+    let version = if reader.is_empty() {
+        0
+    } else {
+        u8::deserialize(reader)?
+    };
+    if version > CURRENT_ACCOUNT_VERSION {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "unsupported account version",
+        ));
+    }
+    Ok(version)
+}
+
+fn version_size(version: u8) -> usize {
+    if version == 0 {
+        0
+    } else {
+        1
+    }
+}
+
 /// The Inbox account.
 pub type InboxAccount = AccountData<Inbox>;
 
 /// The Inbox account data, which is used when processing messages.
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Inbox {
     /// The local domain.
     pub local_domain: u32,
@@ -27,6 +87,11 @@ pub struct Inbox {
     pub default_ism: Pubkey,
     /// The number of messages processed. Used for easy indexing of processed messages.
     pub processed_count: u64,
+    /// The layout version of the account.
+    pub version: u8,
+    /// The ISMs used instead of the default ISM for messages from specific
+    /// origin domains, when the recipient doesn't specify an ISM.
+    pub default_ism_overrides: BTreeMap<u32, Pubkey>,
 }
 
 impl SizedData for Inbox {
@@ -35,11 +100,81 @@ impl SizedData for Inbox {
         // 1 byte inbox_bump_seed
         // 32 byte default_ism
         // 8 byte processed_count
-        4 + 1 + 32 + 8
+        // 0 or 1 byte version
+        // 4 byte overrides length + 36 bytes per override (4 byte domain, 32 byte ISM),
+        // from the overrides version
+        4 + 1 + 32 + 8 + version_size(self.version) + self.default_ism_overrides_size()
+    }
+}
+
+impl BorshSerialize for Inbox {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.local_domain.serialize(writer)?;
+        self.inbox_bump_seed.serialize(writer)?;
+        self.default_ism.serialize(writer)?;
+        self.processed_count.serialize(writer)?;
+        serialize_version(self.version, writer)?;
+        if self.version >= INBOX_DEFAULT_ISM_OVERRIDES_VERSION {
+            self.default_ism_overrides.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for Inbox {
+    fn deserialize(reader: &mut &[u8]) -> std::io::Result<Self> {
+        let local_domain = u32::deserialize(reader)?;
+        let inbox_bump_seed = u8::deserialize(reader)?;
+        let default_ism = Pubkey::deserialize(reader)?;
+        let processed_count = u64::deserialize(reader)?;
+        let version = deserialize_version(reader)?;
+        let default_ism_overrides = if version >= INBOX_DEFAULT_ISM_OVERRIDES_VERSION {
+            BTreeMap::deserialize(reader)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            local_domain,
+            inbox_bump_seed,
+            default_ism,
+            processed_count,
+            version,
+            default_ism_overrides,
+        })
+    }
+}
+
+impl VersionedData for Inbox {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn migrate(&mut self) {
+        // Version 1 only introduced the version byte, and version 2 the
+        // default ISM overrides, which start empty.
+        self.version = CURRENT_ACCOUNT_VERSION;
     }
 }
 
 impl Inbox {
+    /// The ISM used for messages from `origin` whose recipient doesn't
+    /// specify an ISM: the override for the origin, if any, or else the
+    /// default ISM.
+    pub fn default_ism_for_origin(&self, origin: u32) -> Pubkey {
+        self.default_ism_overrides
+            .get(&origin)
+            .copied()
+            .unwrap_or(self.default_ism)
+    }
+
+    fn default_ism_overrides_size(&self) -> usize {
+        if self.version >= INBOX_DEFAULT_ISM_OVERRIDES_VERSION {
+            4 + self.default_ism_overrides.len() * (4 + 32)
+        } else {
+            0
+        }
+    }
+
     /// Verifies that the given account is the canonical Inbox PDA and returns the deserialized inner data.
     pub fn verify_account_and_fetch_inner(
         program_id: &Pubkey,
@@ -90,7 +225,7 @@ impl Inbox {
 pub type OutboxAccount = AccountData<Outbox>;
 
 /// The Outbox account data, which is used when dispatching messages.
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct Outbox {
     /// The local domain.
     pub local_domain: u32,
@@ -104,6 +239,8 @@ pub struct Outbox {
     pub max_protocol_fee: u64,
     /// The protocol fee configuration.
     pub protocol_fee: ProtocolFee,
+    /// The layout version of the account.
+    pub version: u8,
 }
 
 impl SizedData for Outbox {
@@ -114,7 +251,46 @@ impl SizedData for Outbox {
         // 1032 byte tree (32 * 32 = 1024 byte branch, 8 byte count)
         // 8 byte max_protocol_fee
         // 40 byte protocol_fee (8 byte fee, 32 byte beneficiary)
-        4 + 1 + 33 + 1032 + 8 + 40
+        // 0 or 1 byte version
+        4 + 1 + 33 + 1032 + 8 + 40 + version_size(self.version)
+    }
+}
+
+impl BorshSerialize for Outbox {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.local_domain.serialize(writer)?;
+        self.outbox_bump_seed.serialize(writer)?;
+        self.owner.serialize(writer)?;
+        self.tree.serialize(writer)?;
+        self.max_protocol_fee.serialize(writer)?;
+        self.protocol_fee.serialize(writer)?;
+        serialize_version(self.version, writer)
+    }
+}
+
+impl BorshDeserialize for Outbox {
+    fn deserialize(reader: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            local_domain: u32::deserialize(reader)?,
+            outbox_bump_seed: u8::deserialize(reader)?,
+            owner: Option::<Pubkey>::deserialize(reader)?,
+            tree: MerkleTree::deserialize(reader)?,
+            max_protocol_fee: u64::deserialize(reader)?,
+            protocol_fee: ProtocolFee::deserialize(reader)?,
+            version: deserialize_version(reader)?,
+        })
+    }
+}
+
+impl VersionedData for Outbox {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn migrate(&mut self) {
+        // Version 1 only introduced the version byte. Version 2 didn't change
+        // the Outbox layout.
+        self.version = CURRENT_ACCOUNT_VERSION;
     }
 }
 
@@ -137,8 +313,20 @@ impl Outbox {
     ) -> Result<Self, ProgramError> {
         let outbox =
             OutboxAccount::fetch(&mut &outbox_account_info.data.borrow()[..])?.into_inner();
+        Self::verify_account(program_id, outbox_account_info, outbox.outbox_bump_seed)?;
+
+        Ok(*outbox)
+    }
+
+    /// Verifies that the given account is the canonical Outbox PDA, given the
+    /// bump seed stored in its data.
+    pub fn verify_account(
+        program_id: &Pubkey,
+        outbox_account_info: &AccountInfo,
+        outbox_bump_seed: u8,
+    ) -> Result<(), ProgramError> {
         let expected_outbox_key = Pubkey::create_program_address(
-            mailbox_outbox_pda_seeds!(outbox.outbox_bump_seed),
+            mailbox_outbox_pda_seeds!(outbox_bump_seed),
             program_id,
         )?;
         if outbox_account_info.key != &expected_outbox_key {
@@ -147,11 +335,124 @@ impl Outbox {
         if outbox_account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
+        Ok(())
+    }
+}
 
-        Ok(*outbox)
+/// Zero-copy access to the Outbox account data, for the instructions that
+/// only read its fixed fields or update its merkle tree, e.g. dispatching.
+///
+/// Fetching the Outbox deserializes the whole merkle branch, and storing it
+/// serializes it again. This instead reads and writes the fields in place in
+/// the account data, hashing with the keccak syscall.
+///
+/// The data is laid out as the serialized `OutboxAccount`: the initialized
+/// flag, then the fields of the Outbox. Only the owner has a variable size,
+/// so the offsets of the fields after it depend on whether it's set.
+pub struct OutboxData<D> {
+    data: D,
+    /// The offset of the merkle tree in the data
+    tree_offset: usize,
+}
+
+impl<D: Deref<Target = [u8]>> OutboxData<D> {
+    // 1 byte initialized flag
+    const LOCAL_DOMAIN_OFFSET: usize = 1;
+    const OUTBOX_BUMP_SEED_OFFSET: usize = Self::LOCAL_DOMAIN_OFFSET + 4;
+    const OWNER_OFFSET: usize = Self::OUTBOX_BUMP_SEED_OFFSET + 1;
+    // 32 * 32 = 1024 byte branch, 8 byte count
+    const TREE_SIZE: usize = TREE_DEPTH * 32 + 8;
+    // 8 byte max_protocol_fee, 8 byte protocol_fee.fee, 32 byte protocol_fee.beneficiary
+    const TRAILING_FIELDS_SIZE: usize = 8 + 8 + 32;
+
+    /// Checks that the data is an initialized Outbox account.
+    pub fn new(data: D) -> Result<Self, ProgramError> {
+        if data.len() <= Self::OWNER_OFFSET || data[0] != 1 {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        let tree_offset = match data[Self::OWNER_OFFSET] {
+            0 => Self::OWNER_OFFSET + 1,
+            1 => Self::OWNER_OFFSET + 1 + 32,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        if data.len() < tree_offset + Self::TREE_SIZE + Self::TRAILING_FIELDS_SIZE {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self { data, tree_offset })
+    }
+
+    /// The local domain.
+    pub fn local_domain(&self) -> u32 {
+        u32::from_le_bytes(*array_ref![self.data, Self::LOCAL_DOMAIN_OFFSET, 4])
+    }
+
+    /// The bump seed of the outbox PDA.
+    pub fn outbox_bump_seed(&self) -> u8 {
+        self.data[Self::OUTBOX_BUMP_SEED_OFFSET]
+    }
+
+    /// The current protocol fee.
+    pub fn protocol_fee(&self) -> u64 {
+        // Skips the max_protocol_fee
+        let offset = self.tree_offset + Self::TREE_SIZE + 8;
+        u64::from_le_bytes(*array_ref![self.data, offset, 8])
+    }
+
+    /// The number of leaves in the merkle tree.
+    pub fn count(&self) -> usize {
+        u64::from_le_bytes(*array_ref![self.data, self.count_offset(), 8]) as usize
+    }
+
+    /// The root of the merkle tree, computed like `IncrementalMerkle::root`.
+    pub fn root(&self) -> H256 {
+        let mut node = H256::zero();
+        let mut size = self.count();
+        for (i, zero_hash) in ZERO_HASHES.iter().take(TREE_DEPTH).enumerate() {
+            node = if (size & 1) == 1 {
+                hash_concat(self.branch(i), node.as_bytes())
+            } else {
+                hash_concat(node.as_bytes(), zero_hash.as_bytes())
+            };
+            size /= 2;
+        }
+        node
+    }
+
+    fn branch(&self, i: usize) -> &[u8; 32] {
+        array_ref![self.data, self.tree_offset + i * 32, 32]
+    }
+
+    fn count_offset(&self) -> usize {
+        self.tree_offset + TREE_DEPTH * 32
     }
 }
 
+impl<D: DerefMut<Target = [u8]>> OutboxData<D> {
+    /// Inserts a leaf into the merkle tree, like `IncrementalMerkle::ingest`.
+    pub fn ingest(&mut self, element: H256) {
+        let count = self.count();
+        assert!(count < u32::MAX as usize);
+        let count_offset = self.count_offset();
+        *array_mut_ref![self.data, count_offset, 8] = (count as u64 + 1).to_le_bytes();
+
+        let mut node = element;
+        let mut size = count + 1;
+        for i in 0..TREE_DEPTH {
+            if (size & 1) == 1 {
+                let offset = self.tree_offset + i * 32;
+                array_mut_ref![self.data, offset, 32].copy_from_slice(node.as_bytes());
+                return;
+            }
+            node = hash_concat(self.branch(i), node.as_bytes());
+            size /= 2;
+        }
+    }
+}
+
+fn hash_concat(left: &[u8], right: &[u8]) -> H256 {
+    H256(keccak::hashv(&[left, right]).to_bytes())
+}
+
 /// An account corresponding to a dispatched message.
 pub type DispatchedMessageAccount = AccountData<DispatchedMessage>;
 
@@ -160,6 +461,11 @@ pub type DispatchedMessageAccount = AccountData<DispatchedMessage>;
 pub const DISPATCHED_MESSAGE_DISCRIMINATOR: &[u8; 8] = b"DISPATCH";
 
 /// A dispatched message.
+///
+/// Unlike the Inbox and Outbox, dispatched message accounts are written once and
+/// never updated, and are searched for by agents using fixed offsets into their
+/// data. Their layout is therefore identified by their discriminator rather than
+/// a version byte, and a new layout requires a new discriminator.
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct DispatchedMessage {
     /// The discriminator, intended to be set to `DISPATCHED_MESSAGE_DISCRIMINATOR`.
@@ -256,7 +562,7 @@ pub type ProcessedMessageAccount = AccountData<ProcessedMessage>;
 pub const PROCESSED_MESSAGE_DISCRIMINATOR: &[u8; 8] = b"PROCESSD";
 
 /// A processed message.
-#[derive(Debug, Default, Eq, PartialEq, BorshSerialize)]
+#[derive(Debug, Default, Eq, PartialEq)]
 pub struct ProcessedMessage {
     /// The discriminator, intended to be set to `PROCESSED_MESSAGE_DISCRIMINATOR`.
     pub discriminator: [u8; 8],
@@ -267,16 +573,21 @@ pub struct ProcessedMessage {
     pub message_id: H256,
     /// The slot in which the message was processed.
     pub slot: Slot,
+    /// The payer of the transaction that processed the message, i.e. the relayer.
+    /// None for messages processed before the payer was recorded, whose accounts
+    /// end after the slot.
+    pub payer: Option<Pubkey>,
 }
 
 impl ProcessedMessage {
     /// Creates a new processed message.
-    pub fn new(sequence: u64, message_id: H256, slot: Slot) -> Self {
+    pub fn new(sequence: u64, message_id: H256, slot: Slot, payer: Pubkey) -> Self {
         Self {
             discriminator: *PROCESSED_MESSAGE_DISCRIMINATOR,
             sequence,
             message_id,
             slot,
+            payer: Some(payer),
         }
     }
 }
@@ -287,7 +598,23 @@ impl SizedData for ProcessedMessage {
         // 8 byte sequence
         // 32 byte message_id
         // 8 byte slot
-        8 + 8 + 32 + 8
+        // 32 byte payer, if recorded
+        8 + 8 + 32 + 8 + self.payer.map_or(0, |_| 32)
+    }
+}
+
+/// The payer is written without an Option prefix, so that accounts processed
+/// before it was recorded are only shorter.
+impl BorshSerialize for ProcessedMessage {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.discriminator)?;
+        writer.write_all(&self.sequence.to_le_bytes())?;
+        writer.write_all(self.message_id.as_ref())?;
+        writer.write_all(&self.slot.to_le_bytes())?;
+        if let Some(payer) = self.payer {
+            writer.write_all(payer.as_ref())?;
+        }
+        Ok(())
     }
 }
 
@@ -312,11 +639,20 @@ impl BorshDeserialize for ProcessedMessage {
         let mut slot = [0u8; 8];
         reader.read_exact(&mut slot)?;
 
+        let payer = if reader.is_empty() {
+            None
+        } else {
+            let mut payer = [0u8; 32];
+            reader.read_exact(&mut payer)?;
+            Some(Pubkey::new_from_array(payer))
+        };
+
         Ok(Self {
             discriminator,
             sequence: u64::from_le_bytes(sequence),
             message_id: H256::from_slice(&message_id),
             slot: u64::from_le_bytes(slot),
+            payer,
         })
     }
 }
@@ -339,6 +675,7 @@ mod test {
                 fee: 69696969,
                 beneficiary: Pubkey::new_unique(),
             },
+            version: CURRENT_ACCOUNT_VERSION,
         };
 
         let mut serialized = vec![];
@@ -350,6 +687,49 @@ mod test {
         assert_eq!(serialized.len(), outbox.size());
     }
 
+    #[test]
+    fn test_outbox_data_matches_deserialized_outbox() {
+        for owner in [None, Some(Pubkey::new_unique())] {
+            let mut outbox = Outbox {
+                local_domain: 420,
+                outbox_bump_seed: 69,
+                owner,
+                tree: MerkleTree::default(),
+                max_protocol_fee: 100000000,
+                protocol_fee: ProtocolFee {
+                    fee: 69696969,
+                    beneficiary: Pubkey::new_unique(),
+                },
+                version: CURRENT_ACCOUNT_VERSION,
+            };
+            // The initialized flag, then the Outbox
+            let mut data = vec![1];
+            outbox.serialize(&mut data).unwrap();
+
+            for i in 0..10u8 {
+                let leaf = H256::repeat_byte(i);
+                outbox.tree.ingest(leaf);
+                OutboxData::new(&mut data[..]).unwrap().ingest(leaf);
+
+                let outbox_data = OutboxData::new(&data[..]).unwrap();
+                assert_eq!(outbox_data.local_domain(), outbox.local_domain);
+                assert_eq!(outbox_data.outbox_bump_seed(), outbox.outbox_bump_seed);
+                assert_eq!(outbox_data.protocol_fee(), outbox.protocol_fee.fee);
+                assert_eq!(outbox_data.count(), outbox.tree.count());
+                assert_eq!(outbox_data.root(), outbox.tree.root());
+            }
+            assert_eq!(
+                *OutboxAccount::fetch(&mut &data[..]).unwrap().into_inner(),
+                outbox
+            );
+        }
+
+        assert_eq!(
+            OutboxData::new(&[0u8; 1200][..]).err(),
+            Some(ProgramError::UninitializedAccount)
+        );
+    }
+
     #[test]
     fn test_inbox_ser_deser() {
         let inbox = Inbox {
@@ -357,6 +737,11 @@ mod test {
             inbox_bump_seed: 69,
             default_ism: Pubkey::new_unique(),
             processed_count: 69696969,
+            version: CURRENT_ACCOUNT_VERSION,
+            default_ism_overrides: BTreeMap::from([
+                (1, Pubkey::new_unique()),
+                (1399811149, Pubkey::new_unique()),
+            ]),
         };
 
         let mut serialized = vec![];
@@ -368,6 +753,125 @@ mod test {
         assert_eq!(serialized.len(), inbox.size());
     }
 
+    #[test]
+    fn test_inbox_version_0_deser_and_migrate() {
+        let default_ism = Pubkey::new_unique();
+
+        // The layout of an Inbox created before layouts were versioned.
+        let mut serialized = vec![];
+        420u32.serialize(&mut serialized).unwrap();
+        69u8.serialize(&mut serialized).unwrap();
+        default_ism.serialize(&mut serialized).unwrap();
+        69696969u64.serialize(&mut serialized).unwrap();
+
+        let mut inbox = Inbox::deserialize(&mut serialized.as_slice()).unwrap();
+        assert_eq!(inbox.version(), 0);
+        assert_eq!(serialized.len(), inbox.size());
+
+        // Version 0 accounts are stored without a version byte
+        let mut reserialized = vec![];
+        inbox.serialize(&mut reserialized).unwrap();
+        assert_eq!(reserialized, serialized);
+
+        inbox.migrate();
+        assert_eq!(
+            inbox,
+            Inbox {
+                local_domain: 420,
+                inbox_bump_seed: 69,
+                default_ism,
+                processed_count: 69696969,
+                version: CURRENT_ACCOUNT_VERSION,
+                default_ism_overrides: BTreeMap::new(),
+            }
+        );
+        // The version byte and the length of the empty overrides
+        assert_eq!(inbox.size(), serialized.len() + 1 + 4);
+    }
+
+    #[test]
+    fn test_inbox_version_1_deser() {
+        let inbox = Inbox {
+            local_domain: 420,
+            inbox_bump_seed: 69,
+            default_ism: Pubkey::new_unique(),
+            processed_count: 69696969,
+            version: 1,
+            default_ism_overrides: BTreeMap::new(),
+        };
+
+        // Version 1 accounts end with the version byte
+        let mut serialized = vec![];
+        inbox.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), 4 + 1 + 32 + 8 + 1);
+        assert_eq!(serialized.len(), inbox.size());
+
+        let deserialized = Inbox::deserialize(&mut serialized.as_slice()).unwrap();
+        assert_eq!(deserialized, inbox);
+    }
+
+    #[test]
+    fn test_inbox_default_ism_for_origin() {
+        let default_ism = Pubkey::new_unique();
+        let override_ism = Pubkey::new_unique();
+        let inbox = Inbox {
+            default_ism,
+            default_ism_overrides: BTreeMap::from([(1, override_ism)]),
+            ..Default::default()
+        };
+
+        assert_eq!(inbox.default_ism_for_origin(1), override_ism);
+        assert_eq!(inbox.default_ism_for_origin(2), default_ism);
+    }
+
+    #[test]
+    fn test_outbox_version_0_deser_and_migrate() {
+        let mut outbox = Outbox {
+            local_domain: 420,
+            outbox_bump_seed: 69,
+            owner: Some(Pubkey::new_unique()),
+            tree: MerkleTree::default(),
+            max_protocol_fee: 100000000,
+            protocol_fee: ProtocolFee {
+                fee: 69696969,
+                beneficiary: Pubkey::new_unique(),
+            },
+            version: 0,
+        };
+
+        let mut serialized = vec![];
+        outbox.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), outbox.size());
+
+        let deserialized = Outbox::deserialize(&mut serialized.as_slice()).unwrap();
+        assert_eq!(outbox, deserialized);
+
+        outbox.migrate();
+        let mut migrated = vec![];
+        outbox.serialize(&mut migrated).unwrap();
+        assert_eq!(migrated.len(), serialized.len() + 1);
+        assert_eq!(
+            Outbox::deserialize(&mut migrated.as_slice()).unwrap(),
+            outbox
+        );
+    }
+
+    #[test]
+    fn test_deser_errors_on_unsupported_version() {
+        let inbox = Inbox {
+            version: CURRENT_ACCOUNT_VERSION,
+            ..Default::default()
+        };
+
+        let mut serialized = vec![];
+        inbox.serialize(&mut serialized).unwrap();
+        // The version byte follows the local domain, bump seed, default ISM
+        // and processed count
+        serialized[4 + 1 + 32 + 8] = CURRENT_ACCOUNT_VERSION + 1;
+
+        assert!(Inbox::deserialize(&mut serialized.as_slice()).is_err());
+    }
+
     #[test]
     fn test_dispatched_message_ser_deser() {
         let dispatched_message = DispatchedMessage::new(
@@ -388,10 +892,29 @@ mod test {
 
     #[test]
     fn test_processed_message_ser_deser() {
-        let processed_message = ProcessedMessage::new(420420420, H256::random(), 69696969);
+        let processed_message =
+            ProcessedMessage::new(420420420, H256::random(), 69696969, Pubkey::new_unique());
+
+        let mut serialized = vec![];
+        processed_message.serialize(&mut serialized).unwrap();
+
+        let deserialized = ProcessedMessage::deserialize(&mut serialized.as_slice()).unwrap();
+
+        assert_eq!(processed_message, deserialized);
+        assert_eq!(serialized.len(), processed_message.size());
+    }
+
+    #[test]
+    fn test_processed_message_without_payer_deser() {
+        let processed_message = ProcessedMessage {
+            payer: None,
+            ..ProcessedMessage::new(420420420, H256::random(), 69696969, Pubkey::new_unique())
+        };
 
+        // Accounts processed before the payer was recorded end after the slot
         let mut serialized = vec![];
         processed_message.serialize(&mut serialized).unwrap();
+        assert_eq!(serialized.len(), 8 + 8 + 32 + 8);
 
         let deserialized = ProcessedMessage::deserialize(&mut serialized.as_slice()).unwrap();
 
@@ -27,6 +27,12 @@ pub enum Error {
     /// The message is too large.
     #[error("Message is larger than the maximum allowed")]
     MaxMessageSizeExceeded = 7,
+    /// A batch of messages to process is empty.
+    #[error("Batch of messages is empty")]
+    EmptyBatch = 8,
+    /// The messages of a batch don't all use the same ISM.
+    #[error("Messages of the batch use different ISMs")]
+    BatchIsmMismatch = 9,
 }
 
 impl From<Error> for ProgramError {
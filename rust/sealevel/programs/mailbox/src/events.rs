@@ -0,0 +1,148 @@
+//! Events logged by the Mailbox when it dispatches or processes a message, or
+//! when its configuration changes.
+//!
+//! Events are logged with a CPI to the SPL Noop program, which makes them
+//! available to indexers in the transaction's inner instructions without being
+//! subject to the truncation of program logs. The data of each is the
+//! `MAILBOX_EVENT_DISCRIMINATOR` followed by the Borsh encoded `MailboxEvent`,
+//! whose variant index tells the events apart.
+//!
+//! Anyone can log data to the SPL Noop program, so consumers should only trust
+//! events that the Mailbox is known to have logged, e.g. because the
+//! transaction also instructs the Mailbox to make the change.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use hyperlane_core::H256;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Prefix of the data of the SPL Noop instructions that log Mailbox events,
+/// to tell them apart from other data logged to the SPL Noop program.
+pub const MAILBOX_EVENT_DISCRIMINATOR: &[u8; 8] = b"HYPLMBEV";
+
+/// An event of the Mailbox.
+///
+/// The variant index is part of the encoding, so new events must only ever be
+/// appended.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone)]
+pub enum MailboxEvent {
+    /// The default ISM was set.
+    DefaultIsmSet {
+        /// The default ISM before the change.
+        previous_ism: Pubkey,
+        /// The new default ISM.
+        new_ism: Pubkey,
+    },
+    /// Ownership of the Mailbox was transferred.
+    OwnershipTransferred {
+        /// The owner before the transfer.
+        previous_owner: Option<Pubkey>,
+        /// The new owner. None if ownership was renounced.
+        new_owner: Option<Pubkey>,
+    },
+    /// A message was dispatched by the Outbox.
+    Dispatch {
+        /// The ID of the message.
+        message_id: H256,
+        /// The nonce of the message.
+        nonce: u32,
+        /// The domain the message was sent to.
+        destination: u32,
+        /// The unique message account, which the dispatched message PDA is
+        /// derived from.
+        unique_message_pubkey: Pubkey,
+        /// The encoded message.
+        message: Vec<u8>,
+    },
+    /// A message was processed by the Inbox.
+    Process {
+        /// The ID of the message.
+        message_id: H256,
+        /// The domain the message was sent from.
+        origin: u32,
+        /// The sequence of the processed message, i.e. the number of messages
+        /// the Inbox processed before it.
+        sequence: u64,
+        /// The program that handled the message.
+        recipient: Pubkey,
+    },
+    /// The default ISM override for an origin domain was set or removed.
+    DefaultIsmOverrideSet {
+        /// The origin domain of the messages the override applies to.
+        origin: u32,
+        /// The override before the change, if any.
+        previous_ism: Option<Pubkey>,
+        /// The new override. None if the override was removed.
+        new_ism: Option<Pubkey>,
+    },
+}
+
+impl MailboxEvent {
+    /// Serializes the event into the data of an SPL Noop instruction.
+    pub fn to_noop_data(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut data = MAILBOX_EVENT_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)
+            .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+        Ok(data)
+    }
+
+    /// Deserializes an event from the data of an SPL Noop instruction.
+    /// Returns None if the data isn't a Mailbox event.
+    pub fn from_noop_data(data: &[u8]) -> Option<Self> {
+        let event_data = data.strip_prefix(&MAILBOX_EVENT_DISCRIMINATOR[..])?;
+        Self::try_from_slice(event_data).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_noop_data_roundtrip() {
+        let events = [
+            MailboxEvent::DefaultIsmSet {
+                previous_ism: Pubkey::new_unique(),
+                new_ism: Pubkey::new_unique(),
+            },
+            MailboxEvent::OwnershipTransferred {
+                previous_owner: Some(Pubkey::new_unique()),
+                new_owner: None,
+            },
+            MailboxEvent::Dispatch {
+                message_id: H256::random(),
+                nonce: 7,
+                destination: 1234,
+                unique_message_pubkey: Pubkey::new_unique(),
+                message: vec![3, 0, 0, 0, 7],
+            },
+            MailboxEvent::Process {
+                message_id: H256::random(),
+                origin: 4321,
+                sequence: 42,
+                recipient: Pubkey::new_unique(),
+            },
+            MailboxEvent::DefaultIsmOverrideSet {
+                origin: 1,
+                previous_ism: None,
+                new_ism: Some(Pubkey::new_unique()),
+            },
+        ];
+        for event in events {
+            let data = event.to_noop_data().unwrap();
+            assert_eq!(MailboxEvent::from_noop_data(&data), Some(event));
+        }
+    }
+
+    #[test]
+    fn test_from_noop_data_ignores_other_data() {
+        // The message ID logged by the inbox before it logged events
+        assert_eq!(
+            MailboxEvent::from_noop_data(b"Hyperlane inbox: 0x1234"),
+            None
+        );
+        assert_eq!(
+            MailboxEvent::from_noop_data(MAILBOX_EVENT_DISCRIMINATOR),
+            None
+        );
+    }
+}
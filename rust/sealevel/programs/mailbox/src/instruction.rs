@@ -8,7 +8,10 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-use crate::{mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds, protocol_fee::ProtocolFee};
+use crate::{
+    mailbox_config_events_pda_seeds, mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds,
+    mailbox_processed_message_pda_seeds, protocol_fee::ProtocolFee,
+};
 
 /// The current message version.
 pub const VERSION: u8 = 3;
@@ -40,6 +43,23 @@ pub enum Instruction {
     ClaimProtocolFees,
     /// Sets the protocol fee configuration.
     SetProtocolFeeConfig(ProtocolFee),
+    /// Quotes the total lamports required to dispatch a message.
+    OutboxQuoteDispatch(OutboxQuoteDispatch),
+    /// Migrates an account to the current layout version.
+    MigrateAccount(MigratableAccount),
+    /// Gets whether each of the messages with the given IDs has been delivered.
+    InboxGetDelivered(Vec<H256>),
+    /// Gets the sequence, slot and payer of the processing of the message with the given ID.
+    InboxGetProcessedMessage(H256),
+    /// Sets or removes the ISM used instead of the default ISM for messages
+    /// from an origin domain.
+    InboxSetDefaultIsmOverride(InboxSetDefaultIsmOverride),
+    /// Gets the ISM used for the recipient's messages from an origin domain,
+    /// taking default ISM overrides into account.
+    InboxGetRecipientIsmForOrigin(InboxGetRecipientIsmForOrigin),
+    /// Processes several messages that share an ISM, verifying them with a
+    /// single call to the ISM.
+    InboxProcessBatch(InboxProcessBatch),
 }
 
 impl Instruction {
@@ -68,6 +88,37 @@ pub struct Init {
     pub protocol_fee: ProtocolFee,
 }
 
+/// Return data of the InboxGetProcessedMessage instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct ProcessedMessageInfo {
+    /// The sequence of the processed message.
+    pub sequence: u64,
+    /// The slot in which the message was processed.
+    pub slot: u64,
+    /// The payer of the transaction that processed the message, i.e. the relayer.
+    /// None for messages processed before the payer was recorded.
+    pub payer: Option<Pubkey>,
+}
+
+/// Instruction data for the InboxSetDefaultIsmOverride instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct InboxSetDefaultIsmOverride {
+    /// The origin domain of the messages the override applies to.
+    pub origin: u32,
+    /// The ISM to use for messages from the origin domain. If None, the
+    /// override is removed and the default ISM is used again.
+    pub ism: Option<Pubkey>,
+}
+
+/// Instruction data for the InboxGetRecipientIsmForOrigin instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct InboxGetRecipientIsmForOrigin {
+    /// The recipient program.
+    pub recipient: Pubkey,
+    /// The origin domain of the message.
+    pub origin: u32,
+}
+
 /// Instruction data for the OutboxDispatch instruction.
 #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
 pub struct OutboxDispatch {
@@ -85,6 +136,39 @@ pub struct OutboxDispatch {
     pub message_body: Vec<u8>,
 }
 
+/// Instruction data for the OutboxQuoteDispatch instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct OutboxQuoteDispatch {
+    /// The destination domain of the message.
+    pub destination_domain: u32,
+    /// The message body. Only its length affects the quote.
+    pub message_body: Vec<u8>,
+    /// The destination gas amount to quote an IGP payment for.
+    /// If None, no IGP payment is included in the quote.
+    pub gas_amount: Option<u64>,
+}
+
+/// The IGP accounts used to quote the gas payment portion of an
+/// OutboxQuoteDispatch instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteDispatchIgpAccounts {
+    /// The IGP program.
+    pub program_id: Pubkey,
+    /// The IGP account.
+    pub igp: Pubkey,
+    /// The overhead IGP account, if any.
+    pub overhead_igp: Option<Pubkey>,
+}
+
+/// The accounts that can be migrated by the MigrateAccount instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MigratableAccount {
+    /// The Inbox PDA account.
+    Inbox,
+    /// The Outbox PDA account.
+    Outbox,
+}
+
 /// Instruction data for the InboxProcess instruction.
 #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
 pub struct InboxProcess {
@@ -94,6 +178,29 @@ pub struct InboxProcess {
     pub message: Vec<u8>,
 }
 
+/// Instruction data for the InboxProcessBatch instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct InboxProcessBatch {
+    /// The messages, in the order their accounts are passed.
+    pub items: Vec<InboxProcessBatchItem>,
+    /// The number of accounts required by the ISM's VerifyBatch instruction.
+    pub ism_verify_account_count: u8,
+}
+
+/// A message processed by the InboxProcessBatch instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct InboxProcessBatchItem {
+    /// The metadata required by the ISM to process the message.
+    pub metadata: Vec<u8>,
+    /// The encoded message.
+    pub message: Vec<u8>,
+    /// The number of accounts required to get the recipient's ISM.
+    pub ism_getter_account_count: u8,
+    /// The number of accounts required by the recipient's Handle instruction,
+    /// not counting the process authority.
+    pub handle_account_count: u8,
+}
+
 /// Creates an Init instruction.
 pub fn init_instruction(
     program_id: Pubkey,
@@ -139,14 +246,22 @@ pub fn transfer_ownership_instruction(
         Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
             .ok_or(ProgramError::InvalidSeeds)?;
 
+    let (config_events_account, _config_events_bump) =
+        Pubkey::try_find_program_address(mailbox_config_events_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
     // 0. `[writeable]` The Outbox PDA account.
     // 1. `[signer]` The current owner.
+    // 2. `[executable]` The SPL Noop program.
+    // 3. `[]` The config events PDA account.
     let instruction = SolanaInstruction {
         program_id,
         data: Instruction::TransferOwnership(new_owner).into_instruction_data()?,
         accounts: vec![
             AccountMeta::new(outbox_account, false),
             AccountMeta::new(owner_payer, true),
+            AccountMeta::new_readonly(spl_noop::id(), false),
+            AccountMeta::new_readonly(config_events_account, false),
         ],
     };
     Ok(instruction)
@@ -165,9 +280,15 @@ pub fn set_default_ism_instruction(
         Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
             .ok_or(ProgramError::InvalidSeeds)?;
 
+    let (config_events_account, _config_events_bump) =
+        Pubkey::try_find_program_address(mailbox_config_events_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
     // 0. `[writeable]` - The Inbox PDA account.
     // 1. `[]` - The Outbox PDA account.
     // 2. `[signer]` - The owner of the Mailbox.
+    // 3. `[executable]` - The SPL Noop program.
+    // 4. `[]` - The config events PDA account.
     let instruction = SolanaInstruction {
         program_id,
         data: Instruction::InboxSetDefaultIsm(default_ism).into_instruction_data()?,
@@ -175,7 +296,210 @@ pub fn set_default_ism_instruction(
             AccountMeta::new(inbox_account, false),
             AccountMeta::new_readonly(outbox_account, false),
             AccountMeta::new(owner_payer, true),
+            AccountMeta::new_readonly(spl_noop::id(), false),
+            AccountMeta::new_readonly(config_events_account, false),
+        ],
+    };
+    Ok(instruction)
+}
+
+/// Creates an InboxSetDefaultIsmOverride instruction. The owner pays for the
+/// reallocation of the Inbox when an override is added.
+pub fn set_default_ism_override_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    origin: u32,
+    ism: Option<Pubkey>,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (inbox_account, _inbox_bump) =
+        Pubkey::try_find_program_address(mailbox_inbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (config_events_account, _config_events_bump) =
+        Pubkey::try_find_program_address(mailbox_config_events_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[executable]` - The system program.
+    // 1. `[writeable]` - The Inbox PDA account.
+    // 2. `[]` - The Outbox PDA account.
+    // 3. `[signer, writeable]` - The owner of the Mailbox.
+    // 4. `[executable]` - The SPL Noop program.
+    // 5. `[]` - The config events PDA account.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::InboxSetDefaultIsmOverride(InboxSetDefaultIsmOverride { origin, ism })
+            .into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(inbox_account, false),
+            AccountMeta::new_readonly(outbox_account, false),
+            AccountMeta::new(owner_payer, true),
+            AccountMeta::new_readonly(spl_noop::id(), false),
+            AccountMeta::new_readonly(config_events_account, false),
         ],
     };
     Ok(instruction)
 }
+
+/// Creates an OutboxQuoteDispatch instruction.
+/// IGP accounts must be provided if and only if `quote.gas_amount` is Some.
+pub fn quote_dispatch_instruction(
+    program_id: Pubkey,
+    quote: OutboxQuoteDispatch,
+    igp_accounts: Option<QuoteDispatchIgpAccounts>,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[]` The Outbox PDA account.
+    // 1. `[executable]` The IGP program (optional).
+    // 2. `[executable]` The system program (optional).
+    // 3. `[]` The IGP account (optional).
+    // 4. `[]` The overhead IGP account (optional).
+    let mut accounts = vec![AccountMeta::new_readonly(outbox_account, false)];
+    if let Some(igp_accounts) = igp_accounts {
+        accounts.extend([
+            AccountMeta::new_readonly(igp_accounts.program_id, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(igp_accounts.igp, false),
+        ]);
+        if let Some(overhead_igp) = igp_accounts.overhead_igp {
+            accounts.push(AccountMeta::new_readonly(overhead_igp, false));
+        }
+    }
+
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::OutboxQuoteDispatch(quote).into_instruction_data()?,
+        accounts,
+    };
+    Ok(instruction)
+}
+
+/// Creates a MigrateAccount instruction.
+pub fn migrate_account_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    account: MigratableAccount,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[executable]` The system program.
+    // 1. `[writeable]` The Outbox PDA account.
+    // 2. `[signer, writeable]` The owner of the Mailbox, who pays for any realloc.
+    // 3. `[writeable]` The Inbox PDA account, if migrating the Inbox.
+    let mut accounts = vec![
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new(outbox_account, false),
+        AccountMeta::new(owner_payer, true),
+    ];
+    if account == MigratableAccount::Inbox {
+        let (inbox_account, _inbox_bump) =
+            Pubkey::try_find_program_address(mailbox_inbox_pda_seeds!(), &program_id)
+                .ok_or(ProgramError::InvalidSeeds)?;
+        accounts.push(AccountMeta::new(inbox_account, false));
+    }
+
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::MigrateAccount(account).into_instruction_data()?,
+        accounts,
+    };
+    Ok(instruction)
+}
+
+/// Creates an InboxGetDelivered instruction.
+pub fn get_delivered_instruction(
+    program_id: Pubkey,
+    message_ids: Vec<H256>,
+) -> Result<SolanaInstruction, ProgramError> {
+    // 0..N. `[]` The processed message PDA account of each message.
+    let accounts = message_ids
+        .iter()
+        .map(|message_id| {
+            Pubkey::try_find_program_address(
+                mailbox_processed_message_pda_seeds!(message_id),
+                &program_id,
+            )
+            .map(|(processed_message_account, _bump)| {
+                AccountMeta::new_readonly(processed_message_account, false)
+            })
+            .ok_or(ProgramError::InvalidSeeds)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::InboxGetDelivered(message_ids).into_instruction_data()?,
+        accounts,
+    };
+    Ok(instruction)
+}
+
+/// Creates an InboxGetProcessedMessage instruction.
+pub fn get_processed_message_instruction(
+    program_id: Pubkey,
+    message_id: H256,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (processed_message_account, _bump) = Pubkey::try_find_program_address(
+        mailbox_processed_message_pda_seeds!(message_id),
+        &program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)?;
+
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::InboxGetProcessedMessage(message_id).into_instruction_data()?,
+        // 0. `[]` The processed message PDA account.
+        accounts: vec![AccountMeta::new_readonly(processed_message_account, false)],
+    };
+    Ok(instruction)
+}
+
+/// Reads whether the `index`th message of an InboxGetDelivered instruction
+/// was delivered from the bitmap it returned.
+pub fn is_delivered_in_bitmap(bitmap: &[u8], index: usize) -> bool {
+    bitmap
+        .get(index / 8)
+        .map_or(false, |byte| byte & (1 << (index % 8)) != 0)
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::{collection::vec, prelude::*};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_decode_arbitrary_instruction_data_does_not_panic(
+            data in vec(any::<u8>(), 0..512),
+        ) {
+            if let Ok(instruction) = Instruction::from_instruction_data(&data) {
+                prop_assert_eq!(instruction.into_instruction_data().unwrap(), data);
+            }
+        }
+
+        #[test]
+        fn test_outbox_dispatch_round_trips(
+            sender in any::<[u8; 32]>(),
+            destination_domain in any::<u32>(),
+            recipient in any::<[u8; 32]>(),
+            message_body in vec(any::<u8>(), 0..1024),
+        ) {
+            let instruction = Instruction::OutboxDispatch(OutboxDispatch {
+                sender: Pubkey::new_from_array(sender),
+                destination_domain,
+                recipient: H256::from(recipient),
+                message_body,
+            });
+            let data = instruction.try_to_vec().unwrap();
+            prop_assert_eq!(Instruction::from_instruction_data(&data).unwrap(), instruction);
+        }
+    }
+}
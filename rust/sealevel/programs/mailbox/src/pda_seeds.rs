@@ -24,6 +24,20 @@ macro_rules! mailbox_outbox_pda_seeds {
     }};
 }
 
+/// PDA seeds for the account that's passed to instructions changing the
+/// configuration of the Mailbox. It holds no data, but makes these
+/// transactions easy to find with `getSignaturesForAddress`.
+#[macro_export]
+macro_rules! mailbox_config_events_pda_seeds {
+    () => {{
+        &[b"hyperlane", b"-", b"config_events"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[b"hyperlane", b"-", b"config_events", &[$bump_seed]]
+    }};
+}
+
 /// Gets the PDA seeds for a message storage account that's
 /// based upon the pubkey of a unique message account.
 #[macro_export]
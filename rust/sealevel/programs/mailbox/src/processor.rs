@@ -1,5 +1,7 @@
 //! Entrypoint, dispatch, and execution for the Hyperlane Sealevel mailbox instruction.
 
+use std::collections::BTreeMap;
+
 use access_control::AccessControl;
 use account_utils::{verify_rent_exempt, SizedData};
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -22,8 +24,9 @@ use solana_program::{
 };
 
 use account_utils::{create_pda_account, verify_account_uninitialized};
+use hyperlane_sealevel_igp::instruction::{Instruction as IgpInstruction, QuoteGasPayment};
 use hyperlane_sealevel_interchain_security_module_interface::{
-    InterchainSecurityModuleInstruction, VerifyInstruction,
+    InterchainSecurityModuleInstruction, VerifyBatchInstruction, VerifyInstruction,
 };
 use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
@@ -33,11 +36,17 @@ use serializable_account_meta::SimulationReturnData;
 use crate::{
     accounts::{
         DispatchedMessage, DispatchedMessageAccount, Inbox, InboxAccount, Outbox, OutboxAccount,
-        ProcessedMessage, ProcessedMessageAccount,
+        OutboxData, ProcessedMessage, ProcessedMessageAccount, VersionedData,
+        CURRENT_ACCOUNT_VERSION,
     },
     error::Error,
-    instruction::{InboxProcess, Init, Instruction as MailboxIxn, OutboxDispatch, VERSION},
-    mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds,
+    events::MailboxEvent,
+    instruction::{
+        InboxProcess, InboxProcessBatch, InboxSetDefaultIsmOverride, Init,
+        Instruction as MailboxIxn, MigratableAccount, OutboxDispatch, OutboxQuoteDispatch,
+        ProcessedMessageInfo, VERSION,
+    },
+    mailbox_config_events_pda_seeds, mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds,
     mailbox_message_dispatch_authority_pda_seeds, mailbox_outbox_pda_seeds,
     mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds,
     protocol_fee::ProtocolFee,
@@ -57,7 +66,7 @@ pub fn process_instruction(
         MailboxIxn::InboxProcess(process) => inbox_process(program_id, accounts, process),
         MailboxIxn::InboxSetDefaultIsm(ism) => inbox_set_default_ism(program_id, accounts, ism),
         MailboxIxn::InboxGetRecipientIsm(recipient) => {
-            inbox_get_recipient_ism(program_id, accounts, recipient)
+            inbox_get_recipient_ism(program_id, accounts, recipient, None)
         }
         MailboxIxn::OutboxDispatch(dispatch) => outbox_dispatch(program_id, accounts, dispatch),
         MailboxIxn::OutboxGetCount => outbox_get_count(program_id, accounts),
@@ -71,6 +80,26 @@ pub fn process_instruction(
         MailboxIxn::SetProtocolFeeConfig(new_protocol_fee_config) => {
             set_protocol_fee_config(program_id, accounts, new_protocol_fee_config)
         }
+        MailboxIxn::OutboxQuoteDispatch(quote) => {
+            outbox_quote_dispatch(program_id, accounts, quote)
+        }
+        MailboxIxn::MigrateAccount(account) => migrate_account(program_id, accounts, account),
+        MailboxIxn::InboxGetDelivered(message_ids) => {
+            inbox_get_delivered(program_id, accounts, message_ids)
+        }
+        MailboxIxn::InboxGetProcessedMessage(message_id) => {
+            inbox_get_processed_message(program_id, accounts, message_id)
+        }
+        MailboxIxn::InboxSetDefaultIsmOverride(ism_override) => {
+            inbox_set_default_ism_override(program_id, accounts, ism_override)
+        }
+        MailboxIxn::InboxGetRecipientIsmForOrigin(get_ism) => inbox_get_recipient_ism(
+            program_id,
+            accounts,
+            get_ism.recipient,
+            Some(get_ism.origin),
+        ),
+        MailboxIxn::InboxProcessBatch(batch) => inbox_process_batch(program_id, accounts, batch),
     }
     .map_err(|err| {
         msg!("{}", err);
@@ -116,6 +145,8 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
         inbox_bump_seed: inbox_bump,
         default_ism: init.default_ism,
         processed_count: 0,
+        version: CURRENT_ACCOUNT_VERSION,
+        default_ism_overrides: BTreeMap::new(),
     });
     if init.protocol_fee.fee > init.max_protocol_fee {
         msg!("Invalid initialization config: Protocol fee is greater than max protocol fee",);
@@ -151,6 +182,7 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
         tree: MerkleTree::default(),
         max_protocol_fee: init.max_protocol_fee,
         protocol_fee: init.protocol_fee,
+        version: CURRENT_ACCOUNT_VERSION,
     });
 
     // Create the outbox PDA account.
@@ -283,7 +315,7 @@ fn inbox_process(
         &recipient_program_id,
         get_ism_infos,
         get_ism_account_metas,
-        inbox.default_ism,
+        inbox.default_ism_for_origin(message.origin),
     )?;
 
     // Account N: SPL Noop program.
@@ -360,10 +392,12 @@ fn inbox_process(
     invoke(&verify, &ism_verify_infos)?;
 
     // Mark the message as delivered by creating the processed message account.
+    let sequence = inbox.processed_count;
     let processed_message_account_data = ProcessedMessageAccount::from(ProcessedMessage::new(
-        inbox.processed_count,
+        sequence,
         message_id,
         Clock::get()?.slot,
+        *payer_info.key,
     ));
     let processed_message_account_data_size = processed_message_account_data.size();
     create_pda_account(
@@ -406,10 +440,16 @@ fn inbox_process(
 
     #[cfg(not(feature = "no-spl-noop"))]
     {
+        let event = MailboxEvent::Process {
+            message_id,
+            origin: message.origin,
+            sequence,
+            recipient: recipient_program_id,
+        };
         let noop_cpi_log = Instruction {
             program_id: spl_noop::id(),
             accounts: vec![],
-            data: format!("Hyperlane inbox: {:?}", message_id).into_bytes(),
+            data: event.to_noop_data()?,
         };
         invoke(&noop_cpi_log, &[])?;
     }
@@ -419,7 +459,292 @@ fn inbox_process(
     Ok(())
 }
 
+/// Process several messages that use the same ISM, verifying them all with a
+/// single call to the ISM's VerifyBatch instruction. The whole batch fails if
+/// any of the messages can't be processed. Non-reentrant through the use of a
+/// RefMut.
+///
+// Accounts:
+// 0.      `[signer]` Payer account. This pays for the creation of the processed message PDAs.
+// 1.      `[executable]` The system program.
+// 2.      `[writable]` Inbox PDA account.
+// 3.      `[executable]` SPL noop
+// 4.      `[executable]` ISM
+// 5..N.   [??] Accounts required to invoke the ISM's VerifyBatch instruction.
+// Then, for each message, in the order of the instruction's items:
+//         `[]` Mailbox process authority specific to the message recipient.
+//         `[writable]` Processed message PDA.
+//         [??] Accounts required to invoke the recipient's InterchainSecurityModule instruction.
+//         `[executable]` Recipient program.
+//         [??] Accounts required to invoke the recipient's Handle instruction.
+fn inbox_process_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    batch: InboxProcessBatch,
+) -> ProgramResult {
+    if batch.items.is_empty() {
+        return Err(Error::EmptyBatch.into());
+    }
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: Payer account.
+    let payer_info = next_account_info(accounts_iter)?;
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 1: The system program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 2: Inbox PDA.
+    let inbox_info = next_account_info(accounts_iter)?;
+    // The refmut of the Inbox data is held until all the messages are handled,
+    // so it's a reentrancy guard for both this and the single process instruction.
+    let (mut inbox, mut inbox_data_refmut) =
+        Inbox::verify_account_and_fetch_inner_with_data_refmut(program_id, inbox_info)?;
+
+    // Account 3: SPL Noop program.
+    let spl_noop_info = next_account_info(accounts_iter)?;
+    if spl_noop_info.key != &spl_noop::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    #[cfg(not(feature = "no-spl-noop"))]
+    if !spl_noop_info.executable {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 4: The ISM.
+    let ism_info = next_account_info(accounts_iter)?;
+
+    // Accounts 5..N: The accounts required for ISM verification.
+    let (ism_verify_infos, ism_verify_account_metas) =
+        next_account_infos_and_metas(accounts_iter, batch.ism_verify_account_count)?;
+
+    let mut verify_items = Vec::with_capacity(batch.items.len());
+    let mut deliveries = Vec::with_capacity(batch.items.len());
+    for item in batch.items {
+        // Decode the message bytes.
+        let message = HyperlaneMessage::read_from(&mut std::io::Cursor::new(&item.message))
+            .map_err(|_| ProgramError::from(Error::DecodeError))?;
+        let message_id = message.id();
+
+        // Require the message version to match what we expect.
+        if message.version != VERSION {
+            return Err(ProgramError::from(Error::UnsupportedMessageVersion));
+        }
+        // Verify the message's destination matches the inbox's local domain.
+        if inbox.local_domain != message.destination {
+            return Err(Error::DestinationDomainNotLocalDomain.into());
+        }
+        let recipient_program_id = Pubkey::new_from_array(message.recipient.0);
+
+        // Process authority account that is specific to the message recipient.
+        let process_authority_info = next_account_info(accounts_iter)?;
+        let (expected_process_authority_key, process_authority_bump) = Pubkey::find_program_address(
+            mailbox_process_authority_pda_seeds!(&recipient_program_id),
+            program_id,
+        );
+        if process_authority_info.key != &expected_process_authority_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Processed message PDA. Whether it's uninitialized is checked when
+        // it's created, which also catches a message that's in the batch twice.
+        let processed_message_account_info = next_account_info(accounts_iter)?;
+        let (expected_processed_message_key, processed_message_bump) = Pubkey::find_program_address(
+            mailbox_processed_message_pda_seeds!(message_id),
+            program_id,
+        );
+        if processed_message_account_info.key != &expected_processed_message_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // The accounts required for getting the ISM the recipient wants to use.
+        let (get_ism_infos, get_ism_account_metas) =
+            next_account_infos_and_metas(accounts_iter, item.ism_getter_account_count)?;
+        let ism = get_recipient_ism(
+            &recipient_program_id,
+            get_ism_infos,
+            get_ism_account_metas,
+            inbox.default_ism_for_origin(message.origin),
+        )?;
+        if &ism != ism_info.key {
+            return Err(Error::BatchIsmMismatch.into());
+        }
+
+        // The recipient program.
+        let recipient_info = next_account_info(accounts_iter)?;
+        if &recipient_program_id != recipient_info.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if !recipient_info.executable {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // The accounts required for the recipient program handler.
+        let (handle_infos, handle_account_metas) =
+            next_account_infos_and_metas(accounts_iter, item.handle_account_count)?;
+        let mut recipient_infos = vec![process_authority_info.clone()];
+        recipient_infos.extend(handle_infos);
+        let mut recipient_account_metas = vec![AccountMeta {
+            pubkey: *process_authority_info.key,
+            is_signer: true,
+            is_writable: false,
+        }];
+        recipient_account_metas.extend(handle_account_metas);
+
+        verify_items.push(VerifyInstruction {
+            metadata: item.metadata,
+            message: item.message,
+        });
+        deliveries.push(BatchDelivery {
+            message,
+            message_id,
+            recipient_program_id,
+            process_authority_bump,
+            processed_message_account_info,
+            processed_message_bump,
+            recipient_infos,
+            recipient_account_metas,
+        });
+    }
+
+    if accounts_iter.next().is_some() {
+        return Err(Error::ExtraneousAccount.into());
+    }
+
+    // Call into the ISM to verify all the messages at once.
+    let verify_instruction =
+        InterchainSecurityModuleInstruction::VerifyBatch(VerifyBatchInstruction::new(verify_items));
+    let verify = Instruction::new_with_bytes(
+        *ism_info.key,
+        &verify_instruction.encode()?,
+        ism_verify_account_metas,
+    );
+    invoke(&verify, &ism_verify_infos)?;
+
+    let slot = Clock::get()?.slot;
+    let rent = Rent::get()?;
+    for delivery in deliveries {
+        // If the processed message account already exists, then the message
+        // has been processed already, possibly earlier in this batch.
+        if verify_account_uninitialized(delivery.processed_message_account_info).is_err() {
+            return Err(Error::MessageAlreadyProcessed.into());
+        }
+
+        // Mark the message as delivered by creating the processed message account.
+        let sequence = inbox.processed_count;
+        let processed_message_account_data = ProcessedMessageAccount::from(ProcessedMessage::new(
+            sequence,
+            delivery.message_id,
+            slot,
+            *payer_info.key,
+        ));
+        create_pda_account(
+            payer_info,
+            &rent,
+            processed_message_account_data.size(),
+            program_id,
+            system_program_info,
+            delivery.processed_message_account_info,
+            mailbox_processed_message_pda_seeds!(
+                delivery.message_id,
+                delivery.processed_message_bump
+            ),
+        )?;
+        processed_message_account_data.store(delivery.processed_message_account_info, false)?;
+
+        // Increment the processed count and store the updated Inbox account
+        // before calling into the recipient, as the single process instruction does.
+        inbox.processed_count += 1;
+        InboxAccount::from(inbox.clone())
+            .store_in_slice(&mut inbox_data_refmut)
+            .map_err(|e| ProgramError::BorshIoError(e.to_string()))?;
+
+        // Now call into the recipient program with the verified message!
+        let handle_intruction = Instruction::new_with_bytes(
+            delivery.recipient_program_id,
+            &MessageRecipientInstruction::Handle(HandleInstruction::new(
+                delivery.message.origin,
+                delivery.message.sender,
+                delivery.message.body,
+            ))
+            .encode()?,
+            delivery.recipient_account_metas,
+        );
+        invoke_signed(
+            &handle_intruction,
+            &delivery.recipient_infos,
+            &[mailbox_process_authority_pda_seeds!(
+                &delivery.recipient_program_id,
+                delivery.process_authority_bump
+            )],
+        )?;
+
+        #[cfg(not(feature = "no-spl-noop"))]
+        {
+            let event = MailboxEvent::Process {
+                message_id: delivery.message_id,
+                origin: delivery.message.origin,
+                sequence,
+                recipient: delivery.recipient_program_id,
+            };
+            let noop_cpi_log = Instruction {
+                program_id: spl_noop::id(),
+                accounts: vec![],
+                data: event.to_noop_data()?,
+            };
+            invoke(&noop_cpi_log, &[])?;
+        }
+
+        msg!(
+            "Hyperlane inbox processed message {:?}",
+            delivery.message_id
+        );
+    }
+
+    Ok(())
+}
+
+/// A verified message of a batch, with the accounts to deliver it.
+struct BatchDelivery<'a, 'b> {
+    message: HyperlaneMessage,
+    message_id: H256,
+    recipient_program_id: Pubkey,
+    process_authority_bump: u8,
+    processed_message_account_info: &'a AccountInfo<'b>,
+    processed_message_bump: u8,
+    recipient_infos: Vec<AccountInfo<'b>>,
+    recipient_account_metas: Vec<AccountMeta>,
+}
+
+/// Takes the next `count` accounts, along with the metas to pass them on to a CPI.
+fn next_account_infos_and_metas<'a, 'b: 'a>(
+    accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+    count: u8,
+) -> Result<(Vec<AccountInfo<'b>>, Vec<AccountMeta>), ProgramError> {
+    let mut account_infos = Vec::with_capacity(count.into());
+    let mut account_metas = Vec::with_capacity(count.into());
+    for _ in 0..count {
+        let account_info = next_account_info(accounts_iter)?;
+        account_infos.push(account_info.clone());
+        account_metas.push(AccountMeta {
+            pubkey: *account_info.key,
+            is_signer: account_info.is_signer,
+            is_writable: account_info.is_writable,
+        });
+    }
+    Ok((account_infos, account_metas))
+}
+
 /// Gets the ISM to use for a recipient program and sets it as return data.
+/// If the origin of the message is given, the ISM falls back to the default
+/// ISM override for the origin rather than to the default ISM.
 ///
 /// Accounts:
 /// 0.    `[]` - The Inbox PDA.
@@ -431,6 +756,7 @@ fn inbox_get_recipient_ism(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     recipient: Pubkey,
+    origin: Option<u32>,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
@@ -456,7 +782,11 @@ fn inbox_get_recipient_ism(
         });
     }
 
-    let ism = get_recipient_ism(&recipient, account_infos, account_metas, inbox.default_ism)?;
+    let default_ism = match origin {
+        Some(origin) => inbox.default_ism_for_origin(origin),
+        None => inbox.default_ism,
+    };
+    let ism = get_recipient_ism(&recipient, account_infos, account_metas, default_ism)?;
 
     // Return the borsh serialized ISM pubkey.
     set_return_data(
@@ -510,12 +840,104 @@ fn get_recipient_ism(
     Ok(ism)
 }
 
+/// Gets whether each of the messages with the given IDs has been delivered,
+/// as a bitmap in return data. Bit `i % 8` of byte `i / 8` is set if the
+/// `i`th message was delivered.
+///
+/// Accounts:
+/// 0..N. `[]` The processed message PDA account of each message, in the same
+///       order as the message IDs.
+fn inbox_get_delivered(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    message_ids: Vec<H256>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let mut bitmap = vec![0u8; (message_ids.len() + 7) / 8];
+    for (index, message_id) in message_ids.iter().enumerate() {
+        // Account i: Processed message PDA.
+        let processed_message_account_info = next_account_info(accounts_iter)?;
+        let (expected_processed_message_key, _expected_processed_message_bump) =
+            Pubkey::find_program_address(
+                mailbox_processed_message_pda_seeds!(message_id),
+                program_id,
+            );
+        if processed_message_account_info.key != &expected_processed_message_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        // The processed message account is created when the message is processed.
+        if verify_account_uninitialized(processed_message_account_info).is_err() {
+            bitmap[index / 8] |= 1 << (index % 8);
+        }
+    }
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    // Wrap it in the SimulationReturnData because the bitmap
+    // may end with zero byte(s), which are incorrectly truncated as
+    // simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(bitmap)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+    Ok(())
+}
+
+/// Gets who processed a message and when, so that deliveries can be attributed
+/// to relayers. Returns None if the message wasn't processed.
+///
+/// Accounts:
+/// 0. `[]` The processed message PDA account of the message.
+fn inbox_get_processed_message(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    message_id: H256,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: Processed message PDA.
+    let processed_message_account_info = next_account_info(accounts_iter)?;
+    let (expected_processed_message_key, _expected_processed_message_bump) =
+        Pubkey::find_program_address(mailbox_processed_message_pda_seeds!(message_id), program_id);
+    if processed_message_account_info.key != &expected_processed_message_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    let processed_message = ProcessedMessageAccount::fetch_data(
+        &mut &processed_message_account_info.data.borrow()[..],
+    )?
+    .map(|processed_message| ProcessedMessageInfo {
+        sequence: processed_message.sequence,
+        slot: processed_message.slot,
+        payer: processed_message.payer,
+    });
+
+    // Wrap it in the SimulationReturnData because None is serialized
+    // as a zero byte, which would be truncated as simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(processed_message)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+    Ok(())
+}
+
 /// Sets the default ISM.
 ///
 /// Accounts:
 /// 0. `[writeable]` - The Inbox PDA account.
 /// 1. `[]` - The Outbox PDA account.
 /// 2. `[signer]` - The owner of the Mailbox.
+/// 3. `[executable]` - The SPL Noop program (optional).
+/// 4. `[]` - The config events PDA (optional, required if the SPL Noop program is provided).
 fn inbox_set_default_ism(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -536,16 +958,97 @@ fn inbox_set_default_ism(
     // Errors if the owner account isn't correct or isn't a signer.
     outbox.ensure_owner_signer(owner_info)?;
 
+    // Accounts 3..4: The accounts used to log the config change (optional).
+    let log_with_noop_cpi = next_config_event_accounts(program_id, accounts_iter)?;
+
     if accounts_iter.next().is_some() {
         return Err(ProgramError::from(Error::ExtraneousAccount));
     }
 
+    let previous_ism = inbox.default_ism;
     // Set the new default ISM.
     inbox.default_ism = ism;
     // Store the updated inbox.
     InboxAccount::from(inbox).store(inbox_info, false)?;
 
-    Ok(())
+    log_config_event(
+        MailboxEvent::DefaultIsmSet {
+            previous_ism,
+            new_ism: ism,
+        },
+        log_with_noop_cpi,
+    )
+}
+
+/// Sets or removes the ISM used instead of the default ISM for messages from
+/// an origin domain. Migrates the Inbox to the current layout version if
+/// needed, reallocating it at the owner's expense.
+///
+/// Accounts:
+/// 0. `[executable]` - The system program.
+/// 1. `[writeable]` - The Inbox PDA account.
+/// 2. `[]` - The Outbox PDA account.
+/// 3. `[signer, writeable]` - The owner of the Mailbox.
+/// 4. `[executable]` - The SPL Noop program (optional).
+/// 5. `[]` - The config events PDA (optional, required if the SPL Noop program is provided).
+fn inbox_set_default_ism_override(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ism_override: InboxSetDefaultIsmOverride,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let rent = Rent::get()?;
+
+    // Account 0: The system program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Inbox PDA account.
+    let inbox_info = next_account_info(accounts_iter)?;
+    let mut inbox = Inbox::verify_account_and_fetch_inner(program_id, inbox_info)?;
+
+    // Account 2: Outbox PDA account.
+    let outbox_info = next_account_info(accounts_iter)?;
+    let outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+
+    // Account 3: The owner of the Mailbox.
+    let owner_info = next_account_info(accounts_iter)?;
+    // Errors if the owner account isn't correct or isn't a signer.
+    outbox.ensure_owner_signer(owner_info)?;
+
+    // Accounts 4..5: The accounts used to log the config change (optional).
+    let log_with_noop_cpi = next_config_event_accounts(program_id, accounts_iter)?;
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    // Older layouts can't store the overrides.
+    inbox.migrate();
+
+    let InboxSetDefaultIsmOverride { origin, ism } = ism_override;
+    let previous_ism = match ism {
+        Some(ism) => inbox.default_ism_overrides.insert(origin, ism),
+        None => inbox.default_ism_overrides.remove(&origin),
+    };
+    InboxAccount::from(inbox).store_with_rent_exempt_realloc(
+        inbox_info,
+        &rent,
+        owner_info,
+        system_program_info,
+    )?;
+
+    log_config_event(
+        MailboxEvent::DefaultIsmOverrideSet {
+            origin,
+            previous_ism,
+            new_ism: ism,
+        },
+        log_with_noop_cpi,
+    )
 }
 
 /// Dispatches a message.
@@ -573,8 +1076,15 @@ fn outbox_dispatch(
     let accounts_iter = &mut accounts.iter();
 
     // Account 0: Outbox PDA.
+    // The Outbox is accessed in place rather than deserialized, which saves
+    // compute. The data isn't borrowed across the CPIs below, which would fail.
     let outbox_info = next_account_info(accounts_iter)?;
-    let mut outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+    let (local_domain, count, protocol_fee) = {
+        let data = outbox_info.try_borrow_data()?;
+        let outbox = OutboxData::new(&data[..])?;
+        Outbox::verify_account(program_id, outbox_info, outbox.outbox_bump_seed())?;
+        (outbox.local_domain(), outbox.count(), outbox.protocol_fee())
+    };
 
     // Account 1: Message sender signer.
     let sender_signer_info = next_account_info(accounts_iter)?;
@@ -645,13 +1155,8 @@ fn outbox_dispatch(
         return Err(ProgramError::from(Error::ExtraneousAccount));
     }
 
-    let count = outbox
-        .tree
-        .count()
-        .try_into()
-        .expect("Too many messages in outbox tree");
+    let count = count.try_into().expect("Too many messages in outbox tree");
 
-    let protocol_fee = outbox.protocol_fee.fee;
     invoke(
         &system_instruction::transfer(payer_info.key, outbox_info.key, protocol_fee),
         &[payer_info.clone(), outbox_info.clone()],
@@ -666,7 +1171,7 @@ fn outbox_dispatch(
     let message = HyperlaneMessage {
         version: VERSION,
         nonce: count,
-        origin: outbox.local_domain,
+        origin: local_domain,
         sender: H256(dispatch.sender.to_bytes()),
         destination: dispatch.destination_domain,
         recipient: dispatch.recipient,
@@ -678,7 +1183,14 @@ fn outbox_dispatch(
         .map_err(|_| ProgramError::from(Error::EncodeError))?;
 
     let id = message.id();
-    outbox.tree.ingest(id);
+    #[cfg(not(feature = "no-spl-noop"))]
+    let event = MailboxEvent::Dispatch {
+        message_id: id,
+        nonce: message.nonce,
+        destination: message.destination,
+        unique_message_pubkey: *unique_message_account_info.key,
+        message: encoded_message.clone(),
+    };
 
     // Create the dispatched message PDA.
     let dispatched_message_account = DispatchedMessageAccount::from(DispatchedMessage::new(
@@ -708,7 +1220,7 @@ fn outbox_dispatch(
         let noop_cpi_log = Instruction {
             program_id: *spl_noop_info.key,
             accounts: vec![],
-            data: dispatched_message_account_info.data.borrow().to_vec(),
+            data: event.to_noop_data()?,
         };
         invoke(&noop_cpi_log, &[])?;
     }
@@ -719,13 +1231,140 @@ fn outbox_dispatch(
         id
     );
 
-    // Store the Outbox with the new updates.
-    OutboxAccount::from(outbox).store(outbox_info, true)?;
+    // Insert the message into the Outbox's tree, in place.
+    let mut data = outbox_info.try_borrow_mut_data()?;
+    OutboxData::new(&mut data[..])?.ingest(id);
 
     set_return_data(id.as_ref());
     Ok(())
 }
 
+/// Quotes the total lamports a payer needs to dispatch a message as return data.
+/// This is the sum of the protocol fee, the rent of the dispatched message PDA,
+/// and, if `quote.gas_amount` is Some, the IGP payment for that gas amount.
+///
+/// The IGP accounts are chosen by the caller, who is expected to provide the
+/// same IGP they intend to pay. They are only validated by the IGP program.
+///
+/// Accounts:
+/// 0. `[]` Outbox PDA account.
+/// 1. `[executable]` The IGP program (required if `quote.gas_amount` is Some).
+/// 2. `[executable]` The system program (required if `quote.gas_amount` is Some).
+/// 3. `[]` The IGP account (required if `quote.gas_amount` is Some).
+/// 4. `[]` The overhead IGP account (optional).
+fn outbox_quote_dispatch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    quote: OutboxQuoteDispatch,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: Outbox PDA.
+    let outbox_info = next_account_info(accounts_iter)?;
+    let outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+
+    let igp_payment = if let Some(gas_amount) = quote.gas_amount {
+        // Account 1: IGP program.
+        let igp_program_info = next_account_info(accounts_iter)?;
+        if !igp_program_info.executable {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Accounts 2-4: The accounts required by the IGP's QuoteGasPayment instruction.
+        let igp_account_infos: Vec<AccountInfo> = accounts_iter.by_ref().take(3).cloned().collect();
+
+        quote_igp_payment(
+            igp_program_info,
+            igp_account_infos,
+            quote.destination_domain,
+            gas_amount,
+        )?
+    } else {
+        0
+    };
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    // The dispatched message PDA's size only depends on the message body length,
+    // so placeholder values are used for everything that doesn't affect it.
+    let message = HyperlaneMessage {
+        version: VERSION,
+        nonce: 0,
+        origin: outbox.local_domain,
+        sender: H256::zero(),
+        destination: quote.destination_domain,
+        recipient: H256::zero(),
+        body: quote.message_body,
+    };
+    let mut encoded_message = vec![];
+    message
+        .write_to(&mut encoded_message)
+        .map_err(|_| ProgramError::from(Error::EncodeError))?;
+    let dispatched_message_account_size = DispatchedMessageAccount::from(DispatchedMessage::new(
+        message.nonce,
+        0,
+        Pubkey::default(),
+        encoded_message,
+    ))
+    .size();
+    let dispatched_message_rent = Rent::get()?.minimum_balance(dispatched_message_account_size);
+
+    let total = outbox
+        .protocol_fee
+        .fee
+        .checked_add(dispatched_message_rent)
+        .and_then(|total| total.checked_add(igp_payment))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // Wrap it in the SimulationReturnData because serialized `total`
+    // may end with zero byte(s), which are incorrectly truncated as
+    // simulated transaction return data.
+    // See `SimulationReturnData` for details.
+    let bytes = SimulationReturnData::new(total)
+        .try_to_vec()
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+    set_return_data(&bytes[..]);
+    Ok(())
+}
+
+/// Quotes an IGP payment by CPI'ing into the IGP program's QuoteGasPayment instruction.
+///
+/// Expects `account_infos` to be those required by the QuoteGasPayment instruction.
+fn quote_igp_payment(
+    igp_program_info: &AccountInfo,
+    mut account_infos: Vec<AccountInfo>,
+    destination_domain: u32,
+    gas_amount: u64,
+) -> Result<u64, ProgramError> {
+    let account_metas = account_infos
+        .iter()
+        .map(|info| AccountMeta::new_readonly(*info.key, false))
+        .collect();
+    let quote_instruction = Instruction::new_with_borsh(
+        *igp_program_info.key,
+        &IgpInstruction::QuoteGasPayment(QuoteGasPayment {
+            destination_domain,
+            gas_amount,
+        }),
+        account_metas,
+    );
+    account_infos.push(igp_program_info.clone());
+    invoke(&quote_instruction, &account_infos)?;
+
+    let (returning_program_id, returned_data) =
+        get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    if returning_program_id != *igp_program_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let payment = SimulationReturnData::<u64>::try_from_slice(&returned_data[..])
+        .map_err(|err| ProgramError::BorshIoError(err.to_string()))?
+        .return_data;
+
+    Ok(payment)
+}
+
 /// Gets the number of dispatched messages as little endian encoded return data.
 ///
 /// Accounts:
@@ -735,14 +1374,15 @@ fn outbox_get_count(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
 
     // Account 0: Outbox PDA.
     let outbox_info = next_account_info(accounts_iter)?;
-    let outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+    let data = outbox_info.try_borrow_data()?;
+    let outbox = OutboxData::new(&data[..])?;
+    Outbox::verify_account(program_id, outbox_info, outbox.outbox_bump_seed())?;
 
     if accounts_iter.next().is_some() {
         return Err(ProgramError::from(Error::ExtraneousAccount));
     }
 
     let count: u32 = outbox
-        .tree
         .count()
         .try_into()
         .expect("Too many messages in outbox tree");
@@ -765,15 +1405,16 @@ fn outbox_get_latest_checkpoint(program_id: &Pubkey, accounts: &[AccountInfo]) -
     let accounts_iter = &mut accounts.iter();
 
     let outbox_info = next_account_info(accounts_iter)?;
-    let outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+    let data = outbox_info.try_borrow_data()?;
+    let outbox = OutboxData::new(&data[..])?;
+    Outbox::verify_account(program_id, outbox_info, outbox.outbox_bump_seed())?;
 
     if accounts_iter.next().is_some() {
         return Err(ProgramError::from(Error::ExtraneousAccount));
     }
 
-    let root = outbox.tree.root();
+    let root = outbox.root();
     let count: u32 = outbox
-        .tree
         .count()
         .try_into()
         .expect("Too many messages in outbox tree");
@@ -802,13 +1443,15 @@ fn outbox_get_root(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResu
 
     // Account 0: Outbox PDA.
     let outbox_info = next_account_info(accounts_iter)?;
-    let outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+    let data = outbox_info.try_borrow_data()?;
+    let outbox = OutboxData::new(&data[..])?;
+    Outbox::verify_account(program_id, outbox_info, outbox.outbox_bump_seed())?;
 
     if accounts_iter.next().is_some() {
         return Err(ProgramError::from(Error::ExtraneousAccount));
     }
 
-    let root = outbox.tree.root();
+    let root = outbox.root();
 
     // Wrap it in the SimulationReturnData because serialized root
     // may end with zero byte(s), which are incorrectly truncated as
@@ -848,6 +1491,8 @@ fn get_owner(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
 /// Accounts:
 /// 0. `[writeable]` The Outbox PDA account.
 /// 1. `[signer]` The current owner.
+/// 2. `[executable]` The SPL Noop program (optional).
+/// 3. `[]` The config events PDA (optional, required if the SPL Noop program is provided).
 fn transfer_ownership(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -861,12 +1506,76 @@ fn transfer_ownership(
 
     // Account 1: Current owner.
     let owner_info = next_account_info(accounts_iter)?;
+    let previous_owner = outbox.owner;
     // Errors if the owner_account is not the actual owner or is not a signer.
     outbox.transfer_ownership(owner_info, new_owner)?;
 
+    // Accounts 2..3: The accounts used to log the config change (optional).
+    let log_with_noop_cpi = next_config_event_accounts(program_id, accounts_iter)?;
+
     // Store the updated outbox.
     OutboxAccount::from(outbox).store(outbox_info, false)?;
 
+    log_config_event(
+        MailboxEvent::OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        },
+        log_with_noop_cpi,
+    )
+}
+
+/// Gets the optional accounts used to log a config change with a CPI to the
+/// SPL Noop program. Returns whether they were provided; clients that predate
+/// config change events don't provide them.
+///
+/// Accounts:
+/// 0. `[executable]` The SPL Noop program.
+/// 1. `[]` The config events PDA, which lets indexers find config changes
+///    with `getSignaturesForAddress`.
+fn next_config_event_accounts<'a, 'b>(
+    program_id: &Pubkey,
+    accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+) -> Result<bool, ProgramError> {
+    let spl_noop_info = match accounts_iter.next() {
+        Some(spl_noop_info) => spl_noop_info,
+        None => return Ok(false),
+    };
+    if spl_noop_info.key != &spl_noop::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    #[cfg(not(feature = "no-spl-noop"))]
+    if !spl_noop_info.executable {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let config_events_info = next_account_info(accounts_iter)?;
+    let (config_events_key, _config_events_bump) =
+        Pubkey::find_program_address(mailbox_config_events_pda_seeds!(), program_id);
+    if config_events_info.key != &config_events_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    Ok(true)
+}
+
+/// Logs a config change, and if requested, also logs it with a CPI to the
+/// SPL Noop program so indexers can decode it from the inner instructions.
+fn log_config_event(event: MailboxEvent, with_noop_cpi: bool) -> ProgramResult {
+    msg!("Hyperlane mailbox config change: {:?}", event);
+
+    #[cfg(not(feature = "no-spl-noop"))]
+    if with_noop_cpi {
+        let noop_cpi_log = Instruction {
+            program_id: spl_noop::id(),
+            accounts: vec![],
+            data: event.to_noop_data()?,
+        };
+        invoke(&noop_cpi_log, &[])?;
+    }
+    #[cfg(feature = "no-spl-noop")]
+    let _ = with_noop_cpi;
+
     Ok(())
 }
 
@@ -932,3 +1641,81 @@ fn set_protocol_fee_config(
 
     Ok(())
 }
+
+/// Migrates the Inbox or Outbox account to the current layout version,
+/// reallocating it if the new layout is larger.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[writeable]` The Outbox PDA account.
+/// 2. `[signer, writeable]` The owner of the Mailbox, who pays for any realloc.
+/// 3. `[writeable]` The Inbox PDA account, if migrating the Inbox.
+fn migrate_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    account: MigratableAccount,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let rent = Rent::get()?;
+
+    // Account 0: The system program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Outbox PDA.
+    let outbox_info = next_account_info(accounts_iter)?;
+    let mut outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+
+    // Account 2: The owner of the Mailbox.
+    let owner_info = next_account_info(accounts_iter)?;
+    // Errors if the owner account isn't correct or isn't a signer.
+    outbox.ensure_owner_signer(owner_info)?;
+
+    match account {
+        MigratableAccount::Outbox => {
+            if accounts_iter.next().is_some() {
+                return Err(ProgramError::from(Error::ExtraneousAccount));
+            }
+
+            msg!(
+                "Migrating Outbox from version {} to {}",
+                outbox.version(),
+                CURRENT_ACCOUNT_VERSION
+            );
+            outbox.migrate();
+            OutboxAccount::from(outbox).store_with_rent_exempt_realloc(
+                outbox_info,
+                &rent,
+                owner_info,
+                system_program_info,
+            )?;
+        }
+        MigratableAccount::Inbox => {
+            // Account 3: Inbox PDA.
+            let inbox_info = next_account_info(accounts_iter)?;
+            let mut inbox = Inbox::verify_account_and_fetch_inner(program_id, inbox_info)?;
+
+            if accounts_iter.next().is_some() {
+                return Err(ProgramError::from(Error::ExtraneousAccount));
+            }
+
+            msg!(
+                "Migrating Inbox from version {} to {}",
+                inbox.version(),
+                CURRENT_ACCOUNT_VERSION
+            );
+            inbox.migrate();
+            InboxAccount::from(inbox).store_with_rent_exempt_realloc(
+                inbox_info,
+                &rent,
+                owner_info,
+                system_program_info,
+            )?;
+        }
+    }
+
+    Ok(())
+}
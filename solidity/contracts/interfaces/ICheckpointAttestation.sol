@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity >=0.6.11;
+
+interface ICheckpointAttestation {
+    /**
+     * @notice Records a checkpoint signed by a validator, as an on-chain
+     * proof that the validator is live
+     * @dev Implementations are expected to recover the validator from the
+     * signature of the checkpoint digest, see `CheckpointLib.digest`
+     * @param _merkleTreeHook The address of the merkle tree hook of the
+     * checkpoint, as bytes32
+     * @param _root The root of the checkpoint
+     * @param _index The index of the checkpoint
+     * @param _messageId The message ID of the checkpoint
+     * @param _signature The validator's signature of the checkpoint
+     */
+    function attestCheckpoint(
+        bytes32 _merkleTreeHook,
+        bytes32 _root,
+        uint32 _index,
+        bytes32 _messageId,
+        bytes calldata _signature
+    ) external;
+}
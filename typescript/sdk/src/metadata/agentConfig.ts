@@ -9,7 +9,10 @@ import { ProtocolType } from '@hyperlane-xyz/utils';
 import { MultiProvider } from '../providers/MultiProvider.js';
 import { ChainMap, ChainName } from '../types.js';
 
-import { ChainMetadataSchemaObject } from './chainMetadataTypes.js';
+import {
+  ChainMetadataSchemaObject,
+  RpcUrlSchema,
+} from './chainMetadataTypes.js';
 import { ZHash, ZNzUint, ZUWei, ZUint } from './customZodTypes.js';
 import {
   HyperlaneDeploymentArtifacts,
@@ -44,11 +47,18 @@ export enum AgentIndexMode {
   Sequence = 'sequence',
 }
 
+export enum AgentHashAlgorithm {
+  Keccak256 = 'keccak256',
+  Sha256 = 'sha256',
+  Blake2b256 = 'blake2b256',
+}
+
 export enum AgentSignerKeyType {
   Aws = 'aws',
   Hex = 'hexKey',
   Node = 'node',
   Cosmos = 'cosmosKey',
+  Remote = 'remote',
 }
 
 export enum AgentSealevelPriorityFeeOracleType {
@@ -93,6 +103,26 @@ const AgentSignerCosmosKeySchema = z
     key: ZHash,
   })
   .describe('Cosmos key');
+const AgentSignerRemoteSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Remote),
+    address: ZHash.describe(
+      'The address of the key held by the signing service',
+    ),
+    urls: z
+      .string()
+      .min(1)
+      .describe(
+        'Comma separated list of signing service endpoints, in order of preference',
+      ),
+    requestSigningKey: z
+      .string()
+      .optional()
+      .describe('Optional shared secret used to sign requests to the service'),
+  })
+  .describe(
+    'A remote (e.g. threshold ECDSA) signing service exposing a web3signer-style API',
+  );
 const AgentSignerNodeSchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Node),
@@ -103,12 +133,14 @@ const AgentSignerSchema = z.union([
   AgentSignerHexKeySchema,
   AgentSignerAwsKeySchema,
   AgentSignerCosmosKeySchema,
+  AgentSignerRemoteSchema,
   AgentSignerNodeSchema,
 ]);
 
 export type AgentSignerHexKey = z.infer<typeof AgentSignerHexKeySchema>;
 export type AgentSignerAwsKey = z.infer<typeof AgentSignerAwsKeySchema>;
 export type AgentSignerCosmosKey = z.infer<typeof AgentSignerNodeSchema>;
+export type AgentSignerRemote = z.infer<typeof AgentSignerRemoteSchema>;
 export type AgentSignerNode = z.infer<typeof AgentSignerNodeSchema>;
 export type AgentSigner = z.infer<typeof AgentSignerSchema>;
 
@@ -140,6 +172,23 @@ export type AgentCosmosGasPrice = z.infer<
   typeof AgentCosmosChainMetadataSchema
 >['gasPrice'];
 
+const AgentSealevelAccountMetaTemplateSchema = z.object({
+  // Exactly one of the following
+  pubkey: z.string().optional(),
+  tokenRecipient: z.boolean().optional(),
+  tokenRecipientAta: z
+    .object({
+      mint: z.string(),
+      tokenProgram: z.string(),
+    })
+    .optional(),
+  multisigIsmDomainData: z
+    .string()
+    .optional()
+    .describe('The message ID multisig ISM program id'),
+  writable: z.boolean().optional(),
+});
+
 const AgentSealevelChainMetadataSchema = z.object({
   priorityFeeOracle: z
     .union([
@@ -162,6 +211,20 @@ const AgentSealevelChainMetadataSchema = z.object({
       url: z.string().optional(),
     })
     .optional(),
+  knownRecipients: z
+    .array(
+      z.object({
+        recipient: z.string(),
+        ism: z.string(),
+        ismGetter: z.array(AgentSealevelAccountMetaTemplateSchema).optional(),
+        ismVerify: z.array(AgentSealevelAccountMetaTemplateSchema).optional(),
+        handle: z.array(AgentSealevelAccountMetaTemplateSchema).optional(),
+      }),
+    )
+    .optional()
+    .describe(
+      'Recipient programs whose messages are processed with the configured accounts instead of ones discovered by simulation',
+    ),
 });
 
 export type AgentSealevelChainMetadata = z.infer<
@@ -188,9 +251,59 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
       .nativeEnum(RpcConsensusType)
       .describe('The consensus type to use when multiple RPCs are configured.')
       .optional(),
+    rpcRateLimit: z
+      .object({
+        requestsPerSecond: z
+          .number()
+          .positive()
+          .describe('The sustained number of RPC requests per second.'),
+        burst: ZNzUint.optional().describe(
+          'The number of requests that can be sent at once after a quiet period. Defaults to one second worth of requests.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Limits the rate of requests to the RPCs of this chain, shared by all of its clients. Not applied to websocket RPCs.',
+      ),
+    feeHistoryCacheTtl: ZUint.optional().describe(
+      'Ethereum only. If set, the fee history used to estimate EIP-1559 fees is cached for up to this many seconds, or until a new block is produced, and shared by all the transactions submitted to this chain.',
+    ),
+    storageLocationsCacheTtl: ZUint.optional().describe(
+      'Ethereum only. If set, the storage locations announced by validators on this chain are cached for up to this many seconds, so that only validators new to a validator set are queried when building metadata.',
+    ),
+    batchLogQueries: z
+      .boolean()
+      .optional()
+      .describe(
+        'Ethereum only. If true, the mailbox, merkle tree hook and IGP indexers of this chain fetch their logs in a single eth_getLogs query per block range. Defaults to false.',
+      ),
+    batchReads: z
+      .boolean()
+      .optional()
+      .describe(
+        'Ethereum only. If true, the reads of the mailbox and ISMs made while building message metadata are batched into Multicall3 calls, at batchContractAddress if set. Defaults to false.',
+      ),
+    batchAccountQueries: z
+      .boolean()
+      .optional()
+      .describe(
+        'Sealevel only. If true, the accounts fetched concurrently from this chain, e.g. by the indexers while they backfill, are batched into getMultipleAccounts requests of up to 100 accounts. Defaults to false.',
+      ),
+    archiveRpcUrls: z
+      .array(RpcUrlSchema)
+      .optional()
+      .describe(
+        'Sealevel only. RPC endpoints of archive nodes. Blocks that the first of the rpcUrls has pruned from its history are fetched from the first healthy archive node that still has them.',
+      ),
     signer: AgentSignerSchema.optional().describe(
       'The signer to use for this chain',
     ),
+    additionalSigners: z
+      .array(AgentSignerSchema)
+      .optional()
+      .describe(
+        'Sealevel only. Signers that the transactions delivering messages are spread across along with `signer`, each paying for the transactions it signs, so that they are submitted in parallel from different fee payers.',
+      ),
     index: z
       .object({
         from: ZUint.optional().describe(
@@ -207,6 +320,12 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
           ),
       })
       .optional(),
+    hashAlgorithm: z
+      .nativeEnum(AgentHashAlgorithm)
+      .optional()
+      .describe(
+        'The hash function the chain contracts use for checkpoint and announcement digests. Message ids and merkle trees always use keccak256. Defaults to keccak256.',
+      ),
   })
   .merge(AgentCosmosChainMetadataSchema.partial())
   .merge(AgentSealevelChainMetadataSchema.partial())
@@ -272,7 +391,49 @@ export const AgentConfigSchema = z.object({
   metricsPort: ZNzUint.lte(65535)
     .optional()
     .describe(
-      'The port to expose prometheus metrics on. Accessible via `GET /metrics`.',
+      'The port to expose prometheus metrics on. Accessible via `GET /metrics`. The same port serves the health of the agent as JSON on `GET /health` and `GET /ready`.',
+    ),
+  persistentMetrics: z
+    .boolean()
+    .optional()
+    .describe(
+      'Whether to persist selected counters in the agent DB so they survive restarts. Defaults to true.',
+    ),
+  metricsHistogramBuckets: z
+    .record(z.array(z.number()).nonempty())
+    .optional()
+    .describe(
+      'Bucket bounds overriding the defaults of histogram metrics, by metric name without the `hyperlane_` prefix, e.g. `{"transaction_inclusion_seconds": [0.4, 0.8, 1.6, 5]}`. The bounds must be increasing.',
+    ),
+  enableProfiling: z
+    .boolean()
+    .optional()
+    .describe(
+      'Whether to serve CPU profiles and heap stats on the metrics port, via `GET /debug/pprof/profile` and `GET /debug/pprof/heap`. Requires the agent to be built with the `profiling` and `jemalloc` features respectively.',
+    ),
+  dbBackupDir: z
+    .string()
+    .optional()
+    .describe(
+      'If set, `POST /db/backup` on the metrics port writes a consistent snapshot of the agent DB to a new directory in this one. Supported by the validator and relayer.',
+    ),
+  restoreDbFrom: z
+    .string()
+    .optional()
+    .describe(
+      'Path of a DB backup the agent restores its DB from on startup, if its DB does not exist yet. Backups of a newer schema version than the agent supports are refused. Supported by the validator and relayer.',
+    ),
+  selfTest: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, the agent tests its signer and checkpoint syncer against a synthetic checkpoint, reports which of them work and exits instead of running. The exit code is non-zero if any of them fails. Supported by the validator and relayer.',
+    ),
+  verifyDeployments: z
+    .boolean()
+    .optional()
+    .describe(
+      'Whether the agent checks at startup that the mailbox, IGP, merkle tree hook and validator announce of its EVM chains are deployed at the configured addresses, by probing their view functions, and exits with an error if one is not. Defaults to true.',
     ),
   chains: z
     .record(AgentChainMetadataSchema)
@@ -296,12 +457,20 @@ export const AgentConfigSchema = z.object({
       'The default consensus type to use for any chains that have not defined their own.',
     )
     .optional(),
+  domainRegistry: z
+    .string()
+    .optional()
+    .describe(
+      'Path to a JSON domain registry adding to or overriding the one embedded in the agents. It lists domains as objects with a `domainId`, `name`, `protocol`, `isTestnet`, `technicalStack`, `reorgPeriod` and `nativeTokenDecimals`, which chains missing some of these values default to.',
+    ),
   log: z
     .object({
       format: z
         .nativeEnum(AgentLogFormat)
         .optional()
-        .describe('The format to use for tracing logs.'),
+        .describe(
+          'The format to use for tracing logs. `json` emits newline-delimited JSON records. The records of the relayer about a message have its id as a top-level `correlation_id`.',
+        ),
       level: z
         .nativeEnum(AgentLogLevel)
         .optional()
@@ -349,6 +518,60 @@ const MetricAppContextSchema = z.object({
   ),
 });
 
+const PreTransactionSchema = z.discriminatedUnion('type', [
+  z.object({
+    type: z.literal('createAssociatedTokenAccount'),
+    mint: ZHash.describe(
+      'The mint of the transferred token. The account is created for the recipient of the warp route transfer. Sealevel only.',
+    ),
+  }),
+  z.object({
+    type: z.literal('call'),
+    to: ZHash.describe('The called contract. EVM only.'),
+    data: z
+      .string()
+      .regex(/^0x([0-9a-fA-F]{2})*$/)
+      .describe('The hex encoded calldata'),
+  }),
+]);
+
+const PreTransactionsSchema = z.object({
+  matchingList: MatchingListSchema.describe(
+    'The messages that require the pre-transactions.',
+  ),
+  transactions: z
+    .array(PreTransactionSchema)
+    .describe('The pre-transactions, submitted in order.'),
+  maxPerDay: ZUint.optional().describe(
+    'The most pre-transactions of this config submitted per UTC day, across all messages. The relayer pays for them, including the rent of the associated token accounts they create, which the gas payments of the messages do not cover. Unlimited if unset.',
+  ),
+});
+
+const MessageExpirySchema = z.object({
+  matchingList: MatchingListSchema.optional().describe(
+    'The messages that expire. Defaults to all messages.',
+  ),
+  ttl: ZUint.describe(
+    'How long after the relayer first queued a message it expires, in seconds.',
+  ),
+});
+
+const AlertSeveritySchema = z
+  .enum(['warning', 'critical'])
+  .describe(
+    'Only alerts of at least this severity are sent to the webhook. Defaults to warning.',
+  );
+
+const MessageBackoffScheduleSchema = z.object({
+  initial: ZUint.describe('Delay after the first failed attempt, in seconds.'),
+  factor: z
+    .number()
+    .min(1)
+    .optional()
+    .describe('What the delay is multiplied by on every retry. Defaults to 2.'),
+  max: ZUint.describe('Longest delay, in seconds.'),
+});
+
 export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
@@ -358,6 +581,9 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   relayChains: CommaSeparatedChainList.describe(
     'Comma separated list of chains to relay messages between.',
   ),
+  readOnlyChains: CommaSeparatedChainList.optional().describe(
+    'Comma separated list of chains in relayChains that messages are only relayed from, never delivered to. These chains do not need a signer, while every other relay chain does.',
+  ),
   gasPaymentEnforcement: z
     .union([z.array(GasPaymentEnforcementSchema), z.string().min(1)])
     .optional()
@@ -376,28 +602,322 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'If no blacklist is provided ALL will be considered to not be on the blacklist.',
     ),
+  whitelistUrl: z
+    .string()
+    .url()
+    .optional()
+    .describe(
+      'HTTPS URL a signed whitelist is published at. It is polled for new versions and takes precedence over `whitelist`.',
+    ),
+  blacklistUrl: z
+    .string()
+    .url()
+    .optional()
+    .describe(
+      'HTTPS URL a signed blacklist is published at. It is polled for new versions and takes precedence over `blacklist`.',
+    ),
+  filterListSigner: ZHash.optional().describe(
+    'Address that must have signed the lists fetched from `whitelistUrl` and `blacklistUrl`. Required if either is set.',
+  ),
+  filterListPollInterval: ZUint.optional().describe(
+    'How often the filter list URLs are polled, in seconds. Defaults to 60.',
+  ),
   addressBlacklist: z
     .string()
     .optional()
     .describe('Comma separated list of addresses to blacklist.'),
+  recipientCodeHashAllowlist: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated list of recipient code hashes. If set, messages are only delivered to recipients whose code hash on the destination is in this list.',
+    ),
+  recipientCodeHashDenylist: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated list of recipient code hashes. Messages are never delivered to recipients whose code hash on the destination is in this list.',
+    ),
   transactionGasLimit: ZUWei.optional().describe(
     'This is optional. If not specified, any amount of gas will be valid, otherwise this is the max allowed gas in wei to relay a transaction.',
   ),
   skipTransactionGasLimitFor: CommaSeparatedDomainList.optional().describe(
     'Comma separated List of chain names to skip applying the transaction gas limit to.',
   ),
+  shadowChains: CommaSeparatedChainList.optional().describe(
+    'Comma separated list of destination chain names in shadow mode. Messages to these chains are prepared until another relayer delivers them, but never submitted. Messages delivered by another relayer that could not be prepared are reported through the relayer API and metrics.',
+  ),
+  pausedChains: CommaSeparatedDomainList.optional().describe(
+    'Comma separated list of chain names that no messages are delivered to or from. Can be changed without a restart by sending the relayer a SIGHUP.',
+  ),
+  metadataSizeLimits: z
+    .record(ZNzUint)
+    .optional()
+    .describe(
+      'Max size in bytes of the ISM metadata by destination chain name, for chains that limit the size of transactions.',
+    ),
+  maxConcurrentSimulations: z
+    .record(ZNzUint)
+    .optional()
+    .describe(
+      'Max number of simulations, e.g. ISM calls and gas estimations, run against a destination at the same time, by destination chain name. Unlimited for chains that are not configured.',
+    ),
   allowLocalCheckpointSyncers: z
     .boolean()
     .optional()
     .describe(
       'If true, allows local storage based checkpoint syncers. Not intended for production use.',
     ),
+  probeMode: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, messages are prepared, including building their metadata and estimating their delivery, but never submitted. Useful for evaluating a route before funding a relayer key.',
+    ),
+  preflightSimulation: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, failed simulations of message deliveries are classified as ISM verification failures, recipient reverts or running out of gas. Each class is counted in a metric, and messages that run out of gas are retried less often.',
+    ),
+  unknownDestinationPolicy: z
+    .enum(['ignore', 'count', 'park'])
+    .optional()
+    .describe(
+      'How messages to destinations the relayer does not deliver to are handled: skipped (`ignore`, the default), counted in a metric (`count`), or counted and listed through the API until their TTL elapses (`park`).',
+    ),
+  unknownDestinationTtl: ZUint.optional().describe(
+    'How long messages to unknown destinations stay parked, in seconds, counted from when they were first seen. Expired messages are marked as processed. Defaults to a day.',
+  ),
+  preTransactions: z
+    .union([z.array(PreTransactionsSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Transactions submitted and confirmed on the destination before the messages that require them are delivered, e.g. to create the associated token account of the recipient of a Sealevel transfer. Failed pre-transactions are retried on their own schedule. The relayer pays for them, so each config should cap them with `maxPerDay`.',
+    ),
+  messageExpiry: z
+    .union([z.array(MessageExpirySchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'TTLs after which undelivered messages are moved to a dead-letter state instead of being retried. The first policy whose matching list matches a message applies. Expired messages are persisted, counted in the `messages_expired` metric and listed through the `/list_expired_messages` API.',
+    ),
+  evidenceBundleFailureThreshold: ZUint.optional().describe(
+    'After how many consecutive failed submissions to a destination an evidence bundle is collected and logged as JSON. The bundle holds the recent failures, the RPC request counts of the destination, its last successful delivery and a hash of its config. Unset or 0 disables it.',
+  ),
+  parkAfterPrepareFailures: ZUint.optional().describe(
+    'After how many retries a message that keeps failing to prepare is parked, i.e. moved out of the prepare queue and only prepared again every `parkedRetryInterval`. Messages are never parked if unset.',
+  ),
+  parkedRetryInterval: ZUint.optional().describe(
+    'How long a parked message waits before being prepared again, in seconds. Defaults to 3600.',
+  ),
+  logDeduplicationWindow: ZUint.optional().describe(
+    'If set, repeated warnings about the same message are only logged once per window, in seconds, with a summary of the suppressed occurrences. Unset or 0 disables deduplication.',
+  ),
+  messageBackoff: z
+    .object({
+      jitter: z
+        .number()
+        .min(0)
+        .max(1)
+        .optional()
+        .describe(
+          'Up to which fraction of a delay it is randomly lengthened or shortened. Defaults to 0.',
+        ),
+      gasUnderpayment: MessageBackoffScheduleSchema.optional().describe(
+        'Schedule of the messages that did not meet the gas payment requirement.',
+      ),
+      ismNotReady: MessageBackoffScheduleSchema.optional().describe(
+        'Schedule of the messages whose ISM metadata is not available yet, or was rejected.',
+      ),
+      revert: MessageBackoffScheduleSchema.optional().describe(
+        'Schedule of the messages whose delivery reverted.',
+      ),
+      other: MessageBackoffScheduleSchema.optional().describe(
+        'Schedule of the messages that failed for any other reason.',
+      ),
+    })
+    .optional()
+    .describe(
+      'How long messages wait before being retried, by what their last attempt failed on. Failure classes without a schedule keep the default stepped backoff.',
+    ),
+  signerBalanceFloors: z
+    .record(ZUWei)
+    .optional()
+    .describe(
+      "Floors of the relayer's signer balance, by destination chain name. The balance on each of these chains is monitored and exported as metrics.",
+    ),
+  haltBelowSignerBalanceFloor: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, messages are not delivered to destinations whose signer balance is below its floor until it is topped up. Defaults to false.',
+    ),
+  routeMatrix: z
+    .record(z.record(z.boolean()))
+    .optional()
+    .describe(
+      'Routes to enable or disable, as a map from origin chain name to a map from destination chain name to whether messages are delivered on the route. Routes that are not listed are enabled. Can be changed at runtime through the relayer API.',
+    ),
+  igpFeeTokenExchangeRates: z
+    .record(z.string().regex(/^\d+(\.\d+)?$/))
+    .optional()
+    .describe(
+      'For origins whose IGP charges in an ERC-20 token, how many units of the native token a unit of the fee token is worth, as a map from origin chain name to a decimal string. Both amounts are in their smallest denomination. Gas payments are converted to the native token with it before the gas payment enforcement policies are evaluated.',
+    ),
+  igpClaimThresholds: z
+    .record(ZUWei)
+    .optional()
+    .describe(
+      "Payments held by the IGP of an origin from which they are claimed to the IGP's beneficiary, by origin chain name, in the smallest unit of the origin's native token. Only the IGPs of origins with a threshold are claimed, with transactions paid by the relayer's signer.",
+    ),
+  igpClaimInterval: ZUint.optional().describe(
+    'How often the claimable IGP payments are checked, in seconds. Defaults to 3600.',
+  ),
+  igpGasOracleDrift: z
+    .object({
+      threshold: z
+        .number()
+        .nonnegative()
+        .describe(
+          'Relative difference between a value of the gas oracle and its reference above which it has drifted, e.g. 0.5 for 50%.',
+        ),
+      interval: ZUint.optional().describe(
+        'How often the gas oracles are checked, in seconds. Defaults to 600.',
+      ),
+      tokenExchangeRates: z
+        .record(z.record(ZUWei))
+        .optional()
+        .describe(
+          'Reference token exchange rates, as a map from origin chain name to a map from destination chain name to the rate, scaled by 1e10 like those of the gas oracles.',
+        ),
+    })
+    .optional()
+    .describe(
+      "Periodic checks of the gas price and token exchange rate the IGP of each origin quotes gas payments for each destination with. Gas prices are compared to the destination's current base fee. Missing oracles and values off their reference by more than the threshold are alerted.",
+    ),
+  storageLocationsTtl: ZUint.optional().describe(
+    'How long the storage locations announced by validators are stored in the database and used for before they are fetched again, in seconds. Defaults to 600.',
+  ),
+  validatorAnnounceIndexInterval: ZUint.optional().describe(
+    'How often the storage locations of all the validators announced on each origin are indexed, in seconds. If unset, they are only fetched for the validators of the messages being relayed.',
+  ),
+  fundingPools: z
+    .union([
+      z.array(
+        z.object({
+          name: z.string().min(1),
+          budget: ZUWei.describe(
+            'What the chains of the pool may spend in a period, in a unit common to them',
+          ),
+          period: ZUint.optional().describe(
+            'How often the spend of the pool is reset, in seconds. Defaults to a day.',
+          ),
+          chains: z.record(
+            z.object({
+              weight: ZUint.optional().describe(
+                'Share of the budget relative to the other chains of the pool. Defaults to 1.',
+              ),
+              exchangeRate: z
+                .string()
+                .regex(/^\d+(\.\d+)?$/)
+                .optional()
+                .describe(
+                  "How many pool units a unit of the chain's native token is worth, both in their smallest denomination. Defaults to 1.",
+                ),
+            }),
+          ),
+        }),
+      ),
+      z.string().min(1),
+    ])
+    .optional()
+    .describe(
+      'Destination chains whose signers are funded by the same wallet. The budget left in the period of a pool is split between its chains proportionally to their weight times the value of their pending operations, i.e. their estimated gas at the gas price last paid on the chain, plus the average value of the chains of the pool. No messages are delivered to a chain that spent its allowance until it gets more. The spend of the period is persisted, so restarts don't reset it.',
+    ),
   metricAppContexts: z
     .union([z.array(MetricAppContextSchema), z.string().min(1)])
     .optional()
     .describe(
       'A list of app contexts and their matching lists to use for metrics. A message will be classified as the first matching app context.',
     ),
+  appContextBudgets: z
+    .union([
+      z.array(
+        z.object({
+          appContext: z.string().min(1),
+          destinationChain: z.string().min(1),
+          dailyBudget: ZUWei.describe(
+            "In the smallest unit of the destination's native token",
+          ),
+        }),
+      ),
+      z.string().min(1),
+    ])
+    .optional()
+    .describe(
+      'Daily spend budgets of app contexts by destination chain. Messages of an app context that exceeded its budget are not delivered until the next UTC day.',
+    ),
+  alertWebhooks: z
+    .union([
+      z.array(
+        z.discriminatedUnion('type', [
+          z.object({
+            type: z.literal('slack'),
+            url: z.string().url(),
+            minSeverity: AlertSeveritySchema.optional(),
+          }),
+          z.object({
+            type: z.literal('pagerDuty'),
+            routingKey: z.string().min(1),
+            url: z
+              .string()
+              .url()
+              .optional()
+              .describe('Defaults to the PagerDuty Events API v2'),
+            minSeverity: AlertSeveritySchema.optional(),
+          }),
+        ]),
+      ),
+      z.string().min(1),
+    ])
+    .optional()
+    .describe(
+      'Webhooks critical conditions are sent to. Alerting is disabled if none are set.',
+    ),
+  alertRateLimit: ZUint.optional().describe(
+    'Minimum time between two alerts of the same kind for the same chain, in seconds. Defaults to an hour.',
+  ),
+  alertSignerBalanceThresholds: z
+    .record(ZUWei)
+    .optional()
+    .describe(
+      "Alert when the relayer's signer balance falls below these amounts, by destination chain name",
+    ),
+  alertQueueSaturationThreshold: ZNzUint.optional().describe(
+    "Alert when a destination's prepare queue holds this many operations",
+  ),
+  alertOperationFailureThreshold: ZNzUint.optional().describe(
+    'Alert when an operation failed this many times in a row',
+  ),
+  alertHaltedChainTimeout: ZNzUint.optional().describe(
+    'Alert when a destination produced no block for this many seconds',
+  ),
+  messageStatusWebhookUrl: z
+    .string()
+    .url()
+    .optional()
+    .describe(
+      'A URL to POST the status changes of messages to: prepared, submitted, delivered, dropped and gas_underpaid.',
+    ),
+  messageStatusWebhookSecret: z
+    .string()
+    .optional()
+    .describe(
+      'If set, message status notifications are signed with HMAC-SHA256 keyed by this secret, in the X-Hyperlane-Signature header.',
+    ),
+  messageStatusWebhookMaxAttempts: ZNzUint.optional().describe(
+    'Attempts to send a message status notification before giving up on it. Defaults to 5.',
+  ),
 });
 
 export type RelayerConfig = z.infer<typeof RelayerAgentConfigSchema>;
@@ -407,6 +927,31 @@ export const ScraperAgentConfigSchema = AgentConfigSchema.extend({
   chainsToScrape: CommaSeparatedChainList.describe(
     'Comma separated list of chain names to scrape',
   ),
+  chainRegistryPollingInterval: ZUint.optional().describe(
+    'How often to check the chain registry table for chains to start scraping, in seconds. If set, all chains in the config are parsed so registered chains can reference them. If unset, the chain registry is not used.',
+  ),
+  environment: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'Environment the scraped events are tagged with, e.g. mainnet, so one database can serve several explorers. Defaults to "default".',
+    ),
+  chainEnvironments: z
+    .record(z.string().min(1))
+    .optional()
+    .describe(
+      'Environment of the chains scraped for another environment than `environment`, by chain name. A chain is only ever scraped for one environment, so the scraper refuses to scrape a chain already scraped for another one.',
+    ),
+  retentionDays: z
+    .record(ZNzUint)
+    .optional()
+    .describe(
+      'How many days the events of an environment are kept after they were scraped, by environment. Events of other environments are kept forever.',
+    ),
+  reorgCheckInterval: ZUint.optional().describe(
+    'How often the recently scraped blocks are checked for reorgs deeper than the reorg period of their chain, in seconds. Events of reorged blocks are invalidated and scraped again. Blocks are not checked if unset.',
+  ),
 });
 
 export type ScraperConfig = z.infer<typeof ScraperAgentConfigSchema>;
@@ -468,6 +1013,49 @@ export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  autoAnnounce: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true or unset, the validator announces its storage location using the origin chain signer if it is not announced yet.',
+    ),
+  checkpointBatches: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, the validator also writes each completed batch of 1000 checkpoints as a single gzipped file, which relayers fetch instead of the individual checkpoints.',
+    ),
+  latestIndividualCheckpoints: z
+    .number()
+    .int()
+    .nonnegative()
+    .optional()
+    .describe(
+      'With checkpointBatches, only this many of the latest checkpoints signed at once are also written individually. The others are only written in their batch once it is complete, or individually if it is not complete within ten minutes.',
+    ),
+  checkpointAttestation: z
+    .object({
+      address: ZHash.describe(
+        'The address of the checkpoint attestation contract on the origin chain.',
+      ),
+      interval: ZNzUint.optional().describe(
+        'Every checkpoint whose index is a multiple of this is submitted to the contract. Defaults to 100.',
+      ),
+      maxDailySpend: ZUWei.optional().describe(
+        'The maximum amount of native tokens, in the smallest unit, spent on submissions per UTC day. Unlimited if unset.',
+      ),
+    })
+    .optional()
+    .describe(
+      'If set, the validator also submits checkpoints to an attestation contract on the origin chain (EVM chains only), so that its liveness can be verified on chain.',
+    ),
+  quorumRpcUrl: z
+    .string()
+    .url()
+    .optional()
+    .describe(
+      'An RPC of the origin chain, independent from its configured RPCs. If set, the validator only signs a checkpoint once the merkle tree hook read through this RPC returns the same checkpoint (EVM and Sealevel chains only).',
+    ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;
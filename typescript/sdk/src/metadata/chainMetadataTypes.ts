@@ -144,7 +144,9 @@ export const ChainMetadataSchemaObject = z.object({
         .positive()
         .finite()
         .optional()
-        .describe('Rough estimate of time per block in seconds.'),
+        .describe(
+          'Rough estimate of time per block in seconds. Agents use it to tune how often they poll the chain.',
+        ),
     })
     .optional()
     .describe('Block settings for the chain/deployment.'),
@@ -357,6 +357,7 @@ export abstract class SealevelHypTokenAdapter
       mailbox: mailboxPubKey,
       randomWallet: randomWallet.publicKey,
       igp: await this.getIgpKeys(),
+      depositorAllowlist: await this.getDepositorAllowlistKey(),
     });
 
     const value = new SealevelInstructionWrapper({
@@ -415,6 +416,11 @@ export abstract class SealevelHypTokenAdapter
     return igpAdapter?.getPaymentKeys();
   }
 
+  // Only collateral programs built with a depositor allowlist have one
+  async getDepositorAllowlistKey(): Promise<PublicKey | undefined> {
+    return undefined;
+  }
+
   // Should match https://github.com/hyperlane-xyz/hyperlane-monorepo/blob/main/rust/sealevel/libraries/hyperlane-sealevel-token/src/processor.rs#L257-L274
   getTransferInstructionKeyList({
     sender,
@@ -739,15 +745,39 @@ export class SealevelHypCollateralAdapter extends SealevelHypTokenAdapter {
       },
       /// 12.  [writeable] The escrow PDA account.
       { pubkey: this.deriveEscrowAccount(), isSigner: false, isWritable: true },
+      /// 13.  [] The depositor allowlist PDA account, if the program has one.
+      ...(params.depositorAllowlist
+        ? [
+            {
+              pubkey: params.depositorAllowlist,
+              isSigner: false,
+              isWritable: false,
+            },
+          ]
+        : []),
     ];
   }
 
+  override async getDepositorAllowlistKey(): Promise<PublicKey | undefined> {
+    const depositorAllowlist = this.deriveDepositorAllowlistAccount();
+    const accountInfo =
+      await this.getProvider().getAccountInfo(depositorAllowlist);
+    return accountInfo ? depositorAllowlist : undefined;
+  }
+
   deriveEscrowAccount(): PublicKey {
     return super.derivePda(
       ['hyperlane_token', '-', 'escrow'],
       this.warpProgramPubKey,
     );
   }
+
+  deriveDepositorAllowlistAccount(): PublicKey {
+    return super.derivePda(
+      ['hyperlane_token', '-', 'depositor_allowlist'],
+      this.warpProgramPubKey,
+    );
+  }
 }
 
 // Interacts with Hyp Synthetic token programs (aka 'HypTokens')
@@ -820,4 +850,5 @@ interface KeyListParams {
   mailbox: PublicKey;
   randomWallet: PublicKey;
   igp?: IgpPaymentKeys;
+  depositorAllowlist?: PublicKey;
 }